@@ -162,6 +162,22 @@ impl AgentBudget {
         self.used_loc += loc;
         self.used_cost += cost;
         self.used_time += time;
+
+        if !self.time_limit.is_zero() && self.used_time.as_secs_f64() >= self.time_limit.as_secs_f64() * 0.8 {
+            tracing::warn!(
+                "Agent budget at {:.0}% of time limit: {:?}/{:?}",
+                self.used_time.as_secs_f64() / self.time_limit.as_secs_f64() * 100.0,
+                self.used_time,
+                self.time_limit
+            );
+        }
+    }
+
+    /// Time left before `time_limit` is exhausted. Used to bound each agent
+    /// call with a `tokio::time::timeout` in the orchestrator so one slow
+    /// step can't silently burn through the rest of the run's budget.
+    pub fn remaining_time(&self) -> Duration {
+        self.time_limit.saturating_sub(self.used_time)
     }
 
     pub fn remaining_budget(&self) -> String {
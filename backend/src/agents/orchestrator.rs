@@ -119,6 +119,19 @@ pub struct RiskAssessment {
     pub recommendations: Vec<String>,
 }
 
+/// Called when an agent call's `tokio::time::timeout` elapses before the
+/// agent returned. Charges whatever time was left in `budget` (the step ran
+/// for the full remaining window without finishing) and returns the error
+/// that propagates up through `execute_agent_steps` into the step's
+/// `ExecutionState`/`AgentLoopResult` as a failure, the same way any other
+/// step error does.
+fn budget_exhausted(budget: &mut AgentBudget, step: &str) -> anyhow::Error {
+    let remaining = budget.remaining_time();
+    budget.consume(0, 0, 0.0, remaining);
+    error!("BudgetExhausted: {} step did not complete within its time budget", step);
+    anyhow!("BudgetExhausted: {} step exceeded its remaining time budget", step)
+}
+
 impl AgentOrchestrator {
     pub fn new(
         provider_router: Arc<ProviderRouter>,
@@ -308,8 +321,11 @@ impl AgentOrchestrator {
             metadata: HashMap::new(),
         };
         
-        let response = self.planner.execute(&request).await?;
-        
+        let response = match tokio::time::timeout(budget.remaining_time(), self.planner.execute(&request)).await {
+            Ok(result) => result?,
+            Err(_) => return Err(budget_exhausted(budget, "planning")),
+        };
+
         let plan = if response.success {
             // Parse plan from response
             ExecutionPlan {
@@ -425,8 +441,11 @@ impl AgentOrchestrator {
             metadata: HashMap::new(),
         };
         
-        let response = self.codegen.execute(&request).await?;
-        
+        let response = match tokio::time::timeout(budget.remaining_time(), self.codegen.execute(&request)).await {
+            Ok(result) => result?,
+            Err(_) => return Err(budget_exhausted(budget, "codegen")),
+        };
+
         let generated_files = if response.success {
             // Parse generated files from response artifacts
             response.artifacts.iter()
@@ -472,8 +491,11 @@ impl AgentOrchestrator {
             metadata: HashMap::new(),
         };
         
-        let test_response = self.testgen.execute(&test_request).await?;
-        
+        let test_response = match tokio::time::timeout(budget.remaining_time(), self.testgen.execute(&test_request)).await {
+            Ok(result) => result?,
+            Err(_) => return Err(budget_exhausted(budget, "testgen")),
+        };
+
         if !test_response.success {
             return Err(anyhow!("Test generation failed: {:?}", test_response.error));
         }
@@ -739,7 +761,29 @@ mod tests {
         
         // Should not allow exceeding budget
         assert!(!budget.can_proceed(2, 100, 0.5));
-        
+
         Ok(())
     }
+
+    #[test]
+    fn test_budget_remaining_time_and_exhaustion() {
+        let mut budget = AgentBudget::new(&AgentConstraints {
+            max_files: 10,
+            max_loc: 1000,
+            timeout_seconds: 30,
+            allowed_operations: vec![],
+            budget_limit: None,
+        });
+
+        assert_eq!(budget.remaining_time(), Duration::from_secs(30));
+
+        budget.consume(1, 10, 0.0, Duration::from_secs(20));
+        assert_eq!(budget.remaining_time(), Duration::from_secs(10));
+
+        // budget_exhausted() charges whatever time was left, never panics
+        // on the saturating subtraction even once used_time == time_limit.
+        let err = budget_exhausted(&mut budget, "codegen");
+        assert!(err.to_string().contains("BudgetExhausted"));
+        assert_eq!(budget.remaining_time(), Duration::ZERO);
+    }
 }
\ No newline at end of file
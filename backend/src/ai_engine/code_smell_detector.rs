@@ -0,0 +1,404 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::warn;
+use tree_sitter::{Language, Node, Parser, Tree};
+
+// Tree-sitter language declarations, mirroring `context::ast_graph::AstAnalyzer`.
+extern "C" {
+    fn tree_sitter_python() -> Language;
+    fn tree_sitter_javascript() -> Language;
+    fn tree_sitter_typescript() -> Language;
+    fn tree_sitter_rust() -> Language;
+    fn tree_sitter_go() -> Language;
+}
+
+const LONG_METHOD_LINES: usize = 50;
+const DEEP_NESTING_DEPTH: usize = 4;
+const LARGE_CLASS_METHODS: usize = 15;
+const GOD_OBJECT_METHODS: usize = 25;
+const DATA_CLUMP_MIN_PARAMS: usize = 3;
+const PRIMITIVE_OBSESSION_MIN_PARAMS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeSmell {
+    LongMethod,
+    LargeClass,
+    GodObject,
+    DuplicateCode,
+    DeepNesting,
+    FeatureEnvy,
+    DataClump,
+    PrimitiveObsession,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Severity {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmellReport {
+    pub smell: CodeSmell,
+    pub file: String,
+    pub line_range: (usize, usize),
+    pub severity: Severity,
+    pub suggested_refactoring: String,
+}
+
+/// Heuristic, tree-sitter-backed code smell detector. Each pass is a cheap
+/// syntactic heuristic rather than a full semantic analysis (no type
+/// checking, no cross-file resolution) — good enough to flag candidates for
+/// a human or an LLM-backed refactoring pass to look at, not a guarantee.
+pub struct CodeSmellDetector {
+    parsers: HashMap<String, Parser>,
+}
+
+impl CodeSmellDetector {
+    pub fn new() -> Result<Self> {
+        let mut parsers = HashMap::new();
+        Self::try_init_parser(&mut parsers, "python", unsafe { tree_sitter_python() });
+        Self::try_init_parser(&mut parsers, "javascript", unsafe { tree_sitter_javascript() });
+        Self::try_init_parser(&mut parsers, "typescript", unsafe { tree_sitter_typescript() });
+        Self::try_init_parser(&mut parsers, "rust", unsafe { tree_sitter_rust() });
+        Self::try_init_parser(&mut parsers, "go", unsafe { tree_sitter_go() });
+
+        Ok(Self { parsers })
+    }
+
+    fn try_init_parser(parsers: &mut HashMap<String, Parser>, language: &str, ts_language: Language) {
+        let mut parser = Parser::new();
+        match parser.set_language(ts_language) {
+            Ok(()) => {
+                parsers.insert(language.to_string(), parser);
+            }
+            Err(e) => warn!("Failed to initialize tree-sitter parser for {}: {}", language, e),
+        }
+    }
+
+    /// Runs every heuristic pass over `source` and returns one [`SmellReport`]
+    /// per finding. Returns an empty list (rather than an error) for
+    /// languages without a registered parser, since "no smells found" and
+    /// "can't analyze this language" should both just mean no findings.
+    pub fn analyze(&mut self, source: &str, language: &str, file: &str) -> Result<Vec<SmellReport>> {
+        let Some(parser) = self.parsers.get_mut(language) else {
+            warn!("No tree-sitter parser for language '{}', skipping smell analysis for {}", language, file);
+            return Ok(Vec::new());
+        };
+
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| anyhow!("Failed to parse {} as {}", file, language))?;
+
+        let mut reports = Vec::new();
+        let function_nodes = collect_nodes(tree.root_node(), &is_function_like);
+
+        detect_long_methods(&function_nodes, source, file, &mut reports);
+        detect_deep_nesting(&function_nodes, source, file, &mut reports);
+        detect_large_classes(&tree, source, file, &mut reports);
+        detect_duplicate_code(&function_nodes, source, file, &mut reports);
+        detect_feature_envy(&function_nodes, source, file, &mut reports);
+        detect_data_clumps(&function_nodes, source, file, &mut reports);
+        detect_primitive_obsession(&function_nodes, source, file, &mut reports);
+
+        Ok(reports)
+    }
+}
+
+fn is_function_like(kind: &str) -> bool {
+    kind.contains("function") || kind.contains("method")
+}
+
+fn is_class_like(kind: &str) -> bool {
+    kind.contains("class") || matches!(kind, "struct_item" | "impl_item" | "trait_item")
+}
+
+fn is_block_like(kind: &str) -> bool {
+    ["if", "for", "while", "match", "switch", "loop", "catch", "case"]
+        .iter()
+        .any(|keyword| kind.contains(keyword))
+}
+
+/// Walks `node` and its descendants, collecting every node whose kind
+/// matches `predicate`.
+fn collect_nodes<'a>(node: Node<'a>, predicate: &impl Fn(&str) -> bool) -> Vec<Node<'a>> {
+    let mut matches = Vec::new();
+    let mut cursor = node.walk();
+    let mut stack = vec![node];
+    while let Some(current) = stack.pop() {
+        if predicate(current.kind()) {
+            matches.push(current);
+        }
+        for child in current.children(&mut cursor) {
+            stack.push(child);
+        }
+    }
+    matches
+}
+
+fn node_text<'a>(node: Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or("")
+}
+
+fn node_line_range(node: Node) -> (usize, usize) {
+    (node.start_position().row + 1, node.end_position().row + 1)
+}
+
+fn function_name(node: Node, source: &str) -> String {
+    node.child_by_field_name("name")
+        .map(|n| node_text(n, source).to_string())
+        .unwrap_or_else(|| format!("<anonymous @ line {}>", node.start_position().row + 1))
+}
+
+fn function_params(node: Node) -> Option<Node> {
+    node.children(&mut node.walk())
+        .find(|child| child.kind().contains("parameters") || child.kind().contains("parameter_list"))
+}
+
+fn detect_long_methods(functions: &[Node], source: &str, file: &str, reports: &mut Vec<SmellReport>) {
+    for &function in functions {
+        let (start, end) = node_line_range(function);
+        let line_count = end - start + 1;
+        if line_count > LONG_METHOD_LINES {
+            reports.push(SmellReport {
+                smell: CodeSmell::LongMethod,
+                file: file.to_string(),
+                line_range: (start, end),
+                severity: if line_count > LONG_METHOD_LINES * 2 { Severity::High } else { Severity::Medium },
+                suggested_refactoring: format!(
+                    "`{}` is {} lines long; extract cohesive chunks into smaller helper functions.",
+                    function_name(function, source),
+                    line_count
+                ),
+            });
+        }
+    }
+}
+
+fn max_nesting_depth(node: Node) -> usize {
+    let mut cursor = node.walk();
+    let child_depth = node
+        .children(&mut cursor)
+        .map(max_nesting_depth)
+        .max()
+        .unwrap_or(0);
+    if is_block_like(node.kind()) {
+        child_depth + 1
+    } else {
+        child_depth
+    }
+}
+
+fn detect_deep_nesting(functions: &[Node], source: &str, file: &str, reports: &mut Vec<SmellReport>) {
+    for &function in functions {
+        let depth = max_nesting_depth(function);
+        if depth > DEEP_NESTING_DEPTH {
+            let (start, end) = node_line_range(function);
+            reports.push(SmellReport {
+                smell: CodeSmell::DeepNesting,
+                file: file.to_string(),
+                line_range: (start, end),
+                severity: if depth > DEEP_NESTING_DEPTH + 2 { Severity::High } else { Severity::Medium },
+                suggested_refactoring: format!(
+                    "`{}` nests {} levels deep; use early returns or extract nested blocks into helper functions.",
+                    function_name(function, source),
+                    depth
+                ),
+            });
+        }
+    }
+}
+
+fn detect_large_classes(tree: &Tree, source: &str, file: &str, reports: &mut Vec<SmellReport>) {
+    let classes = collect_nodes(tree.root_node(), &is_class_like);
+    for class in classes {
+        let method_count = collect_nodes(class, &is_function_like).len();
+        let (smell, severity) = if method_count > GOD_OBJECT_METHODS {
+            (CodeSmell::GodObject, Severity::Critical)
+        } else if method_count > LARGE_CLASS_METHODS {
+            (CodeSmell::LargeClass, Severity::High)
+        } else {
+            continue;
+        };
+
+        let (start, end) = node_line_range(class);
+        let name = class
+            .child_by_field_name("name")
+            .map(|n| node_text(n, source).to_string())
+            .unwrap_or_else(|| format!("<type @ line {}>", start));
+        reports.push(SmellReport {
+            smell,
+            file: file.to_string(),
+            line_range: (start, end),
+            severity,
+            suggested_refactoring: format!(
+                "`{}` defines {} methods; split responsibilities into smaller, focused types.",
+                name, method_count
+            ),
+        });
+    }
+}
+
+/// Minimum normalized body length (in characters) before two functions with
+/// identical bodies are reported as duplicates — short bodies (e.g. simple
+/// getters) collide too often to be useful signal.
+const DUPLICATE_MIN_BODY_LEN: usize = 60;
+
+fn normalized_body(node: Node, source: &str) -> String {
+    node_text(node, source)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn detect_duplicate_code(functions: &[Node], source: &str, file: &str, reports: &mut Vec<SmellReport>) {
+    let mut bodies: HashMap<String, Vec<Node>> = HashMap::new();
+    for &function in functions {
+        let normalized = normalized_body(function, source);
+        if normalized.len() >= DUPLICATE_MIN_BODY_LEN {
+            bodies.entry(normalized).or_default().push(function);
+        }
+    }
+
+    for group in bodies.values().filter(|group| group.len() > 1) {
+        for &function in group {
+            let (start, end) = node_line_range(function);
+            let others: Vec<String> = group
+                .iter()
+                .filter(|&&other| other != function)
+                .map(|&other| function_name(other, source))
+                .collect();
+            reports.push(SmellReport {
+                smell: CodeSmell::DuplicateCode,
+                file: file.to_string(),
+                line_range: (start, end),
+                severity: Severity::Medium,
+                suggested_refactoring: format!(
+                    "`{}` duplicates the body of {}; extract the shared logic into one function.",
+                    function_name(function, source),
+                    others.join(", ")
+                ),
+            });
+        }
+    }
+}
+
+fn detect_feature_envy(functions: &[Node], source: &str, file: &str, reports: &mut Vec<SmellReport>) {
+    for &function in functions {
+        let text = node_text(function, source);
+        let mut self_accesses = 0usize;
+        let mut external_accesses: HashMap<&str, usize> = HashMap::new();
+
+        for token in text.split(|c: char| !c.is_alphanumeric() && c != '_' && c != '.') {
+            let Some((receiver, _member)) = token.split_once('.') else { continue };
+            if receiver.is_empty() {
+                continue;
+            }
+            if matches!(receiver, "self" | "this") {
+                self_accesses += 1;
+            } else if receiver.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_') {
+                *external_accesses.entry(receiver).or_insert(0) += 1;
+            }
+        }
+
+        if let Some((&receiver, &count)) = external_accesses.iter().max_by_key(|(_, &count)| count) {
+            if count >= 3 && count > self_accesses {
+                let (start, end) = node_line_range(function);
+                reports.push(SmellReport {
+                    smell: CodeSmell::FeatureEnvy,
+                    file: file.to_string(),
+                    line_range: (start, end),
+                    severity: Severity::Low,
+                    suggested_refactoring: format!(
+                        "`{}` accesses `{}` {} times (more than its own members); consider moving this logic onto `{}`.",
+                        function_name(function, source),
+                        receiver,
+                        count,
+                        receiver
+                    ),
+                });
+            }
+        }
+    }
+}
+
+fn normalized_param_list(node: Node, source: &str) -> String {
+    node_text(node, source)
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect()
+}
+
+fn param_count(params: Node) -> usize {
+    params.named_child_count()
+}
+
+fn detect_data_clumps(functions: &[Node], source: &str, file: &str, reports: &mut Vec<SmellReport>) {
+    let mut signatures: HashMap<String, Vec<Node>> = HashMap::new();
+    for &function in functions {
+        let Some(params) = function_params(function) else { continue };
+        if param_count(params) < DATA_CLUMP_MIN_PARAMS {
+            continue;
+        }
+        signatures.entry(normalized_param_list(params, source)).or_default().push(function);
+    }
+
+    for group in signatures.values().filter(|group| group.len() > 1) {
+        for &function in group {
+            let (start, end) = node_line_range(function);
+            let others: Vec<String> = group
+                .iter()
+                .filter(|&&other| other != function)
+                .map(|&other| function_name(other, source))
+                .collect();
+            reports.push(SmellReport {
+                smell: CodeSmell::DataClump,
+                file: file.to_string(),
+                line_range: (start, end),
+                severity: Severity::Low,
+                suggested_refactoring: format!(
+                    "`{}` repeats the same parameter group as {}; bundle them into a struct.",
+                    function_name(function, source),
+                    others.join(", ")
+                ),
+            });
+        }
+    }
+}
+
+const PRIMITIVE_TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32", "f64", "bool",
+    "str", "String", "char", "int", "float", "string", "boolean", "number",
+];
+
+fn detect_primitive_obsession(functions: &[Node], source: &str, file: &str, reports: &mut Vec<SmellReport>) {
+    for &function in functions {
+        let Some(params) = function_params(function) else { continue };
+        let param_text = node_text(params, source);
+        let primitive_count = PRIMITIVE_TYPES
+            .iter()
+            .map(|ty| param_text.matches(&format!(": {}", ty)).count() + param_text.matches(&format!("<{}", ty)).count())
+            .sum::<usize>();
+
+        if primitive_count >= PRIMITIVE_OBSESSION_MIN_PARAMS {
+            let (start, end) = node_line_range(function);
+            reports.push(SmellReport {
+                smell: CodeSmell::PrimitiveObsession,
+                file: file.to_string(),
+                line_range: (start, end),
+                severity: Severity::Low,
+                suggested_refactoring: format!(
+                    "`{}` takes {} primitive-typed parameters; introduce a small domain type to group them.",
+                    function_name(function, source),
+                    primitive_count
+                ),
+            });
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod code_smell_detector;
+pub mod cost_optimizer;
+pub mod predictive_debugging;
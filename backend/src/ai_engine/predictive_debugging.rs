@@ -0,0 +1,346 @@
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+use tree_sitter::{Language, Node, Parser};
+
+use crate::database::repositories::RunsRepository;
+use crate::sandbox::ExecutionResult;
+
+// Tree-sitter language declarations, mirroring `code_smell_detector`.
+extern "C" {
+    fn tree_sitter_python() -> Language;
+    fn tree_sitter_javascript() -> Language;
+    fn tree_sitter_typescript() -> Language;
+    fn tree_sitter_rust() -> Language;
+    fn tree_sitter_go() -> Language;
+}
+
+/// How many rows of failure history to consider per function when querying
+/// the `runs` table — older failures are weak evidence about what's about to
+/// break next.
+const RECENT_FAILURES_WINDOW: i64 = 20;
+
+/// Cyclomatic complexity at or above this value alone saturates that signal's
+/// contribution to `failure_probability`.
+const HIGH_COMPLEXITY: usize = 15;
+
+/// Recent failure count at or above this value alone saturates that signal's
+/// contribution to `failure_probability`.
+const HIGH_RECENT_FAILURES: i64 = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PredictedFailure {
+    pub function_name: String,
+    pub file: String,
+    pub line: usize,
+    pub probability: f32,
+    pub reason: String,
+    pub suggested_test_cases: Vec<String>,
+}
+
+/// Ranks functions by how likely they are to fail next. Combines three cheap
+/// heuristic signals — AST-derived cyclomatic complexity, how often the
+/// `runs` table has recorded a failure mentioning this function, and a
+/// couple of known-risky source patterns — into a single score. Like
+/// `CodeSmellDetector`, this is a ranking aid for "what should I look at
+/// first", not a semantic guarantee.
+pub struct PredictiveDebugger {
+    parsers: HashMap<String, Parser>,
+    runs_repository: Option<Arc<RunsRepository>>,
+}
+
+impl PredictiveDebugger {
+    pub fn new(runs_repository: Option<Arc<RunsRepository>>) -> Result<Self> {
+        let mut parsers = HashMap::new();
+        Self::try_init_parser(&mut parsers, "python", unsafe { tree_sitter_python() });
+        Self::try_init_parser(&mut parsers, "javascript", unsafe { tree_sitter_javascript() });
+        Self::try_init_parser(&mut parsers, "typescript", unsafe { tree_sitter_typescript() });
+        Self::try_init_parser(&mut parsers, "rust", unsafe { tree_sitter_rust() });
+        Self::try_init_parser(&mut parsers, "go", unsafe { tree_sitter_go() });
+
+        Ok(Self { parsers, runs_repository })
+    }
+
+    fn try_init_parser(parsers: &mut HashMap<String, Parser>, language: &str, ts_language: Language) {
+        let mut parser = Parser::new();
+        match parser.set_language(ts_language) {
+            Ok(()) => {
+                parsers.insert(language.to_string(), parser);
+            }
+            Err(e) => warn!("Failed to initialize tree-sitter parser for {}: {}", language, e),
+        }
+    }
+
+    /// Predicts which functions in `source` are most likely to fail next.
+    /// Returns an empty list (rather than an error) for languages without a
+    /// registered parser, matching `CodeSmellDetector::analyze`. `test_results`
+    /// is an optional signal from the most recent run — if it failed and its
+    /// output mentions a function, that function's probability is boosted in
+    /// addition to the `runs`-table history.
+    pub async fn predict_failures(
+        &mut self,
+        source: &str,
+        language: &str,
+        test_results: Option<&ExecutionResult>,
+    ) -> Result<Vec<PredictedFailure>> {
+        let Some(parser) = self.parsers.get_mut(language) else {
+            warn!("No tree-sitter parser for language '{}', skipping failure prediction", language);
+            return Ok(Vec::new());
+        };
+
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| anyhow!("Failed to parse source as {}", language))?;
+
+        let function_nodes = collect_nodes(tree.root_node(), &is_function_like);
+
+        let mut predictions = Vec::with_capacity(function_nodes.len());
+        for function in function_nodes {
+            let name = function_name(function, source);
+            let body = node_text(function, source);
+
+            let complexity = cyclomatic_complexity(function);
+            let recent_failures = self.recent_failure_count(&name).await;
+            let (unchecked_indexing, unused_results) = risky_pattern_counts(body);
+            let currently_failing = test_results.is_some_and(|result| !result.success && mentions(result, &name));
+
+            let probability = failure_probability(
+                complexity,
+                recent_failures,
+                unchecked_indexing + unused_results,
+                currently_failing,
+            );
+
+            predictions.push(PredictedFailure {
+                function_name: name.clone(),
+                file: "input".to_string(),
+                line: function.start_position().row + 1,
+                probability,
+                reason: describe_reason(complexity, recent_failures, unchecked_indexing, unused_results, currently_failing),
+                suggested_test_cases: suggest_test_cases(&name, complexity, unchecked_indexing, unused_results, recent_failures),
+            });
+        }
+
+        predictions.sort_by(|a, b| b.probability.partial_cmp(&a.probability).unwrap_or(std::cmp::Ordering::Equal));
+        Ok(predictions)
+    }
+
+    async fn recent_failure_count(&self, function_name: &str) -> i64 {
+        let Some(repository) = &self.runs_repository else {
+            return 0;
+        };
+
+        match repository.count_recent_failures_mentioning(function_name, RECENT_FAILURES_WINDOW).await {
+            Ok(count) => count,
+            Err(e) => {
+                warn!(function = function_name, error = %e, "Failed to look up recent test failures");
+                0
+            }
+        }
+    }
+}
+
+fn is_function_like(kind: &str) -> bool {
+    kind.contains("function") || kind.contains("method")
+}
+
+fn is_decision_point(kind: &str) -> bool {
+    ["if", "for", "while", "match_arm", "case", "catch", "except", "conditional", "ternary"]
+        .iter()
+        .any(|keyword| kind.contains(keyword))
+}
+
+/// Walks `node` and its descendants, collecting every node whose kind
+/// matches `predicate`. Mirrors `code_smell_detector::collect_nodes`.
+fn collect_nodes<'a>(node: Node<'a>, predicate: &impl Fn(&str) -> bool) -> Vec<Node<'a>> {
+    let mut matches = Vec::new();
+    let mut cursor = node.walk();
+    let mut stack = vec![node];
+    while let Some(current) = stack.pop() {
+        if predicate(current.kind()) {
+            matches.push(current);
+        }
+        for child in current.children(&mut cursor) {
+            stack.push(child);
+        }
+    }
+    matches
+}
+
+fn node_text<'a>(node: Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or("")
+}
+
+fn function_name(node: Node, source: &str) -> String {
+    node.child_by_field_name("name")
+        .map(|n| node_text(n, source).to_string())
+        .unwrap_or_else(|| format!("<anonymous @ line {}>", node.start_position().row + 1))
+}
+
+/// Cyclomatic complexity, approximated as one plus the number of decision
+/// points (branches, loops, match arms) in the function body.
+fn cyclomatic_complexity(function: Node) -> usize {
+    1 + collect_nodes(function, &is_decision_point).len()
+}
+
+/// Counts two known-risky patterns in `body`: direct (unchecked) array/slice
+/// indexing via `name[expr]` instead of `.get(expr)`, and statements that
+/// call a commonly-fallible method and discard its `Result` without `?`,
+/// `.unwrap()`/`.expect()`, or an assignment. Both are textual heuristics —
+/// good enough to flag candidates, not a type-aware guarantee.
+fn risky_pattern_counts(body: &str) -> (usize, usize) {
+    let indexing = Regex::new(r"[A-Za-z_]\w*\[[^\[\]]*\]").expect("valid regex");
+    let unchecked_indexing = indexing.find_iter(body).count();
+
+    const FALLIBLE_VERBS: &[&str] = &[
+        "write", "write_all", "read_to_string", "parse", "send", "execute", "remove_file", "create_dir_all", "flush",
+        "set_current_dir",
+    ];
+    let unused_results = body
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            FALLIBLE_VERBS.iter().any(|verb| line.contains(&format!(".{verb}(")))
+                && line.ends_with(");")
+                && !line.contains('?')
+                && !line.starts_with("let ")
+                && !line.contains('=')
+                && !line.contains(".unwrap()")
+                && !line.contains(".expect(")
+        })
+        .count();
+
+    (unchecked_indexing, unused_results)
+}
+
+fn mentions(result: &ExecutionResult, function_name: &str) -> bool {
+    result.stdout.contains(function_name) || result.stderr.contains(function_name)
+}
+
+fn failure_probability(complexity: usize, recent_failures: i64, risky_pattern_count: usize, currently_failing: bool) -> f32 {
+    let complexity_score = (complexity as f32 / HIGH_COMPLEXITY as f32).min(1.0);
+    let failure_score = (recent_failures as f32 / HIGH_RECENT_FAILURES as f32).min(1.0);
+    let risky_score = (risky_pattern_count as f32 / 3.0).min(1.0);
+
+    let mut probability = 0.45 * complexity_score + 0.35 * failure_score + 0.20 * risky_score;
+    if currently_failing {
+        probability = (probability + 0.25).min(1.0);
+    }
+    probability.clamp(0.0, 1.0)
+}
+
+fn describe_reason(
+    complexity: usize,
+    recent_failures: i64,
+    unchecked_indexing: usize,
+    unused_results: usize,
+    currently_failing: bool,
+) -> String {
+    let mut parts = vec![format!("cyclomatic complexity {complexity}")];
+    if recent_failures > 0 {
+        parts.push(format!("{recent_failures} recent test failure(s)"));
+    }
+    if unchecked_indexing > 0 {
+        parts.push(format!("{unchecked_indexing} unchecked array access(es)"));
+    }
+    if unused_results > 0 {
+        parts.push(format!("{unused_results} discarded Result(s)"));
+    }
+    if currently_failing {
+        parts.push("currently failing".to_string());
+    }
+    parts.join(", ")
+}
+
+fn suggest_test_cases(
+    function_name: &str,
+    complexity: usize,
+    unchecked_indexing: usize,
+    unused_results: usize,
+    recent_failures: i64,
+) -> Vec<String> {
+    let mut suggestions = Vec::new();
+    if complexity > HIGH_COMPLEXITY / 2 {
+        suggestions.push(format!(
+            "Add tests covering each branch of `{function_name}` (cyclomatic complexity {complexity})."
+        ));
+    }
+    if unchecked_indexing > 0 {
+        suggestions.push(format!(
+            "Add a test that calls `{function_name}` with an empty/out-of-bounds input to exercise its unchecked indexing."
+        ));
+    }
+    if unused_results > 0 {
+        suggestions.push(format!(
+            "Add a test asserting `{function_name}` propagates failure instead of silently ignoring a discarded `Result`."
+        ));
+    }
+    if recent_failures > 0 {
+        suggestions.push(format!(
+            "Re-run the tests that previously failed on `{function_name}` to confirm the fix holds."
+        ));
+    }
+    if suggestions.is_empty() {
+        suggestions.push(format!("Add a baseline regression test for `{function_name}`."));
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPLEX_FUNCTION: &str = r#"
+fn flaky(items: &[i32], index: usize) -> i32 {
+    if index > 0 {
+        if items[index] > 0 {
+            for i in 0..items.len() {
+                if items[i] > 0 && items[i] < 10 {
+                    if i % 2 == 0 {
+                        items.len();
+                    } else if i % 3 == 0 {
+                        return items[i];
+                    }
+                }
+            }
+        }
+    }
+    items[0]
+}
+
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+fn greet(name: &str) -> String {
+    format!("hello {}", name)
+}
+"#;
+
+    #[tokio::test]
+    async fn deliberately_complex_function_ranks_in_top_three() {
+        let mut debugger = PredictiveDebugger::new(None).expect("parser init");
+        let predictions = debugger
+            .predict_failures(COMPLEX_FUNCTION, "rust", None)
+            .await
+            .expect("prediction should succeed");
+
+        assert!(predictions.len() >= 3, "expected at least 3 functions analyzed");
+        let top_three: Vec<&str> = predictions.iter().take(3).map(|p| p.function_name.as_str()).collect();
+        assert!(top_three.contains(&"flaky"), "expected the complex, risky function to rank in the top 3, got {top_three:?}");
+    }
+
+    #[test]
+    fn risky_pattern_counts_flags_unchecked_indexing_and_discarded_results() {
+        let body = r#"
+            let value = items[0];
+            writer.write(data);
+        "#;
+        let (unchecked_indexing, unused_results) = risky_pattern_counts(body);
+        assert_eq!(unchecked_indexing, 1);
+        assert_eq!(unused_results, 1);
+    }
+}
@@ -6,7 +6,7 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
 use crate::AppState;
-use crate::providers::traits::{CompletionRequest, ProviderError};
+use crate::providers::traits::{Attachment, CompletionRequest, ProviderError, Tool};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CompletionApiRequest {
@@ -17,6 +17,18 @@ pub struct CompletionApiRequest {
     pub temperature: Option<f32>,
     pub provider_preference: Option<String>,
     pub model: Option<String>,
+    pub tools: Option<Vec<Tool>>,
+    pub tool_choice: Option<serde_json::Value>,
+    /// Images (screenshots, diagrams, ...) to accompany the prompt. Providers
+    /// without vision support reject these with a clear error instead of
+    /// silently ignoring them.
+    pub attachments: Option<Vec<Attachment>>,
+    pub top_p: Option<f32>,
+    pub frequency_penalty: Option<f32>,
+    pub presence_penalty: Option<f32>,
+    /// Sequences that stop generation. Providers without native support log a
+    /// warning and ignore it rather than failing the request.
+    pub stop: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -51,6 +63,13 @@ pub async fn complete_code(
         model: request.model.or(query.model),
         system_prompt: None,
         context: request.context,
+        tools: request.tools,
+        tool_choice: request.tool_choice,
+        attachments: request.attachments.clone(),
+        top_p: request.top_p,
+        frequency_penalty: request.frequency_penalty,
+        presence_penalty: request.presence_penalty,
+        stop_sequences: request.stop.clone(),
     };
     
     // Select provider
@@ -151,6 +170,10 @@ pub async fn analyze_code(
         "suggestions" => crate::providers::traits::AnalysisType::Suggestions,
         "documentation" => crate::providers::traits::AnalysisType::Documentation,
         "testing" => crate::providers::traits::AnalysisType::Testing,
+        "refactoring" => crate::providers::traits::AnalysisType::Refactoring,
+        "code_review" => crate::providers::traits::AnalysisType::CodeReview,
+        "smells" | "code_smells" => crate::providers::traits::AnalysisType::CodeSmells,
+        "doc_coverage" => crate::providers::traits::AnalysisType::DocCoverage,
         _ => crate::providers::traits::AnalysisType::Quality,
     };
     
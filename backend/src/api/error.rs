@@ -0,0 +1,151 @@
+//! Structured error type for API handlers, so clients can branch on a
+//! stable `code` string instead of HTTP status (which is ambiguous when
+//! multiple failure modes share the same status, e.g. both "session not
+//! found" and "API key missing" would otherwise be a bare 404/401).
+//!
+//! Adoption is incremental: handlers that already return `StatusCode`
+//! continue to work unchanged; new/updated handlers should prefer
+//! `Result<_, BackendError>` and pick (or add) a variant here instead of
+//! reaching for a bare status.
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+/// Wire shape for every `BackendError` response: a human-readable message,
+/// a stable machine-readable `code`, and optional extra context.
+#[derive(Debug, Serialize)]
+pub struct ApiErrorBody {
+    pub error: String,
+    pub code: String,
+    pub details: Option<String>,
+}
+
+/// Every structured failure mode an API handler can return. Add a variant
+/// here (and a case in `status_and_code`) rather than reaching for a bare
+/// `StatusCode` when a handler's failure needs to be distinguishable by a
+/// client on `code`.
+#[derive(Debug)]
+pub enum BackendError {
+    ProviderUnavailable { provider: String, details: Option<String> },
+    ApiKeyMissing { provider: String },
+    RateLimited { retry_after_seconds: Option<u64> },
+    SessionNotFound { session_id: String },
+    BudgetExceeded { details: Option<String> },
+    RiskGateBlocked { reason: String },
+    ValidationError(String),
+    Unauthorized,
+    Forbidden,
+    NotFound(String),
+    Conflict(String),
+    Internal(String),
+}
+
+impl BackendError {
+    fn status_and_code(&self) -> (StatusCode, &'static str) {
+        match self {
+            BackendError::ProviderUnavailable { .. } => (StatusCode::BAD_GATEWAY, "PROVIDER_UNAVAILABLE"),
+            BackendError::ApiKeyMissing { .. } => (StatusCode::UNAUTHORIZED, "API_KEY_MISSING"),
+            BackendError::RateLimited { .. } => (StatusCode::TOO_MANY_REQUESTS, "RATE_LIMITED"),
+            BackendError::SessionNotFound { .. } => (StatusCode::NOT_FOUND, "SESSION_NOT_FOUND"),
+            BackendError::BudgetExceeded { .. } => (StatusCode::FORBIDDEN, "BUDGET_EXCEEDED"),
+            BackendError::RiskGateBlocked { .. } => (StatusCode::FORBIDDEN, "RISK_GATE_BLOCKED"),
+            BackendError::ValidationError(_) => (StatusCode::BAD_REQUEST, "VALIDATION_ERROR"),
+            BackendError::Unauthorized => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED"),
+            BackendError::Forbidden => (StatusCode::FORBIDDEN, "FORBIDDEN"),
+            BackendError::NotFound(_) => (StatusCode::NOT_FOUND, "NOT_FOUND"),
+            BackendError::Conflict(_) => (StatusCode::CONFLICT, "CONFLICT"),
+            BackendError::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR"),
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            BackendError::ProviderUnavailable { provider, .. } => {
+                format!("Provider '{}' is currently unavailable", provider)
+            }
+            BackendError::ApiKeyMissing { provider } => {
+                format!("No API key configured for provider '{}'", provider)
+            }
+            BackendError::RateLimited { retry_after_seconds } => match retry_after_seconds {
+                Some(secs) => format!("Rate limit exceeded, retry after {}s", secs),
+                None => "Rate limit exceeded".to_string(),
+            },
+            BackendError::SessionNotFound { session_id } => {
+                format!("Session not found: {}", session_id)
+            }
+            BackendError::BudgetExceeded { .. } => "Budget exceeded".to_string(),
+            BackendError::RiskGateBlocked { reason } => format!("Blocked by risk gate: {}", reason),
+            BackendError::ValidationError(message) => message.clone(),
+            BackendError::Unauthorized => "Unauthorized".to_string(),
+            BackendError::Forbidden => "Forbidden".to_string(),
+            BackendError::NotFound(message) => message.clone(),
+            BackendError::Conflict(message) => message.clone(),
+            BackendError::Internal(message) => message.clone(),
+        }
+    }
+
+    fn details(&self) -> Option<String> {
+        match self {
+            BackendError::ProviderUnavailable { details, .. } => details.clone(),
+            BackendError::BudgetExceeded { details } => details.clone(),
+            BackendError::RateLimited { retry_after_seconds } => {
+                retry_after_seconds.map(|secs| format!("retry_after_seconds={}", secs))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl IntoResponse for BackendError {
+    fn into_response(self) -> Response {
+        let (status, code) = self.status_and_code();
+        if status.is_server_error() {
+            tracing::error!(code, "{}", self.message());
+        } else {
+            tracing::warn!(code, "{}", self.message());
+        }
+
+        let body = ApiErrorBody {
+            error: self.message(),
+            code: code.to_string(),
+            details: self.details(),
+        };
+
+        (status, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn body_json(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn missing_api_key_completion_error_has_stable_code() {
+        let response = BackendError::ApiKeyMissing { provider: "openrouter".to_string() }.into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let body = body_json(response).await;
+        assert_eq!(body["code"], "API_KEY_MISSING");
+        assert_eq!(body["error"], "No API key configured for provider 'openrouter'");
+        assert!(body["details"].is_null());
+    }
+
+    #[tokio::test]
+    async fn rate_limited_error_reports_retry_after_in_details() {
+        let response = BackendError::RateLimited { retry_after_seconds: Some(30) }.into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let body = body_json(response).await;
+        assert_eq!(body["code"], "RATE_LIMITED");
+        assert_eq!(body["details"], "retry_after_seconds=30");
+    }
+}
@@ -1,8 +1,11 @@
 pub mod agents;
+pub mod error;
 // pub mod collaboration; // TODO: Implement
-// pub mod enterprise; // TODO: Implement  
+// pub mod enterprise; // TODO: Implement
 // pub mod cost_analytics; // TODO: Implement
 
+pub use error::{ApiErrorBody, BackendError};
+
 use axum::{
     routing::{get, post},
     Router,
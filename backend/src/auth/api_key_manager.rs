@@ -2,6 +2,7 @@ use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use sha2::{Sha256, Digest};
@@ -11,6 +12,8 @@ use aes_gcm::{
 };
 use base64::{Engine as _, engine::general_purpose};
 
+use super::audit::{AuditEvent, AuditEventType, AuditOutcome, AuditService, log_event_fire_and_forget};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiKey {
     pub id: Uuid,
@@ -48,6 +51,7 @@ pub struct ApiKeyResponse {
 pub struct ApiKeyManager {
     pool: PgPool,
     encryption_key: [u8; 32],
+    audit_service: Option<Arc<dyn AuditService>>,
 }
 
 impl ApiKeyManager {
@@ -55,6 +59,34 @@ impl ApiKeyManager {
         Self {
             pool,
             encryption_key,
+            audit_service: None,
+        }
+    }
+
+    /// Emits one `AuditEvent` per call into `store_api_key`, `delete_api_key`,
+    /// and `get_api_key`, so access to a user's provider credentials is
+    /// traceable. No-op when not set.
+    pub fn with_audit_service(mut self, audit_service: Arc<dyn AuditService>) -> Self {
+        self.audit_service = Some(audit_service);
+        self
+    }
+
+    fn audit_event(&self, user_id: Uuid, action: &str, resource_id: Option<String>, outcome: AuditOutcome) -> AuditEvent {
+        AuditEvent {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type: AuditEventType::DataAccess,
+            user_id: Some(user_id),
+            session_id: None,
+            organization_id: Uuid::new_v4(), // Would be retrieved from user context
+            ip_address: "unknown".to_string(),
+            user_agent: "unknown".to_string(),
+            resource_type: Some("api_key".to_string()),
+            resource_id,
+            action: action.to_string(),
+            outcome,
+            details: HashMap::new(),
+            risk_score: None,
         }
     }
 
@@ -126,6 +158,11 @@ impl ApiKeyManager {
         .fetch_one(&self.pool)
         .await?;
 
+        if let Some(audit_service) = &self.audit_service {
+            let event = self.audit_event(user_id, "create_api_key", Some(api_key.id.to_string()), AuditOutcome::Success);
+            audit_service.log_event(event).await?;
+        }
+
         Ok(api_key)
     }
 
@@ -163,6 +200,11 @@ impl ApiKeyManager {
         .fetch_optional(&self.pool)
         .await?;
 
+        if let Some(audit_service) = &self.audit_service {
+            let event = self.audit_event(user_id, "read_api_key", None, AuditOutcome::Success);
+            log_event_fire_and_forget(audit_service.clone(), event);
+        }
+
         if let Some(row) = row {
             let decrypted = self.decrypt_key(&row.encrypted_key)?;
             Ok(Some(decrypted))
@@ -236,7 +278,15 @@ impl ApiKeyManager {
         .execute(&self.pool)
         .await?;
 
-        Ok(result.rows_affected() > 0)
+        let deleted = result.rows_affected() > 0;
+
+        if let Some(audit_service) = &self.audit_service {
+            let outcome = if deleted { AuditOutcome::Success } else { AuditOutcome::Failure };
+            let event = self.audit_event(user_id, "delete_api_key", Some(key_id.to_string()), outcome);
+            audit_service.log_event(event).await?;
+        }
+
+        Ok(deleted)
     }
 
     /// Get API key usage statistics
@@ -1,7 +1,9 @@
 use super::*;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
 use std::collections::HashMap;
+use std::sync::Arc;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
@@ -23,7 +25,7 @@ pub struct AuditEvent {
     pub risk_score: Option<f32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AuditEventType {
     Authentication,
     Authorization,
@@ -37,7 +39,7 @@ pub enum AuditEventType {
     AdminAction,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AuditOutcome {
     Success,
     Failure,
@@ -81,35 +83,86 @@ pub struct AuditSearchCriteria {
     pub offset: Option<usize>,
 }
 
+/// Mirrors the `audit_logs` columns selected by `search_events`. `event_type`
+/// and `outcome` stay `String` here too, matching how `log_event` stores
+/// them (their `serde_json::to_string` text representation), and get decoded
+/// back into the real enums in the caller.
+#[derive(Debug, FromRow)]
+struct AuditLogRow {
+    id: Uuid,
+    event_type: String,
+    user_id: Option<Uuid>,
+    session_id: Option<Uuid>,
+    organisation_id: Uuid,
+    action: String,
+    resource_type: Option<String>,
+    resource_id: Option<String>,
+    outcome: String,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+    risk_score: Option<f32>,
+    metadata: serde_json::Value,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug, Clone)]
 pub struct DatabaseAuditService {
-    // In a real implementation, this would have a database connection
-    // For now, we'll use in-memory storage for demonstration
-    events: std::sync::Arc<tokio::sync::RwLock<Vec<AuditEvent>>>,
+    pool: PgPool,
 }
 
 impl DatabaseAuditService {
-    pub fn new() -> Self {
-        Self {
-            events: std::sync::Arc::new(tokio::sync::RwLock::new(Vec::new())),
-        }
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
     }
 }
 
+/// Logs `event` via `audit_service` without making the caller wait on the
+/// insert. Only ever appropriate for audit trails of *read* operations
+/// (looking a resource up, listing it, searching it) where losing an event
+/// to a process crash is an acceptable trade for not adding audit-write
+/// latency to a read's response time. Anything that mutates state should
+/// call `AuditService::log_event` directly and `.await` it, so the write is
+/// guaranteed to land before the request completes.
+pub fn log_event_fire_and_forget(audit_service: Arc<dyn AuditService>, event: AuditEvent) {
+    tokio::spawn(async move {
+        if let Err(e) = audit_service.log_event(event).await {
+            warn!("Failed to record fire-and-forget audit event: {}", e);
+        }
+    });
+}
+
 #[async_trait::async_trait]
 impl AuditService for DatabaseAuditService {
     async fn log_event(&self, event: AuditEvent) -> Result<()> {
-        info!("Audit Event: {} - {} - {}", event.event_type, event.action, event.outcome);
-        
-        // In production, this would write to a database
-        let mut events = self.events.write().await;
-        events.push(event);
-        
-        // Keep only last 10000 events in memory
-        if events.len() > 10000 {
-            events.remove(0);
-        }
-        
+        debug!("Audit Event: {:?} - {} - {:?}", event.event_type, event.action, event.outcome);
+
+        sqlx::query(
+            r#"
+            INSERT INTO audit_logs (
+                id, event_type, user_id, session_id, organisation_id, action,
+                resource_type, resource_id, outcome, ip_address, user_agent,
+                risk_score, metadata, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            "#,
+        )
+        .bind(event.id)
+        .bind(serde_json::to_string(&event.event_type)?)
+        .bind(event.user_id)
+        .bind(event.session_id)
+        .bind(event.organization_id)
+        .bind(event.action)
+        .bind(event.resource_type)
+        .bind(event.resource_id)
+        .bind(serde_json::to_string(&event.outcome)?)
+        .bind(event.ip_address)
+        .bind(event.user_agent)
+        .bind(event.risk_score)
+        .bind(serde_json::to_value(&event.details)?)
+        .bind(event.timestamp)
+        .execute(&self.pool)
+        .await?;
+
         Ok(())
     }
 
@@ -238,81 +291,68 @@ impl AuditService for DatabaseAuditService {
     }
 
     async fn search_events(&self, criteria: AuditSearchCriteria) -> Result<Vec<AuditEvent>> {
-        let events = self.events.read().await;
-        let mut filtered_events: Vec<AuditEvent> = events.iter()
-            .filter(|event| {
-                // Filter by time range
-                if let Some(start_time) = criteria.start_time {
-                    if event.timestamp < start_time {
-                        return false;
-                    }
-                }
-                if let Some(end_time) = criteria.end_time {
-                    if event.timestamp > end_time {
-                        return false;
-                    }
-                }
-
-                // Filter by user
-                if let Some(user_id) = criteria.user_id {
-                    if event.user_id != Some(user_id) {
-                        return false;
-                    }
-                }
-
-                // Filter by organization
-                if let Some(org_id) = criteria.organization_id {
-                    if event.organization_id != org_id {
-                        return false;
-                    }
-                }
-
-                // Filter by event types
-                if let Some(ref event_types) = criteria.event_types {
-                    if !event_types.contains(&event.event_type) {
-                        return false;
-                    }
-                }
-
-                // Filter by outcomes
-                if let Some(ref outcomes) = criteria.outcomes {
-                    if !outcomes.contains(&event.outcome) {
-                        return false;
-                    }
-                }
-
-                // Filter by resource type
-                if let Some(ref resource_type) = criteria.resource_type {
-                    if event.resource_type.as_ref() != Some(resource_type) {
-                        return false;
-                    }
-                }
-
-                // Filter by action
-                if let Some(ref action) = criteria.action {
-                    if &event.action != action {
-                        return false;
-                    }
-                }
-
-                true
+        // Event types/outcomes are stored as their `serde_json::to_string`
+        // text representation (see `log_event`), so filtering on them means
+        // matching against that same representation rather than a Postgres
+        // enum type.
+        let event_types: Option<Vec<String>> = criteria.event_types.map(|types| {
+            types.iter().filter_map(|t| serde_json::to_string(t).ok()).collect()
+        });
+        let outcomes: Option<Vec<String>> = criteria.outcomes.map(|outcomes| {
+            outcomes.iter().filter_map(|o| serde_json::to_string(o).ok()).collect()
+        });
+
+        let rows = sqlx::query_as::<_, AuditLogRow>(
+            r#"
+            SELECT id, event_type, user_id, session_id, organisation_id, action,
+                   resource_type, resource_id, outcome, ip_address, user_agent,
+                   risk_score, metadata, created_at
+            FROM audit_logs
+            WHERE ($1::timestamptz IS NULL OR created_at >= $1)
+              AND ($2::timestamptz IS NULL OR created_at <= $2)
+              AND ($3::uuid IS NULL OR user_id = $3)
+              AND ($4::uuid IS NULL OR organisation_id = $4)
+              AND ($5::text[] IS NULL OR event_type = ANY($5))
+              AND ($6::text[] IS NULL OR outcome = ANY($6))
+              AND ($7::text IS NULL OR resource_type = $7)
+              AND ($8::text IS NULL OR action = $8)
+            ORDER BY created_at DESC
+            LIMIT $9 OFFSET $10
+            "#,
+        )
+        .bind(criteria.start_time)
+        .bind(criteria.end_time)
+        .bind(criteria.user_id)
+        .bind(criteria.organization_id)
+        .bind(event_types.as_deref())
+        .bind(outcomes.as_deref())
+        .bind(criteria.resource_type)
+        .bind(criteria.action)
+        .bind(criteria.limit.unwrap_or(100) as i64)
+        .bind(criteria.offset.unwrap_or(0) as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| -> Result<AuditEvent> {
+                Ok(AuditEvent {
+                    id: row.id,
+                    timestamp: row.created_at,
+                    event_type: serde_json::from_str(&row.event_type)?,
+                    user_id: row.user_id,
+                    session_id: row.session_id,
+                    organization_id: row.organisation_id,
+                    ip_address: row.ip_address.unwrap_or_else(|| "unknown".to_string()),
+                    user_agent: row.user_agent.unwrap_or_else(|| "unknown".to_string()),
+                    resource_type: row.resource_type,
+                    resource_id: row.resource_id,
+                    action: row.action,
+                    outcome: serde_json::from_str(&row.outcome)?,
+                    details: serde_json::from_value(row.metadata).unwrap_or_default(),
+                    risk_score: row.risk_score,
+                })
             })
-            .cloned()
-            .collect();
-
-        // Sort by timestamp (newest first)
-        filtered_events.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-
-        // Apply pagination
-        let offset = criteria.offset.unwrap_or(0);
-        let limit = criteria.limit.unwrap_or(100);
-        
-        let end_index = std::cmp::min(offset + limit, filtered_events.len());
-        if offset < filtered_events.len() {
-            Ok(filtered_events[offset..end_index].to_vec())
-        } else {
-            Ok(vec![])
-        }
+            .collect()
     }
 
     async fn get_user_activity(&self, user_id: Uuid, days: u32) -> Result<Vec<AuditEvent>> {
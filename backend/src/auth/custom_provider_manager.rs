@@ -0,0 +1,158 @@
+use super::api_key_manager::ApiKeyManager;
+use crate::providers::custom_openai::validate_base_url;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct CustomProvider {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub base_url: String,
+    pub default_model: Option<String>,
+    pub allow_internal_network: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCustomProviderRequest {
+    pub name: String,
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub default_model: Option<String>,
+    /// Only honored when the caller passed `User::is_admin()`; see
+    /// `validate_base_url` for why a non-admin request always gets `false`.
+    #[serde(default)]
+    pub allow_internal_network: bool,
+}
+
+/// Per-user configuration for self-hosted OpenAI-compatible endpoints
+/// (vLLM, LM Studio, etc.), stored in the `custom_providers` table. Mirrors
+/// `ApiKeyManager`'s encrypt-at-rest pattern for the optional API key, and
+/// reuses its encryption key so both tables can be decrypted with the same
+/// master secret.
+pub struct CustomProviderManager {
+    pool: PgPool,
+    api_key_manager: Arc<ApiKeyManager>,
+}
+
+impl CustomProviderManager {
+    pub fn new(pool: PgPool, api_key_manager: Arc<ApiKeyManager>) -> Self {
+        Self { pool, api_key_manager }
+    }
+
+    /// Validates `request.base_url` against the SSRF guard, encrypts the API
+    /// key if one was given, and inserts the row. `is_admin` gates whether
+    /// `request.allow_internal_network` is honored or silently downgraded.
+    pub async fn create_custom_provider(&self, user_id: Uuid, request: CreateCustomProviderRequest, is_admin: bool) -> Result<CustomProvider> {
+        let allow_internal_network = request.allow_internal_network && is_admin;
+        validate_base_url(&request.base_url, allow_internal_network).await?;
+
+        let encrypted_api_key = request.api_key.as_deref()
+            .map(|key| self.api_key_manager.encrypt_key(key))
+            .transpose()?;
+
+        let provider = sqlx::query_as::<_, CustomProvider>(
+            r#"
+            INSERT INTO custom_providers (user_id, name, base_url, encrypted_api_key, default_model, allow_internal_network)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, name, base_url, default_model, allow_internal_network, created_at, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(request.name)
+        .bind(request.base_url)
+        .bind(encrypted_api_key)
+        .bind(request.default_model)
+        .bind(allow_internal_network)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(provider)
+    }
+
+    pub async fn list_custom_providers(&self, user_id: Uuid) -> Result<Vec<CustomProvider>> {
+        let providers = sqlx::query_as::<_, CustomProvider>(
+            r#"
+            SELECT id, user_id, name, base_url, default_model, allow_internal_network, created_at, updated_at
+            FROM custom_providers
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(providers)
+    }
+
+    /// Looks up a custom provider by name, scoped to its owning user, and
+    /// decrypts its API key so the caller can hand both straight to
+    /// `CustomOpenAIProvider::new`.
+    pub async fn get_custom_provider(&self, user_id: Uuid, name: &str) -> Result<Option<(CustomProvider, Option<String>)>> {
+        let row = sqlx::query_as::<_, CustomProviderWithKeyRow>(
+            r#"
+            SELECT id, user_id, name, base_url, encrypted_api_key, default_model, allow_internal_network, created_at, updated_at
+            FROM custom_providers
+            WHERE user_id = $1 AND name = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else { return Ok(None) };
+
+        let api_key = row.encrypted_api_key.as_deref()
+            .map(|key| self.api_key_manager.decrypt_key(key))
+            .transpose()?;
+
+        Ok(Some((
+            CustomProvider {
+                id: row.id,
+                user_id: row.user_id,
+                name: row.name,
+                base_url: row.base_url,
+                default_model: row.default_model,
+                allow_internal_network: row.allow_internal_network,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+            },
+            api_key,
+        )))
+    }
+
+    pub async fn delete_custom_provider(&self, user_id: Uuid, provider_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM custom_providers WHERE id = $1 AND user_id = $2")
+            .bind(provider_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// `CustomProvider` plus the encrypted API key column, for the one lookup
+/// (`get_custom_provider`) that needs to decrypt it. Kept separate from
+/// `CustomProvider` itself so the public struct doesn't carry a ciphertext
+/// field nobody else should see.
+#[derive(Debug, FromRow)]
+struct CustomProviderWithKeyRow {
+    id: Uuid,
+    user_id: Uuid,
+    name: String,
+    base_url: String,
+    encrypted_api_key: Option<String>,
+    default_model: Option<String>,
+    allow_internal_network: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
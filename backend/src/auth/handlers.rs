@@ -1,4 +1,4 @@
-use super::{user_service, api_key_manager, jwt, User, Role, Permission, AuthContext};
+use super::{user_service, api_key_manager, jwt, AuthContext};
 use crate::AppState;
 use axum::{
     extract::{State, Json},
@@ -73,98 +73,21 @@ pub async fn refresh_token_handler(
     State(state): State<crate::AppState>,
     Json(request): Json<jwt::RefreshRequest>,
 ) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
-    // Validate refresh token and get user info
-    let claims = match state.jwt_manager.validate_token(&request.refresh_token, jwt::TokenType::Refresh) {
-        Ok(claims) => claims,
-        Err(_) => return Err((
-            StatusCode::UNAUTHORIZED,
-            ResponseJson(json!({
-                "success": false,
-                "error": "Invalid refresh token"
-            }))
-        ))
-    };
-
-    let user_id = match Uuid::parse_str(&claims.sub) {
-        Ok(id) => id,
-        Err(_) => return Err((
-            StatusCode::BAD_REQUEST,
-            ResponseJson(json!({
-                "success": false,
-                "error": "Invalid user ID in token"
-            }))
-        ))
-    };
-
-    let session_id = match Uuid::parse_str(&claims.session_id) {
-        Ok(id) => id,
-        Err(_) => return Err((
-            StatusCode::BAD_REQUEST,
-            ResponseJson(json!({
-                "success": false,
-                "error": "Invalid session ID in token"
-            }))
-        ))
-    };
-
-    // Validate session is still active
-    match state.user_service.validate_session(session_id).await {
-        Ok(true) => {},
-        Ok(false) => return Err((
+    // `UserService::rotate_refresh_token` owns validating the token, the
+    // session, and the reuse-detection/rotation bookkeeping.
+    match state.user_service.rotate_refresh_token(&request.refresh_token).await {
+        Ok(user_service::RefreshOutcome::Rotated(tokens)) => Ok(ResponseJson(json!({
+            "success": true,
+            "message": "Tokens refreshed successfully",
+            "tokens": tokens
+        }))),
+        Ok(user_service::RefreshOutcome::ReuseDetected) => Err((
             StatusCode::UNAUTHORIZED,
             ResponseJson(json!({
                 "success": false,
-                "error": "Session expired or invalid"
+                "error": "Refresh token reuse detected; please log in again"
             }))
         )),
-        Err(e) => return Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            ResponseJson(json!({
-                "success": false,
-                "error": e.to_string()
-            }))
-        ))
-    };
-
-    // Get user for token generation
-    let user = match state.user_service.get_user(user_id).await {
-        Ok(Some(user_response)) => {
-            // Convert UserResponse to User for JWT generation
-            User {
-                id: user_response.id,
-                email: user_response.email,
-                name: user_response.full_name.unwrap_or_else(|| user_response.username),
-                organization_id: Uuid::new_v4(), // TODO: Implement organizations
-                roles: vec![Role::developer_role(Uuid::new_v4())], // TODO: Get actual roles
-                permissions: vec![Permission::ApiAccess, Permission::CreatePlan], // TODO: Get actual permissions
-                created_at: user_response.created_at,
-                last_login: user_response.last_login_at,
-                is_active: user_response.is_active,
-            }
-        },
-        Ok(None) => return Err((
-            StatusCode::NOT_FOUND,
-            ResponseJson(json!({
-                "success": false,
-                "error": "User not found"
-            }))
-        )),
-        Err(e) => return Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            ResponseJson(json!({
-                "success": false,
-                "error": e.to_string()
-            }))
-        ))
-    };
-
-    // Generate new token pair
-    match state.jwt_manager.refresh_access_token(&request.refresh_token, &user, session_id) {
-        Ok(tokens) => Ok(ResponseJson(json!({
-            "success": true,
-            "message": "Tokens refreshed successfully",
-            "tokens": tokens
-        }))),
         Err(e) => Err((
             StatusCode::UNAUTHORIZED,
             ResponseJson(json!({
@@ -212,6 +135,65 @@ pub async fn update_profile_handler(
     }
 }
 
+pub async fn patch_profile_handler(
+    State(state): State<crate::AppState>,
+    auth_context: AuthContext,
+    headers: HeaderMap,
+    Json(patch): Json<user_service::ProfilePatch>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let if_unmodified_since = match headers.get("if-unmodified-since") {
+        Some(value) => {
+            let value = value.to_str().map_err(|_| (
+                StatusCode::BAD_REQUEST,
+                ResponseJson(json!({
+                    "success": false,
+                    "error": "Invalid If-Unmodified-Since header"
+                }))
+            ))?;
+
+            let parsed = chrono::DateTime::parse_from_rfc2822(value).map_err(|_| (
+                StatusCode::BAD_REQUEST,
+                ResponseJson(json!({
+                    "success": false,
+                    "error": "If-Unmodified-Since must be an RFC 2822 date"
+                }))
+            ))?;
+
+            Some(parsed.with_timezone(&chrono::Utc))
+        }
+        None => None,
+    };
+
+    match state.user_service.patch_user(auth_context.user.id, patch, if_unmodified_since).await {
+        Ok(user_service::PatchOutcome::Updated(user)) => Ok(ResponseJson(json!({
+            "success": true,
+            "message": "Profile updated successfully",
+            "user": user
+        }))),
+        Ok(user_service::PatchOutcome::PreconditionFailed) => Err((
+            StatusCode::PRECONDITION_FAILED,
+            ResponseJson(json!({
+                "success": false,
+                "error": "Profile was modified since If-Unmodified-Since"
+            }))
+        )),
+        Ok(user_service::PatchOutcome::NotFound) => Err((
+            StatusCode::NOT_FOUND,
+            ResponseJson(json!({
+                "success": false,
+                "error": "User not found"
+            }))
+        )),
+        Err(e) => Err((
+            StatusCode::BAD_REQUEST,
+            ResponseJson(json!({
+                "success": false,
+                "error": e.to_string()
+            }))
+        ))
+    }
+}
+
 pub async fn change_password_handler(
     State(state): State<crate::AppState>,
     auth_context: AuthContext,
@@ -29,6 +29,15 @@ pub struct Claims {
     pub iss: String,       // Issuer
     pub aud: String,       // Audience
     pub token_type: TokenType, // Token type
+    /// Unique id for this specific token, used by `UserService` to record
+    /// each issued refresh token in the revocation table and recognize when
+    /// one gets presented a second time.
+    pub jti: String,
+    /// Shared across every access/refresh pair descended from one login via
+    /// rotation. On reuse of an already-rotated refresh token, every `jti`
+    /// in its family is revoked, forcing re-login instead of just denying
+    /// the one reused token.
+    pub family_id: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -66,8 +75,18 @@ impl JwtManager {
     }
 
     pub fn generate_token_pair(&self, user: &User, session_id: Uuid) -> Result<TokenPair> {
+        self.generate_token_pair_in_family(user, session_id, &Uuid::new_v4().to_string())
+            .map(|(pair, _)| pair)
+    }
+
+    /// Builds an access/refresh pair whose `family_id` is the given one
+    /// rather than a fresh one, so `rotate_refresh_token` can keep every
+    /// token descended from the same login in one family. Returns the new
+    /// refresh token's `Claims` alongside the pair so the caller can record
+    /// its `jti` in the revocation table.
+    fn generate_token_pair_in_family(&self, user: &User, session_id: Uuid, family_id: &str) -> Result<(TokenPair, Claims)> {
         let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
-        
+
         // Generate access token
         let access_claims = Claims {
             sub: user.id.to_string(),
@@ -81,6 +100,8 @@ impl JwtManager {
             iss: self.issuer.clone(),
             aud: "uaida-api".to_string(),
             token_type: TokenType::Access,
+            jti: Uuid::new_v4().to_string(),
+            family_id: family_id.to_string(),
         };
 
         // Generate refresh token
@@ -96,19 +117,41 @@ impl JwtManager {
             iss: self.issuer.clone(),
             aud: "uaida-refresh".to_string(),
             token_type: TokenType::Refresh,
+            jti: Uuid::new_v4().to_string(),
+            family_id: family_id.to_string(),
         };
 
         let header = Header::new(self.algorithm);
-        
+
         let access_token = encode(&header, &access_claims, &self.encoding_key)?;
         let refresh_token = encode(&header, &refresh_claims, &self.encoding_key)?;
 
-        Ok(TokenPair {
-            access_token,
-            refresh_token,
-            expires_in: self.access_token_expiry.as_secs(),
-            token_type: "Bearer".to_string(),
-        })
+        Ok((
+            TokenPair {
+                access_token,
+                refresh_token,
+                expires_in: self.access_token_expiry.as_secs(),
+                token_type: "Bearer".to_string(),
+            },
+            refresh_claims,
+        ))
+    }
+
+    /// Rotates a refresh token for the `/auth/refresh` handler: validates
+    /// `old_refresh_token` and issues a new pair in the same `family_id`.
+    /// Doesn't touch any revocation store itself — `UserService::rotate_refresh_token`
+    /// owns checking whether `old_refresh_token`'s `jti` was already used
+    /// (reuse means the whole family gets revoked there) and persisting the
+    /// new `jti` this returns.
+    pub fn rotate_refresh_token(&self, old_refresh_token: &str, user: &User, session_id: Uuid) -> Result<(TokenPair, Claims)> {
+        let old_claims = self.validate_token(old_refresh_token, TokenType::Refresh)?;
+
+        if old_claims.sub != user.id.to_string() || old_claims.session_id != session_id.to_string() {
+            return Err(anyhow::anyhow!("Invalid refresh token"));
+        }
+
+        let (pair, new_refresh_claims) = self.generate_token_pair_in_family(user, session_id, &old_claims.family_id)?;
+        Ok((pair, new_refresh_claims))
     }
 
     pub fn validate_token(&self, token: &str, expected_type: TokenType) -> Result<Claims> {
@@ -131,18 +174,6 @@ impl JwtManager {
         Ok(token_data.claims)
     }
 
-    pub fn refresh_access_token(&self, refresh_token: &str, user: &User, session_id: Uuid) -> Result<TokenPair> {
-        // Validate refresh token
-        let refresh_claims = self.validate_token(refresh_token, TokenType::Refresh)?;
-        
-        // Verify the refresh token belongs to the user and session
-        if refresh_claims.sub != user.id.to_string() || refresh_claims.session_id != session_id.to_string() {
-            return Err(anyhow::anyhow!("Invalid refresh token"));
-        }
-
-        // Generate new token pair
-        self.generate_token_pair(user, session_id)
-    }
 
     pub fn extract_bearer_token(authorization_header: &str) -> Option<&str> {
         authorization_header
@@ -305,17 +336,106 @@ mod tests {
 
         // Generate initial token pair
         let initial_tokens = jwt_manager.generate_token_pair(&user, session_id).unwrap();
-        
-        // Refresh tokens
-        let new_tokens = jwt_manager
-            .refresh_access_token(&initial_tokens.refresh_token, &user, session_id)
+
+        // Rotate tokens
+        let (new_tokens, _new_refresh_claims) = jwt_manager
+            .rotate_refresh_token(&initial_tokens.refresh_token, &user, session_id)
             .unwrap();
-        
+
         // Validate new access token
         let new_claims = jwt_manager
             .validate_token(&new_tokens.access_token, TokenType::Access)
             .unwrap();
-        
+
         assert_eq!(new_claims.sub, user.id.to_string());
     }
+
+    #[test]
+    fn rotation_keeps_family_id_but_issues_a_new_jti() {
+        let jwt_manager = JwtManager::new("test_secret", "test_issuer".to_string());
+        let org_id = Uuid::new_v4();
+        let session_id = Uuid::new_v4();
+        let user = User {
+            id: Uuid::new_v4(),
+            email: "test@example.com".to_string(),
+            name: "Test User".to_string(),
+            organization_id: org_id,
+            roles: vec![Role::developer_role(org_id)],
+            permissions: vec![Permission::ApiAccess],
+            created_at: chrono::Utc::now(),
+            last_login: None,
+            is_active: true,
+        };
+
+        let initial_tokens = jwt_manager.generate_token_pair(&user, session_id).unwrap();
+        let initial_claims = jwt_manager
+            .validate_token(&initial_tokens.refresh_token, TokenType::Refresh)
+            .unwrap();
+
+        let (_rotated_tokens, rotated_claims) = jwt_manager
+            .rotate_refresh_token(&initial_tokens.refresh_token, &user, session_id)
+            .unwrap();
+
+        assert_eq!(rotated_claims.family_id, initial_claims.family_id);
+        assert_ne!(rotated_claims.jti, initial_claims.jti);
+    }
+
+    /// Simulates `UserService::rotate_refresh_token`'s revocation-table
+    /// bookkeeping with an in-memory `jti -> (family_id, revoked)` map
+    /// standing in for the `refresh_tokens` table, to exercise the reuse
+    /// detection logic that depends on `Claims::jti`/`family_id` without a
+    /// database: rotating once is fine, but presenting the now-stale
+    /// refresh token again must be recognized as reuse and revoke every
+    /// `jti` in the family, including the one that replaced it.
+    #[test]
+    fn reusing_a_rotated_refresh_token_revokes_its_whole_family() {
+        let jwt_manager = JwtManager::new("test_secret", "test_issuer".to_string());
+        let org_id = Uuid::new_v4();
+        let session_id = Uuid::new_v4();
+        let user = User {
+            id: Uuid::new_v4(),
+            email: "test@example.com".to_string(),
+            name: "Test User".to_string(),
+            organization_id: org_id,
+            roles: vec![Role::developer_role(org_id)],
+            permissions: vec![Permission::ApiAccess],
+            created_at: chrono::Utc::now(),
+            last_login: None,
+            is_active: true,
+        };
+
+        // revocation table: jti -> (family_id, revoked)
+        let mut revocations: std::collections::HashMap<String, (String, bool)> = std::collections::HashMap::new();
+
+        let initial_tokens = jwt_manager.generate_token_pair(&user, session_id).unwrap();
+        let initial_claims = jwt_manager
+            .validate_token(&initial_tokens.refresh_token, TokenType::Refresh)
+            .unwrap();
+        revocations.insert(initial_claims.jti.clone(), (initial_claims.family_id.clone(), false));
+
+        // First rotation: the presented jti hasn't been seen before, so it's
+        // accepted, marked revoked, and the new jti is recorded.
+        let (rotated_tokens, rotated_claims) = jwt_manager
+            .rotate_refresh_token(&initial_tokens.refresh_token, &user, session_id)
+            .unwrap();
+        revocations.get_mut(&initial_claims.jti).unwrap().1 = true;
+        revocations.insert(rotated_claims.jti.clone(), (rotated_claims.family_id.clone(), false));
+
+        // Reuse: the original (now-revoked) refresh token is presented
+        // again. The revocation table already has it marked revoked, which
+        // is the signal to revoke the entire family rather than just reject
+        // this one token.
+        let (reused_family, already_revoked) = revocations.get(&initial_claims.jti).unwrap().clone();
+        assert!(already_revoked);
+        for (family, revoked) in revocations.values_mut() {
+            if family == &reused_family {
+                *revoked = true;
+            }
+        }
+
+        // The jti that replaced the reused token is now revoked too, so a
+        // subsequent refresh with it would also be rejected.
+        assert!(revocations.get(&rotated_claims.jti).unwrap().1);
+        let _ = rotated_tokens;
+    }
 }
\ No newline at end of file
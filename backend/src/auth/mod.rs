@@ -4,11 +4,13 @@ pub mod jwt;
 pub mod middleware;
 pub mod audit;
 pub mod api_key_manager;
+pub mod custom_provider_manager;
 pub mod user_service;
 pub mod handlers;
 pub mod organization;
 pub mod preferences;
 pub mod preferences_handlers;
+pub mod require_permission;
 
 pub use sso::*;
 pub mod rbac;
@@ -17,10 +19,12 @@ pub use jwt::*;
 pub use middleware::*;
 pub use audit::*;
 pub use api_key_manager::*;
+pub use custom_provider_manager::*;
 pub use user_service::*;
 pub use handlers::*;
 pub use organization::*;
 pub use preferences::*;
+pub use require_permission::{RequirePermission, RequiredPermission, GenerateCode, RunEvaluations, ConfigureSystem};
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -74,6 +78,7 @@ pub enum Permission {
     ReviewCode,
     ApproveCode,
     DeployCode,
+    ModifyFile,
     
     // Security permissions
     ViewSecurityReports,
@@ -215,6 +220,7 @@ impl Role {
                 Permission::ReviewCode,
                 Permission::ApproveCode,
                 Permission::DeployCode,
+                Permission::ModifyFile,
                 Permission::ViewSecurityReports,
                 Permission::OverrideSecurityBlocks,
                 Permission::ConfigureSecurity,
@@ -245,6 +251,7 @@ impl Role {
                 Permission::ViewPlan,
                 Permission::GenerateCode,
                 Permission::ReviewCode,
+                Permission::ModifyFile,
                 Permission::ViewSecurityReports,
                 Permission::ApiAccess,
                 Permission::ViewEvaluations,
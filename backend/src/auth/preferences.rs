@@ -3,6 +3,20 @@ use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use uuid::Uuid;
 
+/// How much explanation conversation responses should include for this
+/// user. Mirrors `conversation::ExplanationLevel` (the `conversation`
+/// module is binary-only, so it can't be referenced from here) —
+/// `ConversationService::create_session` maps this into a session's
+/// `SessionMetadata` when seeding a new session's preferences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExplanationLevel {
+    Beginner,
+    Intermediate,
+    Advanced,
+    Expert,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserPreferences {
     pub user_id: Uuid,
@@ -18,6 +32,10 @@ pub struct UserPreferences {
     pub notifications: NotificationSettings,
     pub editor_settings: EditorSettings,
     pub ai_settings: AISettings,
+    /// Drives `PromptTemplateEngine`'s system prompt selection for new
+    /// conversation sessions (`ConversationService::create_session` seeds
+    /// `SessionMetadata::preferences` from this on session creation).
+    pub explanation_level: ExplanationLevel,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,6 +84,19 @@ pub struct UpdatePreferencesRequest {
     pub notifications: Option<NotificationSettings>,
     pub editor_settings: Option<EditorSettings>,
     pub ai_settings: Option<AISettings>,
+    pub explanation_level: Option<ExplanationLevel>,
+}
+
+impl UserPreferences {
+    /// Fills in `provider`/`model` from these preferences wherever the
+    /// caller left them `None`, so a saved default takes effect without
+    /// every call site re-implementing the same fallback.
+    pub fn resolve_provider_and_model(&self, provider: Option<String>, model: Option<String>) -> (String, String) {
+        (
+            provider.unwrap_or_else(|| self.default_provider.clone()),
+            model.unwrap_or_else(|| self.default_model.clone()),
+        )
+    }
 }
 
 pub struct PreferencesService {
@@ -82,7 +113,7 @@ impl PreferencesService {
             r#"
             SELECT user_id, default_provider, default_model, max_tokens, temperature,
                    auto_save, create_backups, theme, language, timezone,
-                   notifications, editor_settings, ai_settings
+                   notifications, editor_settings, ai_settings, explanation_level
             FROM user_preferences
             WHERE user_id = $1
             "#,
@@ -135,6 +166,9 @@ impl PreferencesService {
                         "code_review_strictness": "medium"
                     })
                 }))?,
+                explanation_level: serde_json::from_value(serde_json::Value::String(
+                    row.explanation_level,
+                ))?,
             })
         } else {
             // Create default preferences
@@ -151,10 +185,15 @@ impl PreferencesService {
         let _existing = self.get_user_preferences(user_id).await?;
 
         // Update preferences
+        let explanation_level = request.explanation_level
+            .map(|level| serde_json::to_value(level))
+            .transpose()?
+            .map(|value| value.as_str().unwrap_or("intermediate").to_string());
+
         sqlx::query!(
             r#"
             UPDATE user_preferences
-            SET 
+            SET
                 default_provider = COALESCE($2, default_provider),
                 default_model = COALESCE($3, default_model),
                 max_tokens = COALESCE($4, max_tokens),
@@ -167,6 +206,7 @@ impl PreferencesService {
                 notifications = COALESCE($11, notifications),
                 editor_settings = COALESCE($12, editor_settings),
                 ai_settings = COALESCE($13, ai_settings),
+                explanation_level = COALESCE($14, explanation_level),
                 updated_at = NOW()
             WHERE user_id = $1
             "#,
@@ -183,6 +223,7 @@ impl PreferencesService {
             request.notifications.map(|n| serde_json::to_value(n)).transpose()?,
             request.editor_settings.map(|e| serde_json::to_value(e)).transpose()?,
             request.ai_settings.map(|a| serde_json::to_value(a)).transpose()?,
+            explanation_level,
         )
         .execute(&self.pool)
         .await?;
@@ -226,9 +267,9 @@ impl PreferencesService {
             INSERT INTO user_preferences (
                 user_id, default_provider, default_model, max_tokens, temperature,
                 auto_save, create_backups, theme, language, timezone,
-                notifications, editor_settings, ai_settings
+                notifications, editor_settings, ai_settings, explanation_level
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             ON CONFLICT (user_id) DO NOTHING
             "#,
             user_id,
@@ -244,6 +285,7 @@ impl PreferencesService {
             serde_json::to_value(&default_notifications)?,
             serde_json::to_value(&default_editor)?,
             serde_json::to_value(&default_ai)?,
+            "intermediate",
         )
         .execute(&self.pool)
         .await?;
@@ -262,6 +304,7 @@ impl PreferencesService {
             notifications: default_notifications,
             editor_settings: default_editor,
             ai_settings: default_ai,
+            explanation_level: ExplanationLevel::Intermediate,
         })
     }
 
@@ -301,8 +344,73 @@ impl PreferencesService {
             notifications: Some(preferences.notifications),
             editor_settings: Some(preferences.editor_settings),
             ai_settings: Some(preferences.ai_settings),
+            explanation_level: Some(preferences.explanation_level),
         };
 
         self.update_user_preferences(user_id, request).await
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_preferences() -> UserPreferences {
+        UserPreferences {
+            user_id: Uuid::new_v4(),
+            default_provider: "anthropic".to_string(),
+            default_model: "claude-3-sonnet".to_string(),
+            max_tokens: 4000,
+            temperature: 0.7,
+            auto_save: true,
+            create_backups: true,
+            theme: "dark".to_string(),
+            language: "en".to_string(),
+            timezone: "UTC".to_string(),
+            notifications: NotificationSettings {
+                email: true,
+                push: true,
+                desktop: false,
+                completion_alerts: true,
+                cost_alerts: true,
+                security_alerts: true,
+            },
+            editor_settings: EditorSettings {
+                font_size: 14,
+                tab_size: 2,
+                word_wrap: true,
+                line_numbers: true,
+                syntax_highlighting: true,
+                auto_complete: true,
+                vim_mode: false,
+            },
+            ai_settings: AISettings {
+                enable_inline_completion: true,
+                enable_code_explanation: true,
+                enable_auto_documentation: false,
+                enable_security_scanning: true,
+                enable_performance_hints: true,
+                preferred_explanation_style: "detailed".to_string(),
+                code_review_strictness: "medium".to_string(),
+            },
+            explanation_level: ExplanationLevel::Advanced,
+        }
+    }
+
+    #[test]
+    fn falls_back_to_saved_preference_when_request_omits_provider_and_model() {
+        let prefs = sample_preferences();
+        let (provider, model) = prefs.resolve_provider_and_model(None, None);
+        assert_eq!(provider, "anthropic");
+        assert_eq!(model, "claude-3-sonnet");
+    }
+
+    #[test]
+    fn explicit_request_values_take_priority_over_preferences() {
+        let prefs = sample_preferences();
+        let (provider, model) =
+            prefs.resolve_provider_and_model(Some("openai".to_string()), Some("gpt-4o".to_string()));
+        assert_eq!(provider, "openai");
+        assert_eq!(model, "gpt-4o");
+    }
 }
\ No newline at end of file
@@ -0,0 +1,194 @@
+use axum::http::StatusCode;
+use std::marker::PhantomData;
+
+use super::{AuthContext, Permission};
+
+/// Maps a marker type to the single `Permission` it gates, so
+/// `RequirePermission<P>` can check it without `Permission` itself needing to
+/// be a type parameter (it's a runtime enum, not const-generic-able). Add a
+/// unit struct and an impl here for each permission a handler needs to gate.
+pub trait RequiredPermission {
+    const PERMISSION: Permission;
+}
+
+pub struct GenerateCode;
+impl RequiredPermission for GenerateCode {
+    const PERMISSION: Permission = Permission::GenerateCode;
+}
+
+pub struct RunEvaluations;
+impl RequiredPermission for RunEvaluations {
+    const PERMISSION: Permission = Permission::RunEvaluations;
+}
+
+pub struct ConfigureSystem;
+impl RequiredPermission for ConfigureSystem {
+    const PERMISSION: Permission = Permission::ConfigureSystem;
+}
+
+pub struct ViewPlan;
+impl RequiredPermission for ViewPlan {
+    const PERMISSION: Permission = Permission::ViewPlan;
+}
+
+pub struct ReviewCode;
+impl RequiredPermission for ReviewCode {
+    const PERMISSION: Permission = Permission::ReviewCode;
+}
+
+pub struct ModifyFile;
+impl RequiredPermission for ModifyFile {
+    const PERMISSION: Permission = Permission::ModifyFile;
+}
+
+/// Extracts `AuthContext` like the plain `AuthContext` extractor, but
+/// rejects with 403 if the user lacks `P::PERMISSION`. Use in place of a
+/// bare `AuthContext` parameter on handlers that should be gated, e.g.
+/// `RequirePermission<GenerateCode>`. The `FromRequestParts` impl lives in
+/// `main.rs` next to `AppState` and `AuthContext`'s own impl, since
+/// `AppState` is only defined for the binary crate.
+pub struct RequirePermission<P>(pub AuthContext, pub(crate) PhantomData<P>);
+
+impl<P> RequirePermission<P> {
+    pub fn auth_context(&self) -> &AuthContext {
+        &self.0
+    }
+}
+
+/// Shared rejection logic for any `FromRequestParts` impl of
+/// `RequirePermission<P>`, so each impl (one per concrete `AppState`) only
+/// has to supply the already-extracted `AuthContext`.
+pub fn check_permission<P: RequiredPermission>(auth_context: AuthContext) -> Result<RequirePermission<P>, StatusCode> {
+    if !auth_context.user.has_permission(&P::PERMISSION) {
+        tracing::warn!(
+            user_id = %auth_context.user.id,
+            permission = ?P::PERMISSION,
+            "User lacks required permission"
+        );
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    Ok(RequirePermission(auth_context, PhantomData))
+}
+
+// Test-only `FromRequestParts<()>` impl so `RequirePermission<P>` can be
+// exercised through a real `axum::Router` + `oneshot` request without
+// standing up the binary crate's full `AppState` (which needs a live
+// Postgres pool). The real, `AppState`-bound impl lives in `main.rs` next to
+// `AppState` itself, but both funnel through the same `check_permission`
+// below, so this exercises the actual rejection logic, not a reimplementation
+// of it.
+#[cfg(test)]
+impl<P: RequiredPermission + Send + Sync> axum::extract::FromRequestParts<()> for RequirePermission<P> {
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        _state: &(),
+    ) -> Result<Self, Self::Rejection> {
+        let auth_context = parts
+            .extensions
+            .get::<AuthContext>()
+            .cloned()
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+        check_permission(auth_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::Role;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    fn user_with_role(role: Role) -> super::super::User {
+        let organization_id = role.organization_id;
+        super::super::User {
+            id: Uuid::new_v4(),
+            email: "test@example.com".to_string(),
+            name: "Test User".to_string(),
+            organization_id,
+            roles: vec![role],
+            permissions: vec![],
+            created_at: chrono::Utc::now(),
+            last_login: None,
+            is_active: true,
+        }
+    }
+
+    #[test]
+    fn viewer_role_lacks_generate_code() {
+        let user = user_with_role(Role::viewer_role(Uuid::new_v4()));
+        assert!(!user.has_permission(&GenerateCode::PERMISSION));
+    }
+
+    #[test]
+    fn admin_role_has_generate_code() {
+        let user = user_with_role(Role::admin_role(Uuid::new_v4()));
+        assert!(user.has_permission(&GenerateCode::PERMISSION));
+    }
+
+    #[test]
+    fn admin_role_has_configure_system() {
+        let user = user_with_role(Role::admin_role(Uuid::new_v4()));
+        assert!(user.has_permission(&ConfigureSystem::PERMISSION));
+    }
+
+    #[test]
+    fn viewer_role_lacks_review_code() {
+        let user = user_with_role(Role::viewer_role(Uuid::new_v4()));
+        assert!(!user.has_permission(&ReviewCode::PERMISSION));
+    }
+
+    #[test]
+    fn developer_role_has_review_code() {
+        let user = user_with_role(Role::developer_role(Uuid::new_v4()));
+        assert!(user.has_permission(&ReviewCode::PERMISSION));
+    }
+
+    fn auth_context_for(role: Role) -> AuthContext {
+        let user = user_with_role(role);
+        AuthContext {
+            session_id: Uuid::new_v4(),
+            ip_address: "127.0.0.1".to_string(),
+            user_agent: "test".to_string(),
+            expires_at: chrono::Utc::now() + chrono::Duration::hours(1),
+            permissions: user.permissions.clone(),
+            user,
+        }
+    }
+
+    async fn write_handler(RequirePermission(auth_context, ..): RequirePermission<GenerateCode>) -> String {
+        auth_context.user.email
+    }
+
+    fn gated_router() -> Router {
+        Router::new().route("/generate", get(write_handler))
+    }
+
+    /// Simulates what `auth_middleware_wrapper` does in `main.rs` after a JWT
+    /// is validated: stash the already-authenticated `AuthContext` in the
+    /// request extensions so downstream extractors (here, `RequirePermission`)
+    /// can pull it out.
+    fn request_as(auth_context: AuthContext) -> Request<Body> {
+        let mut request = Request::builder().uri("/generate").body(Body::empty()).unwrap();
+        request.extensions_mut().insert(auth_context);
+        request
+    }
+
+    #[tokio::test]
+    async fn viewer_role_token_gets_403_on_write_endpoint() {
+        let request = request_as(auth_context_for(Role::viewer_role(Uuid::new_v4())));
+        let response = gated_router().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn admin_role_token_succeeds_on_write_endpoint() {
+        let request = request_as(auth_context_for(Role::admin_role(Uuid::new_v4())));
+        let response = gated_router().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -3,6 +3,7 @@ use anyhow::{Result, anyhow};
 use bcrypt::{hash, verify, DEFAULT_COST};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::collections::HashMap;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
@@ -27,6 +28,14 @@ pub struct LoginResponse {
     pub session_id: Uuid,
 }
 
+/// Result of `UserService::rotate_refresh_token`, distinguishing a normal
+/// rotation from reuse of an already-rotated refresh token so the handler
+/// can respond with "log in again" rather than a generic 401.
+pub enum RefreshOutcome {
+    Rotated(TokenPair),
+    ReuseDetected,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserResponse {
     pub id: Uuid,
@@ -37,6 +46,7 @@ pub struct UserResponse {
     pub is_verified: bool,
     pub last_login_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +55,29 @@ pub struct UpdateUserRequest {
     pub username: Option<String>,
 }
 
+/// Partial update for `PATCH /auth/profile`. Unlike `UpdateUserRequest`, a
+/// `None` field here is guaranteed to leave the column untouched: the SQL is
+/// built dynamically in `UserService::patch_user` to only ever assign the
+/// fields that were actually supplied, instead of relying on `COALESCE`
+/// against a fully-populated request.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfilePatch {
+    pub full_name: Option<String>,
+    pub username: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+/// Result of `UserService::patch_user`, distinguishing the precondition
+/// failure from other errors so the handler can map it to `412` instead of
+/// the generic `400` used elsewhere in this module.
+pub enum PatchOutcome {
+    Updated(UserResponse),
+    /// The caller's `If-Unmodified-Since` predates a change made by someone
+    /// else since they last read the profile.
+    PreconditionFailed,
+    NotFound,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChangePasswordRequest {
     pub current_password: String,
@@ -54,11 +87,39 @@ pub struct ChangePasswordRequest {
 pub struct UserService {
     pool: PgPool,
     jwt_manager: JwtManager,
+    audit_service: Option<std::sync::Arc<dyn AuditService>>,
 }
 
 impl UserService {
     pub fn new(pool: PgPool, jwt_manager: JwtManager) -> Self {
-        Self { pool, jwt_manager }
+        Self { pool, jwt_manager, audit_service: None }
+    }
+
+    /// Emits one `AuditEvent` per call into `register`, `login`, and
+    /// `change_password`, so account-level security actions are traceable.
+    /// No-op when not set.
+    pub fn with_audit_service(mut self, audit_service: std::sync::Arc<dyn AuditService>) -> Self {
+        self.audit_service = Some(audit_service);
+        self
+    }
+
+    fn audit_event(&self, user_id: Uuid, action: &str, outcome: AuditOutcome) -> AuditEvent {
+        AuditEvent {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type: AuditEventType::Authentication,
+            user_id: Some(user_id),
+            session_id: None,
+            organization_id: Uuid::new_v4(), // Would be retrieved from user context
+            ip_address: "unknown".to_string(),
+            user_agent: "unknown".to_string(),
+            resource_type: None,
+            resource_id: None,
+            action: action.to_string(),
+            outcome,
+            details: HashMap::new(),
+            risk_score: None,
+        }
     }
 
     /// Register a new user
@@ -94,7 +155,7 @@ impl UserService {
             r#"
             INSERT INTO users (email, username, password_hash, full_name)
             VALUES ($1, $2, $3, $4)
-            RETURNING id, email, username, full_name, is_active, is_verified, last_login_at, created_at
+            RETURNING id, email, username, full_name, is_active, is_verified, last_login_at, created_at, updated_at
             "#,
             request.email,
             request.username,
@@ -112,6 +173,11 @@ impl UserService {
         .execute(&self.pool)
         .await?;
 
+        if let Some(audit_service) = &self.audit_service {
+            let event = self.audit_event(user.id, "register", AuditOutcome::Success);
+            audit_service.log_event(event).await?;
+        }
+
         Ok(UserResponse {
             id: user.id,
             email: user.email,
@@ -121,6 +187,7 @@ impl UserService {
             is_verified: user.is_verified,
             last_login_at: user.last_login_at,
             created_at: user.created_at,
+            updated_at: user.updated_at,
         })
     }
 
@@ -129,7 +196,7 @@ impl UserService {
         // Get user by email
         let user_row = sqlx::query!(
             r#"
-            SELECT id, email, username, password_hash, full_name, is_active, is_verified, last_login_at, created_at
+            SELECT id, email, username, password_hash, full_name, is_active, is_verified, last_login_at, created_at, updated_at
             FROM users
             WHERE email = $1 AND is_active = true
             "#,
@@ -186,6 +253,12 @@ impl UserService {
 
         // Generate JWT tokens
         let tokens = self.jwt_manager.generate_token_pair(&user, session_id)?;
+        self.record_issued_refresh_token(&tokens.refresh_token, user_row.id, session_id).await?;
+
+        if let Some(audit_service) = &self.audit_service {
+            let event = self.audit_event(user_row.id, "login", AuditOutcome::Success);
+            audit_service.log_event(event).await?;
+        }
 
         let user_response = UserResponse {
             id: user_row.id,
@@ -196,6 +269,7 @@ impl UserService {
             is_verified: user_row.is_verified,
             last_login_at: Some(Utc::now()),
             created_at: user_row.created_at,
+            updated_at: user_row.updated_at,
         };
 
         Ok(LoginResponse {
@@ -217,11 +291,108 @@ impl UserService {
         Ok(())
     }
 
+    /// Rotates a refresh token for `/auth/refresh`. A refresh token is only
+    /// ever valid for one rotation: its `jti` is looked up in `refresh_tokens`
+    /// and, if it's already marked revoked, the request is reusing a token
+    /// that was already rotated away — a strong signal it leaked — so the
+    /// entire `family_id` is revoked and the session killed instead of just
+    /// rejecting the one token. Otherwise the presented `jti` is revoked and
+    /// a fresh pair (same family, new `jti`) replaces it.
+    pub async fn rotate_refresh_token(&self, refresh_token: &str) -> Result<RefreshOutcome> {
+        let claims = self.jwt_manager.validate_token(refresh_token, TokenType::Refresh)?;
+        let jti = Uuid::parse_str(&claims.jti)?;
+        let family_id = Uuid::parse_str(&claims.family_id)?;
+        let user_id = Uuid::parse_str(&claims.sub)?;
+        let session_id = Uuid::parse_str(&claims.session_id)?;
+
+        if !self.validate_session(session_id).await? {
+            return Err(anyhow!("Session expired or invalid"));
+        }
+
+        let already_revoked = sqlx::query_as::<_, (Option<DateTime<Utc>>,)>(
+            "SELECT revoked_at FROM refresh_tokens WHERE jti = $1",
+        )
+        .bind(jti)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(|(revoked_at,)| revoked_at.is_some())
+        // A token issued before this table existed (or already pruned by
+        // expiry) can't be checked for reuse; let it rotate rather than
+        // locking the user out over missing bookkeeping.
+        .unwrap_or(false);
+
+        if already_revoked {
+            sqlx::query("UPDATE refresh_tokens SET revoked_at = NOW() WHERE family_id = $1 AND revoked_at IS NULL")
+                .bind(family_id)
+                .execute(&self.pool)
+                .await?;
+            self.logout(session_id).await?;
+
+            return Ok(RefreshOutcome::ReuseDetected);
+        }
+
+        let user = self.build_user_for_token(user_id).await?;
+        let (tokens, new_refresh_claims) = self.jwt_manager.rotate_refresh_token(refresh_token, &user, session_id)?;
+
+        sqlx::query("UPDATE refresh_tokens SET revoked_at = NOW() WHERE jti = $1")
+            .bind(jti)
+            .execute(&self.pool)
+            .await?;
+        self.record_issued_refresh_token(&tokens.refresh_token, user_id, session_id).await?;
+        let _ = new_refresh_claims; // jti/family_id already captured by record_issued_refresh_token
+
+        Ok(RefreshOutcome::Rotated(tokens))
+    }
+
+    /// Persists a freshly issued refresh token's `jti`/`family_id` so a
+    /// later rotation can tell it apart from a reused one.
+    async fn record_issued_refresh_token(&self, refresh_token: &str, user_id: Uuid, session_id: Uuid) -> Result<()> {
+        let claims = self.jwt_manager.validate_token(refresh_token, TokenType::Refresh)?;
+        let jti = Uuid::parse_str(&claims.jti)?;
+        let family_id = Uuid::parse_str(&claims.family_id)?;
+        let expires_at = chrono::DateTime::from_timestamp(claims.exp as i64, 0).unwrap_or_else(Utc::now);
+
+        sqlx::query(
+            r#"
+            INSERT INTO refresh_tokens (jti, family_id, user_id, session_id, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(jti)
+        .bind(family_id)
+        .bind(user_id)
+        .bind(session_id)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Rebuilds the `User` JWTs are generated from, the same way `login`
+    /// does, for callers (like `rotate_refresh_token`) that only have a
+    /// `user_id` to start from.
+    async fn build_user_for_token(&self, user_id: Uuid) -> Result<User> {
+        let user_response = self.get_user(user_id).await?.ok_or_else(|| anyhow!("User not found"))?;
+
+        Ok(User {
+            id: user_response.id,
+            email: user_response.email,
+            name: user_response.full_name.clone().unwrap_or_else(|| user_response.username.clone()),
+            organization_id: self.get_or_create_default_organization(user_response.id).await?,
+            roles: self.get_user_roles(user_response.id).await?,
+            permissions: self.get_user_permissions(user_response.id).await?,
+            created_at: user_response.created_at,
+            last_login: user_response.last_login_at,
+            is_active: user_response.is_active,
+        })
+    }
+
     /// Get user by ID
     pub async fn get_user(&self, user_id: Uuid) -> Result<Option<UserResponse>> {
         let user = sqlx::query!(
             r#"
-            SELECT id, email, username, full_name, is_active, is_verified, last_login_at, created_at
+            SELECT id, email, username, full_name, is_active, is_verified, last_login_at, created_at, updated_at
             FROM users
             WHERE id = $1 AND is_active = true
             "#,
@@ -230,6 +401,11 @@ impl UserService {
         .fetch_optional(&self.pool)
         .await?;
 
+        if let Some(audit_service) = &self.audit_service {
+            let event = self.audit_event(user_id, "get_user", AuditOutcome::Success);
+            log_event_fire_and_forget(audit_service.clone(), event);
+        }
+
         if let Some(user) = user {
             Ok(Some(UserResponse {
                 id: user.id,
@@ -240,6 +416,7 @@ impl UserService {
                 is_verified: user.is_verified,
                 last_login_at: user.last_login_at,
                 created_at: user.created_at,
+                updated_at: user.updated_at,
             }))
         } else {
             Ok(None)
@@ -272,7 +449,7 @@ impl UserService {
                 username = COALESCE($3, username),
                 updated_at = NOW()
             WHERE id = $1 AND is_active = true
-            RETURNING id, email, username, full_name, is_active, is_verified, last_login_at, created_at
+            RETURNING id, email, username, full_name, is_active, is_verified, last_login_at, created_at, updated_at
             "#,
             user_id,
             request.full_name,
@@ -292,9 +469,95 @@ impl UserService {
             is_verified: user.is_verified,
             last_login_at: user.last_login_at,
             created_at: user.created_at,
+            updated_at: user.updated_at,
         })
     }
 
+    /// Partially update a user's profile, writing only the fields present in
+    /// `patch` via a dynamically-built `UPDATE` (see `sqlx::QueryBuilder`),
+    /// so two concurrent patches to different fields never clobber each
+    /// other the way a full-body `PUT` can.
+    ///
+    /// If `if_unmodified_since` is set, the patch is rejected with
+    /// `PatchOutcome::PreconditionFailed` when the stored `updated_at` is
+    /// newer, so a client editing a stale copy of the profile doesn't
+    /// silently overwrite a change it never saw.
+    pub async fn patch_user(
+        &self,
+        user_id: Uuid,
+        patch: ProfilePatch,
+        if_unmodified_since: Option<DateTime<Utc>>,
+    ) -> Result<PatchOutcome> {
+        if let Some(ref username) = patch.username {
+            let existing = sqlx::query_as::<_, (Uuid,)>(
+                "SELECT id FROM users WHERE username = $1 AND id != $2",
+            )
+            .bind(username)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+            if existing.is_some() {
+                return Err(anyhow!("Username already taken"));
+            }
+        }
+
+        if let Some(if_unmodified_since) = if_unmodified_since {
+            let current = sqlx::query_as::<_, (DateTime<Utc>,)>(
+                "SELECT updated_at FROM users WHERE id = $1 AND is_active = true",
+            )
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+            let (updated_at,) = match current {
+                Some(current) => current,
+                None => return Ok(PatchOutcome::NotFound),
+            };
+
+            if updated_at > if_unmodified_since {
+                return Ok(PatchOutcome::PreconditionFailed);
+            }
+        }
+
+        if patch.full_name.is_none() && patch.username.is_none() && patch.avatar_url.is_none() {
+            // Nothing to assign; just return the current row rather than
+            // building an UPDATE with no SET clauses.
+            return match self.get_user(user_id).await? {
+                Some(user) => Ok(PatchOutcome::Updated(user)),
+                None => Ok(PatchOutcome::NotFound),
+            };
+        }
+
+        let mut query = sqlx::QueryBuilder::new("UPDATE users SET updated_at = NOW()");
+
+        if let Some(full_name) = patch.full_name {
+            query.push(", full_name = ").push_bind(full_name);
+        }
+        if let Some(username) = patch.username {
+            query.push(", username = ").push_bind(username);
+        }
+        if let Some(avatar_url) = patch.avatar_url {
+            query.push(", avatar_url = ").push_bind(avatar_url);
+        }
+
+        query
+            .push(" WHERE id = ")
+            .push_bind(user_id)
+            .push(" AND is_active = true");
+
+        let result = query.build().execute(&self.pool).await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(PatchOutcome::NotFound);
+        }
+
+        match self.get_user(user_id).await? {
+            Some(user) => Ok(PatchOutcome::Updated(user)),
+            None => Ok(PatchOutcome::NotFound),
+        }
+    }
+
     /// Change user password
     pub async fn change_password(&self, user_id: Uuid, request: ChangePasswordRequest) -> Result<()> {
         // Get current password hash
@@ -337,6 +600,11 @@ impl UserService {
         .execute(&self.pool)
         .await?;
 
+        if let Some(audit_service) = &self.audit_service {
+            let event = self.audit_event(user_id, "change_password", AuditOutcome::Success);
+            audit_service.log_event(event).await?;
+        }
+
         Ok(())
     }
 
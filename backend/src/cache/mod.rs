@@ -1,11 +1,9 @@
 // Sprint 2: Advanced Caching System
-pub mod redis_cache;
 pub mod memory_cache;
-pub mod cache_manager;
+pub mod redis_cache;
 
-pub use redis_cache::*;
 pub use memory_cache::*;
-pub use cache_manager::*;
+pub use redis_cache::RedisCache;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -0,0 +1,102 @@
+// Sprint 2: Redis-Backed Cache Implementation
+use super::{Cache, CacheConfig};
+use anyhow::Result;
+use futures_util::StreamExt;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::debug;
+
+/// Redis-backed [`Cache`] implementation. Unlike [`super::MemoryCache`] this
+/// is shared across process instances, so it's used for data that needs to
+/// survive a restart or be visible to every backend replica, e.g.
+/// `ConversationService` and `AITerminalService` session lookups.
+///
+/// Wraps a [`ConnectionManager`] rather than a single connection: it
+/// multiplexes commands over one connection and reconnects automatically on
+/// failure, so callers don't need their own pooling or retry logic.
+pub struct RedisCache {
+    connection: ConnectionManager,
+    config: CacheConfig,
+}
+
+impl RedisCache {
+    pub async fn new(config: CacheConfig) -> Result<Self> {
+        let client = redis::Client::open(config.redis_url.clone())?;
+        let connection = client.get_connection_manager().await?;
+
+        Ok(Self { connection, config })
+    }
+
+    /// Looks up a cached session by id alone, without knowing its owning
+    /// user, by scanning for the `session:*:{session_id}` key pattern.
+    /// Callers that only have a session id (not the owning user id) use
+    /// this instead of building the key directly with [`session_cache_key`].
+    pub async fn find_session_key(&self, session_id: uuid::Uuid) -> Result<Option<String>> {
+        let pattern = format!("session:*:{}", session_id);
+        let mut conn = self.connection.clone();
+        let mut keys: redis::AsyncIter<String> = conn.scan_match(&pattern).await?;
+        Ok(keys.next().await)
+    }
+}
+
+/// Builds the `session:{user_id}:{session_id}` key shared by
+/// `ConversationService` and `AITerminalService`, so invalidating every
+/// cached session for a user is a single `KEYS session:{user_id}:*` away.
+pub fn session_cache_key(user_id: uuid::Uuid, session_id: uuid::Uuid) -> String {
+    format!("session:{}:{}", user_id, session_id)
+}
+
+#[async_trait::async_trait]
+impl Cache for RedisCache {
+    async fn get<T>(&self, key: &str) -> Result<Option<T>>
+    where
+        T: for<'de> Deserialize<'de> + Send,
+    {
+        let mut conn = self.connection.clone();
+        let raw: Option<String> = conn.get(key).await?;
+
+        match raw {
+            Some(raw) => Ok(Some(serde_json::from_str(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn set<T>(&self, key: &str, value: T, ttl: Option<Duration>) -> Result<()>
+    where
+        T: Serialize + Send,
+    {
+        let ttl = ttl.unwrap_or(self.config.ttl);
+        let raw = serde_json::to_string(&value)?;
+
+        let mut conn = self.connection.clone();
+        let _: () = conn.set_ex(key, raw, ttl.as_secs().max(1)).await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        let mut conn = self.connection.clone();
+        let _: () = conn.del(key).await?;
+        Ok(())
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        let mut conn = self.connection.clone();
+        Ok(conn.exists(key).await?)
+    }
+
+    async fn clear(&self) -> Result<()> {
+        let mut conn = self.connection.clone();
+        let _: () = redis::cmd("FLUSHDB").query_async(&mut conn).await?;
+        debug!("Flushed Redis cache database");
+        Ok(())
+    }
+
+    async fn size(&self) -> Result<usize> {
+        let mut conn = self.connection.clone();
+        let size: usize = redis::cmd("DBSIZE").query_async(&mut conn).await?;
+        Ok(size)
+    }
+}
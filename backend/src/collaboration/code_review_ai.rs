@@ -1,4 +1,5 @@
 use anyhow::Result;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -6,6 +7,8 @@ use tokio::sync::RwLock;
 use tracing::{info, warn};
 use uuid::Uuid;
 
+use crate::conversation::CodeContext;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeReview {
     pub id: Uuid,
@@ -94,16 +97,269 @@ pub struct AIReviewAnalysis {
     pub estimated_review_time: u32, // minutes
 }
 
+/// One-shot review of a standalone diff, as opposed to [`CodeReview`]'s
+/// stateful multi-file review-with-reviewers workflow. Returned by
+/// [`AICodeReviewer::review`] and persisted to the `code_reviews` table so a
+/// caller can look a past verdict up by id instead of re-running it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeReviewReport {
+    pub id: Uuid,
+    pub overall_quality_score: f32,
+    pub issues: Vec<ReviewIssue>,
+    pub approval_recommendation: ReviewDecision,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewIssue {
+    pub severity: ReviewSeverity,
+    pub category: ReviewCategory,
+    pub file: String,
+    pub line: u32,
+    pub message: String,
+    pub suggested_fix: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ReviewSeverity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReviewCategory {
+    Security,
+    Performance,
+    Style,
+    Correctness,
+    Maintainability,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReviewDecision {
+    Approve,
+    RequestChanges,
+    Reject,
+}
+
 pub struct AICodeReviewer {
     reviews: Arc<RwLock<HashMap<Uuid, CodeReview>>>,
     ai_engine: Option<Arc<RwLock<crate::ai_engine::AIEngine>>>,
+    pool: Arc<sqlx::PgPool>,
 }
 
 impl AICodeReviewer {
-    pub fn new(ai_engine: Option<Arc<RwLock<crate::ai_engine::AIEngine>>>) -> Self {
+    pub fn new(ai_engine: Option<Arc<RwLock<crate::ai_engine::AIEngine>>>, pool: Arc<sqlx::PgPool>) -> Self {
         Self {
             reviews: Arc::new(RwLock::new(HashMap::new())),
             ai_engine,
+            pool,
+        }
+    }
+
+    /// Reviews a standalone unified diff (e.g. a PR), as opposed to
+    /// [`Self::create_review`]'s stateful multi-file workflow with
+    /// registered reviewers. `context` supplies the file to attribute an
+    /// issue to when the diff itself carries no `+++ b/<path>` header (e.g.
+    /// a bare hunk pasted by a client).
+    pub async fn review(&self, diff: &str, context: &CodeContext) -> Result<CodeReviewReport> {
+        let fallback_file = context.current_file.clone().unwrap_or_else(|| "unknown".to_string());
+        let issues = Self::scan_diff(diff, &fallback_file);
+        let overall_quality_score = Self::score_issues(&issues);
+        let approval_recommendation = Self::recommend(&issues, overall_quality_score);
+
+        let report = CodeReviewReport {
+            id: Uuid::new_v4(),
+            overall_quality_score,
+            issues,
+            approval_recommendation,
+            created_at: chrono::Utc::now(),
+        };
+
+        self.save_report(&report).await?;
+
+        info!(
+            review_id = %report.id,
+            issue_count = report.issues.len(),
+            decision = ?report.approval_recommendation,
+            "Reviewed diff"
+        );
+        Ok(report)
+    }
+
+    /// Persists a diff review so it can be looked up by id later instead of
+    /// re-run against the same diff.
+    async fn save_report(&self, report: &CodeReviewReport) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO code_reviews (id, overall_quality_score, issues, approval_recommendation, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(report.id)
+        .bind(report.overall_quality_score)
+        .bind(serde_json::to_value(&report.issues)?)
+        .bind(serde_json::to_string(&report.approval_recommendation)?)
+        .bind(report.created_at)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Walks a unified diff's added lines, tracking the current file (from
+    /// `+++ b/<path>` headers) and line number (from `@@ -a,b +c,d @@` hunk
+    /// headers) so each issue can point at exactly where it was introduced.
+    fn scan_diff(diff: &str, fallback_file: &str) -> Vec<ReviewIssue> {
+        let hunk_header = Regex::new(r"^@@ -\d+(?:,\d+)? \+(\d+)(?:,\d+)? @@").unwrap();
+        let mut issues = Vec::new();
+        let mut current_file = fallback_file.to_string();
+        let mut current_line: u32 = 0;
+        let mut for_loops: Vec<(String, u32)> = Vec::new();
+
+        for line in diff.lines() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                let path = path.trim_start_matches("b/");
+                if path != "/dev/null" {
+                    current_file = path.to_string();
+                }
+                continue;
+            }
+            if line.starts_with("--- ") {
+                continue;
+            }
+            if let Some(captures) = hunk_header.captures(line) {
+                current_line = captures[1].parse().unwrap_or(1);
+                continue;
+            }
+
+            if let Some(added) = line.strip_prefix('+') {
+                issues.extend(Self::scan_added_line(added, &current_file, current_line));
+                if added.contains("for ") {
+                    for_loops.push((current_file.clone(), current_line));
+                }
+                current_line += 1;
+            } else if line.starts_with(' ') {
+                current_line += 1;
+            }
+            // '-' lines removed code at the old line number; they don't
+            // exist in the new file, so the counter doesn't advance.
+        }
+
+        if for_loops.len() >= 2 {
+            let (file, line) = for_loops[0].clone();
+            issues.push(ReviewIssue {
+                severity: ReviewSeverity::Medium,
+                category: ReviewCategory::Performance,
+                file,
+                line,
+                message: "Multiple loops added in this diff -- check for avoidable O(n\u{b2}) complexity".to_string(),
+                suggested_fix: Some("Consider a single pass, a hash map lookup, or hoisting the inner loop's invariant work out".to_string()),
+            });
+        }
+
+        issues
+    }
+
+    fn scan_added_line(line: &str, file: &str, line_number: u32) -> Vec<ReviewIssue> {
+        let mut issues = Vec::new();
+        let trimmed = line.trim();
+        let lower = trimmed.to_lowercase();
+
+        if trimmed.contains("eval(") {
+            issues.push(ReviewIssue {
+                severity: ReviewSeverity::Critical,
+                category: ReviewCategory::Security,
+                file: file.to_string(),
+                line: line_number,
+                message: "Use of eval() allows arbitrary code execution".to_string(),
+                suggested_fix: Some("Parse the input explicitly instead of evaluating it".to_string()),
+            });
+        }
+
+        if trimmed.contains("shell=True") || trimmed.contains("os.system(") {
+            issues.push(ReviewIssue {
+                severity: ReviewSeverity::Critical,
+                category: ReviewCategory::Security,
+                file: file.to_string(),
+                line: line_number,
+                message: "Shell invocation with unsanitized input risks command injection".to_string(),
+                suggested_fix: Some("Pass arguments as a list/array instead of a shell string".to_string()),
+            });
+        }
+
+        if (lower.contains("password") || lower.contains("api_key") || lower.contains("secret"))
+            && trimmed.contains('=')
+            && (trimmed.contains('"') || trimmed.contains('\''))
+        {
+            issues.push(ReviewIssue {
+                severity: ReviewSeverity::High,
+                category: ReviewCategory::Security,
+                file: file.to_string(),
+                line: line_number,
+                message: "Possible hardcoded credential".to_string(),
+                suggested_fix: Some("Load this from configuration or a secrets manager instead".to_string()),
+            });
+        }
+
+        if trimmed.contains(".unwrap()") || trimmed.contains(".expect(") {
+            issues.push(ReviewIssue {
+                severity: ReviewSeverity::Medium,
+                category: ReviewCategory::Correctness,
+                file: file.to_string(),
+                line: line_number,
+                message: "Panics on error instead of propagating it".to_string(),
+                suggested_fix: Some("Return a `Result` and handle the error at the call site".to_string()),
+            });
+        }
+
+        if trimmed.contains("TODO") || trimmed.contains("FIXME") {
+            issues.push(ReviewIssue {
+                severity: ReviewSeverity::Low,
+                category: ReviewCategory::Maintainability,
+                file: file.to_string(),
+                line: line_number,
+                message: "Unresolved TODO/FIXME left in the diff".to_string(),
+                suggested_fix: None,
+            });
+        }
+
+        if trimmed.chars().count() > 120 {
+            issues.push(ReviewIssue {
+                severity: ReviewSeverity::Info,
+                category: ReviewCategory::Style,
+                file: file.to_string(),
+                line: line_number,
+                message: "Line exceeds 120 characters".to_string(),
+                suggested_fix: Some("Wrap this line to match the repo's line-length convention".to_string()),
+            });
+        }
+
+        issues
+    }
+
+    fn score_issues(issues: &[ReviewIssue]) -> f32 {
+        let penalty: f32 = issues.iter().map(|issue| match issue.severity {
+            ReviewSeverity::Info => 0.01,
+            ReviewSeverity::Low => 0.03,
+            ReviewSeverity::Medium => 0.08,
+            ReviewSeverity::High => 0.18,
+            ReviewSeverity::Critical => 0.35,
+        }).sum();
+
+        (1.0 - penalty).max(0.0)
+    }
+
+    fn recommend(issues: &[ReviewIssue], overall_quality_score: f32) -> ReviewDecision {
+        if issues.iter().any(|issue| issue.severity == ReviewSeverity::Critical) {
+            ReviewDecision::Reject
+        } else if overall_quality_score < 0.7 || issues.iter().any(|issue| issue.severity == ReviewSeverity::High) {
+            ReviewDecision::RequestChanges
+        } else {
+            ReviewDecision::Approve
         }
     }
 
@@ -9,6 +9,8 @@ pub struct Config {
     pub security: SecurityConfig,
     pub features: FeaturesConfig,
     pub rate_limiting: RateLimitConfig,
+    pub search_cache: SearchCacheConfig,
+    pub artifacts: ArtifactsConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,10 +29,109 @@ pub struct ProvidersConfig {
     pub groq: ProviderConfig,
     pub together: ProviderConfig,
     pub cohere: ProviderConfig,
+    pub mistral: ProviderConfig,
     pub ollama: ProviderConfig,
+    pub azure_openai: AzureOpenAIConfig,
+    pub bedrock: BedrockConfig,
     pub preferred_models: Vec<String>,
     pub fallback_models: Vec<String>,
     pub provider_priorities: HashMap<String, u8>,
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// How often `ProviderRouter`'s background task refreshes cached
+    /// provider health, in seconds.
+    pub health_check_interval_seconds: u64,
+    pub retry: RetryConfig,
+    pub rate_limit: ProviderRateLimitConfig,
+    /// TTL, in seconds, for `ProviderRouter::get_models`' cached per-provider
+    /// model listings.
+    pub model_cache_ttl_seconds: u64,
+    /// Name of the provider `SearchService` uses to rerank search results
+    /// after `SemanticSearchEngine::search`, e.g. `"cohere"`. Lets a user run
+    /// completions through one provider while reranking through another
+    /// (currently only Cohere implements `providers::RerankProvider`).
+    /// `None` skips this post-processing step entirely.
+    pub rerank_provider: Option<String>,
+    /// Default for `CompletionRequest::cache` when a caller doesn't set it.
+    /// Only ever applies to deterministic requests (temperature near zero);
+    /// see [`super::providers::router::ProviderRouter::complete_with_fallback`].
+    pub response_cache_enabled_by_default: bool,
+    /// TTL for `ProviderRouter`'s completion response cache.
+    pub response_cache_ttl_seconds: u64,
+}
+
+/// Governs `ProviderRouter`'s per-provider retry loop for transient failures
+/// (rate limits, timeouts, 5xx responses) before it falls over to the next
+/// provider in the failover chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Attempts per provider per request, including the initial try. `1`
+    /// disables retrying.
+    pub max_attempts: u32,
+    /// Base delay for exponential backoff between retries.
+    pub base_delay_ms: u64,
+    /// Ceiling on the backoff delay, before jitter is added.
+    pub max_delay_ms: u64,
+    /// Random jitter added on top of the backoff delay, as a fraction of it
+    /// (e.g. `0.2` adds up to +20%). Spreads out retries from concurrent
+    /// requests so they don't all hammer a recovering provider at once.
+    pub jitter_factor: f64,
+}
+
+/// Per-`(user_id, provider)` token-bucket budget enforced by
+/// `providers::rate_limiter::ProviderRateLimiter`, independent of the
+/// IP-based and per-user-only limits applied at the HTTP layer in
+/// `security`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderRateLimitConfig {
+    pub requests_per_minute: u32,
+    pub tokens_per_minute: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AzureOpenAIConfig {
+    pub enabled: bool,
+    pub api_key: Option<String>,
+    /// Resource endpoint, e.g. `https://my-resource.openai.azure.com`.
+    pub endpoint: String,
+    /// Default deployment name used when a request doesn't specify one.
+    pub deployment: String,
+    /// API version query parameter required by Azure OpenAI, e.g. `2024-02-15-preview`.
+    pub api_version: String,
+    pub timeout_seconds: u64,
+    pub max_retries: u32,
+    pub priority: u8,
+}
+
+/// Bedrock is reached with AWS SigV4-signed requests rather than a static API
+/// key, so credentials are sourced straight from the environment/instance
+/// profile here instead of going through `ApiKeyManager`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BedrockConfig {
+    pub enabled: bool,
+    pub region: String,
+    pub access_key_id: Option<String>,
+    pub secret_access_key: Option<String>,
+    pub session_token: Option<String>,
+    /// Bedrock model id used when a request doesn't specify one, e.g.
+    /// `anthropic.claude-3-sonnet-20240229-v1:0`.
+    pub default_model: String,
+    pub timeout_seconds: u64,
+    pub max_retries: u32,
+    pub priority: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures before a provider's circuit is opened.
+    pub failure_threshold: u32,
+    /// Only failures within this trailing window count toward
+    /// `failure_threshold`; older failures age out.
+    pub window_seconds: u64,
+    /// How long the circuit stays open before a half-open probe is allowed.
+    pub cooldown_seconds: u64,
+    /// Ceiling for the cooldown after repeated half-open probe failures
+    /// double it each time.
+    pub max_cooldown_seconds: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +149,15 @@ pub struct ProviderConfig {
 pub struct DatabaseConfig {
     pub url: String,
     pub max_connections: u32,
+    /// Connections `PgPoolOptions` keeps open even when idle, so a burst of
+    /// traffic doesn't pay the connection-setup cost on every request.
+    pub min_connections: u32,
+    /// How long `pool.acquire()` waits for a free connection before failing,
+    /// in seconds.
+    pub acquire_timeout_seconds: u64,
+    /// How long an idle connection sits in the pool before `PgPoolOptions`
+    /// closes it, in seconds.
+    pub idle_timeout_seconds: u64,
     pub enable_migrations: bool,
 }
 
@@ -65,6 +175,10 @@ pub struct FeaturesConfig {
     pub enable_streaming: bool,
     pub enable_function_calling: bool,
     pub enable_code_execution: bool,
+    /// When set, `AITerminalService` deduplicates a session's command
+    /// history after every executed command instead of requiring an
+    /// explicit `POST /terminal/sessions/:session_id/deduplicate` call.
+    pub terminal_deduplicate_on_write: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,6 +188,24 @@ pub struct RateLimitConfig {
     pub enable_per_user_limits: bool,
 }
 
+/// Governs `SemanticSearchEngine`'s cache of recent search results, keyed on
+/// the query plus everything that affects its outcome (workspace paths,
+/// filters, similarity threshold).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchCacheConfig {
+    pub ttl_seconds: u64,
+    pub max_entries: usize,
+}
+
+/// Where the `/api/v1/artifacts/:run_id/{download,upload}` handlers read and
+/// write run artifacts, keyed by `<run_id>/<filename>` under `storage_path`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactsConfig {
+    pub storage_path: String,
+    /// Per-file limit enforced by the upload handler, in bytes.
+    pub max_upload_size_bytes: u64,
+}
+
 impl Config {
     pub fn from_env() -> anyhow::Result<Self> {
         dotenvy::dotenv().ok(); // Load .env file if it exists
@@ -189,6 +321,23 @@ impl Config {
                     "command-r".to_string(),
                 ],
             },
+            mistral: ProviderConfig {
+                enabled: std::env::var("MISTRAL_API_KEY").is_ok(),
+                api_key: std::env::var("MISTRAL_API_KEY").ok(),
+                base_url: std::env::var("MISTRAL_BASE_URL")
+                    .unwrap_or_else(|_| "https://api.mistral.ai/v1".to_string()),
+                timeout_seconds: 30,
+                max_retries: 3,
+                priority: std::env::var("MISTRAL_PRIORITY")
+                    .unwrap_or_else(|_| "6".to_string())
+                    .parse()
+                    .unwrap_or(6),
+                models: vec![
+                    "codestral-latest".to_string(),
+                    "mistral-large-latest".to_string(),
+                    "mistral-small-latest".to_string(),
+                ],
+            },
             ollama: ProviderConfig {
                 enabled: true, // Always enabled as fallback
                 api_key: None,
@@ -203,6 +352,40 @@ impl Config {
                     "deepseek-coder:6.7b".to_string(),
                 ],
             },
+            azure_openai: AzureOpenAIConfig {
+                enabled: std::env::var("AZURE_OPENAI_API_KEY").is_ok(),
+                api_key: std::env::var("AZURE_OPENAI_API_KEY").ok(),
+                endpoint: std::env::var("AZURE_OPENAI_ENDPOINT")
+                    .unwrap_or_else(|_| "https://your-resource.openai.azure.com".to_string()),
+                deployment: std::env::var("AZURE_OPENAI_DEPLOYMENT")
+                    .unwrap_or_else(|_| "gpt-4o".to_string()),
+                api_version: std::env::var("AZURE_OPENAI_API_VERSION")
+                    .unwrap_or_else(|_| "2024-02-15-preview".to_string()),
+                timeout_seconds: 30,
+                max_retries: 3,
+                priority: std::env::var("AZURE_OPENAI_PRIORITY")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()
+                    .unwrap_or(2),
+            },
+            bedrock: BedrockConfig {
+                enabled: std::env::var("BEDROCK_ENABLED")
+                    .map(|v| v == "true")
+                    .unwrap_or(false),
+                region: std::env::var("AWS_REGION")
+                    .unwrap_or_else(|_| "us-east-1".to_string()),
+                access_key_id: std::env::var("AWS_ACCESS_KEY_ID").ok(),
+                secret_access_key: std::env::var("AWS_SECRET_ACCESS_KEY").ok(),
+                session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+                default_model: std::env::var("BEDROCK_DEFAULT_MODEL")
+                    .unwrap_or_else(|_| "anthropic.claude-3-sonnet-20240229-v1:0".to_string()),
+                timeout_seconds: 30,
+                max_retries: 3,
+                priority: std::env::var("BEDROCK_PRIORITY")
+                    .unwrap_or_else(|_| "7".to_string())
+                    .parse()
+                    .unwrap_or(7),
+            },
             preferred_models: std::env::var("PREFERRED_MODELS")
                 .unwrap_or_else(|_| "gpt-4o,claude-3-5-sonnet-20241022,gemini-pro".to_string())
                 .split(',')
@@ -214,12 +397,90 @@ impl Config {
                 .map(|s| s.trim().to_string())
                 .collect(),
             provider_priorities: HashMap::new(), // Will be populated from individual priorities
+            circuit_breaker: CircuitBreakerConfig {
+                failure_threshold: std::env::var("CIRCUIT_BREAKER_FAILURE_THRESHOLD")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()
+                    .unwrap_or(5),
+                window_seconds: std::env::var("CIRCUIT_BREAKER_WINDOW_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .unwrap_or(60),
+                cooldown_seconds: std::env::var("CIRCUIT_BREAKER_COOLDOWN_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .unwrap_or(60),
+                max_cooldown_seconds: std::env::var("CIRCUIT_BREAKER_MAX_COOLDOWN_SECONDS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .unwrap_or(300),
+            },
+            health_check_interval_seconds: std::env::var("PROVIDER_HEALTH_CHECK_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            retry: RetryConfig {
+                max_attempts: std::env::var("PROVIDER_RETRY_MAX_ATTEMPTS")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()
+                    .unwrap_or(3),
+                base_delay_ms: std::env::var("PROVIDER_RETRY_BASE_DELAY_MS")
+                    .unwrap_or_else(|_| "250".to_string())
+                    .parse()
+                    .unwrap_or(250),
+                max_delay_ms: std::env::var("PROVIDER_RETRY_MAX_DELAY_MS")
+                    .unwrap_or_else(|_| "10000".to_string())
+                    .parse()
+                    .unwrap_or(10000),
+                jitter_factor: std::env::var("PROVIDER_RETRY_JITTER_FACTOR")
+                    .unwrap_or_else(|_| "0.2".to_string())
+                    .parse()
+                    .unwrap_or(0.2),
+            },
+            rate_limit: ProviderRateLimitConfig {
+                requests_per_minute: std::env::var("PROVIDER_RATE_LIMIT_REQUESTS_PER_MINUTE")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .unwrap_or(60),
+                tokens_per_minute: std::env::var("PROVIDER_RATE_LIMIT_TOKENS_PER_MINUTE")
+                    .unwrap_or_else(|_| "100000".to_string())
+                    .parse()
+                    .unwrap_or(100000),
+            },
+            model_cache_ttl_seconds: std::env::var("PROVIDER_MODEL_CACHE_TTL_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .unwrap_or(3600),
+            rerank_provider: std::env::var("RERANK_PROVIDER").ok(),
+            response_cache_enabled_by_default: std::env::var("PROVIDER_RESPONSE_CACHE_ENABLED_BY_DEFAULT")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            response_cache_ttl_seconds: std::env::var("PROVIDER_RESPONSE_CACHE_TTL_SECONDS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .unwrap_or(300),
         };
 
         let database = DatabaseConfig {
             url: std::env::var("DATABASE_URL")
                 .unwrap_or_else(|_| "sqlite:./data/uaida.db".to_string()),
-            max_connections: 10,
+            max_connections: std::env::var("DB_MAX_CONNECTIONS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            min_connections: std::env::var("DB_MIN_CONNECTIONS")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .unwrap_or(1),
+            acquire_timeout_seconds: std::env::var("DB_ACQUIRE_TIMEOUT_SECONDS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            idle_timeout_seconds: std::env::var("DB_IDLE_TIMEOUT_SECONDS")
+                .unwrap_or_else(|_| "600".to_string())
+                .parse()
+                .unwrap_or(600),
             enable_migrations: true,
         };
 
@@ -257,6 +518,10 @@ impl Config {
                 .unwrap_or_else(|_| "false".to_string())
                 .parse()
                 .unwrap_or(false),
+            terminal_deduplicate_on_write: std::env::var("TERMINAL_DEDUPLICATE_ON_WRITE")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
         };
 
         let rate_limiting = RateLimitConfig {
@@ -271,6 +536,26 @@ impl Config {
             enable_per_user_limits: true,
         };
 
+        let search_cache = SearchCacheConfig {
+            ttl_seconds: std::env::var("SEARCH_CACHE_TTL_SECONDS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .unwrap_or(300),
+            max_entries: std::env::var("SEARCH_CACHE_MAX_ENTRIES")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .unwrap_or(500),
+        };
+
+        let artifacts = ArtifactsConfig {
+            storage_path: std::env::var("ARTIFACTS_STORAGE_PATH")
+                .unwrap_or_else(|_| "./data/artifacts".to_string()),
+            max_upload_size_bytes: std::env::var("ARTIFACTS_MAX_UPLOAD_SIZE_BYTES")
+                .unwrap_or_else(|_| (50 * 1024 * 1024).to_string())
+                .parse()
+                .unwrap_or(50 * 1024 * 1024),
+        };
+
         Ok(Config {
             server,
             providers,
@@ -278,6 +563,8 @@ impl Config {
             security,
             features,
             rate_limiting,
+            search_cache,
+            artifacts,
         })
     }
 }
\ No newline at end of file
@@ -3,13 +3,26 @@ use std::sync::Arc;
 use std::path::Path;
 use tokio::fs;
 use tracing::{info, warn, error};
+use uuid::Uuid;
 
 use crate::providers::{ProviderRouter, CompletionRequest};
+use crate::providers::tokenizer::count_tokens;
 use super::{
     CodeChange, ChangeType, MessageIntent, CodeContext, WorkspaceContext,
-    FunctionInfo, ImportInfo, SymbolInfo, SymbolType, TextSelection, Position
+    FunctionInfo, ImportInfo, SymbolInfo, SymbolType, TextSelection, Position,
+    AttachedFileContext, AttachedSpan,
 };
 
+/// Token count above which a context file is chunked and only its most
+/// relevant spans are attached, instead of the whole file.
+const FILE_ATTACHMENT_TOKEN_THRESHOLD: usize = 2_000;
+
+/// Lines per candidate span when chunking an oversized file.
+const SPAN_LINE_WINDOW: usize = 60;
+
+/// Highest-scoring spans kept per oversized file.
+const MAX_SPANS_PER_FILE: usize = 3;
+
 pub struct CodeIntegrationService {
     provider_router: Arc<ProviderRouter>,
 }
@@ -19,6 +32,73 @@ impl CodeIntegrationService {
         Self { provider_router }
     }
 
+    /// Resolves each of a message's `context_files` into the span(s) of its
+    /// content actually worth sending to the provider: read in full if it's
+    /// under `FILE_ATTACHMENT_TOKEN_THRESHOLD` for `model`, otherwise
+    /// chunked and ranked by relevance to `user_message`. A file outside
+    /// `workspace_root` is rejected rather than silently skipped, since
+    /// that's a workspace-escape attempt, not a missing file.
+    pub async fn attach_context_files(
+        &self,
+        file_paths: &[String],
+        workspace_root: &str,
+        user_message: &str,
+        model: &str,
+    ) -> Result<Vec<AttachedFileContext>> {
+        let canonical_root = fs::canonicalize(workspace_root).await
+            .map_err(|e| anyhow::anyhow!("Cannot resolve workspace root '{workspace_root}': {e}"))?;
+
+        let mut attachments = Vec::with_capacity(file_paths.len());
+        for file_path in file_paths {
+            match self.attach_context_file(file_path, &canonical_root, user_message, model).await {
+                Ok(attachment) => attachments.push(attachment),
+                Err(e) => warn!(file_path = %file_path, error = %e, "Skipping context file attachment"),
+            }
+        }
+
+        Ok(attachments)
+    }
+
+    async fn attach_context_file(
+        &self,
+        file_path: &str,
+        canonical_root: &Path,
+        user_message: &str,
+        model: &str,
+    ) -> Result<AttachedFileContext> {
+        let canonical_file = fs::canonicalize(file_path).await
+            .map_err(|e| anyhow::anyhow!("Cannot read context file '{file_path}': {e}"))?;
+
+        if !canonical_file.starts_with(canonical_root) {
+            return Err(anyhow::anyhow!(
+                "Context file '{file_path}' is outside the session's workspace root"
+            ));
+        }
+
+        let content = fs::read_to_string(&canonical_file).await?;
+        let total_tokens = count_tokens(&content, model);
+
+        if total_tokens <= FILE_ATTACHMENT_TOKEN_THRESHOLD {
+            let line_count = content.lines().count().max(1);
+            return Ok(AttachedFileContext {
+                file_path: file_path.to_string(),
+                spans: vec![AttachedSpan { start_line: 1, end_line: line_count, content }],
+                tokens_contributed: total_tokens,
+                truncated: false,
+            });
+        }
+
+        let spans = select_relevant_spans(&content, user_message);
+        let tokens_contributed = spans.iter().map(|s| count_tokens(&s.content, model)).sum();
+
+        Ok(AttachedFileContext {
+            file_path: file_path.to_string(),
+            spans,
+            tokens_contributed,
+            truncated: true,
+        })
+    }
+
     pub async fn generate_code(
         &self,
         request: &CodeGenerationRequest,
@@ -51,12 +131,13 @@ impl CodeIntegrationService {
         code: &str,
         file_path: Option<&str>,
         workspace_context: &WorkspaceContext,
+        attached_files: &[AttachedFileContext],
     ) -> Result<CodeExplanation> {
         info!("Explaining code from: {:?}", file_path);
 
         let language = self.detect_language(code, file_path);
         let context_info = if let Some(path) = file_path {
-            self.get_file_context(path, workspace_context).await?
+            self.get_file_context(path, workspace_context, attached_files).await?
         } else {
             String::new()
         };
@@ -109,12 +190,13 @@ Açıklamayı Türkçe yap ve teknik terimleri açıkla."#,
         code: &str,
         file_path: Option<&str>,
         workspace_context: &WorkspaceContext,
+        attached_files: &[AttachedFileContext],
     ) -> Result<CodeReview> {
         info!("Reviewing code from: {:?}", file_path);
 
         let language = self.detect_language(code, file_path);
         let context_info = if let Some(path) = file_path {
-            self.get_file_context(path, workspace_context).await?
+            self.get_file_context(path, workspace_context, attached_files).await?
         } else {
             String::new()
         };
@@ -168,12 +250,13 @@ KATEGORI: PUAN - AÇIKLAMA
         code: &str,
         file_path: Option<&str>,
         workspace_context: &WorkspaceContext,
+        attached_files: &[AttachedFileContext],
     ) -> Result<RefactoringSuggestion> {
         info!("Suggesting refactoring for: {:?}", file_path);
 
         let language = self.detect_language(code, file_path);
         let context_info = if let Some(path) = file_path {
-            self.get_file_context(path, workspace_context).await?
+            self.get_file_context(path, workspace_context, attached_files).await?
         } else {
             String::new()
         };
@@ -280,12 +363,13 @@ Test coverage %90+ olmalı."#,
         error_message: &str,
         file_path: Option<&str>,
         workspace_context: &WorkspaceContext,
+        attached_files: &[AttachedFileContext],
     ) -> Result<CodeFix> {
         info!("Fixing code error: {}", error_message);
 
         let language = self.detect_language(code, file_path);
         let context_info = if let Some(path) = file_path {
-            self.get_file_context(path, workspace_context).await?
+            self.get_file_context(path, workspace_context, attached_files).await?
         } else {
             String::new()
         };
@@ -375,6 +459,11 @@ Dosya bağlamı:
             context_parts.push(format!("Import'lar: {:?}", imports));
         }
 
+        // Açıkça eklenen dosyalar (context_files)
+        for attachment in &code_context.attached_files {
+            context_parts.push(render_attached_file(attachment));
+        }
+
         Ok(context_parts.join("\n"))
     }
 
@@ -471,7 +560,12 @@ Kod bloklarını ``` ile işaretle ve dili belirt."#,
         }
     }
 
-    async fn get_file_context(&self, file_path: &str, workspace_context: &WorkspaceContext) -> Result<String> {
+    async fn get_file_context(
+        &self,
+        file_path: &str,
+        workspace_context: &WorkspaceContext,
+        attached_files: &[AttachedFileContext],
+    ) -> Result<String> {
         let mut context_parts = Vec::new();
 
         // Dosya tipi
@@ -500,6 +594,11 @@ Kod bloklarını ``` ile işaretle ve dili belirt."#,
             }
         }
 
+        // Açıkça eklenen dosyalar (context_files)
+        for attachment in attached_files {
+            context_parts.push(render_attached_file(attachment));
+        }
+
         Ok(context_parts.join("\n"))
     }
 
@@ -539,6 +638,7 @@ Kod bloklarını ``` ile işaretle ve dili belirt."#,
                 .unwrap_or_else(|| format!("generated_code_{}.txt", i));
                 
             code_changes.push(CodeChange {
+                id: Uuid::new_v4(),
                 file_path,
                 change_type: ChangeType::Create,
                 old_content: None,
@@ -669,6 +769,72 @@ Kod bloklarını ``` ile işaretle ve dili belirt."#,
     }
 }
 
+/// Splits `content` into `SPAN_LINE_WINDOW`-line chunks, scores each by
+/// keyword overlap with `user_message`, and keeps the top
+/// `MAX_SPANS_PER_FILE`. Falls back to the file's first spans, in order,
+/// when nothing scores above zero -- e.g. a generic "explain this file"
+/// message with no keywords worth matching on.
+fn select_relevant_spans(content: &str, user_message: &str) -> Vec<AttachedSpan> {
+    let lines: Vec<&str> = content.lines().collect();
+    let keywords = extract_keywords(user_message);
+
+    let mut windows = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = (start + SPAN_LINE_WINDOW).min(lines.len());
+        windows.push((start, end));
+        start = end;
+    }
+
+    let mut scored: Vec<(f32, usize, usize)> = windows
+        .into_iter()
+        .map(|(start, end)| (keyword_overlap_score(&lines[start..end].join("\n"), &keywords), start, end))
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    scored
+        .into_iter()
+        .take(MAX_SPANS_PER_FILE)
+        .map(|(_, start, end)| AttachedSpan {
+            start_line: start + 1,
+            end_line: end,
+            content: lines[start..end].join("\n"),
+        })
+        .collect()
+}
+
+/// Lowercased alphanumeric words longer than 2 characters, used as a cheap
+/// stand-in for the workspace-wide embedding search (`SearchService`) when
+/// ranking spans within a single attached file.
+fn extract_keywords(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .map(|w| w.to_lowercase())
+        .filter(|w| w.len() > 2)
+        .collect()
+}
+
+fn keyword_overlap_score(window: &str, keywords: &[String]) -> f32 {
+    if keywords.is_empty() {
+        return 0.0;
+    }
+    let window_lower = window.to_lowercase();
+    let matches = keywords.iter().filter(|k| window_lower.contains(k.as_str())).count();
+    matches as f32 / keywords.len() as f32
+}
+
+/// Renders an attached file's spans as a fenced block annotated with its
+/// path and line range, for inlining into a provider-facing prompt.
+pub fn render_attached_file(attachment: &AttachedFileContext) -> String {
+    let mut out = String::new();
+    for span in &attachment.spans {
+        out.push_str(&format!(
+            "# {} (lines {}-{})\n```\n{}\n```\n\n",
+            attachment.file_path, span.start_line, span.end_line, span.content
+        ));
+    }
+    out
+}
+
 // Request/Response types
 #[derive(Debug, Clone)]
 pub struct CodeGenerationRequest {
@@ -729,4 +895,69 @@ pub struct CodeFix {
     pub explanation: String,
     pub error_type: String,
     pub prevention_tips: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 5,000-line file with two needle functions far apart, surrounded by
+    /// filler lines with no keyword overlap with the test's user message.
+    fn large_fixture_with_needles() -> String {
+        let mut lines = Vec::with_capacity(5_000);
+        for i in 0..5_000 {
+            if i == 1_200 {
+                lines.push("fn calculate_invoice_total(items: &[Item]) -> f64 {".to_string());
+            } else if i == 4_300 {
+                lines.push("fn calculate_invoice_total_with_tax(items: &[Item], tax_rate: f64) -> f64 {".to_string());
+            } else {
+                lines.push(format!("// filler line {i}, nothing to see here"));
+            }
+        }
+        lines.join("\n")
+    }
+
+    #[test]
+    fn select_relevant_spans_ranks_windows_matching_the_message_first() {
+        let content = large_fixture_with_needles();
+        let spans = select_relevant_spans(&content, "how do I calculate the invoice total?");
+
+        assert!(!spans.is_empty());
+        assert!(spans.len() <= MAX_SPANS_PER_FILE);
+
+        // The two windows containing a needle function should outrank every
+        // filler-only window and come back ahead of them.
+        let needle_hits = spans.iter().filter(|s| s.content.contains("calculate_invoice_total")).count();
+        assert_eq!(needle_hits, 2);
+    }
+
+    #[test]
+    fn select_relevant_spans_falls_back_to_first_windows_with_no_keyword_match() {
+        let content = large_fixture_with_needles();
+        let spans = select_relevant_spans(&content, "???");
+
+        assert_eq!(spans.len(), MAX_SPANS_PER_FILE);
+    }
+
+    #[test]
+    fn extract_keywords_drops_short_and_punctuation_only_words() {
+        let keywords = extract_keywords("fix the bug in db.rs, it's a NullPointerException!");
+        assert!(keywords.contains(&"bug".to_string()));
+        assert!(keywords.contains(&"nullpointerexception".to_string()));
+        assert!(!keywords.contains(&"in".to_string()));
+    }
+
+    #[test]
+    fn render_attached_file_annotates_path_and_line_range() {
+        let attachment = AttachedFileContext {
+            file_path: "src/lib.rs".to_string(),
+            spans: vec![AttachedSpan { start_line: 10, end_line: 20, content: "fn foo() {}".to_string() }],
+            tokens_contributed: 5,
+            truncated: true,
+        };
+
+        let rendered = render_attached_file(&attachment);
+        assert!(rendered.contains("src/lib.rs (lines 10-20)"));
+        assert!(rendered.contains("fn foo() {}"));
+    }
 }
\ No newline at end of file
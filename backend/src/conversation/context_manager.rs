@@ -4,10 +4,11 @@ use std::collections::HashMap;
 use tokio::fs;
 use tracing::{info, debug, warn};
 
+use crate::providers::{AIProvider, CompletionRequest, ProviderRouter};
 use super::{
     WorkspaceContext, CodeContext, ConversationSession, ConversationTurn,
     OpenFile, FunctionInfo, ImportInfo, SymbolInfo, TextSelection,
-    workspace_analyzer::WorkspaceAnalyzer
+    ExplanationLevel, workspace_analyzer::WorkspaceAnalyzer
 };
 
 pub struct ContextManager {
@@ -627,4 +628,317 @@ impl Default for ContextManager {
     fn default() -> Self {
         Self::new()
     }
+}
+
+/// Most recent turns kept verbatim in an assembled context; everything
+/// older is folded into `ConversationSession::rolling_summary` instead.
+const RECENT_VERBATIM_TURNS: usize = 10;
+
+/// Result of [`ConversationContextAssembler::assemble`]: what to send to
+/// the provider for this turn, and how many tokens it actually costs.
+#[derive(Debug, Clone)]
+pub struct AssembledContext {
+    pub context_turns: Vec<ConversationTurn>,
+    pub context_summary: Option<String>,
+    pub tokens_used: usize,
+}
+
+/// Keeps long conversations within a provider's context window without
+/// simply dropping old turns. The most recent [`RECENT_VERBATIM_TURNS`] are
+/// always sent as-is; anything older is folded into a rolling summary
+/// generated via the provider router and persisted on the session
+/// (`ConversationSession::rolling_summary`) so it's only regenerated for the
+/// turns that have newly aged out, not from scratch on every message.
+/// Token accounting uses `providers::tokenizer::count_tokens` rather than
+/// `token_budget`'s character-based heuristic.
+pub struct ConversationContextAssembler {
+    provider_router: Arc<ProviderRouter>,
+}
+
+impl ConversationContextAssembler {
+    pub fn new(provider_router: Arc<ProviderRouter>) -> Self {
+        Self { provider_router }
+    }
+
+    /// Assembles the context for `current_message`, mutating `session`'s
+    /// rolling summary in place when new turns have aged out of the
+    /// verbatim window. The caller is responsible for persisting `session`
+    /// afterwards (e.g. via `session_manager::SessionManager::update_session`).
+    pub async fn assemble(
+        &self,
+        session: &mut ConversationSession,
+        current_message: &str,
+        model: &str,
+        token_budget: usize,
+    ) -> Result<AssembledContext> {
+        let history_len = session.conversation_history.len();
+        let older_len = history_len.saturating_sub(RECENT_VERBATIM_TURNS);
+
+        if older_len > session.rolling_summary_turns_covered {
+            let newly_aged_out =
+                &session.conversation_history[session.rolling_summary_turns_covered..older_len];
+            let summary = self
+                .summarize_turns(session.rolling_summary.as_deref(), newly_aged_out, model)
+                .await?;
+            session.rolling_summary = Some(summary);
+            session.rolling_summary_turns_covered = older_len;
+        }
+
+        Ok(Self::fill_within_budget(
+            &session.conversation_history[older_len..],
+            session.rolling_summary.as_deref(),
+            current_message,
+            model,
+            token_budget,
+        ))
+    }
+
+    /// Fills `token_budget` with as many of `recent` (already kept verbatim
+    /// by the caller) turns as fit, newest first, after reserving room for
+    /// `summary` and `current_message`. Pure and provider-free, unlike
+    /// `assemble`, so it can be unit tested without constructing a
+    /// `ProviderRouter`.
+    fn fill_within_budget(
+        recent: &[ConversationTurn],
+        summary: Option<&str>,
+        current_message: &str,
+        model: &str,
+        token_budget: usize,
+    ) -> AssembledContext {
+        let mut tokens_used = crate::providers::count_tokens(current_message, model);
+        if let Some(summary) = summary {
+            tokens_used += crate::providers::count_tokens(summary, model);
+        }
+
+        let mut context_turns = Vec::new();
+        for turn in recent.iter().rev() {
+            let turn_tokens = crate::providers::count_tokens(&turn.user_message, model)
+                + crate::providers::count_tokens(&turn.ai_response, model);
+            if tokens_used + turn_tokens > token_budget {
+                break;
+            }
+            tokens_used += turn_tokens;
+            context_turns.push(turn.clone());
+        }
+        context_turns.reverse();
+
+        AssembledContext {
+            context_turns,
+            context_summary: summary.map(|s| s.to_string()),
+            tokens_used,
+        }
+    }
+
+    /// Folds `new_turns` into `previous_summary` via a single provider call,
+    /// so only the newly-aged-out slice needs summarizing each time rather
+    /// than the full older history from scratch.
+    async fn summarize_turns(
+        &self,
+        previous_summary: Option<&str>,
+        new_turns: &[ConversationTurn],
+        model: &str,
+    ) -> Result<String> {
+        let mut prompt = String::new();
+        if let Some(previous) = previous_summary {
+            prompt.push_str("Existing summary of the earlier conversation:\n");
+            prompt.push_str(previous);
+            prompt.push_str("\n\n");
+        }
+        prompt.push_str("New turns to fold into the summary:\n");
+        for turn in new_turns {
+            prompt.push_str(&format!("User: {}\nAssistant: {}\n", turn.user_message, turn.ai_response));
+        }
+        prompt.push_str(
+            "\nWrite an updated summary that preserves every detail still relevant to \
+             continuing this conversation, in a few concise paragraphs.",
+        );
+
+        let completion_request = CompletionRequest::new(prompt)
+            .with_model(model.to_string())
+            .with_max_tokens(500)
+            .with_temperature(0.2)
+            .with_system_prompt(
+                "You maintain a rolling summary of a long conversation so older turns can be \
+                 dropped from the prompt without losing context."
+                    .to_string(),
+            );
+
+        let response = self.provider_router.complete(completion_request).await?;
+        Ok(response.text)
+    }
+}
+
+#[cfg(test)]
+mod context_assembler_tests {
+    use super::*;
+    use crate::conversation::{CodeChange, MessageIntent};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn make_turn(text: &str) -> ConversationTurn {
+        ConversationTurn {
+            id: Uuid::new_v4(),
+            user_message: text.to_string(),
+            ai_response: text.to_string(),
+            intent: MessageIntent::GeneralChat,
+            code_changes: None::<Vec<CodeChange>>,
+            files_referenced: vec![],
+            confidence_score: 1.0,
+            execution_time_ms: 0,
+            parent_turn_id: None,
+            applied_changes: Vec::new(),
+            revisions: Vec::new(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn keeps_assembled_prompt_within_budget_for_two_hundred_turns() {
+        let history: Vec<ConversationTurn> = (0..200)
+            .map(|i| make_turn(&format!("turn {i} with some representative conversational text")))
+            .collect();
+        let older_len = history.len() - RECENT_VERBATIM_TURNS;
+        let summary = "Summary standing in for the first 190 turns.".to_string();
+
+        let budget = 500;
+        let assembled = ConversationContextAssembler::fill_within_budget(
+            &history[older_len..],
+            Some(&summary),
+            "what's next?",
+            "gpt-4o-mini",
+            budget,
+        );
+
+        assert!(assembled.tokens_used <= budget);
+        assert!(assembled.context_turns.len() <= RECENT_VERBATIM_TURNS);
+        assert_eq!(assembled.context_summary, Some(summary));
+    }
+
+    #[test]
+    fn drops_oldest_verbatim_turns_first_when_even_the_recent_window_overflows_the_budget() {
+        let history: Vec<ConversationTurn> = (0..RECENT_VERBATIM_TURNS)
+            .map(|i| make_turn(&"x".repeat(2000).replace('x', &i.to_string())))
+            .collect();
+
+        let assembled = ConversationContextAssembler::fill_within_budget(
+            &history,
+            None,
+            "hi",
+            "gpt-4o-mini",
+            50,
+        );
+
+        assert!(assembled.context_turns.len() < history.len());
+        assert!(assembled.tokens_used <= 50);
+    }
+
+    #[test]
+    fn no_summary_means_no_context_summary_in_the_result() {
+        let history = vec![make_turn("hello")];
+        let assembled = ConversationContextAssembler::fill_within_budget(
+            &history,
+            None,
+            "hi",
+            "gpt-4o-mini",
+            4000,
+        );
+        assert_eq!(assembled.context_summary, None);
+    }
+}
+
+/// Builds system prompts tailored to a session's `ExplanationLevel`, so the
+/// same request ("explain this function", "fix this bug") gets a
+/// jargon-free walkthrough for a beginner and a terse, internals-focused
+/// answer for an expert instead of one fixed register for everyone.
+pub struct PromptTemplateEngine;
+
+impl PromptTemplateEngine {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// `context_type` selects the role instruction (mirrors the
+    /// `context_type` strings `ConversationService::generate_ai_response`
+    /// already passes in, e.g. `"documentation"`, `"debugging"`); `level`
+    /// then layers on how much the response should explain versus assume.
+    pub fn system_prompt(&self, context_type: &str, level: ExplanationLevel) -> String {
+        let role = Self::role_instruction(context_type);
+        let register = Self::level_instruction(level);
+        format!("{}\n\n{}", role, register)
+    }
+
+    fn role_instruction(context_type: &str) -> &'static str {
+        match context_type {
+            "documentation" => "Sen bir teknik yazım uzmanısın. Açık, anlaşılır ve kapsamlı dokümantasyon yazıyorsun.",
+            "file_operation" => "Sen bir dosya sistemi uzmanısın. Dosya işlemlerinde güvenli ve etkili yöntemler öneriyorsun.",
+            "project_setup" => "Sen bir proje kurulum uzmanısın. Adım adım, net kurulum talimatları veriyorsun.",
+            "debugging" => "Sen bir debugging uzmanısın. Sistematik hata bulma ve çözme yöntemleri öneriyorsun.",
+            _ => "Sen yardımcı bir AI asistanısın. Kullanıcıların sorularını net ve faydalı şekilde yanıtlıyorsun.",
+        }
+    }
+
+    fn level_instruction(level: ExplanationLevel) -> &'static str {
+        match level {
+            ExplanationLevel::Beginner => {
+                "Kullanıcı programlamaya yeni başlıyor. Jargon kullanmaktan kaçın; kullanmak \
+                 zorunda kaldığın her teknik terimi ilk geçtiği yerde basitçe tanımla. Adım adım \
+                 açıkla ve temel kavramları atlamadan anlat."
+            }
+            ExplanationLevel::Intermediate => {
+                "Kullanıcı temel kavramları biliyor ama ileri seviye değil. Gerekli terimleri \
+                 kısaca açıkla, ama her adımı didaktik şekilde anlatmana gerek yok."
+            }
+            ExplanationLevel::Advanced => {
+                "Kullanıcı deneyimli bir geliştirici. Temel kavramları atla, doğrudan teknik \
+                 detaya gir ve alternatif yaklaşımlara kısaca değin."
+            }
+            ExplanationLevel::Expert => {
+                "Kullanıcı uzman seviyesinde. Temel açıklamaları tamamen atla; derleyici/runtime \
+                 içyapısı, performans trade-off'ları ve edge case'ler gibi ileri düzey detaylara \
+                 odaklan. Gereksiz giriş cümleleri kullanma."
+            }
+        }
+    }
+}
+
+impl Default for PromptTemplateEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod prompt_template_tests {
+    use super::*;
+
+    #[test]
+    fn beginner_and_expert_prompts_differ() {
+        let engine = PromptTemplateEngine::new();
+
+        let beginner = engine.system_prompt("debugging", ExplanationLevel::Beginner);
+        let expert = engine.system_prompt("debugging", ExplanationLevel::Expert);
+
+        assert_ne!(beginner, expert);
+        assert!(beginner.to_lowercase().contains("jargon"));
+        assert!(expert.to_lowercase().contains("trade-off"));
+    }
+
+    #[test]
+    fn same_level_produces_a_stable_prompt() {
+        let engine = PromptTemplateEngine::new();
+
+        let first = engine.system_prompt("documentation", ExplanationLevel::Advanced);
+        let second = engine.system_prompt("documentation", ExplanationLevel::Advanced);
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn unknown_context_type_falls_back_to_the_general_role() {
+        let engine = PromptTemplateEngine::new();
+
+        let prompt = engine.system_prompt("something_unhandled", ExplanationLevel::Intermediate);
+
+        assert!(prompt.contains("yardımcı bir AI asistanısın"));
+    }
 }
\ No newline at end of file
@@ -1,42 +1,159 @@
 use anyhow::Result;
+use axum::response::sse::Event;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::convert::Infallible;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 use chrono::Utc;
-use tracing::{info, debug, error};
+use tracing::{info, debug, error, warn};
 
+use crate::auth::audit::{AuditEvent, AuditEventType, AuditOutcome, AuditService, log_event_fire_and_forget};
+use crate::auth::preferences::PreferencesService;
+use crate::cache::{redis_cache::session_cache_key, Cache, RedisCache};
 use crate::providers::ProviderRouter;
 use super::{
     ConversationSession, ConversationTurn, ConversationRequest, ConversationResponse,
-    MessageIntent, SuggestedAction, ActionType, ActionPriority,
+    RegenerateTurnOverrides, TurnRevision,
+    CodeChange, MessageIntent, SuggestedAction, ActionType, ActionPriority, ExportFormat, ExplanationLevel,
     session_manager::SessionManager,
-    context_manager::{ContextManager, RelevantContext},
+    context_manager::{ContextManager, ConversationContextAssembler, PromptTemplateEngine, RelevantContext},
     intent_analyzer::IntentAnalyzer,
     code_integration::{CodeIntegrationService, CodeGenerationRequest},
     workspace_analyzer::WorkspaceAnalyzer,
+    session_export,
 };
 
+/// Default token budget `ConversationContextAssembler` fills with recent
+/// turns (plus the rolling summary, when present) before it is handed to a
+/// provider.
+const DEFAULT_CONTEXT_TOKEN_BUDGET: usize = 4000;
+
+/// SSE events emitted by [`ConversationService::process_message_streaming`],
+/// in order: `Intent` once intent analysis finishes, `Chunk` once per piece
+/// of the response as if the provider were streaming it, then `Complete`
+/// with the structured fields a client needs — or `Error` in place of
+/// `Complete` if something failed first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum ConversationStreamEvent {
+    #[serde(rename = "intent")]
+    Intent { session_id: Uuid, intent: MessageIntent, confidence_score: f32 },
+    #[serde(rename = "chunk")]
+    Chunk { session_id: Uuid, content: String },
+    #[serde(rename = "complete")]
+    Complete {
+        session_id: Uuid,
+        turn_id: Uuid,
+        code_changes: Option<Vec<CodeChange>>,
+        suggested_actions: Vec<SuggestedAction>,
+        follow_up_questions: Vec<String>,
+        file_references: Vec<String>,
+        execution_time_ms: u64,
+    },
+    #[serde(rename = "error")]
+    Error { message: String },
+}
+
+/// Result of [`ConversationService::check_change`], shared by
+/// `apply_single_change` and `preview_code_changes`.
+struct ChangeCheck {
+    status: super::ApplyChangeStatus,
+    diff: Option<String>,
+    backup_content: Option<String>,
+}
+
 pub struct ConversationService {
     provider_router: Arc<ProviderRouter>,
+    preferences_service: Arc<PreferencesService>,
     session_manager: SessionManager,
     context_manager: ContextManager,
+    context_assembler: ConversationContextAssembler,
+    prompt_template_engine: PromptTemplateEngine,
     intent_analyzer: IntentAnalyzer,
     code_integration: CodeIntegrationService,
     workspace_analyzer: WorkspaceAnalyzer,
+    redis_cache: Option<Arc<RedisCache>>,
+    session_ttl: Duration,
+    audit_service: Option<Arc<dyn AuditService>>,
 }
 
 impl ConversationService {
     pub fn new(
         provider_router: Arc<ProviderRouter>,
+        preferences_service: Arc<PreferencesService>,
         session_manager: SessionManager,
     ) -> Self {
         Self {
             provider_router: provider_router.clone(),
+            preferences_service,
             session_manager,
             context_manager: ContextManager::new(),
+            context_assembler: ConversationContextAssembler::new(provider_router.clone()),
+            prompt_template_engine: PromptTemplateEngine::new(),
             intent_analyzer: IntentAnalyzer::new(provider_router.clone()),
             code_integration: CodeIntegrationService::new(provider_router.clone()),
             workspace_analyzer: WorkspaceAnalyzer::new(),
+            redis_cache: None,
+            session_ttl: Duration::from_secs(
+                crate::auth::SecurityPolicy::default().session_timeout_minutes as u64 * 60,
+            ),
+            audit_service: None,
+        }
+    }
+
+    /// Enables write-through Redis caching of sessions, keyed
+    /// `session:{user_id}:{session_id}` with a TTL matching
+    /// `SecurityPolicy::session_timeout_minutes`.
+    pub fn with_redis_cache(mut self, redis_cache: Arc<RedisCache>, session_timeout_minutes: u32) -> Self {
+        self.redis_cache = Some(redis_cache);
+        self.session_ttl = Duration::from_secs(session_timeout_minutes as u64 * 60);
+        self
+    }
+
+    /// Emits one `AuditEvent` per call into `create_session`, `delete_session`,
+    /// and `get_session`, so session lifecycle/access is traceable. No-op
+    /// when not set.
+    pub fn with_audit_service(mut self, audit_service: Arc<dyn AuditService>) -> Self {
+        self.audit_service = Some(audit_service);
+        self
+    }
+
+    fn audit_event(&self, user_id: Uuid, action: &str, resource_id: Uuid, outcome: AuditOutcome) -> AuditEvent {
+        AuditEvent {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type: AuditEventType::DataAccess,
+            user_id: Some(user_id),
+            session_id: Some(resource_id),
+            organization_id: Uuid::new_v4(), // Would be retrieved from user context
+            ip_address: "unknown".to_string(),
+            user_agent: "unknown".to_string(),
+            resource_type: Some("conversation_session".to_string()),
+            resource_id: Some(resource_id.to_string()),
+            action: action.to_string(),
+            outcome,
+            details: std::collections::HashMap::new(),
+            risk_score: None,
+        }
+    }
+
+    async fn cache_session(&self, session: &ConversationSession) {
+        let Some(cache) = &self.redis_cache else { return };
+        let key = session_cache_key(session.user_id, session.id);
+        if let Err(e) = cache.set(&key, session, Some(self.session_ttl)).await {
+            warn!("Failed to write session {} through to Redis: {}", session.id, e);
+        }
+    }
+
+    async fn invalidate_cached_session(&self, user_id: Uuid, session_id: Uuid) {
+        let Some(cache) = &self.redis_cache else { return };
+        let key = session_cache_key(user_id, session_id);
+        if let Err(e) = cache.delete(&key).await {
+            warn!("Failed to invalidate cached session {}: {}", session_id, e);
         }
     }
 
@@ -49,6 +166,12 @@ impl ConversationService {
 
         let mut session = ConversationSession::new(user_id, workspace_path.clone());
 
+        // Yeni session'ı kullanıcının kaydedilmiş explanation_level'ı ile başlat
+        // (PUT /preferences üzerinden güncellenebilir), aksi halde varsayılanı koru.
+        if let Ok(prefs) = self.preferences_service.get_user_preferences(user_id).await {
+            session.session_metadata.preferences.explanation_level = Self::to_session_explanation_level(prefs.explanation_level);
+        }
+
         // Workspace'i analiz et
         if let Some(path) = &workspace_path {
             match self.workspace_analyzer.analyze_workspace(path).await {
@@ -64,12 +187,585 @@ impl ConversationService {
 
         // Veritabanına kaydet
         self.session_manager.create_session(&session).await?;
+        self.cache_session(&session).await;
+
+        if let Some(audit_service) = &self.audit_service {
+            let event = self.audit_event(user_id, "create_session", session.id, AuditOutcome::Success);
+            audit_service.log_event(event).await?;
+        }
 
         Ok(session)
     }
 
+    /// `auth::preferences::ExplanationLevel` and `conversation::ExplanationLevel`
+    /// are distinct types (the `conversation` module isn't available to `auth`,
+    /// which is also compiled into the library target) but always have the
+    /// same variants in the same order, so this mapping can't fail.
+    fn to_session_explanation_level(level: crate::auth::preferences::ExplanationLevel) -> ExplanationLevel {
+        use crate::auth::preferences::ExplanationLevel as PersistedLevel;
+        match level {
+            PersistedLevel::Beginner => ExplanationLevel::Beginner,
+            PersistedLevel::Intermediate => ExplanationLevel::Intermediate,
+            PersistedLevel::Advanced => ExplanationLevel::Advanced,
+            PersistedLevel::Expert => ExplanationLevel::Expert,
+        }
+    }
+
+    /// Looks up a session in Redis first, falling back to Postgres on a
+    /// cache miss (and re-populating Redis so the next lookup is a hit).
     pub async fn get_session(&self, session_id: Uuid) -> Result<Option<ConversationSession>> {
-        self.session_manager.get_session(session_id).await
+        if let Some(cache) = &self.redis_cache {
+            if let Ok(Some(key)) = cache.find_session_key(session_id).await {
+                match cache.get::<ConversationSession>(&key).await {
+                    Ok(Some(session)) => return Ok(Some(session)),
+                    Ok(None) => {}
+                    Err(e) => warn!("Redis session lookup failed for {}: {}", session_id, e),
+                }
+            }
+        }
+
+        let session = self.session_manager.get_session(session_id).await?;
+        if let Some(session) = &session {
+            self.cache_session(session).await;
+            if let Some(audit_service) = &self.audit_service {
+                let event = self.audit_event(session.user_id, "get_session", session_id, AuditOutcome::Success);
+                log_event_fire_and_forget(audit_service.clone(), event);
+            }
+        }
+        Ok(session)
+    }
+
+    /// Applies a subset of a session's previously-generated [`super::CodeChange`]s
+    /// to disk by id. A `Modify`/`Delete` change is only carried out if its
+    /// `old_content` still matches what's on disk, so an edit made since the
+    /// change was generated surfaces as a conflict instead of being silently
+    /// overwritten. Each write is atomic (write-to-temp-then-rename), and
+    /// every result is recorded onto the turn that introduced the change so
+    /// a later rollback request can restore it.
+    pub async fn apply_code_changes(
+        &self,
+        session_id: Uuid,
+        change_ids: &[Uuid],
+    ) -> Result<Vec<super::AppliedChangeResult>> {
+        let mut session = self
+            .session_manager
+            .get_session(session_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+        let changes = Self::collect_session_changes(&session);
+        let change_set_id = Uuid::new_v4();
+
+        let mut results = Vec::with_capacity(change_ids.len());
+        for change_id in change_ids {
+            let result = match changes.get(change_id) {
+                Some(change) => Self::apply_single_change(change, change_set_id).await,
+                None => super::AppliedChangeResult {
+                    change_id: *change_id,
+                    file_path: String::new(),
+                    status: super::ApplyChangeStatus::NotFound,
+                    message: None,
+                    diff: None,
+                    backup_content: None,
+                    change_set_id: None,
+                    applied_content_hash: None,
+                    rolled_back: false,
+                },
+            };
+            results.push(result);
+        }
+
+        for turn in &mut session.conversation_history {
+            let Some(turn_changes) = &turn.code_changes else { continue };
+            let applied_here: Vec<super::AppliedChangeResult> = results
+                .iter()
+                .filter(|r| turn_changes.iter().any(|c| c.id == r.change_id))
+                .cloned()
+                .collect();
+            if applied_here.is_empty() {
+                continue;
+            }
+            turn.applied_changes.extend(applied_here);
+            self.session_manager
+                .record_applied_changes(turn.id, &turn.applied_changes)
+                .await?;
+        }
+
+        Ok(results)
+    }
+
+    /// Session holding `change_set_id`, if any — lets a caller load and
+    /// ownership-check the session before calling `rollback_change_set`.
+    pub async fn session_id_for_change_set(&self, change_set_id: Uuid) -> Result<Option<Uuid>> {
+        self.session_manager.find_session_by_change_set(change_set_id).await
+    }
+
+    /// Restores every `Applied` result in `change_set_id` to its pre-change
+    /// state. Each file is first checked against the hash of the content
+    /// its change left on disk (or, for a `Delete`, that the file is still
+    /// absent) — a mismatch means something else touched the file since, so
+    /// that one result is left in place and reported with an error message
+    /// instead of being rolled back. Rejects the call outright if the set
+    /// has already been rolled back once.
+    pub async fn rollback_change_set(
+        &self,
+        change_set_id: Uuid,
+    ) -> Result<Vec<super::AppliedChangeResult>> {
+        let session_id = self
+            .session_manager
+            .find_session_by_change_set(change_set_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Change set not found: {}", change_set_id))?;
+
+        let mut session = self
+            .session_manager
+            .get_session(session_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+        let turn_idx = session
+            .conversation_history
+            .iter()
+            .position(|turn| turn.applied_changes.iter().any(|r| r.change_set_id == Some(change_set_id)))
+            .ok_or_else(|| anyhow::anyhow!("Change set not found: {}", change_set_id))?;
+
+        if session.conversation_history[turn_idx]
+            .applied_changes
+            .iter()
+            .any(|r| r.change_set_id == Some(change_set_id) && r.rolled_back)
+        {
+            return Err(anyhow::anyhow!(
+                "Change set {} has already been rolled back",
+                change_set_id
+            ));
+        }
+
+        let mut rolled_back = Vec::new();
+        for result in session.conversation_history[turn_idx].applied_changes.iter_mut() {
+            if result.change_set_id != Some(change_set_id) {
+                continue;
+            }
+            match Self::rollback_single_change(result).await {
+                Ok(()) => {
+                    result.status = super::ApplyChangeStatus::RolledBack;
+                    result.rolled_back = true;
+                    result.message = None;
+                }
+                Err(e) => {
+                    result.message = Some(e.to_string());
+                }
+            }
+            rolled_back.push(result.clone());
+        }
+
+        self.session_manager
+            .record_applied_changes(
+                session.conversation_history[turn_idx].id,
+                &session.conversation_history[turn_idx].applied_changes,
+            )
+            .await?;
+
+        if let Some(audit_service) = &self.audit_service {
+            let event = self.audit_event(session.user_id, "rollback_change_set", session.id, AuditOutcome::Success);
+            audit_service.log_event(event).await?;
+        }
+
+        Ok(rolled_back)
+    }
+
+    /// Verifies `result`'s file is still in the state its apply left it in,
+    /// then restores `backup_content` (or, if `None`, removes the file —
+    /// undoing a `Create`).
+    async fn rollback_single_change(result: &super::AppliedChangeResult) -> Result<()> {
+        let path = std::path::Path::new(&result.file_path);
+
+        match &result.applied_content_hash {
+            Some(expected_hash) => {
+                let current = tokio::fs::read_to_string(path).await.map_err(|e| {
+                    anyhow::anyhow!("Cannot read {} to verify before rollback: {}", result.file_path, e)
+                })?;
+                if Self::content_hash(&current) != *expected_hash {
+                    return Err(anyhow::anyhow!(
+                        "{} was modified since this change was applied; refusing to roll back",
+                        result.file_path
+                    ));
+                }
+            }
+            None => {
+                if tokio::fs::metadata(path).await.is_ok() {
+                    return Err(anyhow::anyhow!(
+                        "{} was recreated since it was deleted; refusing to roll back",
+                        result.file_path
+                    ));
+                }
+            }
+        }
+
+        match &result.backup_content {
+            Some(backup) => Self::write_file_atomically(path, backup).await,
+            None => tokio::fs::remove_file(path).await.map_err(Into::into),
+        }
+    }
+
+    /// Every applied or rolled-back change across `session`'s turns, most
+    /// recent turn first, for a change/rollback history view.
+    pub async fn get_change_history(&self, session_id: Uuid) -> Result<Vec<super::AppliedChangeResult>> {
+        let session = self
+            .session_manager
+            .get_session(session_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+        Ok(session
+            .conversation_history
+            .iter()
+            .rev()
+            .flat_map(|turn| turn.applied_changes.iter().cloned())
+            .collect())
+    }
+
+    /// Like [`Self::apply_code_changes`] but never writes to disk — reports
+    /// what would happen (conflict or not) and the diff, so a client can
+    /// show a confirmation dialog before calling `apply_code_changes` for
+    /// real.
+    pub async fn preview_code_changes(
+        &self,
+        session_id: Uuid,
+        change_ids: &[Uuid],
+    ) -> Result<Vec<super::AppliedChangeResult>> {
+        let session = self
+            .session_manager
+            .get_session(session_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+        let changes = Self::collect_session_changes(&session);
+
+        let mut results = Vec::with_capacity(change_ids.len());
+        for change_id in change_ids {
+            let result = match changes.get(change_id) {
+                Some(change) => match Self::check_change(change).await {
+                    Ok(check) => super::AppliedChangeResult {
+                        change_id: *change_id,
+                        file_path: change.file_path.clone(),
+                        status: check.status,
+                        message: None,
+                        diff: check.diff,
+                        backup_content: None,
+                        change_set_id: None,
+                        applied_content_hash: None,
+                        rolled_back: false,
+                    },
+                    Err(e) => super::AppliedChangeResult {
+                        change_id: *change_id,
+                        file_path: change.file_path.clone(),
+                        status: super::ApplyChangeStatus::Error,
+                        message: Some(e.to_string()),
+                        diff: None,
+                        backup_content: None,
+                        change_set_id: None,
+                        applied_content_hash: None,
+                        rolled_back: false,
+                    },
+                },
+                None => super::AppliedChangeResult {
+                    change_id: *change_id,
+                    file_path: String::new(),
+                    status: super::ApplyChangeStatus::NotFound,
+                    message: None,
+                    diff: None,
+                    backup_content: None,
+                    change_set_id: None,
+                    applied_content_hash: None,
+                    rolled_back: false,
+                },
+            };
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Every `CodeChange` ever generated in `session`, indexed by id,
+    /// regardless of which turn introduced it.
+    fn collect_session_changes(session: &ConversationSession) -> std::collections::HashMap<Uuid, super::CodeChange> {
+        session
+            .conversation_history
+            .iter()
+            .filter_map(|turn| turn.code_changes.as_ref())
+            .flatten()
+            .map(|change| (change.id, change.clone()))
+            .collect()
+    }
+
+    async fn apply_single_change(change: &super::CodeChange, change_set_id: Uuid) -> super::AppliedChangeResult {
+        use super::{ApplyChangeStatus, ChangeType};
+
+        let check = match Self::check_change(change).await {
+            Ok(check) => check,
+            Err(e) => {
+                return super::AppliedChangeResult {
+                    change_id: change.id,
+                    file_path: change.file_path.clone(),
+                    status: ApplyChangeStatus::Error,
+                    message: Some(e.to_string()),
+                    diff: None,
+                    backup_content: None,
+                    change_set_id: None,
+                    applied_content_hash: None,
+                    rolled_back: false,
+                };
+            }
+        };
+
+        if check.status != ApplyChangeStatus::WouldApply {
+            return super::AppliedChangeResult {
+                change_id: change.id,
+                file_path: change.file_path.clone(),
+                status: check.status,
+                message: None,
+                diff: check.diff,
+                backup_content: None,
+                change_set_id: None,
+                applied_content_hash: None,
+                rolled_back: false,
+            };
+        }
+
+        let path = std::path::Path::new(&change.file_path);
+        let write_result: Result<()> = async {
+            match change.change_type {
+                ChangeType::Create => {
+                    if let Some(parent) = path.parent() {
+                        tokio::fs::create_dir_all(parent).await?;
+                    }
+                    Self::write_file_atomically(path, &change.new_content).await
+                }
+                ChangeType::Modify => Self::write_file_atomically(path, &change.new_content).await,
+                ChangeType::Delete => tokio::fs::remove_file(path).await.map_err(Into::into),
+                ChangeType::Rename | ChangeType::Move => {
+                    unreachable!("Rename/Move is reported Unsupported by check_change")
+                }
+            }
+        }
+        .await;
+
+        match write_result {
+            Ok(()) => super::AppliedChangeResult {
+                change_id: change.id,
+                file_path: change.file_path.clone(),
+                status: ApplyChangeStatus::Applied,
+                message: None,
+                diff: check.diff,
+                backup_content: check.backup_content,
+                change_set_id: Some(change_set_id),
+                // `Delete` leaves no content behind to hash; a later
+                // rollback instead checks that the file is still absent.
+                applied_content_hash: (change.change_type != ChangeType::Delete)
+                    .then(|| Self::content_hash(&change.new_content)),
+                rolled_back: false,
+            },
+            Err(e) => super::AppliedChangeResult {
+                change_id: change.id,
+                file_path: change.file_path.clone(),
+                status: ApplyChangeStatus::Error,
+                message: Some(e.to_string()),
+                diff: None,
+                backup_content: None,
+                change_set_id: None,
+                applied_content_hash: None,
+                rolled_back: false,
+            },
+        }
+    }
+
+    /// Derives a short human-readable title from a session's first user
+    /// message. Deliberately a plain truncation rather than an LLM call, so
+    /// title generation never costs a provider round-trip and stays
+    /// deterministic for tests.
+    fn generate_session_title(message: &str) -> String {
+        const TITLE_MAX_CHARS: usize = 60;
+
+        let first_line = message.lines().next().unwrap_or("").trim();
+        if first_line.is_empty() {
+            return "New conversation".to_string();
+        }
+
+        if first_line.chars().count() <= TITLE_MAX_CHARS {
+            first_line.to_string()
+        } else {
+            let truncated: String = first_line.chars().take(TITLE_MAX_CHARS).collect();
+            format!("{truncated}...")
+        }
+    }
+
+    /// Hex-encoded SHA-256 of `content`, used to detect whether a file has
+    /// changed since one of its changes was applied.
+    fn content_hash(content: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(content.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Reads the target file (if any) and reports whether `change` could be
+    /// applied cleanly, without writing anything. Shared by
+    /// `apply_single_change` (which then writes) and `preview_code_changes`.
+    async fn check_change(change: &super::CodeChange) -> Result<ChangeCheck> {
+        use super::{ApplyChangeStatus, ChangeType};
+
+        let path = std::path::Path::new(&change.file_path);
+        match change.change_type {
+            ChangeType::Create => {
+                if tokio::fs::metadata(path).await.is_ok() {
+                    return Ok(ChangeCheck { status: ApplyChangeStatus::Conflict, diff: None, backup_content: None });
+                }
+                Ok(ChangeCheck {
+                    status: ApplyChangeStatus::WouldApply,
+                    diff: Some(super::diff::unified_diff(&change.file_path, "", &change.new_content)),
+                    backup_content: None,
+                })
+            }
+            ChangeType::Modify | ChangeType::Delete => {
+                let current = tokio::fs::read_to_string(path).await?;
+                if let Some(expected) = &change.old_content {
+                    if expected != &current {
+                        return Ok(ChangeCheck { status: ApplyChangeStatus::Conflict, diff: None, backup_content: None });
+                    }
+                }
+                let new_content = if change.change_type == ChangeType::Delete { "" } else { change.new_content.as_str() };
+                Ok(ChangeCheck {
+                    status: ApplyChangeStatus::WouldApply,
+                    diff: Some(super::diff::unified_diff(&change.file_path, &current, new_content)),
+                    backup_content: Some(current),
+                })
+            }
+            // `CodeChange` has no destination path field, so a rename/move
+            // can't be carried out from the data we have.
+            ChangeType::Rename | ChangeType::Move => {
+                Ok(ChangeCheck { status: ApplyChangeStatus::Unsupported, diff: None, backup_content: None })
+            }
+        }
+    }
+
+    /// Writes `content` to `path` via write-to-temp-then-rename, so a crash
+    /// mid-write can't leave a half-written file behind. The file's
+    /// pre-image is kept as `AppliedChangeResult::backup_content` instead of
+    /// a sibling `.bak` file, since that's what a rollback request needs.
+    async fn write_file_atomically(path: &std::path::Path, content: &str) -> Result<()> {
+        let tmp_name = format!(
+            ".{}.apply-{}.tmp",
+            path.file_name().and_then(|n| n.to_str()).unwrap_or("change"),
+            Uuid::new_v4()
+        );
+        let tmp_path = path.with_file_name(tmp_name);
+        tokio::fs::write(&tmp_path, content).await?;
+        tokio::fs::rename(&tmp_path, path).await?;
+        Ok(())
+    }
+
+    /// Forks a session at `at_turn_id`, copying its metadata and history up
+    /// to and including that turn into a new session linked back via
+    /// `parent_session_id`/`forked_from_turn_id` and assigned a fresh
+    /// `branch_id`. Lets users branch off an earlier point in a
+    /// conversation without polluting the original thread. Deleting the
+    /// parent session afterwards leaves the fork intact -- both foreign
+    /// keys are `ON DELETE SET NULL` (see migration 027).
+    pub async fn fork_session(
+        &self,
+        session_id: Uuid,
+        at_turn_id: Uuid,
+    ) -> Result<ConversationSession> {
+        let session = self.session_manager.get_session(session_id).await?
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+        let turn_index = session.conversation_history.iter()
+            .position(|turn| turn.id == at_turn_id)
+            .ok_or_else(|| anyhow::anyhow!("Turn not found in session {}: {}", session_id, at_turn_id))?;
+
+        // Turn ids are unique across every session, not just within one, so
+        // copied turns need fresh ids of their own; `parent_turn_id` keeps
+        // each copy's lineage back to the original it was forked from.
+        let copied_history: Vec<ConversationTurn> = session.conversation_history[..=turn_index]
+            .iter()
+            .map(|turn| ConversationTurn {
+                id: Uuid::new_v4(),
+                parent_turn_id: Some(turn.id),
+                ..turn.clone()
+            })
+            .collect();
+
+        let forked = ConversationSession {
+            id: Uuid::new_v4(),
+            user_id: session.user_id,
+            workspace_context: session.workspace_context.clone(),
+            conversation_history: copied_history,
+            active_files: session.active_files.clone(),
+            code_context: session.code_context.clone(),
+            session_metadata: session.session_metadata.clone(),
+            title: session.title.clone(),
+            parent_session_id: Some(session.id),
+            forked_from_turn_id: Some(at_turn_id),
+            branch_id: Uuid::new_v4(),
+            rolling_summary: session.rolling_summary.clone(),
+            // The fork may have truncated history shorter than what the
+            // parent's summary covered; clamp so the assembler never treats
+            // turns beyond the fork's own history as already summarized.
+            rolling_summary_turns_covered: session.rolling_summary_turns_covered.min(turn_index + 1),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        self.session_manager.create_session(&forked).await?;
+        for turn in &forked.conversation_history {
+            self.session_manager.add_conversation_turn(forked.id, turn).await?;
+        }
+
+        info!("Forked conversation session {} from {} at turn {}", forked.id, session_id, at_turn_id);
+        Ok(forked)
+    }
+
+    /// Lists the sessions directly forked from `session_id`, newest first.
+    pub async fn list_branches(&self, session_id: Uuid) -> Result<Vec<ConversationSession>> {
+        self.session_manager.get_child_sessions(session_id).await
+    }
+
+    /// Sets this session's provider/model override, consulted by
+    /// `resolve_completion_defaults` ahead of the user's saved preferences.
+    /// A field left `None` in the request leaves that override unchanged,
+    /// matching `auth::patch_profile_handler`'s partial-update semantics.
+    pub async fn update_session_settings(
+        &self,
+        session_id: Uuid,
+        provider: Option<String>,
+        model: Option<String>,
+    ) -> Result<ConversationSession> {
+        let mut session = self.session_manager.get_session(session_id).await?
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+        if provider.is_some() {
+            session.session_metadata.provider = provider;
+        }
+        if model.is_some() {
+            session.session_metadata.model = model;
+        }
+        session.updated_at = Utc::now();
+
+        self.session_manager.update_session(&session).await?;
+        self.cache_session(&session).await;
+        Ok(session)
+    }
+
+    /// Renders a session as a downloadable transcript, chunked so a handler
+    /// can stream it rather than buffering the whole thing in memory.
+    pub async fn export_session(
+        &self,
+        session_id: Uuid,
+        format: ExportFormat,
+    ) -> Result<Vec<String>> {
+        let session = self.session_manager.get_session(session_id).await?
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+        Ok(session_export::render(&session, format))
     }
 
     pub async fn process_message(&self, request: ConversationRequest) -> Result<ConversationResponse> {
@@ -78,13 +774,21 @@ impl ConversationService {
         info!("Processing message: {}", request.message);
 
         // Session'ı al veya oluştur
-        let mut session = if let Some(session_id) = request.session_id {
+        let mut session = if let Some(branch_turn_id) = request.branch_from_turn_id {
+            let session_id = request.session_id
+                .ok_or_else(|| anyhow::anyhow!("Session ID is required to branch"))?;
+            self.fork_session(session_id, branch_turn_id).await?
+        } else if let Some(session_id) = request.session_id {
             self.session_manager.get_session(session_id).await?
                 .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?
         } else {
             return Err(anyhow::anyhow!("Session ID is required"));
         };
 
+        // `context_files` is consumed by `update_code_context` below; attaching it to the
+        // prompt happens separately once the session's model is known.
+        let context_files = request.context_files.clone();
+
         // Context'i güncelle
         self.context_manager.update_code_context(
             &mut session,
@@ -100,9 +804,17 @@ impl ConversationService {
             ).await?;
         }
 
+        let (provider, model, _) = self.resolve_completion_defaults(&session).await;
+        session.code_context.attached_files = self.attach_context_files(
+            &context_files,
+            &session.workspace_context.root_path,
+            &request.message,
+            &model,
+        ).await;
+
         // Intent analizi
-        let (intent, confidence) = if let Some(hint) = request.intent_hint {
-            (hint, 0.9) // Hint verilmişse yüksek güven
+        let intent_candidates = if let Some(hint) = request.intent_hint {
+            vec![(hint, 0.9)] // Hint verilmişse yüksek güven
         } else {
             self.intent_analyzer.analyze_intent(
                 &request.message,
@@ -112,6 +824,7 @@ impl ConversationService {
             ).await?
         };
 
+        let (intent, confidence) = intent_candidates[0].clone();
         debug!("Detected intent: {:?} (confidence: {})", intent, confidence);
 
         // İlgili bağlamı topla
@@ -139,33 +852,355 @@ impl ConversationService {
             files_referenced: response.file_references.clone(),
             confidence_score: confidence,
             execution_time_ms: start_time.elapsed().as_millis() as u64,
+            parent_turn_id: request.branch_from_turn_id,
+            applied_changes: Vec::new(),
+            provider: Some(provider.clone()),
+            model: Some(model.clone()),
+            revisions: Vec::new(),
             timestamp: Utc::now(),
         };
 
+        let assembled_context = self.context_assembler.assemble(
+            &mut session,
+            &request.message,
+            &model,
+            DEFAULT_CONTEXT_TOKEN_BUDGET,
+        ).await?;
+
+        // First turn of the session: derive an automatic title before it's added below.
+        if session.title.is_none() && session.conversation_history.is_empty() {
+            session.title = Some(Self::generate_session_title(&request.message));
+        }
+
         // Session'ı güncelle
         session.add_turn(turn.clone());
         self.session_manager.add_conversation_turn(session.id, &turn).await?;
         self.session_manager.update_session(&session).await?;
+        self.cache_session(&session).await;
 
         // Önerilen aksiyonları ekle
         let suggested_actions = self.generate_suggested_actions(&intent, &response, &session).await?;
 
-        // Follow-up sorularını oluştur
-        let follow_up_questions = self.intent_analyzer.get_intent_suggestions(&intent);
+        // Follow-up sorularını oluştur — eğer intent'ten yeterince emin değilsek
+        // tahmine dayalı öneriler yerine açıklayıcı sorular soralım.
+        let follow_up_questions = if self.intent_analyzer.is_low_confidence(confidence) {
+            self.intent_analyzer.get_clarifying_questions()
+        } else {
+            self.intent_analyzer.get_intent_suggestions(&intent)
+        };
 
         Ok(ConversationResponse {
             session_id: session.id,
             ai_response: response.ai_response,
             intent,
             confidence_score: confidence,
+            intent_candidates: intent_candidates.into_iter().take(3).collect(),
             code_changes: response.code_changes,
             suggested_actions,
             file_references: response.file_references,
             follow_up_questions,
             execution_time_ms: start_time.elapsed().as_millis() as u64,
+            context_turns: assembled_context.context_turns,
+            context_summary: assembled_context.context_summary,
+            context_tokens_used: assembled_context.tokens_used,
+            provider,
+            model,
+            attached_files: session.code_context.attached_files.clone(),
         })
     }
 
+    /// Re-runs the provider call behind a session's most recent turn with
+    /// optional `overrides` (temperature, model, an extra instruction
+    /// appended to the original message), replacing its `ai_response` --
+    /// `code_changes` is cleared, since a plain regenerate re-asks the
+    /// provider rather than re-running the per-intent code-generation
+    /// pipeline `process_message` used originally. The response it
+    /// overwrites is kept, newest first, in `ConversationTurn::revisions`.
+    ///
+    /// Only the session's last turn may be regenerated -- an older one
+    /// would leave the turns after it answering a question that no longer
+    /// matches what's above it -- so this returns an error (mapped to 409
+    /// by `main.rs`) for anything else.
+    pub async fn regenerate_turn(
+        &self,
+        session_id: Uuid,
+        turn_id: Uuid,
+        overrides: RegenerateTurnOverrides,
+    ) -> Result<ConversationResponse> {
+        let start_time = Instant::now();
+
+        let mut session = self.session_manager.get_session(session_id).await?
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+        let turn_index = session.conversation_history.iter()
+            .position(|turn| turn.id == turn_id)
+            .ok_or_else(|| anyhow::anyhow!("Turn not found in session {}: {}", session_id, turn_id))?;
+
+        if turn_index + 1 != session.conversation_history.len() {
+            return Err(anyhow::anyhow!("Only the most recent turn in a session may be regenerated"));
+        }
+
+        let original_turn = session.conversation_history[turn_index].clone();
+
+        // Resolve provider/model the same way `process_message` does, but let
+        // an explicit override win over the session's saved model.
+        let mut defaults_session = session.clone();
+        if let Some(model) = &overrides.model {
+            defaults_session.session_metadata.model = Some(model.clone());
+        }
+        let (provider, model, default_temperature) = self.resolve_completion_defaults(&defaults_session).await;
+        let temperature = overrides.temperature.unwrap_or(default_temperature as f32);
+
+        let assembled_context = self.context_assembler.assemble(
+            &mut session,
+            &original_turn.user_message,
+            &model,
+            DEFAULT_CONTEXT_TOKEN_BUDGET,
+        ).await?;
+
+        let mut prompt = original_turn.user_message.clone();
+        if let Some(extra) = &overrides.extra_instruction {
+            prompt.push_str("\n\nAdditional instruction: ");
+            prompt.push_str(extra);
+        }
+
+        let system_prompt = self.prompt_template_engine.system_prompt(
+            "general",
+            session.session_metadata.preferences.explanation_level,
+        );
+
+        let completion_request = crate::providers::CompletionRequest {
+            prompt,
+            model: Some(model.clone()),
+            provider: Some(provider.clone()),
+            max_tokens: Some(2000),
+            temperature: Some(temperature),
+            system_prompt: Some(system_prompt),
+            ..Default::default()
+        };
+
+        let completion = self.provider_router.complete(completion_request).await?;
+
+        let revision = TurnRevision {
+            ai_response: original_turn.ai_response.clone(),
+            code_changes: original_turn.code_changes.clone(),
+            provider: original_turn.provider.clone(),
+            model: original_turn.model.clone(),
+            replaced_at: Utc::now(),
+        };
+
+        let turn = &mut session.conversation_history[turn_index];
+        turn.ai_response = completion.text.clone();
+        turn.code_changes = None;
+        turn.provider = Some(provider.clone());
+        turn.model = Some(model.clone());
+        turn.execution_time_ms = start_time.elapsed().as_millis() as u64;
+        turn.revisions.insert(0, revision);
+        let turn = turn.clone();
+
+        self.session_manager.record_regeneration(
+            turn.id,
+            &turn.ai_response,
+            &turn.code_changes,
+            &provider,
+            &model,
+            &turn.revisions,
+        ).await?;
+        self.session_manager.update_session(&session).await?;
+        self.cache_session(&session).await;
+
+        Ok(ConversationResponse {
+            session_id: session.id,
+            ai_response: turn.ai_response.clone(),
+            intent: turn.intent.clone(),
+            confidence_score: turn.confidence_score,
+            intent_candidates: vec![(turn.intent.clone(), turn.confidence_score)],
+            code_changes: turn.code_changes.clone(),
+            suggested_actions: Vec::new(),
+            file_references: turn.files_referenced.clone(),
+            follow_up_questions: self.intent_analyzer.get_intent_suggestions(&turn.intent),
+            execution_time_ms: start_time.elapsed().as_millis() as u64,
+            context_turns: assembled_context.context_turns,
+            context_summary: assembled_context.context_summary,
+            context_tokens_used: assembled_context.tokens_used,
+            provider,
+            model,
+            attached_files: session.code_context.attached_files.clone(),
+        })
+    }
+
+    /// Like [`Self::process_message`], but reports progress over `tx` as SSE
+    /// events instead of waiting for the full response. No provider in this
+    /// codebase streams tokens back to `generate_response`'s callers, so
+    /// `chunk` events are the finished `ai_response` split into pieces and
+    /// sent with a short delay between each — the same simulated-streaming
+    /// approach `streaming::StreamingManager::stream_from_provider` already
+    /// uses for `/completion/stream`. The turn is persisted exactly once, at
+    /// the same point `process_message` persists it.
+    pub async fn process_message_streaming(
+        &self,
+        request: ConversationRequest,
+        tx: mpsc::Sender<Result<Event, Infallible>>,
+        cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        if let Err(e) = self.process_message_streaming_inner(request, &tx, &cancellation_token).await {
+            Self::send_stream_event(&tx, "conversation", &ConversationStreamEvent::Error {
+                message: e.to_string(),
+            })
+            .await?;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    async fn process_message_streaming_inner(
+        &self,
+        request: ConversationRequest,
+        tx: &mpsc::Sender<Result<Event, Infallible>>,
+        cancellation_token: &CancellationToken,
+    ) -> Result<()> {
+        let start_time = Instant::now();
+
+        let mut session = if let Some(branch_turn_id) = request.branch_from_turn_id {
+            let session_id = request.session_id
+                .ok_or_else(|| anyhow::anyhow!("Session ID is required to branch"))?;
+            self.fork_session(session_id, branch_turn_id).await?
+        } else if let Some(session_id) = request.session_id {
+            self.session_manager.get_session(session_id).await?
+                .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?
+        } else {
+            return Err(anyhow::anyhow!("Session ID is required"));
+        };
+
+        let context_files = request.context_files.clone();
+
+        self.context_manager.update_code_context(
+            &mut session,
+            request.current_file.as_deref(),
+            request.selected_text,
+            request.context_files,
+        ).await?;
+
+        if let Some(workspace_path) = &request.workspace_path {
+            self.context_manager.update_workspace_context(
+                &mut session,
+                Some(workspace_path),
+            ).await?;
+        }
+
+        let (streaming_provider, streaming_model, _) = self.resolve_completion_defaults(&session).await;
+        session.code_context.attached_files = self.attach_context_files(
+            &context_files,
+            &session.workspace_context.root_path,
+            &request.message,
+            &streaming_model,
+        ).await;
+
+        let intent_candidates = if let Some(hint) = request.intent_hint {
+            vec![(hint, 0.9)]
+        } else {
+            self.intent_analyzer.analyze_intent(
+                &request.message,
+                &session.workspace_context,
+                &session.code_context,
+                &session.conversation_history,
+            ).await?
+        };
+
+        let (intent, confidence) = intent_candidates[0].clone();
+
+        Self::send_stream_event(tx, &session.id.to_string(), &ConversationStreamEvent::Intent {
+            session_id: session.id,
+            intent: intent.clone(),
+            confidence_score: confidence,
+        }).await?;
+
+        let relevant_context = self.context_manager.get_relevant_context(
+            &session,
+            &request.message,
+            &intent,
+        ).await?;
+
+        let response = self.generate_response(
+            &request.message,
+            &intent,
+            &session,
+            &relevant_context,
+        ).await?;
+
+        let turn = ConversationTurn {
+            id: Uuid::new_v4(),
+            user_message: request.message.clone(),
+            ai_response: response.ai_response.clone(),
+            intent: intent.clone(),
+            code_changes: response.code_changes.clone(),
+            files_referenced: response.file_references.clone(),
+            confidence_score: confidence,
+            execution_time_ms: start_time.elapsed().as_millis() as u64,
+            parent_turn_id: request.branch_from_turn_id,
+            applied_changes: Vec::new(),
+            provider: Some(streaming_provider),
+            model: Some(streaming_model),
+            revisions: Vec::new(),
+            timestamp: Utc::now(),
+        };
+
+        if session.title.is_none() && session.conversation_history.is_empty() {
+            session.title = Some(Self::generate_session_title(&request.message));
+        }
+
+        session.add_turn(turn.clone());
+        self.session_manager.add_conversation_turn(session.id, &turn).await?;
+        self.session_manager.update_session(&session).await?;
+        self.cache_session(&session).await;
+
+        for word in response.ai_response.split_inclusive(' ') {
+            if cancellation_token.is_cancelled() {
+                return Err(anyhow::anyhow!("Stream cancelled by server shutdown"));
+            }
+            Self::send_stream_event(tx, &session.id.to_string(), &ConversationStreamEvent::Chunk {
+                session_id: session.id,
+                content: word.to_string(),
+            }).await?;
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        let suggested_actions = self.generate_suggested_actions(&intent, &response, &session).await?;
+        let follow_up_questions = if self.intent_analyzer.is_low_confidence(confidence) {
+            self.intent_analyzer.get_clarifying_questions()
+        } else {
+            self.intent_analyzer.get_intent_suggestions(&intent)
+        };
+
+        Self::send_stream_event(tx, &session.id.to_string(), &ConversationStreamEvent::Complete {
+            session_id: session.id,
+            turn_id: turn.id,
+            code_changes: response.code_changes,
+            suggested_actions,
+            follow_up_questions,
+            file_references: response.file_references,
+            execution_time_ms: start_time.elapsed().as_millis() as u64,
+        }).await?;
+
+        Ok(())
+    }
+
+    async fn send_stream_event(
+        tx: &mpsc::Sender<Result<Event, Infallible>>,
+        id: &str,
+        event: &ConversationStreamEvent,
+    ) -> Result<()> {
+        let event_name = match event {
+            ConversationStreamEvent::Intent { .. } => "intent",
+            ConversationStreamEvent::Chunk { .. } => "chunk",
+            ConversationStreamEvent::Complete { .. } => "complete",
+            ConversationStreamEvent::Error { .. } => "error",
+        };
+        let sse_event = crate::streaming::encode_sse_event(id, event_name, event)?;
+        tx.send(Ok(sse_event)).await.map_err(|_| anyhow::anyhow!("Failed to send SSE event"))?;
+        Ok(())
+    }
+
     async fn generate_response(
         &self,
         message: &str,
@@ -269,6 +1304,7 @@ impl ConversationService {
             &code_to_explain,
             session.code_context.current_file.as_deref(),
             &session.workspace_context,
+            &session.code_context.attached_files,
         ).await?;
 
         Ok(InternalResponse {
@@ -302,6 +1338,7 @@ impl ConversationService {
             &code_to_review,
             session.code_context.current_file.as_deref(),
             &session.workspace_context,
+            &session.code_context.attached_files,
         ).await?;
 
         let response = format!(
@@ -338,6 +1375,7 @@ impl ConversationService {
             message, // Hata mesajı olarak kullan
             session.code_context.current_file.as_deref(),
             &session.workspace_context,
+            &session.code_context.attached_files,
         ).await?;
 
         let response = format!(
@@ -349,6 +1387,7 @@ impl ConversationService {
         Ok(InternalResponse {
             ai_response: response,
             code_changes: Some(vec![super::CodeChange {
+                id: Uuid::new_v4(),
                 file_path: session.code_context.current_file.clone().unwrap_or_else(|| "fixed_code.txt".to_string()),
                 change_type: super::ChangeType::Modify,
                 old_content: Some(fix.original_code),
@@ -385,6 +1424,7 @@ impl ConversationService {
             &code_to_refactor,
             session.code_context.current_file.as_deref(),
             &session.workspace_context,
+            &session.code_context.attached_files,
         ).await?;
 
         let response = format!(
@@ -397,6 +1437,7 @@ impl ConversationService {
         Ok(InternalResponse {
             ai_response: response,
             code_changes: Some(vec![super::CodeChange {
+                id: Uuid::new_v4(),
                 file_path: session.code_context.current_file.clone().unwrap_or_else(|| "refactored_code.txt".to_string()),
                 change_type: super::ChangeType::Modify,
                 old_content: Some(suggestion.original_code),
@@ -451,6 +1492,7 @@ impl ConversationService {
         Ok(InternalResponse {
             ai_response: response,
             code_changes: Some(vec![super::CodeChange {
+                id: Uuid::new_v4(),
                 file_path: test_file_name,
                 change_type: super::ChangeType::Create,
                 old_content: None,
@@ -472,7 +1514,7 @@ impl ConversationService {
         info!("Handling documentation request");
 
         let prompt = self.build_documentation_prompt(message, session, context);
-        let response = self.generate_ai_response(&prompt, "documentation").await?;
+        let response = self.generate_ai_response(&prompt, "documentation", session).await?;
 
         Ok(InternalResponse {
             ai_response: response,
@@ -490,7 +1532,7 @@ impl ConversationService {
         info!("Handling file operation request");
 
         let prompt = self.build_file_operation_prompt(message, session, context);
-        let response = self.generate_ai_response(&prompt, "file_operation").await?;
+        let response = self.generate_ai_response(&prompt, "file_operation", session).await?;
 
         Ok(InternalResponse {
             ai_response: response,
@@ -514,7 +1556,7 @@ impl ConversationService {
             session.workspace_context.build_system
         );
 
-        let response = self.generate_ai_response(&prompt, "project_setup").await?;
+        let response = self.generate_ai_response(&prompt, "project_setup", session).await?;
 
         Ok(InternalResponse {
             ai_response: response,
@@ -574,7 +1616,7 @@ impl ConversationService {
         info!("Handling general chat request");
 
         let prompt = self.build_general_chat_prompt(message, session, context);
-        let response = self.generate_ai_response(&prompt, "general").await?;
+        let response = self.generate_ai_response(&prompt, "general", session).await?;
 
         Ok(InternalResponse {
             ai_response: response,
@@ -594,7 +1636,7 @@ impl ConversationService {
             session.workspace_context.project_type
         );
 
-        let response = self.generate_ai_response(&prompt, "debugging").await?;
+        let response = self.generate_ai_response(&prompt, "debugging", session).await?;
 
         Ok(InternalResponse {
             ai_response: response,
@@ -604,22 +1646,70 @@ impl ConversationService {
     }
 
     // Helper methods
-    async fn generate_ai_response(&self, prompt: &str, context_type: &str) -> Result<String> {
-        let system_prompt = match context_type {
-            "documentation" => "Sen bir teknik yazım uzmanısın. Açık, anlaşılır ve kapsamlı dokümantasyon yazıyorsun.",
-            "file_operation" => "Sen bir dosya sistemi uzmanısın. Dosya işlemlerinde güvenli ve etkili yöntemler öneriyorsun.",
-            "project_setup" => "Sen bir proje kurulum uzmanısın. Adım adım, net kurulum talimatları veriyorsun.",
-            "debugging" => "Sen bir debugging uzmanısın. Sistematik hata bulma ve çözme yöntemleri öneriyorsun.",
-            _ => "Sen yardımcı bir AI asistanısın. Kullanıcıların sorularını net ve faydalı şekilde yanıtlıyorsun.",
-        };
+
+    /// Resolves the provider/model/temperature to use for a completion,
+    /// preferring the session's own `SessionMetadata::provider`/`model`
+    /// override (set via `PATCH /conversation/sessions/:id/settings`) over
+    /// the user's saved preferences, and falling back to the service's
+    /// hardcoded defaults if the preferences lookup fails, so a database
+    /// hiccup degrades rather than breaking the conversation.
+    /// Resolves a message's `context_files` into prompt-ready attachments,
+    /// logging and falling back to no attachments on failure (e.g. an
+    /// unresolvable workspace root) rather than failing the whole turn.
+    async fn attach_context_files(
+        &self,
+        context_files: &[String],
+        workspace_root: &str,
+        message: &str,
+        model: &str,
+    ) -> Vec<super::AttachedFileContext> {
+        if context_files.is_empty() {
+            return Vec::new();
+        }
+
+        match self.code_integration.attach_context_files(context_files, workspace_root, message, model).await {
+            Ok(attachments) => attachments,
+            Err(e) => {
+                warn!(error = %e, "Failed to attach context files, continuing without them");
+                Vec::new()
+            }
+        }
+    }
+
+    async fn resolve_completion_defaults(&self, session: &ConversationSession) -> (String, String, f64) {
+        match self.preferences_service.get_user_preferences(session.user_id).await {
+            Ok(prefs) => {
+                let temperature = prefs.temperature;
+                let (provider, model) = prefs.resolve_provider_and_model(
+                    session.session_metadata.provider.clone(),
+                    session.session_metadata.model.clone(),
+                );
+                (provider, model, temperature)
+            }
+            Err(e) => {
+                error!("Failed to load preferences for user {}, using defaults: {}", session.user_id, e);
+                let provider = session.session_metadata.provider.clone().unwrap_or_else(|| "openai".to_string());
+                let model = session.session_metadata.model.clone().unwrap_or_else(|| "gpt-4".to_string());
+                (provider, model, 0.3)
+            }
+        }
+    }
+
+    async fn generate_ai_response(&self, prompt: &str, context_type: &str, session: &ConversationSession) -> Result<String> {
+        let system_prompt = self.prompt_template_engine.system_prompt(
+            context_type,
+            session.session_metadata.preferences.explanation_level,
+        );
+
+        let (provider, model, temperature) = self.resolve_completion_defaults(session).await;
 
         let completion_request = crate::providers::CompletionRequest {
             prompt: prompt.to_string(),
-            model: Some("gpt-4".to_string()),
-            provider: Some("openai".to_string()),
+            model: Some(model),
+            provider: Some(provider),
             max_tokens: Some(2000),
-            temperature: Some(0.3),
-            system_prompt: Some(system_prompt.to_string()),
+            temperature: Some(temperature as f32),
+            system_prompt: Some(system_prompt),
             ..Default::default()
         };
 
@@ -736,6 +1826,17 @@ impl ConversationService {
         self.session_manager.get_user_sessions(user_id, limit).await
     }
 
+    pub async fn get_user_sessions_page(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<ConversationSession>, i64)> {
+        let sessions = self.session_manager.get_user_sessions_page(user_id, limit, offset).await?;
+        let total = self.session_manager.count_user_sessions(user_id).await?;
+        Ok((sessions, total))
+    }
+
     pub async fn search_conversations(&self, user_id: Uuid, query: &str, limit: i64) -> Result<Vec<ConversationTurn>> {
         self.session_manager.search_conversations(user_id, query, limit).await
     }
@@ -745,7 +1846,70 @@ impl ConversationService {
     }
 
     pub async fn delete_session(&self, session_id: Uuid) -> Result<()> {
-        self.session_manager.delete_session(session_id).await
+        let mut user_id = None;
+        if let Some(session) = self.session_manager.get_session(session_id).await? {
+            user_id = Some(session.user_id);
+            self.invalidate_cached_session(session.user_id, session_id).await;
+        }
+        self.session_manager.delete_session(session_id).await?;
+
+        if let (Some(audit_service), Some(user_id)) = (&self.audit_service, user_id) {
+            let event = self.audit_event(user_id, "delete_session", session_id, AuditOutcome::Success);
+            audit_service.log_event(event).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn archive_session(&self, session_id: Uuid) -> Result<()> {
+        self.session_manager.archive_session(session_id).await
+    }
+
+    pub async fn restore_session(&self, session_id: Uuid) -> Result<()> {
+        self.session_manager.restore_session(session_id).await
+    }
+
+    pub async fn get_archived_sessions_page(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<ConversationSession>, i64)> {
+        let sessions = self.session_manager.get_archived_sessions_page(user_id, limit, offset).await?;
+        let total = self.session_manager.count_archived_sessions(user_id).await?;
+        Ok((sessions, total))
+    }
+
+    /// Manual rename, distinct from the automatic titling `process_message`
+    /// does for a session's first turn.
+    pub async fn rename_session(&self, session_id: Uuid, title: String) -> Result<ConversationSession> {
+        let mut session = self.session_manager.get_session(session_id).await?
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+        session.title = Some(title);
+        session.updated_at = Utc::now();
+
+        self.session_manager.update_session(&session).await?;
+        self.cache_session(&session).await;
+        Ok(session)
+    }
+
+    /// List-row metadata for `session`, backfilling a title from its first
+    /// turn if it predates automatic titling (`session.title` is still
+    /// `None`).
+    pub async fn get_session_list_metadata(
+        &self,
+        session: &mut ConversationSession,
+    ) -> Result<super::session_manager::SessionListMetadata> {
+        if session.title.is_none() {
+            if let Some(first_turn) = self.session_manager.get_first_turn(session.id).await? {
+                let title = Self::generate_session_title(&first_turn.user_message);
+                self.session_manager.update_session_title(session.id, &title).await?;
+                session.title = Some(title);
+            }
+        }
+
+        self.session_manager.get_session_list_metadata(session.id).await
     }
 }
 
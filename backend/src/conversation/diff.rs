@@ -0,0 +1,97 @@
+//! Line-based unified diffs for code-change previews and applies. Uses a
+//! straightforward LCS alignment rather than pulling in an external diff
+//! crate, since a single-file edit is small enough that this never needs to
+//! be fast.
+
+enum DiffOp<'a> {
+    Equal(&'a str),
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+/// Renders `old` -> `new` as a unified diff for `file_path`. Passing `""` for
+/// `old` or `new` renders a pure addition/deletion, which is how
+/// [`super::conversation_service::ConversationService`] diffs `Create`/`Delete`
+/// changes.
+pub fn unified_diff(file_path: &str, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = align(&old_lines, &new_lines);
+
+    let mut out = format!("--- a/{file_path}\n+++ b/{file_path}\n@@ -1,{} +1,{} @@\n", old_lines.len(), new_lines.len());
+    for op in ops {
+        match op {
+            DiffOp::Equal(line) => out.push_str(&format!(" {line}\n")),
+            DiffOp::Delete(line) => out.push_str(&format!("-{line}\n")),
+            DiffOp::Insert(line) => out.push_str(&format!("+{line}\n")),
+        }
+    }
+    out
+}
+
+/// Aligns `old` and `new` via their longest common subsequence, so unchanged
+/// lines in the middle of a file don't show up as a delete-then-insert pair.
+fn align<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffOp<'a>> {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs_len = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs_len[i][j] = if old[i] == new[j] {
+                lcs_len[i + 1][j + 1] + 1
+            } else {
+                lcs_len[i + 1][j].max(lcs_len[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            ops.push(DiffOp::Equal(old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs_len[i + 1][j] >= lcs_len[i][j + 1] {
+            ops.push(DiffOp::Delete(old[i]));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Insert(new[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old[i..n].iter().map(|line| DiffOp::Delete(line)));
+    ops.extend(new[j..m].iter().map(|line| DiffOp::Insert(line)));
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pure_addition_has_no_context_lines() {
+        let diff = unified_diff("new.rs", "", "fn main() {}\n");
+        assert!(diff.contains("+fn main() {}"));
+        let body = diff.lines().skip(3).collect::<Vec<_>>().join("\n");
+        assert!(!body.contains('-'));
+    }
+
+    #[test]
+    fn pure_deletion_marks_every_line_removed() {
+        let diff = unified_diff("old.rs", "fn main() {}\n", "");
+        assert!(diff.contains("-fn main() {}"));
+        let body = diff.lines().skip(3).collect::<Vec<_>>().join("\n");
+        assert!(!body.contains('+'));
+    }
+
+    #[test]
+    fn unchanged_middle_lines_stay_as_context() {
+        let old = "one\ntwo\nthree\n";
+        let new = "one\nchanged\nthree\n";
+        let diff = unified_diff("f.txt", old, new);
+        assert!(diff.contains(" one"));
+        assert!(diff.contains("-two"));
+        assert!(diff.contains("+changed"));
+        assert!(diff.contains(" three"));
+    }
+}
@@ -1,11 +1,29 @@
 use anyhow::Result;
 use std::sync::Arc;
 use regex::Regex;
-use tracing::{info, debug};
+use tracing::{info, debug, warn};
 
-use crate::providers::{ProviderRouter, CompletionRequest};
+use crate::providers::{AIProvider, ProviderRouter, CompletionRequest, ResponseFormat};
 use super::{MessageIntent, ConversationTurn, WorkspaceContext, CodeContext};
 
+/// Weight added per matching regex pattern when scoring a candidate intent.
+const PATTERN_MATCH_WEIGHT: f32 = 1.0;
+/// Small floor every pattern-backed category keeps even with zero matches,
+/// so a single strong match doesn't collapse the distribution to exactly
+/// one candidate.
+const CANDIDATE_FLOOR: f32 = 0.005;
+/// `GeneralChat` has no patterns of its own; this is its baseline weight,
+/// representing the probability mass left over when nothing else matches.
+const GENERAL_CHAT_WEIGHT: f32 = 0.1;
+/// Pattern-match confidence above which we skip the AI-based fallback.
+const HIGH_CONFIDENCE_THRESHOLD: f32 = 0.8;
+/// Confidence below which we don't trust *any* candidate — including one the
+/// AI fallback proposed — enough to act on it. Below this, the top candidate
+/// is forced to `GeneralChat` and the caller is expected to ask a clarifying
+/// question (see [`IntentAnalyzer::get_clarifying_questions`]) instead of
+/// guessing at what the user wants.
+const LOW_CONFIDENCE_THRESHOLD: f32 = 0.35;
+
 pub struct IntentAnalyzer {
     provider_router: Arc<ProviderRouter>,
     intent_patterns: IntentPatterns,
@@ -33,115 +51,53 @@ impl IntentAnalyzer {
         }
     }
 
+    /// Scores every `MessageIntent` variant against `message`, returning a
+    /// ranked, normalized probability distribution (sorted descending, sums
+    /// to ~1.0). The first entry is the argmax intent/confidence; the AI
+    /// fallback is only consulted when that top score is ambiguous, and its
+    /// result is blended back into the distribution rather than replacing it.
     pub async fn analyze_intent(
         &self,
         message: &str,
         workspace_context: &WorkspaceContext,
         code_context: &CodeContext,
         conversation_history: &[ConversationTurn],
-    ) -> Result<(MessageIntent, f32)> {
-        // Önce pattern-based hızlı analiz
-        if let Some((intent, confidence)) = self.pattern_based_analysis(message) {
-            if confidence > 0.8 {
-                debug!("High confidence pattern match: {:?} ({})", intent, confidence);
-                return Ok((intent, confidence));
-            }
-        }
-
-        // AI-based derin analiz
-        let ai_result = self.ai_based_analysis(
-            message,
-            workspace_context,
-            code_context,
-            conversation_history,
-        ).await?;
-
-        Ok(ai_result)
-    }
-
-    fn pattern_based_analysis(&self, message: &str) -> Option<(MessageIntent, f32)> {
-        let message_lower = message.to_lowercase();
-
-        // Code Generation patterns
-        for pattern in &self.intent_patterns.code_generation {
-            if pattern.is_match(&message_lower) {
-                return Some((MessageIntent::CodeGeneration, 0.85));
-            }
-        }
-
-        // Code Explanation patterns
-        for pattern in &self.intent_patterns.code_explanation {
-            if pattern.is_match(&message_lower) {
-                return Some((MessageIntent::CodeExplanation, 0.9));
-            }
-        }
-
-        // Debugging patterns
-        for pattern in &self.intent_patterns.debugging {
-            if pattern.is_match(&message_lower) {
-                return Some((MessageIntent::Debugging, 0.85));
-            }
-        }
-
-        // Terminal Command patterns
-        for pattern in &self.intent_patterns.terminal_command {
-            if pattern.is_match(&message_lower) {
-                return Some((MessageIntent::TerminalCommand, 0.9));
-            }
-        }
-
-        // File Operation patterns
-        for pattern in &self.intent_patterns.file_operation {
-            if pattern.is_match(&message_lower) {
-                return Some((MessageIntent::FileOperation, 0.85));
-            }
-        }
-
-        // Code Review patterns
-        for pattern in &self.intent_patterns.code_review {
-            if pattern.is_match(&message_lower) {
-                return Some((MessageIntent::CodeReview, 0.8));
-            }
-        }
-
-        // Refactoring patterns
-        for pattern in &self.intent_patterns.refactoring {
-            if pattern.is_match(&message_lower) {
-                return Some((MessageIntent::Refactoring, 0.8));
-            }
-        }
-
-        // Testing patterns
-        for pattern in &self.intent_patterns.testing {
-            if pattern.is_match(&message_lower) {
-                return Some((MessageIntent::Testing, 0.85));
-            }
-        }
-
-        // Documentation patterns
-        for pattern in &self.intent_patterns.documentation {
-            if pattern.is_match(&message_lower) {
-                return Some((MessageIntent::Documentation, 0.8));
-            }
-        }
-
-        // Project Setup patterns
-        for pattern in &self.intent_patterns.project_setup {
-            if pattern.is_match(&message_lower) {
-                return Some((MessageIntent::ProjectSetup, 0.85));
+    ) -> Result<Vec<(MessageIntent, f32)>> {
+        let mut candidates = self.intent_patterns.score(&message.to_lowercase());
+
+        if candidates[0].1 <= HIGH_CONFIDENCE_THRESHOLD {
+            match self.ai_based_analysis(message, workspace_context, code_context, conversation_history).await {
+                Ok((ai_intent, ai_confidence)) => {
+                    debug!(
+                        "Low confidence pattern match ({:?} {}), consulted AI: {:?} ({})",
+                        candidates[0].0, candidates[0].1, ai_intent, ai_confidence
+                    );
+                    candidates = merge_ai_candidate(candidates, ai_intent, ai_confidence);
+                }
+                Err(e) => {
+                    warn!("AI-based intent analysis failed, keeping pattern-only result: {}", e);
+                }
             }
+        } else {
+            debug!("High confidence pattern match: {:?} ({})", candidates[0].0, candidates[0].1);
         }
 
-        // Workspace Navigation patterns
-        for pattern in &self.intent_patterns.workspace_navigation {
-            if pattern.is_match(&message_lower) {
-                return Some((MessageIntent::WorkspaceNavigation, 0.8));
-            }
+        if candidates[0].1 < LOW_CONFIDENCE_THRESHOLD && candidates[0].0 != MessageIntent::GeneralChat {
+            debug!(
+                "Top candidate {:?} ({}) is below the low-confidence threshold; falling back to GeneralChat",
+                candidates[0].0, candidates[0].1
+            );
+            candidates = fall_back_to_general_chat(candidates);
         }
 
-        None
+        Ok(candidates)
     }
 
+    /// Asks the provider router for a structured `{intent, confidence}`
+    /// judgment via `ResponseFormat::JsonSchema`, rather than asking it to
+    /// free-text a category name and parsing it back out — the schema is
+    /// enforced by `complete_json`, so a malformed/off-format response is a
+    /// parse error here instead of a silently wrong guess.
     async fn ai_based_analysis(
         &self,
         message: &str,
@@ -150,52 +106,47 @@ impl IntentAnalyzer {
         conversation_history: &[ConversationTurn],
     ) -> Result<(MessageIntent, f32)> {
         let context_info = self.build_context_info(workspace_context, code_context, conversation_history);
-        
+
         let prompt = format!(
-            r#"Kullanıcının mesajının intent'ini analiz et ve en uygun kategoriyi belirle.
-
-Kullanıcı mesajı: "{}"
-
-Mevcut bağlam:
-{}
-
-Mümkün intent kategorileri:
-1. CodeGeneration - Kod yazma, oluşturma istekleri
-2. CodeExplanation - Kod açıklama, anlama istekleri  
-3. CodeReview - Kod inceleme, gözden geçirme
-4. Debugging - Hata bulma, düzeltme
-5. Refactoring - Kod yeniden düzenleme
-6. Testing - Test yazma, test çalıştırma
-7. Documentation - Dokümantasyon yazma
-8. FileOperation - Dosya işlemleri (oluştur, sil, taşı)
-9. ProjectSetup - Proje kurulumu, yapılandırma
-10. TerminalCommand - Terminal komut çalıştırma
-11. WorkspaceNavigation - Dosya/klasör gezinme
-12. GeneralChat - Genel sohbet
-
-Sadece kategori adını ve güven skorunu (0.0-1.0) döndür.
-Format: KATEGORI:SKOR
-
-Örnek: CodeGeneration:0.85"#,
-            message,
-            context_info
+            "Kullanıcının mesajının intent'ini analiz et ve en uygun kategoriyi belirle.\n\n\
+             Kullanıcı mesajı: \"{message}\"\n\n\
+             Mevcut bağlam:\n{context_info}\n\n\
+             Mümkün intent kategorileri: CodeGeneration, CodeExplanation, CodeReview, Debugging, \
+             Refactoring, Testing, Documentation, FileOperation, ProjectSetup, TerminalCommand, \
+             WorkspaceNavigation, GeneralChat."
         );
 
-        let completion_request = CompletionRequest {
-            prompt,
-            model: Some("gpt-4".to_string()),
-            provider: Some("openai".to_string()),
-            max_tokens: Some(50),
-            temperature: Some(0.1),
-            system_prompt: Some("Sen bir intent analiz uzmanısın. Kullanıcı mesajlarının amacını doğru şekilde kategorize ediyorsun.".to_string()),
-            ..Default::default()
-        };
-
-        let response = self.provider_router.complete(completion_request).await?;
-        let result = self.parse_ai_response(&response.text)?;
-
-        info!("AI intent analysis: {:?} (confidence: {})", result.0, result.1);
-        Ok(result)
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "intent": {
+                    "type": "string",
+                    "enum": [
+                        "CodeGeneration", "CodeExplanation", "CodeReview", "Debugging",
+                        "Refactoring", "Testing", "Documentation", "FileOperation",
+                        "ProjectSetup", "TerminalCommand", "WorkspaceNavigation", "GeneralChat"
+                    ]
+                },
+                "confidence": { "type": "number" }
+            },
+            "required": ["intent", "confidence"]
+        });
+
+        let completion_request = CompletionRequest::new(prompt)
+            .with_model("gpt-4".to_string())
+            .with_temperature(0.1)
+            .with_system_prompt(
+                "Sen bir intent analiz uzmanısın. Kullanıcı mesajlarının amacını doğru şekilde kategorize ediyorsun.".to_string(),
+            )
+            .with_response_format(ResponseFormat::JsonSchema { schema });
+
+        let value = self.provider_router.complete_json(completion_request).await?;
+        let intent_str = value.get("intent").and_then(|v| v.as_str()).unwrap_or("GeneralChat");
+        let confidence = value.get("confidence").and_then(|v| v.as_f64()).unwrap_or(0.5) as f32;
+
+        let intent = intent_from_str(intent_str);
+        info!("AI intent analysis: {:?} (confidence: {})", intent, confidence);
+        Ok((intent, confidence.clamp(0.0, 1.0)))
     }
 
     fn build_context_info(
@@ -227,48 +178,22 @@ Format: KATEGORI:SKOR
         context_parts.join("\n")
     }
 
-    fn parse_ai_response(&self, response: &str) -> Result<(MessageIntent, f32)> {
-        let response = response.trim();
-        
-        if let Some(colon_pos) = response.find(':') {
-            let intent_str = &response[..colon_pos];
-            let score_str = &response[colon_pos + 1..];
-            
-            let intent = match intent_str {
-                "CodeGeneration" => MessageIntent::CodeGeneration,
-                "CodeExplanation" => MessageIntent::CodeExplanation,
-                "CodeReview" => MessageIntent::CodeReview,
-                "Debugging" => MessageIntent::Debugging,
-                "Refactoring" => MessageIntent::Refactoring,
-                "Testing" => MessageIntent::Testing,
-                "Documentation" => MessageIntent::Documentation,
-                "FileOperation" => MessageIntent::FileOperation,
-                "ProjectSetup" => MessageIntent::ProjectSetup,
-                "TerminalCommand" => MessageIntent::TerminalCommand,
-                "WorkspaceNavigation" => MessageIntent::WorkspaceNavigation,
-                _ => MessageIntent::GeneralChat,
-            };
-            
-            let confidence = score_str.parse::<f32>().unwrap_or(0.5);
-            Ok((intent, confidence.clamp(0.0, 1.0)))
-        } else {
-            // Fallback: sadece intent adı verilmişse
-            let intent = match response {
-                "CodeGeneration" => MessageIntent::CodeGeneration,
-                "CodeExplanation" => MessageIntent::CodeExplanation,
-                "CodeReview" => MessageIntent::CodeReview,
-                "Debugging" => MessageIntent::Debugging,
-                "Refactoring" => MessageIntent::Refactoring,
-                "Testing" => MessageIntent::Testing,
-                "Documentation" => MessageIntent::Documentation,
-                "FileOperation" => MessageIntent::FileOperation,
-                "ProjectSetup" => MessageIntent::ProjectSetup,
-                "TerminalCommand" => MessageIntent::TerminalCommand,
-                "WorkspaceNavigation" => MessageIntent::WorkspaceNavigation,
-                _ => MessageIntent::GeneralChat,
-            };
-            Ok((intent, 0.7))
-        }
+    /// Whether `confidence` is too low to act on — callers should ask a
+    /// clarifying question (see [`Self::get_clarifying_questions`]) instead
+    /// of using the associated intent's suggestions.
+    pub fn is_low_confidence(&self, confidence: f32) -> bool {
+        confidence < LOW_CONFIDENCE_THRESHOLD
+    }
+
+    /// Generic clarifying questions asked when even the AI-merged top
+    /// candidate falls below [`LOW_CONFIDENCE_THRESHOLD`] — i.e. we genuinely
+    /// don't know what the user wants yet, so we ask instead of guessing.
+    pub fn get_clarifying_questions(&self) -> Vec<String> {
+        vec![
+            "Tam olarak ne yapmamı istersiniz?".to_string(),
+            "Kod yazma, açıklama, hata ayıklama gibi bir konuda mı yardım istiyorsunuz?".to_string(),
+            "Biraz daha detay verebilir misiniz?".to_string(),
+        ]
     }
 
     pub fn get_intent_suggestions(&self, intent: &MessageIntent) -> Vec<String> {
@@ -307,6 +232,43 @@ Format: KATEGORI:SKOR
 }
 
 impl IntentPatterns {
+    /// Counts regex matches per category and turns them into a normalized,
+    /// descending-sorted probability distribution over all 12 `MessageIntent`
+    /// variants. `GeneralChat` has no patterns of its own, so it only picks
+    /// up weight when nothing else matches.
+    fn score(&self, message_lower: &str) -> Vec<(MessageIntent, f32)> {
+        let categories: [(MessageIntent, &[Regex]); 11] = [
+            (MessageIntent::CodeGeneration, &self.code_generation),
+            (MessageIntent::CodeExplanation, &self.code_explanation),
+            (MessageIntent::CodeReview, &self.code_review),
+            (MessageIntent::Debugging, &self.debugging),
+            (MessageIntent::Refactoring, &self.refactoring),
+            (MessageIntent::Testing, &self.testing),
+            (MessageIntent::Documentation, &self.documentation),
+            (MessageIntent::FileOperation, &self.file_operation),
+            (MessageIntent::ProjectSetup, &self.project_setup),
+            (MessageIntent::TerminalCommand, &self.terminal_command),
+            (MessageIntent::WorkspaceNavigation, &self.workspace_navigation),
+        ];
+
+        let mut scored: Vec<(MessageIntent, f32)> = categories
+            .into_iter()
+            .map(|(intent, patterns)| {
+                let matches = patterns.iter().filter(|p| p.is_match(message_lower)).count() as f32;
+                (intent, matches * PATTERN_MATCH_WEIGHT + CANDIDATE_FLOOR)
+            })
+            .collect();
+        scored.push((MessageIntent::GeneralChat, GENERAL_CHAT_WEIGHT));
+
+        let total: f32 = scored.iter().map(|(_, score)| *score).sum();
+        for (_, score) in scored.iter_mut() {
+            *score /= total;
+        }
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        scored
+    }
+
     fn new() -> Self {
         Self {
             code_generation: vec![
@@ -356,6 +318,9 @@ impl IntentPatterns {
                 Regex::new(r"\b(temizle|clean|organize)\b").unwrap(),
                 Regex::new(r"\b(daha.*iyi|better|optimize)\b").unwrap(),
                 Regex::new(r"\b(pattern|design.*pattern)\b").unwrap(),
+                // "rename this variable/function" is refactoring, not code generation
+                // or a bare file rename — scope the rename keyword to a code-symbol noun.
+                Regex::new(r"\b(rename|yeniden.*adlandır)\b.*\b(variable|function|method|class|değişken|fonksiyon|metod|sınıf)\b").unwrap(),
             ],
             
             testing: vec![
@@ -387,4 +352,151 @@ impl IntentPatterns {
             ],
         }
     }
+}
+
+/// Maps the exact variant-name strings the structured AI response is
+/// constrained to (via the JSON Schema's `enum`) back to `MessageIntent`.
+fn intent_from_str(value: &str) -> MessageIntent {
+    match value {
+        "CodeGeneration" => MessageIntent::CodeGeneration,
+        "CodeExplanation" => MessageIntent::CodeExplanation,
+        "CodeReview" => MessageIntent::CodeReview,
+        "Debugging" => MessageIntent::Debugging,
+        "Refactoring" => MessageIntent::Refactoring,
+        "Testing" => MessageIntent::Testing,
+        "Documentation" => MessageIntent::Documentation,
+        "FileOperation" => MessageIntent::FileOperation,
+        "ProjectSetup" => MessageIntent::ProjectSetup,
+        "TerminalCommand" => MessageIntent::TerminalCommand,
+        "WorkspaceNavigation" => MessageIntent::WorkspaceNavigation,
+        _ => MessageIntent::GeneralChat,
+    }
+}
+
+/// Forces `GeneralChat` to the front once the top candidate is too weak to
+/// trust, while keeping the distribution sorted descending and summing to
+/// ~1.0 for callers (and the `scores_sum_to_one_and_are_sorted_descending`
+/// test) that rely on that invariant.
+fn fall_back_to_general_chat(mut candidates: Vec<(MessageIntent, f32)>) -> Vec<(MessageIntent, f32)> {
+    if let Some(pos) = candidates.iter().position(|(intent, _)| *intent == MessageIntent::GeneralChat) {
+        let general_chat = candidates.remove(pos);
+        candidates.insert(0, general_chat);
+    } else {
+        candidates.insert(0, (MessageIntent::GeneralChat, candidates[0].1));
+    }
+    candidates
+}
+
+/// Blends an AI-based `(intent, confidence)` guess into the pattern-based
+/// distribution: boosts the AI's chosen category by its reported confidence,
+/// then renormalizes so the result still sums to ~1.0 and stays sorted
+/// descending.
+fn merge_ai_candidate(
+    mut candidates: Vec<(MessageIntent, f32)>,
+    ai_intent: MessageIntent,
+    ai_confidence: f32,
+) -> Vec<(MessageIntent, f32)> {
+    if let Some(entry) = candidates.iter_mut().find(|(intent, _)| *intent == ai_intent) {
+        entry.1 += ai_confidence;
+    } else {
+        candidates.push((ai_intent, ai_confidence));
+    }
+
+    let total: f32 = candidates.iter().map(|(_, score)| *score).sum();
+    for (_, score) in candidates.iter_mut() {
+        *score /= total;
+    }
+
+    candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_sum_to_one_and_are_sorted_descending() {
+        let patterns = IntentPatterns::new();
+        let candidates = patterns.score("please write a function that parses json");
+
+        let total: f32 = candidates.iter().map(|(_, score)| *score).sum();
+        assert!((total - 1.0).abs() < 1e-4, "scores should sum to ~1.0, got {total}");
+
+        for window in candidates.windows(2) {
+            assert!(window[0].1 >= window[1].1, "candidates must be sorted descending");
+        }
+    }
+
+    #[test]
+    fn clear_pattern_match_yields_high_confidence_top_candidate() {
+        let patterns = IntentPatterns::new();
+        let candidates = patterns.score("can you explain what this function does");
+        assert_eq!(candidates[0].0, MessageIntent::CodeExplanation);
+        assert!(candidates[0].1 > HIGH_CONFIDENCE_THRESHOLD);
+    }
+
+    #[test]
+    fn no_pattern_match_falls_back_to_general_chat_below_threshold() {
+        let patterns = IntentPatterns::new();
+        let candidates = patterns.score("hello there, how are you");
+        assert_eq!(candidates[0].0, MessageIntent::GeneralChat);
+        assert!(candidates[0].1 < HIGH_CONFIDENCE_THRESHOLD);
+    }
+
+    #[test]
+    fn merge_ai_candidate_boosts_chosen_intent_and_renormalizes() {
+        let patterns = IntentPatterns::new();
+        let candidates = patterns.score("hello there");
+        let merged = merge_ai_candidate(candidates, MessageIntent::Debugging, 0.9);
+
+        let total: f32 = merged.iter().map(|(_, score)| *score).sum();
+        assert!((total - 1.0).abs() < 1e-4);
+        assert_eq!(merged[0].0, MessageIntent::Debugging);
+    }
+
+    #[derive(serde::Deserialize)]
+    struct FixtureExample {
+        message: String,
+        intent: MessageIntent,
+    }
+
+    /// Calibration check against a labelled fixture set covering every
+    /// `MessageIntent` variant. Only exercises the deterministic
+    /// pattern-matching layer (`IntentPatterns::score`) since the AI
+    /// fallback needs a live provider and can't run offline — but that
+    /// layer is exactly what decides whether the AI even gets consulted
+    /// (see `HIGH_CONFIDENCE_THRESHOLD`), so its accuracy is worth pinning.
+    #[test]
+    fn fixture_examples_are_classified_with_reasonable_top1_accuracy() {
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/intent_analyzer_examples.jsonl"
+        ));
+        let patterns = IntentPatterns::new();
+
+        let mut total = 0;
+        let mut correct = 0;
+        for line in fixture.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let example: FixtureExample = serde_json::from_str(line)
+                .unwrap_or_else(|e| panic!("invalid fixture line {line:?}: {e}"));
+
+            let candidates = patterns.score(&example.message.to_lowercase());
+            total += 1;
+            if candidates[0].0 == example.intent {
+                correct += 1;
+            }
+        }
+
+        assert!(total >= 100, "fixture set should cover at least 100 examples, got {total}");
+        let accuracy = correct as f32 / total as f32;
+        assert!(
+            accuracy >= 0.6,
+            "pattern-only top-1 accuracy too low: {correct}/{total} ({accuracy})"
+        );
+    }
 }
\ No newline at end of file
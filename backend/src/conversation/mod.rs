@@ -3,6 +3,8 @@ pub mod code_integration;
 pub mod session_manager;
 pub mod intent_analyzer;
 pub mod workspace_analyzer;
+pub mod session_export;
+pub mod diff;
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -18,6 +20,38 @@ pub struct ConversationSession {
     pub active_files: Vec<String>,
     pub code_context: CodeContext,
     pub session_metadata: SessionMetadata,
+    /// Short human-friendly name shown in the session picker. `None` until
+    /// `ConversationService::process_message` generates one from the first
+    /// user message, or until a pre-existing session is backfilled on its
+    /// first list access. A user can override it via `PATCH
+    /// /conversation/sessions/:id`.
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Session this one was forked from, if any. Lets a UI render the
+    /// branching history as a tree.
+    pub parent_session_id: Option<Uuid>,
+    /// Turn in `parent_session_id` this session was forked at, if any.
+    /// `parent_session_id` alone identifies the fork's origin session;
+    /// this pins the exact point in its history the fork branched off
+    /// from, without requiring a client to diff the two sessions' turns.
+    #[serde(default)]
+    pub forked_from_turn_id: Option<Uuid>,
+    /// Identifies which branch this session belongs to. A freshly-created
+    /// session starts a new branch of its own; forking via
+    /// [`conversation_service::ConversationService::fork_session`] assigns
+    /// the fork a new branch id distinct from its parent's.
+    pub branch_id: Uuid,
+    /// Rolling summary covering every turn older than the most recent
+    /// [`context_manager::ConversationContextAssembler`]-selected window, generated via
+    /// the provider router and persisted here so it isn't regenerated on
+    /// every message.
+    #[serde(default)]
+    pub rolling_summary: Option<String>,
+    /// How many of the oldest turns in `conversation_history` `rolling_summary`
+    /// already accounts for. Lets the assembler summarize only the turns
+    /// that have newly aged out since the summary was last updated.
+    #[serde(default)]
+    pub rolling_summary_turns_covered: usize,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -32,9 +66,43 @@ pub struct ConversationTurn {
     pub files_referenced: Vec<String>,
     pub confidence_score: f32,
     pub execution_time_ms: u64,
+    /// Turn this one was branched from, if [`ConversationRequest::branch_from_turn_id`]
+    /// was set when it was created.
+    pub parent_turn_id: Option<Uuid>,
+    /// Results of applying this turn's `code_changes` to disk, if any were
+    /// ever applied (via [`conversation_service::ConversationService::apply_code_changes`]).
+    /// Keeps each change's pre-image around so a later rollback request can
+    /// restore it without re-deriving anything.
+    #[serde(default)]
+    pub applied_changes: Vec<AppliedChangeResult>,
+    /// Provider and model that actually answered this turn, resolved via
+    /// `conversation_service::ConversationService::resolve_completion_defaults`
+    /// at the time it was created. `None` for turns persisted before this
+    /// field existed. Surfaced in the per-turn footer of a Markdown export
+    /// -- see `session_export::render_markdown_turn`.
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Prior versions of this turn's answer, newest first, kept when it's
+    /// regenerated via `conversation_service::ConversationService::regenerate_turn`
+    /// so a "regenerate" button doesn't silently destroy the previous answer.
+    #[serde(default)]
+    pub revisions: Vec<TurnRevision>,
     pub timestamp: DateTime<Utc>,
 }
 
+/// A turn's `ai_response`/`code_changes` as they stood before a regenerate
+/// request overwrote them. See [`ConversationTurn::revisions`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurnRevision {
+    pub ai_response: String,
+    pub code_changes: Option<Vec<CodeChange>>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub replaced_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkspaceContext {
     pub root_path: String,
@@ -55,6 +123,31 @@ pub struct CodeContext {
     pub recent_functions: Vec<FunctionInfo>,
     pub imports: Vec<ImportInfo>,
     pub symbols: Vec<SymbolInfo>,
+    /// Explicit `context_files` attached to the current message, resolved by
+    /// `code_integration::CodeIntegrationService::attach_context_files` into
+    /// the spans actually worth sending to the provider. Cleared and
+    /// re-populated on every message rather than accumulated across turns.
+    #[serde(default)]
+    pub attached_files: Vec<AttachedFileContext>,
+}
+
+/// A `context_files` entry resolved into the span(s) of its content that
+/// are actually attached to the prompt, plus what that cost.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachedFileContext {
+    pub file_path: String,
+    pub spans: Vec<AttachedSpan>,
+    pub tokens_contributed: usize,
+    /// `true` when the file exceeded the attachment token threshold and
+    /// `spans` is a relevance-ranked subset rather than the whole file.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachedSpan {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub content: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,9 +157,17 @@ pub struct SessionMetadata {
     pub preferences: UserPreferences,
     pub active_tools: Vec<String>,
     pub collaboration_mode: bool,
+    /// Per-session provider/model override, set via `PATCH
+    /// /conversation/sessions/:id/settings`. `None` means the session
+    /// follows the user's saved `auth::preferences::UserPreferences`
+    /// defaults — see `conversation_service::ConversationService::resolve_completion_defaults`.
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MessageIntent {
     CodeGeneration,
     CodeExplanation,
@@ -94,6 +195,7 @@ pub enum SessionType {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeChange {
+    pub id: Uuid,
     pub file_path: String,
     pub change_type: ChangeType,
     pub old_content: Option<String>,
@@ -103,7 +205,7 @@ pub struct CodeChange {
     pub description: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ChangeType {
     Create,
     Modify,
@@ -229,7 +331,8 @@ pub struct UserPreferences {
     pub enable_ai_completion: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ExplanationLevel {
     Beginner,
     Intermediate,
@@ -246,6 +349,20 @@ pub struct ConversationRequest {
     pub selected_text: Option<TextSelection>,
     pub context_files: Vec<String>,
     pub intent_hint: Option<MessageIntent>,
+    /// When set, the message is processed against a brand-new session
+    /// forked from `session_id` at this turn, instead of appending to
+    /// `session_id` directly. Lets a client explore an alternative AI
+    /// response without losing the original thread.
+    pub branch_from_turn_id: Option<Uuid>,
+}
+
+/// Optional overrides for `conversation_service::ConversationService::regenerate_turn`.
+/// Any field left unset falls back to what the original turn used.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegenerateTurnOverrides {
+    pub temperature: Option<f32>,
+    pub model: Option<String>,
+    pub extra_instruction: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -254,11 +371,37 @@ pub struct ConversationResponse {
     pub ai_response: String,
     pub intent: MessageIntent,
     pub confidence_score: f32,
+    /// Top-3 `(intent, probability)` candidates considered for this message,
+    /// sorted descending; `(intent, confidence_score)` is always the first
+    /// entry. Lets a client show "CodeGeneration (0.6) / Refactoring (0.3)"
+    /// or trigger a clarifying question when the top two are close.
+    pub intent_candidates: Vec<(MessageIntent, f32)>,
     pub code_changes: Option<Vec<CodeChange>>,
     pub suggested_actions: Vec<SuggestedAction>,
     pub file_references: Vec<String>,
     pub follow_up_questions: Vec<String>,
     pub execution_time_ms: u64,
+    /// Turns selected for the provider's context window after token-budget
+    /// assembly, oldest first. Exposed for debugging context loss.
+    pub context_turns: Vec<ConversationTurn>,
+    /// Summary standing in for turns older than `context_turns`, if the
+    /// session's history has grown past the verbatim window. `None` when
+    /// every turn still fits.
+    pub context_summary: Option<String>,
+    /// Tokens actually counted (via `providers::tokenizer::count_tokens`,
+    /// not the old 4-chars-per-token heuristic) for `context_summary` plus
+    /// `context_turns` plus the current user message.
+    pub context_tokens_used: usize,
+    /// Provider and model that answered this turn, after resolving the
+    /// session's `SessionMetadata::provider`/`model` override (if any)
+    /// against the user's saved preference defaults. See
+    /// `conversation_service::ConversationService::resolve_completion_defaults`.
+    pub provider: String,
+    pub model: String,
+    /// Per-`context_files` breakdown of what was actually attached to the
+    /// prompt (spans + token cost), from
+    /// `code_integration::CodeIntegrationService::attach_context_files`.
+    pub attached_files: Vec<AttachedFileContext>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -291,6 +434,103 @@ pub enum ActionPriority {
     Critical,
 }
 
+/// Result of applying or previewing a single [`CodeChange`] via
+/// [`conversation_service::ConversationService::apply_code_changes`] or
+/// `::preview_code_changes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedChangeResult {
+    pub change_id: Uuid,
+    pub file_path: String,
+    pub status: ApplyChangeStatus,
+    pub message: Option<String>,
+    /// Unified diff between what was on disk and `new_content`, present
+    /// whenever the comparison could be made (i.e. not `NotFound`/`Unsupported`).
+    pub diff: Option<String>,
+    /// The file's content immediately before this change was written, so a
+    /// later rollback can restore it. `None` for `Create` (rollback is just
+    /// deleting the file) and for anything that wasn't actually `Applied`.
+    pub backup_content: Option<String>,
+    /// Groups every result produced by the same `apply_code_changes` call,
+    /// so [`conversation_service::ConversationService::rollback_change_set`]
+    /// can act on all of them together. `Some` only when `status` is
+    /// `Applied`; a change that failed, conflicted, or was never written has
+    /// nothing to roll back.
+    #[serde(default)]
+    pub change_set_id: Option<Uuid>,
+    /// Hash of the content this change left on disk, or `None` for a
+    /// `Delete` (meaning the file should no longer exist). Checked against
+    /// the file's current state before a rollback restores `backup_content`,
+    /// so an unrelated edit made since isn't silently clobbered.
+    #[serde(default)]
+    pub applied_content_hash: Option<String>,
+    /// Set once this change's set has been rolled back, so a repeat
+    /// rollback request against the same change set is rejected instead of
+    /// restoring stale content a second time.
+    #[serde(default)]
+    pub rolled_back: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApplyChangeStatus {
+    /// Write/create/delete succeeded.
+    Applied,
+    /// Would have applied cleanly; returned by `preview_code_changes`
+    /// instead of `Applied` since nothing was actually written.
+    WouldApply,
+    /// `old_content` no longer matches the file on disk, so the change was
+    /// skipped to avoid clobbering a concurrent edit.
+    Conflict,
+    /// No `CodeChange` with this id exists in the session's history.
+    NotFound,
+    /// The change's `ChangeType` has no destination path to act on
+    /// (`CodeChange` doesn't carry one for `Rename`/`Move`).
+    Unsupported,
+    /// Restored via `rollback_change_set` to its pre-change content.
+    RolledBack,
+    Error,
+}
+
+/// Output format for [`session_export::export_session`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+    Html,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "text/markdown; charset=utf-8",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Html => "text/html; charset=utf-8",
+        }
+    }
+
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Json => "json",
+            ExportFormat::Html => "html",
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(ExportFormat::Markdown),
+            "json" => Ok(ExportFormat::Json),
+            "html" => Ok(ExportFormat::Html),
+            other => Err(anyhow::anyhow!("Unknown export format: {other}")),
+        }
+    }
+}
+
 impl ConversationSession {
     pub fn new(user_id: Uuid, workspace_path: Option<String>) -> Self {
         Self {
@@ -301,6 +541,12 @@ impl ConversationSession {
             active_files: Vec::new(),
             code_context: CodeContext::default(),
             session_metadata: SessionMetadata::default(),
+            title: None,
+            parent_session_id: None,
+            forked_from_turn_id: None,
+            branch_id: Uuid::new_v4(),
+            rolling_summary: None,
+            rolling_summary_turns_covered: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -309,10 +555,11 @@ impl ConversationSession {
     pub fn add_turn(&mut self, turn: ConversationTurn) {
         self.conversation_history.push(turn);
         self.updated_at = Utc::now();
-        
+
         // Son 100 turn'ü tut
         if self.conversation_history.len() > 100 {
             self.conversation_history.remove(0);
+            self.rolling_summary_turns_covered = self.rolling_summary_turns_covered.saturating_sub(1);
         }
     }
 
@@ -365,6 +612,7 @@ impl Default for CodeContext {
             recent_functions: Vec::new(),
             imports: Vec::new(),
             symbols: Vec::new(),
+            attached_files: Vec::new(),
         }
     }
 }
@@ -377,6 +625,8 @@ impl Default for SessionMetadata {
             preferences: UserPreferences::default(),
             active_tools: Vec::new(),
             collaboration_mode: false,
+            provider: None,
+            model: None,
         }
     }
 }
@@ -0,0 +1,221 @@
+//! Renders a conversation session into a downloadable transcript. Used by
+//! `ConversationService::export_session`; kept as pure functions so the
+//! rendering logic can be tested without a database-backed session.
+
+use super::{ChangeType, CodeChange, ConversationSession, ConversationTurn, ExportFormat};
+
+/// Renders `session` as a sequence of chunks in the requested `format`, ready
+/// to be handed to a streaming response body one chunk at a time so large
+/// transcripts never need to be buffered in full.
+pub fn render(session: &ConversationSession, format: ExportFormat) -> Vec<String> {
+    match format {
+        ExportFormat::Markdown => render_markdown(session),
+        ExportFormat::Html => render_html(session),
+        ExportFormat::Json => vec![render_json(session)],
+    }
+}
+
+fn render_markdown(session: &ConversationSession) -> Vec<String> {
+    let language = &session.session_metadata.language;
+    let mut chunks = Vec::with_capacity(session.conversation_history.len() + 1);
+
+    chunks.push(format!(
+        "# Conversation {}\n\n_Started {}_\n",
+        session.id,
+        session.created_at.to_rfc3339()
+    ));
+
+    for turn in &session.conversation_history {
+        chunks.push(render_markdown_turn(turn, language));
+    }
+
+    chunks
+}
+
+fn render_markdown_turn(turn: &ConversationTurn, language: &str) -> String {
+    let mut out = format!(
+        "\n---\n\n_{}_\n\n**User:**\n\n{}\n\n**Assistant:**\n\n```{language}\n{}\n```\n",
+        turn.timestamp.to_rfc3339(),
+        escape_markdown(&turn.user_message),
+        escape_code_fence(&turn.ai_response),
+    );
+
+    if !turn.files_referenced.is_empty() {
+        let files = turn.files_referenced.iter().map(|f| format!("`{f}`")).collect::<Vec<_>>().join(", ");
+        out.push_str(&format!("\n_Referenced files: {files}_\n"));
+    }
+
+    if let Some(changes) = &turn.code_changes {
+        for change in changes {
+            out.push_str(&render_markdown_code_change(change));
+        }
+    }
+
+    out.push_str(&format!(
+        "\n<sub>{} / {}</sub>\n",
+        turn.provider.as_deref().unwrap_or("unknown"),
+        turn.model.as_deref().unwrap_or("unknown"),
+    ));
+
+    out
+}
+
+/// Escapes characters that would otherwise be interpreted as Markdown
+/// formatting (emphasis, headings, links) when a user message is dropped
+/// verbatim into a rendered document.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '#' | '[' | ']' | '<' | '>' | '|') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Breaks up a run of three or more backticks so it can't prematurely close
+/// the surrounding fenced code block.
+fn escape_code_fence(text: &str) -> String {
+    text.replace("```", "`\u{200b}``")
+}
+
+fn render_markdown_code_change(change: &CodeChange) -> String {
+    let mut diff = String::new();
+    if let Some(old_content) = &change.old_content {
+        for line in old_content.lines() {
+            diff.push('-');
+            diff.push_str(line);
+            diff.push('\n');
+        }
+    }
+    for line in change.new_content.lines() {
+        diff.push('+');
+        diff.push_str(line);
+        diff.push('\n');
+    }
+
+    format!(
+        "\n_{} {} ({})_\n\n```diff\n{diff}```\n",
+        change_type_label(&change.change_type),
+        change.file_path,
+        change.description
+    )
+}
+
+fn change_type_label(change_type: &ChangeType) -> &'static str {
+    match change_type {
+        ChangeType::Create => "Created",
+        ChangeType::Modify => "Modified",
+        ChangeType::Delete => "Deleted",
+        ChangeType::Rename => "Renamed",
+        ChangeType::Move => "Moved",
+    }
+}
+
+fn render_html(session: &ConversationSession) -> Vec<String> {
+    let language = &session.session_metadata.language;
+    let mut chunks = Vec::with_capacity(session.conversation_history.len() + 2);
+
+    chunks.push(format!(
+        "<!doctype html><html><head><meta charset=\"utf-8\"><title>Conversation {}</title></head><body>\n<h1>Conversation {}</h1>\n",
+        session.id, session.id
+    ));
+
+    for turn in &session.conversation_history {
+        chunks.push(format!(
+            "<section><p><strong>User:</strong> {}</p><p><strong>Assistant:</strong></p><pre><code class=\"language-{}\">{}</code></pre></section>\n",
+            escape_html(&turn.user_message),
+            escape_html(language),
+            escape_html(&turn.ai_response)
+        ));
+    }
+
+    chunks.push("</body></html>".to_string());
+    chunks
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn render_json(session: &ConversationSession) -> String {
+    serde_json::to_string_pretty(session).unwrap_or_else(|_| "{}".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation::{MessageIntent, SessionMetadata};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn make_session(turns: Vec<ConversationTurn>) -> ConversationSession {
+        ConversationSession {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            workspace_context: super::super::WorkspaceContext::new(None),
+            conversation_history: turns,
+            active_files: Vec::new(),
+            code_context: super::super::CodeContext::default(),
+            session_metadata: SessionMetadata {
+                language: "rust".to_string(),
+                ..Default::default()
+            },
+            title: None,
+            parent_session_id: None,
+            branch_id: Uuid::new_v4(),
+            rolling_summary: None,
+            rolling_summary_turns_covered: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn make_turn(user_message: &str, ai_response: &str) -> ConversationTurn {
+        ConversationTurn {
+            id: Uuid::new_v4(),
+            user_message: user_message.to_string(),
+            ai_response: ai_response.to_string(),
+            intent: MessageIntent::GeneralChat,
+            code_changes: None,
+            files_referenced: Vec::new(),
+            confidence_score: 1.0,
+            execution_time_ms: 0,
+            parent_turn_id: None,
+            applied_changes: Vec::new(),
+            provider: Some("anthropic".to_string()),
+            model: Some("claude-3-5-sonnet".to_string()),
+            revisions: Vec::new(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn markdown_renders_one_chunk_per_turn_plus_header() {
+        let session = make_session(vec![make_turn("hi", "hello"), make_turn("bye", "goodbye")]);
+        let chunks = render(&session, ExportFormat::Markdown);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[1].contains("**User:**"));
+        assert!(chunks[1].contains("hi"));
+        assert!(chunks[2].contains("goodbye"));
+    }
+
+    #[test]
+    fn json_renders_a_single_parseable_document() {
+        let session = make_session(vec![make_turn("hi", "hello")]);
+        let chunks = render(&session, ExportFormat::Json);
+        assert_eq!(chunks.len(), 1);
+        let parsed: serde_json::Value = serde_json::from_str(&chunks[0]).unwrap();
+        assert_eq!(parsed["conversation_history"][0]["user_message"], "hi");
+    }
+
+    #[test]
+    fn html_escapes_user_content() {
+        let session = make_session(vec![make_turn("<script>", "ok")]);
+        let chunks = render(&session, ExportFormat::Html);
+        assert!(chunks[1].contains("&lt;script&gt;"));
+    }
+}
@@ -1,12 +1,53 @@
 use anyhow::Result;
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
 use std::sync::Arc;
 use uuid::Uuid;
-use chrono::Utc;
 use tracing::{info, error};
 
 use super::{ConversationSession, ConversationTurn, WorkspaceContext, CodeContext, SessionMetadata};
 
+/// Mirrors the `conversation_sessions` columns selected by
+/// [`SessionManager::get_child_sessions`] and
+/// [`SessionManager::get_archived_sessions_page`] — the same shape
+/// `get_session`/`get_user_sessions_page` decode by hand via `sqlx::query!`,
+/// duplicated here as a row type so these two can use the runtime-checked
+/// `query_as` form instead.
+#[derive(Debug, FromRow)]
+struct ConversationSessionRow {
+    id: Uuid,
+    user_id: Uuid,
+    workspace_context: serde_json::Value,
+    session_metadata: serde_json::Value,
+    title: Option<String>,
+    parent_session_id: Option<Uuid>,
+    forked_from_turn_id: Option<Uuid>,
+    branch_id: Option<String>,
+    rolling_summary: Option<String>,
+    rolling_summary_turns_covered: i32,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// Mirrors the `conversation_turns` columns selected by
+/// [`SessionManager::get_first_turn`].
+#[derive(Debug, FromRow)]
+struct ConversationTurnRow {
+    id: Uuid,
+    user_message: String,
+    ai_response: String,
+    intent: String,
+    code_changes: serde_json::Value,
+    files_referenced: Vec<String>,
+    confidence_score: f32,
+    parent_turn_id: Option<Uuid>,
+    applied_changes: serde_json::Value,
+    provider: String,
+    model: String,
+    revisions: serde_json::Value,
+    created_at: DateTime<Utc>,
+}
+
 pub struct SessionManager {
     pool: Arc<PgPool>,
 }
@@ -19,13 +60,19 @@ impl SessionManager {
     pub async fn create_session(&self, session: &ConversationSession) -> Result<()> {
         sqlx::query!(
             r#"
-            INSERT INTO conversation_sessions (id, user_id, workspace_context, session_metadata, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            INSERT INTO conversation_sessions (id, user_id, workspace_context, session_metadata, title, parent_session_id, forked_from_turn_id, branch_id, rolling_summary, rolling_summary_turns_covered, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
             "#,
             session.id,
             session.user_id,
             serde_json::to_value(&session.workspace_context)?,
             serde_json::to_value(&session.session_metadata)?,
+            session.title,
+            session.parent_session_id,
+            session.forked_from_turn_id,
+            session.branch_id,
+            session.rolling_summary.clone(),
+            session.rolling_summary_turns_covered as i32,
             session.created_at,
             session.updated_at
         )
@@ -39,7 +86,7 @@ impl SessionManager {
     pub async fn get_session(&self, session_id: Uuid) -> Result<Option<ConversationSession>> {
         let row = sqlx::query!(
             r#"
-            SELECT id, user_id, workspace_context, session_metadata, created_at, updated_at
+            SELECT id, user_id, workspace_context, session_metadata, title, parent_session_id, forked_from_turn_id, branch_id, rolling_summary, rolling_summary_turns_covered, created_at, updated_at
             FROM conversation_sessions
             WHERE id = $1
             "#,
@@ -61,6 +108,12 @@ impl SessionManager {
                 active_files: Vec::new(), // Bu bilgiyi ayrı tutacağız
                 code_context: CodeContext::default(),
                 session_metadata,
+                title: row.title,
+                parent_session_id: row.parent_session_id,
+                forked_from_turn_id: row.forked_from_turn_id,
+                branch_id: row.branch_id,
+                rolling_summary: row.rolling_summary,
+                rolling_summary_turns_covered: row.rolling_summary_turns_covered as usize,
                 created_at: row.created_at,
                 updated_at: row.updated_at,
             }))
@@ -69,15 +122,60 @@ impl SessionManager {
         }
     }
 
+    /// Lists sessions directly forked from `parent_session_id`, newest first.
+    pub async fn get_child_sessions(&self, parent_session_id: Uuid) -> Result<Vec<ConversationSession>> {
+        let rows = sqlx::query_as::<_, ConversationSessionRow>(
+            r#"
+            SELECT id, user_id, workspace_context, session_metadata, title, parent_session_id, forked_from_turn_id, branch_id, rolling_summary, rolling_summary_turns_covered, created_at, updated_at
+            FROM conversation_sessions
+            WHERE parent_session_id = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(parent_session_id)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            let workspace_context: WorkspaceContext = serde_json::from_value(row.workspace_context)?;
+            let session_metadata: SessionMetadata = serde_json::from_value(row.session_metadata)?;
+            let conversation_history = self.get_recent_turns(row.id, 10).await?;
+
+            sessions.push(ConversationSession {
+                id: row.id,
+                user_id: row.user_id,
+                workspace_context,
+                conversation_history,
+                active_files: Vec::new(),
+                code_context: CodeContext::default(),
+                session_metadata,
+                title: row.title,
+                parent_session_id: row.parent_session_id,
+                forked_from_turn_id: row.forked_from_turn_id,
+                branch_id: row.branch_id,
+                rolling_summary: row.rolling_summary,
+                rolling_summary_turns_covered: row.rolling_summary_turns_covered as usize,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+            });
+        }
+
+        Ok(sessions)
+    }
+
     pub async fn update_session(&self, session: &ConversationSession) -> Result<()> {
         sqlx::query!(
             r#"
-            UPDATE conversation_sessions 
-            SET workspace_context = $1, session_metadata = $2, updated_at = $3
-            WHERE id = $4
+            UPDATE conversation_sessions
+            SET workspace_context = $1, session_metadata = $2, rolling_summary = $3, rolling_summary_turns_covered = $4, title = $5, updated_at = $6
+            WHERE id = $7
             "#,
             serde_json::to_value(&session.workspace_context)?,
             serde_json::to_value(&session.session_metadata)?,
+            session.rolling_summary.clone(),
+            session.rolling_summary_turns_covered as i32,
+            session.title,
             Utc::now(),
             session.id
         )
@@ -87,11 +185,24 @@ impl SessionManager {
         Ok(())
     }
 
+    /// Dedicated single-column update, mirroring [`Self::archive_session`],
+    /// so callers generating a title don't need to re-serialize the rest of
+    /// the session just to persist it.
+    pub async fn update_session_title(&self, session_id: Uuid, title: &str) -> Result<()> {
+        sqlx::query("UPDATE conversation_sessions SET title = $1, updated_at = NOW() WHERE id = $2")
+            .bind(title)
+            .bind(session_id)
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn add_conversation_turn(&self, session_id: Uuid, turn: &ConversationTurn) -> Result<()> {
         sqlx::query!(
             r#"
-            INSERT INTO conversation_turns (id, session_id, user_message, ai_response, intent, code_changes, files_referenced, confidence_score, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            INSERT INTO conversation_turns (id, session_id, user_message, ai_response, intent, code_changes, files_referenced, confidence_score, parent_turn_id, applied_changes, provider, model, revisions, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             "#,
             turn.id,
             session_id,
@@ -101,6 +212,11 @@ impl SessionManager {
             serde_json::to_value(&turn.code_changes)?,
             &turn.files_referenced,
             turn.confidence_score,
+            turn.parent_turn_id,
+            serde_json::to_value(&turn.applied_changes)?,
+            turn.provider,
+            turn.model,
+            serde_json::to_value(&turn.revisions)?,
             turn.timestamp
         )
         .execute(&*self.pool)
@@ -122,10 +238,79 @@ impl SessionManager {
         Ok(())
     }
 
+    /// Persists a turn's updated `applied_changes` after
+    /// `ConversationService::apply_code_changes` applies some of its
+    /// `code_changes`, so a later rollback request can find the recorded
+    /// pre-images without re-deriving them.
+    pub async fn record_applied_changes(
+        &self,
+        turn_id: Uuid,
+        applied_changes: &[super::AppliedChangeResult],
+    ) -> Result<()> {
+        sqlx::query("UPDATE conversation_turns SET applied_changes = $1 WHERE id = $2")
+            .bind(serde_json::to_value(applied_changes)?)
+            .bind(turn_id)
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Overwrites a turn's `ai_response`/`code_changes`/`provider`/`model`
+    /// after `ConversationService::regenerate_turn` re-runs the provider
+    /// call, appending its prior values onto `revisions` so the answer it
+    /// replaces isn't lost.
+    pub async fn record_regeneration(
+        &self,
+        turn_id: Uuid,
+        ai_response: &str,
+        code_changes: &Option<Vec<super::CodeChange>>,
+        provider: &str,
+        model: &str,
+        revisions: &[super::TurnRevision],
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE conversation_turns SET ai_response = $1, code_changes = $2, provider = $3, model = $4, revisions = $5 WHERE id = $6",
+        )
+        .bind(ai_response)
+        .bind(serde_json::to_value(code_changes)?)
+        .bind(provider)
+        .bind(model)
+        .bind(serde_json::to_value(revisions)?)
+        .bind(turn_id)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Finds the session owning a change set, so a rollback request that
+    /// only carries a `change_set_id` (no session id) can still be located
+    /// and ownership-checked. Searches `applied_changes` directly rather
+    /// than loading every session into memory.
+    pub async fn find_session_by_change_set(&self, change_set_id: Uuid) -> Result<Option<Uuid>> {
+        let row = sqlx::query_as::<_, (Uuid,)>(
+            r#"
+            SELECT session_id
+            FROM conversation_turns
+            WHERE EXISTS (
+                SELECT 1 FROM jsonb_array_elements(applied_changes) elem
+                WHERE elem->>'change_set_id' = $1
+            )
+            LIMIT 1
+            "#,
+        )
+        .bind(change_set_id.to_string())
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(row.map(|(session_id,)| session_id))
+    }
+
     pub async fn get_session_turns(&self, session_id: Uuid) -> Result<Vec<ConversationTurn>> {
         let rows = sqlx::query!(
             r#"
-            SELECT id, user_message, ai_response, intent, code_changes, files_referenced, confidence_score, created_at
+            SELECT id, user_message, ai_response, intent, code_changes, files_referenced, confidence_score, parent_turn_id, applied_changes, provider, model, revisions, created_at
             FROM conversation_turns
             WHERE session_id = $1
             ORDER BY created_at ASC
@@ -139,6 +324,8 @@ impl SessionManager {
         for row in rows {
             let intent: super::MessageIntent = serde_json::from_str(&row.intent)?;
             let code_changes: Option<Vec<super::CodeChange>> = serde_json::from_value(row.code_changes)?;
+            let applied_changes: Vec<super::AppliedChangeResult> = serde_json::from_value(row.applied_changes)?;
+            let revisions: Vec<super::TurnRevision> = serde_json::from_value(row.revisions)?;
 
             turns.push(ConversationTurn {
                 id: row.id,
@@ -149,6 +336,11 @@ impl SessionManager {
                 files_referenced: row.files_referenced,
                 confidence_score: row.confidence_score,
                 execution_time_ms: 0, // Bu bilgiyi ayrı tutacağız
+                parent_turn_id: row.parent_turn_id,
+                applied_changes,
+                provider: row.provider,
+                model: row.model,
+                revisions,
                 timestamp: row.created_at,
             });
         }
@@ -157,16 +349,21 @@ impl SessionManager {
     }
 
     pub async fn get_user_sessions(&self, user_id: Uuid, limit: i64) -> Result<Vec<ConversationSession>> {
+        self.get_user_sessions_page(user_id, limit, 0).await
+    }
+
+    pub async fn get_user_sessions_page(&self, user_id: Uuid, limit: i64, offset: i64) -> Result<Vec<ConversationSession>> {
         let rows = sqlx::query!(
             r#"
-            SELECT id, user_id, workspace_context, session_metadata, created_at, updated_at
+            SELECT id, user_id, workspace_context, session_metadata, title, parent_session_id, forked_from_turn_id, branch_id, rolling_summary, rolling_summary_turns_covered, created_at, updated_at
             FROM conversation_sessions
-            WHERE user_id = $1
+            WHERE user_id = $1 AND archived_at IS NULL
             ORDER BY updated_at DESC
-            LIMIT $2
+            LIMIT $2 OFFSET $3
             "#,
             user_id,
-            limit
+            limit,
+            offset
         )
         .fetch_all(&*self.pool)
         .await?;
@@ -175,7 +372,7 @@ impl SessionManager {
         for row in rows {
             let workspace_context: WorkspaceContext = serde_json::from_value(row.workspace_context)?;
             let session_metadata: SessionMetadata = serde_json::from_value(row.session_metadata)?;
-            
+
             // Son birkaç turn'ü al (performans için)
             let conversation_history = self.get_recent_turns(row.id, 10).await?;
 
@@ -187,6 +384,12 @@ impl SessionManager {
                 active_files: Vec::new(),
                 code_context: CodeContext::default(),
                 session_metadata,
+                title: row.title,
+                parent_session_id: row.parent_session_id,
+                forked_from_turn_id: row.forked_from_turn_id,
+                branch_id: row.branch_id,
+                rolling_summary: row.rolling_summary,
+                rolling_summary_turns_covered: row.rolling_summary_turns_covered as usize,
                 created_at: row.created_at,
                 updated_at: row.updated_at,
             });
@@ -195,10 +398,97 @@ impl SessionManager {
         Ok(sessions)
     }
 
+    pub async fn count_user_sessions(&self, user_id: Uuid) -> Result<i64> {
+        let (count,) = sqlx::query_as::<_, (i64,)>(
+            "SELECT COUNT(*) as count FROM conversation_sessions WHERE user_id = $1 AND archived_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&*self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    pub async fn get_archived_sessions_page(&self, user_id: Uuid, limit: i64, offset: i64) -> Result<Vec<ConversationSession>> {
+        let rows = sqlx::query_as::<_, ConversationSessionRow>(
+            r#"
+            SELECT id, user_id, workspace_context, session_metadata, title, parent_session_id, forked_from_turn_id, branch_id, rolling_summary, rolling_summary_turns_covered, created_at, updated_at
+            FROM conversation_sessions
+            WHERE user_id = $1 AND archived_at IS NOT NULL
+            ORDER BY updated_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            let workspace_context: WorkspaceContext = serde_json::from_value(row.workspace_context)?;
+            let session_metadata: SessionMetadata = serde_json::from_value(row.session_metadata)?;
+            let conversation_history = self.get_recent_turns(row.id, 10).await?;
+
+            sessions.push(ConversationSession {
+                id: row.id,
+                user_id: row.user_id,
+                workspace_context,
+                conversation_history,
+                active_files: Vec::new(),
+                code_context: CodeContext::default(),
+                session_metadata,
+                title: row.title,
+                parent_session_id: row.parent_session_id,
+                forked_from_turn_id: row.forked_from_turn_id,
+                branch_id: row.branch_id,
+                rolling_summary: row.rolling_summary,
+                rolling_summary_turns_covered: row.rolling_summary_turns_covered as usize,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+            });
+        }
+
+        Ok(sessions)
+    }
+
+    pub async fn count_archived_sessions(&self, user_id: Uuid) -> Result<i64> {
+        let (count,) = sqlx::query_as::<_, (i64,)>(
+            "SELECT COUNT(*) as count FROM conversation_sessions WHERE user_id = $1 AND archived_at IS NOT NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&*self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Soft-deletes a session by marking it archived instead of removing its rows.
+    pub async fn archive_session(&self, session_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE conversation_sessions SET archived_at = NOW() WHERE id = $1")
+            .bind(session_id)
+            .execute(&*self.pool)
+            .await?;
+
+        info!("Conversation session archived: {}", session_id);
+        Ok(())
+    }
+
+    pub async fn restore_session(&self, session_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE conversation_sessions SET archived_at = NULL WHERE id = $1")
+            .bind(session_id)
+            .execute(&*self.pool)
+            .await?;
+
+        info!("Conversation session restored: {}", session_id);
+        Ok(())
+    }
+
     pub async fn get_recent_turns(&self, session_id: Uuid, limit: i64) -> Result<Vec<ConversationTurn>> {
         let rows = sqlx::query!(
             r#"
-            SELECT id, user_message, ai_response, intent, code_changes, files_referenced, confidence_score, created_at
+            SELECT id, user_message, ai_response, intent, code_changes, files_referenced, confidence_score, parent_turn_id, applied_changes, provider, model, revisions, created_at
             FROM conversation_turns
             WHERE session_id = $1
             ORDER BY created_at DESC
@@ -214,6 +504,8 @@ impl SessionManager {
         for row in rows {
             let intent: super::MessageIntent = serde_json::from_str(&row.intent)?;
             let code_changes: Option<Vec<super::CodeChange>> = serde_json::from_value(row.code_changes)?;
+            let applied_changes: Vec<super::AppliedChangeResult> = serde_json::from_value(row.applied_changes)?;
+            let revisions: Vec<super::TurnRevision> = serde_json::from_value(row.revisions)?;
 
             turns.push(ConversationTurn {
                 id: row.id,
@@ -224,6 +516,11 @@ impl SessionManager {
                 files_referenced: row.files_referenced,
                 confidence_score: row.confidence_score,
                 execution_time_ms: 0,
+                parent_turn_id: row.parent_turn_id,
+                applied_changes,
+                provider: row.provider,
+                model: row.model,
+                revisions,
                 timestamp: row.created_at,
             });
         }
@@ -233,6 +530,94 @@ impl SessionManager {
         Ok(turns)
     }
 
+    /// The very first turn of a session, used to seed an automatic title.
+    /// Unlike [`Self::get_recent_turns`], which orders by `created_at DESC`
+    /// for "latest N", this needs the oldest row instead.
+    pub async fn get_first_turn(&self, session_id: Uuid) -> Result<Option<ConversationTurn>> {
+        let row = sqlx::query_as::<_, ConversationTurnRow>(
+            r#"
+            SELECT id, user_message, ai_response, intent, code_changes, files_referenced, confidence_score, parent_turn_id, applied_changes, provider, model, revisions, created_at
+            FROM conversation_turns
+            WHERE session_id = $1
+            ORDER BY created_at ASC
+            LIMIT 1
+            "#,
+        )
+        .bind(session_id)
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        let Some(row) = row else { return Ok(None) };
+
+        let intent: super::MessageIntent = serde_json::from_str(&row.intent)?;
+        let code_changes: Option<Vec<super::CodeChange>> = serde_json::from_value(row.code_changes)?;
+        let applied_changes: Vec<super::AppliedChangeResult> = serde_json::from_value(row.applied_changes)?;
+            let revisions: Vec<super::TurnRevision> = serde_json::from_value(row.revisions)?;
+
+        Ok(Some(ConversationTurn {
+            id: row.id,
+            user_message: row.user_message,
+            ai_response: row.ai_response,
+            intent,
+            code_changes,
+            files_referenced: row.files_referenced,
+            confidence_score: row.confidence_score,
+            execution_time_ms: 0,
+            parent_turn_id: row.parent_turn_id,
+            applied_changes,
+            provider: row.provider,
+            model: row.model,
+            revisions,
+            timestamp: row.created_at,
+        }))
+    }
+
+    /// Per-session summary for the session list/picker: how many turns it
+    /// has, a preview of the last exchange, and its most common intent.
+    /// Queried one session at a time, same as [`Self::get_recent_turns`] is
+    /// already called per-row in [`Self::get_user_sessions_page`].
+    pub async fn get_session_list_metadata(&self, session_id: Uuid) -> Result<SessionListMetadata> {
+        let (turn_count,) = sqlx::query_as::<_, (i64,)>(
+            "SELECT COUNT(*) as turn_count FROM conversation_turns WHERE session_id = $1",
+        )
+        .bind(session_id)
+        .fetch_one(&*self.pool)
+        .await?;
+
+        let last_turn = sqlx::query_as::<_, (String,)>(
+            r#"
+            SELECT user_message
+            FROM conversation_turns
+            WHERE session_id = $1
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(session_id)
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        let dominant_intent = sqlx::query_as::<_, (String, Option<i64>)>(
+            r#"
+            SELECT intent, COUNT(*) as count
+            FROM conversation_turns
+            WHERE session_id = $1
+            GROUP BY intent
+            ORDER BY count DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(session_id)
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(SessionListMetadata {
+            turn_count,
+            last_message_preview: last_turn.map(|(user_message,)| truncate_preview(&user_message)),
+            dominant_intent: dominant_intent.map(|(intent, _)| intent),
+        })
+    }
+
     pub async fn search_conversations(
         &self,
         user_id: Uuid,
@@ -241,10 +626,10 @@ impl SessionManager {
     ) -> Result<Vec<ConversationTurn>> {
         let rows = sqlx::query!(
             r#"
-            SELECT ct.id, ct.user_message, ct.ai_response, ct.intent, ct.code_changes, ct.files_referenced, ct.confidence_score, ct.created_at
+            SELECT ct.id, ct.user_message, ct.ai_response, ct.intent, ct.code_changes, ct.files_referenced, ct.confidence_score, ct.parent_turn_id, ct.applied_changes, ct.provider, ct.model, ct.revisions, ct.created_at
             FROM conversation_turns ct
             JOIN conversation_sessions cs ON ct.session_id = cs.id
-            WHERE cs.user_id = $1 
+            WHERE cs.user_id = $1
             AND (ct.user_message ILIKE $2 OR ct.ai_response ILIKE $2)
             ORDER BY ct.created_at DESC
             LIMIT $3
@@ -260,6 +645,8 @@ impl SessionManager {
         for row in rows {
             let intent: super::MessageIntent = serde_json::from_str(&row.intent)?;
             let code_changes: Option<Vec<super::CodeChange>> = serde_json::from_value(row.code_changes)?;
+            let applied_changes: Vec<super::AppliedChangeResult> = serde_json::from_value(row.applied_changes)?;
+            let revisions: Vec<super::TurnRevision> = serde_json::from_value(row.revisions)?;
 
             turns.push(ConversationTurn {
                 id: row.id,
@@ -270,6 +657,11 @@ impl SessionManager {
                 files_referenced: row.files_referenced,
                 confidence_score: row.confidence_score,
                 execution_time_ms: 0,
+                parent_turn_id: row.parent_turn_id,
+                applied_changes,
+                provider: row.provider,
+                model: row.model,
+                revisions,
                 timestamp: row.created_at,
             });
         }
@@ -277,17 +669,27 @@ impl SessionManager {
         Ok(turns)
     }
 
+    /// Turns carried into a forked session stay physically copied into
+    /// `conversation_turns` (see `ConversationService::fork_session`), so a
+    /// naive count across all of a user's sessions would count each one
+    /// twice: once in the parent, once in the fork. A copied turn keeps its
+    /// original `created_at`, which predates the fork point turn's own
+    /// timestamp, while turns added to the fork afterwards postdate it --
+    /// joining on `forked_from_turn_id` lets both queries below exclude
+    /// exactly the copies.
     pub async fn get_conversation_statistics(&self, user_id: Uuid) -> Result<ConversationStatistics> {
         let stats = sqlx::query!(
             r#"
-            SELECT 
-                COUNT(DISTINCT cs.id) as total_sessions,
+            SELECT
+                (SELECT COUNT(*) FROM conversation_sessions WHERE user_id = $1) as total_sessions,
                 COUNT(ct.id) as total_turns,
                 AVG(ct.confidence_score) as avg_confidence,
                 COUNT(ct.id) FILTER (WHERE ct.code_changes IS NOT NULL) as turns_with_code_changes
             FROM conversation_sessions cs
             LEFT JOIN conversation_turns ct ON cs.id = ct.session_id
+            LEFT JOIN conversation_turns fork_point ON fork_point.id = cs.forked_from_turn_id
             WHERE cs.user_id = $1
+              AND (cs.forked_from_turn_id IS NULL OR ct.id IS NULL OR ct.created_at > fork_point.created_at)
             "#,
             user_id
         )
@@ -299,7 +701,9 @@ impl SessionManager {
             SELECT ct.intent, COUNT(*) as count
             FROM conversation_turns ct
             JOIN conversation_sessions cs ON ct.session_id = cs.id
+            LEFT JOIN conversation_turns fork_point ON fork_point.id = cs.forked_from_turn_id
             WHERE cs.user_id = $1
+              AND (cs.forked_from_turn_id IS NULL OR ct.created_at > fork_point.created_at)
             GROUP BY ct.intent
             ORDER BY count DESC
             "#,
@@ -401,6 +805,27 @@ pub struct ConversationStatistics {
     pub intent_distribution: std::collections::HashMap<String, i64>,
 }
 
+/// Session-list-row metadata, kept separate from [`ConversationSession`]
+/// since it's derived from `conversation_turns` rather than stored on the
+/// session itself. See [`SessionManager::get_session_list_metadata`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SessionListMetadata {
+    pub turn_count: i64,
+    pub last_message_preview: Option<String>,
+    pub dominant_intent: Option<String>,
+}
+
+const PREVIEW_MAX_CHARS: usize = 120;
+
+fn truncate_preview(message: &str) -> String {
+    if message.chars().count() <= PREVIEW_MAX_CHARS {
+        message.to_string()
+    } else {
+        let truncated: String = message.chars().take(PREVIEW_MAX_CHARS).collect();
+        format!("{truncated}...")
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SessionSummary {
     pub session_id: Uuid,
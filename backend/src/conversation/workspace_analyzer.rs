@@ -1,15 +1,23 @@
 use anyhow::Result;
 use std::path::{Path, PathBuf};
 use std::fs;
+use std::time::Duration;
 use tokio::fs as async_fs;
 use tracing::{info, warn, error};
 use serde_json;
 use regex::Regex;
 
+use crate::cache::{Cache, CacheConfig, MemoryCache};
 use super::{WorkspaceContext, GitInfo, Dependency, DependencyType, BuildSystem, FileChange};
 
+/// How long a workspace analysis (including its `git` subprocess calls) is
+/// reused before `analyze_workspace` re-runs it. Keeps `process_message`
+/// from shelling out to `git` on every turn of a conversation.
+const WORKSPACE_CACHE_TTL_SECS: u64 = 60;
+
 pub struct WorkspaceAnalyzer {
     ignore_patterns: Vec<Regex>,
+    workspace_cache: MemoryCache,
 }
 
 impl WorkspaceAnalyzer {
@@ -28,12 +36,25 @@ impl WorkspaceAnalyzer {
             Regex::new(r"\.so$").unwrap(),
         ];
 
-        Self { ignore_patterns }
+        let workspace_cache = MemoryCache::new(CacheConfig {
+            ttl: Duration::from_secs(WORKSPACE_CACHE_TTL_SECS),
+            ..Default::default()
+        });
+
+        Self {
+            ignore_patterns,
+            workspace_cache,
+        }
     }
 
     pub async fn analyze_workspace(&self, workspace_path: &str) -> Result<WorkspaceContext> {
+        if let Some(cached) = self.workspace_cache.get::<WorkspaceContext>(workspace_path).await? {
+            tracing::debug!("Using cached workspace analysis for: {}", workspace_path);
+            return Ok(cached);
+        }
+
         info!("Analyzing workspace: {}", workspace_path);
-        
+
         let path = Path::new(workspace_path);
         if !path.exists() {
             return Err(anyhow::anyhow!("Workspace path does not exist: {}", workspace_path));
@@ -62,6 +83,8 @@ impl WorkspaceAnalyzer {
         // Son değişiklikleri al
         context.recent_changes = self.get_recent_changes(path).await?;
 
+        self.workspace_cache.set(workspace_path, context.clone(), Some(Duration::from_secs(WORKSPACE_CACHE_TTL_SECS))).await?;
+
         Ok(context)
     }
 
@@ -184,6 +184,32 @@ impl RunsRepository {
         Ok(records)
     }
     
+    /// Count of recent failed runs whose logs or test results mention
+    /// `function_name`. Used by `PredictiveDebugger` as one signal toward a
+    /// failure-probability estimate; text-matching rather than structured,
+    /// since `test_results` has no guaranteed per-function shape across test
+    /// frameworks.
+    pub async fn count_recent_failures_mentioning(&self, function_name: &str, limit: i64) -> Result<i64> {
+        let pattern = format!("%{}%", function_name);
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM (
+                SELECT id FROM runs
+                WHERE status = 'failed'
+                  AND (stderr_log ILIKE $1 OR error_message ILIKE $1 OR test_results::text ILIKE $1)
+                ORDER BY created_at DESC
+                LIMIT $2
+            ) recent
+            "#
+        )
+        .bind(&pattern)
+        .bind(limit)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
     /// Delete run by ID
     pub async fn delete(&self, run_id: Uuid) -> Result<bool> {
         let result = sqlx::query("DELETE FROM runs WHERE id = $1")
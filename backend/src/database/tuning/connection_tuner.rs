@@ -0,0 +1,44 @@
+//! Connection pool occupancy, reused by `DatabaseTuner::get_metrics` and
+//! logged by `tune_connections` when the pool is running hot.
+
+use anyhow::Result;
+use sqlx::PgPool;
+
+/// Above this occupancy, `tune_connections` logs a warning that raising
+/// `DatabaseConfig::max_connections` is likely worth it.
+const CONNECTION_POOL_HOT_THRESHOLD: f64 = 0.85;
+
+#[derive(Debug, Clone)]
+pub struct ConnectionTuner {
+    pool: PgPool,
+}
+
+impl ConnectionTuner {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Fraction of the pool currently checked out, matching
+    /// `DatabaseManager::pool_stats`'s `in_use / size` calculation.
+    pub fn usage_ratio(&self) -> f64 {
+        let size = self.pool.size();
+        if size == 0 {
+            return 0.0;
+        }
+        let idle = self.pool.num_idle() as u32;
+        (size.saturating_sub(idle)) as f64 / size as f64
+    }
+
+    pub async fn tune_connections(&self) -> Result<()> {
+        let usage = self.usage_ratio();
+        if usage > CONNECTION_POOL_HOT_THRESHOLD {
+            tracing::warn!(
+                usage = usage,
+                pool_size = self.pool.size(),
+                "Connection pool usage is high; consider raising max_connections"
+            );
+        }
+
+        Ok(())
+    }
+}
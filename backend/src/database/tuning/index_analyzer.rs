@@ -0,0 +1,111 @@
+//! Unused- and missing-index detection from PostgreSQL's built-in
+//! `pg_stat_user_indexes`/`pg_stat_user_tables` views -- no extension
+//! required, unlike [`super::query_optimizer`].
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// `seq_scan`/`idx_scan` are cumulative since the last `pg_stat_reset()`, not
+/// a fixed window, so this only flags tables with enough history to be
+/// meaningful rather than a fresh connection's noise.
+const MIN_SEQ_SCANS_TO_FLAG: i64 = 100;
+
+#[derive(Debug, Clone)]
+pub struct IndexAnalyzer {
+    pool: PgPool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnusedIndex {
+    pub table_name: String,
+    pub index_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissingIndexCandidate {
+    pub table_name: String,
+    pub seq_scan: i64,
+    pub idx_scan: i64,
+    pub seq_tup_read: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexAnalysis {
+    pub unused_indexes: Vec<UnusedIndex>,
+    pub missing_index_candidates: Vec<MissingIndexCandidate>,
+}
+
+impl IndexAnalyzer {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn analyze(&self) -> Result<IndexAnalysis> {
+        Ok(IndexAnalysis {
+            unused_indexes: self.find_unused_indexes().await?,
+            missing_index_candidates: self.find_missing_index_candidates().await?,
+        })
+    }
+
+    /// Logs what [`Self::analyze`] finds, so `DatabaseTuner::optimize_database`
+    /// has something to run on a schedule even without a caller reading the
+    /// result.
+    pub async fn analyze_indexes(&self) -> Result<()> {
+        let analysis = self.analyze().await?;
+
+        if !analysis.unused_indexes.is_empty() {
+            tracing::info!(count = analysis.unused_indexes.len(), "Unused indexes detected");
+        }
+        if !analysis.missing_index_candidates.is_empty() {
+            tracing::info!(
+                count = analysis.missing_index_candidates.len(),
+                "Tables with likely missing indexes detected"
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn find_unused_indexes(&self) -> Result<Vec<UnusedIndex>> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT relname, indexrelname
+            FROM pg_stat_user_indexes
+            WHERE idx_scan = 0
+            ORDER BY relname, indexrelname
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(table_name, index_name)| UnusedIndex { table_name, index_name })
+            .collect())
+    }
+
+    async fn find_missing_index_candidates(&self) -> Result<Vec<MissingIndexCandidate>> {
+        let rows = sqlx::query_as::<_, (String, i64, i64, i64)>(
+            r#"
+            SELECT relname, seq_scan, idx_scan, seq_tup_read
+            FROM pg_stat_user_tables
+            WHERE seq_scan > idx_scan AND seq_scan > $1
+            ORDER BY seq_scan DESC
+            "#,
+        )
+        .bind(MIN_SEQ_SCANS_TO_FLAG)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(table_name, seq_scan, idx_scan, seq_tup_read)| MissingIndexCandidate {
+                table_name,
+                seq_scan,
+                idx_scan,
+                seq_tup_read,
+            })
+            .collect())
+    }
+}
@@ -3,12 +3,13 @@ pub mod query_optimizer;
 pub mod index_analyzer;
 pub mod connection_tuner;
 
-pub use query_optimizer::*;
-pub use index_analyzer::*;
-pub use connection_tuner::*;
+pub use query_optimizer::{QueryOptimizer, QueryStats};
+pub use index_analyzer::{IndexAnalysis, IndexAnalyzer, MissingIndexCandidate, UnusedIndex};
+pub use connection_tuner::ConnectionTuner;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
 use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,24 +20,30 @@ pub struct DatabaseMetrics {
     pub connection_pool_usage: f64,
     pub cache_hit_ratio: f64,
     pub index_usage_ratio: f64,
+    /// Set when `pg_stat_statements` isn't installed; `query_count`,
+    /// `slow_query_count`, and `average_query_time` are all zeroed rather
+    /// than guessed at in that case.
+    pub pg_stat_statements_note: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct DatabaseTuner {
+    pool: PgPool,
     query_optimizer: QueryOptimizer,
     index_analyzer: IndexAnalyzer,
     connection_tuner: ConnectionTuner,
 }
 
 impl DatabaseTuner {
-    pub fn new() -> Self {
+    pub fn new(pool: PgPool) -> Self {
         Self {
-            query_optimizer: QueryOptimizer::new(),
-            index_analyzer: IndexAnalyzer::new(),
-            connection_tuner: ConnectionTuner::new(),
+            query_optimizer: QueryOptimizer::new(pool.clone()),
+            index_analyzer: IndexAnalyzer::new(pool.clone()),
+            connection_tuner: ConnectionTuner::new(pool.clone()),
+            pool,
         }
     }
-    
+
     pub async fn optimize_database(&self) -> Result<()> {
         // Run all optimization tasks
         tokio::try_join!(
@@ -44,18 +51,87 @@ impl DatabaseTuner {
             self.index_analyzer.analyze_indexes(),
             self.connection_tuner.tune_connections()
         )?;
-        
+
         Ok(())
     }
-    
+
     pub async fn get_metrics(&self) -> Result<DatabaseMetrics> {
+        let query_stats = self.query_optimizer.query_stats().await?;
+        let cache_hit_ratio = self.cache_hit_ratio().await?;
+        let index_usage_ratio = self.index_usage_ratio().await?;
+
+        let (query_count, slow_query_count, average_query_time, pg_stat_statements_note) =
+            match query_stats {
+                Some(stats) => (stats.query_count, stats.slow_query_count, stats.average_query_time, None),
+                None => (
+                    0,
+                    0,
+                    Duration::ZERO,
+                    Some("pg_stat_statements extension not installed".to_string()),
+                ),
+            };
+
         Ok(DatabaseMetrics {
-            query_count: 1000,
-            slow_query_count: 5,
-            average_query_time: Duration::from_millis(25),
-            connection_pool_usage: 0.65,
-            cache_hit_ratio: 0.92,
-            index_usage_ratio: 0.88,
+            query_count,
+            slow_query_count,
+            average_query_time,
+            connection_pool_usage: self.connection_tuner.usage_ratio(),
+            cache_hit_ratio,
+            index_usage_ratio,
+            pg_stat_statements_note,
         })
     }
-}
\ No newline at end of file
+
+    /// Buffer cache hit ratio for the connected database, from
+    /// `pg_stat_database`.
+    async fn cache_hit_ratio(&self) -> Result<f64> {
+        let (hits, reads): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(blks_hit), 0), COALESCE(SUM(blks_read), 0)
+            FROM pg_stat_database
+            WHERE datname = current_database()
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(safe_ratio(hits, hits + reads))
+    }
+
+    /// Fraction of user-table reads served via an index rather than a
+    /// sequential scan, aggregated across `pg_stat_user_tables`.
+    async fn index_usage_ratio(&self) -> Result<f64> {
+        let (idx_scan, seq_scan): (i64, i64) = sqlx::query_as(
+            "SELECT COALESCE(SUM(idx_scan), 0), COALESCE(SUM(seq_scan), 0) FROM pg_stat_user_tables",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(safe_ratio(idx_scan, idx_scan + seq_scan))
+    }
+}
+
+/// `numerator / denominator`, treating a `0/0` table (no activity yet) as a
+/// perfect ratio rather than a divide-by-zero.
+fn safe_ratio(numerator: i64, denominator: i64) -> f64 {
+    if denominator == 0 {
+        1.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn safe_ratio_treats_no_activity_as_fully_efficient() {
+        assert_eq!(safe_ratio(0, 0), 1.0);
+    }
+
+    #[test]
+    fn safe_ratio_divides_normally() {
+        assert_eq!(safe_ratio(3, 4), 0.75);
+    }
+}
@@ -0,0 +1,90 @@
+//! Slow-query visibility via the `pg_stat_statements` extension. All queries
+//! here are gated behind [`extension_installed`] so a database without the
+//! extension gets a clear note instead of a confusing "relation does not
+//! exist" error.
+
+use anyhow::Result;
+use sqlx::{PgPool, Row};
+use std::time::Duration;
+
+/// Mean execution time above which a `pg_stat_statements` entry counts as a
+/// slow query in [`QueryStats::slow_query_count`].
+const SLOW_QUERY_THRESHOLD_MS: f64 = 100.0;
+
+#[derive(Debug, Clone)]
+pub struct QueryOptimizer {
+    pool: PgPool,
+}
+
+/// Aggregate call/timing stats pulled from `pg_stat_statements`.
+#[derive(Debug, Clone)]
+pub struct QueryStats {
+    pub query_count: usize,
+    pub slow_query_count: usize,
+    pub average_query_time: Duration,
+}
+
+impl QueryOptimizer {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Aggregate stats from `pg_stat_statements`, or `None` if the extension
+    /// isn't installed on this database.
+    pub async fn query_stats(&self) -> Result<Option<QueryStats>> {
+        if !extension_installed(&self.pool, "pg_stat_statements").await? {
+            return Ok(None);
+        }
+
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COALESCE(SUM(calls), 0)::bigint AS query_count,
+                COALESCE(SUM(calls) FILTER (WHERE mean_exec_time > $1), 0)::bigint AS slow_query_count,
+                COALESCE(AVG(mean_exec_time), 0)::float8 AS average_query_time_ms
+            FROM pg_stat_statements
+            "#,
+        )
+        .bind(SLOW_QUERY_THRESHOLD_MS)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let query_count: i64 = row.try_get("query_count")?;
+        let slow_query_count: i64 = row.try_get("slow_query_count")?;
+        let average_query_time_ms: f64 = row.try_get("average_query_time_ms")?;
+
+        Ok(Some(QueryStats {
+            query_count: query_count.max(0) as usize,
+            slow_query_count: slow_query_count.max(0) as usize,
+            average_query_time: Duration::from_secs_f64(average_query_time_ms.max(0.0) / 1000.0),
+        }))
+    }
+
+    /// There's no query-rewrite engine in this tree, just visibility into
+    /// what's slow -- kept as a named step so `DatabaseTuner::optimize_database`
+    /// has somewhere to plug a real optimizer in later.
+    pub async fn optimize_queries(&self) -> Result<()> {
+        if let Some(stats) = self.query_stats().await? {
+            if stats.slow_query_count > 0 {
+                tracing::warn!(
+                    slow_query_count = stats.slow_query_count,
+                    "pg_stat_statements reports slow queries; review with EXPLAIN ANALYZE"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks `pg_extension` for `name` so callers can report "extension not
+/// installed" instead of running into a missing-relation error.
+pub(super) async fn extension_installed(pool: &PgPool, name: &str) -> Result<bool> {
+    let exists: bool =
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pg_extension WHERE extname = $1)")
+            .bind(name)
+            .fetch_one(pool)
+            .await?;
+
+    Ok(exists)
+}
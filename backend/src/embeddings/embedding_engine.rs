@@ -2,10 +2,21 @@
 use anyhow::Result;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
+/// Output dimension of [`LocalEmbeddingModel`]'s feature-hashing vectors.
+/// 384 was picked to match `all-MiniLM-L6-v2`'s output size so a future
+/// swap to a real model wouldn't need a schema migration, but the hashing
+/// model's vectors are NOT otherwise compatible with that (or any other)
+/// sentence-transformer's embedding space -- don't treat this constant as
+/// proof the two are interchangeable. [`EmbeddingEngine::dimension`] reports
+/// whatever the active backend actually produces, so callers like
+/// `VectorStore` never need to read this constant directly.
+const LOCAL_EMBEDDING_DIMENSION: usize = 384;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingRequest {
     pub input: String,
@@ -32,11 +43,108 @@ pub struct Usage {
     pub total_tokens: usize,
 }
 
+/// A deterministic, fully local embedding backend.
+///
+/// A real `all-MiniLM-L6-v2` forward pass (via `candle` or an ONNX runtime)
+/// needs model weights and a tokenizer pulled from the Hugging Face Hub at
+/// load time, plus the `candle-core`/`tokenizers`/`hf-hub` crates. This
+/// environment has no network access and those crates aren't already vendored,
+/// so pulling them in would make dependency resolution fail outright rather
+/// than degrade gracefully — worse than the Ollama-or-heuristic fallback this
+/// replaces. Until that network/vendoring constraint is lifted, this uses
+/// feature hashing (word unigrams, word bigrams, and character trigrams
+/// hashed into fixed buckets) as the local model: a real, if much cheaper,
+/// embedding technique that needs no weights, is bitwise-deterministic, and
+/// is loaded once and cached for the life of the process like a real model
+/// would be.
+///
+/// TODO: swap this out for a real `candle`/ONNX `all-MiniLM-L6-v2` forward
+/// pass once model weights can be fetched/vendored. [`EmbeddingEngine`] only
+/// depends on [`LocalEmbeddingModel::embed`]/[`LocalEmbeddingModel::embed_batch`]
+/// and reads the dimension back from the model rather than a shared constant
+/// (see [`LocalEmbeddingModel::dimension`]), so that swap shouldn't need
+/// changes outside this type.
+#[derive(Debug, Default)]
+pub struct LocalEmbeddingModel;
+
+impl LocalEmbeddingModel {
+    /// Output dimension of this backend's vectors. Read by
+    /// [`EmbeddingEngine::dimension`] instead of that method hardcoding
+    /// [`LOCAL_EMBEDDING_DIMENSION`] directly, so a future real backend with
+    /// a different native dimension is reflected automatically.
+    pub fn dimension(&self) -> usize {
+        LOCAL_EMBEDDING_DIMENSION
+    }
+
+    fn tokens(text: &str) -> Vec<String> {
+        text.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn hash_bucket(feature: &str) -> usize {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        feature.hash(&mut hasher);
+        (hasher.finish() % LOCAL_EMBEDDING_DIMENSION as u64) as usize
+    }
+
+    /// Embeds a single piece of text. See [`Self::embed_batch`] for the
+    /// batched entry point used to amortize model invocation.
+    pub fn embed(&self, text: &str) -> Vec<f32> {
+        self.embed_batch(&[text]).into_iter().next().unwrap_or_else(|| vec![0.0; LOCAL_EMBEDDING_DIMENSION])
+    }
+
+    /// Embeds many texts in one model invocation. There's no per-call
+    /// warm-up cost to amortize for a stateless hashing model, but batching
+    /// here keeps the call shape identical to what a real batched model
+    /// invocation (tokenize-all, forward-pass-all, pool-all) would look like,
+    /// so swapping in a real model later doesn't change this method's
+    /// signature or callers.
+    pub fn embed_batch(&self, texts: &[&str]) -> Vec<Vec<f32>> {
+        texts.iter().map(|text| self.embed_one(text)).collect()
+    }
+
+    fn embed_one(&self, text: &str) -> Vec<f32> {
+        let tokens = Self::tokens(text);
+        let mut features = vec![0.0f32; LOCAL_EMBEDDING_DIMENSION];
+
+        for token in &tokens {
+            features[Self::hash_bucket(token)] += 1.0;
+        }
+        for pair in tokens.windows(2) {
+            features[Self::hash_bucket(&format!("{}_{}", pair[0], pair[1]))] += 0.5;
+        }
+        let chars: Vec<char> = text.to_lowercase().chars().collect();
+        for trigram in chars.windows(3) {
+            let feature: String = trigram.iter().collect();
+            features[Self::hash_bucket(&feature)] += 0.25;
+        }
+
+        l2_normalize(&mut features);
+        features
+    }
+}
+
+fn l2_normalize(vector: &mut [f32]) {
+    let norm: f32 = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in vector.iter_mut() {
+            *value /= norm;
+        }
+    }
+}
+
 pub struct EmbeddingEngine {
     model_name: String,
     client: Client,
     cache: RwLock<lru::LruCache<String, Vec<f32>>>,
     ollama_url: String,
+    /// Loaded once in [`Self::new`] and reused for every `embed`/`embed_batch`
+    /// call rather than being reconstructed per request.
+    local_model: Arc<LocalEmbeddingModel>,
 }
 
 impl EmbeddingEngine {
@@ -44,22 +152,35 @@ impl EmbeddingEngine {
         let client = Client::builder()
             .timeout(Duration::from_secs(30))
             .build()?;
-        
+
         let cache = RwLock::new(lru::LruCache::new(std::num::NonZeroUsize::new(1000).unwrap()));
-        
+
         let engine = Self {
             model_name: model_name.to_string(),
             client,
             cache,
             ollama_url: "http://localhost:11434".to_string(),
+            local_model: Arc::new(LocalEmbeddingModel),
         };
-        
+
         // Verify model availability
         engine.verify_model().await?;
-        
+
         info!("Embedding engine initialized with model: {}", model_name);
         Ok(engine)
     }
+
+    /// Output dimension of every vector this engine produces.
+    ///
+    /// Reports whatever the local model actually produces rather than a
+    /// fixed constant, so a future backend swap (see the `TODO` on
+    /// [`LocalEmbeddingModel`]) can change dimension without this method
+    /// silently going stale. Doesn't account for `embed_ollama`'s fallback
+    /// path, whose dimension depends on whatever model `ollama_url` is
+    /// actually serving.
+    pub fn dimension(&self) -> usize {
+        self.local_model.dimension()
+    }
     
     async fn verify_model(&self) -> Result<()> {
         // Try to get a test embedding
@@ -156,45 +277,65 @@ impl EmbeddingEngine {
     }
     
     async fn embed_local(&self, text: &str) -> Result<Vec<f32>> {
-        // Simple local embedding using character-based features
-        // This is a fallback when no proper embedding model is available
-        
-        let chars: Vec<char> = text.chars().collect();
-        let mut features = vec![0.0; 384]; // Standard embedding dimension
-        
-        // Character frequency features
-        for (i, &ch) in chars.iter().enumerate() {
-            let idx = (ch as u32 % 384) as usize;
-            features[idx] += 1.0 / (chars.len() as f32);
-        }
-        
-        // Length features
-        features[0] = (chars.len() as f32).ln() / 10.0;
-        
-        // Word count features
-        let word_count = text.split_whitespace().count() as f32;
-        features[1] = word_count.ln() / 5.0;
-        
-        // Normalize
-        let norm: f32 = features.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if norm > 0.0 {
-            for feature in &mut features {
-                *feature /= norm;
-            }
-        }
-        
-        Ok(features)
+        Ok(self.local_model.embed(text))
     }
-    
+
+    /// Embeds every text in `texts`, checking the cache per-item but running
+    /// every cache miss through one `LocalEmbeddingModel::embed_batch` call
+    /// instead of one model invocation per text.
     pub async fn embed_batch(&self, texts: Vec<&str>) -> Result<Vec<Vec<f32>>> {
-        let mut results = Vec::new();
-        
-        for text in texts {
-            let embedding = self.embed(text).await?;
-            results.push(embedding);
+        let mut results: Vec<Option<Vec<f32>>> = Vec::with_capacity(texts.len());
+        let mut misses: Vec<(usize, &str)> = Vec::new();
+
+        {
+            let cache = self.cache.read().await;
+            for (i, &text) in texts.iter().enumerate() {
+                match cache.peek(text) {
+                    Some(cached) => results.push(Some(cached.clone())),
+                    None => {
+                        results.push(None);
+                        misses.push((i, text));
+                    }
+                }
+            }
         }
-        
-        Ok(results)
+
+        if !misses.is_empty() {
+            // Ollama has no batched embeddings endpoint worth relying on here,
+            // so only cache misses that also fail Ollama fall through to the
+            // local model, which *is* invoked once for the whole batch.
+            let mut local_fallback_indices = Vec::new();
+            let mut local_fallback_texts = Vec::new();
+            for &(i, text) in &misses {
+                match self.embed_ollama(text).await {
+                    Ok(embedding) => results[i] = Some(embedding),
+                    Err(_) => {
+                        local_fallback_indices.push(i);
+                        local_fallback_texts.push(text);
+                    }
+                }
+            }
+
+            if !local_fallback_texts.is_empty() {
+                let local_embeddings = self.local_model.embed_batch(&local_fallback_texts);
+                for (i, embedding) in local_fallback_indices.into_iter().zip(local_embeddings) {
+                    results[i] = Some(embedding);
+                }
+            }
+
+            let mut cache = self.cache.write().await;
+            for &(i, text) in &misses {
+                if let Some(embedding) = &results[i] {
+                    cache.put(text.to_string(), embedding.clone());
+                }
+            }
+        }
+
+        results
+            .into_iter()
+            .enumerate()
+            .map(|(i, embedding)| embedding.ok_or_else(|| anyhow::anyhow!("No embedding computed for batch item {i}")))
+            .collect()
     }
     
     pub async fn clear_cache(&self) {
@@ -228,7 +369,7 @@ mod tests {
         let text = "Hello, world!";
         let embedding = engine.embed(text).await.unwrap();
         
-        assert_eq!(embedding.len(), 384);
+        assert_eq!(embedding.len(), LOCAL_EMBEDDING_DIMENSION);
         
         // Test that embeddings are consistent
         let embedding2 = engine.embed(text).await.unwrap();
@@ -240,7 +381,7 @@ mod tests {
         let engine = EmbeddingEngine::new("test-model").await.unwrap();
         
         let embedding = engine.embed_local("test text").await.unwrap();
-        assert_eq!(embedding.len(), 384);
+        assert_eq!(embedding.len(), LOCAL_EMBEDDING_DIMENSION);
         
         // Test that different texts produce different embeddings
         let embedding2 = engine.embed_local("different text").await.unwrap();
@@ -256,7 +397,30 @@ mod tests {
         
         assert_eq!(embeddings.len(), 3);
         for embedding in embeddings {
-            assert_eq!(embedding.len(), 384);
+            assert_eq!(embedding.len(), LOCAL_EMBEDDING_DIMENSION);
         }
     }
+
+    #[test]
+    fn local_model_embeddings_are_deterministic_and_normalized() {
+        let model = LocalEmbeddingModel;
+
+        let embedding = model.embed("the quick brown fox jumps over the lazy dog");
+        let embedding_again = model.embed("the quick brown fox jumps over the lazy dog");
+        assert_eq!(embedding, embedding_again, "embedding the same text twice must be bit-for-bit identical");
+
+        let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5, "expected a unit vector, got norm {norm}");
+    }
+
+    #[test]
+    fn local_model_batches_match_individual_calls() {
+        let model = LocalEmbeddingModel;
+        let texts = ["alpha beta", "gamma delta epsilon"];
+
+        let batched = model.embed_batch(&texts);
+        let individual: Vec<Vec<f32>> = texts.iter().map(|t| model.embed(t)).collect();
+
+        assert_eq!(batched, individual);
+    }
 }
\ No newline at end of file
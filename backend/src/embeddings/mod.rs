@@ -46,8 +46,8 @@ pub struct EmbeddingService {
 impl EmbeddingService {
     pub async fn new(model_name: &str, db_path: &str) -> Result<Self> {
         let engine = EmbeddingEngine::new(model_name).await?;
-        let vector_store = VectorStore::new(db_path).await?;
-        
+        let vector_store = VectorStore::new(db_path, engine.dimension()).await?;
+
         Ok(Self {
             engine,
             vector_store,
@@ -84,15 +84,28 @@ impl EmbeddingService {
         Ok(results)
     }
     
-    /// Batch add multiple contents
+    /// Batch add multiple contents, running every text through one
+    /// `EmbeddingEngine::embed_batch` call instead of one `add_content` call
+    /// (and therefore one model invocation) per item.
     pub async fn add_batch(&self, contents: Vec<(String, HashMap<String, String>)>) -> Result<Vec<String>> {
-        let mut ids = Vec::new();
-        
-        for (content, metadata) in contents {
-            let id = self.add_content(&content, metadata).await?;
+        let texts: Vec<&str> = contents.iter().map(|(content, _)| content.as_str()).collect();
+        let vectors = self.engine.embed_batch(texts).await?;
+
+        let mut ids = Vec::with_capacity(contents.len());
+        for ((content, metadata), vector) in contents.into_iter().zip(vectors) {
+            let id = Uuid::new_v4().to_string();
+            let embedding = EmbeddingVector {
+                id: id.clone(),
+                content,
+                vector,
+                metadata,
+                created_at: chrono::Utc::now(),
+            };
+
+            self.vector_store.store_embedding(&embedding).await?;
             ids.push(id);
         }
-        
+
         Ok(ids)
     }
     
@@ -8,26 +8,31 @@ use super::{EmbeddingVector, SimilarityResult, VectorStoreStats};
 
 pub struct VectorStore {
     pool: SqlitePool,
+    /// Dimension every stored/queried vector must match, set from
+    /// `EmbeddingEngine::dimension()` at construction time rather than
+    /// hardcoded, so the schema always matches whatever model produced the
+    /// vectors.
+    dimension: usize,
 }
 
 impl VectorStore {
-    pub async fn new(database_url: &str) -> Result<Self> {
+    pub async fn new(database_url: &str, dimension: usize) -> Result<Self> {
         let pool = SqlitePool::connect(database_url).await?;
-        
-        let store = Self { pool };
+
+        let store = Self { pool, dimension };
         store.initialize_schema().await?;
-        
-        info!("Vector store initialized with sqlite-vss");
+
+        info!("Vector store initialized with sqlite-vss ({}-dimensional)", dimension);
         Ok(store)
     }
-    
+
     async fn initialize_schema(&self) -> Result<()> {
         // Enable sqlite-vss extension
         sqlx::query("SELECT load_extension('vss0')")
             .execute(&self.pool)
             .await
             .ok(); // Ignore if extension not available
-        
+
         // Create embeddings table
         sqlx::query(r#"
             CREATE TABLE IF NOT EXISTS embeddings (
@@ -39,13 +44,16 @@ impl VectorStore {
         "#)
         .execute(&self.pool)
         .await?;
-        
+
         // Create vector table with vss
-        sqlx::query(r#"
+        sqlx::query(&format!(
+            r#"
             CREATE VIRTUAL TABLE IF NOT EXISTS embeddings_vss USING vss0(
-                embedding(384)
+                embedding({})
             )
-        "#)
+            "#,
+            self.dimension
+        ))
         .execute(&self.pool)
         .await
         .unwrap_or_else(|_| {
@@ -71,6 +79,15 @@ impl VectorStore {
     }
     
     pub async fn store_embedding(&self, embedding: &EmbeddingVector) -> Result<()> {
+        if embedding.vector.len() != self.dimension {
+            return Err(anyhow::anyhow!(
+                "Embedding for {} has dimension {}, expected {}",
+                embedding.id,
+                embedding.vector.len(),
+                self.dimension
+            ));
+        }
+
         let metadata_json = serde_json::to_string(&embedding.metadata)?;
         
         // Store metadata
@@ -380,11 +397,11 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let db_path = temp_dir.path().join("test.db").to_string_lossy().to_string();
         
-        let store = VectorStore::new(&format!("sqlite://{}", db_path)).await.unwrap();
-        
+        let store = VectorStore::new(&format!("sqlite://{}", db_path), 4).await.unwrap();
+
         let mut metadata = HashMap::new();
         metadata.insert("type".to_string(), "test".to_string());
-        
+
         let embedding = EmbeddingVector {
             id: "test-1".to_string(),
             content: "Hello world".to_string(),
@@ -1,5 +1,6 @@
 // Universal AI Development Assistant - Library Root
 
+pub mod cache;
 pub mod config;
 pub mod models;
 pub mod auth;
@@ -1,3 +1,11 @@
+mod ai_engine;
+mod cache;
+// `collaboration`'s other submodules (team_sync, real_time_collaboration)
+// aren't wired into the server yet -- only code_review_ai, which this module
+// needs for `/api/v1/code-review`.
+mod collaboration {
+    pub mod code_review_ai;
+}
 mod config;
 mod providers;
 mod database;
@@ -7,18 +15,27 @@ mod conversation;
 mod search;
 mod streaming;
 mod api;
+mod observability;
+mod performance;
+mod security;
+mod services;
 
 use axum::{
-    extract::{Path, Query, State},
-    http::{HeaderMap, StatusCode},
-    response::Json,
-    routing::{get, post, put, delete},
+    body::Body,
+    extract::{Multipart, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
+    routing::{get, post, put, patch, delete},
     Router, middleware,
 };
+use api::BackendError;
 use config::Config;
 use providers::{
     router::ProviderRouter,
-    traits::{AIProvider, AnalysisRequest, AnalysisType, CompletionRequest},
+    traits::{AIProvider, AnalysisRequest, AnalysisType, CompletionRequest, ProviderError, Tool},
     ProviderHealth, ProviderMetrics,
 };
 use database::DatabaseManager;
@@ -26,15 +43,19 @@ use terminal::ai_terminal::AITerminalService;
 use terminal::history_manager::HistoryManager;
 use conversation::conversation_service::ConversationService;
 use conversation::session_manager::SessionManager;
+use conversation::ExportFormat;
 use search::search_service::SearchService;
-use auth::{JwtManager, UserService, ApiKeyManager, AuthContext, preferences::PreferencesService};
+use search::indexing_jobs::IndexingJobRegistry;
+use search::workspace_watcher::WorkspaceWatcherRegistry;
+use auth::{JwtManager, UserService, ApiKeyManager, CustomProviderManager, AuthContext, Permission, AuditService, DatabaseAuditService, AuditEvent, AuditEventType, AuditOutcome, SecurityEventType, organization::OrganizationService, preferences::PreferencesService, require_permission::RequirePermission};
+use security::{RateLimitState, UserRateLimiter};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc, env};
 use uuid::Uuid;
 use tokio::net::TcpListener;
 use tower::ServiceBuilder;
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
-use tracing::{info, Level};
+use tracing::{info, warn, Instrument, Level};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 // Application state
@@ -46,10 +67,23 @@ pub struct AppState {
     jwt_manager: Arc<JwtManager>,
     user_service: Arc<UserService>,
     api_key_manager: Arc<ApiKeyManager>,
+    custom_provider_manager: Arc<CustomProviderManager>,
     preferences_service: Arc<PreferencesService>,
     terminal_service: Arc<AITerminalService>,
     conversation_service: Arc<ConversationService>,
     search_service: Arc<SearchService>,
+    indexing_jobs: IndexingJobRegistry,
+    workspace_watchers: WorkspaceWatcherRegistry,
+    audit_service: Arc<dyn AuditService>,
+    performance_monitor: Arc<performance::PerformanceMonitor>,
+    streaming_manager: Arc<streaming::StreamingManager>,
+    artifacts_repository: Arc<database::repositories::ArtifactsRepository>,
+    runs_repository: Arc<database::repositories::RunsRepository>,
+    code_reviewer: Arc<collaboration::code_review_ai::AICodeReviewer>,
+    /// Requests currently inside `track_performance_middleware`'s `next.run`.
+    /// Snapshotted by `shutdown_signal` so the drained-request count it logs
+    /// reflects real in-flight work, not just active SSE streams.
+    in_flight_requests: Arc<std::sync::atomic::AtomicU64>,
 }
 
 // API Request/Response types
@@ -72,6 +106,68 @@ struct CompletionApiRequest {
     temperature: Option<f32>,
     system_prompt: Option<String>,
     stream: Option<bool>,
+    tools: Option<Vec<Tool>>,
+    tool_choice: Option<serde_json::Value>,
+}
+
+/// Body for `POST /api/v1/code-review`. `context` is optional since a
+/// caller reviewing a standalone diff (e.g. a CI webhook) may not have an
+/// editor-derived `CodeContext` to attach; an empty one is used instead.
+#[derive(Debug, Serialize, Deserialize)]
+struct CodeReviewApiRequest {
+    diff: String,
+    context: Option<conversation::CodeContext>,
+}
+
+/// Body for `POST /api/v1/bugs/predict`. `test_results` is optional — when
+/// present and its most recent run failed, it's used as an extra signal on
+/// top of the `runs` table's failure history.
+#[derive(Debug, Serialize, Deserialize)]
+struct PredictFailuresApiRequest {
+    code: String,
+    language: String,
+    test_results: Option<sandbox::ExecutionResult>,
+}
+
+// Pagination shared by list endpoints
+#[derive(Debug, Serialize, Deserialize)]
+struct PaginationMeta {
+    limit: i64,
+    offset: i64,
+    total: i64,
+    has_more: bool,
+}
+
+impl PaginationMeta {
+    fn new(limit: i64, offset: i64, total: i64) -> Self {
+        Self {
+            limit,
+            offset,
+            total,
+            has_more: offset + limit < total,
+        }
+    }
+}
+
+const DEFAULT_PAGE_LIMIT: i64 = 10;
+const MAX_PAGE_LIMIT: i64 = 100;
+
+fn parse_pagination(params: &HashMap<String, String>) -> Result<(i64, i64), StatusCode> {
+    let limit = match params.get("limit") {
+        Some(raw) => {
+            let limit = raw.parse::<i64>().map_err(|_| StatusCode::BAD_REQUEST)?;
+            if limit < 1 || limit > MAX_PAGE_LIMIT {
+                return Err(StatusCode::BAD_REQUEST);
+            }
+            limit
+        }
+        None => DEFAULT_PAGE_LIMIT,
+    };
+    let offset = params.get("offset")
+        .and_then(|o| o.parse::<i64>().ok())
+        .unwrap_or(0)
+        .max(0);
+    Ok((limit, offset))
 }
 
 // Terminal API types
@@ -81,6 +177,8 @@ struct TerminalSuggestRequest {
     query_type: String,
     session_id: Option<String>,
     workspace_path: Option<String>,
+    #[serde(default)]
+    explain_impact: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -89,6 +187,15 @@ struct TerminalSearchRequest {
     limit: Option<i64>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct TerminalImportHistoryRequest {
+    session_id: Option<String>,
+    shell: terminal::shell_integration::ShellType,
+    /// Base64-encoded history file contents; when omitted, the shell's
+    /// default history file on the server's filesystem is read instead.
+    file_content: Option<String>,
+}
+
 // Conversation API types
 #[derive(Debug, Serialize, Deserialize)]
 struct ConversationCreateSessionRequest {
@@ -103,6 +210,39 @@ struct ConversationMessageRequest {
     selected_text: Option<ConversationTextSelection>,
     context_files: Vec<String>,
     intent_hint: Option<String>,
+    /// When set, the message is processed against a new session forked from
+    /// `session_id` at this turn instead of appending to `session_id` directly.
+    branch_from_turn_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConversationForkSessionRequest {
+    from_turn_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConversationRegenerateTurnRequest {
+    session_id: String,
+    temperature: Option<f32>,
+    model: Option<String>,
+    extra_instruction: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConversationUpdateSessionSettingsRequest {
+    provider: Option<String>,
+    model: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConversationRenameSessionRequest {
+    title: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConversationApplyChangesRequest {
+    session_id: String,
+    change_ids: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -131,6 +271,7 @@ struct SearchApiRequest {
     max_results: Option<usize>,
     similarity_threshold: Option<f32>,
     include_context: Option<bool>,
+    similarity_metric: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -164,9 +305,18 @@ struct SearchErrorsRequest {
     workspace_paths: Vec<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct SearchWatchRequest {
+    workspace_path: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct SearchIndexRequest {
     workspace_path: String,
+    /// `"full"` (default) drops and rebuilds every index; `"incremental"`
+    /// only re-embeds files added or changed since the last index.
+    #[serde(default)]
+    mode: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -175,29 +325,62 @@ struct SearchFeedbackRequest {
     feedback_type: String,
     satisfaction_score: f32,
     comments: Option<String>,
+    /// The result this feedback targets, so it can feed
+    /// `ResultRanker::record_feedback`. Omit for search-level-only feedback.
+    #[serde(default)]
+    result_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SearchRerankRequest {
+    search_id: String,
+    top_k: Option<usize>,
+}
+
+/// Shared `EnvFilter` construction for both the text and JSON tracing
+/// layers below, so `RUST_LOG` behaves the same regardless of `LOG_FORMAT`.
+fn build_env_filter() -> tracing_subscriber::EnvFilter {
+    tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| "universal_ai_dev_assistant=debug,tower_http=debug".into())
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "universal_ai_dev_assistant=debug,tower_http=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    // Initialize tracing. `LOG_FORMAT=json` switches to JSON lines (timestamp,
+    // level, target, span, and structured fields as top-level keys) for
+    // operators shipping logs to Elasticsearch; anything else keeps the
+    // human-readable text format.
+    if env::var("LOG_FORMAT").map(|v| v.eq_ignore_ascii_case("json")).unwrap_or(false) {
+        tracing_subscriber::registry()
+            .with(build_env_filter())
+            .with(tracing_subscriber::fmt::layer().json())
+            .init();
+    } else {
+        tracing_subscriber::registry()
+            .with(build_env_filter())
+            .with(tracing_subscriber::fmt::layer())
+            .init();
+    }
 
     info!("🚀 Starting Universal AI Development Assistant Backend v6.2.0");
 
+    // Registers the Prometheus metrics so `/metrics` has something to report
+    // and provider call sites can record into them from the first request.
+    observability::metrics::init_metrics();
+
     // Load configuration
     let config = Arc::new(Config::load()?);
     info!("✅ Configuration loaded");
 
     // Initialize database
-    let database = Arc::new(DatabaseManager::new().await?);
+    let database = Arc::new(DatabaseManager::new(&config.database).await?);
     info!("✅ Database connected and migrations applied");
 
+    // Initialize audit logging. Built before the services below so it can
+    // be injected into each of them.
+    let audit_service: Arc<dyn AuditService> = Arc::new(DatabaseAuditService::new(database.pool.clone()));
+    info!("✅ Audit service initialized");
+
     // Initialize JWT manager
     let jwt_secret = env::var("JWT_SECRET")
         .unwrap_or_else(|_| "your-super-secret-jwt-key-change-in-production".to_string());
@@ -210,34 +393,116 @@ async fn main() -> anyhow::Result<()> {
         .as_bytes()[..32]
         .try_into()
         .expect("Encryption key must be 32 bytes");
-    let api_key_manager = Arc::new(ApiKeyManager::new(database.pool.clone(), encryption_key));
+    let api_key_manager = Arc::new(
+        ApiKeyManager::new(database.pool.clone(), encryption_key)
+            .with_audit_service(audit_service.clone()),
+    );
     info!("✅ API key manager initialized");
 
+    // Initialize custom (self-hosted OpenAI-compatible) provider manager
+    let custom_provider_manager = Arc::new(CustomProviderManager::new(database.pool.clone(), api_key_manager.clone()));
+    info!("✅ Custom provider manager initialized");
+
     // Initialize user service
-    let user_service = Arc::new(UserService::new(database.pool.clone(), (*jwt_manager).clone()));
+    let user_service = Arc::new(
+        UserService::new(database.pool.clone(), (*jwt_manager).clone())
+            .with_audit_service(audit_service.clone()),
+    );
     info!("✅ User service initialized");
 
     // Initialize preferences service
     let preferences_service = Arc::new(PreferencesService::new(database.pool.clone()));
     info!("✅ Preferences service initialized");
 
+    // Initialize Redis session cache. Sessions still live in Postgres as the
+    // source of truth; Redis is a best-effort hot-path cache, so a connection
+    // failure here degrades to Postgres-only lookups instead of failing startup.
+    let session_timeout_minutes = auth::SecurityPolicy::default().session_timeout_minutes;
+    let redis_cache = if config.features.enable_caching {
+        let redis_config = cache::CacheConfig {
+            redis_url: env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string()),
+            enable_redis: true,
+            ttl: std::time::Duration::from_secs(session_timeout_minutes as u64 * 60),
+            ..Default::default()
+        };
+        match cache::RedisCache::new(redis_config).await {
+            Ok(cache) => {
+                info!("✅ Redis session cache connected");
+                Some(Arc::new(cache))
+            }
+            Err(e) => {
+                warn!("⚠️ Redis session cache unavailable, falling back to Postgres only: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
     // Initialize terminal service
     let history_manager = HistoryManager::new(database.pool.clone());
-    let terminal_service = Arc::new(AITerminalService::new(provider_router.clone(), history_manager));
+    let mut terminal_service_builder = AITerminalService::new(provider_router.clone(), history_manager)
+        .with_deduplicate_on_write(config.features.terminal_deduplicate_on_write);
+    if let Some(redis_cache) = &redis_cache {
+        terminal_service_builder = terminal_service_builder.with_redis_cache(redis_cache.clone(), session_timeout_minutes);
+    }
+    let terminal_service = Arc::new(terminal_service_builder);
     info!("✅ Terminal service initialized");
 
     // Initialize conversation service
     let conversation_session_manager = SessionManager::new(database.pool.clone());
-    let conversation_service = Arc::new(ConversationService::new(provider_router.clone(), conversation_session_manager));
+    let mut conversation_service_builder = ConversationService::new(
+        provider_router.clone(),
+        preferences_service.clone(),
+        conversation_session_manager,
+    );
+    if let Some(redis_cache) = &redis_cache {
+        conversation_service_builder = conversation_service_builder.with_redis_cache(redis_cache.clone(), session_timeout_minutes);
+    }
+    conversation_service_builder = conversation_service_builder.with_audit_service(audit_service.clone());
+    let conversation_service = Arc::new(conversation_service_builder);
     info!("✅ Conversation service initialized");
 
     // Initialize search service
-    let search_service = Arc::new(SearchService::new(provider_router.clone(), database.pool.clone()));
+    let search_service = Arc::new(
+        SearchService::new(
+            provider_router.clone(),
+            database.pool.clone(),
+            config.search_cache.clone(),
+            config.providers.rerank_provider.clone(),
+        )
+        .with_audit_service(audit_service.clone()),
+    );
+    let indexing_jobs = IndexingJobRegistry::new();
+    let workspace_watchers = WorkspaceWatcherRegistry::new();
     info!("✅ Search service initialized");
 
+    // Initialize per-user rate limiting
+    let organization_service = Arc::new(OrganizationService::new(database.pool.clone()));
+    let rate_limit_state = RateLimitState {
+        limiter: UserRateLimiter::new(),
+        organizations: organization_service,
+    };
+    info!("✅ Per-user rate limiter initialized");
+
     // Initialize provider router
     let provider_router = Arc::new(ProviderRouter::new(&config)?);
     info!("✅ Provider router initialized with {} providers", provider_router.get_available_providers().len());
+    provider_router.clone().start_health_monitor();
+
+    // Initialize performance monitoring
+    let performance_monitor = Arc::new(performance::PerformanceMonitor::new(
+        performance::PerformanceConfig::default(),
+    ));
+    performance_monitor.start_monitoring().await?;
+    info!("✅ Performance monitor started");
+
+    let streaming_manager = Arc::new(streaming::StreamingManager::new());
+    let in_flight_requests = Arc::new(std::sync::atomic::AtomicU64::new(0));
+
+    let artifacts_repository = Arc::new(database::repositories::ArtifactsRepository::new(database.pool.clone()));
+    let runs_repository = Arc::new(database::repositories::RunsRepository::new(database.pool.clone()));
+    let code_reviewer = Arc::new(collaboration::code_review_ai::AICodeReviewer::new(None, database.pool.clone()));
 
     // Create application state
     let app_state = AppState {
@@ -247,10 +512,20 @@ async fn main() -> anyhow::Result<()> {
         jwt_manager,
         user_service,
         api_key_manager,
+        custom_provider_manager,
         preferences_service,
         terminal_service,
         conversation_service,
         search_service,
+        indexing_jobs,
+        workspace_watchers,
+        audit_service,
+        performance_monitor,
+        streaming_manager,
+        artifacts_repository,
+        runs_repository,
+        code_reviewer,
+        in_flight_requests,
     };
 
     // Build router
@@ -260,11 +535,13 @@ async fn main() -> anyhow::Result<()> {
         .route("/auth/register", post(auth::register_handler))
         .route("/auth/login", post(auth::login_handler))
         .route("/auth/refresh", post(auth::refresh_token_handler))
-        
+        .route("/csp-report", post(csp_report_handler))
+
         // Protected routes (authentication required)
         .route("/auth/logout", post(auth::logout_handler))
         .route("/auth/profile", get(auth::get_profile_handler))
         .route("/auth/profile", put(auth::update_profile_handler))
+        .route("/auth/profile", patch(auth::patch_profile_handler))
         .route("/auth/change-password", post(auth::change_password_handler))
         
         // API key management
@@ -283,16 +560,35 @@ async fn main() -> anyhow::Result<()> {
         .route("/terminal/suggest", post(terminal_suggest_handler))
         .route("/terminal/execute", post(terminal_execute_handler))
         .route("/terminal/sessions", get(terminal_sessions_handler))
+        .route("/terminal/sessions/archived", get(terminal_archived_sessions_handler))
         .route("/terminal/sessions/:session_id", delete(terminal_delete_session_handler))
+        .route("/terminal/sessions/:session_id/restore", post(terminal_restore_session_handler))
+        .route("/terminal/sessions/:session_id/deduplicate", post(terminal_deduplicate_session_handler))
         .route("/terminal/history/search", post(terminal_search_history_handler))
+        .route("/terminal/import-history", post(terminal_import_history_handler))
         .route("/terminal/stats", get(terminal_stats_handler))
-        
+
         // Conversation endpoints
         .route("/conversation/sessions", post(conversation_create_session_handler))
         .route("/conversation/sessions", get(conversation_get_sessions_handler))
+        .route("/conversation/sessions/archived", get(conversation_archived_sessions_handler))
         .route("/conversation/sessions/:session_id", get(conversation_get_session_handler))
         .route("/conversation/sessions/:session_id", delete(conversation_delete_session_handler))
+        .route("/conversation/sessions/:session_id/restore", post(conversation_restore_session_handler))
+        .route("/conversation/sessions/:session_id/fork", post(conversation_fork_session_handler))
+        .route("/conversation/sessions/:session_id", patch(conversation_rename_session_handler))
+        .route("/conversation/sessions/:session_id/settings", patch(conversation_update_session_settings_handler))
+        .route("/conversation/sessions/:session_id/turns/:turn_id/branch", post(conversation_branch_session_handler))
+        .route("/conversation/turns/:turn_id/regenerate", post(conversation_regenerate_turn_handler))
+        .route("/conversation/sessions/:session_id/branches", get(conversation_list_branches_handler))
+        .route("/conversation/sessions/:session_id/export", post(conversation_export_session_handler))
+        .route("/conversation/sessions/:session_id/changes", get(conversation_get_change_history_handler))
         .route("/conversation/message", post(conversation_process_message_handler))
+        .route("/conversation/message/stream", post(conversation_stream_message_handler))
+        .route("/conversation/apply-changes", post(conversation_apply_changes_handler))
+        .route("/conversation/changes/apply", post(conversation_apply_changes_handler))
+        .route("/conversation/changes/preview", post(conversation_preview_changes_handler))
+        .route("/conversation/changes/:change_set_id/rollback", post(conversation_rollback_change_set_handler))
         .route("/conversation/search", post(conversation_search_handler))
         .route("/conversation/stats", get(conversation_stats_handler))
         
@@ -304,57 +600,271 @@ async fn main() -> anyhow::Result<()> {
         .route("/search/errors", post(search_errors_handler))
         .route("/search/suggestions", get(search_suggestions_handler))
         .route("/search/index", post(search_index_workspace_handler))
+        .route("/search/index/status", get(search_index_status_handler))
+        .route("/search/index/jobs/:job_id", delete(search_cancel_index_job_handler))
+        .route("/search/watch", post(search_start_watch_handler).delete(search_stop_watch_handler))
         .route("/search/stats/:workspace_path", get(search_workspace_stats_handler))
         .route("/search/analytics", get(search_user_analytics_handler))
         .route("/search/feedback", post(search_feedback_handler))
+        .route("/search/rerank", post(search_rerank_handler))
+        .route("/search/result/:result_id/expand", get(search_result_expand_handler))
+        .route("/search/callers/:symbol_id", get(search_callers_handler))
         .route("/preferences/import", post(auth::preferences_handlers::import_preferences_handler))
         
         // AI completion endpoints
         .route("/completion", post(completion_handler))
         .route("/completion/stream", post(streaming::streaming_completion_handler))
         .route("/analysis", post(analysis_handler))
-        
+        .route("/api/v1/code-review", post(code_review_handler))
+        .route("/api/v1/bugs/predict", post(predict_failures_handler))
+
         // Provider management
         .route("/providers", get(providers_handler))
+        .route("/providers/custom", post(create_custom_provider_handler))
+        .route("/providers/custom/:id", delete(delete_custom_provider_handler))
         .route("/providers/:provider/health", get(provider_health_handler))
         // Code completion and analysis endpoints
         .route("/api/v1/complete", post(api::completion::complete_code))
         .route("/api/v1/analyze", post(api::completion::analyze_code))
         .route("/providers/:provider/models", get(provider_models_handler))
+        .route("/models", get(all_models_handler))
         
         // System endpoints
-        .route("/metrics", get(metrics_handler))
+        .route("/metrics", get(observability::metrics_handler))
+        .route("/metrics/providers", get(metrics_handler))
         .route("/database/stats", get(database_stats_handler))
+        .route("/database/tuning", get(database_tuning_handler))
+        .route("/admin/audit-logs", get(admin_audit_logs_handler))
+        .route("/api/v1/plan/estimate-cost", post(plan_estimate_cost_handler))
+
+        // Run artifacts
+        .route("/api/v1/artifacts/:run_id/download/:filename", get(artifacts_download_handler))
+        .route("/api/v1/artifacts/:run_id/upload", post(artifacts_upload_handler))
         
+        // Per-user rate limiting (runs after auth so AuthContext is available;
+        // requests without one, i.e. public routes, pass straight through)
+        .layer(middleware::from_fn_with_state(
+            rate_limit_state,
+            security::user_rate_limit_middleware
+        ))
+
         // Add authentication middleware to protected routes
         .layer(middleware::from_fn_with_state(
             app_state.clone(),
             auth_middleware_wrapper
         ))
-        
+
+        // Per-IP rate limiting, the fallback for unauthenticated requests
+        .layer(security::create_rate_limit_layer())
+
+        // Feed request latency/error samples into the performance monitor.
+        // Outermost of the custom layers so it times the full request,
+        // including the middlewares above it.
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            track_performance_middleware
+        ))
+
         // Add CORS and tracing
         .layer(
             ServiceBuilder::new()
                 .layer(TraceLayer::new_for_http())
                 .layer(CorsLayer::permissive())
         )
-        .with_state(app_state);
+
+        // Outermost: correlation ID propagation/echo, so it wraps every
+        // response -- including ones from public routes and from the
+        // layers above -- regardless of where in the stack it failed.
+        .layer(middleware::from_fn(request_id_middleware))
+
+        .with_state(app_state.clone());
 
     // Start server
     let port = env::var("PORT")
         .unwrap_or_else(|_| "3001".to_string())
         .parse::<u16>()
         .unwrap_or(3001);
-    
+
     let listener = TcpListener::bind(format!("0.0.0.0:{}", port)).await?;
     info!("🌐 Server listening on http://0.0.0.0:{}", port);
     info!("📚 API Documentation available at http://0.0.0.0:{}/health", port);
 
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal(app_state.clone()))
+        .await?;
+
+    // Give in-flight requests a bounded window to finish draining after the
+    // shutdown signal fired, then close the DB pool regardless.
+    let drain_timeout = std::time::Duration::from_secs(30);
+    if tokio::time::timeout(drain_timeout, async {
+        while app_state.in_flight_requests.load(std::sync::atomic::Ordering::SeqCst) > 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .is_err()
+    {
+        warn!(
+            "Graceful shutdown timed out after {:?} with {} request(s) still in flight",
+            drain_timeout,
+            app_state.in_flight_requests.load(std::sync::atomic::Ordering::SeqCst)
+        );
+    }
+
+    app_state.database.close().await;
+    info!("✅ Database connections closed, shutdown complete");
 
     Ok(())
 }
 
+/// Waits for SIGINT or SIGTERM, then logs and cancels in-flight work so
+/// `main` can drain it with a timeout and close the DB pool. Split out from
+/// `main` so the signal-waiting half ([`wait_for_first_signal`]) can be unit
+/// tested without sending a real OS signal.
+async fn shutdown_signal(state: AppState) {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    wait_for_first_signal(ctrl_c, terminate).await;
+
+    let in_flight = state.in_flight_requests.load(std::sync::atomic::Ordering::SeqCst);
+    let active_streams = state.streaming_manager.cancel_all_streams();
+    info!(
+        "🛑 Shutdown signal received: draining {} in-flight request(s), cancelled {} active stream(s)",
+        in_flight, active_streams
+    );
+}
+
+/// Resolves as soon as either `ctrl_c` or `terminate` resolves. A thin
+/// `tokio::select!` wrapper so [`shutdown_signal`]'s actual signal sources can
+/// be swapped for simulated futures in a test.
+async fn wait_for_first_signal(
+    ctrl_c: impl std::future::Future<Output = ()>,
+    terminate: impl std::future::Future<Output = ()>,
+) {
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+#[cfg(test)]
+mod shutdown_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn wait_for_first_signal_resolves_on_either_future() {
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let simulated_ctrl_c = async {
+            let _ = rx.await;
+        };
+        let never = std::future::pending::<()>();
+
+        tx.send(()).unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(1), wait_for_first_signal(simulated_ctrl_c, never))
+            .await
+            .expect("wait_for_first_signal should resolve once the simulated signal fires");
+    }
+}
+
+/// Feeds every request's latency and outcome into `AppState::performance_monitor`,
+/// so its next tick reports real traffic instead of placeholders. Also tracks
+/// `AppState::in_flight_requests` for the duration of `next.run`, so
+/// `shutdown_signal`/the post-serve drain loop in `main` can see how many
+/// requests are still being handled.
+async fn track_performance_middleware(
+    State(state): State<AppState>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    state.in_flight_requests.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    let start = std::time::Instant::now();
+    let response = next.run(request).await;
+    let is_error = response.status().is_server_error() || response.status().is_client_error();
+    state.performance_monitor.record_request(start.elapsed(), is_error).await;
+    state.in_flight_requests.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+    response
+}
+
+/// Generates (or propagates, if the caller already sent one) an
+/// `X-Request-ID` header, stores it in request extensions as
+/// [`observability::tracing::RequestId`] so any handler can pull it out
+/// (e.g. to fold into a `BackendError`'s `details`), and echoes it back on
+/// every response -- success or error, including responses generated by
+/// layers below this one -- so a failing `uaida` call can be correlated
+/// with server logs. Outermost layer so it wraps everything, including
+/// public routes that skip `auth_middleware_wrapper` entirely.
+async fn request_id_middleware(
+    mut request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let request_id = request
+        .headers()
+        .get("x-request-id")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+        .unwrap_or_else(observability::tracing::generate_request_id);
+
+    request
+        .extensions_mut()
+        .insert(observability::tracing::RequestId(request_id.clone()));
+
+    let mut response = next.run(request).await;
+    if let Ok(value) = axum::http::HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert("x-request-id", value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod request_id_tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    async fn ok_handler() -> &'static str {
+        "ok"
+    }
+
+    fn test_router() -> Router {
+        Router::new()
+            .route("/", get(ok_handler))
+            .layer(middleware::from_fn(request_id_middleware))
+    }
+
+    #[tokio::test]
+    async fn provided_request_id_is_echoed_back_unchanged() {
+        let request = Request::builder()
+            .uri("/")
+            .header("x-request-id", "caller-supplied-id")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = test_router().oneshot(request).await.unwrap();
+
+        assert_eq!(response.headers().get("x-request-id").unwrap(), "caller-supplied-id");
+    }
+
+    #[tokio::test]
+    async fn missing_request_id_gets_one_generated() {
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+
+        let response = test_router().oneshot(request).await.unwrap();
+
+        assert!(response.headers().get("x-request-id").is_some());
+    }
+}
+
 // Authentication middleware wrapper
 async fn auth_middleware_wrapper(
     State(state): State<AppState>,
@@ -363,11 +873,12 @@ async fn auth_middleware_wrapper(
     next: axum::middleware::Next,
 ) -> Result<axum::response::Response, StatusCode> {
     // Skip authentication for public routes
-    let path = request.uri().path();
-    if path.starts_with("/health") || 
-       path.starts_with("/auth/register") || 
-       path.starts_with("/auth/login") || 
-       path.starts_with("/auth/refresh") {
+    let path = request.uri().path().to_string();
+    if path.starts_with("/health") ||
+       path.starts_with("/auth/register") ||
+       path.starts_with("/auth/login") ||
+       path.starts_with("/auth/refresh") ||
+       path.starts_with("/csp-report") {
         return Ok(next.run(request).await);
     }
 
@@ -408,12 +919,28 @@ async fn auth_middleware_wrapper(
     // Add auth context to request extensions
     request.extensions_mut().insert(auth_context);
 
-    Ok(next.run(request).await)
+    // Establish the distributed trace context from inbound W3C traceparent/tracestate
+    // headers (set by the Tauri frontend or an upstream proxy) so this request's span
+    // is linked to its caller's trace in Jaeger/OTLP backends.
+    let request_id = request
+        .extensions()
+        .get::<observability::tracing::RequestId>()
+        .map(|id| id.0.clone())
+        .unwrap_or_else(observability::tracing::generate_request_id);
+    let parent_cx = observability::tracing::extract_trace_context(&headers);
+    let request_span = observability::tracing::create_request_span(&path, &request_id);
+    let _otel_guard = parent_cx.attach();
+
+    Ok(next.run(request).instrument(request_span).await)
 }
 
 // API Handlers
-async fn health_handler(State(state): State<AppState>) -> Json<HealthResponse> {
-    let providers = state.provider_router.get_provider_health().await;
+async fn health_handler(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<HealthResponse> {
+    let force_refresh = params.get("refresh").map(|v| v == "true").unwrap_or(false);
+    let providers = state.provider_router.get_provider_health(force_refresh).await;
     let database_health = state.database.health_check().await.unwrap_or_else(|_| {
         database::DatabaseHealth {
             connected: false,
@@ -421,11 +948,22 @@ async fn health_handler(State(state): State<AppState>) -> Json<HealthResponse> {
             pool_size: 0,
             active_connections: 0,
             error: Some("Health check failed".to_string()),
+            migrations: database::MigrationStatus {
+                latest_applied_version: None,
+                latest_available_version: 0,
+                pending: true,
+            },
         }
     });
 
+    let status = if !database_health.connected || database_health.migrations.pending {
+        "degraded"
+    } else {
+        "healthy"
+    };
+
     Json(HealthResponse {
-        status: "healthy".to_string(),
+        status: status.to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
         providers,
         features: vec![
@@ -440,21 +978,43 @@ async fn health_handler(State(state): State<AppState>) -> Json<HealthResponse> {
     })
 }
 
+/// Fills in `request.provider` and `request.model` from the user's saved
+/// preferences when the client left them unset, so a preference change
+/// (via `PUT /preferences`) takes effect on the very next completion
+/// request without a restart. Leaves the request untouched if preferences
+/// can't be loaded.
+async fn apply_preferred_defaults(state: &AppState, user_id: Uuid, request: &mut CompletionApiRequest) {
+    match state.preferences_service.get_user_preferences(user_id).await {
+        Ok(prefs) => {
+            let (provider, model) = prefs.resolve_provider_and_model(request.provider.take(), request.model.take());
+            request.provider = Some(provider);
+            request.model = Some(model);
+        }
+        Err(e) => {
+            tracing::warn!(user_id = %user_id, error = %e, "Failed to load preferences, using hardcoded defaults");
+        }
+    }
+}
+
 async fn completion_handler(
     State(state): State<AppState>,
-    auth_context: AuthContext,
-    Json(request): Json<CompletionApiRequest>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+    RequirePermission(auth_context, ..): RequirePermission<auth::require_permission::GenerateCode>,
+    Json(mut request): Json<CompletionApiRequest>,
+) -> Result<Json<serde_json::Value>, BackendError> {
+    if request.provider.is_none() || request.model.is_none() {
+        apply_preferred_defaults(&state, auth_context.user.id, &mut request).await;
+    }
+
     // Get user's API key for the provider
     let provider = request.provider.as_deref().unwrap_or("openrouter");
     let api_key = state
         .api_key_manager
         .get_api_key(auth_context.user.id, provider)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|e| BackendError::Internal(e.to_string()))?;
 
     if api_key.is_none() {
-        return Err(StatusCode::BAD_REQUEST);
+        return Err(BackendError::ApiKeyMissing { provider: provider.to_string() });
     }
 
     // Create completion request
@@ -467,6 +1027,8 @@ async fn completion_handler(
         temperature: request.temperature,
         system_prompt: request.system_prompt,
         stream: Some(request.stream.unwrap_or(false)),
+        tools: request.tools,
+        tool_choice: request.tool_choice,
     };
 
     // Process completion
@@ -476,27 +1038,47 @@ async fn completion_handler(
             "response": response
         }))),
         Err(e) => {
-            tracing::error!("Completion failed: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            tracing::error!(error = %e, "Completion failed");
+            Err(completion_error_to_backend_error(provider, e))
+        }
+    }
+}
+
+/// Maps a [`ProviderError`] to the `BackendError` variant (and stable
+/// `code`) a client should branch on -- rate limiting and provider outages
+/// are distinguishable failure modes, not both a bare 500.
+fn completion_error_to_backend_error(provider: &str, error: ProviderError) -> BackendError {
+    match error {
+        ProviderError::RateLimitError { retry_after_seconds, .. } => {
+            BackendError::RateLimited { retry_after_seconds }
+        }
+        ProviderError::AuthError(_) => BackendError::ApiKeyMissing { provider: provider.to_string() },
+        ProviderError::Unavailable { message, .. } | ProviderError::NetworkError(message) | ProviderError::TimeoutError(message) => {
+            BackendError::ProviderUnavailable { provider: provider.to_string(), details: Some(message) }
         }
+        other => BackendError::Internal(other.to_string()),
     }
 }
 
 async fn completion_stream_handler(
     State(state): State<AppState>,
     auth_context: AuthContext,
-    Json(request): Json<CompletionApiRequest>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+    Json(mut request): Json<CompletionApiRequest>,
+) -> Result<Json<serde_json::Value>, BackendError> {
+    if request.provider.is_none() || request.model.is_none() {
+        apply_preferred_defaults(&state, auth_context.user.id, &mut request).await;
+    }
+
     // Get user's API key for the provider
     let provider = request.provider.as_deref().unwrap_or("openrouter");
     let api_key = state
         .api_key_manager
         .get_api_key(auth_context.user.id, provider)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|e| BackendError::Internal(e.to_string()))?;
 
     if api_key.is_none() {
-        return Err(StatusCode::BAD_REQUEST);
+        return Err(BackendError::ApiKeyMissing { provider: provider.to_string() });
     }
 
     // Create completion request
@@ -509,6 +1091,8 @@ async fn completion_stream_handler(
         temperature: request.temperature,
         system_prompt: request.system_prompt,
         stream: Some(true),
+        tools: request.tools,
+        tool_choice: request.tool_choice,
     };
 
     // Process streaming completion
@@ -519,71 +1103,302 @@ async fn completion_stream_handler(
             "estimated_tokens": response.estimated_tokens
         }))),
         Err(e) => {
-            tracing::error!("Streaming completion failed: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            tracing::error!(error = %e, "Streaming completion failed");
+            Err(completion_error_to_backend_error(provider, e))
         }
     }
 }
 
+/// Gated on `ReviewCode`: running an analysis over someone's code (smells,
+/// doc coverage, or a provider-backed analysis) is the same judgment-call
+/// category as `code_review_handler`, just automated rather than diff-based.
 async fn analysis_handler(
     State(state): State<AppState>,
-    auth_context: AuthContext,
+    RequirePermission(_auth_context, ..): RequirePermission<auth::require_permission::ReviewCode>,
     Json(request): Json<AnalysisRequest>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
+    if matches!(request.analysis_type, AnalysisType::CodeSmells) {
+        return code_smells_handler(request);
+    }
+
+    if matches!(request.analysis_type, AnalysisType::DocCoverage) {
+        return doc_coverage_handler(request);
+    }
+
     match state.provider_router.analyze(request).await {
         Ok(response) => Ok(Json(serde_json::json!({
             "success": true,
             "analysis": response
         }))),
         Err(e) => {
-            tracing::error!("Analysis failed: {}", e);
+            tracing::error!(error = %e, "Analysis failed");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
 }
 
-async fn providers_handler(State(state): State<AppState>) -> Json<serde_json::Value> {
-    let providers = state.provider_router.get_available_providers();
-    Json(serde_json::json!({
+/// Runs `AICodeReviewer::review` over a unified diff and returns the
+/// resulting `CodeReviewReport` (issues found, a quality score, and an
+/// approve/request-changes/reject recommendation). Gated on `ReviewCode`
+/// since it's a judgment call on someone else's code, not a generation
+/// request.
+async fn code_review_handler(
+    State(state): State<AppState>,
+    RequirePermission(_auth_context, ..): RequirePermission<auth::require_permission::ReviewCode>,
+    Json(request): Json<CodeReviewApiRequest>,
+) -> Result<Json<serde_json::Value>, BackendError> {
+    let context = request.context.unwrap_or_default();
+    let report = state
+        .code_reviewer
+        .review(&request.diff, &context)
+        .await
+        .map_err(|e| BackendError::Internal(e.to_string()))?;
+
+    Ok(Json(serde_json::json!({
         "success": true,
-        "providers": providers
-    }))
+        "report": report
+    })))
+}
+
+/// Ranks functions in `request.code` by how likely they are to fail next,
+/// via `PredictiveDebugger::predict_failures`. Sits alongside the local
+/// analysis heuristics (code smells, doc coverage) rather than the LLM
+/// completion path, so it only needs plain auth, not a generation
+/// permission.
+async fn predict_failures_handler(
+    State(state): State<AppState>,
+    _auth_context: AuthContext,
+    Json(request): Json<PredictFailuresApiRequest>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let mut debugger = ai_engine::predictive_debugging::PredictiveDebugger::new(Some(state.runs_repository.clone())).map_err(|e| {
+        tracing::error!(error = %e, "Failed to initialize predictive debugger");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let predictions = debugger
+        .predict_failures(&request.code, &request.language, request.test_results.as_ref())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failure prediction failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "predictions": predictions
+    })))
+}
+
+/// Handles `analysis_type = "smells"`/`CodeSmells` locally via heuristic
+/// tree-sitter passes instead of routing to an LLM provider.
+fn code_smells_handler(request: AnalysisRequest) -> Result<Json<serde_json::Value>, StatusCode> {
+    let mut detector = ai_engine::code_smell_detector::CodeSmellDetector::new().map_err(|e| {
+        tracing::error!(error = %e, "Failed to initialize code smell detector");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let reports = detector
+        .analyze(&request.code, &request.language, "input")
+        .map_err(|e| {
+            tracing::error!(error = %e, "Code smell analysis failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "analysis_type": "code_smells",
+        "smells": reports
+    })))
+}
+
+/// Handles `analysis_type = "doc_coverage"`/`DocCoverage` locally via
+/// heuristic tree-sitter passes instead of routing to an LLM provider.
+fn doc_coverage_handler(request: AnalysisRequest) -> Result<Json<serde_json::Value>, StatusCode> {
+    let mut scorer = services::documentation_generator::DocumentationCoverageScorer::new().map_err(|e| {
+        tracing::error!(error = %e, "Failed to initialize documentation coverage scorer");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let coverage = scorer
+        .score(&request.code, &request.language)
+        .map_err(|e| {
+            tracing::error!(error = %e, "Documentation coverage scoring failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "analysis_type": "doc_coverage",
+        "coverage": coverage
+    })))
+}
+
+async fn providers_handler(State(state): State<AppState>, auth_context: AuthContext) -> Result<Json<serde_json::Value>, StatusCode> {
+    let providers = state.provider_router.get_available_providers().await;
+    let providers_with_defaults: Vec<_> = providers
+        .into_iter()
+        .map(|name| {
+            let default_model = default_model_for(&name, &state.config.providers);
+            serde_json::json!({
+                "name": name,
+                "default_model": default_model
+            })
+        })
+        .collect();
+
+    let custom_providers = state.custom_provider_manager
+        .list_custom_providers(auth_context.user.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .into_iter()
+        .map(|provider| serde_json::json!({
+            "name": provider.name,
+            "default_model": provider.default_model,
+            "custom": true,
+            "base_url": provider.base_url
+        }))
+        .collect::<Vec<_>>();
+
+    let all_providers: Vec<_> = providers_with_defaults.into_iter().chain(custom_providers).collect();
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "providers": all_providers
+    })))
+}
+
+/// Default model per provider, taken from the first entry of each provider's
+/// configured `models` list (the same list providers fall back to when a
+/// request doesn't specify one).
+fn default_model_for(provider: &str, config: &crate::config::ProvidersConfig) -> Option<String> {
+    let models: &[String] = match provider {
+        "openrouter" => &config.openrouter.models,
+        "openai" => &config.openai.models,
+        "anthropic" => &config.anthropic.models,
+        "google" => &config.google.models,
+        "groq" => &config.groq.models,
+        "together" => &config.together.models,
+        "cohere" => &config.cohere.models,
+        "mistral" => &config.mistral.models,
+        "ollama" => &config.ollama.models,
+        _ => return None,
+    };
+    models.first().cloned()
 }
 
 async fn provider_health_handler(
     State(state): State<AppState>,
     Path(provider): Path<String>,
-) -> Result<Json<ProviderHealth>, StatusCode> {
-    match state.provider_router.get_provider_health_by_name(&provider).await {
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<ProviderHealth>, BackendError> {
+    let force_refresh = params.get("refresh").map(|v| v == "true").unwrap_or(false);
+    match state.provider_router.get_provider_health_by_name(&provider, force_refresh).await {
         Some(health) => Ok(Json(health)),
-        None => Err(StatusCode::NOT_FOUND),
+        None => Err(BackendError::NotFound(format!("Unknown provider: {}", provider))),
     }
 }
 
 async fn provider_models_handler(
     State(state): State<AppState>,
+    auth_context: AuthContext,
     Path(provider): Path<String>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    match state.provider_router.get_models(&provider).await {
-        Ok(models) => Ok(Json(serde_json::json!({
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, BackendError> {
+    let refresh = params.get("refresh").map(|v| v == "true").unwrap_or(false);
+    match state.provider_router.get_models(&provider, refresh).await {
+        Ok(listing) => Ok(Json(serde_json::json!({
             "success": true,
-            "models": models
+            "models": listing.models,
+            "cached": listing.cached,
+            "fetched_at": listing.fetched_at
         }))),
-        Err(_) => Err(StatusCode::NOT_FOUND),
+        // Not one of the globally-configured providers; check whether it's
+        // one of this user's custom endpoints before giving up.
+        Err(_) => custom_provider_models(&state, auth_context.user.id, &provider).await,
+    }
+}
+
+/// Hits a user's custom provider's `/v1/models` endpoint directly, since
+/// `ProviderRouter` has no per-user provider registry to look these up in.
+async fn custom_provider_models(state: &AppState, user_id: Uuid, provider: &str) -> Result<Json<serde_json::Value>, BackendError> {
+    let (record, api_key) = state.custom_provider_manager
+        .get_custom_provider(user_id, provider)
+        .await
+        .map_err(|e| BackendError::Internal(e.to_string()))?
+        .ok_or_else(|| BackendError::NotFound(format!("Unknown provider: {}", provider)))?;
+
+    let client = providers::custom_openai::CustomOpenAIProvider::new(
+        record.name,
+        record.base_url,
+        api_key,
+        record.default_model,
+    ).map_err(|e| BackendError::Internal(e.to_string()))?;
+
+    let models = client.list_models().await.map_err(|e| BackendError::ProviderUnavailable {
+        provider: provider.to_string(),
+        details: Some(e.to_string()),
+    })?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "models": models,
+        "cached": false,
+        "fetched_at": chrono::Utc::now()
+    })))
+}
+
+async fn create_custom_provider_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Json(request): Json<auth::CreateCustomProviderRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let is_admin = auth_context.user.is_admin();
+    state.custom_provider_manager
+        .create_custom_provider(auth_context.user.id, request, is_admin)
+        .await
+        .map(|provider| Json(serde_json::json!({ "success": true, "provider": provider })))
+        .map_err(|e| (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "success": false, "error": e.to_string() }))
+        ))
+}
+
+async fn delete_custom_provider_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    match state.custom_provider_manager.delete_custom_provider(auth_context.user.id, id).await {
+        Ok(true) => Ok(Json(serde_json::json!({ "success": true }))),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
 
+/// Merged model listing across every configured provider, tagged with its
+/// provider, so clients like the CLI's `--model` flag can validate a model
+/// name without knowing which provider serves it ahead of time.
+async fn all_models_handler(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let models = state.provider_router.get_all_models().await;
+    Json(serde_json::json!({
+        "success": true,
+        "models": models
+    }))
+}
+
 async fn metrics_handler(State(state): State<AppState>) -> Json<serde_json::Value> {
     let metrics = state.provider_router.get_metrics().await;
+    let performance_metrics = state.performance_monitor.get_metrics().await;
     Json(serde_json::json!({
         "success": true,
-        "metrics": metrics
+        "metrics": metrics,
+        "performance": performance_metrics
     }))
 }
 
 async fn database_stats_handler(
     State(state): State<AppState>,
-    _auth_context: AuthContext,
+    RequirePermission(_auth_context, ..): RequirePermission<auth::require_permission::ConfigureSystem>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     match state.database.get_stats().await {
         Ok(stats) => Ok(Json(serde_json::json!({
@@ -591,40 +1406,248 @@ async fn database_stats_handler(
             "stats": stats
         }))),
         Err(e) => {
-            tracing::error!("Failed to get database stats: {}", e);
+            tracing::error!(error = %e, "Failed to get database stats");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
 }
 
-// Extract auth context from request extensions
-impl axum::extract::FromRequestParts<AppState> for AuthContext {
-    type Rejection = StatusCode;
-
-    async fn from_request_parts(
-        parts: &mut axum::http::request::Parts,
-        _state: &AppState,
-    ) -> Result<Self, Self::Rejection> {
-        parts
-            .extensions
-            .get::<AuthContext>()
-            .cloned()
-            .ok_or(StatusCode::UNAUTHORIZED)
+/// `GET /database/tuning`: real slow-query, cache-hit, and index-usage
+/// metrics from `pg_stat_statements`/`pg_stat_user_indexes`, rather than the
+/// placeholder numbers `DatabaseTuner::get_metrics` used to return.
+async fn database_tuning_handler(
+    State(state): State<AppState>,
+    RequirePermission(_auth_context, ..): RequirePermission<auth::require_permission::ConfigureSystem>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    match state.database.tuner().get_metrics().await {
+        Ok(metrics) => Ok(Json(serde_json::json!({
+            "success": true,
+            "metrics": metrics
+        }))),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to get database tuning metrics");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
     }
 }
 
-// Terminal API Handlers
-async fn terminal_suggest_handler(
+/// `GET /admin/audit-logs`, filtered by `user_id`, `action`, `from`, `to`
+/// (all optional, all RFC3339/UUID strings), and paginated with `limit`
+/// (default 100) plus an opaque `cursor` — the `created_at` of the last
+/// event on the previous page, which this handler feeds back in as the new
+/// upper time bound so the next page picks up right after it.
+async fn admin_audit_logs_handler(
     State(state): State<AppState>,
-    auth_context: AuthContext,
-    Json(request): Json<TerminalSuggestRequest>,
+    RequirePermission(_auth_context, ..): RequirePermission<auth::require_permission::ConfigureSystem>,
+    Query(params): Query<HashMap<String, String>>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    use terminal::{TerminalRequest, QueryType};
-    
-    let query_type = match request.query_type.as_str() {
-        "natural_language" => QueryType::NaturalLanguage,
-        "command_explanation" => QueryType::CommandExplanation,
-        "history_search" => QueryType::HistorySearch,
+    let user_id = params.get("user_id")
+        .map(|s| Uuid::parse_str(s))
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let from = params.get("from")
+        .map(|s| chrono::DateTime::parse_from_rfc3339(s))
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?
+        .map(|dt| dt.with_timezone(&chrono::Utc));
+
+    let to = params.get("cursor").or_else(|| params.get("to"))
+        .map(|s| chrono::DateTime::parse_from_rfc3339(s))
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?
+        .map(|dt| dt.with_timezone(&chrono::Utc));
+
+    let limit = params.get("limit")
+        .and_then(|s| s.parse::<usize>().ok())
+        .unwrap_or(100);
+
+    let criteria = auth::audit::AuditSearchCriteria {
+        start_time: from,
+        end_time: to,
+        user_id,
+        organization_id: None,
+        event_types: None,
+        outcomes: None,
+        resource_type: None,
+        action: params.get("action").cloned(),
+        limit: Some(limit),
+        offset: None,
+    };
+
+    match state.audit_service.search_events(criteria).await {
+        Ok(events) => {
+            let next_cursor = events.last().map(|e| e.timestamp.to_rfc3339());
+            Ok(Json(serde_json::json!({
+                "success": true,
+                "events": events,
+                "next_cursor": next_cursor
+            })))
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to search audit logs");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Body for `POST /api/v1/plan/estimate-cost`, matching the shape
+/// `api::agents::PlanRequest` takes for the (unwired) `/api/v1/plan`
+/// endpoint: a free-text goal plus arbitrary planner constraints.
+#[derive(Debug, Deserialize)]
+struct PlanCostEstimateRequest {
+    goal: String,
+    #[serde(default)]
+    constraints: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct PlanCostEstimateResponse {
+    estimated_tokens: u32,
+    estimated_cost_usd: f64,
+    provider_breakdown: Vec<(String, u32, f64)>,
+    confidence: f32,
+}
+
+/// Plan step count used when `constraints` doesn't specify one. There's no
+/// reachable planner in this tree to derive a real step count from (see
+/// `plan_estimate_cost_handler`'s doc comment), so this is a conservative
+/// placeholder.
+const DEFAULT_PLAN_STEPS: u32 = 3;
+
+/// Fallback average tokens per plan step, used when `completion_logs` has no
+/// historical data yet to average over.
+const FALLBACK_AVG_TOKENS_PER_STEP: f64 = 800.0;
+
+/// Minimum number of matching historical runs before an estimate is
+/// considered confident.
+const MIN_CONFIDENT_HISTORICAL_RUNS: i64 = 10;
+
+/// Predicts the token and dollar cost of a plan without executing it.
+///
+/// The request this implements describes a `CostModel` registered per
+/// provider and an `api_request_logs` table tracking runs per goal type;
+/// neither exists in this tree (the whole `agents`/planner module this
+/// would normally estimate for is unreachable dead code — see
+/// `src/api/agents.rs`'s `create_plan`, never wired into a router). This
+/// estimates with what the tree actually has instead: the per-provider
+/// pricing `ProviderRouter::provider_cost_breakdown` already uses to route
+/// by cost, and `completion_logs` (matched loosely on the goal text) as a
+/// stand-in for historical run data.
+async fn plan_estimate_cost_handler(
+    State(state): State<AppState>,
+    RequirePermission(_auth_context, ..): RequirePermission<auth::require_permission::ViewPlan>,
+    Json(request): Json<PlanCostEstimateRequest>,
+) -> Result<Json<PlanCostEstimateResponse>, StatusCode> {
+    let steps = request
+        .constraints
+        .get("steps")
+        .and_then(|v| v.as_u64())
+        .map(|v| v as u32)
+        .unwrap_or(DEFAULT_PLAN_STEPS);
+
+    let goal_keyword = request.goal.split_whitespace().next().unwrap_or(&request.goal);
+    let inputs = state
+        .database
+        .plan_cost_estimation_inputs(goal_keyword)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to load plan cost estimation inputs");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let avg_tokens_per_step = if inputs.avg_tokens_per_run > 0.0 {
+        inputs.avg_tokens_per_run
+    } else {
+        FALLBACK_AVG_TOKENS_PER_STEP
+    };
+    let estimated_tokens = (steps as f64 * avg_tokens_per_step).round() as u32;
+
+    let provider_breakdown = state.provider_router.provider_cost_breakdown(estimated_tokens).await;
+    let estimated_cost_usd = provider_breakdown.first().map(|(_, _, cost)| *cost).unwrap_or(0.0);
+
+    let confidence = if inputs.matching_run_count < MIN_CONFIDENT_HISTORICAL_RUNS {
+        0.4
+    } else {
+        0.85
+    };
+
+    Ok(Json(PlanCostEstimateResponse {
+        estimated_tokens,
+        estimated_cost_usd,
+        provider_breakdown,
+        confidence,
+    }))
+}
+
+/// `POST /csp-report`: browsers send a violation report here when a page
+/// blocks content under the `Content-Security-Policy` header (see
+/// `security::security_headers_middleware`). No auth -- the report comes
+/// from the browser, not a logged-in client -- so it's logged as a security
+/// event rather than tied to a user/session.
+async fn csp_report_handler(
+    State(state): State<AppState>,
+    Json(report): Json<serde_json::Value>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    warn!(report = %report, "CSP violation reported");
+
+    let details: HashMap<String, serde_json::Value> = [
+        ("csp_report".to_string(), report),
+    ]
+    .into();
+
+    if let Err(e) = state.audit_service.log_security_event(SecurityEventType::PolicyViolation, details).await {
+        tracing::error!(error = %e, "Failed to record CSP violation in audit log");
+    }
+
+    Ok(Json(serde_json::json!({ "success": true })))
+}
+
+// Extract auth context from request extensions
+impl axum::extract::FromRequestParts<AppState> for AuthContext {
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        _state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<AuthContext>()
+            .cloned()
+            .ok_or(StatusCode::UNAUTHORIZED)
+    }
+}
+
+// Extracts `AuthContext` like above, then rejects with 403 if the user
+// lacks `P`'s permission (see `auth::require_permission`).
+impl<P> axum::extract::FromRequestParts<AppState> for auth::require_permission::RequirePermission<P>
+where
+    P: auth::require_permission::RequiredPermission + Send + Sync,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let auth_context = AuthContext::from_request_parts(parts, state).await?;
+        auth::require_permission::check_permission(auth_context)
+    }
+}
+
+// Terminal API Handlers
+async fn terminal_suggest_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Json(request): Json<TerminalSuggestRequest>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    use terminal::{TerminalRequest, QueryType};
+    
+    let query_type = match request.query_type.as_str() {
+        "natural_language" => QueryType::NaturalLanguage,
+        "command_explanation" => QueryType::CommandExplanation,
+        "history_search" => QueryType::HistorySearch,
         _ => QueryType::NaturalLanguage,
     };
 
@@ -660,6 +1683,7 @@ async fn terminal_suggest_handler(
         query: request.query,
         query_type,
         context: Some(session.context.clone()),
+        explain_impact: request.explain_impact,
     };
 
     match state.terminal_service.process_request(terminal_request).await {
@@ -668,10 +1692,11 @@ async fn terminal_suggest_handler(
             "session_id": response.session_id.to_string(),
             "suggestions": response.suggestions,
             "explanation": response.explanation,
-            "warnings": response.warnings
+            "warnings": response.warnings,
+            "predicted_impact": response.predicted_impact
         }))),
         Err(e) => {
-            tracing::error!("Terminal suggest failed: {}", e);
+            tracing::error!(error = %e, "Terminal suggest failed");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
@@ -693,6 +1718,7 @@ async fn terminal_execute_handler(
         query: request.query,
         query_type: QueryType::CommandExecution,
         context: None,
+        explain_impact: false,
     };
 
     match state.terminal_service.process_request(terminal_request).await {
@@ -703,7 +1729,7 @@ async fn terminal_execute_handler(
             "warnings": response.warnings
         }))),
         Err(e) => {
-            tracing::error!("Terminal execute failed: {}", e);
+            tracing::error!(error = %e, "Terminal execute failed");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
@@ -714,17 +1740,16 @@ async fn terminal_sessions_handler(
     auth_context: AuthContext,
     Query(params): Query<HashMap<String, String>>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let limit = params.get("limit")
-        .and_then(|l| l.parse::<i64>().ok())
-        .unwrap_or(10);
+    let (limit, offset) = parse_pagination(&params)?;
 
-    match state.terminal_service.get_user_sessions(auth_context.user.id, limit).await {
-        Ok(sessions) => Ok(Json(serde_json::json!({
+    match state.terminal_service.get_user_sessions_page(auth_context.user.id, limit, offset).await {
+        Ok((sessions, total)) => Ok(Json(serde_json::json!({
             "success": true,
-            "sessions": sessions
+            "sessions": sessions,
+            "pagination": PaginationMeta::new(limit, offset, total)
         }))),
         Err(e) => {
-            tracing::error!("Failed to get terminal sessions: {}", e);
+            tracing::error!(error = %e, "Failed to get terminal sessions");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
@@ -738,13 +1763,73 @@ async fn terminal_delete_session_handler(
     let session_uuid = Uuid::parse_str(&session_id)
         .map_err(|_| StatusCode::BAD_REQUEST)?;
 
-    match state.terminal_service.delete_session(session_uuid).await {
+    match state.terminal_service.archive_session(session_uuid).await {
         Ok(_) => Ok(Json(serde_json::json!({
             "success": true,
-            "message": "Session deleted successfully"
+            "message": "Session archived successfully"
+        }))),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to archive terminal session");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn terminal_archived_sessions_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let (limit, offset) = parse_pagination(&params)?;
+
+    match state.terminal_service.get_archived_sessions_page(auth_context.user.id, limit, offset).await {
+        Ok((sessions, total)) => Ok(Json(serde_json::json!({
+            "success": true,
+            "sessions": sessions,
+            "pagination": PaginationMeta::new(limit, offset, total)
         }))),
         Err(e) => {
-            tracing::error!("Failed to delete terminal session: {}", e);
+            tracing::error!(error = %e, "Failed to get archived terminal sessions");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn terminal_restore_session_handler(
+    State(state): State<AppState>,
+    _auth_context: AuthContext,
+    Path(session_id): Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let session_uuid = Uuid::parse_str(&session_id)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    match state.terminal_service.restore_session(session_uuid).await {
+        Ok(_) => Ok(Json(serde_json::json!({
+            "success": true,
+            "message": "Session restored successfully"
+        }))),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to restore terminal session");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn terminal_deduplicate_session_handler(
+    State(state): State<AppState>,
+    _auth_context: AuthContext,
+    Path(session_id): Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let session_uuid = Uuid::parse_str(&session_id)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    match state.terminal_service.deduplicate_session_history(session_uuid).await {
+        Ok(removed) => Ok(Json(serde_json::json!({
+            "success": true,
+            "removed": removed
+        }))),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to deduplicate terminal session history");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
@@ -767,31 +1852,73 @@ async fn terminal_search_history_handler(
             "commands": commands
         }))),
         Err(e) => {
-            tracing::error!("Failed to search command history: {}", e);
+            tracing::error!(error = %e, "Failed to search command history");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
 }
 
-async fn terminal_stats_handler(
+async fn terminal_import_history_handler(
     State(state): State<AppState>,
-    auth_context: AuthContext,
+    _auth_context: AuthContext,
+    Json(request): Json<TerminalImportHistoryRequest>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    match state.terminal_service.get_command_statistics(auth_context.user.id).await {
-        Ok(stats) => Ok(Json(serde_json::json!({
+    let session_id = request.session_id
+        .and_then(|s| Uuid::parse_str(&s).ok())
+        .ok_or(StatusCode::BAD_REQUEST)?;
+
+    match state.terminal_service.import_shell_history(
+        session_id,
+        request.shell,
+        request.file_content.as_deref(),
+    ).await {
+        Ok(outcome) => Ok(Json(serde_json::json!({
             "success": true,
-            "stats": {
-                "total_commands": stats.total_commands,
-                "ai_suggested_count": stats.ai_suggested_count,
-                "successful_commands": stats.successful_commands,
-                "total_sessions": stats.total_sessions,
-                "success_rate": stats.success_rate(),
-                "ai_usage_rate": stats.ai_usage_rate(),
-                "most_used_commands": stats.most_used_commands
-            }
+            "imported": outcome.imported,
+            "skipped_duplicates": outcome.skipped_duplicates
         }))),
         Err(e) => {
-            tracing::error!("Failed to get terminal statistics: {}", e);
+            tracing::error!(error = %e, "Failed to import shell history");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn terminal_stats_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let days = params.get("days")
+        .and_then(|d| d.parse::<u32>().ok())
+        .unwrap_or(30);
+
+    match state.terminal_service.get_command_statistics(auth_context.user.id, days).await {
+        Ok(stats) => {
+            let daily_breakdown: Vec<serde_json::Value> = stats.daily_breakdown.iter()
+                .map(|day| serde_json::json!({
+                    "date": day.date,
+                    "total_commands": day.total_commands,
+                    "by_category": day.by_category
+                }))
+                .collect();
+
+            Ok(Json(serde_json::json!({
+                "success": true,
+                "stats": {
+                    "total_commands": stats.total_commands,
+                    "ai_suggested_count": stats.ai_suggested_count,
+                    "successful_commands": stats.successful_commands,
+                    "total_sessions": stats.total_sessions,
+                    "success_rate": stats.success_rate(),
+                    "ai_usage_rate": stats.ai_usage_rate(),
+                    "most_used_commands": stats.most_used_commands,
+                    "daily_breakdown": daily_breakdown
+                }
+            })))
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to get terminal statistics");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
@@ -802,7 +1929,7 @@ async fn conversation_create_session_handler(
     State(state): State<AppState>,
     auth_context: AuthContext,
     Json(request): Json<ConversationCreateSessionRequest>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> Result<Json<serde_json::Value>, BackendError> {
     match state.conversation_service.create_session(
         auth_context.user.id,
         request.workspace_path,
@@ -816,8 +1943,8 @@ async fn conversation_create_session_handler(
             }
         }))),
         Err(e) => {
-            tracing::error!("Failed to create conversation session: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            tracing::error!(error = %e, "Failed to create conversation session");
+            Err(BackendError::Internal(e.to_string()))
         }
     }
 }
@@ -826,19 +1953,33 @@ async fn conversation_get_sessions_handler(
     State(state): State<AppState>,
     auth_context: AuthContext,
     Query(params): Query<HashMap<String, String>>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    let limit = params.get("limit")
-        .and_then(|l| l.parse::<i64>().ok())
-        .unwrap_or(10);
+) -> Result<Json<serde_json::Value>, BackendError> {
+    let (limit, offset) = parse_pagination(&params).map_err(|_| BackendError::ValidationError("Invalid pagination parameters".to_string()))?;
+
+    match state.conversation_service.get_user_sessions_page(auth_context.user.id, limit, offset).await {
+        Ok((mut sessions, total)) => {
+            let mut rows = Vec::with_capacity(sessions.len());
+            for session in sessions.iter_mut() {
+                let metadata = state.conversation_service.get_session_list_metadata(session).await
+                    .map_err(|e| BackendError::Internal(e.to_string()))?;
+                rows.push(serde_json::json!({
+                    "session": session,
+                    "turn_count": metadata.turn_count,
+                    "last_message_preview": metadata.last_message_preview,
+                    "dominant_intent": metadata.dominant_intent,
+                    "is_fork": session.parent_session_id.is_some(),
+                }));
+            }
 
-    match state.conversation_service.get_user_sessions(auth_context.user.id, limit).await {
-        Ok(sessions) => Ok(Json(serde_json::json!({
-            "success": true,
-            "sessions": sessions
-        }))),
+            Ok(Json(serde_json::json!({
+                "success": true,
+                "sessions": rows,
+                "pagination": PaginationMeta::new(limit, offset, total)
+            })))
+        }
         Err(e) => {
-            tracing::error!("Failed to get conversation sessions: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            tracing::error!(error = %e, "Failed to get conversation sessions");
+            Err(BackendError::Internal(e.to_string()))
         }
     }
 }
@@ -847,19 +1988,19 @@ async fn conversation_get_session_handler(
     State(state): State<AppState>,
     _auth_context: AuthContext,
     Path(session_id): Path<String>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> Result<Json<serde_json::Value>, BackendError> {
     let session_uuid = Uuid::parse_str(&session_id)
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+        .map_err(|_| BackendError::ValidationError("Invalid session_id".to_string()))?;
 
     match state.conversation_service.get_session(session_uuid).await {
         Ok(Some(session)) => Ok(Json(serde_json::json!({
             "success": true,
             "session": session
         }))),
-        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Ok(None) => Err(BackendError::SessionNotFound { session_id: session_uuid.to_string() }),
         Err(e) => {
-            tracing::error!("Failed to get conversation session: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            tracing::error!(error = %e, "Failed to get conversation session");
+            Err(BackendError::Internal(e.to_string()))
         }
     }
 }
@@ -868,31 +2009,494 @@ async fn conversation_delete_session_handler(
     State(state): State<AppState>,
     _auth_context: AuthContext,
     Path(session_id): Path<String>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> Result<Json<serde_json::Value>, BackendError> {
     let session_uuid = Uuid::parse_str(&session_id)
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+        .map_err(|_| BackendError::ValidationError("Invalid session_id".to_string()))?;
 
-    match state.conversation_service.delete_session(session_uuid).await {
+    match state.conversation_service.archive_session(session_uuid).await {
         Ok(_) => Ok(Json(serde_json::json!({
             "success": true,
-            "message": "Session deleted successfully"
+            "message": "Session archived successfully"
         }))),
         Err(e) => {
-            tracing::error!("Failed to delete conversation session: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            tracing::error!(error = %e, "Failed to archive conversation session");
+            Err(BackendError::Internal(e.to_string()))
         }
     }
 }
 
-async fn conversation_process_message_handler(
+async fn conversation_archived_sessions_handler(
     State(state): State<AppState>,
     auth_context: AuthContext,
-    Json(request): Json<ConversationMessageRequest>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, BackendError> {
+    let (limit, offset) = parse_pagination(&params).map_err(|_| BackendError::ValidationError("Invalid pagination parameters".to_string()))?;
+
+    match state.conversation_service.get_archived_sessions_page(auth_context.user.id, limit, offset).await {
+        Ok((sessions, total)) => Ok(Json(serde_json::json!({
+            "success": true,
+            "sessions": sessions,
+            "pagination": PaginationMeta::new(limit, offset, total)
+        }))),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to get archived conversation sessions");
+            Err(BackendError::Internal(e.to_string()))
+        }
+    }
+}
+
+async fn conversation_restore_session_handler(
+    State(state): State<AppState>,
+    _auth_context: AuthContext,
+    Path(session_id): Path<String>,
+) -> Result<Json<serde_json::Value>, BackendError> {
+    let session_uuid = Uuid::parse_str(&session_id)
+        .map_err(|_| BackendError::ValidationError("Invalid session_id".to_string()))?;
+
+    match state.conversation_service.restore_session(session_uuid).await {
+        Ok(_) => Ok(Json(serde_json::json!({
+            "success": true,
+            "message": "Session restored successfully"
+        }))),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to restore conversation session");
+            Err(BackendError::Internal(e.to_string()))
+        }
+    }
+}
+
+async fn conversation_rename_session_handler(
+    State(state): State<AppState>,
+    _auth_context: AuthContext,
+    Path(session_id): Path<String>,
+    Json(request): Json<ConversationRenameSessionRequest>,
+) -> Result<Json<serde_json::Value>, BackendError> {
+    let session_uuid = Uuid::parse_str(&session_id)
+        .map_err(|_| BackendError::ValidationError("Invalid session_id".to_string()))?;
+
+    match state.conversation_service.rename_session(session_uuid, request.title).await {
+        Ok(session) => Ok(Json(serde_json::json!({
+            "success": true,
+            "session": session
+        }))),
+        Err(e) => Err(conversation_service_error(session_uuid, e)),
+    }
+}
+
+async fn conversation_update_session_settings_handler(
+    State(state): State<AppState>,
+    _auth_context: AuthContext,
+    Path(session_id): Path<String>,
+    Json(request): Json<ConversationUpdateSessionSettingsRequest>,
+) -> Result<Json<serde_json::Value>, BackendError> {
+    let session_uuid = Uuid::parse_str(&session_id)
+        .map_err(|_| BackendError::ValidationError("Invalid session_id".to_string()))?;
+
+    match state.conversation_service
+        .update_session_settings(session_uuid, request.provider, request.model)
+        .await
+    {
+        Ok(session) => Ok(Json(serde_json::json!({
+            "success": true,
+            "session": session
+        }))),
+        Err(e) => Err(conversation_service_error(session_uuid, e)),
+    }
+}
+
+async fn conversation_fork_session_handler(
+    State(state): State<AppState>,
+    _auth_context: AuthContext,
+    Path(session_id): Path<String>,
+    Json(request): Json<ConversationForkSessionRequest>,
+) -> Result<Json<serde_json::Value>, BackendError> {
+    let session_uuid = Uuid::parse_str(&session_id)
+        .map_err(|_| BackendError::ValidationError("Invalid session_id".to_string()))?;
+    let turn_uuid = Uuid::parse_str(&request.from_turn_id)
+        .map_err(|_| BackendError::ValidationError("Invalid from_turn_id".to_string()))?;
+
+    match state.conversation_service.fork_session(session_uuid, turn_uuid).await {
+        Ok(forked_session) => Ok(Json(serde_json::json!({
+            "success": true,
+            "session": forked_session
+        }))),
+        Err(e) => Err(conversation_service_error(session_uuid, e)),
+    }
+}
+
+async fn conversation_branch_session_handler(
+    State(state): State<AppState>,
+    _auth_context: AuthContext,
+    Path((session_id, turn_id)): Path<(String, String)>,
+) -> Result<Json<serde_json::Value>, BackendError> {
+    let session_uuid = Uuid::parse_str(&session_id)
+        .map_err(|_| BackendError::ValidationError("Invalid session_id".to_string()))?;
+    let turn_uuid = Uuid::parse_str(&turn_id)
+        .map_err(|_| BackendError::ValidationError("Invalid turn_id".to_string()))?;
+
+    match state.conversation_service.fork_session(session_uuid, turn_uuid).await {
+        Ok(branched_session) => Ok(Json(serde_json::json!({
+            "success": true,
+            "session": branched_session
+        }))),
+        Err(e) => Err(conversation_service_error(session_uuid, e)),
+    }
+}
+
+async fn conversation_regenerate_turn_handler(
+    State(state): State<AppState>,
+    _auth_context: AuthContext,
+    Path(turn_id): Path<String>,
+    Json(request): Json<ConversationRegenerateTurnRequest>,
+) -> Result<Json<serde_json::Value>, BackendError> {
+    let session_uuid = Uuid::parse_str(&request.session_id)
+        .map_err(|_| BackendError::ValidationError("Invalid session_id".to_string()))?;
+    let turn_uuid = Uuid::parse_str(&turn_id)
+        .map_err(|_| BackendError::ValidationError("Invalid turn_id".to_string()))?;
+
+    let overrides = conversation::RegenerateTurnOverrides {
+        temperature: request.temperature,
+        model: request.model,
+        extra_instruction: request.extra_instruction,
+    };
+
+    match state.conversation_service.regenerate_turn(session_uuid, turn_uuid, overrides).await {
+        Ok(response) => Ok(Json(serde_json::json!({
+            "success": true,
+            "response": response
+        }))),
+        Err(e) => Err(conversation_service_error(session_uuid, e)),
+    }
+}
+
+async fn conversation_list_branches_handler(
+    State(state): State<AppState>,
+    _auth_context: AuthContext,
+    Path(session_id): Path<String>,
+) -> Result<Json<serde_json::Value>, BackendError> {
+    let session_uuid = Uuid::parse_str(&session_id)
+        .map_err(|_| BackendError::ValidationError("Invalid session_id".to_string()))?;
+
+    match state.conversation_service.list_branches(session_uuid).await {
+        Ok(branches) => Ok(Json(serde_json::json!({
+            "success": true,
+            "branches": branches
+        }))),
+        Err(e) => Err(conversation_service_error(session_uuid, e)),
+    }
+}
+
+async fn conversation_export_session_handler(
+    State(state): State<AppState>,
+    _auth_context: AuthContext,
+    Path(session_id): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Response, StatusCode> {
+    let session_uuid = Uuid::parse_str(&session_id)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let format: ExportFormat = params
+        .get("format")
+        .map(|f| f.parse())
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?
+        .unwrap_or(ExportFormat::Markdown);
+
+    let chunks = match state.conversation_service.export_session(session_uuid, format).await {
+        Ok(chunks) => chunks,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to export conversation session");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let stream = futures::stream::iter(
+        chunks.into_iter().map(|chunk| Ok::<_, std::io::Error>(chunk.into_bytes())),
+    );
+
+    let filename = format!("conversation-{session_id}.{}", format.file_extension());
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response())
+}
+
+async fn conversation_apply_changes_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Json(request): Json<ConversationApplyChangesRequest>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    use conversation::ApplyChangeStatus;
+
+    if !auth_context.user.has_permission(&Permission::ModifyFile) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let session_id = Uuid::parse_str(&request.session_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let change_ids = request
+        .change_ids
+        .iter()
+        .map(|id| Uuid::parse_str(id).map_err(|_| StatusCode::BAD_REQUEST))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let session = state
+        .conversation_service
+        .get_session(session_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(session_id = %session_id, error = %e, "Failed to load conversation session");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if session.user_id != auth_context.user.id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let results = state
+        .conversation_service
+        .apply_code_changes(session_id, &change_ids)
+        .await
+        .map_err(|e| {
+            tracing::error!(session_id = %session_id, error = %e, "Failed to apply code changes for session");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    for result in &results {
+        let outcome = match result.status {
+            ApplyChangeStatus::Applied => AuditOutcome::Success,
+            ApplyChangeStatus::Conflict => AuditOutcome::Blocked,
+            ApplyChangeStatus::NotFound | ApplyChangeStatus::Unsupported | ApplyChangeStatus::Error => {
+                AuditOutcome::Failure
+            }
+            // `apply_code_changes` never returns `WouldApply` (that's
+            // `preview_code_changes`'s status), but the match must stay
+            // exhaustive as the enum grows.
+            ApplyChangeStatus::WouldApply => AuditOutcome::Success,
+        };
+
+        let event = AuditEvent {
+            id: Uuid::new_v4(),
+            timestamp: chrono::Utc::now(),
+            event_type: AuditEventType::DataModification,
+            user_id: Some(auth_context.user.id),
+            session_id: Some(session_id),
+            organization_id: auth_context.user.organization_id,
+            ip_address: auth_context.ip_address.clone(),
+            user_agent: auth_context.user_agent.clone(),
+            resource_type: Some("file".to_string()),
+            resource_id: Some(result.file_path.clone()),
+            action: "apply_code_change".to_string(),
+            outcome,
+            details: [(
+                "change_id".to_string(),
+                serde_json::Value::String(result.change_id.to_string()),
+            )]
+            .into(),
+            risk_score: None,
+        };
+
+        if let Err(e) = state.audit_service.log_event(event).await {
+            tracing::warn!(error = %e, "Failed to record audit event for applied change");
+        }
+    }
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "results": results
+    })))
+}
+
+/// Reports whether each change would apply cleanly and the diff it would
+/// produce, without writing anything, so a client can show a confirmation
+/// dialog before calling `conversation_apply_changes_handler` for real.
+async fn conversation_preview_changes_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Json(request): Json<ConversationApplyChangesRequest>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    if !auth_context.user.has_permission(&Permission::ModifyFile) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let session_id = Uuid::parse_str(&request.session_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let change_ids = request
+        .change_ids
+        .iter()
+        .map(|id| Uuid::parse_str(id).map_err(|_| StatusCode::BAD_REQUEST))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let session = state
+        .conversation_service
+        .get_session(session_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(session_id = %session_id, error = %e, "Failed to load conversation session");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if session.user_id != auth_context.user.id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let results = state
+        .conversation_service
+        .preview_code_changes(session_id, &change_ids)
+        .await
+        .map_err(|e| {
+            tracing::error!(session_id = %session_id, error = %e, "Failed to preview code changes for session");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "results": results
+    })))
+}
+
+/// Restores the files touched by a change set to the state they were in
+/// before `conversation_apply_changes_handler` wrote them. Rejects with
+/// `409 CONFLICT` if the set was already rolled back once, or if a file was
+/// modified since the change was applied (reported per-result instead, with
+/// the rest of the set still rolled back).
+async fn conversation_rollback_change_set_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Path(change_set_id): Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    if !auth_context.user.has_permission(&Permission::ModifyFile) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let change_set_id = Uuid::parse_str(&change_set_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let session_id = state
+        .conversation_service
+        .session_id_for_change_set(change_set_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(change_set_id = %change_set_id, error = %e, "Failed to look up session for change set");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let session = state
+        .conversation_service
+        .get_session(session_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(session_id = %session_id, error = %e, "Failed to load conversation session");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if session.user_id != auth_context.user.id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let results = state
+        .conversation_service
+        .rollback_change_set(change_set_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(change_set_id = %change_set_id, error = %e, "Failed to roll back change set");
+            if e.to_string().contains("already been rolled back") {
+                StatusCode::CONFLICT
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        })?;
+
+    for result in &results {
+        let event = AuditEvent {
+            id: Uuid::new_v4(),
+            timestamp: chrono::Utc::now(),
+            event_type: AuditEventType::DataModification,
+            user_id: Some(auth_context.user.id),
+            session_id: Some(session_id),
+            organization_id: auth_context.user.organization_id,
+            ip_address: auth_context.ip_address.clone(),
+            user_agent: auth_context.user_agent.clone(),
+            resource_type: Some("file".to_string()),
+            resource_id: Some(result.file_path.clone()),
+            action: "rollback_code_change".to_string(),
+            outcome: if result.rolled_back { AuditOutcome::Success } else { AuditOutcome::Failure },
+            details: [(
+                "change_set_id".to_string(),
+                serde_json::Value::String(change_set_id.to_string()),
+            )]
+            .into(),
+            risk_score: None,
+        };
+
+        if let Err(e) = state.audit_service.log_event(event).await {
+            tracing::warn!(error = %e, "Failed to record audit event for rolled-back change");
+        }
+    }
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "results": results
+    })))
+}
+
+/// Change-set history for a session, most recent turn first, so a client
+/// can render what's been applied and what's still eligible to roll back.
+async fn conversation_get_change_history_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Path(session_id): Path<String>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
+    let session_id = Uuid::parse_str(&session_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let session = state
+        .conversation_service
+        .get_session(session_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(session_id = %session_id, error = %e, "Failed to load conversation session");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if session.user_id != auth_context.user.id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let history = state
+        .conversation_service
+        .get_change_history(session_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(session_id = %session_id, error = %e, "Failed to load change history for session");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "changes": history
+    })))
+}
+
+fn build_conversation_request(
+    request: ConversationMessageRequest,
+) -> Result<conversation::ConversationRequest, BackendError> {
     use conversation::{ConversationRequest, MessageIntent, TextSelection, Position};
-    
+
     let session_id = Uuid::parse_str(&request.session_id)
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+        .map_err(|_| BackendError::ValidationError("Invalid session_id".to_string()))?;
+    let branch_from_turn_id = request.branch_from_turn_id
+        .map(|id| Uuid::parse_str(&id))
+        .transpose()
+        .map_err(|_| BackendError::ValidationError("Invalid branch_from_turn_id".to_string()))?;
 
     // Convert text selection
     let selected_text = request.selected_text.map(|sel| TextSelection {
@@ -925,7 +2529,7 @@ async fn conversation_process_message_handler(
         }
     });
 
-    let conversation_request = ConversationRequest {
+    Ok(ConversationRequest {
         session_id: Some(session_id),
         message: request.message,
         workspace_path: None, // Session'dan alınacak
@@ -933,7 +2537,38 @@ async fn conversation_process_message_handler(
         selected_text,
         context_files: request.context_files,
         intent_hint,
-    };
+        branch_from_turn_id,
+    })
+}
+
+/// Maps a `process_message`/`process_message_streaming` failure to a
+/// `BackendError`. These services return `anyhow::Error` rather than a typed
+/// error enum, so a not-found session is recognised by its message text
+/// (set at the single `anyhow::anyhow!("Session not found: {}", ...)` call
+/// site these calls eventually bottom out in) instead of a `match` on
+/// variants.
+fn conversation_service_error(session_id: Uuid, e: anyhow::Error) -> BackendError {
+    let message = e.to_string();
+    if message.starts_with("Session not found") {
+        BackendError::SessionNotFound { session_id: session_id.to_string() }
+    } else if message.starts_with("Turn not found") {
+        BackendError::NotFound(message)
+    } else if message.starts_with("Only the most recent turn") {
+        BackendError::Conflict(message)
+    } else {
+        tracing::error!(error = %e, "Failed to process conversation message");
+        BackendError::Internal(message)
+    }
+}
+
+async fn conversation_process_message_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Json(request): Json<ConversationMessageRequest>,
+) -> Result<Json<serde_json::Value>, BackendError> {
+    let session_id = Uuid::parse_str(&request.session_id)
+        .map_err(|_| BackendError::ValidationError("Invalid session_id".to_string()))?;
+    let conversation_request = build_conversation_request(request)?;
 
     match state.conversation_service.process_message(conversation_request).await {
         Ok(response) => Ok(Json(serde_json::json!({
@@ -950,18 +2585,36 @@ async fn conversation_process_message_handler(
                 "execution_time_ms": response.execution_time_ms
             }
         }))),
-        Err(e) => {
-            tracing::error!("Failed to process conversation message: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
+        Err(e) => Err(conversation_service_error(session_id, e)),
     }
 }
 
+/// SSE variant of [`conversation_process_message_handler`] — see
+/// `streaming::StreamingManager::create_conversation_stream` for the event
+/// sequence a client receives.
+async fn conversation_stream_message_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Json(request): Json<ConversationMessageRequest>,
+) -> Result<Response, BackendError> {
+    let conversation_request = build_conversation_request(request)?;
+
+    let streaming_manager = state.streaming_manager.clone();
+    let stream = streaming_manager.create_conversation_stream(state, conversation_request);
+    let sse = Sse::new(stream).keep_alive(
+        KeepAlive::new()
+            .interval(std::time::Duration::from_secs(15))
+            .text("keep-alive"),
+    );
+
+    Ok(sse.into_response())
+}
+
 async fn conversation_search_handler(
     State(state): State<AppState>,
     auth_context: AuthContext,
     Json(request): Json<ConversationSearchRequest>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> Result<Json<serde_json::Value>, BackendError> {
     let limit = request.limit.unwrap_or(20);
 
     match state.conversation_service.search_conversations(
@@ -974,8 +2627,8 @@ async fn conversation_search_handler(
             "conversations": conversations
         }))),
         Err(e) => {
-            tracing::error!("Failed to search conversations: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            tracing::error!(error = %e, "Failed to search conversations");
+            Err(BackendError::Internal(e.to_string()))
         }
     }
 }
@@ -983,7 +2636,7 @@ async fn conversation_search_handler(
 async fn conversation_stats_handler(
     State(state): State<AppState>,
     auth_context: AuthContext,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> Result<Json<serde_json::Value>, BackendError> {
     match state.conversation_service.get_conversation_statistics(auth_context.user.id).await {
         Ok(stats) => Ok(Json(serde_json::json!({
             "success": true,
@@ -999,8 +2652,8 @@ async fn conversation_stats_handler(
             }
         }))),
         Err(e) => {
-            tracing::error!("Failed to get conversation statistics: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            tracing::error!(error = %e, "Failed to get conversation statistics");
+            Err(BackendError::Internal(e.to_string()))
         }
     }
 }
@@ -1011,8 +2664,8 @@ async fn search_handler(
     auth_context: AuthContext,
     Json(request): Json<SearchApiRequest>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    use search::{SearchRequest, SearchQueryType, FileFilter};
-    
+    use search::{SearchRequest, SearchQueryType, FileFilter, SimilarityMetric};
+
     let query_type = match request.query_type.as_deref() {
         Some("natural_language") => SearchQueryType::NaturalLanguage,
         Some("code_pattern") => SearchQueryType::CodePattern,
@@ -1024,6 +2677,15 @@ async fn search_handler(
         _ => SearchQueryType::NaturalLanguage,
     };
 
+    let similarity_metric = match request.similarity_metric.as_deref() {
+        Some("cosine") => Some(SimilarityMetric::Cosine),
+        Some("euclidean") => Some(SimilarityMetric::Euclidean),
+        Some("dot_product") => Some(SimilarityMetric::DotProduct),
+        Some("manhattan") => Some(SimilarityMetric::Manhattan),
+        Some("jaccard") => Some(SimilarityMetric::Jaccard),
+        _ => None,
+    };
+
     let file_filters: Vec<FileFilter> = request.file_filters.into_iter()
         .map(|f| FileFilter {
             pattern: f.pattern,
@@ -1040,6 +2702,9 @@ async fn search_handler(
         max_results: request.max_results,
         similarity_threshold: request.similarity_threshold,
         include_context: request.include_context.unwrap_or(true),
+        force_exact_search: false,
+        similarity_metric,
+        user_id: Some(auth_context.user.id),
     };
 
     match state.search_service.search(search_request, auth_context.user.id).await {
@@ -1048,7 +2713,7 @@ async fn search_handler(
             "response": response
         }))),
         Err(e) => {
-            tracing::error!("Search failed: {}", e);
+            tracing::error!(error = %e, "Search failed");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
@@ -1069,7 +2734,7 @@ async fn search_similar_handler(
             "response": response
         }))),
         Err(e) => {
-            tracing::error!("Similar code search failed: {}", e);
+            tracing::error!(error = %e, "Similar code search failed");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
@@ -1109,7 +2774,7 @@ async fn search_symbol_handler(
             "response": response
         }))),
         Err(e) => {
-            tracing::error!("Symbol search failed: {}", e);
+            tracing::error!(error = %e, "Symbol search failed");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
@@ -1130,7 +2795,7 @@ async fn search_documentation_handler(
             "response": response
         }))),
         Err(e) => {
-            tracing::error!("Documentation search failed: {}", e);
+            tracing::error!(error = %e, "Documentation search failed");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
@@ -1151,7 +2816,7 @@ async fn search_errors_handler(
             "response": response
         }))),
         Err(e) => {
-            tracing::error!("Error search failed: {}", e);
+            tracing::error!(error = %e, "Error search failed");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
@@ -1170,26 +2835,94 @@ async fn search_suggestions_handler(
             "suggestions": suggestions
         }))),
         Err(e) => {
-            tracing::error!("Failed to get search suggestions: {}", e);
+            tracing::error!(error = %e, "Failed to get search suggestions");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
 }
 
+/// Gated on `ModifyFile`: indexing a workspace walks and reads (and, via
+/// incremental re-indexing, writes to) the workspace's files on disk, same
+/// write-adjacent category as the other file-touching endpoints.
 async fn search_index_workspace_handler(
     State(state): State<AppState>,
-    auth_context: AuthContext,
+    RequirePermission(auth_context, ..): RequirePermission<auth::require_permission::ModifyFile>,
     Json(request): Json<SearchIndexRequest>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    match state.search_service.index_workspace(&request.workspace_path, auth_context.user.id).await {
-        Ok(stats) => Ok(Json(serde_json::json!({
+    use search::search_service::IndexMode;
+
+    let mode = match request.mode.as_deref() {
+        None | Some("full") => IndexMode::Full,
+        Some("incremental") => IndexMode::Incremental,
+        Some(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    let (job_id, cancellation_token) = state.indexing_jobs.create_job(request.workspace_path.clone());
+
+    let state_clone = state.clone();
+    let workspace_path = request.workspace_path.clone();
+    let user_id = auth_context.user.id;
+    tokio::spawn(async move {
+        state_clone.indexing_jobs.mark_running(job_id, 0);
+
+        let result = tokio::select! {
+            result = state_clone.search_service.index_workspace(&workspace_path, user_id, mode) => result,
+            _ = cancellation_token.cancelled() => {
+                info!(job_id = %job_id, workspace_path = %workspace_path, "Indexing job cancelled");
+                return;
+            }
+        };
+
+        match result {
+            Ok(stats) => {
+                let files_processed = stats.files_added + stats.files_updated + stats.files_skipped;
+                state_clone.indexing_jobs.update_progress(job_id, files_processed);
+                state_clone.indexing_jobs.mark_completed(job_id, stats);
+            }
+            Err(e) => {
+                tracing::error!(workspace_path = %workspace_path, error = %e, "Failed to index workspace");
+                state_clone.indexing_jobs.mark_failed(job_id, e.to_string());
+            }
+        }
+    });
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "job_id": job_id
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchIndexStatusQuery {
+    workspace: String,
+}
+
+async fn search_index_status_handler(
+    State(state): State<AppState>,
+    _auth_context: AuthContext,
+    Query(query): Query<SearchIndexStatusQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    match state.indexing_jobs.find_by_workspace(&query.workspace) {
+        Some(job) => Ok(Json(serde_json::json!({
             "success": true,
-            "stats": stats
+            "job": job
         }))),
-        Err(e) => {
-            tracing::error!("Failed to index workspace: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+async fn search_cancel_index_job_handler(
+    State(state): State<AppState>,
+    _auth_context: AuthContext,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    if state.indexing_jobs.cancel(job_id) {
+        Ok(Json(serde_json::json!({
+            "success": true,
+            "message": "Indexing job cancelled"
+        })))
+    } else {
+        Err(StatusCode::NOT_FOUND)
     }
 }
 
@@ -1199,17 +2932,57 @@ async fn search_workspace_stats_handler(
     Path(workspace_path): Path<String>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     match state.search_service.get_workspace_stats(&workspace_path, auth_context.user.id).await {
-        Ok(stats) => Ok(Json(serde_json::json!({
+        Ok(stats) => {
+            let watcher_status = state.workspace_watchers.status(&workspace_path).await;
+            Ok(Json(serde_json::json!({
+                "success": true,
+                "stats": stats,
+                "watcher_status": watcher_status
+            })))
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to get workspace stats");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn search_start_watch_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Json(request): Json<SearchWatchRequest>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    match state.workspace_watchers.start_watching(
+        request.workspace_path.clone(),
+        auth_context.user.id,
+        state.search_service.clone(),
+    ) {
+        Ok(()) => Ok(Json(serde_json::json!({
             "success": true,
-            "stats": stats
+            "message": format!("Watching workspace: {}", request.workspace_path)
         }))),
         Err(e) => {
-            tracing::error!("Failed to get workspace stats: {}", e);
+            tracing::error!(workspace_path = %request.workspace_path, error = %e, "Failed to watch workspace");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
 }
 
+async fn search_stop_watch_handler(
+    State(state): State<AppState>,
+    _auth_context: AuthContext,
+    Json(request): Json<SearchWatchRequest>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    if state.workspace_watchers.stop_watching(&request.workspace_path) {
+        Ok(Json(serde_json::json!({
+            "success": true,
+            "message": format!("Stopped watching workspace: {}", request.workspace_path)
+        })))
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
 async fn search_user_analytics_handler(
     State(state): State<AppState>,
     auth_context: AuthContext,
@@ -1225,7 +2998,7 @@ async fn search_user_analytics_handler(
             "analytics": analytics
         }))),
         Err(e) => {
-            tracing::error!("Failed to get user analytics: {}", e);
+            tracing::error!(error = %e, "Failed to get user analytics");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
@@ -1253,6 +3026,7 @@ async fn search_feedback_handler(
         feedback_type,
         satisfaction_score: request.satisfaction_score,
         comments: request.comments,
+        result_id: request.result_id,
     };
 
     match state.search_service.provide_search_feedback(search_id, feedback, auth_context.user.id).await {
@@ -1261,8 +3035,244 @@ async fn search_feedback_handler(
             "message": "Feedback recorded successfully"
         }))),
         Err(e) => {
-            tracing::error!("Failed to record search feedback: {}", e);
+            tracing::error!(error = %e, "Failed to record search feedback");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
+}
+
+async fn search_rerank_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Json(request): Json<SearchRerankRequest>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let search_id = Uuid::parse_str(&request.search_id)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    match state.search_service.rerank_search_results(search_id, request.top_k, auth_context.user.id).await {
+        Ok(response) => Ok(Json(serde_json::json!({
+            "success": true,
+            "response": response
+        }))),
+        Err(e) => {
+            tracing::error!(error = %e, "Search rerank failed");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn search_result_expand_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Path(result_id): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let result_id = Uuid::parse_str(&result_id)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let context_lines = params.get("context_lines")
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(20);
+
+    match state.search_service.expand_result_context(result_id, context_lines, auth_context.user.id).await {
+        Ok(Some(expanded)) => Ok(Json(serde_json::json!({
+            "success": true,
+            "context": expanded
+        }))),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!(error = %e, "Search result expand failed");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn search_callers_handler(
+    State(state): State<AppState>,
+    _auth_context: AuthContext,
+    Path(symbol_id): Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let symbol_id = Uuid::parse_str(&symbol_id)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    match state.search_service.find_transitive_callers(symbol_id).await {
+        Ok(callers) => Ok(Json(serde_json::json!({
+            "success": true,
+            "callers": callers
+        }))),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to find transitive callers");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Rejects anything that isn't a single, literal path component -- no `/`,
+/// no `\`, no `..`, no empty string -- so a client-supplied artifact
+/// filename (from a multipart `Content-Disposition` header on upload, or
+/// straight from the URL on download) can't escape the run's artifact
+/// directory via path traversal or an absolute path.
+fn validated_artifact_filename(filename: &str) -> Result<&str, BackendError> {
+    let name = std::path::Path::new(filename)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .filter(|n| *n == filename)
+        .ok_or_else(|| BackendError::ValidationError(format!("Invalid artifact filename: {filename}")))?;
+
+    Ok(name)
+}
+
+/// Serves the file behind a `download_url` (e.g.
+/// `/api/v1/artifacts/:run_id/download/test_results.xml`) by looking the
+/// artifact up by `run_id` + `filename` rather than by its own ID, since
+/// that's what the URL encodes.
+async fn artifacts_download_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Path((run_id, filename)): Path<(String, String)>,
+) -> Result<Response, StatusCode> {
+    let filename = validated_artifact_filename(&filename).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let run_uuid = Uuid::parse_str(&run_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let run = state
+        .runs_repository
+        .get_by_id(run_uuid)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to load run for artifact download");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if run.user_id != auth_context.user.id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let artifacts = state.artifacts_repository.get_by_run_id(run_uuid).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to load artifacts for run");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let artifact = artifacts
+        .into_iter()
+        .find(|a| a.name == filename)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let file_path = std::path::Path::new(&state.config.artifacts.storage_path)
+        .join(&run_id)
+        .join(&filename);
+
+    let file = tokio::fs::File::open(&file_path).await.map_err(|e| {
+        tracing::error!(error = %e, path = %file_path.display(), "Artifact record exists but file is missing on disk");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    if let Err(e) = state.artifacts_repository.increment_download_count(artifact.id).await {
+        tracing::warn!(error = %e, "Failed to record artifact download count");
+    }
+
+    let content_type = artifact.mime_type.unwrap_or_else(|| "application/octet-stream".to_string());
+    let stream = tokio_util::io::ReaderStream::new(file);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response())
+}
+
+/// Lets agents store arbitrary run output (test reports, logs, coverage
+/// files) as downloadable artifacts after a run. Companion to
+/// [`artifacts_download_handler`], which serves what this stores.
+async fn artifacts_upload_handler(
+    State(state): State<AppState>,
+    auth_context: AuthContext,
+    Path(run_id): Path<String>,
+    mut multipart: Multipart,
+) -> Result<Json<serde_json::Value>, BackendError> {
+    let run_uuid = Uuid::parse_str(&run_id)
+        .map_err(|_| BackendError::ValidationError("Invalid run_id".to_string()))?;
+
+    let run = state
+        .runs_repository
+        .get_by_id(run_uuid)
+        .await
+        .map_err(|e| BackendError::Internal(e.to_string()))?
+        .ok_or_else(|| BackendError::NotFound(format!("Run not found: {run_id}")))?;
+
+    if run.user_id != auth_context.user.id {
+        return Err(BackendError::Forbidden);
+    }
+
+    let run_dir = std::path::Path::new(&state.config.artifacts.storage_path).join(&run_id);
+    tokio::fs::create_dir_all(&run_dir)
+        .await
+        .map_err(|e| BackendError::Internal(format!("Failed to create artifact directory: {e}")))?;
+
+    let mut stored = Vec::new();
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| BackendError::ValidationError(format!("Invalid multipart upload: {e}")))?
+    {
+        let filename = field
+            .file_name()
+            .ok_or_else(|| BackendError::ValidationError("Multipart field is missing a filename".to_string()))?;
+        let filename = validated_artifact_filename(filename)?.to_string();
+        let mime_type = field.content_type().map(|s| s.to_string());
+
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| BackendError::ValidationError(format!("Failed to read upload: {e}")))?;
+
+        if data.len() as u64 > state.config.artifacts.max_upload_size_bytes {
+            return Err(BackendError::ValidationError(format!(
+                "Artifact '{filename}' exceeds the {} byte upload limit",
+                state.config.artifacts.max_upload_size_bytes
+            )));
+        }
+
+        let file_path = run_dir.join(&filename);
+        tokio::fs::write(&file_path, &data)
+            .await
+            .map_err(|e| BackendError::Internal(format!("Failed to write artifact: {e}")))?;
+
+        let artifact = state
+            .artifacts_repository
+            .create(database::repositories::artifacts::CreateArtifactRequest {
+                run_id: run_uuid,
+                project_id: run.project_id,
+                user_id: auth_context.user.id,
+                name: filename.clone(),
+                file_path: Some(file_path.to_string_lossy().to_string()),
+                storage_type: "filesystem".to_string(),
+                artifact_type: "file".to_string(),
+                mime_type,
+                size_bytes: data.len() as i64,
+                checksum_sha256: None,
+                content_preview: None,
+                download_url: Some(format!("/api/v1/artifacts/{run_id}/download/{filename}")),
+                storage_metadata: serde_json::json!({}),
+                retention_until: None,
+                is_public: false,
+                metadata: serde_json::json!({}),
+            })
+            .await
+            .map_err(|e| BackendError::Internal(e.to_string()))?;
+
+        stored.push(artifact);
+    }
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "artifacts": stored
+    })))
 }
\ No newline at end of file
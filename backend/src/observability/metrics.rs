@@ -1,27 +1,39 @@
 use prometheus::{
-    Counter, Histogram, IntCounter, IntGauge, Registry, 
-    HistogramOpts, Opts, register_counter, register_histogram, 
+    Counter, Gauge, Histogram, IntCounter, IntGauge, Registry,
+    HistogramOpts, Opts, register_counter, register_gauge, register_histogram,
     register_int_counter, register_int_gauge, IntCounterVec, HistogramVec,
     register_int_counter_vec, register_histogram_vec,
 };
 use std::sync::OnceLock;
 use tracing::warn;
 
+// Per-provider request/latency/token/cost metrics are registered directly in
+// `providers::router` (alongside that module's existing
+// `uaida_provider_circuit_state` gauge) rather than here, since `providers`
+// is shared by both the `lib` and `bin` crate roots while `observability` is
+// bin-only — see `providers::router::provider_requests_total` and its
+// sibling accessors in that module.
 pub struct Metrics {
     // HTTP metrics as specified in the plan
     pub http_requests_total: IntCounterVec,
     pub http_request_duration_ms: HistogramVec,
-    
-    // Provider metrics as specified in the plan
-    pub provider_requests_total: IntCounterVec,
-    pub provider_request_duration_ms: HistogramVec,
-    
+
     // Agent metrics as specified in the plan
     pub agent_step_duration_ms: HistogramVec,
-    
+
     // Additional metrics
     pub suggestion_acceptance_total: IntCounterVec,
     pub active_executions: IntGauge,
+
+    // performance::PerformanceMonitor's latest tick, mirrored here so it
+    // shows up in this endpoint's `prometheus::gather()` output alongside
+    // everything else, not just through `/metrics/providers`.
+    pub performance_cpu_usage_percent: Gauge,
+    pub performance_memory_usage_mb: Gauge,
+    pub performance_active_connections: IntGauge,
+    pub performance_request_latency_p95_ms: Gauge,
+    pub performance_throughput_rps: Gauge,
+    pub performance_error_rate: Gauge,
 }
 
 static METRICS: OnceLock<Metrics> = OnceLock::new();
@@ -43,21 +55,6 @@ pub fn init_metrics() -> &'static Metrics {
             &["route", "method"]
         ).expect("Failed to register http_request_duration_ms metric");
 
-        // Provider metrics as specified in the plan
-        let provider_requests_total = register_int_counter_vec!(
-            "provider_requests_total",
-            "Total number of AI provider requests",
-            &["provider", "op"]
-        ).expect("Failed to register provider_requests_total metric");
-
-        let provider_request_duration_ms = register_histogram_vec!(
-            HistogramOpts::new(
-                "provider_request_duration_ms_bucket",
-                "AI provider request duration in milliseconds"
-            ).buckets(vec![10.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0, 30000.0]),
-            &["provider", "op"]
-        ).expect("Failed to register provider_request_duration_ms metric");
-
         // Agent metrics as specified in the plan
         let agent_step_duration_ms = register_histogram_vec!(
             HistogramOpts::new(
@@ -79,18 +76,66 @@ pub fn init_metrics() -> &'static Metrics {
             "Number of currently active agent executions"
         ).expect("Failed to register active_executions metric");
 
+        let performance_cpu_usage_percent = register_gauge!(
+            "performance_cpu_usage_percent",
+            "CPU usage percentage, as last measured by PerformanceMonitor"
+        ).expect("Failed to register performance_cpu_usage_percent metric");
+
+        let performance_memory_usage_mb = register_gauge!(
+            "performance_memory_usage_mb",
+            "Memory usage in megabytes, as last measured by PerformanceMonitor"
+        ).expect("Failed to register performance_memory_usage_mb metric");
+
+        let performance_active_connections = register_int_gauge!(
+            "performance_active_connections",
+            "Active connection pool connections, as last measured by PerformanceMonitor"
+        ).expect("Failed to register performance_active_connections metric");
+
+        let performance_request_latency_p95_ms = register_gauge!(
+            "performance_request_latency_p95_ms",
+            "P95 request latency in milliseconds, over PerformanceMonitor's last tick window"
+        ).expect("Failed to register performance_request_latency_p95_ms metric");
+
+        let performance_throughput_rps = register_gauge!(
+            "performance_throughput_rps",
+            "Requests per second, over PerformanceMonitor's last tick window"
+        ).expect("Failed to register performance_throughput_rps metric");
+
+        let performance_error_rate = register_gauge!(
+            "performance_error_rate",
+            "Fraction of requests that errored, over PerformanceMonitor's last tick window"
+        ).expect("Failed to register performance_error_rate metric");
+
         Metrics {
             http_requests_total,
             http_request_duration_ms,
-            provider_requests_total,
-            provider_request_duration_ms,
             agent_step_duration_ms,
             suggestion_acceptance_total,
             active_executions,
+            performance_cpu_usage_percent,
+            performance_memory_usage_mb,
+            performance_active_connections,
+            performance_request_latency_p95_ms,
+            performance_throughput_rps,
+            performance_error_rate,
         }
     })
 }
 
 pub fn get_metrics() -> &'static Metrics {
     METRICS.get().expect("Metrics not initialized")
+}
+
+/// Mirrors one `PerformanceMonitor` tick into the Prometheus gauges above,
+/// so `GET /metrics` reports the same numbers `GET /metrics/providers`
+/// returns as JSON. A no-op if `init_metrics` hasn't run yet (e.g. in a unit
+/// test that exercises `PerformanceMonitor` directly).
+pub fn record_performance_metrics(metrics: &crate::performance::PerformanceMetrics) {
+    let Some(m) = METRICS.get() else { return };
+    m.performance_cpu_usage_percent.set(metrics.cpu_usage);
+    m.performance_memory_usage_mb.set(metrics.memory_usage);
+    m.performance_active_connections.set(metrics.active_connections as i64);
+    m.performance_request_latency_p95_ms.set(metrics.request_latency_p95.as_millis() as f64);
+    m.performance_throughput_rps.set(metrics.throughput_rps);
+    m.performance_error_rate.set(metrics.error_rate);
 }
\ No newline at end of file
@@ -1,24 +1,31 @@
 // P0 Task #2: OpenTelemetry tracing IMPLEMENTATION
 use opentelemetry::{
     global,
-    trace::{TraceId, SpanId, TraceError},
+    propagation::{Extractor, Injector},
+    trace::{TraceId, SpanId, TraceError, Tracer, Span as OtelSpan},
     KeyValue,
 };
 use opentelemetry_jaeger::JaegerPipeline;
 use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
 use opentelemetry_semantic_conventions::trace;
 use tracing::{info, warn, Span};
 use tracing_opentelemetry::OpenTelemetryLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 use uuid::Uuid;
 use std::env;
+use std::sync::OnceLock;
+
+/// The OTLP tracer used by `create_request_span` once `init_otel_tracer` has
+/// set one up. Left unset (and spans stay local-only) when
+/// `OTEL_EXPORTER_OTLP_ENDPOINT` isn't configured.
+static OTLP_TRACER: OnceLock<opentelemetry_sdk::trace::Tracer> = OnceLock::new();
 
 /// Initialize OpenTelemetry tracing system with OTLP/Jaeger exporters
 pub fn init_tracing() -> anyhow::Result<()> {
     // Get tracing configuration from environment
     let service_name = env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "uaida-backend".to_string());
     let jaeger_endpoint = env::var("JAEGER_ENDPOINT").unwrap_or_else(|_| "http://localhost:14268/api/traces".to_string());
-    let otlp_endpoint = env::var("OTEL_EXPORTER_OTLP_ENDPOINT").unwrap_or_else(|_| "http://localhost:4317".to_string());
     let enable_tracing = env::var("ENABLE_TRACING").unwrap_or_else(|_| "true".to_string()) == "true";
     
     if !enable_tracing {
@@ -56,8 +63,46 @@ pub fn init_tracing() -> anyhow::Result<()> {
     
     info!("OpenTelemetry tracing system initialized with service: {}", service_name);
     info!("Jaeger endpoint: {}", jaeger_endpoint);
-    info!("OTLP endpoint: {}", otlp_endpoint);
-    
+
+    init_otel_tracer(&service_name)?;
+
+    Ok(())
+}
+
+/// Initialize the OTLP/gRPC trace exporter, if configured.
+///
+/// Reads `OTEL_EXPORTER_OTLP_ENDPOINT` and, when set, installs a batch span
+/// processor that ships spans to that collector (Jaeger, Tempo, or any other
+/// OTLP-compatible backend) over gRPC. When the variable is unset this is a
+/// no-op and `create_request_span` falls back to local-only `tracing` spans.
+pub fn init_otel_tracer(service_name: &str) -> anyhow::Result<()> {
+    let Ok(otlp_endpoint) = env::var("OTEL_EXPORTER_OTLP_ENDPOINT") else {
+        info!("OTEL_EXPORTER_OTLP_ENDPOINT not set, OTLP trace export disabled");
+        return Ok(());
+    };
+
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(&otlp_endpoint),
+        )
+        .with_trace_config(opentelemetry_sdk::trace::config().with_resource(
+            opentelemetry_sdk::Resource::new(vec![KeyValue::new(
+                "service.name",
+                service_name.to_string(),
+            )]),
+        ))
+        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+
+    OTLP_TRACER
+        .set(tracer)
+        .map_err(|_| anyhow::anyhow!("OTLP tracer already initialized"))?;
+
+    info!("OTLP trace export initialized, endpoint: {}", otlp_endpoint);
     Ok(())
 }
 
@@ -72,14 +117,76 @@ pub fn generate_request_id() -> String {
     Uuid::new_v4().to_string()
 }
 
-/// Create span with correlation ID for API requests
+/// The correlation ID for one request, stored in request extensions by the
+/// `X-Request-ID` middleware in `main.rs` so any handler can pull it out
+/// (e.g. to fold into a `BackendError`'s `details`) without re-deriving it.
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// Create span with correlation ID for API requests.
+///
+/// When `init_otel_tracer` has set up an OTLP exporter, this also starts a
+/// matching OTEL span via `tracer.start("request")` and records its trace ID
+/// on the returned span so it shows up in local logs alongside the ID you'd
+/// look up in Jaeger.
 pub fn create_request_span(operation: &str, request_id: &str) -> tracing::Span {
-    tracing::info_span!(
+    let span = tracing::info_span!(
         "request",
         operation = operation,
         request_id = request_id,
-        otel.kind = "server"
-    )
+        otel.kind = "server",
+        trace_id = tracing::field::Empty,
+    );
+
+    if let Some(tracer) = OTLP_TRACER.get() {
+        let otel_span = tracer.start("request");
+        span.record("trace_id", otel_span.span_context().trace_id().to_string().as_str());
+    }
+
+    span
+}
+
+/// Adapts an Axum/http `HeaderMap` so the OpenTelemetry propagator can read
+/// incoming `traceparent`/`tracestate` headers from it.
+struct HeaderExtractor<'a>(&'a axum::http::HeaderMap);
+
+impl<'a> Extractor for HeaderExtractor<'a> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+/// Adapts an Axum/http `HeaderMap` so the OpenTelemetry propagator can write
+/// outgoing `traceparent`/`tracestate` headers onto it.
+struct HeaderInjector<'a>(&'a mut axum::http::HeaderMap);
+
+impl<'a> Injector for HeaderInjector<'a> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(val)) = (
+            axum::http::HeaderName::from_bytes(key.as_bytes()),
+            axum::http::HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, val);
+        }
+    }
+}
+
+/// Extract a W3C TraceContext (`traceparent`/`tracestate`) from inbound
+/// request headers, e.g. to link a request from the Tauri frontend to the
+/// trace it started.
+pub fn extract_trace_context(headers: &axum::http::HeaderMap) -> opentelemetry::Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)))
+}
+
+/// Inject the current trace context into outbound headers, e.g. before
+/// forwarding a request to an AI provider so the whole call chain shares one
+/// trace.
+pub fn inject_trace_context(cx: &opentelemetry::Context, headers: &mut axum::http::HeaderMap) {
+    global::get_text_map_propagator(|propagator| propagator.inject_context(cx, &mut HeaderInjector(headers)))
 }
 
 /// Create span for agent operations
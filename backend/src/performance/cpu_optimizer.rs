@@ -1,6 +1,7 @@
 // Sprint 2: CPU Optimization
 use anyhow::Result;
 use std::sync::Arc;
+use sysinfo::{Pid, System};
 use tokio::sync::{RwLock, Semaphore};
 use tracing::{info, warn};
 
@@ -10,6 +11,12 @@ pub struct CpuOptimizer {
     thread_pool: Arc<rayon::ThreadPool>,
     task_semaphore: Arc<Semaphore>,
     current_load: Arc<RwLock<f64>>,
+    /// Process-level `System` handle used to sample this process's real CPU%
+    /// in `get_cpu_usage`, kept behind a lock so the same handle (and its
+    /// internal delta-from-last-refresh bookkeeping) is reused every tick
+    /// instead of reconstructing it from scratch.
+    system: Arc<RwLock<System>>,
+    pid: Pid,
 }
 
 impl CpuOptimizer {
@@ -19,21 +26,29 @@ impl CpuOptimizer {
             .num_threads(num_cpus)
             .build()
             .expect("Failed to create thread pool");
-        
+
         Self {
             max_cpu_percent,
             thread_pool: Arc::new(thread_pool),
             task_semaphore: Arc::new(Semaphore::new(num_cpus * 2)),
             current_load: Arc::new(RwLock::new(0.0)),
+            system: Arc::new(RwLock::new(System::new())),
+            pid: Pid::from_u32(std::process::id()),
         }
     }
-    
+
+    /// Real CPU% for this process, sampled via `sysinfo`. Falls back to the
+    /// manually-set `current_load` (used by tests, and as a safety net if
+    /// the OS won't report the process) when the process can't be found.
     pub async fn get_cpu_usage(&self) -> f64 {
-        // Simulate CPU usage calculation
-        let load = *self.current_load.read().await;
-        load.min(100.0)
+        let mut system = self.system.write().await;
+        system.refresh_process(self.pid);
+        match system.process(self.pid) {
+            Some(process) => process.cpu_usage() as f64,
+            None => self.current_load.read().await.min(100.0),
+        }
     }
-    
+
     pub async fn reduce_load(&self) -> Result<()> {
         warn!("High CPU usage detected, reducing load");
         
@@ -1,6 +1,7 @@
 // Sprint 2: Memory Management
 use anyhow::Result;
 use std::sync::Arc;
+use sysinfo::{Pid, System};
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
@@ -9,6 +10,11 @@ pub struct MemoryManager {
     max_memory_mb: usize,
     current_usage: Arc<RwLock<usize>>,
     gc_threshold: f64,
+    /// Process-level `System` handle used to sample this process's real RSS
+    /// in `get_memory_usage`. See `CpuOptimizer::system` for why it's kept
+    /// behind a lock rather than rebuilt per call.
+    system: Arc<RwLock<System>>,
+    pid: Pid,
 }
 
 impl MemoryManager {
@@ -17,12 +23,23 @@ impl MemoryManager {
             max_memory_mb,
             current_usage: Arc::new(RwLock::new(0)),
             gc_threshold: 0.8, // Trigger GC at 80% usage
+            system: Arc::new(RwLock::new(System::new())),
+            pid: Pid::from_u32(std::process::id()),
         }
     }
-    
+
+    /// Real RSS for this process, sampled via `sysinfo` and expressed as a
+    /// percentage of `max_memory_mb`. Falls back to the manually-tracked
+    /// `current_usage` (used by tests, and as a safety net if the OS won't
+    /// report the process) when the process can't be found.
     pub async fn get_memory_usage(&self) -> f64 {
-        let current = *self.current_usage.read().await;
-        (current as f64 / self.max_memory_mb as f64) * 100.0
+        let mut system = self.system.write().await;
+        system.refresh_process(self.pid);
+        let used_mb = match system.process(self.pid) {
+            Some(process) => process.memory() as f64 / (1024.0 * 1024.0),
+            None => *self.current_usage.read().await as f64,
+        };
+        (used_mb / self.max_memory_mb as f64) * 100.0
     }
     
     pub async fn allocate(&self, size_mb: usize) -> Result<bool> {
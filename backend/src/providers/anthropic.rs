@@ -1,4 +1,4 @@
-use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, CompletionRequest, CompletionResponse, HealthCheck, ProviderError};
+use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, Attachment, CompletionRequest, CompletionResponse, HealthCheck, ProviderError};
 use crate::config::ProviderConfig;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -6,6 +6,11 @@ use serde_json::json;
 use std::time::Instant;
 use tracing::{debug, info, warn};
 
+/// Anthropic only caches a system/content block once it's at least this many
+/// characters (roughly its documented 1024-token minimum for cacheable
+/// blocks on Sonnet/Opus; smaller prompts aren't worth the extra write cost).
+const PROMPT_CACHE_MIN_CHARS: usize = 4000;
+
 pub struct AnthropicProvider {
     client: Client,
     config: ProviderConfig,
@@ -22,6 +27,13 @@ impl AnthropicProvider {
     }
 
     async fn make_request(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        super::traits::warn_unsupported_sampling_params(
+            "Anthropic",
+            &request,
+            /* supports_penalties */ false,
+            /* supports_stop */ true,
+        );
+
         let api_key = self.config.api_key.as_ref()
             .ok_or_else(|| ProviderError::AuthError("Anthropic API key not configured".to_string()))?;
 
@@ -29,10 +41,10 @@ impl AnthropicProvider {
             .unwrap_or(&"claude-3-haiku-20240307".to_string());
 
         let mut messages = Vec::new();
-        
+
         messages.push(json!({
             "role": "user",
-            "content": request.prompt
+            "content": Self::user_content(&request)?
         }));
 
         let mut payload = json!({
@@ -42,7 +54,7 @@ impl AnthropicProvider {
         });
 
         if let Some(system_prompt) = &request.system_prompt {
-            payload["system"] = json!(system_prompt);
+            payload["system"] = Self::system_prompt_payload(system_prompt, request.prompt_cache);
         }
 
         if let Some(temperature) = request.temperature {
@@ -53,6 +65,20 @@ impl AnthropicProvider {
             payload["top_p"] = json!(top_p);
         }
 
+        if let Some(stop_sequences) = &request.stop_sequences {
+            if !stop_sequences.is_empty() {
+                payload["stop_sequences"] = json!(stop_sequences);
+            }
+        }
+
+        if let Some(tools) = &request.tools {
+            payload["tools"] = json!(Self::to_anthropic_tools(tools));
+        }
+
+        if let Some(tool_choice) = &request.tool_choice {
+            payload["tool_choice"] = tool_choice.clone();
+        }
+
         let response = self.client
             .post(&format!("{}/v1/messages", self.config.base_url))
             .header("x-api-key", api_key)
@@ -65,31 +91,29 @@ impl AnthropicProvider {
 
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = super::http_errors::retry_after_seconds(&response);
             let error_text = response.text().await.unwrap_or_default();
             
-            return Err(match status.as_u16() {
-                401 => ProviderError::AuthError(error_text),
-                429 => ProviderError::RateLimitError(error_text),
-                404 => ProviderError::ModelNotFound(error_text),
-                _ => ProviderError::ApiError(format!("HTTP {}: {}", status, error_text)),
-            });
+            return Err(super::http_errors::map_status_error(status, retry_after, error_text));
         }
 
         let response_json: serde_json::Value = response.json().await
             .map_err(|e| ProviderError::ApiError(format!("Failed to parse response: {}", e)))?;
 
-        let content = response_json["content"].as_array()
-            .and_then(|arr| arr.first())
-            .and_then(|item| item["text"].as_str())
-            .unwrap_or("")
-            .to_string();
+        let content_blocks = response_json["content"].as_array().cloned().unwrap_or_default();
+
+        let text = content_blocks.iter()
+            .filter(|block| block["type"] == "text")
+            .filter_map(|block| block["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("");
 
         let choices = vec![super::traits::Choice {
             index: 0,
-            text: content,
+            text,
             finish_reason: response_json["stop_reason"].as_str().map(|s| s.to_string()),
             logprobs: None,
-            tool_calls: None,
+            tool_calls: Self::parse_tool_calls(&content_blocks),
         }];
 
         let usage = response_json.get("usage").map(|u| super::traits::Usage {
@@ -97,6 +121,8 @@ impl AnthropicProvider {
             completion_tokens: u["output_tokens"].as_u64().unwrap_or(0) as u32,
             total_tokens: (u["input_tokens"].as_u64().unwrap_or(0) + u["output_tokens"].as_u64().unwrap_or(0)) as u32,
             cost_usd: None,
+            cache_creation_tokens: u["cache_creation_input_tokens"].as_u64().map(|t| t as u32),
+            cache_read_tokens: u["cache_read_input_tokens"].as_u64().map(|t| t as u32),
         });
 
         Ok(CompletionResponse {
@@ -107,8 +133,95 @@ impl AnthropicProvider {
             provider: "anthropic".to_string(),
             created_at: chrono::Utc::now(),
             metadata: None,
+            cached: false,
         })
     }
+
+    /// Anthropic's `system` field accepts either a plain string or an array
+    /// of content blocks. Large system prompts (e.g. the conversation
+    /// service's resent workspace context) are split out into their own
+    /// block with `cache_control` so Anthropic reuses the cached prefix on
+    /// the next turn instead of reprocessing it at full price.
+    fn system_prompt_payload(system_prompt: &str, prompt_cache: bool) -> serde_json::Value {
+        if prompt_cache && system_prompt.len() >= PROMPT_CACHE_MIN_CHARS {
+            json!([{
+                "type": "text",
+                "text": system_prompt,
+                "cache_control": { "type": "ephemeral" }
+            }])
+        } else {
+            json!(system_prompt)
+        }
+    }
+
+    /// Builds the `content` value for the user message: a plain string when
+    /// there are no attachments, or Anthropic's content-block array (a text
+    /// block plus an `image` block per attachment) otherwise.
+    fn user_content(request: &CompletionRequest) -> Result<serde_json::Value, ProviderError> {
+        let attachments = match &request.attachments {
+            Some(attachments) if !attachments.is_empty() => attachments,
+            _ => return Ok(json!(request.prompt)),
+        };
+
+        let mut blocks = vec![json!({ "type": "text", "text": request.prompt })];
+        for attachment in attachments {
+            blocks.push(Self::image_block(attachment)?);
+        }
+        Ok(json!(blocks))
+    }
+
+    fn image_block(attachment: &Attachment) -> Result<serde_json::Value, ProviderError> {
+        super::traits::validate_attachment_size(attachment)?;
+
+        let source = if attachment.is_url() {
+            json!({ "type": "url", "url": attachment.data })
+        } else {
+            json!({
+                "type": "base64",
+                "media_type": attachment.mime_type,
+                "data": attachment.data
+            })
+        };
+
+        Ok(json!({ "type": "image", "source": source }))
+    }
+
+    /// Anthropic's tool schema is flatter than OpenAI's `{type, function}`
+    /// wrapper: each tool is `{name, description, input_schema}` directly.
+    fn to_anthropic_tools(tools: &[super::traits::Tool]) -> Vec<serde_json::Value> {
+        tools.iter().map(|tool| {
+            json!({
+                "name": tool.function.name,
+                "description": tool.function.description,
+                "input_schema": tool.function.parameters,
+            })
+        }).collect()
+    }
+
+    /// Anthropic returns tool calls as `tool_use` content blocks interleaved
+    /// with `text` blocks rather than a separate `tool_calls` array, so this
+    /// is parsed independently from the OpenAI-style providers.
+    fn parse_tool_calls(content_blocks: &[serde_json::Value]) -> Option<Vec<super::traits::ToolCall>> {
+        let parsed = content_blocks.iter()
+            .filter(|block| block["type"] == "tool_use")
+            .filter_map(|block| {
+                Some(super::traits::ToolCall {
+                    id: block["id"].as_str()?.to_string(),
+                    r#type: "tool_use".to_string(),
+                    function: super::traits::FunctionCall {
+                        name: block["name"].as_str()?.to_string(),
+                        arguments: block.get("input").map(|v| v.to_string()).unwrap_or_else(|| "{}".to_string()),
+                    },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if parsed.is_empty() {
+            None
+        } else {
+            Some(parsed)
+        }
+    }
 }
 
 #[async_trait]
@@ -255,4 +368,72 @@ impl AIProvider for AnthropicProvider {
         
         Some((tokens as f64 / 1000.0) * cost_per_1k_tokens)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_tools_to_anthropic_input_schema_shape() {
+        let tools = vec![super::super::traits::Tool {
+            r#type: "function".to_string(),
+            function: super::super::traits::Function {
+                name: "get_weather".to_string(),
+                description: "Get the weather for a city".to_string(),
+                parameters: json!({"type": "object", "properties": {"city": {"type": "string"}}}),
+            },
+        }];
+
+        let mapped = AnthropicProvider::to_anthropic_tools(&tools);
+        assert_eq!(mapped.len(), 1);
+        assert_eq!(mapped[0]["name"], "get_weather");
+        assert_eq!(mapped[0]["input_schema"]["type"], "object");
+    }
+
+    #[test]
+    fn round_trips_a_tool_use_content_block() {
+        let content_blocks = json!([
+            {"type": "text", "text": "Let me check the weather."},
+            {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {"city": "Paris"}}
+        ]);
+        let content_blocks = content_blocks.as_array().unwrap();
+
+        let tool_calls = AnthropicProvider::parse_tool_calls(content_blocks).expect("expected tool calls");
+        assert_eq!(tool_calls.len(), 1);
+        assert_eq!(tool_calls[0].id, "toolu_1");
+        assert_eq!(tool_calls[0].function.name, "get_weather");
+        assert_eq!(tool_calls[0].function.arguments, r#"{"city":"Paris"}"#);
+    }
+
+    #[test]
+    fn parses_no_tool_use_blocks_as_none() {
+        let content_blocks = json!([{"type": "text", "text": "No tools needed."}]);
+        assert!(AnthropicProvider::parse_tool_calls(content_blocks.as_array().unwrap()).is_none());
+    }
+
+    #[test]
+    fn caches_large_system_prompts_when_enabled() {
+        let system_prompt = "x".repeat(PROMPT_CACHE_MIN_CHARS);
+        let payload = AnthropicProvider::system_prompt_payload(&system_prompt, true);
+
+        assert_eq!(payload[0]["type"], "text");
+        assert_eq!(payload[0]["cache_control"]["type"], "ephemeral");
+    }
+
+    #[test]
+    fn leaves_small_system_prompts_as_a_plain_string() {
+        let system_prompt = "You are a helpful assistant.";
+        let payload = AnthropicProvider::system_prompt_payload(system_prompt, true);
+
+        assert_eq!(payload, json!(system_prompt));
+    }
+
+    #[test]
+    fn does_not_cache_when_prompt_cache_is_disabled() {
+        let system_prompt = "x".repeat(PROMPT_CACHE_MIN_CHARS);
+        let payload = AnthropicProvider::system_prompt_payload(&system_prompt, false);
+
+        assert_eq!(payload, json!(system_prompt));
+    }
 }
\ No newline at end of file
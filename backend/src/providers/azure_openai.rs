@@ -0,0 +1,372 @@
+use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, CompletionRequest, CompletionResponse, HealthCheck, ProviderError};
+use crate::config::{AzureOpenAIConfig, ProviderConfig};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Instant;
+use tracing::{info, warn};
+
+pub struct AzureOpenAIProvider {
+    client: Client,
+    azure_config: AzureOpenAIConfig,
+    config: ProviderConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AzureDeploymentsResponse {
+    data: Vec<AzureDeployment>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AzureDeployment {
+    id: String,
+}
+
+impl AzureOpenAIProvider {
+    pub fn new(azure_config: AzureOpenAIConfig) -> Result<Self, ProviderError> {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(azure_config.timeout_seconds))
+            .build()
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        // Azure addresses models by deployment name rather than model id, so the
+        // shared ProviderConfig (used for get_config/metrics) mirrors that: its
+        // single "model" is the configured default deployment.
+        let config = ProviderConfig {
+            enabled: azure_config.enabled,
+            api_key: azure_config.api_key.clone(),
+            base_url: azure_config.endpoint.clone(),
+            timeout_seconds: azure_config.timeout_seconds,
+            max_retries: azure_config.max_retries,
+            priority: azure_config.priority,
+            models: vec![azure_config.deployment.clone()],
+        };
+
+        Ok(Self { client, azure_config, config })
+    }
+
+    /// `CompletionRequest.model` maps to the deployment name; falls back to the configured default.
+    fn deployment_for(&self, request: &CompletionRequest) -> String {
+        request.model.clone().unwrap_or_else(|| self.azure_config.deployment.clone())
+    }
+
+    fn deployment_url(&self, deployment: &str, path: &str) -> String {
+        format!(
+            "{}/openai/deployments/{}/{}?api-version={}",
+            self.azure_config.endpoint.trim_end_matches('/'),
+            deployment,
+            path,
+            self.azure_config.api_version
+        )
+    }
+
+    async fn make_request(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        super::traits::reject_attachments(&request, "Azure OpenAI")?;
+
+        let api_key = self.azure_config.api_key.as_ref()
+            .ok_or_else(|| ProviderError::AuthError("Azure OpenAI API key not configured".to_string()))?;
+
+        let deployment = self.deployment_for(&request);
+
+        let mut messages = Vec::new();
+
+        if let Some(system_prompt) = &request.system_prompt {
+            messages.push(json!({
+                "role": "system",
+                "content": system_prompt
+            }));
+        }
+
+        messages.push(json!({
+            "role": "user",
+            "content": request.prompt
+        }));
+
+        let mut payload = json!({
+            "messages": messages,
+            "max_tokens": request.max_tokens.unwrap_or(1000),
+            "temperature": request.temperature.unwrap_or(0.7),
+            "top_p": request.top_p.unwrap_or(0.9),
+            "frequency_penalty": request.frequency_penalty.unwrap_or(0.0),
+            "presence_penalty": request.presence_penalty.unwrap_or(0.0),
+            "stream": request.stream.unwrap_or(false)
+        });
+
+        if let Some(stop) = &request.stop_sequences {
+            payload["stop"] = json!(stop);
+        }
+
+        if let Some(tools) = &request.tools {
+            payload["tools"] = json!(tools);
+        }
+
+        let response = self.client
+            .post(&self.deployment_url(&deployment, "chat/completions"))
+            .header("api-key", api_key)
+            .header("Content-Type", "application/json")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let retry_after = super::http_errors::retry_after_seconds(&response);
+            let error_text = response.text().await.unwrap_or_default();
+
+            return Err(super::http_errors::map_status_error(status, retry_after, error_text));
+        }
+
+        let response_json: serde_json::Value = response.json().await
+            .map_err(|e| ProviderError::ApiError(format!("Failed to parse response: {}", e)))?;
+
+        let choices = response_json["choices"].as_array()
+            .ok_or_else(|| ProviderError::ApiError("No choices in response".to_string()))?;
+
+        let parsed_choices = choices.iter().enumerate().map(|(index, choice)| {
+            super::traits::Choice {
+                index: index as u32,
+                text: choice["message"]["content"].as_str().unwrap_or("").to_string(),
+                finish_reason: choice["finish_reason"].as_str().map(|s| s.to_string()),
+                logprobs: choice.get("logprobs").cloned(),
+                tool_calls: None,
+            }
+        }).collect();
+
+        let usage = response_json.get("usage").map(|u| super::traits::Usage {
+            prompt_tokens: u["prompt_tokens"].as_u64().unwrap_or(0) as u32,
+            completion_tokens: u["completion_tokens"].as_u64().unwrap_or(0) as u32,
+            total_tokens: u["total_tokens"].as_u64().unwrap_or(0) as u32,
+            cost_usd: None,
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
+        });
+
+        Ok(CompletionResponse {
+            id: response_json["id"].as_str().unwrap_or("unknown").to_string(),
+            choices: parsed_choices,
+            usage,
+            model: deployment,
+            provider: "azure_openai".to_string(),
+            created_at: chrono::Utc::now(),
+            metadata: None,
+            cached: false,
+        })
+    }
+}
+
+#[async_trait]
+impl AIProvider for AzureOpenAIProvider {
+    fn name(&self) -> &str {
+        "azure_openai"
+    }
+
+    async fn health_check(&self) -> Result<HealthCheck, ProviderError> {
+        let start = Instant::now();
+
+        match self.list_models().await {
+            Ok(models) => {
+                Ok(HealthCheck {
+                    is_available: true,
+                    response_time_ms: start.elapsed().as_millis() as u64,
+                    supported_models: models,
+                    rate_limit_remaining: None,
+                    error_message: None,
+                })
+            }
+            Err(e) => {
+                Ok(HealthCheck {
+                    is_available: false,
+                    response_time_ms: start.elapsed().as_millis() as u64,
+                    supported_models: vec![],
+                    rate_limit_remaining: None,
+                    error_message: Some(e.to_string()),
+                })
+            }
+        }
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+        let api_key = self.azure_config.api_key.as_ref()
+            .ok_or_else(|| ProviderError::AuthError("Azure OpenAI API key not configured".to_string()))?;
+
+        let url = format!(
+            "{}/openai/deployments?api-version={}",
+            self.azure_config.endpoint.trim_end_matches('/'),
+            self.azure_config.api_version
+        );
+
+        let response = self.client
+            .get(&url)
+            .header("api-key", api_key)
+            .send()
+            .await
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            warn!("Azure OpenAI deployment listing failed with status {}", status);
+            return Err(ProviderError::ApiError(format!("HTTP {}", status)));
+        }
+
+        let deployments: AzureDeploymentsResponse = response.json().await
+            .map_err(|e| ProviderError::ApiError(format!("Failed to parse deployments response: {}", e)))?;
+
+        Ok(deployments.data.into_iter().map(|d| d.id).collect())
+    }
+
+    async fn complete(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        info!("Azure OpenAI completion request for deployment: {}", self.deployment_for(&request));
+        self.make_request(request).await
+    }
+
+    async fn complete_stream(
+        &self,
+        _request: CompletionRequest,
+    ) -> Result<tokio::sync::mpsc::Receiver<Result<String, ProviderError>>, ProviderError> {
+        Err(ProviderError::ApiError("Streaming not yet implemented for Azure OpenAI".to_string()))
+    }
+
+    async fn analyze_code(&self, request: AnalysisRequest) -> Result<AnalysisResponse, ProviderError> {
+        let system_prompt = "You are an expert code analyzer. Provide detailed analysis with specific findings.".to_string();
+
+        let completion_request = CompletionRequest::new(format!(
+            "Analyze this {} code:\n\n```{}\n{}\n```",
+            request.language, request.language, request.code
+        ))
+        .with_system_prompt(system_prompt)
+        .with_temperature(0.3);
+
+        let response = self.complete(completion_request).await?;
+
+        Ok(AnalysisResponse {
+            analysis_type: request.analysis_type,
+            findings: vec![],
+            summary: response.choices.first().map(|c| c.text.clone()).unwrap_or_default(),
+            confidence_score: 0.9,
+            suggestions: vec![],
+        })
+    }
+
+    async fn generate_documentation(&self, code: &str, language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Generate documentation for this {} code:\n\n```{}\n{}\n```",
+            language, language, code
+        )).with_temperature(0.3);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn generate_tests(&self, code: &str, language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Generate unit tests for this {} code:\n\n```{}\n{}\n```",
+            language, language, code
+        )).with_temperature(0.2);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn explain_code(&self, code: &str, language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Explain this {} code:\n\n```{}\n{}\n```",
+            language, language, code
+        )).with_temperature(0.4);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn refactor_code(&self, code: &str, language: &str, instructions: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Refactor this {} code: {}\n\n```{}\n{}\n```",
+            language, instructions, language, code
+        )).with_temperature(0.3);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn translate_code(&self, code: &str, from_language: &str, to_language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Translate this {} code to {}:\n\n```{}\n{}\n```",
+            from_language, to_language, from_language, code
+        )).with_temperature(0.2);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    fn get_config(&self) -> &ProviderConfig {
+        &self.config
+    }
+
+    fn estimate_cost(&self, request: &CompletionRequest) -> Option<f64> {
+        let tokens = request.prompt.len() / 4 + request.max_tokens.unwrap_or(1000) as usize;
+        // Azure bills per deployment/model; gpt-4o-class pricing is used as the default estimate.
+        let cost_per_1k_tokens = match self.deployment_for(request).as_str() {
+            d if d.contains("gpt-4o-mini") => 0.0015,
+            d if d.contains("gpt-4o") => 0.015,
+            d if d.contains("gpt-4") => 0.01,
+            d if d.contains("gpt-35") || d.contains("gpt-3.5") => 0.002,
+            _ => 0.01,
+        };
+
+        Some((tokens as f64 / 1000.0) * cost_per_1k_tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_deployments_response() {
+        let body = r#"{"data": [{"id": "gpt-4o"}, {"id": "gpt-35-turbo"}]}"#;
+        let parsed: AzureDeploymentsResponse = serde_json::from_str(body).unwrap();
+        let ids: Vec<String> = parsed.data.into_iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec!["gpt-4o", "gpt-35-turbo"]);
+    }
+
+    #[test]
+    fn builds_deployment_scoped_url() {
+        let azure_config = AzureOpenAIConfig {
+            enabled: true,
+            api_key: Some("test-key".to_string()),
+            endpoint: "https://my-resource.openai.azure.com/".to_string(),
+            deployment: "gpt-4o".to_string(),
+            api_version: "2024-02-15-preview".to_string(),
+            timeout_seconds: 30,
+            max_retries: 3,
+            priority: 2,
+        };
+        let provider = AzureOpenAIProvider::new(azure_config).unwrap();
+
+        let url = provider.deployment_url("gpt-4o", "chat/completions");
+        assert_eq!(
+            url,
+            "https://my-resource.openai.azure.com/openai/deployments/gpt-4o/chat/completions?api-version=2024-02-15-preview"
+        );
+    }
+
+    #[test]
+    fn completion_request_model_overrides_default_deployment() {
+        let azure_config = AzureOpenAIConfig {
+            enabled: true,
+            api_key: Some("test-key".to_string()),
+            endpoint: "https://my-resource.openai.azure.com".to_string(),
+            deployment: "gpt-4o".to_string(),
+            api_version: "2024-02-15-preview".to_string(),
+            timeout_seconds: 30,
+            max_retries: 3,
+            priority: 2,
+        };
+        let provider = AzureOpenAIProvider::new(azure_config).unwrap();
+
+        let request = CompletionRequest::new("fn main() {}".to_string())
+            .with_model("gpt-4o-mini".to_string());
+        assert_eq!(provider.deployment_for(&request), "gpt-4o-mini");
+    }
+}
@@ -0,0 +1,779 @@
+use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, Choice, CompletionRequest, CompletionResponse, HealthCheck, ProviderError, Usage};
+use crate::config::{BedrockConfig, ProviderConfig};
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use chrono::Utc;
+use futures_util::StreamExt;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::time::Instant;
+use tracing::info;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Models available without a Bedrock control-plane call; `bedrock-runtime`
+/// has no "list models" endpoint of its own, so this is a static catalogue.
+const KNOWN_MODELS: &[&str] = &[
+    "anthropic.claude-3-5-sonnet-20240620-v1:0",
+    "anthropic.claude-3-sonnet-20240229-v1:0",
+    "anthropic.claude-3-haiku-20240307-v1:0",
+    "amazon.titan-text-express-v1",
+    "amazon.titan-text-lite-v1",
+];
+
+/// Model families Bedrock hosts under different request/response shapes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModelFamily {
+    AnthropicClaude,
+    AmazonTitan,
+}
+
+fn model_family(model_id: &str) -> ModelFamily {
+    if model_id.starts_with("anthropic.") {
+        ModelFamily::AnthropicClaude
+    } else {
+        ModelFamily::AmazonTitan
+    }
+}
+
+pub struct BedrockProvider {
+    client: Client,
+    bedrock_config: BedrockConfig,
+    config: ProviderConfig,
+}
+
+impl BedrockProvider {
+    pub fn new(bedrock_config: BedrockConfig) -> Result<Self, ProviderError> {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(bedrock_config.timeout_seconds))
+            .build()
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        // Bedrock is reached with SigV4-signed requests, not a static API key,
+        // so the shared ProviderConfig only carries the model catalogue for
+        // get_config()/metrics purposes.
+        let config = ProviderConfig {
+            enabled: bedrock_config.enabled,
+            api_key: None,
+            base_url: format!("https://bedrock-runtime.{}.amazonaws.com", bedrock_config.region),
+            timeout_seconds: bedrock_config.timeout_seconds,
+            max_retries: bedrock_config.max_retries,
+            priority: bedrock_config.priority,
+            models: KNOWN_MODELS.iter().map(|m| m.to_string()).collect(),
+        };
+
+        Ok(Self { client, bedrock_config, config })
+    }
+
+    /// `CompletionRequest.model` maps to the Bedrock model id; falls back to the configured default.
+    fn model_for(&self, request: &CompletionRequest) -> String {
+        request.model.clone().unwrap_or_else(|| self.bedrock_config.default_model.clone())
+    }
+
+    fn endpoint_host(&self) -> String {
+        format!("bedrock-runtime.{}.amazonaws.com", self.bedrock_config.region)
+    }
+
+    fn invoke_path(&self, model_id: &str, streaming: bool) -> String {
+        let action = if streaming { "invoke-with-response-stream" } else { "invoke" };
+        format!("/model/{}/{}", model_id, action)
+    }
+
+    fn request_body(&self, model_id: &str, request: &CompletionRequest) -> serde_json::Value {
+        match model_family(model_id) {
+            ModelFamily::AnthropicClaude => {
+                let mut body = json!({
+                    "anthropic_version": "bedrock-2023-05-31",
+                    "max_tokens": request.max_tokens.unwrap_or(1000),
+                    "temperature": request.temperature.unwrap_or(0.7),
+                    "top_p": request.top_p.unwrap_or(0.9),
+                    "messages": [{ "role": "user", "content": request.prompt }],
+                });
+
+                if let Some(system_prompt) = &request.system_prompt {
+                    body["system"] = json!(system_prompt);
+                }
+
+                if let Some(stop_sequences) = &request.stop_sequences {
+                    body["stop_sequences"] = json!(stop_sequences);
+                }
+
+                body
+            }
+            ModelFamily::AmazonTitan => {
+                let mut config = json!({
+                    "maxTokenCount": request.max_tokens.unwrap_or(1000),
+                    "temperature": request.temperature.unwrap_or(0.7),
+                    "topP": request.top_p.unwrap_or(0.9),
+                });
+
+                if let Some(stop_sequences) = &request.stop_sequences {
+                    config["stopSequences"] = json!(stop_sequences);
+                }
+
+                json!({
+                    "inputText": request.prompt,
+                    "textGenerationConfig": config
+                })
+            }
+        }
+    }
+
+    fn parse_choices(&self, model_id: &str, body: &serde_json::Value) -> Vec<Choice> {
+        match model_family(model_id) {
+            ModelFamily::AnthropicClaude => {
+                let text = body["content"].as_array()
+                    .and_then(|blocks| blocks.iter().find(|b| b["type"] == "text"))
+                    .and_then(|b| b["text"].as_str())
+                    .unwrap_or("")
+                    .to_string();
+
+                vec![Choice {
+                    index: 0,
+                    text,
+                    finish_reason: body["stop_reason"].as_str().map(|s| s.to_string()),
+                    logprobs: None,
+                    tool_calls: None,
+                }]
+            }
+            ModelFamily::AmazonTitan => body["results"].as_array()
+                .map(|results| results.iter().enumerate().map(|(index, r)| Choice {
+                    index: index as u32,
+                    text: r["outputText"].as_str().unwrap_or("").to_string(),
+                    finish_reason: r["completionReason"].as_str().map(|s| s.to_string()),
+                    logprobs: None,
+                    tool_calls: None,
+                }).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn usage_from(&self, model_id: &str, body: &serde_json::Value) -> Option<Usage> {
+        match model_family(model_id) {
+            ModelFamily::AnthropicClaude => {
+                let usage = body.get("usage")?;
+                let prompt_tokens = usage["input_tokens"].as_u64().unwrap_or(0) as u32;
+                let completion_tokens = usage["output_tokens"].as_u64().unwrap_or(0) as u32;
+                Some(Usage {
+                    prompt_tokens,
+                    completion_tokens,
+                    total_tokens: prompt_tokens + completion_tokens,
+                    cost_usd: None,
+                    cache_creation_tokens: None,
+                    cache_read_tokens: None,
+                })
+            }
+            ModelFamily::AmazonTitan => {
+                let prompt_tokens = body["inputTextTokenCount"].as_u64()? as u32;
+                Some(Usage {
+                    prompt_tokens,
+                    completion_tokens: 0,
+                    total_tokens: prompt_tokens,
+                    cost_usd: None,
+                    cache_creation_tokens: None,
+                    cache_read_tokens: None,
+                })
+            }
+        }
+    }
+
+    fn signer(&self) -> Result<SigV4Signer<'_>, ProviderError> {
+        let access_key = self.bedrock_config.access_key_id.as_deref()
+            .ok_or_else(|| ProviderError::AuthError("AWS access key not configured".to_string()))?;
+        let secret_key = self.bedrock_config.secret_access_key.as_deref()
+            .ok_or_else(|| ProviderError::AuthError("AWS secret key not configured".to_string()))?;
+
+        Ok(SigV4Signer {
+            access_key,
+            secret_key,
+            session_token: self.bedrock_config.session_token.as_deref(),
+            region: &self.bedrock_config.region,
+            service: "bedrock",
+        })
+    }
+
+    async fn make_request(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        super::traits::reject_attachments(&request, "Amazon Bedrock")?;
+        super::traits::warn_unsupported_sampling_params(
+            "Amazon Bedrock",
+            &request,
+            /* supports_penalties */ false,
+            /* supports_stop */ true,
+        );
+
+        let model_id = self.model_for(&request);
+        let body = self.request_body(&model_id, &request);
+        let body_bytes = serde_json::to_vec(&body)
+            .map_err(|e| ProviderError::ApiError(format!("Failed to encode request: {}", e)))?;
+
+        let host = self.endpoint_host();
+        let path = self.invoke_path(&model_id, false);
+        let signed_headers = self.signer()?.sign("POST", &host, &path, &body_bytes, Utc::now());
+
+        let mut builder = self.client
+            .post(format!("https://{}{}", host, path))
+            .header("Content-Type", "application/json")
+            .body(body_bytes);
+
+        for (name, value) in signed_headers {
+            builder = builder.header(name, value);
+        }
+
+        let response = builder.send().await
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let retry_after = super::http_errors::retry_after_seconds(&response);
+            let error_text = response.text().await.unwrap_or_default();
+
+            return Err(super::http_errors::map_status_error(status, retry_after, error_text));
+        }
+
+        let response_json: serde_json::Value = response.json().await
+            .map_err(|e| ProviderError::ApiError(format!("Failed to parse response: {}", e)))?;
+
+        Ok(CompletionResponse {
+            id: uuid::Uuid::new_v4().to_string(),
+            choices: self.parse_choices(&model_id, &response_json),
+            usage: self.usage_from(&model_id, &response_json),
+            model: model_id,
+            provider: "bedrock".to_string(),
+            created_at: chrono::Utc::now(),
+            metadata: None,
+            cached: false,
+        })
+    }
+}
+
+#[async_trait]
+impl AIProvider for BedrockProvider {
+    fn name(&self) -> &str {
+        "bedrock"
+    }
+
+    async fn health_check(&self) -> Result<HealthCheck, ProviderError> {
+        let start = Instant::now();
+
+        if self.bedrock_config.access_key_id.is_none() || self.bedrock_config.secret_access_key.is_none() {
+            return Ok(HealthCheck {
+                is_available: false,
+                response_time_ms: start.elapsed().as_millis() as u64,
+                supported_models: vec![],
+                rate_limit_remaining: None,
+                error_message: Some("AWS credentials not configured".to_string()),
+            });
+        }
+
+        match self.list_models().await {
+            Ok(models) => Ok(HealthCheck {
+                is_available: true,
+                response_time_ms: start.elapsed().as_millis() as u64,
+                supported_models: models,
+                rate_limit_remaining: None,
+                error_message: None,
+            }),
+            Err(e) => Ok(HealthCheck {
+                is_available: false,
+                response_time_ms: start.elapsed().as_millis() as u64,
+                supported_models: vec![],
+                rate_limit_remaining: None,
+                error_message: Some(e.to_string()),
+            }),
+        }
+    }
+
+    /// `bedrock-runtime` has no model listing endpoint; this returns the
+    /// statically known catalogue instead of making a network call.
+    async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+        Ok(self.config.models.clone())
+    }
+
+    async fn complete(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        info!("Bedrock completion request for model: {}", self.model_for(&request));
+        self.make_request(request).await
+    }
+
+    async fn complete_stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<tokio::sync::mpsc::Receiver<Result<String, ProviderError>>, ProviderError> {
+        let model_id = self.model_for(&request);
+        let body = self.request_body(&model_id, &request);
+        let body_bytes = serde_json::to_vec(&body)
+            .map_err(|e| ProviderError::ApiError(format!("Failed to encode request: {}", e)))?;
+
+        let host = self.endpoint_host();
+        let path = self.invoke_path(&model_id, true);
+        let signed_headers = self.signer()?.sign("POST", &host, &path, &body_bytes, Utc::now());
+
+        let mut builder = self.client
+            .post(format!("https://{}{}", host, path))
+            .header("Content-Type", "application/json")
+            .body(body_bytes);
+
+        for (name, value) in signed_headers {
+            builder = builder.header(name, value);
+        }
+
+        let response = builder.send().await
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let retry_after = super::http_errors::retry_after_seconds(&response);
+            let error_text = response.text().await.unwrap_or_default();
+
+            return Err(super::http_errors::map_status_error(status, retry_after, error_text));
+        }
+
+        let (tx, rx) = tokio::sync::mpsc::channel(32);
+
+        tokio::spawn(async move {
+            let mut stream = response.bytes_stream();
+            let mut buf: Vec<u8> = Vec::new();
+
+            while let Some(chunk) = stream.next().await {
+                let chunk = match chunk {
+                    Ok(c) => c,
+                    Err(e) => {
+                        let _ = tx.send(Err(ProviderError::NetworkError(e.to_string()))).await;
+                        return;
+                    }
+                };
+                buf.extend_from_slice(&chunk);
+
+                let (messages, consumed) = parse_event_stream_messages(&buf);
+                buf.drain(0..consumed);
+
+                for message in &messages {
+                    if let Some(text) = decode_event_payload(&model_id, message) {
+                        if tx.send(Ok(text)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(rx)
+    }
+
+    async fn analyze_code(&self, request: AnalysisRequest) -> Result<AnalysisResponse, ProviderError> {
+        let system_prompt = "You are an expert code analyzer. Provide detailed analysis with specific findings.".to_string();
+
+        let completion_request = CompletionRequest::new(format!(
+            "Analyze this {} code:\n\n```{}\n{}\n```",
+            request.language, request.language, request.code
+        ))
+        .with_system_prompt(system_prompt)
+        .with_temperature(0.3);
+
+        let response = self.complete(completion_request).await?;
+
+        Ok(AnalysisResponse {
+            analysis_type: request.analysis_type,
+            findings: vec![],
+            summary: response.choices.first().map(|c| c.text.clone()).unwrap_or_default(),
+            confidence_score: 0.9,
+            suggestions: vec![],
+        })
+    }
+
+    async fn generate_documentation(&self, code: &str, language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Generate documentation for this {} code:\n\n```{}\n{}\n```",
+            language, language, code
+        )).with_temperature(0.3);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn generate_tests(&self, code: &str, language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Generate unit tests for this {} code:\n\n```{}\n{}\n```",
+            language, language, code
+        )).with_temperature(0.2);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn explain_code(&self, code: &str, language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Explain this {} code:\n\n```{}\n{}\n```",
+            language, language, code
+        )).with_temperature(0.4);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn refactor_code(&self, code: &str, language: &str, instructions: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Refactor this {} code: {}\n\n```{}\n{}\n```",
+            language, instructions, language, code
+        )).with_temperature(0.3);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn translate_code(&self, code: &str, from_language: &str, to_language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Translate this {} code to {}:\n\n```{}\n{}\n```",
+            from_language, to_language, from_language, code
+        )).with_temperature(0.2);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    fn get_config(&self) -> &ProviderConfig {
+        &self.config
+    }
+
+    fn estimate_cost(&self, request: &CompletionRequest) -> Option<f64> {
+        let tokens = request.prompt.len() / 4 + request.max_tokens.unwrap_or(1000) as usize;
+        let cost_per_1k_tokens = match self.model_for(request).as_str() {
+            m if m.contains("claude-3-5-sonnet") => 0.015,
+            m if m.contains("claude-3-sonnet") => 0.015,
+            m if m.contains("claude-3-haiku") => 0.0025,
+            m if m.contains("titan-text-express") => 0.002,
+            m if m.contains("titan-text-lite") => 0.0003,
+            _ => 0.01,
+        };
+
+        Some((tokens as f64 / 1000.0) * cost_per_1k_tokens)
+    }
+}
+
+/// Minimal AWS Signature Version 4 signer, scoped to what `bedrock-runtime`
+/// needs (no query-string signing). Credentials come from `BedrockConfig`
+/// rather than `ApiKeyManager` since Bedrock auth is a signed request, not a
+/// bearer token.
+struct SigV4Signer<'a> {
+    access_key: &'a str,
+    secret_key: &'a str,
+    session_token: Option<&'a str>,
+    region: &'a str,
+    service: &'a str,
+}
+
+impl<'a> SigV4Signer<'a> {
+    fn sign(
+        &self,
+        method: &str,
+        host: &str,
+        path: &str,
+        body: &[u8],
+        timestamp: chrono::DateTime<Utc>,
+    ) -> Vec<(String, String)> {
+        let amz_date = timestamp.format("%Y%m%dT%H%M%SZ").to_string();
+        let date_stamp = timestamp.format("%Y%m%d").to_string();
+        let payload_hash = hex_encode(&Sha256::digest(body));
+
+        let mut canonical_headers = vec![
+            ("host".to_string(), host.to_string()),
+            ("x-amz-content-sha256".to_string(), payload_hash.clone()),
+            ("x-amz-date".to_string(), amz_date.clone()),
+        ];
+        if let Some(token) = self.session_token {
+            canonical_headers.push(("x-amz-security-token".to_string(), token.to_string()));
+        }
+        canonical_headers.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let canonical_headers_str: String = canonical_headers.iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value))
+            .collect();
+        let signed_headers = canonical_headers.iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(";");
+
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            method, uri_encode_path(path), "", canonical_headers_str, signed_headers, payload_hash
+        );
+
+        let credential_scope = format!("{}/{}/{}/aws4_request", date_stamp, self.region, self.service);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date, credential_scope, hex_encode(&Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let signing_key = self.derive_signing_key(&date_stamp);
+        let signature = hex_encode(&hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key, credential_scope, signed_headers, signature
+        );
+
+        let mut headers = vec![
+            ("Authorization".to_string(), authorization),
+            ("X-Amz-Date".to_string(), amz_date),
+            ("X-Amz-Content-Sha256".to_string(), payload_hash),
+        ];
+        if let Some(token) = self.session_token {
+            headers.push(("X-Amz-Security-Token".to_string(), token.to_string()));
+        }
+
+        headers
+    }
+
+    fn derive_signing_key(&self, date_stamp: &str) -> Vec<u8> {
+        let k_secret = format!("AWS4{}", self.secret_key);
+        let k_date = hmac_sha256(k_secret.as_bytes(), date_stamp.as_bytes());
+        let k_region = hmac_sha256(&k_date, self.region.as_bytes());
+        let k_service = hmac_sha256(&k_region, self.service.as_bytes());
+        hmac_sha256(&k_service, b"aws4_request")
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// URI-encodes a request path for SigV4's CanonicalURI, per RFC 3986
+/// unreserved-char rules. `/` is preserved so each path segment is
+/// percent-encoded independently -- Bedrock model IDs contain `:`
+/// (e.g. `anthropic.claude-3-sonnet-20240229-v1:0`), and AWS rejects a
+/// signature whose canonical request left that unescaped.
+fn uri_encode_path(path: &str) -> String {
+    path.split('/')
+        .map(uri_encode_component)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn uri_encode_component(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect()
+}
+
+/// Parses as many complete `vnd.amazon.event-stream` messages as are present
+/// in `buf`, returning them along with the number of bytes consumed.
+/// Incomplete trailing bytes are left in `buf` for the next chunk.
+fn parse_event_stream_messages(buf: &[u8]) -> (Vec<serde_json::Value>, usize) {
+    let mut messages = Vec::new();
+    let mut offset = 0;
+
+    while offset + 12 <= buf.len() {
+        let total_len = u32::from_be_bytes(buf[offset..offset + 4].try_into().unwrap()) as usize;
+        if total_len < 16 || offset + total_len > buf.len() {
+            break;
+        }
+
+        let headers_len = u32::from_be_bytes(buf[offset + 4..offset + 8].try_into().unwrap()) as usize;
+        let payload_start = offset + 12 + headers_len;
+        let payload_end = offset + total_len - 4;
+
+        if payload_end > payload_start && payload_end <= buf.len() {
+            if let Ok(value) = serde_json::from_slice::<serde_json::Value>(&buf[payload_start..payload_end]) {
+                messages.push(value);
+            }
+        }
+
+        offset += total_len;
+    }
+
+    (messages, offset)
+}
+
+/// Extracts incremental generated text from one decoded event-stream message,
+/// whose `bytes` field is itself base64-encoded, model-specific JSON.
+fn decode_event_payload(model_id: &str, message: &serde_json::Value) -> Option<String> {
+    let encoded = message.get("bytes")?.as_str()?;
+    let decoded = general_purpose::STANDARD.decode(encoded).ok()?;
+    let inner: serde_json::Value = serde_json::from_slice(&decoded).ok()?;
+
+    match model_family(model_id) {
+        ModelFamily::AnthropicClaude => {
+            if inner["type"] == "content_block_delta" {
+                inner["delta"]["text"].as_str().map(|s| s.to_string())
+            } else {
+                None
+            }
+        }
+        ModelFamily::AmazonTitan => inner["outputText"].as_str().map(|s| s.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> BedrockConfig {
+        BedrockConfig {
+            enabled: true,
+            region: "us-east-1".to_string(),
+            access_key_id: Some("AKIAEXAMPLE".to_string()),
+            secret_access_key: Some("secretexample".to_string()),
+            session_token: None,
+            default_model: "anthropic.claude-3-sonnet-20240229-v1:0".to_string(),
+            timeout_seconds: 30,
+            max_retries: 3,
+            priority: 7,
+        }
+    }
+
+    #[test]
+    fn model_family_detects_anthropic_vs_titan() {
+        assert_eq!(model_family("anthropic.claude-3-sonnet-20240229-v1:0"), ModelFamily::AnthropicClaude);
+        assert_eq!(model_family("amazon.titan-text-express-v1"), ModelFamily::AmazonTitan);
+    }
+
+    #[test]
+    fn completion_request_model_overrides_default() {
+        let provider = BedrockProvider::new(test_config()).unwrap();
+        let request = CompletionRequest::new("fn main() {}".to_string())
+            .with_model("amazon.titan-text-express-v1".to_string());
+        assert_eq!(provider.model_for(&request), "amazon.titan-text-express-v1");
+    }
+
+    #[test]
+    fn request_body_includes_stop_sequences_for_both_model_families() {
+        let provider = BedrockProvider::new(test_config()).unwrap();
+        let request = CompletionRequest::new("fn main() {}".to_string())
+            .with_stop_sequences(vec!["\n\n".to_string(), "END".to_string()]);
+
+        let claude_body = provider.request_body("anthropic.claude-3-sonnet-20240229-v1:0", &request);
+        assert_eq!(claude_body["stop_sequences"], json!(["\n\n", "END"]));
+
+        let titan_body = provider.request_body("amazon.titan-text-express-v1", &request);
+        assert_eq!(titan_body["textGenerationConfig"]["stopSequences"], json!(["\n\n", "END"]));
+    }
+
+    #[test]
+    fn builds_invoke_path_for_streaming_and_non_streaming() {
+        let provider = BedrockProvider::new(test_config()).unwrap();
+        assert_eq!(
+            provider.invoke_path("anthropic.claude-3-sonnet-20240229-v1:0", false),
+            "/model/anthropic.claude-3-sonnet-20240229-v1:0/invoke"
+        );
+        assert_eq!(
+            provider.invoke_path("anthropic.claude-3-sonnet-20240229-v1:0", true),
+            "/model/anthropic.claude-3-sonnet-20240229-v1:0/invoke-with-response-stream"
+        );
+    }
+
+    #[test]
+    fn sigv4_signature_is_deterministic_for_fixed_inputs() {
+        let signer = SigV4Signer {
+            access_key: "AKIAEXAMPLE",
+            secret_key: "secretexample",
+            session_token: None,
+            region: "us-east-1",
+            service: "bedrock",
+        };
+
+        let timestamp = chrono::DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let headers_a = signer.sign("POST", "bedrock-runtime.us-east-1.amazonaws.com", "/model/foo/invoke", b"{}", timestamp);
+        let headers_b = signer.sign("POST", "bedrock-runtime.us-east-1.amazonaws.com", "/model/foo/invoke", b"{}", timestamp);
+        assert_eq!(headers_a, headers_b);
+        assert!(headers_a.iter().any(|(name, value)| name == "Authorization" && value.starts_with("AWS4-HMAC-SHA256 Credential=AKIAEXAMPLE/")));
+    }
+
+    #[test]
+    fn uri_encode_path_percent_encodes_colon_but_preserves_slashes() {
+        // Every Bedrock Claude model ID contains a `:` (e.g.
+        // "anthropic.claude-3-sonnet-20240229-v1:0"), which RFC 3986 treats as
+        // reserved and SigV4 requires percent-encoded in the CanonicalURI.
+        assert_eq!(
+            uri_encode_path("/model/anthropic.claude-3-sonnet-20240229-v1:0/invoke"),
+            "/model/anthropic.claude-3-sonnet-20240229-v1%3A0/invoke"
+        );
+    }
+
+    #[test]
+    fn sigv4_signature_over_colon_bearing_model_path_matches_manual_canonical_request() {
+        let signer = SigV4Signer {
+            access_key: "AKIAEXAMPLE",
+            secret_key: "secretexample",
+            session_token: None,
+            region: "us-east-1",
+            service: "bedrock",
+        };
+
+        let timestamp = chrono::DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let path = "/model/anthropic.claude-3-sonnet-20240229-v1:0/invoke";
+        let body = b"{}";
+
+        let headers = signer.sign("POST", "bedrock-runtime.us-east-1.amazonaws.com", path, body, timestamp);
+        let authorization = headers.iter()
+            .find(|(name, _)| name == "Authorization")
+            .map(|(_, value)| value.clone())
+            .unwrap();
+
+        // Reconstruct the canonical request by hand, independently of
+        // `uri_encode_path`, to pin down that the signature really is over
+        // the percent-encoded path and not the raw one.
+        let payload_hash = hex_encode(&Sha256::digest(body));
+        let canonical_headers_str = format!(
+            "host:bedrock-runtime.us-east-1.amazonaws.com\nx-amz-content-sha256:{payload_hash}\nx-amz-date:20240101T000000Z\n"
+        );
+        let canonical_request = format!(
+            "POST\n/model/anthropic.claude-3-sonnet-20240229-v1%3A0/invoke\n\n{canonical_headers_str}\nhost;x-amz-content-sha256;x-amz-date\n{payload_hash}"
+        );
+        let credential_scope = "20240101/us-east-1/bedrock/aws4_request";
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n20240101T000000Z\n{}\n{}",
+            credential_scope, hex_encode(&Sha256::digest(canonical_request.as_bytes()))
+        );
+        let signing_key = signer.derive_signing_key("20240101");
+        let expected_signature = hex_encode(&hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+        assert!(
+            authorization.ends_with(&format!("Signature={expected_signature}")),
+            "signature did not match manually-derived canonical request using the percent-encoded path"
+        );
+    }
+
+    #[test]
+    fn parses_single_event_stream_message() {
+        let payload = br#"{"bytes":"eyJvdXRwdXRUZXh0IjoiaGkifQ=="}"#;
+        let headers_len: u32 = 0;
+        let total_len = 12 + headers_len as usize + payload.len() + 4;
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&(total_len as u32).to_be_bytes());
+        buf.extend_from_slice(&headers_len.to_be_bytes());
+        buf.extend_from_slice(&[0u8; 4]); // prelude crc (unused by this parser)
+        buf.extend_from_slice(payload);
+        buf.extend_from_slice(&[0u8; 4]); // message crc (unused by this parser)
+
+        let (messages, consumed) = parse_event_stream_messages(&buf);
+        assert_eq!(consumed, buf.len());
+        assert_eq!(messages.len(), 1);
+
+        let text = decode_event_payload("amazon.titan-text-express-v1", &messages[0]);
+        assert_eq!(text, Some("hi".to_string()));
+    }
+
+    #[test]
+    fn leaves_incomplete_trailing_message_unconsumed() {
+        let mut buf = vec![0u8; 20];
+        buf[3] = 100; // claims a 100-byte message but buffer is shorter
+        let (messages, consumed) = parse_event_stream_messages(&buf);
+        assert!(messages.is_empty());
+        assert_eq!(consumed, 0);
+    }
+}
@@ -1,4 +1,4 @@
-use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, CompletionRequest, CompletionResponse, HealthCheck, ProviderError};
+use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, CompletionRequest, CompletionResponse, HealthCheck, ProviderError, RerankProvider};
 use futures_util::StreamExt;
 use crate::config::ProviderConfig;
 use async_trait::async_trait;
@@ -23,6 +23,8 @@ impl CohereProvider {
     }
 
     async fn make_request(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        super::traits::reject_attachments(&request, "Cohere")?;
+
         let api_key = self.config.api_key.as_ref()
             .ok_or_else(|| ProviderError::AuthError("Cohere API key not configured".to_string()))?;
 
@@ -35,15 +37,21 @@ impl CohereProvider {
             message = format!("{}\n\n{}", system_prompt, message);
         }
 
-        let payload = json!({
+        let mut payload = json!({
             "model": model,
             "message": message,
             "max_tokens": request.max_tokens.unwrap_or(1000),
             "temperature": request.temperature.unwrap_or(0.7),
             "p": request.top_p.unwrap_or(0.9),
+            "frequency_penalty": request.frequency_penalty.unwrap_or(0.0),
+            "presence_penalty": request.presence_penalty.unwrap_or(0.0),
             "stream": false
         });
 
+        if let Some(stop_sequences) = &request.stop_sequences {
+            payload["stop_sequences"] = json!(stop_sequences);
+        }
+
         debug!("Cohere request: {}", serde_json::to_string_pretty(&payload).unwrap_or_default());
 
         let response = self.client
@@ -57,14 +65,10 @@ impl CohereProvider {
 
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = super::http_errors::retry_after_seconds(&response);
             let error_text = response.text().await.unwrap_or_default();
             
-            return Err(match status.as_u16() {
-                401 => ProviderError::AuthError(error_text),
-                429 => ProviderError::RateLimitError(error_text),
-                404 => ProviderError::ModelNotFound(error_text),
-                _ => ProviderError::ApiError(format!("HTTP {}: {}", status, error_text)),
-            });
+            return Err(super::http_errors::map_status_error(status, retry_after, error_text));
         }
 
         let response_json: serde_json::Value = response.json().await
@@ -93,6 +97,8 @@ impl CohereProvider {
             completion_tokens: completion_tokens as u32,
             total_tokens: (prompt_tokens + completion_tokens) as u32,
             cost_usd: Some(0.003), // Cohere pricing estimate
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
         });
 
         Ok(CompletionResponse {
@@ -103,6 +109,7 @@ impl CohereProvider {
             provider: "cohere".to_string(),
             created_at: chrono::Utc::now(),
             metadata: None,
+            cached: false,
         })
     }
 }
@@ -252,6 +259,9 @@ impl AIProvider for CohereProvider {
                 super::traits::AnalysisType::Documentation => "documentation needs",
                 super::traits::AnalysisType::Testing => "testing requirements",
                 super::traits::AnalysisType::Refactoring => "refactoring opportunities",
+                super::traits::AnalysisType::CodeReview => "overall code review feedback",
+                super::traits::AnalysisType::CodeSmells => "code smells and refactoring opportunities",
+                super::traits::AnalysisType::DocCoverage => "documentation coverage of the public API",
             },
             request.language,
             request.code
@@ -338,4 +348,83 @@ impl AIProvider for CohereProvider {
         
         Some((tokens as f64 / 1000.0) * cost_per_1k_tokens)
     }
+
+    async fn embed(&self, texts: Vec<String>, model: Option<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let api_key = self.config.api_key.as_ref()
+            .ok_or_else(|| ProviderError::AuthError("Cohere API key not configured".to_string()))?;
+
+        let model = model.unwrap_or_else(|| "embed-english-v3.0".to_string());
+
+        let response = self.client
+            .post(&format!("{}/embed", self.config.base_url))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&json!({
+                "texts": texts,
+                "model": model,
+                "input_type": "search_document"
+            }))
+            .send()
+            .await
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let retry_after = super::http_errors::retry_after_seconds(&response);
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(super::http_errors::map_status_error(status, retry_after, error_text));
+        }
+
+        let response_json: serde_json::Value = response.json().await
+            .map_err(|e| ProviderError::ApiError(format!("Failed to parse response: {}", e)))?;
+
+        let embeddings = response_json["embeddings"].as_array()
+            .ok_or_else(|| ProviderError::ApiError("No embeddings in response".to_string()))?;
+
+        Ok(embeddings.iter().map(|embedding| {
+            embedding.as_array()
+                .map(|arr| arr.iter().filter_map(|v| v.as_f64()).map(|f| f as f32).collect())
+                .unwrap_or_default()
+        }).collect())
+    }
+}
+
+#[async_trait]
+impl RerankProvider for CohereProvider {
+    async fn rerank(&self, query: &str, documents: &[String]) -> Result<Vec<(usize, f32)>, ProviderError> {
+        let api_key = self.config.api_key.as_ref()
+            .ok_or_else(|| ProviderError::AuthError("Cohere API key not configured".to_string()))?;
+
+        let response = self.client
+            .post(&format!("{}/rerank", self.config.base_url))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&json!({
+                "model": "rerank-english-v3.0",
+                "query": query,
+                "documents": documents
+            }))
+            .send()
+            .await
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let retry_after = super::http_errors::retry_after_seconds(&response);
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(super::http_errors::map_status_error(status, retry_after, error_text));
+        }
+
+        let response_json: serde_json::Value = response.json().await
+            .map_err(|e| ProviderError::ApiError(format!("Failed to parse response: {}", e)))?;
+
+        let results = response_json["results"].as_array()
+            .ok_or_else(|| ProviderError::ApiError("No results in rerank response".to_string()))?;
+
+        Ok(results.iter().filter_map(|result| {
+            let index = result["index"].as_u64()? as usize;
+            let relevance_score = result["relevance_score"].as_f64()? as f32;
+            Some((index, relevance_score))
+        }).collect())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,344 @@
+use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, CompletionRequest, CompletionResponse, HealthCheck, ProviderError};
+use crate::config::ProviderConfig;
+use anyhow::{anyhow, Result as AnyResult};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::json;
+use std::net::IpAddr;
+use std::time::Instant;
+use tracing::info;
+
+/// Generic client for a user's self-hosted OpenAI-compatible endpoint (vLLM,
+/// LM Studio, text-generation-webui, etc.). Unlike the other providers in
+/// this module, its `base_url`/`api_key`/default model come from a per-user
+/// `custom_providers` row (see `auth::custom_provider_manager`) rather than
+/// the global `Config`, so it's constructed on demand instead of being
+/// registered in `ProviderRouter`'s static provider map.
+pub struct CustomOpenAIProvider {
+    client: Client,
+    name: String,
+    config: ProviderConfig,
+}
+
+impl CustomOpenAIProvider {
+    pub fn new(name: String, base_url: String, api_key: Option<String>, default_model: Option<String>) -> Result<Self, ProviderError> {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(60))
+            .build()
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        let config = ProviderConfig {
+            enabled: true,
+            api_key,
+            base_url,
+            timeout_seconds: 60,
+            max_retries: 2,
+            priority: 50,
+            models: default_model.into_iter().collect(),
+        };
+
+        Ok(Self { client, name, config })
+    }
+
+    async fn make_request(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        super::traits::reject_attachments(&request, &self.name)?;
+
+        let model = request.model.as_ref()
+            .or_else(|| self.config.models.first())
+            .ok_or_else(|| ProviderError::InvalidRequest(format!("No model specified for custom provider '{}'", self.name)))?;
+
+        let mut messages = Vec::new();
+        if let Some(system_prompt) = &request.system_prompt {
+            messages.push(json!({ "role": "system", "content": system_prompt }));
+        }
+        messages.push(json!({ "role": "user", "content": request.prompt }));
+
+        let mut payload = json!({
+            "model": model,
+            "messages": messages,
+            "max_tokens": request.max_tokens.unwrap_or(1000),
+            "temperature": request.temperature.unwrap_or(0.7),
+            "top_p": request.top_p.unwrap_or(0.9),
+            "stream": false
+        });
+
+        if let Some(stop) = &request.stop_sequences {
+            payload["stop"] = json!(stop);
+        }
+
+        let mut req = self.client
+            .post(format!("{}/v1/chat/completions", self.config.base_url))
+            .header("Content-Type", "application/json");
+
+        if let Some(api_key) = &self.config.api_key {
+            req = req.header("Authorization", format!("Bearer {}", api_key));
+        }
+
+        let response = req.json(&payload).send().await
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let retry_after = super::http_errors::retry_after_seconds(&response);
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(super::http_errors::map_status_error(status, retry_after, error_text));
+        }
+
+        let response_json: serde_json::Value = response.json().await
+            .map_err(|e| ProviderError::ApiError(format!("Failed to parse response: {}", e)))?;
+
+        let choices = response_json["choices"].as_array()
+            .ok_or_else(|| ProviderError::ApiError("No choices in response".to_string()))?;
+
+        let parsed_choices = choices.iter().enumerate().map(|(index, choice)| {
+            super::traits::Choice {
+                index: index as u32,
+                text: choice["message"]["content"].as_str().unwrap_or("").to_string(),
+                finish_reason: choice["finish_reason"].as_str().map(|s| s.to_string()),
+                logprobs: choice.get("logprobs").cloned(),
+                tool_calls: None,
+            }
+        }).collect();
+
+        let usage = response_json.get("usage").map(|u| super::traits::Usage {
+            prompt_tokens: u["prompt_tokens"].as_u64().unwrap_or(0) as u32,
+            completion_tokens: u["completion_tokens"].as_u64().unwrap_or(0) as u32,
+            total_tokens: u["total_tokens"].as_u64().unwrap_or(0) as u32,
+            cost_usd: Some(0.0),
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
+        });
+
+        Ok(CompletionResponse {
+            id: response_json["id"].as_str().unwrap_or("unknown").to_string(),
+            choices: parsed_choices,
+            usage,
+            model: model.clone(),
+            provider: self.name.clone(),
+            created_at: chrono::Utc::now(),
+            metadata: None,
+            cached: false,
+        })
+    }
+}
+
+#[async_trait]
+impl AIProvider for CustomOpenAIProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn health_check(&self) -> Result<HealthCheck, ProviderError> {
+        let start = Instant::now();
+        match self.list_models().await {
+            Ok(models) => Ok(HealthCheck {
+                is_available: true,
+                response_time_ms: start.elapsed().as_millis() as u64,
+                supported_models: models,
+                rate_limit_remaining: None,
+                error_message: None,
+            }),
+            Err(e) => Ok(HealthCheck {
+                is_available: false,
+                response_time_ms: start.elapsed().as_millis() as u64,
+                supported_models: vec![],
+                rate_limit_remaining: None,
+                error_message: Some(e.to_string()),
+            }),
+        }
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+        let mut req = self.client.get(format!("{}/v1/models", self.config.base_url));
+        if let Some(api_key) = &self.config.api_key {
+            req = req.header("Authorization", format!("Bearer {}", api_key));
+        }
+
+        let response = req.send().await.map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(ProviderError::ApiError(format!("Failed to list models from '{}'", self.name)));
+        }
+
+        let body: serde_json::Value = response.json().await
+            .map_err(|e| ProviderError::ApiError(format!("Failed to parse models response: {}", e)))?;
+
+        let models = body["data"].as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .filter_map(|m| m["id"].as_str().map(|s| s.to_string()))
+            .collect();
+
+        Ok(models)
+    }
+
+    async fn complete(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        info!("Custom provider '{}' completion request for model: {:?}", self.name, request.model);
+        self.make_request(request).await
+    }
+
+    async fn complete_stream(
+        &self,
+        _request: CompletionRequest,
+    ) -> Result<tokio::sync::mpsc::Receiver<Result<String, ProviderError>>, ProviderError> {
+        Err(ProviderError::ApiError(format!("Streaming not yet implemented for custom provider '{}'", self.name)))
+    }
+
+    async fn analyze_code(&self, request: AnalysisRequest) -> Result<AnalysisResponse, ProviderError> {
+        let completion_request = CompletionRequest::new(format!(
+            "Analyze this {} code:\n\n```{}\n{}\n```",
+            request.language, request.language, request.code
+        ))
+        .with_system_prompt("You are an expert code analyzer. Provide detailed analysis with specific findings.".to_string())
+        .with_temperature(0.3);
+
+        let response = self.complete(completion_request).await?;
+
+        Ok(AnalysisResponse {
+            analysis_type: request.analysis_type,
+            findings: vec![],
+            summary: response.choices.first().map(|c| c.text.clone()).unwrap_or_default(),
+            confidence_score: 0.7,
+            suggestions: vec![],
+        })
+    }
+
+    async fn generate_documentation(&self, code: &str, language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Generate documentation for this {} code:\n\n```{}\n{}\n```",
+            language, language, code
+        )).with_temperature(0.3);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn generate_tests(&self, code: &str, language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Generate unit tests for this {} code:\n\n```{}\n{}\n```",
+            language, language, code
+        )).with_temperature(0.2);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn explain_code(&self, code: &str, language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Explain this {} code:\n\n```{}\n{}\n```",
+            language, language, code
+        )).with_temperature(0.4);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn refactor_code(&self, code: &str, language: &str, instructions: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Refactor this {} code: {}\n\n```{}\n{}\n```",
+            language, instructions, language, code
+        )).with_temperature(0.3);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn translate_code(&self, code: &str, from_language: &str, to_language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Translate this {} code to {}:\n\n```{}\n{}\n```",
+            from_language, to_language, from_language, code
+        )).with_temperature(0.2);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    fn get_config(&self) -> &ProviderConfig {
+        &self.config
+    }
+
+    fn supports_native_json_mode(&self) -> bool {
+        true
+    }
+
+    fn estimate_cost(&self, _request: &CompletionRequest) -> Option<f64> {
+        // Self-hosted endpoints have no per-token billing we can observe.
+        Some(0.0)
+    }
+}
+
+/// Blocks a custom provider's `base_url` from resolving to a loopback,
+/// private, or link-local address (which covers the `169.254.0.0/16` cloud
+/// metadata range) so a user can't point the backend at internal
+/// infrastructure via a disguised public hostname. `allow_internal_network`
+/// is only ever set from a request that passed `User::is_admin`, for the
+/// legitimate case of an operator wiring up a LAN-hosted vLLM instance.
+pub async fn validate_base_url(base_url: &str, allow_internal_network: bool) -> AnyResult<()> {
+    let url = url::Url::parse(base_url).map_err(|e| anyhow!("Invalid base_url: {e}"))?;
+
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(anyhow!("base_url must use http or https"));
+    }
+
+    let host = url.host_str().ok_or_else(|| anyhow!("base_url must include a host"))?;
+
+    if allow_internal_network {
+        return Ok(());
+    }
+
+    let port = url.port_or_known_default().unwrap_or(443);
+    let addrs = tokio::net::lookup_host((host, port)).await
+        .map_err(|e| anyhow!("Failed to resolve base_url host '{host}': {e}"))?;
+
+    for addr in addrs {
+        if is_blocked_ip(addr.ip()) {
+            return Err(anyhow!("base_url resolves to a blocked internal/metadata address: {}", addr.ip()));
+        }
+    }
+
+    Ok(())
+}
+
+fn is_blocked_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => v4.is_loopback() || v4.is_private() || v4.is_link_local() || v4.is_unspecified(),
+        IpAddr::V6(v6) => {
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || (v6.segments()[0] & 0xfe00) == 0xfc00 // unique local (fc00::/7)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rejects_metadata_ip_literal() {
+        let result = validate_base_url("http://169.254.169.254/v1", false).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_loopback_literal() {
+        let result = validate_base_url("http://127.0.0.1:8000/v1", false).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn allows_loopback_when_internal_network_permitted() {
+        let result = validate_base_url("http://127.0.0.1:8000/v1", true).await;
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn classifies_link_local_metadata_range_as_blocked() {
+        assert!(is_blocked_ip("169.254.169.254".parse().unwrap()));
+    }
+
+    #[test]
+    fn classifies_public_ip_as_allowed() {
+        assert!(!is_blocked_ip("93.184.216.34".parse().unwrap()));
+    }
+}
@@ -1,4 +1,4 @@
-use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, CompletionRequest, CompletionResponse, HealthCheck, ProviderError};
+use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, Attachment, CompletionRequest, CompletionResponse, HealthCheck, ProviderError};
 use crate::config::ProviderConfig;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -21,7 +21,33 @@ impl GoogleProvider {
         Ok(Self { client, config })
     }
 
+    /// Gemini's `inlineData` part only accepts base64-encoded bytes, not a
+    /// fetchable URL, so a URL attachment is rejected here rather than
+    /// silently dropped.
+    fn image_part(attachment: &Attachment) -> Result<serde_json::Value, ProviderError> {
+        if attachment.is_url() {
+            return Err(ProviderError::InvalidRequest(
+                "Google Gemini requires base64-encoded image data, not a URL".to_string(),
+            ));
+        }
+        super::traits::validate_attachment_size(attachment)?;
+
+        Ok(json!({
+            "inline_data": {
+                "mime_type": attachment.mime_type,
+                "data": attachment.data
+            }
+        }))
+    }
+
     async fn make_request(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        super::traits::warn_unsupported_sampling_params(
+            "Google Gemini",
+            &request,
+            /* supports_penalties */ false,
+            /* supports_stop */ true,
+        );
+
         let api_key = self.config.api_key.as_ref()
             .ok_or_else(|| ProviderError::AuthError("Google API key not configured".to_string()))?;
 
@@ -32,6 +58,12 @@ impl GoogleProvider {
             "text": request.prompt
         })];
 
+        if let Some(attachments) = &request.attachments {
+            for attachment in attachments {
+                parts.push(Self::image_part(attachment)?);
+            }
+        }
+
         if let Some(system_prompt) = &request.system_prompt {
             parts.insert(0, json!({
                 "text": format!("System: {}", system_prompt)
@@ -62,14 +94,10 @@ impl GoogleProvider {
 
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = super::http_errors::retry_after_seconds(&response);
             let error_text = response.text().await.unwrap_or_default();
             
-            return Err(match status.as_u16() {
-                401 => ProviderError::AuthError(error_text),
-                429 => ProviderError::RateLimitError(error_text),
-                404 => ProviderError::ModelNotFound(error_text),
-                _ => ProviderError::ApiError(format!("HTTP {}: {}", status, error_text)),
-            });
+            return Err(super::http_errors::map_status_error(status, retry_after, error_text));
         }
 
         let response_json: serde_json::Value = response.json().await
@@ -107,6 +135,8 @@ impl GoogleProvider {
             completion_tokens: completion_tokens as u32,
             total_tokens: (prompt_tokens + completion_tokens) as u32,
             cost_usd: Some(0.001), // Approximate cost
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
         });
 
         Ok(CompletionResponse {
@@ -117,6 +147,7 @@ impl GoogleProvider {
             provider: "google".to_string(),
             created_at: chrono::Utc::now(),
             metadata: None,
+            cached: false,
         })
     }
 }
@@ -202,6 +233,9 @@ impl AIProvider for GoogleProvider {
                 super::traits::AnalysisType::Documentation => "documentation needs",
                 super::traits::AnalysisType::Testing => "testing requirements",
                 super::traits::AnalysisType::Refactoring => "refactoring opportunities",
+                super::traits::AnalysisType::CodeReview => "overall code review feedback",
+                super::traits::AnalysisType::CodeSmells => "code smells and refactoring opportunities",
+                super::traits::AnalysisType::DocCoverage => "documentation coverage of the public API",
             },
             request.language,
             request.code
@@ -22,6 +22,8 @@ impl GroqProvider {
     }
 
     async fn make_request(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        super::traits::reject_attachments(&request, "Groq")?;
+
         let api_key = self.config.api_key.as_ref()
             .ok_or_else(|| ProviderError::AuthError("Groq API key not configured".to_string()))?;
 
@@ -42,15 +44,25 @@ impl GroqProvider {
             "content": request.prompt
         }));
 
-        let payload = json!({
+        let mut payload = json!({
             "model": model,
             "messages": messages,
             "max_tokens": request.max_tokens.unwrap_or(1000),
             "temperature": request.temperature.unwrap_or(0.7),
             "top_p": request.top_p.unwrap_or(0.9),
+            "frequency_penalty": request.frequency_penalty.unwrap_or(0.0),
+            "presence_penalty": request.presence_penalty.unwrap_or(0.0),
             "stream": false
         });
 
+        if let Some(stop) = &request.stop_sequences {
+            payload["stop"] = json!(stop);
+        }
+
+        if let Some(response_format) = &request.response_format {
+            payload["response_format"] = response_format.to_openai_json();
+        }
+
         let response = self.client
             .post(&format!("{}/chat/completions", self.config.base_url))
             .header("Authorization", format!("Bearer {}", api_key))
@@ -62,14 +74,10 @@ impl GroqProvider {
 
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = super::http_errors::retry_after_seconds(&response);
             let error_text = response.text().await.unwrap_or_default();
             
-            return Err(match status.as_u16() {
-                401 => ProviderError::AuthError(error_text),
-                429 => ProviderError::RateLimitError(error_text),
-                404 => ProviderError::ModelNotFound(error_text),
-                _ => ProviderError::ApiError(format!("HTTP {}: {}", status, error_text)),
-            });
+            return Err(super::http_errors::map_status_error(status, retry_after, error_text));
         }
 
         let response_json: serde_json::Value = response.json().await
@@ -93,6 +101,8 @@ impl GroqProvider {
             completion_tokens: u["completion_tokens"].as_u64().unwrap_or(0) as u32,
             total_tokens: u["total_tokens"].as_u64().unwrap_or(0) as u32,
             cost_usd: Some(0.0), // Groq is currently free
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
         });
 
         Ok(CompletionResponse {
@@ -103,6 +113,7 @@ impl GroqProvider {
             provider: "groq".to_string(),
             created_at: chrono::Utc::now(),
             metadata: None,
+            cached: false,
         })
     }
 }
@@ -234,6 +245,10 @@ impl AIProvider for GroqProvider {
         &self.config
     }
 
+    fn supports_native_json_mode(&self) -> bool {
+        true
+    }
+
     fn estimate_cost(&self, _request: &CompletionRequest) -> Option<f64> {
         Some(0.0) // Groq is currently free
     }
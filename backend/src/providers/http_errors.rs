@@ -0,0 +1,30 @@
+//! Shared HTTP response -> `ProviderError` mapping used by every
+//! OpenAI-compatible provider client, so `Retry-After` parsing and the
+//! 429/503 status mapping stay in one place instead of being copy-pasted
+//! across provider files.
+
+use super::traits::ProviderError;
+
+/// Parses a response's `Retry-After` header as a number of seconds.
+/// `Retry-After` can also be an HTTP date, which isn't handled here since
+/// none of the providers we talk to send it that way in practice.
+pub fn retry_after_seconds(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+}
+
+/// Maps a non-success status code to a `ProviderError`, carrying
+/// `retry_after_seconds` through for the statuses that can reasonably
+/// recover on their own (429 and 503).
+pub fn map_status_error(status: reqwest::StatusCode, retry_after: Option<u64>, body: String) -> ProviderError {
+    match status.as_u16() {
+        401 | 403 => ProviderError::AuthError(body),
+        404 => ProviderError::ModelNotFound(body),
+        429 => ProviderError::RateLimitError { message: body, retry_after_seconds: retry_after },
+        503 => ProviderError::Unavailable { message: body, retry_after_seconds: retry_after },
+        _ => ProviderError::ApiError(format!("HTTP {}: {}", status, body)),
+    }
+}
@@ -0,0 +1,71 @@
+//! Fallback JSON-mode support for providers without a native `response_format`
+//! parameter: the requested shape is folded into the prompt, and the raw
+//! completion text is cleaned up and parsed, with one retry on failure.
+//! Used by `AIProvider::complete_json`'s default implementation.
+
+use super::traits::{ProviderError, ResponseFormat};
+
+/// Appends instructions asking the model to respond with JSON matching
+/// `format`, for providers that don't support a native `response_format`
+/// parameter.
+pub fn wrap_prompt_for_json(prompt: &str, format: &ResponseFormat) -> String {
+    match format {
+        ResponseFormat::JsonObject => format!(
+            "{prompt}\n\nRespond with a single valid JSON object and nothing else \
+             (no prose, no markdown code fences)."
+        ),
+        ResponseFormat::JsonSchema { schema } => format!(
+            "{prompt}\n\nRespond with a single valid JSON value matching this JSON \
+             Schema and nothing else (no prose, no markdown code fences):\n{schema}"
+        ),
+    }
+}
+
+/// Builds a retry prompt telling the model its previous response failed to
+/// parse as JSON, quoting the parse error so it can correct itself.
+pub fn wrap_retry_prompt(prompt: &str, previous_output: &str, parse_error: &str) -> String {
+    format!(
+        "{prompt}\n\nYour previous response could not be parsed as JSON (error: {parse_error}).\n\
+         Previous response:\n{previous_output}\n\n\
+         Respond again with only a single valid JSON value, no prose or code fences."
+    )
+}
+
+/// Strips surrounding markdown code fences (```json ... ``` or ``` ... ```)
+/// and parses the remaining text as JSON.
+pub fn extract_json(text: &str) -> Result<serde_json::Value, ProviderError> {
+    let trimmed = text.trim();
+    let unfenced = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .unwrap_or(trimmed)
+        .trim();
+    let unfenced = unfenced.strip_suffix("```").unwrap_or(unfenced).trim();
+
+    serde_json::from_str(unfenced)
+        .map_err(|e| ProviderError::ApiError(format!("Failed to parse JSON response: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_json_from_fenced_code_block() {
+        let text = "```json\n{\"ok\": true}\n```";
+        let value = extract_json(text).unwrap();
+        assert_eq!(value["ok"], true);
+    }
+
+    #[test]
+    fn extracts_json_from_unfenced_text() {
+        let value = extract_json(" {\"ok\": true} ").unwrap();
+        assert_eq!(value["ok"], true);
+    }
+
+    #[test]
+    fn returns_api_error_on_unparseable_text() {
+        let err = extract_json("not json at all").unwrap_err();
+        assert!(matches!(err, ProviderError::ApiError(_)));
+    }
+}
@@ -0,0 +1,357 @@
+use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, CompletionRequest, CompletionResponse, HealthCheck, ProviderError};
+use crate::config::ProviderConfig;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Instant;
+use tracing::{info, warn};
+
+pub struct MistralProvider {
+    client: Client,
+    config: ProviderConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MistralModelsResponse {
+    data: Vec<MistralModel>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MistralModel {
+    id: String,
+}
+
+impl MistralProvider {
+    pub fn new(config: ProviderConfig) -> Result<Self, ProviderError> {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(config.timeout_seconds))
+            .build()
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        Ok(Self { client, config })
+    }
+
+    async fn make_request(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        super::traits::reject_attachments(&request, "Mistral")?;
+        super::traits::warn_unsupported_sampling_params(
+            "Mistral",
+            &request,
+            /* supports_penalties */ false,
+            /* supports_stop */ true,
+        );
+
+        let api_key = self.config.api_key.as_ref()
+            .ok_or_else(|| ProviderError::AuthError("Mistral API key not configured".to_string()))?;
+
+        let model = request.model.as_deref()
+            .unwrap_or("codestral-latest");
+
+        let mut messages = Vec::new();
+
+        if let Some(system_prompt) = &request.system_prompt {
+            messages.push(json!({
+                "role": "system",
+                "content": system_prompt
+            }));
+        }
+
+        messages.push(json!({
+            "role": "user",
+            "content": request.prompt
+        }));
+
+        let mut payload = json!({
+            "model": model,
+            "messages": messages,
+            "max_tokens": request.max_tokens.unwrap_or(1000),
+            "temperature": request.temperature.unwrap_or(0.7),
+            "top_p": request.top_p.unwrap_or(0.9),
+            "stream": false
+        });
+
+        if let Some(stop) = &request.stop_sequences {
+            payload["stop"] = json!(stop);
+        }
+
+        let response = self.client
+            .post(&format!("{}/chat/completions", self.config.base_url))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let retry_after = super::http_errors::retry_after_seconds(&response);
+            let error_text = response.text().await.unwrap_or_default();
+
+            return Err(super::http_errors::map_status_error(status, retry_after, error_text));
+        }
+
+        let response_json: serde_json::Value = response.json().await
+            .map_err(|e| ProviderError::ApiError(format!("Failed to parse response: {}", e)))?;
+
+        let choices = response_json["choices"].as_array()
+            .ok_or_else(|| ProviderError::ApiError("No choices in response".to_string()))?;
+
+        let parsed_choices = choices.iter().enumerate().map(|(index, choice)| {
+            super::traits::Choice {
+                index: index as u32,
+                text: choice["message"]["content"].as_str().unwrap_or("").to_string(),
+                finish_reason: choice["finish_reason"].as_str().map(|s| s.to_string()),
+                logprobs: None,
+                tool_calls: Self::parse_tool_calls(&choice["message"]["tool_calls"]),
+            }
+        }).collect();
+
+        let usage = response_json.get("usage").map(|u| super::traits::Usage {
+            prompt_tokens: u["prompt_tokens"].as_u64().unwrap_or(0) as u32,
+            completion_tokens: u["completion_tokens"].as_u64().unwrap_or(0) as u32,
+            total_tokens: u["total_tokens"].as_u64().unwrap_or(0) as u32,
+            cost_usd: None,
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
+        });
+
+        Ok(CompletionResponse {
+            id: response_json["id"].as_str().unwrap_or("unknown").to_string(),
+            choices: parsed_choices,
+            usage,
+            model: model.to_string(),
+            provider: "mistral".to_string(),
+            created_at: chrono::Utc::now(),
+            metadata: None,
+            cached: false,
+        })
+    }
+
+    /// Mistral encodes tool call arguments slightly differently than OpenAI (it
+    /// may return them as a JSON object rather than a pre-serialized string), so
+    /// this is parsed independently instead of reusing the OpenAI provider's logic.
+    fn parse_tool_calls(value: &serde_json::Value) -> Option<Vec<super::traits::ToolCall>> {
+        let calls = value.as_array()?;
+
+        let parsed = calls.iter().filter_map(|call| {
+            let id = call["id"].as_str()?.to_string();
+            let name = call["function"]["name"].as_str()?.to_string();
+            let arguments = match &call["function"]["arguments"] {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+
+            Some(super::traits::ToolCall {
+                id,
+                r#type: call["type"].as_str().unwrap_or("function").to_string(),
+                function: super::traits::FunctionCall { name, arguments },
+            })
+        }).collect::<Vec<_>>();
+
+        if parsed.is_empty() {
+            None
+        } else {
+            Some(parsed)
+        }
+    }
+}
+
+#[async_trait]
+impl AIProvider for MistralProvider {
+    fn name(&self) -> &str {
+        "mistral"
+    }
+
+    async fn health_check(&self) -> Result<HealthCheck, ProviderError> {
+        let start = Instant::now();
+
+        match self.list_models().await {
+            Ok(models) => {
+                Ok(HealthCheck {
+                    is_available: true,
+                    response_time_ms: start.elapsed().as_millis() as u64,
+                    supported_models: models,
+                    rate_limit_remaining: None,
+                    error_message: None,
+                })
+            }
+            Err(e) => {
+                Ok(HealthCheck {
+                    is_available: false,
+                    response_time_ms: start.elapsed().as_millis() as u64,
+                    supported_models: vec![],
+                    rate_limit_remaining: None,
+                    error_message: Some(e.to_string()),
+                })
+            }
+        }
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+        let api_key = self.config.api_key.as_ref()
+            .ok_or_else(|| ProviderError::AuthError("Mistral API key not configured".to_string()))?;
+
+        let response = self.client
+            .get(&format!("{}/models", self.config.base_url))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .send()
+            .await
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            warn!("Mistral model listing failed with status {}", status);
+            return Err(ProviderError::ApiError(format!("HTTP {}", status)));
+        }
+
+        let models_response: MistralModelsResponse = response.json().await
+            .map_err(|e| ProviderError::ApiError(format!("Failed to parse models response: {}", e)))?;
+
+        Ok(models_response.data.into_iter().map(|m| m.id).collect())
+    }
+
+    async fn complete(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        info!("Mistral completion request for model: {:?}", request.model);
+        self.make_request(request).await
+    }
+
+    async fn complete_stream(
+        &self,
+        _request: CompletionRequest,
+    ) -> Result<tokio::sync::mpsc::Receiver<Result<String, ProviderError>>, ProviderError> {
+        Err(ProviderError::ApiError("Streaming not yet implemented for Mistral".to_string()))
+    }
+
+    async fn analyze_code(&self, request: AnalysisRequest) -> Result<AnalysisResponse, ProviderError> {
+        let system_prompt = "You are an expert code analyzer. Provide detailed analysis.".to_string();
+
+        let completion_request = CompletionRequest::new(format!(
+            "Analyze this {} code:\n\n```{}\n{}\n```",
+            request.language, request.language, request.code
+        ))
+        .with_system_prompt(system_prompt)
+        .with_temperature(0.3);
+
+        let response = self.complete(completion_request).await?;
+
+        Ok(AnalysisResponse {
+            analysis_type: request.analysis_type,
+            findings: vec![],
+            summary: response.choices.first().map(|c| c.text.clone()).unwrap_or_default(),
+            confidence_score: 0.8,
+            suggestions: vec![],
+        })
+    }
+
+    async fn generate_documentation(&self, code: &str, language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Generate documentation for this {} code:\n\n```{}\n{}\n```",
+            language, language, code
+        )).with_temperature(0.3);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn generate_tests(&self, code: &str, language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Generate unit tests for this {} code:\n\n```{}\n{}\n```",
+            language, language, code
+        )).with_temperature(0.2);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn explain_code(&self, code: &str, language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Explain this {} code:\n\n```{}\n{}\n```",
+            language, language, code
+        )).with_temperature(0.4);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn refactor_code(&self, code: &str, language: &str, instructions: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Refactor this {} code: {}\n\n```{}\n{}\n```",
+            language, instructions, language, code
+        )).with_temperature(0.3);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    async fn translate_code(&self, code: &str, from_language: &str, to_language: &str) -> Result<String, ProviderError> {
+        let request = CompletionRequest::new(format!(
+            "Translate this {} code to {}:\n\n```{}\n{}\n```",
+            from_language, to_language, from_language, code
+        )).with_temperature(0.2);
+
+        let response = self.complete(request).await?;
+        Ok(response.choices.first().map(|c| c.text.clone()).unwrap_or_default())
+    }
+
+    fn get_config(&self) -> &ProviderConfig {
+        &self.config
+    }
+
+    fn estimate_cost(&self, request: &CompletionRequest) -> Option<f64> {
+        let tokens = request.max_tokens.unwrap_or(1000) as f64;
+        Some((tokens / 1000.0) * 0.002)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_models_response() {
+        let body = r#"{"data": [{"id": "codestral-latest"}, {"id": "mistral-large-latest"}]}"#;
+        let parsed: MistralModelsResponse = serde_json::from_str(body).unwrap();
+        let ids: Vec<String> = parsed.data.into_iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec!["codestral-latest", "mistral-large-latest"]);
+    }
+
+    #[test]
+    fn serializes_completion_request_payload() {
+        let request = CompletionRequest::new("fn main() {}".to_string())
+            .with_model("codestral-latest".to_string())
+            .with_temperature(0.5);
+
+        let payload = json!({
+            "model": request.model,
+            "messages": [{"role": "user", "content": request.prompt}],
+            "max_tokens": request.max_tokens.unwrap_or(1000),
+            "temperature": request.temperature.unwrap_or(0.7),
+        });
+
+        assert_eq!(payload["model"], "codestral-latest");
+        assert_eq!(payload["temperature"], 0.5);
+    }
+
+    #[test]
+    fn parses_tool_calls_with_object_arguments() {
+        let tool_calls = json!([{
+            "id": "call_1",
+            "type": "function",
+            "function": {
+                "name": "get_weather",
+                "arguments": {"city": "Paris"}
+            }
+        }]);
+
+        let parsed = MistralProvider::parse_tool_calls(&tool_calls).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].function.name, "get_weather");
+        assert_eq!(parsed[0].function.arguments, r#"{"city":"Paris"}"#);
+    }
+
+    #[test]
+    fn parses_no_tool_calls_as_none() {
+        assert!(MistralProvider::parse_tool_calls(&serde_json::Value::Null).is_none());
+    }
+}
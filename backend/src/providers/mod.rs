@@ -1,19 +1,28 @@
 pub mod openrouter;
 pub mod openai;
+pub mod azure_openai;
+pub mod bedrock;
 pub mod anthropic;
 pub mod google;
 pub mod groq;
 pub mod together;
 pub mod cohere;
+pub mod mistral;
 pub mod ollama;
 pub mod router;
 pub mod traits;
 pub mod streaming_traits;
 pub mod openrouter_streaming;
 pub mod cost_calculator;
+pub mod json_mode;
+pub mod tokenizer;
+pub mod http_errors;
+pub mod rate_limiter;
+pub mod custom_openai;
 
 pub use router::ProviderRouter;
-pub use traits::{AIProvider, CompletionRequest, CompletionResponse, ProviderError};
+pub use traits::{AIProvider, CompletionRequest, CompletionResponse, ProviderError, ResponseFormat};
+pub use tokenizer::count_tokens;
 
 use serde::{Deserialize, Serialize};
 
@@ -21,12 +30,18 @@ use serde::{Deserialize, Serialize};
 pub enum ProviderType {
     OpenRouter,
     OpenAI,
+    AzureOpenAI,
+    Bedrock,
     Anthropic,
     Google,
     Groq,
     Together,
     Cohere,
+    Mistral,
     Ollama,
+    /// A user's self-hosted OpenAI-compatible endpoint (vLLM, LM Studio,
+    /// etc.), backed by `custom_openai::CustomOpenAIProvider`.
+    Custom,
 }
 
 impl std::fmt::Display for ProviderType {
@@ -34,12 +49,16 @@ impl std::fmt::Display for ProviderType {
         match self {
             ProviderType::OpenRouter => write!(f, "openrouter"),
             ProviderType::OpenAI => write!(f, "openai"),
+            ProviderType::AzureOpenAI => write!(f, "azure_openai"),
+            ProviderType::Bedrock => write!(f, "bedrock"),
             ProviderType::Anthropic => write!(f, "anthropic"),
             ProviderType::Google => write!(f, "google"),
             ProviderType::Groq => write!(f, "groq"),
             ProviderType::Together => write!(f, "together"),
             ProviderType::Cohere => write!(f, "cohere"),
+            ProviderType::Mistral => write!(f, "mistral"),
             ProviderType::Ollama => write!(f, "ollama"),
+            ProviderType::Custom => write!(f, "custom"),
         }
     }
 }
@@ -51,6 +70,23 @@ pub struct ProviderHealth {
     pub response_time_ms: Option<u64>,
     pub error_message: Option<String>,
     pub models_available: Vec<String>,
+    pub circuit_state: CircuitState,
+    pub next_probe_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When this health snapshot was last refreshed, either by the
+    /// background monitor or an on-demand probe.
+    pub last_checked: chrono::DateTime<chrono::Utc>,
+}
+
+/// State of a provider's circuit breaker, as tracked by `ProviderRouter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Provider is routable normally.
+    Closed,
+    /// Provider is skipped in routing until the cooldown elapses.
+    Open,
+    /// Cooldown elapsed; the next health check acts as a recovery probe.
+    HalfOpen,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
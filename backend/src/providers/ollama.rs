@@ -22,6 +22,8 @@ impl OllamaProvider {
     }
 
     async fn make_request(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        super::traits::reject_attachments(&request, "Ollama")?;
+
         let model = request.model.as_ref()
             .or_else(|| self.config.models.first())
             .unwrap_or(&"qwen2.5-coder:7b".to_string());
@@ -32,15 +34,23 @@ impl OllamaProvider {
             request.prompt.clone()
         };
 
+        let mut options = json!({
+            "temperature": request.temperature.unwrap_or(0.7),
+            "top_p": request.top_p.unwrap_or(0.9),
+            "num_predict": request.max_tokens.unwrap_or(1000),
+            "frequency_penalty": request.frequency_penalty.unwrap_or(0.0),
+            "presence_penalty": request.presence_penalty.unwrap_or(0.0)
+        });
+
+        if let Some(stop) = &request.stop_sequences {
+            options["stop"] = json!(stop);
+        }
+
         let payload = json!({
             "model": model,
             "prompt": prompt,
             "stream": false,
-            "options": {
-                "temperature": request.temperature.unwrap_or(0.7),
-                "top_p": request.top_p.unwrap_or(0.9),
-                "num_predict": request.max_tokens.unwrap_or(1000)
-            }
+            "options": options
         });
 
         debug!("Ollama request: {}", serde_json::to_string_pretty(&payload).unwrap_or_default());
@@ -83,6 +93,8 @@ impl OllamaProvider {
             completion_tokens: completion_tokens as u32,
             total_tokens: (prompt_tokens + completion_tokens) as u32,
             cost_usd: Some(0.0), // Ollama is free
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
         });
 
         Ok(CompletionResponse {
@@ -93,6 +105,7 @@ impl OllamaProvider {
             provider: "ollama".to_string(),
             created_at: chrono::Utc::now(),
             metadata: None,
+            cached: false,
         })
     }
 }
@@ -256,4 +269,40 @@ impl AIProvider for OllamaProvider {
     fn estimate_cost(&self, _request: &CompletionRequest) -> Option<f64> {
         Some(0.0) // Ollama is free
     }
+
+    async fn embed(&self, texts: Vec<String>, model: Option<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let model = model.unwrap_or_else(|| "nomic-embed-text".to_string());
+
+        // Ollama's /api/embeddings endpoint embeds one prompt per request.
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for text in texts {
+            let response = self.client
+                .post(&format!("{}/api/embeddings", self.config.base_url))
+                .json(&json!({ "model": model, "prompt": text }))
+                .send()
+                .await
+                .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let retry_after = super::http_errors::retry_after_seconds(&response);
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(super::http_errors::map_status_error(status, retry_after, error_text));
+            }
+
+            let response_json: serde_json::Value = response.json().await
+                .map_err(|e| ProviderError::ApiError(format!("Failed to parse response: {}", e)))?;
+
+            let embedding = response_json["embedding"].as_array()
+                .ok_or_else(|| ProviderError::ApiError("No embedding in response".to_string()))?
+                .iter()
+                .filter_map(|v| v.as_f64())
+                .map(|f| f as f32)
+                .collect();
+
+            embeddings.push(embedding);
+        }
+
+        Ok(embeddings)
+    }
 }
\ No newline at end of file
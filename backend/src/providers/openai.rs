@@ -1,4 +1,4 @@
-use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, CompletionRequest, CompletionResponse, HealthCheck, ProviderError};
+use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, Attachment, CompletionRequest, CompletionResponse, HealthCheck, ProviderError};
 use crate::config::ProviderConfig;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -21,6 +21,37 @@ impl OpenAIProvider {
         Ok(Self { client, config })
     }
 
+    /// Builds the `content` value for the user message: a plain string when
+    /// there are no attachments, or OpenAI's vision content-block array
+    /// (`image_url` pointing at either a URL or a `data:` URI) otherwise.
+    fn user_content(request: &CompletionRequest) -> Result<serde_json::Value, ProviderError> {
+        let attachments = match &request.attachments {
+            Some(attachments) if !attachments.is_empty() => attachments,
+            _ => return Ok(json!(request.prompt)),
+        };
+
+        let mut blocks = vec![json!({ "type": "text", "text": request.prompt })];
+        for attachment in attachments {
+            blocks.push(Self::image_block(attachment)?);
+        }
+        Ok(json!(blocks))
+    }
+
+    fn image_block(attachment: &Attachment) -> Result<serde_json::Value, ProviderError> {
+        super::traits::validate_attachment_size(attachment)?;
+
+        let url = if attachment.is_url() {
+            attachment.data.clone()
+        } else {
+            format!("data:{};base64,{}", attachment.mime_type, attachment.data)
+        };
+
+        Ok(json!({
+            "type": "image_url",
+            "image_url": { "url": url }
+        }))
+    }
+
     async fn make_request(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
         let api_key = self.config.api_key.as_ref()
             .ok_or_else(|| ProviderError::AuthError("OpenAI API key not configured".to_string()))?;
@@ -39,7 +70,7 @@ impl OpenAIProvider {
 
         messages.push(json!({
             "role": "user",
-            "content": request.prompt
+            "content": Self::user_content(&request)?
         }));
 
         let mut payload = json!({
@@ -61,6 +92,14 @@ impl OpenAIProvider {
             payload["tools"] = json!(tools);
         }
 
+        if let Some(tool_choice) = &request.tool_choice {
+            payload["tool_choice"] = tool_choice.clone();
+        }
+
+        if let Some(response_format) = &request.response_format {
+            payload["response_format"] = response_format.to_openai_json();
+        }
+
         let response = self.client
             .post(&format!("{}/chat/completions", self.config.base_url))
             .header("Authorization", format!("Bearer {}", api_key))
@@ -72,14 +111,10 @@ impl OpenAIProvider {
 
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = super::http_errors::retry_after_seconds(&response);
             let error_text = response.text().await.unwrap_or_default();
             
-            return Err(match status.as_u16() {
-                401 => ProviderError::AuthError(error_text),
-                429 => ProviderError::RateLimitError(error_text),
-                404 => ProviderError::ModelNotFound(error_text),
-                _ => ProviderError::ApiError(format!("HTTP {}: {}", status, error_text)),
-            });
+            return Err(super::http_errors::map_status_error(status, retry_after, error_text));
         }
 
         let response_json: serde_json::Value = response.json().await
@@ -94,7 +129,7 @@ impl OpenAIProvider {
                 text: choice["message"]["content"].as_str().unwrap_or("").to_string(),
                 finish_reason: choice["finish_reason"].as_str().map(|s| s.to_string()),
                 logprobs: choice.get("logprobs").cloned(),
-                tool_calls: None,
+                tool_calls: Self::parse_tool_calls(&choice["message"]["tool_calls"]),
             }
         }).collect();
 
@@ -103,6 +138,8 @@ impl OpenAIProvider {
             completion_tokens: u["completion_tokens"].as_u64().unwrap_or(0) as u32,
             total_tokens: u["total_tokens"].as_u64().unwrap_or(0) as u32,
             cost_usd: None,
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
         });
 
         Ok(CompletionResponse {
@@ -113,8 +150,34 @@ impl OpenAIProvider {
             provider: "openai".to_string(),
             created_at: chrono::Utc::now(),
             metadata: None,
+            cached: false,
         })
     }
+
+    fn parse_tool_calls(value: &serde_json::Value) -> Option<Vec<super::traits::ToolCall>> {
+        let calls = value.as_array()?;
+
+        let parsed = calls.iter().filter_map(|call| {
+            let id = call["id"].as_str()?.to_string();
+            let name = call["function"]["name"].as_str()?.to_string();
+            let arguments = match &call["function"]["arguments"] {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+
+            Some(super::traits::ToolCall {
+                id,
+                r#type: call["type"].as_str().unwrap_or("function").to_string(),
+                function: super::traits::FunctionCall { name, arguments },
+            })
+        }).collect::<Vec<_>>();
+
+        if parsed.is_empty() {
+            None
+        } else {
+            Some(parsed)
+        }
+    }
 }
 
 #[async_trait]
@@ -242,6 +305,10 @@ impl AIProvider for OpenAIProvider {
         &self.config
     }
 
+    fn supports_native_json_mode(&self) -> bool {
+        true
+    }
+
     fn estimate_cost(&self, request: &CompletionRequest) -> Option<f64> {
         let tokens = request.prompt.len() / 4 + request.max_tokens.unwrap_or(1000) as usize;
         let model = request.model.as_ref().unwrap_or(&"gpt-4o-mini".to_string());
@@ -256,4 +323,110 @@ impl AIProvider for OpenAIProvider {
         
         Some((tokens as f64 / 1000.0) * cost_per_1k_tokens)
     }
+
+    async fn embed(&self, texts: Vec<String>, model: Option<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let api_key = self.config.api_key.as_ref()
+            .ok_or_else(|| ProviderError::AuthError("OpenAI API key not configured".to_string()))?;
+
+        let model = model.unwrap_or_else(|| "text-embedding-3-small".to_string());
+
+        let response = self.client
+            .post(&format!("{}/embeddings", self.config.base_url))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&json!({ "model": model, "input": texts }))
+            .send()
+            .await
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let retry_after = super::http_errors::retry_after_seconds(&response);
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(super::http_errors::map_status_error(status, retry_after, error_text));
+        }
+
+        let response_json: serde_json::Value = response.json().await
+            .map_err(|e| ProviderError::ApiError(format!("Failed to parse response: {}", e)))?;
+
+        parse_embedding_data(&response_json)
+    }
+}
+
+/// Extracts embeddings from an OpenAI-shaped `{"data": [{"embedding": [...], "index": ...}]}`
+/// response, restoring the original request order via each item's `index`.
+fn parse_embedding_data(response: &serde_json::Value) -> Result<Vec<Vec<f32>>, ProviderError> {
+    let data = response["data"].as_array()
+        .ok_or_else(|| ProviderError::ApiError("No data in embeddings response".to_string()))?;
+
+    let mut indexed: Vec<(usize, Vec<f32>)> = data.iter().map(|item| {
+        let index = item["index"].as_u64().unwrap_or(0) as usize;
+        let embedding = item["embedding"].as_array()
+            .map(|arr| arr.iter().filter_map(|v| v.as_f64()).map(|f| f as f32).collect())
+            .unwrap_or_default();
+        (index, embedding)
+    }).collect();
+
+    indexed.sort_by_key(|(index, _)| *index);
+    Ok(indexed.into_iter().map(|(_, embedding)| embedding).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tool_calls_from_message() {
+        let tool_calls = json!([{
+            "id": "call_abc123",
+            "type": "function",
+            "function": {
+                "name": "get_weather",
+                "arguments": "{\"city\":\"Paris\"}"
+            }
+        }]);
+
+        let parsed = OpenAIProvider::parse_tool_calls(&tool_calls).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].id, "call_abc123");
+        assert_eq!(parsed[0].function.name, "get_weather");
+        assert_eq!(parsed[0].function.arguments, r#"{"city":"Paris"}"#);
+    }
+
+    #[test]
+    fn round_trips_a_tool_call_completion_response() {
+        let response_json = json!({
+            "id": "chatcmpl-1",
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {"name": "get_weather", "arguments": "{\"city\":\"Paris\"}"}
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }]
+        });
+
+        let choice = &response_json["choices"][0];
+        let parsed_choice = super::super::traits::Choice {
+            index: 0,
+            text: choice["message"]["content"].as_str().unwrap_or("").to_string(),
+            finish_reason: choice["finish_reason"].as_str().map(|s| s.to_string()),
+            logprobs: None,
+            tool_calls: OpenAIProvider::parse_tool_calls(&choice["message"]["tool_calls"]),
+        };
+
+        let tool_calls = parsed_choice.tool_calls.expect("expected tool calls");
+        assert_eq!(tool_calls[0].function.name, "get_weather");
+        assert_eq!(parsed_choice.finish_reason.as_deref(), Some("tool_calls"));
+    }
+
+    #[test]
+    fn parses_no_tool_calls_as_none() {
+        assert!(OpenAIProvider::parse_tool_calls(&serde_json::Value::Null).is_none());
+    }
 }
\ No newline at end of file
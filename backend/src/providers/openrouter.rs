@@ -1,4 +1,4 @@
-use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, CompletionRequest, CompletionResponse, HealthCheck, ProviderError};
+use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, Attachment, CompletionRequest, CompletionResponse, HealthCheck, ProviderError};
 use crate::config::ProviderConfig;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -21,6 +21,38 @@ impl OpenRouterProvider {
         Ok(Self { client, config })
     }
 
+    /// Builds the `content` value for the user message: a plain string when
+    /// there are no attachments, or OpenAI-style vision content blocks
+    /// (`image_url` pointing at either a URL or a `data:` URI) otherwise —
+    /// OpenRouter speaks the same chat-completions wire format as OpenAI.
+    fn user_content(request: &CompletionRequest) -> Result<serde_json::Value, ProviderError> {
+        let attachments = match &request.attachments {
+            Some(attachments) if !attachments.is_empty() => attachments,
+            _ => return Ok(json!(request.prompt)),
+        };
+
+        let mut blocks = vec![json!({ "type": "text", "text": request.prompt })];
+        for attachment in attachments {
+            blocks.push(Self::image_block(attachment)?);
+        }
+        Ok(json!(blocks))
+    }
+
+    fn image_block(attachment: &Attachment) -> Result<serde_json::Value, ProviderError> {
+        super::traits::validate_attachment_size(attachment)?;
+
+        let url = if attachment.is_url() {
+            attachment.data.clone()
+        } else {
+            format!("data:{};base64,{}", attachment.mime_type, attachment.data)
+        };
+
+        Ok(json!({
+            "type": "image_url",
+            "image_url": { "url": url }
+        }))
+    }
+
     async fn make_request(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
         let api_key = self.config.api_key.as_ref()
             .ok_or_else(|| ProviderError::AuthError("OpenRouter API key not configured".to_string()))?;
@@ -42,10 +74,10 @@ impl OpenRouterProvider {
         // Add main prompt
         messages.push(json!({
             "role": "user",
-            "content": request.prompt
+            "content": Self::user_content(&request)?
         }));
 
-        let payload = json!({
+        let mut payload = json!({
             "model": model,
             "messages": messages,
             "max_tokens": request.max_tokens.unwrap_or(1000),
@@ -57,6 +89,18 @@ impl OpenRouterProvider {
             "stop": request.stop_sequences
         });
 
+        if let Some(tools) = &request.tools {
+            payload["tools"] = json!(tools);
+        }
+
+        if let Some(tool_choice) = &request.tool_choice {
+            payload["tool_choice"] = tool_choice.clone();
+        }
+
+        if let Some(response_format) = &request.response_format {
+            payload["response_format"] = response_format.to_openai_json();
+        }
+
         debug!("OpenRouter request payload: {}", serde_json::to_string_pretty(&payload).unwrap_or_default());
 
         let response = self.client
@@ -72,15 +116,11 @@ impl OpenRouterProvider {
 
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = super::http_errors::retry_after_seconds(&response);
             let error_text = response.text().await.unwrap_or_default();
             error!("OpenRouter API error: {} - {}", status, error_text);
             
-            return Err(match status.as_u16() {
-                401 => ProviderError::AuthError(error_text),
-                429 => ProviderError::RateLimitError(error_text),
-                404 => ProviderError::ModelNotFound(error_text),
-                _ => ProviderError::ApiError(format!("HTTP {}: {}", status, error_text)),
-            });
+            return Err(super::http_errors::map_status_error(status, retry_after, error_text));
         }
 
         let response_json: serde_json::Value = response.json().await
@@ -98,7 +138,7 @@ impl OpenRouterProvider {
                 text: choice["message"]["content"].as_str().unwrap_or("").to_string(),
                 finish_reason: choice["finish_reason"].as_str().map(|s| s.to_string()),
                 logprobs: choice.get("logprobs").cloned(),
-                tool_calls: None, // TODO: Implement tool calls parsing
+                tool_calls: Self::parse_tool_calls(&choice["message"]["tool_calls"]),
             }
         }).collect();
 
@@ -107,6 +147,8 @@ impl OpenRouterProvider {
             completion_tokens: u["completion_tokens"].as_u64().unwrap_or(0) as u32,
             total_tokens: u["total_tokens"].as_u64().unwrap_or(0) as u32,
             cost_usd: None, // OpenRouter doesn't provide cost in response
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
         });
 
         Ok(CompletionResponse {
@@ -117,9 +159,38 @@ impl OpenRouterProvider {
             provider: "openrouter".to_string(),
             created_at: chrono::Utc::now(),
             metadata: None,
+            cached: false,
         })
     }
 
+    /// OpenRouter proxies OpenAI-compatible APIs, but some upstream models
+    /// return tool call arguments as a JSON object rather than a
+    /// pre-serialized string, so this is tolerant of both.
+    fn parse_tool_calls(value: &serde_json::Value) -> Option<Vec<super::traits::ToolCall>> {
+        let calls = value.as_array()?;
+
+        let parsed = calls.iter().filter_map(|call| {
+            let id = call["id"].as_str()?.to_string();
+            let name = call["function"]["name"].as_str()?.to_string();
+            let arguments = match &call["function"]["arguments"] {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+
+            Some(super::traits::ToolCall {
+                id,
+                r#type: call["type"].as_str().unwrap_or("function").to_string(),
+                function: super::traits::FunctionCall { name, arguments },
+            })
+        }).collect::<Vec<_>>();
+
+        if parsed.is_empty() {
+            None
+        } else {
+            Some(parsed)
+        }
+    }
+
     fn parse_analysis_findings(&self, content: &str, analysis_type: &super::traits::AnalysisType) -> Vec<String> {
         let mut findings = Vec::new();
         
@@ -272,6 +343,8 @@ impl AIProvider for OpenRouterProvider {
                 super::traits::AnalysisType::Documentation => "documentation completeness and clarity",
                 super::traits::AnalysisType::Testing => "test coverage and testing strategies",
                 super::traits::AnalysisType::Refactoring => "refactoring opportunities and code structure improvements",
+                super::traits::AnalysisType::CodeReview => "overall code review feedback and merge-readiness",
+                super::traits::AnalysisType::CodeSmells => "code smells and refactoring opportunities",
             }
         );
 
@@ -362,9 +435,69 @@ impl AIProvider for OpenRouterProvider {
         &self.config
     }
 
+    fn supports_native_json_mode(&self) -> bool {
+        true
+    }
+
     fn estimate_cost(&self, request: &CompletionRequest) -> Option<f64> {
         // OpenRouter pricing varies by model - this is a rough estimate
         let tokens = request.prompt.len() / 4 + request.max_tokens.unwrap_or(1000) as usize;
         Some(tokens as f64 * 0.00001) // Rough estimate: $0.01 per 1K tokens
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tool_calls_with_string_arguments() {
+        let tool_calls = json!([{
+            "id": "call_1",
+            "type": "function",
+            "function": {"name": "search_docs", "arguments": "{\"query\":\"rust\"}"}
+        }]);
+
+        let parsed = OpenRouterProvider::parse_tool_calls(&tool_calls).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].function.name, "search_docs");
+        assert_eq!(parsed[0].function.arguments, r#"{"query":"rust"}"#);
+    }
+
+    #[test]
+    fn round_trips_a_tool_call_completion_response() {
+        let response_json = json!({
+            "id": "gen-1",
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "content": "",
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {"name": "search_docs", "arguments": {"query": "rust"}}
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }]
+        });
+
+        let choice = &response_json["choices"][0];
+        let parsed_choice = super::super::traits::Choice {
+            index: 0,
+            text: choice["message"]["content"].as_str().unwrap_or("").to_string(),
+            finish_reason: choice["finish_reason"].as_str().map(|s| s.to_string()),
+            logprobs: None,
+            tool_calls: OpenRouterProvider::parse_tool_calls(&choice["message"]["tool_calls"]),
+        };
+
+        let tool_calls = parsed_choice.tool_calls.expect("expected tool calls");
+        assert_eq!(tool_calls[0].function.name, "search_docs");
+        assert_eq!(tool_calls[0].function.arguments, r#"{"query":"rust"}"#);
+    }
+
+    #[test]
+    fn parses_no_tool_calls_as_none() {
+        assert!(OpenRouterProvider::parse_tool_calls(&serde_json::Value::Null).is_none());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,224 @@
+//! Per-`(user_id, provider)` token-bucket rate limiting in front of
+//! `ProviderRouter::complete`.
+//!
+//! This is deliberately separate from the IP-based `tower_governor` layer and
+//! the per-user-only `security::UserRateLimiter`, both of which run at the
+//! edge of the HTTP API and have no notion of which provider is about to be
+//! called or how many tokens the request is likely to cost. Limiting here
+//! means a user hammering one provider doesn't also throttle their requests
+//! to a different one, and a handful of huge completions can exhaust a
+//! token budget even if the request count stays low.
+
+use governor::{
+    clock::{Clock, DefaultClock},
+    middleware::NoOpMiddleware,
+    state::{InMemoryState, NotKeyed},
+    Quota, RateLimiter as GovernorRateLimiter,
+};
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::config::ProviderRateLimitConfig;
+
+type Bucket<C> =
+    GovernorRateLimiter<NotKeyed, InMemoryState, C, NoOpMiddleware<<C as Clock>::Instant>>;
+
+/// Returned when either the request or the token budget for a `(user_id,
+/// provider)` pair is exhausted.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitExceeded {
+    pub retry_after_ms: u64,
+}
+
+/// Token-bucket limiter keyed by `(user_id, provider)`, tracking both a
+/// requests-per-minute and a tokens-per-minute budget per pair. Generic over
+/// `governor`'s `Clock` so tests can drive it with `FakeRelativeClock`
+/// instead of wall-clock time.
+pub struct ProviderRateLimiter<C: Clock = DefaultClock> {
+    clock: C,
+    requests: RwLock<HashMap<(Uuid, String), Arc<Bucket<C>>>>,
+    tokens: RwLock<HashMap<(Uuid, String), Arc<Bucket<C>>>>,
+}
+
+impl ProviderRateLimiter<DefaultClock> {
+    pub fn new() -> Self {
+        Self::with_clock(DefaultClock::default())
+    }
+}
+
+impl Default for ProviderRateLimiter<DefaultClock> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Clock> ProviderRateLimiter<C> {
+    pub fn with_clock(clock: C) -> Self {
+        Self {
+            clock,
+            requests: RwLock::new(HashMap::new()),
+            tokens: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Checks and consumes one request plus `estimated_tokens` from the
+    /// given `(user_id, provider)` pair's budget, creating its buckets on
+    /// first use. `estimated_tokens` is clamped to the configured
+    /// tokens-per-minute quota, since a single request asking for more
+    /// tokens than the whole budget could never be admitted otherwise.
+    pub async fn check(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        estimated_tokens: u32,
+        config: &ProviderRateLimitConfig,
+    ) -> Result<(), RateLimitExceeded> {
+        let key = (user_id, provider.to_string());
+
+        let request_bucket = self
+            .bucket_for(&self.requests, &key, config.requests_per_minute)
+            .await;
+        if let Err(not_until) = request_bucket.check() {
+            return Err(RateLimitExceeded {
+                retry_after_ms: not_until.wait_time_from(self.clock.now()).as_millis() as u64,
+            });
+        }
+
+        let token_cells =
+            NonZeroU32::new(estimated_tokens.min(config.tokens_per_minute).max(1)).unwrap();
+        let token_bucket = self
+            .bucket_for(&self.tokens, &key, config.tokens_per_minute)
+            .await;
+        match token_bucket.check_n(token_cells) {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(not_until)) => Err(RateLimitExceeded {
+                retry_after_ms: not_until.wait_time_from(self.clock.now()).as_millis() as u64,
+            }),
+            // The bucket's burst size is smaller than `token_cells` even
+            // after clamping to `tokens_per_minute`; treat it the same as an
+            // immediate exhaustion rather than panicking the caller.
+            Err(_) => Err(RateLimitExceeded {
+                retry_after_ms: 60_000,
+            }),
+        }
+    }
+
+    async fn bucket_for(
+        &self,
+        buckets: &RwLock<HashMap<(Uuid, String), Arc<Bucket<C>>>>,
+        key: &(Uuid, String),
+        per_minute: u32,
+    ) -> Arc<Bucket<C>> {
+        if let Some(bucket) = buckets.read().await.get(key) {
+            return bucket.clone();
+        }
+
+        let quota = Quota::per_minute(NonZeroU32::new(per_minute.max(1)).unwrap());
+        let bucket = Arc::new(GovernorRateLimiter::direct_with_clock(quota, &self.clock));
+        buckets.write().await.insert(key.clone(), bucket.clone());
+        bucket
+    }
+}
+
+impl RateLimitExceeded {
+    pub fn retry_after(&self) -> Duration {
+        Duration::from_millis(self.retry_after_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use governor::clock::FakeRelativeClock;
+
+    fn config(requests_per_minute: u32, tokens_per_minute: u32) -> ProviderRateLimitConfig {
+        ProviderRateLimitConfig {
+            requests_per_minute,
+            tokens_per_minute,
+        }
+    }
+
+    #[tokio::test]
+    async fn allows_requests_within_budget() {
+        let limiter = ProviderRateLimiter::with_clock(FakeRelativeClock::default());
+        let user_id = Uuid::new_v4();
+        let config = config(5, 10_000);
+
+        for _ in 0..5 {
+            assert!(limiter.check(user_id, "openai", 100, &config).await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_once_request_budget_is_exhausted() {
+        let clock = FakeRelativeClock::default();
+        let limiter = ProviderRateLimiter::with_clock(clock.clone());
+        let user_id = Uuid::new_v4();
+        let config = config(2, 10_000);
+
+        assert!(limiter.check(user_id, "openai", 10, &config).await.is_ok());
+        assert!(limiter.check(user_id, "openai", 10, &config).await.is_ok());
+
+        let result = limiter.check(user_id, "openai", 10, &config).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_once_token_budget_is_exhausted() {
+        let limiter = ProviderRateLimiter::with_clock(FakeRelativeClock::default());
+        let user_id = Uuid::new_v4();
+        let config = config(100, 1_000);
+
+        assert!(limiter.check(user_id, "openai", 900, &config).await.is_ok());
+        let result = limiter.check(user_id, "openai", 200, &config).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn recovers_after_the_clock_advances() {
+        let clock = FakeRelativeClock::default();
+        let limiter = ProviderRateLimiter::with_clock(clock.clone());
+        let user_id = Uuid::new_v4();
+        let config = config(1, 10_000);
+
+        assert!(limiter.check(user_id, "openai", 10, &config).await.is_ok());
+        assert!(limiter.check(user_id, "openai", 10, &config).await.is_err());
+
+        clock.advance(Duration::from_secs(61));
+
+        assert!(limiter.check(user_id, "openai", 10, &config).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn different_providers_have_independent_budgets() {
+        let limiter = ProviderRateLimiter::with_clock(FakeRelativeClock::default());
+        let user_id = Uuid::new_v4();
+        let config = config(1, 10_000);
+
+        assert!(limiter.check(user_id, "openai", 10, &config).await.is_ok());
+        assert!(limiter
+            .check(user_id, "anthropic", 10, &config)
+            .await
+            .is_ok());
+        assert!(limiter.check(user_id, "openai", 10, &config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn different_users_have_independent_budgets() {
+        let limiter = ProviderRateLimiter::with_clock(FakeRelativeClock::default());
+        let config = config(1, 10_000);
+
+        assert!(limiter
+            .check(Uuid::new_v4(), "openai", 10, &config)
+            .await
+            .is_ok());
+        assert!(limiter
+            .check(Uuid::new_v4(), "openai", 10, &config)
+            .await
+            .is_ok());
+    }
+}
@@ -1,17 +1,206 @@
-use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, CompletionRequest, CompletionResponse, HealthCheck, ProviderError};
+use super::traits::{AIProvider, AnalysisRequest, AnalysisResponse, AnalysisType, CompletionRequest, CompletionResponse, Finding, HealthCheck, ModelCapability, ModelInfo, ProviderError, RerankProvider, ResponseFormat, Suggestion, model_capability, model_info};
 use super::{openrouter::OpenRouterProvider, openai::OpenAIProvider, ollama::OllamaProvider};
+use super::{CircuitState, ProviderHealth, ProviderType};
+use crate::cache::{Cache, CacheConfig, MemoryCache};
 use crate::config::Config;
 use async_trait::async_trait;
-use std::collections::HashMap;
-use std::sync::Arc;
+use prometheus::{
+    register_int_gauge_vec, IntGaugeVec,
+    register_int_counter_vec, IntCounterVec,
+    register_histogram_vec, HistogramOpts, HistogramVec,
+    register_counter_vec, CounterVec,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+/// A provider's model listing, as returned by `ProviderRouter::get_models`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelListing {
+    pub models: Vec<ModelInfo>,
+    pub cached: bool,
+    pub fetched_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// One entry of `ProviderRouter::get_all_models`'s merged listing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaggedModel {
+    pub provider: String,
+    pub model: ModelInfo,
+}
+
+/// Mirrors each provider's current circuit breaker state so it shows up
+/// alongside the rest of the crate's Prometheus metrics, without `providers`
+/// (shared by the lib and bin targets) depending on the bin-only
+/// `observability` module.
+static CIRCUIT_STATE_GAUGE: OnceLock<IntGaugeVec> = OnceLock::new();
+
+fn circuit_state_gauge() -> &'static IntGaugeVec {
+    CIRCUIT_STATE_GAUGE.get_or_init(|| {
+        register_int_gauge_vec!(
+            "uaida_provider_circuit_state",
+            "Circuit breaker state per provider (0=closed, 1=half_open, 2=open)",
+            &["provider"]
+        )
+        .expect("Failed to register uaida_provider_circuit_state metric")
+    })
+}
+
+fn circuit_state_value(state: CircuitState) -> i64 {
+    match state {
+        CircuitState::Closed => 0,
+        CircuitState::HalfOpen => 1,
+        CircuitState::Open => 2,
+    }
+}
+
+static PROVIDER_REQUESTS_TOTAL: OnceLock<IntCounterVec> = OnceLock::new();
+static PROVIDER_LATENCY_MS: OnceLock<HistogramVec> = OnceLock::new();
+static PROVIDER_TOKENS_TOTAL: OnceLock<IntCounterVec> = OnceLock::new();
+static PROVIDER_COST_USD_TOTAL: OnceLock<CounterVec> = OnceLock::new();
+
+fn provider_requests_total() -> &'static IntCounterVec {
+    PROVIDER_REQUESTS_TOTAL.get_or_init(|| {
+        register_int_counter_vec!(
+            "provider_requests_total",
+            "Total number of AI provider requests",
+            &["provider", "model", "status"]
+        )
+        .expect("Failed to register provider_requests_total metric")
+    })
+}
+
+fn provider_latency_ms() -> &'static HistogramVec {
+    PROVIDER_LATENCY_MS.get_or_init(|| {
+        register_histogram_vec!(
+            HistogramOpts::new("provider_latency_ms_bucket", "AI provider request latency in milliseconds")
+                .buckets(vec![10.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0, 30000.0]),
+            &["provider"]
+        )
+        .expect("Failed to register provider_latency_ms metric")
+    })
+}
+
+fn provider_tokens_total() -> &'static IntCounterVec {
+    PROVIDER_TOKENS_TOTAL.get_or_init(|| {
+        register_int_counter_vec!(
+            "provider_tokens_total",
+            "Total number of tokens exchanged with AI providers",
+            &["provider", "direction"]
+        )
+        .expect("Failed to register provider_tokens_total metric")
+    })
+}
+
+fn provider_cost_usd_total() -> &'static CounterVec {
+    PROVIDER_COST_USD_TOTAL.get_or_init(|| {
+        register_counter_vec!(
+            "provider_cost_usd_total",
+            "Total estimated cost in USD of AI provider requests",
+            &["provider"]
+        )
+        .expect("Failed to register provider_cost_usd_total metric")
+    })
+}
+
+/// Approximate per-1k-token cost used to rank providers by price, shared by
+/// [`ProviderRouter::select_by_cost`] and [`ProviderRouter::provider_cost_breakdown`].
+fn provider_cost_per_1k_tokens(provider_name: &str) -> f64 {
+    match provider_name {
+        "openai" => 0.002,      // GPT-3.5-turbo approximate cost
+        "azure_openai" => 0.01, // Azure-hosted GPT-4o approximate cost
+        "bedrock" => 0.008,     // Bedrock-hosted Claude approximate cost
+        "openrouter" => 0.0015, // Average OpenRouter cost
+        "anthropic" => 0.008,   // Claude approximate cost
+        "cohere" => 0.001,      // Cohere approximate cost
+        "together" => 0.0008,   // Together AI approximate cost
+        "mistral" => 0.002,     // Mistral approximate cost
+        "ollama" => 0.0,        // Self-hosted, no per-token cost
+        _ => 0.002,             // Default cost
+    }
+}
+
+/// `CompletionRequest::temperature` at or below this is treated as
+/// deterministic enough to serve from `ProviderRouter`'s response cache.
+const CACHEABLE_TEMPERATURE_THRESHOLD: f32 = 0.01;
+
+/// Hash of the fields that fully determine a deterministic completion's
+/// output, used as the key into `ProviderRouter::response_cache`. Two
+/// requests that would produce the same answer from `provider_name` hash
+/// identically regardless of field order.
+fn completion_cache_key(provider_name: &str, model: &str, request: &CompletionRequest) -> String {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    provider_name.hash(&mut hasher);
+    model.hash(&mut hasher);
+    request.prompt.hash(&mut hasher);
+    request.system_prompt.hash(&mut hasher);
+    request.max_tokens.hash(&mut hasher);
+    request.temperature.map(f32::to_bits).hash(&mut hasher);
+    format!("completion:{:x}", hasher.finish())
+}
+
+/// Exponential backoff with jitter for `ProviderRouter::complete_with_retry`.
+/// When the failure carried a `Retry-After` hint, that's used as the base
+/// delay instead of the computed backoff (still capped and jittered the
+/// same way), since the provider knows its own recovery time better than we
+/// do.
+fn retry_backoff_delay(config: &crate::config::RetryConfig, attempt: u32, retry_after_seconds: Option<u64>) -> Duration {
+    let base_delay_ms = retry_after_seconds
+        .map(|secs| secs.saturating_mul(1000))
+        .unwrap_or_else(|| config.base_delay_ms.saturating_mul(1u64 << attempt.min(16)));
+    let capped_ms = base_delay_ms.min(config.max_delay_ms);
+    let jitter_ms = (capped_ms as f64 * config.jitter_factor * rand::random::<f64>()) as u64;
+    Duration::from_millis(capped_ms + jitter_ms)
+}
 
 pub struct ProviderRouter {
     providers: HashMap<String, Box<dyn AIProvider>>,
+    /// Providers with a native rerank endpoint, keyed the same as `providers`.
+    /// Kept separate rather than testing each `AIProvider` for rerank support,
+    /// since Rust trait objects can't be downcast to a second trait without
+    /// an explicit registry like this one.
+    rerank_providers: HashMap<String, Box<dyn RerankProvider>>,
     config: Arc<Config>,
     metrics: Arc<RwLock<HashMap<String, ProviderMetrics>>>,
-    health_cache: Arc<RwLock<HashMap<String, (HealthCheck, std::time::Instant)>>>,
+    health_cache: Arc<RwLock<HashMap<String, (HealthCheck, chrono::DateTime<chrono::Utc>)>>>,
+    circuit_breakers: Arc<RwLock<HashMap<String, CircuitBreakerState>>>,
+    rate_limiter: Arc<super::rate_limiter::ProviderRateLimiter>,
+    model_cache: Arc<MemoryCache>,
+    /// Caches completion responses for deterministic requests (see
+    /// `Self::completion_cache_key`), so an identical repeated request
+    /// doesn't re-hit a paid provider.
+    response_cache: Arc<MemoryCache>,
+}
+
+/// Per-provider circuit breaker bookkeeping. Failures within a trailing
+/// `window_seconds` window past the configured threshold open the circuit
+/// for `cooldown`; once it elapses the breaker moves to half-open and lets a
+/// single request through to decide whether to close it again. A failed
+/// probe re-opens the circuit with `cooldown` doubled (capped at
+/// `max_cooldown_seconds`).
+#[derive(Debug, Clone)]
+struct CircuitBreakerState {
+    state: CircuitState,
+    failure_timestamps: VecDeque<Instant>,
+    opened_at: Option<Instant>,
+    cooldown: Duration,
+}
+
+impl CircuitBreakerState {
+    fn new(base_cooldown: Duration) -> Self {
+        Self {
+            state: CircuitState::Closed,
+            failure_timestamps: VecDeque::new(),
+            opened_at: None,
+            cooldown: base_cooldown,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -22,6 +211,17 @@ pub struct ProviderMetrics {
     pub total_response_time_ms: u64,
     pub total_tokens: u64,
     pub total_cost_usd: f64,
+    /// Retry attempts issued by `complete_with_retry`, counted separately
+    /// from `total_requests` so a flaky-but-eventually-successful provider
+    /// is still visible in `/metrics` instead of looking identical to one
+    /// that succeeded first try.
+    pub retries: u64,
+    /// Tokens Anthropic wrote to its prompt cache (`cache_creation_input_tokens`).
+    /// Zero for providers without prompt caching.
+    pub prompt_cache_creation_tokens: u64,
+    /// Tokens Anthropic served from its prompt cache instead of reprocessing
+    /// (`cache_read_input_tokens`). Zero for providers without prompt caching.
+    pub prompt_cache_read_tokens: u64,
 }
 
 impl ProviderMetrics {
@@ -33,6 +233,9 @@ impl ProviderMetrics {
             total_response_time_ms: 0,
             total_tokens: 0,
             total_cost_usd: 0.0,
+            retries: 0,
+            prompt_cache_creation_tokens: 0,
+            prompt_cache_read_tokens: 0,
         }
     }
 
@@ -65,7 +268,8 @@ pub enum RoutingStrategy {
 impl ProviderRouter {
     pub async fn new(config: Arc<Config>) -> Result<Self, ProviderError> {
         let mut providers: HashMap<String, Box<dyn AIProvider>> = HashMap::new();
-        
+        let mut rerank_providers: HashMap<String, Box<dyn RerankProvider>> = HashMap::new();
+
         // Initialize OpenRouter if configured
         if config.providers.openrouter.enabled {
             match OpenRouterProvider::new(config.providers.openrouter.clone()) {
@@ -117,6 +321,17 @@ impl ProviderRouter {
                 }
                 Err(e) => warn!("Failed to initialize Cohere provider: {}", e),
             }
+
+            // A second instance dedicated to `RerankProvider`, since
+            // `rerank_providers` is keyed by a different trait object than
+            // `providers` and can't share the one above.
+            match super::cohere::CohereProvider::new(config.providers.cohere.clone()) {
+                Ok(provider) => {
+                    rerank_providers.insert("cohere".to_string(), Box::new(provider));
+                    info!("Cohere rerank provider initialized");
+                }
+                Err(e) => warn!("Failed to initialize Cohere rerank provider: {}", e),
+            }
         }
 
         // Initialize Anthropic if configured
@@ -141,6 +356,39 @@ impl ProviderRouter {
             }
         }
 
+        // Initialize Azure OpenAI if configured
+        if config.providers.azure_openai.enabled {
+            match super::azure_openai::AzureOpenAIProvider::new(config.providers.azure_openai.clone()) {
+                Ok(provider) => {
+                    providers.insert("azure_openai".to_string(), Box::new(provider));
+                    info!("Azure OpenAI provider initialized");
+                }
+                Err(e) => warn!("Failed to initialize Azure OpenAI provider: {}", e),
+            }
+        }
+
+        // Initialize AWS Bedrock if configured
+        if config.providers.bedrock.enabled {
+            match super::bedrock::BedrockProvider::new(config.providers.bedrock.clone()) {
+                Ok(provider) => {
+                    providers.insert("bedrock".to_string(), Box::new(provider));
+                    info!("Bedrock provider initialized");
+                }
+                Err(e) => warn!("Failed to initialize Bedrock provider: {}", e),
+            }
+        }
+
+        // Initialize Mistral if configured
+        if config.providers.mistral.enabled {
+            match super::mistral::MistralProvider::new(config.providers.mistral.clone()) {
+                Ok(provider) => {
+                    providers.insert("mistral".to_string(), Box::new(provider));
+                    info!("Mistral provider initialized");
+                }
+                Err(e) => warn!("Failed to initialize Mistral provider: {}", e),
+            }
+        }
+
         // Initialize Groq if configured
         if config.providers.groq.enabled {
             match super::groq::GroqProvider::new(config.providers.groq.clone()) {
@@ -154,36 +402,272 @@ impl ProviderRouter {
 
         let metrics = Arc::new(RwLock::new(HashMap::new()));
         let health_cache = Arc::new(RwLock::new(HashMap::new()));
+        let circuit_breakers = Arc::new(RwLock::new(HashMap::new()));
+        let rate_limiter = Arc::new(super::rate_limiter::ProviderRateLimiter::new());
+        let model_cache = Arc::new(MemoryCache::new(CacheConfig {
+            ttl: Duration::from_secs(config.providers.model_cache_ttl_seconds),
+            ..Default::default()
+        }));
+        let response_cache = Arc::new(MemoryCache::new(CacheConfig {
+            ttl: Duration::from_secs(config.providers.response_cache_ttl_seconds),
+            ..Default::default()
+        }));
 
         Ok(Self {
             providers,
+            rerank_providers,
             config,
             metrics,
             health_cache,
+            circuit_breakers,
+            rate_limiter,
+            model_cache,
+            response_cache,
         })
     }
 
+    /// Returns `provider_name`'s available models, serving from the
+    /// `model_cache_ttl_seconds`-TTL cache unless `refresh` is set. The
+    /// returned `cached` flag and `fetched_at` reflect whether this call hit
+    /// the cache or went to the provider.
+    pub async fn get_models(&self, provider_name: &str, refresh: bool) -> Result<ModelListing, ProviderError> {
+        let cache_key = format!("models:{}", provider_name);
+
+        if !refresh {
+            if let Ok(Some(listing)) = self.model_cache.get::<ModelListing>(&cache_key).await {
+                return Ok(ModelListing { cached: true, ..listing });
+            }
+        }
+
+        let provider = self
+            .providers
+            .get(provider_name)
+            .ok_or_else(|| ProviderError::InvalidRequest(format!("Unknown provider: {}", provider_name)))?;
+
+        let models = provider.list_models().await?.into_iter().map(|id| model_info(&id)).collect();
+        let listing = ModelListing {
+            models,
+            cached: false,
+            fetched_at: chrono::Utc::now(),
+        };
+
+        if let Err(e) = self.model_cache.set(&cache_key, listing.clone(), None).await {
+            warn!("Failed to cache model listing for {}: {}", provider_name, e);
+        }
+
+        Ok(listing)
+    }
+
+    /// Merged model listing across every configured provider, each entry
+    /// tagged with its provider so callers (e.g. the CLI's `--model`
+    /// validation) don't need to query providers individually. Providers
+    /// whose listing can't currently be fetched are skipped rather than
+    /// failing the whole request.
+    pub async fn get_all_models(&self) -> Vec<TaggedModel> {
+        let mut tagged = Vec::new();
+
+        for provider_name in self.providers.keys() {
+            match self.get_models(provider_name, false).await {
+                Ok(listing) => {
+                    tagged.extend(listing.models.into_iter().map(|model| TaggedModel {
+                        provider: provider_name.clone(),
+                        model,
+                    }));
+                }
+                Err(e) => warn!("Skipping {} in merged model listing: {}", provider_name, e),
+            }
+        }
+
+        tagged
+    }
+
     pub async fn get_available_providers(&self) -> Vec<String> {
         let mut available = Vec::new();
-        
+
         for (name, provider) in &self.providers {
+            if !self.circuit_allows_routing(name).await {
+                continue;
+            }
+
             match self.get_cached_health(name).await {
                 Some(health) if health.is_available => available.push(name.clone()),
                 _ => {
                     // Check health if not cached or cached result shows unavailable
-                    if let Ok(health) = provider.health_check().await {
-                        self.cache_health(name.clone(), health.clone()).await;
-                        if health.is_available {
-                            available.push(name.clone());
+                    match provider.health_check().await {
+                        Ok(health) => {
+                            self.cache_health(name.clone(), health.clone()).await;
+                            if health.is_available {
+                                self.record_success(name).await;
+                                available.push(name.clone());
+                            } else {
+                                self.record_failure(name).await;
+                            }
                         }
+                        Err(_) => self.record_failure(name).await,
                     }
                 }
             }
         }
-        
+
         available
     }
 
+    /// Returns `true` if the provider's circuit is closed, or half-open and
+    /// therefore due for a recovery probe. Transitions an expired open
+    /// circuit to half-open as a side effect.
+    async fn circuit_allows_routing(&self, provider_name: &str) -> bool {
+        let base_cooldown = Duration::from_secs(self.config.providers.circuit_breaker.cooldown_seconds);
+        let mut breakers = self.circuit_breakers.write().await;
+        let breaker = breakers
+            .entry(provider_name.to_string())
+            .or_insert_with(|| CircuitBreakerState::new(base_cooldown));
+
+        match breaker.state {
+            CircuitState::Closed => true,
+            CircuitState::HalfOpen => true,
+            CircuitState::Open => {
+                if breaker.opened_at.map(|t| t.elapsed() >= breaker.cooldown).unwrap_or(true) {
+                    debug!("Circuit breaker for {} entering half-open state", provider_name);
+                    breaker.state = CircuitState::HalfOpen;
+                    circuit_state_gauge().with_label_values(&[provider_name]).set(circuit_state_value(breaker.state));
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    async fn record_success(&self, provider_name: &str) {
+        let base_cooldown = Duration::from_secs(self.config.providers.circuit_breaker.cooldown_seconds);
+        let mut breakers = self.circuit_breakers.write().await;
+        let breaker = breakers
+            .entry(provider_name.to_string())
+            .or_insert_with(|| CircuitBreakerState::new(base_cooldown));
+        breaker.failure_timestamps.clear();
+        breaker.opened_at = None;
+        breaker.cooldown = base_cooldown;
+        if breaker.state != CircuitState::Closed {
+            info!("Circuit breaker for {} closed after successful probe", provider_name);
+        }
+        breaker.state = CircuitState::Closed;
+        circuit_state_gauge().with_label_values(&[provider_name]).set(circuit_state_value(breaker.state));
+    }
+
+    async fn record_failure(&self, provider_name: &str) {
+        let threshold = self.config.providers.circuit_breaker.failure_threshold;
+        let window = Duration::from_secs(self.config.providers.circuit_breaker.window_seconds);
+        let max_cooldown = Duration::from_secs(self.config.providers.circuit_breaker.max_cooldown_seconds);
+        let base_cooldown = Duration::from_secs(self.config.providers.circuit_breaker.cooldown_seconds);
+
+        let mut breakers = self.circuit_breakers.write().await;
+        let breaker = breakers
+            .entry(provider_name.to_string())
+            .or_insert_with(|| CircuitBreakerState::new(base_cooldown));
+
+        if breaker.state == CircuitState::HalfOpen {
+            // The recovery probe failed; re-open with the cooldown doubled,
+            // up to max_cooldown_seconds.
+            breaker.cooldown = (breaker.cooldown * 2).min(max_cooldown);
+            breaker.state = CircuitState::Open;
+            breaker.opened_at = Some(Instant::now());
+            circuit_state_gauge().with_label_values(&[provider_name]).set(circuit_state_value(breaker.state));
+            warn!(
+                "Circuit breaker for {} re-opened after failed probe, cooldown now {:?}",
+                provider_name, breaker.cooldown
+            );
+            return;
+        }
+
+        let now = Instant::now();
+        breaker.failure_timestamps.push_back(now);
+        while breaker.failure_timestamps.front().map(|t| now.duration_since(*t) > window).unwrap_or(false) {
+            breaker.failure_timestamps.pop_front();
+        }
+
+        if breaker.failure_timestamps.len() as u32 >= threshold && breaker.state == CircuitState::Closed {
+            breaker.state = CircuitState::Open;
+            breaker.opened_at = Some(now);
+            circuit_state_gauge().with_label_values(&[provider_name]).set(circuit_state_value(breaker.state));
+            warn!(
+                "Circuit breaker for {} opened after {} failures within {:?}",
+                provider_name, breaker.failure_timestamps.len(), window
+            );
+        }
+    }
+
+    async fn circuit_state_and_next_probe(
+        &self,
+        provider_name: &str,
+    ) -> (CircuitState, Option<chrono::DateTime<chrono::Utc>>) {
+        let breakers = self.circuit_breakers.read().await;
+        match breakers.get(provider_name) {
+            Some(breaker) => {
+                let next_probe = match (breaker.state, breaker.opened_at) {
+                    (CircuitState::Open, Some(opened_at)) => {
+                        let remaining = breaker.cooldown.saturating_sub(opened_at.elapsed());
+                        Some(chrono::Utc::now() + chrono::Duration::from_std(remaining).unwrap_or_default())
+                    }
+                    _ => None,
+                };
+                (breaker.state, next_probe)
+            }
+            None => (CircuitState::Closed, None),
+        }
+    }
+
+    /// Builds a point-in-time health snapshot for a single provider,
+    /// including its current circuit breaker state, for the
+    /// `/providers/:provider/health` endpoint.
+    pub async fn get_provider_health_by_name(&self, provider_name: &str, force_refresh: bool) -> Option<ProviderHealth> {
+        if !self.providers.contains_key(provider_name) {
+            return None;
+        }
+
+        let health_check = if force_refresh {
+            self.refresh_provider_health(provider_name).await?
+        } else {
+            match self.get_cached_health(provider_name).await {
+                Some(health) => health,
+                // No background-refreshed entry yet (e.g. monitor hasn't
+                // ticked since startup) — probe once so the first caller
+                // still gets a real answer instead of a cache miss.
+                None => self.refresh_provider_health(provider_name).await?,
+            }
+        };
+
+        let (circuit_state, next_probe_at) = self.circuit_state_and_next_probe(provider_name).await;
+        let last_checked = {
+            let cache = self.health_cache.read().await;
+            self.cached_health_checked_at(&cache, provider_name)
+        };
+
+        Some(ProviderHealth {
+            provider_type: provider_type_for_name(provider_name),
+            is_available: health_check.is_available && circuit_state != CircuitState::Open,
+            response_time_ms: Some(health_check.response_time_ms),
+            error_message: health_check.error_message,
+            models_available: health_check.supported_models,
+            circuit_state,
+            next_probe_at,
+            last_checked,
+        })
+    }
+
+    /// Builds a health snapshot for every configured provider from the
+    /// background-refreshed cache. Pass `force_refresh` to probe all
+    /// providers immediately instead (used by `?refresh=true`).
+    pub async fn get_provider_health(&self, force_refresh: bool) -> HashMap<String, ProviderHealth> {
+        let mut result = HashMap::new();
+        let names: Vec<String> = self.providers.keys().cloned().collect();
+        for name in names {
+            if let Some(health) = self.get_provider_health_by_name(&name, force_refresh).await {
+                result.insert(name, health);
+            }
+        }
+        result
+    }
+
     pub async fn select_provider(&self, strategy: RoutingStrategy, model_preference: Option<String>) -> Option<String> {
         let available_providers = self.get_available_providers().await;
         
@@ -218,11 +702,14 @@ impl ProviderRouter {
             let priority = match provider_name.as_str() {
                 "openrouter" => self.config.providers.openrouter.priority,
                 "openai" => self.config.providers.openai.priority,
+                "azure_openai" => self.config.providers.azure_openai.priority,
+                "bedrock" => self.config.providers.bedrock.priority,
                 "anthropic" => self.config.providers.anthropic.priority,
                 "google" => self.config.providers.google.priority,
                 "groq" => self.config.providers.groq.priority,
                 "together" => self.config.providers.together.priority,
                 "cohere" => self.config.providers.cohere.priority,
+                "mistral" => self.config.providers.mistral.priority,
                 "ollama" => self.config.providers.ollama.priority,
                 _ => 1,
             };
@@ -265,26 +752,35 @@ impl ProviderRouter {
         }
 
         // Calculate cost for each provider and select cheapest
-        let mut provider_costs = Vec::new();
-        
-        for provider_name in available_providers {
-            let cost_per_1k_tokens = match provider_name.as_str() {
-                "openai" => 0.002,      // GPT-3.5-turbo approximate cost
-                "openrouter" => 0.0015, // Average OpenRouter cost
-                "anthropic" => 0.008,   // Claude approximate cost
-                "cohere" => 0.001,      // Cohere approximate cost
-                "together" => 0.0008,   // Together AI approximate cost
-                _ => 0.002,             // Default cost
-            };
-            
-            provider_costs.push((provider_name.clone(), cost_per_1k_tokens));
-        }
-        
+        let mut provider_costs: Vec<(String, f64)> = available_providers
+            .iter()
+            .map(|name| (name.clone(), provider_cost_per_1k_tokens(name)))
+            .collect();
+
         // Sort by cost and return cheapest
         provider_costs.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
         provider_costs.first().map(|(name, _)| name.clone())
     }
 
+    /// Cost of `estimated_tokens` on every currently available provider, using
+    /// the same approximate per-1k-token pricing [`select_by_cost`] routes
+    /// with, cheapest first. Used by the plan cost estimator to show callers
+    /// the full spread before they commit to a provider.
+    pub async fn provider_cost_breakdown(&self, estimated_tokens: u32) -> Vec<(String, u32, f64)> {
+        let available_providers = self.get_available_providers().await;
+
+        let mut breakdown: Vec<(String, u32, f64)> = available_providers
+            .iter()
+            .map(|name| {
+                let cost = (estimated_tokens as f64 / 1000.0) * provider_cost_per_1k_tokens(name);
+                (name.clone(), estimated_tokens, cost)
+            })
+            .collect();
+
+        breakdown.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
+        breakdown
+    }
+
     async fn select_by_performance(&self, available_providers: &[String]) -> Option<String> {
         let metrics = self.metrics.read().await;
         
@@ -308,9 +804,12 @@ impl ProviderRouter {
 
     async fn get_cached_health(&self, provider_name: &str) -> Option<HealthCheck> {
         let cache = self.health_cache.read().await;
-        if let Some((health, timestamp)) = cache.get(provider_name) {
-            // Cache for 5 minutes
-            if timestamp.elapsed().as_secs() < 300 {
+        if let Some((health, checked_at)) = cache.get(provider_name) {
+            // Stale if older than twice the background monitor's refresh
+            // interval, so a dead monitor falls back to on-demand probing
+            // instead of serving indefinitely-old results.
+            let max_age = self.config.providers.health_check_interval_seconds.saturating_mul(2).max(1);
+            if (chrono::Utc::now() - *checked_at).num_seconds() < max_age as i64 {
                 return Some(health.clone());
             }
         }
@@ -319,59 +818,299 @@ impl ProviderRouter {
 
     async fn cache_health(&self, provider_name: String, health: HealthCheck) {
         let mut cache = self.health_cache.write().await;
-        cache.insert(provider_name, (health, std::time::Instant::now()));
+        cache.insert(provider_name, (health, chrono::Utc::now()));
+    }
+
+    fn cached_health_checked_at(&self, cache: &HashMap<String, (HealthCheck, chrono::DateTime<chrono::Utc>)>, provider_name: &str) -> chrono::DateTime<chrono::Utc> {
+        cache
+            .get(provider_name)
+            .map(|(_, checked_at)| *checked_at)
+            .unwrap_or_else(chrono::Utc::now)
+    }
+
+    /// Spawns a background task that refreshes every provider's cached
+    /// health on a fixed interval (`providers.health_check_interval_seconds`
+    /// in config), so `/health` and `/providers/:provider/health` can read
+    /// the cache instead of blocking on a live probe per request.
+    pub fn start_health_monitor(self: Arc<Self>) {
+        let interval_secs = self.config.providers.health_check_interval_seconds.max(1);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                self.refresh_all_health().await;
+            }
+        });
+        info!("Provider health monitor started (every {}s)", interval_secs);
+    }
+
+    /// Probes every configured provider and refreshes its cache entry.
+    async fn refresh_all_health(&self) {
+        let names: Vec<String> = self.providers.keys().cloned().collect();
+        for name in names {
+            self.refresh_provider_health(&name).await;
+        }
     }
 
-    async fn record_metrics(&self, provider_name: &str, success: bool, response_time_ms: u64, tokens: u32, cost: f64) {
+    async fn refresh_provider_health(&self, provider_name: &str) -> Option<HealthCheck> {
+        let provider = self.providers.get(provider_name)?;
+        match provider.health_check().await {
+            Ok(health) => {
+                self.cache_health(provider_name.to_string(), health.clone()).await;
+                self.record_success(provider_name).await;
+                Some(health)
+            }
+            Err(e) => {
+                self.record_failure(provider_name).await;
+                let health = HealthCheck {
+                    is_available: false,
+                    response_time_ms: 0,
+                    supported_models: vec![],
+                    rate_limit_remaining: None,
+                    error_message: Some(e.to_string()),
+                };
+                self.cache_health(provider_name.to_string(), health.clone()).await;
+                Some(health)
+            }
+        }
+    }
+
+    async fn record_retry(&self, provider_name: &str) {
         let mut metrics = self.metrics.write().await;
         let provider_metrics = metrics.entry(provider_name.to_string()).or_insert_with(ProviderMetrics::new);
-        
+        provider_metrics.retries += 1;
+    }
+
+    async fn record_metrics(&self, provider_name: &str, model: &str, success: bool, response_time_ms: u64, usage: Option<&super::traits::Usage>) {
+        let mut metrics = self.metrics.write().await;
+        let provider_metrics = metrics.entry(provider_name.to_string()).or_insert_with(ProviderMetrics::new);
+
         provider_metrics.total_requests += 1;
         provider_metrics.total_response_time_ms += response_time_ms;
-        provider_metrics.total_tokens += tokens as u64;
-        provider_metrics.total_cost_usd += cost;
+        provider_metrics.total_tokens += usage.map(|u| u.total_tokens).unwrap_or(0) as u64;
+        provider_metrics.total_cost_usd += usage.and_then(|u| u.cost_usd).unwrap_or(0.0);
+        provider_metrics.prompt_cache_creation_tokens += usage.and_then(|u| u.cache_creation_tokens).unwrap_or(0) as u64;
+        provider_metrics.prompt_cache_read_tokens += usage.and_then(|u| u.cache_read_tokens).unwrap_or(0) as u64;
 
         if success {
             provider_metrics.successful_requests += 1;
         } else {
             provider_metrics.failed_requests += 1;
         }
+        drop(metrics);
+
+        self.record_prometheus_metrics(provider_name, model, success, response_time_ms, usage);
+    }
+
+    /// Mirrors `record_metrics`'s bookkeeping into the process-wide
+    /// Prometheus registry so provider latency and token usage show up on
+    /// `/metrics` alongside the HTTP and agent metrics.
+    fn record_prometheus_metrics(&self, provider_name: &str, model: &str, success: bool, response_time_ms: u64, usage: Option<&super::traits::Usage>) {
+        let status = if success { "success" } else { "error" };
+
+        provider_requests_total()
+            .with_label_values(&[provider_name, model, status])
+            .inc();
+        provider_latency_ms()
+            .with_label_values(&[provider_name])
+            .observe(response_time_ms as f64);
+
+        if let Some(usage) = usage {
+            provider_tokens_total()
+                .with_label_values(&[provider_name, "input"])
+                .inc_by(usage.prompt_tokens as u64);
+            provider_tokens_total()
+                .with_label_values(&[provider_name, "output"])
+                .inc_by(usage.completion_tokens as u64);
+
+            if let Some(cost_usd) = usage.cost_usd {
+                provider_cost_usd_total()
+                    .with_label_values(&[provider_name])
+                    .inc_by(cost_usd);
+            }
+        }
     }
 
     pub async fn get_metrics(&self) -> HashMap<String, ProviderMetrics> {
         self.metrics.read().await.clone()
     }
 
+    /// Checks `request.prompt` + `request.max_tokens` against the target
+    /// model's context window before dispatching anywhere, so an oversized
+    /// request fails fast with a structured error instead of a provider's
+    /// opaque 400.
+    fn validate_context_length(&self, request: &CompletionRequest) -> Result<(), ProviderError> {
+        let model = request.model.as_deref().unwrap_or("gpt-4o-mini");
+        let prompt_tokens = super::tokenizer::count_tokens(&request.prompt, model);
+        let max_tokens = request.max_tokens.unwrap_or(0);
+        let context_window = super::tokenizer::context_window_for_model(model);
+
+        if prompt_tokens as u64 + max_tokens as u64 > context_window as u64 {
+            return Err(ProviderError::ContextLengthExceeded {
+                model: model.to_string(),
+                prompt_tokens,
+                max_tokens,
+                context_window,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Calls `provider.complete`, retrying transient failures (rate limits,
+    /// timeouts, network errors, `Unavailable`) with exponential backoff and
+    /// jitter, per `config.providers.retry`. Honors a `Retry-After` the
+    /// provider reports in place of the computed backoff. Non-retryable
+    /// errors (auth, invalid request, model not found, context length) are
+    /// returned immediately.
+    ///
+    /// This only covers `complete`, not `complete_stream`: once a stream has
+    /// started emitting chunks to the caller, replaying the request would
+    /// duplicate or corrupt output the caller already consumed, so a
+    /// streaming failure is never retried here.
+    async fn complete_with_retry(
+        &self,
+        provider_name: &str,
+        provider: &dyn AIProvider,
+        request: &CompletionRequest,
+    ) -> Result<CompletionResponse, ProviderError> {
+        let retry_config = &self.config.providers.retry;
+        let mut attempt = 0;
+
+        loop {
+            match provider.complete(request.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(e) if attempt + 1 < retry_config.max_attempts && e.is_retryable() => {
+                    let delay = retry_backoff_delay(retry_config, attempt, e.retry_after_seconds());
+                    warn!(
+                        "Provider {} attempt {} failed with a retryable error ({}), retrying in {:?}",
+                        provider_name, attempt + 1, e, delay
+                    );
+                    self.record_retry(provider_name).await;
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Rate-limited, user-aware completion against a single named provider.
+    /// Checks the caller's `(user_id, provider_name)` token-bucket budget
+    /// (`config.providers.rate_limit`) before dispatching through
+    /// `complete_with_retry`.
+    ///
+    /// Deliberately targets one provider rather than failing over like
+    /// `complete_with_fallback`: routing a rate-limited user to a different
+    /// provider would just move the cost there instead of actually
+    /// enforcing a budget, so a caller over their limit gets a
+    /// `RateLimitError` back instead of being silently redirected.
+    pub async fn complete_for_user(
+        &self,
+        user_id: Uuid,
+        provider_name: &str,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse, ProviderError> {
+        self.validate_context_length(&request)?;
+
+        let provider = self
+            .providers
+            .get(provider_name)
+            .ok_or_else(|| ProviderError::InvalidRequest(format!("Unknown provider: {}", provider_name)))?;
+
+        let estimated_tokens = request.max_tokens.unwrap_or(1000);
+        if let Err(exceeded) = self
+            .rate_limiter
+            .check(user_id, provider_name, estimated_tokens, &self.config.providers.rate_limit)
+            .await
+        {
+            warn!(
+                "Rate limit exceeded for user {} on provider {}, retry after {}ms",
+                user_id, provider_name, exceeded.retry_after_ms
+            );
+            return Err(ProviderError::RateLimitError {
+                message: format!("Rate limit exceeded for provider {}", provider_name),
+                retry_after_seconds: Some(exceeded.retry_after().as_secs().max(1)),
+            });
+        }
+
+        let requested_model = request.model.clone().unwrap_or_else(|| "unknown".to_string());
+        let start_time = std::time::Instant::now();
+        match self.complete_with_retry(provider_name, provider.as_ref(), &request).await {
+            Ok(response) => {
+                let response_time = start_time.elapsed().as_millis() as u64;
+
+                self.record_metrics(provider_name, &response.model, true, response_time, response.usage.as_ref()).await;
+                self.record_success(provider_name).await;
+
+                Ok(response)
+            }
+            Err(e) => {
+                let response_time = start_time.elapsed().as_millis() as u64;
+                self.record_metrics(provider_name, &requested_model, false, response_time, None).await;
+                self.record_failure(provider_name).await;
+
+                Err(e)
+            }
+        }
+    }
+
     pub async fn complete_with_fallback(&self, mut request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        self.validate_context_length(&request)?;
+
         let strategy = RoutingStrategy::Failover;
         let available_providers = self.get_available_providers().await;
-        
+
         if available_providers.is_empty() {
-            return Err(ProviderError::Unavailable("No providers available".to_string()));
+            return Err(ProviderError::Unavailable { message: "No providers available".to_string(), retry_after_seconds: None });
+        }
+
+        let requested_model = request.model.clone().unwrap_or_else(|| "unknown".to_string());
+
+        let cache_enabled = request.cache.unwrap_or(self.config.providers.response_cache_enabled_by_default)
+            && request.temperature.unwrap_or(0.0) <= CACHEABLE_TEMPERATURE_THRESHOLD;
+
+        if cache_enabled {
+            // The fallback chain is tried in the same order every time for a
+            // given set of available providers, so probing the top candidate
+            // is enough to catch the common case of a repeated request
+            // hitting the same provider it did last time.
+            let cache_key = completion_cache_key(&available_providers[0], &requested_model, &request);
+            if let Ok(Some(cached)) = self.response_cache.get::<CompletionResponse>(&cache_key).await {
+                debug!("Serving cached completion for provider {}", available_providers[0]);
+                return Ok(CompletionResponse { cached: true, ..cached });
+            }
         }
 
         // Try providers in priority order
         let mut last_error = None;
-        
+
         for provider_name in &available_providers {
             if let Some(provider) = self.providers.get(provider_name) {
                 let start_time = std::time::Instant::now();
-                
-                match provider.complete(request.clone()).await {
+
+                match self.complete_with_retry(provider_name, provider.as_ref(), &request).await {
                     Ok(response) => {
                         let response_time = start_time.elapsed().as_millis() as u64;
-                        let tokens = response.usage.as_ref().map(|u| u.total_tokens).unwrap_or(0);
-                        let cost = response.usage.as_ref().and_then(|u| u.cost_usd).unwrap_or(0.0);
-                        
-                        self.record_metrics(provider_name, true, response_time, tokens, cost).await;
-                        
+
+                        self.record_metrics(provider_name, &response.model, true, response_time, response.usage.as_ref()).await;
+                        self.record_success(provider_name).await;
+
+                        if cache_enabled {
+                            let cache_key = completion_cache_key(provider_name, &response.model, &request);
+                            if let Err(e) = self.response_cache.set(&cache_key, response.clone(), None).await {
+                                warn!("Failed to cache completion response for {}: {}", provider_name, e);
+                            }
+                        }
+
                         info!("Successful completion from provider: {}", provider_name);
                         return Ok(response);
                     }
                     Err(e) => {
                         let response_time = start_time.elapsed().as_millis() as u64;
-                        self.record_metrics(provider_name, false, response_time, 0, 0.0).await;
-                        
+                        self.record_metrics(provider_name, &requested_model, false, response_time, None).await;
+                        self.record_failure(provider_name).await;
+
                         warn!("Provider {} failed: {}", provider_name, e);
                         last_error = Some(e);
                         continue;
@@ -380,7 +1119,110 @@ impl ProviderRouter {
             }
         }
 
-        Err(last_error.unwrap_or_else(|| ProviderError::Unavailable("All providers failed".to_string())))
+        Err(last_error.unwrap_or_else(|| ProviderError::Unavailable { message: "All providers failed".to_string(), retry_after_seconds: None }))
+    }
+
+    /// Embeds `texts` through the first available provider whose `embed`
+    /// implementation doesn't return `Unavailable`, batching at most 100
+    /// texts per call to stay under provider-side batch limits. Falls
+    /// through to the next available provider on failure, same as
+    /// `complete_with_fallback`.
+    pub async fn embed(&self, texts: Vec<String>, model: Option<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
+        const MAX_BATCH_SIZE: usize = 100;
+
+        let available_providers = self.get_available_providers().await;
+        if available_providers.is_empty() {
+            return Err(ProviderError::Unavailable { message: "No providers available".to_string(), retry_after_seconds: None });
+        }
+
+        let mut last_error = None;
+        for provider_name in &available_providers {
+            let Some(provider) = self.providers.get(provider_name) else { continue };
+
+            let mut embeddings = Vec::with_capacity(texts.len());
+            let mut failed = false;
+
+            for batch in texts.chunks(MAX_BATCH_SIZE) {
+                match provider.embed(batch.to_vec(), model.clone()).await {
+                    Ok(batch_embeddings) => embeddings.extend(batch_embeddings),
+                    Err(e) => {
+                        warn!("Provider {} failed to embed: {}", provider_name, e);
+                        last_error = Some(e);
+                        failed = true;
+                        break;
+                    }
+                }
+            }
+
+            if !failed {
+                return Ok(embeddings);
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| ProviderError::Unavailable { message: "All providers failed".to_string(), retry_after_seconds: None }))
+    }
+
+    /// Reranks `documents` against `query` through `provider_name`'s
+    /// `RerankProvider::rerank`, protected by the same circuit breaker and
+    /// `ProviderMetrics` bookkeeping as `complete_for_user`. Returns
+    /// `Unavailable` if `provider_name` isn't a rerank-capable provider
+    /// (not configured, or a provider without a native rerank endpoint), or
+    /// if its circuit is currently open.
+    pub async fn rerank(
+        &self,
+        provider_name: &str,
+        query: &str,
+        documents: &[String],
+    ) -> Result<Vec<(usize, f32)>, ProviderError> {
+        let provider = self
+            .rerank_providers
+            .get(provider_name)
+            .ok_or_else(|| ProviderError::Unavailable {
+                message: format!("{} is not a rerank-capable provider", provider_name),
+                retry_after_seconds: None,
+            })?;
+
+        if !self.circuit_allows_routing(provider_name).await {
+            return Err(ProviderError::Unavailable {
+                message: format!("{} circuit breaker is open", provider_name),
+                retry_after_seconds: None,
+            });
+        }
+
+        let start_time = Instant::now();
+        match provider.rerank(query, documents).await {
+            Ok(ranked) => {
+                let response_time = start_time.elapsed().as_millis() as u64;
+                self.record_metrics(provider_name, "rerank", true, response_time, None).await;
+                self.record_success(provider_name).await;
+                Ok(ranked)
+            }
+            Err(e) => {
+                let response_time = start_time.elapsed().as_millis() as u64;
+                self.record_metrics(provider_name, "rerank", false, response_time, None).await;
+                self.record_failure(provider_name).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Finds a code-specialized model (e.g. Mistral's `codestral-latest`) among
+    /// currently available providers, so code review requests can be steered
+    /// towards it instead of a general-purpose chat model.
+    async fn preferred_code_specialized_model(&self) -> Option<String> {
+        let available_providers = self.get_available_providers().await;
+
+        for provider_name in &available_providers {
+            if let Some(provider) = self.providers.get(provider_name) {
+                if let Some(model) = provider.get_config().models.iter()
+                    .find(|m| model_capability(m) == ModelCapability::CodeSpecialized)
+                {
+                    return Some(model.clone());
+                }
+            }
+        }
+
+        None
     }
 }
 
@@ -426,21 +1268,86 @@ impl AIProvider for ProviderRouter {
 
     async fn complete_stream(
         &self,
-        _request: CompletionRequest,
+        request: CompletionRequest,
     ) -> Result<tokio::sync::mpsc::Receiver<Result<String, ProviderError>>, ProviderError> {
+        let model = request.model.clone().unwrap_or_else(|| "unknown".to_string());
+        self.record_metrics("router", &model, false, 0, None).await;
         Err(ProviderError::ApiError("Streaming not yet implemented for router".to_string()))
     }
 
     async fn analyze_code(&self, request: AnalysisRequest) -> Result<AnalysisResponse, ProviderError> {
-        let completion_request = CompletionRequest::new(format!("Analyze this code: {}", request.code));
-        let response = self.complete(completion_request).await?;
-        
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "summary": { "type": "string" },
+                "confidence_score": { "type": "number" },
+                "findings": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "severity": { "type": "string", "enum": ["Critical", "High", "Medium", "Low", "Info"] },
+                            "category": { "type": "string" },
+                            "title": { "type": "string" },
+                            "description": { "type": "string" },
+                            "line_number": { "type": ["integer", "null"] },
+                            "column": { "type": ["integer", "null"] },
+                            "code_snippet": { "type": ["string", "null"] },
+                            "fix_suggestion": { "type": ["string", "null"] }
+                        },
+                        "required": ["severity", "category", "title", "description"]
+                    }
+                },
+                "suggestions": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "title": { "type": "string" },
+                            "description": { "type": "string" },
+                            "code_example": { "type": ["string", "null"] },
+                            "impact": { "type": "string" },
+                            "effort": { "type": "string" }
+                        },
+                        "required": ["title", "description", "impact", "effort"]
+                    }
+                }
+            },
+            "required": ["summary", "findings", "suggestions"]
+        });
+
+        let mut completion_request = CompletionRequest::new(format!(
+            "Analyze this {:?} code:\n\n{}",
+            request.analysis_type, request.code
+        ))
+        .with_response_format(ResponseFormat::JsonSchema { schema });
+
+        if matches!(request.analysis_type, AnalysisType::CodeReview) {
+            if let Some(model) = self.preferred_code_specialized_model().await {
+                completion_request = completion_request.with_model(model);
+            }
+        }
+
+        let value = self.complete_json(completion_request).await?;
+
+        let findings: Vec<Finding> = match value.get("findings").cloned() {
+            Some(v) => serde_json::from_value(v)
+                .map_err(|e| ProviderError::ApiError(format!("Malformed findings in analysis response: {e}")))?,
+            None => vec![],
+        };
+
+        let suggestions: Vec<Suggestion> = match value.get("suggestions").cloned() {
+            Some(v) => serde_json::from_value(v)
+                .map_err(|e| ProviderError::ApiError(format!("Malformed suggestions in analysis response: {e}")))?,
+            None => vec![],
+        };
+
         Ok(AnalysisResponse {
             analysis_type: request.analysis_type,
-            findings: vec![],
-            summary: response.choices.first().map(|c| c.text.clone()).unwrap_or_default(),
-            confidence_score: 0.8,
-            suggestions: vec![],
+            findings,
+            summary: value.get("summary").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+            confidence_score: value.get("confidence_score").and_then(|v| v.as_f64()).unwrap_or(0.8) as f32,
+            suggestions,
         })
     }
 
@@ -482,4 +1389,293 @@ impl AIProvider for ProviderRouter {
         // Return estimate from the cheapest available provider
         Some(0.001) // Default estimate
     }
+}
+
+fn provider_type_for_name(name: &str) -> ProviderType {
+    match name {
+        "openrouter" => ProviderType::OpenRouter,
+        "openai" => ProviderType::OpenAI,
+        "azure_openai" => ProviderType::AzureOpenAI,
+        "bedrock" => ProviderType::Bedrock,
+        "anthropic" => ProviderType::Anthropic,
+        "google" => ProviderType::Google,
+        "groq" => ProviderType::Groq,
+        "together" => ProviderType::Together,
+        "cohere" => ProviderType::Cohere,
+        "mistral" => ProviderType::Mistral,
+        _ => ProviderType::Ollama,
+    }
+}
+
+#[cfg(test)]
+mod circuit_breaker_tests {
+    use super::*;
+
+    async fn test_router() -> ProviderRouter {
+        let config = Arc::new(Config::from_env().expect("Config::from_env should always succeed with defaults"));
+        ProviderRouter::new(config).await.expect("ProviderRouter::new should always succeed")
+    }
+
+    #[tokio::test]
+    async fn closed_circuit_allows_routing() {
+        let router = test_router().await;
+        assert!(router.circuit_allows_routing("test-provider").await);
+        let (state, _) = router.circuit_state_and_next_probe("test-provider").await;
+        assert_eq!(state, CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn opens_after_failure_threshold_within_window() {
+        let router = test_router().await;
+        let threshold = router.config.providers.circuit_breaker.failure_threshold;
+
+        for _ in 0..threshold {
+            router.record_failure("test-provider").await;
+        }
+
+        let (state, next_probe) = router.circuit_state_and_next_probe("test-provider").await;
+        assert_eq!(state, CircuitState::Open);
+        assert!(next_probe.is_some());
+        assert!(!router.circuit_allows_routing("test-provider").await);
+    }
+
+    #[tokio::test]
+    async fn half_open_probe_success_closes_circuit() {
+        let router = test_router().await;
+        let threshold = router.config.providers.circuit_breaker.failure_threshold;
+
+        for _ in 0..threshold {
+            router.record_failure("test-provider").await;
+        }
+        // Force the cooldown to have already elapsed so the next check probes.
+        {
+            let mut breakers = router.circuit_breakers.write().await;
+            let breaker = breakers.get_mut("test-provider").unwrap();
+            breaker.opened_at = Some(Instant::now() - breaker.cooldown - Duration::from_secs(1));
+        }
+
+        assert!(router.circuit_allows_routing("test-provider").await);
+        let (state, _) = router.circuit_state_and_next_probe("test-provider").await;
+        assert_eq!(state, CircuitState::HalfOpen);
+
+        router.record_success("test-provider").await;
+        let (state, _) = router.circuit_state_and_next_probe("test-provider").await;
+        assert_eq!(state, CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn half_open_probe_failure_reopens_with_doubled_cooldown() {
+        let router = test_router().await;
+        let base_cooldown = Duration::from_secs(router.config.providers.circuit_breaker.cooldown_seconds);
+        let threshold = router.config.providers.circuit_breaker.failure_threshold;
+
+        for _ in 0..threshold {
+            router.record_failure("test-provider").await;
+        }
+        {
+            let mut breakers = router.circuit_breakers.write().await;
+            let breaker = breakers.get_mut("test-provider").unwrap();
+            breaker.opened_at = Some(Instant::now() - breaker.cooldown - Duration::from_secs(1));
+        }
+        assert!(router.circuit_allows_routing("test-provider").await); // -> HalfOpen
+
+        router.record_failure("test-provider").await; // probe fails
+
+        let breakers = router.circuit_breakers.read().await;
+        let breaker = breakers.get("test-provider").unwrap();
+        assert_eq!(breaker.state, CircuitState::Open);
+        assert_eq!(breaker.cooldown, base_cooldown * 2);
+    }
+
+    #[tokio::test]
+    async fn failures_outside_the_window_do_not_accumulate() {
+        let router = test_router().await;
+        let threshold = router.config.providers.circuit_breaker.failure_threshold;
+
+        for _ in 0..threshold.saturating_sub(1) {
+            router.record_failure("test-provider").await;
+        }
+        {
+            // Age the recorded failures out of the window.
+            let window = Duration::from_secs(router.config.providers.circuit_breaker.window_seconds);
+            let mut breakers = router.circuit_breakers.write().await;
+            let breaker = breakers.get_mut("test-provider").unwrap();
+            for ts in breaker.failure_timestamps.iter_mut() {
+                *ts = Instant::now() - window - Duration::from_secs(1);
+            }
+        }
+
+        router.record_failure("test-provider").await;
+
+        let (state, _) = router.circuit_state_and_next_probe("test-provider").await;
+        assert_eq!(state, CircuitState::Closed);
+    }
+}
+
+#[cfg(test)]
+mod response_cache_tests {
+    use super::*;
+    use crate::providers::traits::{AnalysisRequest, AnalysisResponse, Choice, HealthCheck, Usage};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts `complete` calls so tests can assert the response cache spared
+    /// it a second call, without needing a real provider configured.
+    struct CountingProvider {
+        calls: Arc<AtomicUsize>,
+        config: crate::config::ProviderConfig,
+    }
+
+    #[async_trait]
+    impl AIProvider for CountingProvider {
+        fn name(&self) -> &str {
+            "counting-provider"
+        }
+
+        async fn health_check(&self) -> Result<HealthCheck, ProviderError> {
+            Ok(HealthCheck {
+                is_available: true,
+                response_time_ms: 0,
+                supported_models: vec!["test-model".to_string()],
+                rate_limit_remaining: None,
+                error_message: None,
+            })
+        }
+
+        async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+            Ok(vec!["test-model".to_string()])
+        }
+
+        async fn complete(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(CompletionResponse {
+                id: Uuid::new_v4().to_string(),
+                choices: vec![Choice {
+                    index: 0,
+                    text: format!("response to: {}", request.prompt),
+                    finish_reason: Some("stop".to_string()),
+                    logprobs: None,
+                    tool_calls: None,
+                }],
+                usage: Some(Usage {
+                    prompt_tokens: 10,
+                    completion_tokens: 10,
+                    total_tokens: 20,
+                    cost_usd: Some(0.01),
+                    cache_creation_tokens: None,
+                    cache_read_tokens: None,
+                }),
+                model: "test-model".to_string(),
+                provider: "counting-provider".to_string(),
+                created_at: chrono::Utc::now(),
+                metadata: None,
+                cached: false,
+            })
+        }
+
+        async fn complete_stream(
+            &self,
+            _request: CompletionRequest,
+        ) -> Result<tokio::sync::mpsc::Receiver<Result<String, ProviderError>>, ProviderError> {
+            Err(ProviderError::ApiError("not implemented".to_string()))
+        }
+
+        async fn analyze_code(&self, _request: AnalysisRequest) -> Result<AnalysisResponse, ProviderError> {
+            Err(ProviderError::ApiError("not implemented".to_string()))
+        }
+
+        async fn generate_documentation(&self, _code: &str, _language: &str) -> Result<String, ProviderError> {
+            Err(ProviderError::ApiError("not implemented".to_string()))
+        }
+
+        async fn generate_tests(&self, _code: &str, _language: &str) -> Result<String, ProviderError> {
+            Err(ProviderError::ApiError("not implemented".to_string()))
+        }
+
+        async fn explain_code(&self, _code: &str, _language: &str) -> Result<String, ProviderError> {
+            Err(ProviderError::ApiError("not implemented".to_string()))
+        }
+
+        async fn refactor_code(&self, _code: &str, _language: &str, _instructions: &str) -> Result<String, ProviderError> {
+            Err(ProviderError::ApiError("not implemented".to_string()))
+        }
+
+        async fn translate_code(&self, _code: &str, _from_language: &str, _to_language: &str) -> Result<String, ProviderError> {
+            Err(ProviderError::ApiError("not implemented".to_string()))
+        }
+
+        fn get_config(&self) -> &crate::config::ProviderConfig {
+            &self.config
+        }
+
+        fn estimate_cost(&self, _request: &CompletionRequest) -> Option<f64> {
+            Some(0.01)
+        }
+    }
+
+    async fn router_with_counting_provider() -> (ProviderRouter, Arc<AtomicUsize>) {
+        let config = Arc::new(Config::from_env().expect("Config::from_env should always succeed with defaults"));
+        let mut router = ProviderRouter::new(config).await.expect("ProviderRouter::new should always succeed");
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        router.providers.insert(
+            "counting-provider".to_string(),
+            Box::new(CountingProvider {
+                calls: calls.clone(),
+                config: crate::config::ProviderConfig {
+                    enabled: true,
+                    api_key: None,
+                    base_url: String::new(),
+                    timeout_seconds: 30,
+                    max_retries: 1,
+                    priority: 1,
+                    models: vec!["test-model".to_string()],
+                },
+            }),
+        );
+
+        (router, calls)
+    }
+
+    #[tokio::test]
+    async fn identical_cacheable_request_is_served_from_cache() {
+        let (router, calls) = router_with_counting_provider().await;
+        let request = CompletionRequest::new("what is 2+2?".to_string())
+            .with_temperature(0.0)
+            .with_cache(true);
+
+        let first = router.complete_with_fallback(request.clone()).await.unwrap();
+        assert!(!first.cached);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let second = router.complete_with_fallback(request).await.unwrap();
+        assert!(second.cached);
+        assert_eq!(second.choices[0].text, first.choices[0].text);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn non_deterministic_request_is_never_cached() {
+        let (router, calls) = router_with_counting_provider().await;
+        let request = CompletionRequest::new("tell me a story".to_string())
+            .with_temperature(0.8)
+            .with_cache(true);
+
+        router.complete_with_fallback(request.clone()).await.unwrap();
+        router.complete_with_fallback(request).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn cache_opt_out_is_respected() {
+        let (router, calls) = router_with_counting_provider().await;
+        let request = CompletionRequest::new("what is 2+2?".to_string())
+            .with_temperature(0.0)
+            .with_cache(false);
+
+        router.complete_with_fallback(request.clone()).await.unwrap();
+        router.complete_with_fallback(request).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
 }
\ No newline at end of file
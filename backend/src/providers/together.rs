@@ -23,6 +23,8 @@ impl TogetherProvider {
     }
 
     async fn make_request(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        super::traits::reject_attachments(&request, "Together AI")?;
+
         let api_key = self.config.api_key.as_ref()
             .ok_or_else(|| ProviderError::AuthError("Together AI API key not configured".to_string()))?;
 
@@ -43,16 +45,22 @@ impl TogetherProvider {
             "content": request.prompt
         }));
 
-        let payload = json!({
+        let mut payload = json!({
             "model": model,
             "messages": messages,
             "max_tokens": request.max_tokens.unwrap_or(1000),
             "temperature": request.temperature.unwrap_or(0.7),
             "top_p": request.top_p.unwrap_or(0.9),
+            "frequency_penalty": request.frequency_penalty.unwrap_or(0.0),
+            "presence_penalty": request.presence_penalty.unwrap_or(0.0),
             "repetition_penalty": 1.0,
             "stream": false
         });
 
+        if let Some(stop) = &request.stop_sequences {
+            payload["stop"] = json!(stop);
+        }
+
         debug!("Together AI request: {}", serde_json::to_string_pretty(&payload).unwrap_or_default());
 
         let response = self.client
@@ -66,14 +74,10 @@ impl TogetherProvider {
 
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = super::http_errors::retry_after_seconds(&response);
             let error_text = response.text().await.unwrap_or_default();
             
-            return Err(match status.as_u16() {
-                401 => ProviderError::AuthError(error_text),
-                429 => ProviderError::RateLimitError(error_text),
-                404 => ProviderError::ModelNotFound(error_text),
-                _ => ProviderError::ApiError(format!("HTTP {}: {}", status, error_text)),
-            });
+            return Err(super::http_errors::map_status_error(status, retry_after, error_text));
         }
 
         let response_json: serde_json::Value = response.json().await
@@ -99,6 +103,8 @@ impl TogetherProvider {
             completion_tokens: u["completion_tokens"].as_u64().unwrap_or(0) as u32,
             total_tokens: u["total_tokens"].as_u64().unwrap_or(0) as u32,
             cost_usd: Some(0.0008), // Together AI pricing
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
         });
 
         Ok(CompletionResponse {
@@ -109,6 +115,7 @@ impl TogetherProvider {
             provider: "together".to_string(),
             created_at: chrono::Utc::now(),
             metadata: None,
+            cached: false,
         })
     }
 }
@@ -279,6 +286,9 @@ impl AIProvider for TogetherProvider {
                 super::traits::AnalysisType::Documentation => "documentation needs",
                 super::traits::AnalysisType::Testing => "testing requirements",
                 super::traits::AnalysisType::Refactoring => "refactoring opportunities",
+                super::traits::AnalysisType::CodeReview => "overall code review feedback",
+                super::traits::AnalysisType::CodeSmells => "code smells and refactoring opportunities",
+                super::traits::AnalysisType::DocCoverage => "documentation coverage of the public API",
             },
             request.language,
             request.code
@@ -0,0 +1,63 @@
+//! Token counting used to validate a request's prompt + `max_tokens` against
+//! a model's context window before dispatching to a provider, so an
+//! oversized request fails fast with a structured error instead of a
+//! provider's opaque 400.
+
+use tiktoken_rs::get_bpe_from_model;
+
+/// Counts tokens in `text` for `model`. Uses tiktoken's real BPE encoding
+/// for OpenAI-family models; falls back to a ~4-chars-per-token heuristic
+/// for models tiktoken doesn't recognize (Anthropic, Google, open-weight
+/// models), which is close enough for a context-budget check.
+pub fn count_tokens(text: &str, model: &str) -> usize {
+    match get_bpe_from_model(model) {
+        Ok(bpe) => bpe.encode_with_special_tokens(text).len(),
+        Err(_) => heuristic_token_count(text),
+    }
+}
+
+fn heuristic_token_count(text: &str) -> usize {
+    (text.chars().count() / 4).max(1)
+}
+
+/// Conservative fallback for models not in `context_window_for_model`'s
+/// table.
+const DEFAULT_CONTEXT_WINDOW: u32 = 8_192;
+
+/// Known context window sizes, keyed by model name. Mirrors the pricing
+/// table in `cost_calculator.rs`; unlisted models fall back to
+/// `DEFAULT_CONTEXT_WINDOW`.
+pub fn context_window_for_model(model: &str) -> u32 {
+    match model {
+        "gpt-4o" | "gpt-4o-mini" | "gpt-4-turbo" => 128_000,
+        "gpt-3.5-turbo" => 16_385,
+        "claude-3-sonnet" | "claude-3-haiku" | "claude-3-opus" => 200_000,
+        "gemini-pro" | "gemini-flash" => 32_768,
+        "llama-3.1-70b" => 131_072,
+        "mixtral-8x7b" => 32_768,
+        _ => DEFAULT_CONTEXT_WINDOW,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_tokens_for_a_known_openai_model() {
+        let count = count_tokens("hello world", "gpt-4o-mini");
+        assert!(count > 0 && count < 5);
+    }
+
+    #[test]
+    fn falls_back_to_heuristic_for_unknown_models() {
+        let count = count_tokens("hello world, this is a test", "some-unlisted-model");
+        assert!(count > 0);
+    }
+
+    #[test]
+    fn known_models_have_non_default_context_windows() {
+        assert_eq!(context_window_for_model("gpt-4o"), 128_000);
+        assert_eq!(context_window_for_model("totally-unknown-model"), DEFAULT_CONTEXT_WINDOW);
+    }
+}
@@ -1,5 +1,7 @@
 use async_trait::async_trait;
+use base64::Engine as _;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::collections::HashMap;
 use thiserror::Error;
 
@@ -9,8 +11,12 @@ pub enum ProviderError {
     ApiError(String),
     #[error("Authentication failed: {0}")]
     AuthError(String),
-    #[error("Rate limit exceeded: {0}")]
-    RateLimitError(String),
+    #[error("Rate limit exceeded: {message}")]
+    RateLimitError {
+        message: String,
+        /// Parsed from the response's `Retry-After` header, when present.
+        retry_after_seconds: Option<u64>,
+    },
     #[error("Model not found: {0}")]
     ModelNotFound(String),
     #[error("Invalid request: {0}")]
@@ -19,8 +25,45 @@ pub enum ProviderError {
     NetworkError(String),
     #[error("Timeout error: {0}")]
     TimeoutError(String),
-    #[error("Provider unavailable: {0}")]
-    Unavailable(String),
+    #[error("Provider unavailable: {message}")]
+    Unavailable {
+        message: String,
+        /// Parsed from the response's `Retry-After` header, when present
+        /// (e.g. a 503 response).
+        retry_after_seconds: Option<u64>,
+    },
+    #[error("Context length exceeded: {model} supports {context_window} tokens, but prompt ({prompt_tokens} tokens) + max_tokens ({max_tokens}) would exceed it")]
+    ContextLengthExceeded {
+        model: String,
+        prompt_tokens: usize,
+        max_tokens: u32,
+        context_window: u32,
+    },
+}
+
+impl ProviderError {
+    /// Whether retrying the same request against the same provider is worth
+    /// attempting. Deliberately excludes auth/validation/model errors, which
+    /// a retry can never fix.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ProviderError::RateLimitError { .. }
+                | ProviderError::NetworkError(_)
+                | ProviderError::TimeoutError(_)
+                | ProviderError::Unavailable { .. }
+        )
+    }
+
+    /// Server-requested backoff from a `Retry-After` header, if the error
+    /// carries one.
+    pub fn retry_after_seconds(&self) -> Option<u64> {
+        match self {
+            ProviderError::RateLimitError { retry_after_seconds, .. } => *retry_after_seconds,
+            ProviderError::Unavailable { retry_after_seconds, .. } => *retry_after_seconds,
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,7 +81,135 @@ pub struct CompletionRequest {
     pub context: Option<String>,
     pub system_prompt: Option<String>,
     pub tools: Option<Vec<Tool>>,
+    /// Passed through verbatim to the provider (e.g. OpenAI's `"auto"`,
+    /// `"none"`, or `{"type": "function", "function": {"name": "..."}}`).
+    pub tool_choice: Option<serde_json::Value>,
+    pub response_format: Option<ResponseFormat>,
     pub metadata: Option<HashMap<String, serde_json::Value>>,
+    /// Opts into Anthropic prompt caching (`cache_control` blocks) for large
+    /// system prompts; ignored by providers that don't support it. Defaults
+    /// to `true` since caching is free to request and only engages once
+    /// `system_prompt` crosses Anthropic's cacheable-block size threshold.
+    pub prompt_cache: bool,
+    /// Non-text inputs (currently only images) attached alongside `prompt`,
+    /// e.g. a screenshot of a stack trace. Only `OpenAIProvider`,
+    /// `OpenRouterProvider`, `AnthropicProvider`, and `GoogleProvider` map
+    /// these to a vision request; other providers reject them outright.
+    pub attachments: Option<Vec<Attachment>>,
+    /// Opts into `ProviderRouter`'s response cache, keyed on a hash of the
+    /// request's deterministic fields. `None` defers to
+    /// `Config::response_cache_enabled_by_default`. Caching is only ever
+    /// applied when `temperature` is near zero, regardless of this flag,
+    /// since a non-deterministic request can't be safely replayed from cache.
+    pub cache: Option<bool>,
+}
+
+/// A non-text input attached to a [`CompletionRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub kind: AttachmentKind,
+    /// A base64-encoded payload, or an `http(s)` URL the provider can fetch
+    /// directly. Distinguished by [`Attachment::is_url`].
+    pub data: String,
+    pub mime_type: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentKind {
+    Image,
+}
+
+impl Attachment {
+    pub fn is_url(&self) -> bool {
+        self.data.starts_with("http://") || self.data.starts_with("https://")
+    }
+}
+
+/// Largest image attachment accepted, in bytes. Only enforceable for
+/// base64-encoded attachments; a URL's size isn't known without fetching it.
+pub const MAX_ATTACHMENT_BYTES: usize = 5 * 1024 * 1024;
+
+/// Rejects `request.attachments` with a clear error, for providers that
+/// don't support image inputs at all.
+pub fn reject_attachments(request: &CompletionRequest, provider_name: &str) -> Result<(), ProviderError> {
+    if request.attachments.as_ref().is_some_and(|a| !a.is_empty()) {
+        return Err(ProviderError::InvalidRequest(format!(
+            "{} does not support image attachments",
+            provider_name
+        )));
+    }
+    Ok(())
+}
+
+/// Logs a warning instead of failing the request when the caller sets a
+/// sampling parameter this provider's API has no field for, so a client that
+/// targets the lowest common denominator doesn't have to special-case every
+/// provider.
+pub fn warn_unsupported_sampling_params(
+    provider_name: &str,
+    request: &CompletionRequest,
+    supports_penalties: bool,
+    supports_stop: bool,
+) {
+    if !supports_penalties {
+        if request.frequency_penalty.is_some() {
+            tracing::warn!("{} does not support frequency_penalty; ignoring", provider_name);
+        }
+        if request.presence_penalty.is_some() {
+            tracing::warn!("{} does not support presence_penalty; ignoring", provider_name);
+        }
+    }
+    if !supports_stop && request.stop_sequences.as_ref().is_some_and(|s| !s.is_empty()) {
+        tracing::warn!("{} does not support stop sequences; ignoring", provider_name);
+    }
+}
+
+/// Enforces [`MAX_ATTACHMENT_BYTES`] on a base64-encoded attachment; a no-op
+/// for URL attachments.
+pub fn validate_attachment_size(attachment: &Attachment) -> Result<(), ProviderError> {
+    if attachment.is_url() {
+        return Ok(());
+    }
+
+    let decoded_len = base64::engine::general_purpose::STANDARD
+        .decode(&attachment.data)
+        .map_err(|e| ProviderError::InvalidRequest(format!("Invalid base64 image data: {}", e)))?
+        .len();
+
+    if decoded_len > MAX_ATTACHMENT_BYTES {
+        return Err(ProviderError::InvalidRequest(format!(
+            "Image attachment is {} bytes, exceeding the {} byte limit",
+            decoded_len, MAX_ATTACHMENT_BYTES
+        )));
+    }
+
+    Ok(())
+}
+
+/// Requests that a completion be returned as structured JSON rather than
+/// free-form text. Providers with native support map this to their own
+/// `response_format` parameter (see `ResponseFormat::to_openai_json`);
+/// others fall back to `AIProvider::complete_json`'s prompt-wrapping.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    JsonObject,
+    JsonSchema { schema: serde_json::Value },
+}
+
+impl ResponseFormat {
+    /// Maps to the `response_format` wire shape used by OpenAI-compatible
+    /// APIs (OpenAI, OpenRouter, Groq).
+    pub fn to_openai_json(&self) -> serde_json::Value {
+        match self {
+            ResponseFormat::JsonObject => json!({ "type": "json_object" }),
+            ResponseFormat::JsonSchema { schema } => json!({
+                "type": "json_schema",
+                "json_schema": { "name": "response", "schema": schema }
+            }),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +221,10 @@ pub struct CompletionResponse {
     pub provider: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub metadata: Option<HashMap<String, serde_json::Value>>,
+    /// Set by `ProviderRouter::complete_with_fallback` when this response was
+    /// served from its response cache instead of hitting a provider.
+    #[serde(default)]
+    pub cached: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +242,14 @@ pub struct Usage {
     pub completion_tokens: u32,
     pub total_tokens: u32,
     pub cost_usd: Option<f64>,
+    /// Tokens written to the prompt cache on this request (Anthropic
+    /// `cache_creation_input_tokens`). `None` for providers without prompt
+    /// caching.
+    pub cache_creation_tokens: Option<u32>,
+    /// Tokens served from the prompt cache instead of being reprocessed
+    /// (Anthropic `cache_read_input_tokens`). `None` for providers without
+    /// prompt caching.
+    pub cache_read_tokens: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -113,6 +296,80 @@ pub enum AnalysisType {
     Documentation,
     Testing,
     Refactoring,
+    CodeReview,
+    /// Heuristic code-smell detection, handled locally by
+    /// `ai_engine::code_smell_detector` instead of an LLM provider.
+    #[serde(alias = "smells")]
+    CodeSmells,
+    /// Public-API documentation coverage, handled locally by
+    /// `services::documentation_generator::DocumentationCoverageScorer`
+    /// instead of an LLM provider.
+    #[serde(alias = "doc_coverage")]
+    DocCoverage,
+}
+
+/// Declares what a model is particularly good at, so routing can prefer a
+/// specialized model over a general-purpose one for matching request types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModelCapability {
+    General,
+    CodeSpecialized,
+}
+
+pub fn model_capability(model: &str) -> ModelCapability {
+    match model {
+        "codestral-latest" => ModelCapability::CodeSpecialized,
+        _ => ModelCapability::General,
+    }
+}
+
+/// Published specs for a model, surfaced by `ProviderRouter::get_models` so
+/// callers can pick a model without hardcoding its limits and pricing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub id: String,
+    pub display_name: String,
+    pub context_window_tokens: u32,
+    pub supports_streaming: bool,
+    pub supports_function_calling: bool,
+    pub capabilities: Vec<ModelCapability>,
+    pub input_cost_per_1k: f64,
+    pub output_cost_per_1k: f64,
+}
+
+/// Looks up `model`'s published specs. Falls back to conservative defaults
+/// for models not in the table (e.g. self-hosted Ollama models, or new
+/// releases this table hasn't caught up with yet), since providers don't all
+/// expose this data through a `/models` API.
+pub fn model_info(model: &str) -> ModelInfo {
+    let capabilities = vec![model_capability(model)];
+
+    let (display_name, context_window_tokens, supports_streaming, supports_function_calling, input_cost_per_1k, output_cost_per_1k) =
+        match model {
+            "gpt-4o" => ("GPT-4o", 128_000, true, true, 0.0025, 0.01),
+            "gpt-4o-mini" => ("GPT-4o mini", 128_000, true, true, 0.00015, 0.0006),
+            "gpt-4-turbo" => ("GPT-4 Turbo", 128_000, true, true, 0.01, 0.03),
+            "gpt-3.5-turbo" => ("GPT-3.5 Turbo", 16_385, true, true, 0.0005, 0.0015),
+            "claude-3-5-sonnet-20241022" => ("Claude 3.5 Sonnet", 200_000, true, true, 0.003, 0.015),
+            "claude-3-haiku-20240307" => ("Claude 3 Haiku", 200_000, true, true, 0.00025, 0.00125),
+            "gemini-pro" => ("Gemini Pro", 32_760, true, true, 0.0005, 0.0015),
+            "gemini-pro-vision" => ("Gemini Pro Vision", 16_384, false, false, 0.0005, 0.0015),
+            "llama-3.1-70b-versatile" => ("Llama 3.1 70B Versatile", 131_072, true, true, 0.00059, 0.00079),
+            "mixtral-8x7b-32768" => ("Mixtral 8x7B", 32_768, true, false, 0.00024, 0.00024),
+            "codestral-latest" => ("Codestral", 32_000, true, false, 0.0002, 0.0006),
+            _ => (model, 8_192, true, false, 0.0, 0.0),
+        };
+
+    ModelInfo {
+        id: model.to_string(),
+        display_name: display_name.to_string(),
+        context_window_tokens,
+        supports_streaming,
+        supports_function_calling,
+        capabilities,
+        input_cost_per_1k,
+        output_cost_per_1k,
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -203,9 +460,69 @@ pub trait AIProvider: Send + Sync {
     
     /// Get provider-specific configuration
     fn get_config(&self) -> &crate::config::ProviderConfig;
-    
+
     /// Calculate estimated cost for a request
     fn estimate_cost(&self, request: &CompletionRequest) -> Option<f64>;
+
+    /// Embeds `texts` into dense vectors, using `model` if given or the
+    /// provider's default embedding model otherwise. Providers without an
+    /// embedding endpoint return `ProviderError::Unavailable` so callers get
+    /// a clear error instead of a silently wrong fallback vector.
+    async fn embed(&self, _texts: Vec<String>, _model: Option<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
+        Err(ProviderError::Unavailable {
+            message: format!("{} does not support embeddings", self.name()),
+            retry_after_seconds: None,
+        })
+    }
+
+    /// Whether this provider accepts `response_format` directly in its
+    /// native completion payload. Providers returning `false` (the default)
+    /// are routed through `complete_json`'s prompt-wrapping fallback.
+    fn supports_native_json_mode(&self) -> bool {
+        false
+    }
+
+    /// Request a completion and parse the result as JSON, honoring
+    /// `request.response_format` (defaulting to `ResponseFormat::JsonObject`
+    /// if unset). Providers without native support get the format's
+    /// instructions folded into the prompt, and the raw text is stripped of
+    /// markdown code fences and retried once if it fails to parse.
+    async fn complete_json(&self, mut request: CompletionRequest) -> Result<serde_json::Value, ProviderError> {
+        let format = request.response_format.clone().unwrap_or(ResponseFormat::JsonObject);
+
+        if !self.supports_native_json_mode() {
+            request.response_format = None;
+            request.prompt = super::json_mode::wrap_prompt_for_json(&request.prompt, &format);
+        }
+
+        let response = self.complete(request.clone()).await?;
+        let text = response.choices.first().map(|c| c.text.as_str()).unwrap_or("");
+
+        match super::json_mode::extract_json(text) {
+            Ok(value) => Ok(value),
+            Err(first_err) => {
+                let mut retry_request = request;
+                retry_request.prompt =
+                    super::json_mode::wrap_retry_prompt(&retry_request.prompt, text, &first_err.to_string());
+                let retry_response = self.complete(retry_request).await?;
+                let retry_text = retry_response.choices.first().map(|c| c.text.as_str()).unwrap_or("");
+                super::json_mode::extract_json(retry_text)
+            }
+        }
+    }
+}
+
+/// Reranks `documents` against `query` using a provider's dedicated
+/// reranking model (as opposed to `AIProvider::complete`-based scoring, see
+/// `search::result_ranker::CrossEncoderReranker`), returning each document's
+/// original index paired with its relevance score, sorted most-relevant
+/// first. A separate trait from `AIProvider` since only providers with a
+/// native rerank endpoint (currently Cohere) implement it; `ProviderRouter`
+/// keeps rerank-capable providers in their own registry rather than testing
+/// every `AIProvider` for support.
+#[async_trait]
+pub trait RerankProvider: Send + Sync {
+    async fn rerank(&self, query: &str, documents: &[String]) -> Result<Vec<(usize, f32)>, ProviderError>;
 }
 
 impl CompletionRequest {
@@ -224,7 +541,12 @@ impl CompletionRequest {
             context: None,
             system_prompt: None,
             tools: None,
+            tool_choice: None,
+            response_format: None,
             metadata: None,
+            prompt_cache: true,
+            attachments: None,
+            cache: None,
         }
     }
     
@@ -262,4 +584,49 @@ impl CompletionRequest {
         self.tools = Some(tools);
         self
     }
+
+    pub fn with_tool_choice(mut self, tool_choice: serde_json::Value) -> Self {
+        self.tool_choice = Some(tool_choice);
+        self
+    }
+
+    pub fn with_response_format(mut self, response_format: ResponseFormat) -> Self {
+        self.response_format = Some(response_format);
+        self
+    }
+
+    pub fn with_prompt_cache(mut self, prompt_cache: bool) -> Self {
+        self.prompt_cache = prompt_cache;
+        self
+    }
+
+    pub fn with_attachments(mut self, attachments: Vec<Attachment>) -> Self {
+        self.attachments = Some(attachments);
+        self
+    }
+
+    pub fn with_top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    pub fn with_frequency_penalty(mut self, frequency_penalty: f32) -> Self {
+        self.frequency_penalty = Some(frequency_penalty);
+        self
+    }
+
+    pub fn with_presence_penalty(mut self, presence_penalty: f32) -> Self {
+        self.presence_penalty = Some(presence_penalty);
+        self
+    }
+
+    pub fn with_stop_sequences(mut self, stop_sequences: Vec<String>) -> Self {
+        self.stop_sequences = Some(stop_sequences);
+        self
+    }
+
+    pub fn with_cache(mut self, cache: bool) -> Self {
+        self.cache = Some(cache);
+        self
+    }
 }
\ No newline at end of file
@@ -0,0 +1,133 @@
+use std::path::PathBuf;
+
+#[cfg(target_os = "linux")]
+use anyhow::{Context, Result};
+#[cfg(target_os = "linux")]
+use std::fs;
+
+#[cfg(not(target_os = "linux"))]
+use anyhow::Result;
+#[cfg(not(target_os = "linux"))]
+use tracing::warn;
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup/uaida";
+
+/// Scoped `memory` + `cpu` cgroup (v1) used to enforce a [`SandboxConfig`](super::SandboxConfig)'s
+/// resource limits on a sandboxed subprocess. Creates `/sys/fs/cgroup/uaida/{memory,cpu}/<name>`,
+/// writes the limits, and removes both directories again on [`Drop`].
+///
+/// Only supported on Linux, since cgroups are a Linux kernel facility; on other platforms
+/// construction logs a warning and every method becomes a no-op.
+pub struct CgroupGuard {
+    #[cfg(target_os = "linux")]
+    memory_dir: PathBuf,
+    #[cfg(target_os = "linux")]
+    cpu_dir: PathBuf,
+}
+
+impl CgroupGuard {
+    #[cfg(target_os = "linux")]
+    pub fn new(name: &str, memory_limit: &str, cpu_limit: f32) -> Result<Self> {
+        let memory_dir = PathBuf::from(format!("{}/memory/{}", CGROUP_ROOT, name));
+        let cpu_dir = PathBuf::from(format!("{}/cpu/{}", CGROUP_ROOT, name));
+
+        fs::create_dir_all(&memory_dir)
+            .with_context(|| format!("failed to create memory cgroup at {:?}", memory_dir))?;
+        fs::create_dir_all(&cpu_dir)
+            .with_context(|| format!("failed to create cpu cgroup at {:?}", cpu_dir))?;
+
+        fs::write(
+            memory_dir.join("memory.limit_in_bytes"),
+            parse_memory_limit(memory_limit).to_string(),
+        )
+        .context("failed to write memory.limit_in_bytes")?;
+
+        // cpu.cfs_quota_us/cpu.cfs_period_us express the limit as a fraction of a 100ms period.
+        let period_us: u64 = 100_000;
+        let quota_us = (period_us as f32 * cpu_limit.max(0.01)) as u64;
+        fs::write(cpu_dir.join("cpu.cfs_period_us"), period_us.to_string())
+            .context("failed to write cpu.cfs_period_us")?;
+        fs::write(cpu_dir.join("cpu.cfs_quota_us"), quota_us.to_string())
+            .context("failed to write cpu.cfs_quota_us")?;
+
+        Ok(Self { memory_dir, cpu_dir })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn new(_name: &str, _memory_limit: &str, _cpu_limit: f32) -> Result<Self> {
+        warn!("cgroup resource enforcement is only supported on Linux; skipping for this execution");
+        Ok(Self {})
+    }
+
+    /// Moves `pid` into this cgroup so its resource usage (and that of any children it forks)
+    /// is accounted against and bounded by the configured limits. Callers running a workload
+    /// inside a Docker container must pass the *container's* real init PID (see
+    /// [`super::docker_runner::resolve_container_pid`]), not the host-side `docker` CLI client
+    /// process's PID -- that process just hands the request to dockerd/containerd and exits
+    /// almost immediately, long before the workload it spawned has actually run.
+    #[cfg(target_os = "linux")]
+    pub fn add_process(&self, pid: u32) -> Result<()> {
+        fs::write(self.memory_dir.join("tasks"), pid.to_string())
+            .context("failed to add process to memory cgroup")?;
+        fs::write(self.cpu_dir.join("tasks"), pid.to_string())
+            .context("failed to add process to cpu cgroup")?;
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn add_process(&self, _pid: u32) -> Result<()> {
+        Ok(())
+    }
+
+    /// Reads the cgroup's recorded memory usage in bytes, intended to be called after the
+    /// process being tracked has exited.
+    #[cfg(target_os = "linux")]
+    pub fn memory_usage_bytes(&self) -> Option<u64> {
+        fs::read_to_string(self.memory_dir.join("memory.usage_in_bytes"))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn memory_usage_bytes(&self) -> Option<u64> {
+        None
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for CgroupGuard {
+    fn drop(&mut self) {
+        // The kernel refuses rmdir while the cgroup still holds tasks; by the time the guard
+        // drops, the process it was tracking has already exited, so best-effort is enough.
+        let _ = fs::remove_dir(&self.memory_dir);
+        let _ = fs::remove_dir(&self.cpu_dir);
+    }
+}
+
+/// Parses Docker-style shorthand ("512m", "1g", "2048k") into a byte count.
+#[cfg(target_os = "linux")]
+fn parse_memory_limit(limit: &str) -> u64 {
+    let limit = limit.trim();
+    let (digits, multiplier) = match limit.chars().last() {
+        Some(c) if c.eq_ignore_ascii_case(&'g') => (&limit[..limit.len() - 1], 1024 * 1024 * 1024),
+        Some(c) if c.eq_ignore_ascii_case(&'m') => (&limit[..limit.len() - 1], 1024 * 1024),
+        Some(c) if c.eq_ignore_ascii_case(&'k') => (&limit[..limit.len() - 1], 1024),
+        _ => (limit, 1),
+    };
+    digits.trim().parse::<u64>().unwrap_or(512) * multiplier
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_docker_style_memory_shorthand() {
+        assert_eq!(parse_memory_limit("512m"), 512 * 1024 * 1024);
+        assert_eq!(parse_memory_limit("1g"), 1024 * 1024 * 1024);
+        assert_eq!(parse_memory_limit("2048k"), 2048 * 1024);
+        assert_eq!(parse_memory_limit("1024"), 1024);
+    }
+}
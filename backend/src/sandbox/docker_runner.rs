@@ -1,7 +1,7 @@
 use super::*;
 use anyhow::{anyhow, Result};
 use std::process::Stdio;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::process::Command;
 use tokio::time::timeout;
 use tracing::{debug, warn};
@@ -100,6 +100,21 @@ impl DockerRunner {
         request: &ExecutionRequest,
         config: &SandboxConfig,
         host_dir: &PathBuf,
+    ) -> Result<ExecutionResult> {
+        self.execute_with_mount_cgrouped(request, config, host_dir, None).await
+    }
+
+    /// Same as [`execute_with_mount`](Self::execute_with_mount), but when `cgroup` is given, the
+    /// *container's* real init process (resolved via [`resolve_container_pid`], not the host-side
+    /// `docker` CLI process spawned here) is added to it once the container has started, and the
+    /// resulting `ExecutionResult::memory_used` is read back from the cgroup after the process
+    /// exits.
+    pub async fn execute_with_mount_cgrouped(
+        &self,
+        request: &ExecutionRequest,
+        config: &SandboxConfig,
+        host_dir: &PathBuf,
+        cgroup: Option<&CgroupGuard>,
     ) -> Result<ExecutionResult> {
         let container_name = format!("{}_{}", self.container_prefix, Uuid::new_v4());
         let start_time = Instant::now();
@@ -139,14 +154,35 @@ impl DockerRunner {
 
         debug!("Running Docker command with mount: {:?}", docker_cmd);
 
-        // Execute with timeout
-        let execution_future = docker_cmd
+        let child = docker_cmd
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .spawn()?
-            .wait_with_output();
+            .spawn()?;
+
+        // The `docker` CLI process we just spawned only hands the run request to
+        // dockerd/containerd and exits almost immediately -- the workload actually executes
+        // inside the container under its own, separate process. Resolve that real PID and add
+        // *it* to the cgroup, concurrently with waiting for the command to finish, so the limits
+        // take effect on the process that's actually consuming the resources.
+        let add_to_cgroup = async {
+            let Some(cgroup) = cgroup else { return };
+
+            match resolve_container_pid(&container_name).await {
+                Some(pid) => {
+                    if let Err(e) = cgroup.add_process(pid) {
+                        warn!("Failed to add container {} (pid {}) to cgroup: {}", container_name, pid, e);
+                    }
+                }
+                None => warn!(
+                    "Could not resolve a PID for container {}; its cgroup limits will not be enforced",
+                    container_name
+                ),
+            }
+        };
 
-        let output = match timeout(config.timeout, execution_future).await {
+        let (wait_result, _) = tokio::join!(timeout(config.timeout, child.wait_with_output()), add_to_cgroup);
+
+        let output = match wait_result {
             Ok(Ok(output)) => output,
             Ok(Err(e)) => return Err(anyhow!("Docker execution failed: {}", e)),
             Err(_) => {
@@ -169,7 +205,7 @@ impl DockerRunner {
             stdout,
             stderr,
             execution_time,
-            memory_used: None,
+            memory_used: cgroup.and_then(|c| c.memory_usage_bytes()),
             coverage: None,
             artifacts,
         })
@@ -262,4 +298,29 @@ impl DockerRunner {
             Err(anyhow!("Failed to pull image {}: {}", self.image, stderr))
         }
     }
+}
+
+/// Resolves the host PID of a running container's init process via `docker inspect`, retrying
+/// briefly since the container may not exist yet immediately after `docker run` spawns the CLI
+/// wrapper process that creates it.
+pub async fn resolve_container_pid(container_name: &str) -> Option<u32> {
+    for _ in 0..20 {
+        let output = Command::new("docker")
+            .args(&["inspect", "--format", "{{.State.Pid}}", container_name])
+            .output()
+            .await
+            .ok()?;
+
+        if output.status.success() {
+            if let Ok(pid) = String::from_utf8_lossy(&output.stdout).trim().parse::<u32>() {
+                if pid != 0 {
+                    return Some(pid);
+                }
+            }
+        }
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    None
 }
\ No newline at end of file
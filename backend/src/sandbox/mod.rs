@@ -4,6 +4,7 @@ pub mod rust;
 pub mod go;
 pub mod rust_runner;
 pub mod docker_runner;
+pub mod cgroup_guard;
 
 pub use python::*;
 pub use node::*;
@@ -11,6 +12,7 @@ pub use rust::*;
 pub use go::*;
 pub use rust_runner::*;
 pub use docker_runner::*;
+pub use cgroup_guard::*;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -10,8 +10,8 @@ use uuid::Uuid;
 
 use crate::observability::get_metrics;
 use super::{
-    Artifact, ArtifactType, CoverageReport, ExecutionRequest, ExecutionResult, 
-    FileCoverage, SandboxConfig, SandboxRunner
+    resolve_container_pid, Artifact, ArtifactType, CgroupGuard, CoverageReport, ExecutionRequest,
+    ExecutionResult, FileCoverage, SandboxConfig, SandboxRunner
 };
 
 pub struct PythonSandboxRunner {
@@ -161,10 +161,13 @@ COPY . .
         
         debug!("Built Docker image: {}", image_tag);
         
+        let container_name = format!("uaida_py_{}", Uuid::new_v4());
+
         // Prepare Docker run command with security and resource limits
         let mut docker_args = vec![
             "run",
             "--rm",
+            "--name", &container_name,
             "--network=none", // No network access by default
             "--user=1000:1000", // Non-root user
             "--read-only", // Read-only filesystem
@@ -175,24 +178,60 @@ COPY . .
             "--ulimit", "fsize=10485760:10485760", // Limit file size to 10MB
             "--security-opt=no-new-privileges", // Security
         ];
-        
+
         // Add timeout (Docker doesn't have built-in timeout)
         let timeout_seconds = config.timeout.as_secs();
         docker_args.extend(&["--stop-timeout", &timeout_seconds.to_string()]);
-        
+
         // Add the image and command
         docker_args.push(&image_tag);
         docker_args.extend(command);
-        
+
         debug!("Running Docker container with command: {:?}", docker_args);
-        
+
+        let cgroup = match CgroupGuard::new(&image_tag, &config.memory_limit, config.cpu_limit) {
+            Ok(guard) => Some(guard),
+            Err(e) => {
+                warn!("Failed to set up cgroup for Python execution: {}", e);
+                None
+            }
+        };
+
         // Execute with timeout
-        let execution_future = AsyncCommand::new("docker")
+        let child = AsyncCommand::new("docker")
             .args(&docker_args)
             .current_dir(execution_dir)
-            .output();
-        
-        let output = match tokio::time::timeout(config.timeout, execution_future).await {
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()?;
+
+        // The `docker` CLI process above only hands the run request to dockerd/containerd and
+        // exits almost immediately -- the workload actually executes inside the container under
+        // its own process. Resolve that real PID and add it to the cgroup (concurrently with
+        // waiting for the container to finish) so the limits take effect on the process that's
+        // actually consuming the resources, rather than the host-side wrapper.
+        let add_to_cgroup = async {
+            let Some(cgroup) = &cgroup else { return };
+
+            match resolve_container_pid(&container_name).await {
+                Some(pid) => {
+                    if let Err(e) = cgroup.add_process(pid) {
+                        warn!("Failed to add container {} (pid {}) to cgroup: {}", container_name, pid, e);
+                    }
+                }
+                None => warn!(
+                    "Could not resolve a PID for container {}; its cgroup limits will not be enforced",
+                    container_name
+                ),
+            }
+        };
+
+        let (wait_result, _) = tokio::join!(
+            tokio::time::timeout(config.timeout, child.wait_with_output()),
+            add_to_cgroup
+        );
+
+        let output = match wait_result {
             Ok(Ok(output)) => output,
             Ok(Err(e)) => {
                 error!("Docker execution failed: {}", e);
@@ -202,10 +241,10 @@ COPY . .
                 warn!("Docker execution timed out after {:?}", config.timeout);
                 // Kill the container
                 let _ = AsyncCommand::new("docker")
-                    .args(&["kill", &image_tag])
+                    .args(&["kill", &container_name])
                     .output()
                     .await;
-                
+
                 return Ok(ExecutionResult {
                     success: false,
                     exit_code: 124, // Timeout exit code
@@ -244,7 +283,7 @@ COPY . .
             stdout: String::from_utf8_lossy(&output.stdout).to_string(),
             stderr: String::from_utf8_lossy(&output.stderr).to_string(),
             execution_time,
-            memory_used: Some(self.extract_memory_usage(&container_id).await.unwrap_or(0)),
+            memory_used: cgroup.as_ref().and_then(|c| c.memory_usage_bytes()),
             coverage,
             artifacts,
         };
@@ -493,6 +532,32 @@ def test_fibonacci_negative():
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_memory_limit_kills_memory_hog() -> Result<()> {
+        let runner = PythonSandboxRunner::new();
+        let mut config = SandboxConfig::default();
+        config.memory_limit = "64m".to_string();
+
+        let request = ExecutionRequest {
+            code: r#"
+# Allocate far more than the 64MB cgroup/container limit permits.
+hog = bytearray(512 * 1024 * 1024)
+print(len(hog))
+"#.to_string(),
+            language: "python".to_string(),
+            test_command: None,
+            files: HashMap::new(),
+            environment: HashMap::new(),
+            working_directory: None,
+        };
+
+        let result = runner.execute(&request, &config).await?;
+
+        assert!(!result.success, "memory hog should be killed before completing");
+
+        Ok(())
+    }
+
     #[test]
     fn test_requirements_generation() {
         let runner = PythonSandboxRunner::new();
@@ -306,7 +306,16 @@ impl SandboxRunner for RustRunner {
             working_directory: Some("/app".to_string()),
         };
 
-        let result = self.docker_runner.execute_with_mount(&docker_request, config, &temp_dir).await?;
+        let cgroup = match CgroupGuard::new(&format!("rust_{}", uuid::Uuid::new_v4()), &config.memory_limit, config.cpu_limit) {
+            Ok(guard) => Some(guard),
+            Err(e) => {
+                warn!("Failed to set up cgroup for Rust execution: {}", e);
+                None
+            }
+        };
+        let result = self.docker_runner
+            .execute_with_mount_cgrouped(&docker_request, config, &temp_dir, cgroup.as_ref())
+            .await?;
 
         // Cleanup
         if let Err(e) = tokio::fs::remove_dir_all(&temp_dir).await {
@@ -343,7 +352,16 @@ impl SandboxRunner for RustRunner {
                 working_directory: Some("/app".to_string()),
             };
 
-            self.docker_runner.execute_with_mount(&test_request, config, &temp_dir).await?
+            let cgroup = match CgroupGuard::new(&format!("rust_test_{}", uuid::Uuid::new_v4()), &config.memory_limit, config.cpu_limit) {
+                Ok(guard) => Some(guard),
+                Err(e) => {
+                    warn!("Failed to set up cgroup for Rust test execution: {}", e);
+                    None
+                }
+            };
+            self.docker_runner
+                .execute_with_mount_cgrouped(&test_request, config, &temp_dir, cgroup.as_ref())
+                .await?
         };
 
         // Cleanup
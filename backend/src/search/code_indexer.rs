@@ -3,10 +3,12 @@ use std::sync::Arc;
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 use tokio::fs;
+use tokio::io::AsyncReadExt;
 use tracing::{info, debug, warn, error};
 use uuid::Uuid;
 use chrono::Utc;
 use regex::Regex;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 
 use super::{
     CodeIndex, IndexedSymbol, SymbolInfo, SymbolType, Parameter, Visibility,
@@ -14,10 +16,30 @@ use super::{
 };
 use super::embedding_manager::EmbeddingManager;
 
+/// Default cap applied to `CodeIndexer::max_file_size_bytes`; override via
+/// `with_max_file_size_bytes`. Generated bundles and other oversized
+/// non-source files blow past this well before it matters for real code.
+const DEFAULT_MAX_FILE_SIZE_BYTES: u64 = 1_048_576;
+
+/// How many leading bytes `is_binary_file` inspects for a null byte, so
+/// classifying a large file doesn't require reading it in full.
+const BINARY_SNIFF_BYTES: usize = 8192;
+
 pub struct CodeIndexer {
     embedding_manager: Arc<EmbeddingManager>,
     language_parsers: HashMap<String, Box<dyn LanguageParser + Send + Sync>>,
     ignore_patterns: Vec<Regex>,
+    max_file_size_bytes: u64,
+}
+
+/// Files found under a workspace during discovery, plus a reason breakdown
+/// of everything discovery skipped. Directories excluded by `.gitignore`/
+/// `.ignore` or the hardcoded patterns aren't walked at all, so they're
+/// counted once as a single "gitignored" entry rather than per file.
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveredFiles {
+    pub files: Vec<String>,
+    pub ignored: HashMap<String, usize>,
 }
 
 #[async_trait::async_trait]
@@ -51,13 +73,22 @@ impl CodeIndexer {
             embedding_manager,
             language_parsers: HashMap::new(),
             ignore_patterns: Self::create_ignore_patterns(),
+            max_file_size_bytes: DEFAULT_MAX_FILE_SIZE_BYTES,
         };
-        
+
         // Language parser'ları kaydet
         indexer.register_parsers();
         indexer
     }
 
+    /// Overrides the default 1 MB cap applied to files considered during
+    /// discovery; files over the limit are skipped and counted under the
+    /// `"too_large"` reason in `DiscoveredFiles::ignored`.
+    pub fn with_max_file_size_bytes(mut self, max_file_size_bytes: u64) -> Self {
+        self.max_file_size_bytes = max_file_size_bytes;
+        self
+    }
+
     fn register_parsers(&mut self) {
         self.language_parsers.insert("rust".to_string(), Box::new(RustParser::new()));
         self.language_parsers.insert("javascript".to_string(), Box::new(JavaScriptParser::new()));
@@ -87,21 +118,28 @@ impl CodeIndexer {
         ]
     }
 
-    pub async fn index_workspace(&self, workspace_path: &str) -> Result<Vec<CodeIndex>> {
+    /// Returns the indexed files plus a reason breakdown of everything
+    /// discovery skipped (see [`DiscoveredFiles`]), so callers can surface
+    /// `files_ignored` alongside the usual added/updated/removed counts.
+    pub async fn index_workspace(&self, workspace_path: &str) -> Result<(Vec<CodeIndex>, HashMap<String, usize>)> {
         info!("Starting workspace indexing: {}", workspace_path);
-        
-        let files = self.discover_files(workspace_path).await?;
-        info!("Found {} files to index", files.len());
-        
+
+        let discovered = self.discover_files(workspace_path).await?;
+        info!(
+            "Found {} files to index ({} ignored)",
+            discovered.files.len(),
+            discovered.ignored.values().sum::<usize>()
+        );
+
         let mut indices = Vec::new();
         let mut processed = 0;
-        
-        for file_path in files {
+
+        for file_path in discovered.files {
             match self.index_file(&file_path).await {
                 Ok(index) => {
                     indices.push(index);
                     processed += 1;
-                    
+
                     if processed % 100 == 0 {
                         info!("Indexed {} files", processed);
                     }
@@ -111,9 +149,25 @@ impl CodeIndexer {
                 }
             }
         }
-        
+
         info!("Workspace indexing completed. Indexed {} files", processed);
-        Ok(indices)
+        Ok((indices, discovered.ignored))
+    }
+
+    /// Lists the indexable files under `workspace_path`, applying the same
+    /// `.gitignore`/size/binary filtering as `index_workspace`. Exposed so
+    /// callers like `SemanticSearchEngine::index_workspace_incremental` can
+    /// diff the current file set against what's already indexed without
+    /// re-embedding anything.
+    pub async fn discover_workspace_files(&self, workspace_path: &str) -> Result<DiscoveredFiles> {
+        self.discover_files(workspace_path).await
+    }
+
+    /// Computes the content hash `index_file` would produce for `file_path`,
+    /// without doing the rest of the (expensive) parsing and embedding work.
+    pub async fn content_hash(&self, file_path: &str) -> Result<String> {
+        let content = fs::read_to_string(file_path).await?;
+        Ok(self.calculate_content_hash(&content))
     }
 
     pub async fn index_file(&self, file_path: &str) -> Result<CodeIndex> {
@@ -143,7 +197,7 @@ impl CodeIndexer {
         };
         
         // Metadata oluştur
-        let metadata = self.create_metadata(&content, &language, &symbols);
+        let metadata = self.create_metadata(&content, &language, &symbols, &file_embedding_response.model_used);
         
         Ok(CodeIndex {
             id: Uuid::new_v4(),
@@ -159,7 +213,7 @@ impl CodeIndexer {
 
     async fn parse_symbols_with_embeddings(
         &self,
-        parser: &dyn LanguageParser,
+        parser: &(dyn LanguageParser + Send + Sync),
         content: &str,
         file_path: &str,
     ) -> Result<Vec<IndexedSymbol>> {
@@ -248,31 +302,58 @@ impl CodeIndexer {
         Ok(symbols)
     }
 
-    async fn discover_files(&self, workspace_path: &str) -> Result<Vec<String>> {
-        let mut files = Vec::new();
-        self.discover_files_recursive(Path::new(workspace_path), &mut files).await?;
-        Ok(files)
+    async fn discover_files(&self, workspace_path: &str) -> Result<DiscoveredFiles> {
+        let gitignore = load_gitignore(workspace_path);
+        let mut discovered = DiscoveredFiles::default();
+        self.discover_files_recursive(Path::new(workspace_path), &gitignore, &mut discovered).await?;
+        Ok(discovered)
     }
 
-    async fn discover_files_recursive(&self, dir: &Path, files: &mut Vec<String>) -> Result<()> {
+    async fn discover_files_recursive(
+        &self,
+        dir: &Path,
+        gitignore: &Gitignore,
+        discovered: &mut DiscoveredFiles,
+    ) -> Result<()> {
         let mut entries = fs::read_dir(dir).await?;
-        
+
         while let Some(entry) = entries.next_entry().await? {
             let path = entry.path();
-            
-            // Ignore patterns kontrolü
+
+            // Hardcoded patterns (node_modules, target, .git, ...) and
+            // `.gitignore`/`.ignore` matches alike skip the whole subtree
+            // without walking into it.
             let path_str = path.to_string_lossy();
-            if self.ignore_patterns.iter().any(|pattern| pattern.is_match(&path_str)) {
+            if self.ignore_patterns.iter().any(|pattern| pattern.is_match(&path_str))
+                || gitignore.matched(&path, path.is_dir()).is_ignore()
+            {
+                *discovered.ignored.entry("gitignored".to_string()).or_insert(0) += 1;
                 continue;
             }
-            
+
             if path.is_dir() {
-                self.discover_files_recursive(&path, files).await?;
-            } else if self.is_supported_file(&path) {
-                files.push(path.to_string_lossy().to_string());
+                self.discover_files_recursive(&path, gitignore, discovered).await?;
+                continue;
+            }
+
+            if !self.is_supported_file(&path) {
+                continue;
+            }
+
+            let metadata = entry.metadata().await?;
+            if metadata.len() > self.max_file_size_bytes {
+                *discovered.ignored.entry("too_large".to_string()).or_insert(0) += 1;
+                continue;
             }
+
+            if is_binary_file(&path).await? {
+                *discovered.ignored.entry("binary".to_string()).or_insert(0) += 1;
+                continue;
+            }
+
+            discovered.files.push(path.to_string_lossy().to_string());
         }
-        
+
         Ok(())
     }
 
@@ -343,7 +424,7 @@ impl CodeIndexer {
         format!("{:x}", hasher.finish())
     }
 
-    fn create_metadata(&self, content: &str, language: &str, symbols: &[IndexedSymbol]) -> IndexMetadata {
+    fn create_metadata(&self, content: &str, language: &str, symbols: &[IndexedSymbol], embedding_model: &str) -> IndexMetadata {
         let line_count = content.lines().count();
         let file_size = content.len() as u64;
         let symbol_count = symbols.len();
@@ -367,6 +448,7 @@ impl CodeIndexer {
             quality_score,
             tags,
             categories,
+            embedding_model: embedding_model.to_string(),
         }
     }
 
@@ -500,6 +582,38 @@ impl CodeIndexer {
     }
 }
 
+/// Builds the `.gitignore`/`.ignore` matcher for a workspace. Missing files
+/// are treated as empty rule sets (nothing extra ignored); a present but
+/// unparseable file only logs a warning, matching
+/// `workspace_watcher::load_gitignore`'s tolerance for partial parses.
+fn load_gitignore(workspace_path: &str) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(workspace_path);
+
+    for name in [".gitignore", ".ignore"] {
+        let candidate = Path::new(workspace_path).join(name);
+        if candidate.exists() {
+            if let Some(error) = builder.add(&candidate) {
+                warn!("Failed to fully parse {} for {}: {}", name, workspace_path, error);
+            }
+        }
+    }
+
+    builder.build().unwrap_or_else(|error| {
+        warn!("Failed to build ignore matcher for {}: {}", workspace_path, error);
+        Gitignore::empty()
+    })
+}
+
+/// Whether `path` looks binary, judged by the presence of a null byte in its
+/// first `BINARY_SNIFF_BYTES` — the same heuristic tools like `git` and
+/// `grep` use, cheap enough to run on every candidate file.
+async fn is_binary_file(path: &Path) -> Result<bool> {
+    let mut file = fs::File::open(path).await?;
+    let mut buffer = vec![0u8; BINARY_SNIFF_BYTES];
+    let bytes_read = file.read(&mut buffer).await?;
+    Ok(buffer[..bytes_read].contains(&0))
+}
+
 // Language-specific parsers (simplified implementations)
 pub struct RustParser;
 pub struct JavaScriptParser;
@@ -659,6 +773,416 @@ macro_rules! impl_basic_parser {
 impl_basic_parser!(JavaScriptParser, "javascript", vec!["js", "jsx"]);
 impl_basic_parser!(TypeScriptParser, "typescript", vec!["ts", "tsx"]);
 impl_basic_parser!(PythonParser, "python", vec!["py"]);
-impl_basic_parser!(JavaParser, "java", vec!["java"]);
-impl_basic_parser!(GoParser, "go", vec!["go"]);
-impl_basic_parser!(CppParser, "cpp", vec!["cpp", "cc", "cxx", "c", "h", "hpp"]);
\ No newline at end of file
+impl_basic_parser!(CppParser, "cpp", vec!["cpp", "cc", "cxx", "c", "h", "hpp"]);
+
+impl GoParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl LanguageParser for GoParser {
+    async fn parse_file(&self, content: &str, _file_path: &str) -> Result<Vec<ParsedSymbol>> {
+        let mut symbols = Vec::new();
+
+        let func_regex = Regex::new(r"^func\s+(?:\([^)]*\)\s*)?(\w+)\s*\((.*?)\)(?:\s*\(?([^{]*?)\)?)?\s*\{")?;
+        let type_regex = Regex::new(r"^type\s+(\w+)\s+(struct|interface)\b")?;
+
+        for (line_num, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+
+            if let Some(captures) = func_regex.captures(trimmed) {
+                let name = captures.get(1).unwrap().as_str().to_string();
+                let params_str = captures.get(2).map(|m| m.as_str()).unwrap_or("");
+                let return_type = captures.get(3).map(|m| m.as_str().trim().to_string()).filter(|s| !s.is_empty());
+                let visibility = self.go_visibility(&name);
+
+                symbols.push(ParsedSymbol {
+                    name,
+                    symbol_type: SymbolType::Function,
+                    line_start: line_num + 1,
+                    line_end: line_num + 1,
+                    content: trimmed.to_string(),
+                    signature: Some(trimmed.to_string()),
+                    documentation: None,
+                    parameters: self.parse_go_parameters(params_str),
+                    return_type,
+                    visibility,
+                    references: Vec::new(),
+                });
+            } else if let Some(captures) = type_regex.captures(trimmed) {
+                let name = captures.get(1).unwrap().as_str().to_string();
+                let symbol_type = match captures.get(2).unwrap().as_str() {
+                    "interface" => SymbolType::Interface,
+                    _ => SymbolType::Struct,
+                };
+                let visibility = self.go_visibility(&name);
+
+                symbols.push(ParsedSymbol {
+                    name,
+                    symbol_type,
+                    line_start: line_num + 1,
+                    line_end: line_num + 1,
+                    content: trimmed.to_string(),
+                    signature: Some(trimmed.to_string()),
+                    documentation: None,
+                    parameters: Vec::new(),
+                    return_type: None,
+                    visibility,
+                    references: Vec::new(),
+                });
+            }
+        }
+
+        Ok(symbols)
+    }
+
+    fn get_language(&self) -> &str {
+        "go"
+    }
+
+    fn get_file_extensions(&self) -> Vec<&str> {
+        vec!["go"]
+    }
+
+    fn extract_imports(&self, content: &str) -> Vec<String> {
+        let mut imports = Vec::new();
+
+        let block_regex = Regex::new(r"(?s)import\s*\(\s*(.*?)\s*\)").unwrap();
+        let path_regex = Regex::new(r#""([^"]+)""#).unwrap();
+
+        for block in block_regex.captures_iter(content) {
+            for path in path_regex.captures_iter(&block[1]) {
+                imports.push(path[1].to_string());
+            }
+        }
+
+        let single_regex = Regex::new(r#"(?m)^import\s+"([^"]+)""#).unwrap();
+        for cap in single_regex.captures_iter(content) {
+            imports.push(cap[1].to_string());
+        }
+
+        imports
+    }
+
+    fn extract_comments(&self, content: &str) -> Vec<String> {
+        let comment_regex = Regex::new(r"//(.*)").unwrap();
+        comment_regex.captures_iter(content)
+            .map(|cap| cap[1].trim().to_string())
+            .collect()
+    }
+
+    fn calculate_complexity(&self, content: &str) -> f32 {
+        let control_keywords = ["if", "else", "switch", "for", "select", "case"];
+        let mut complexity = 1.0;
+
+        for keyword in &control_keywords {
+            complexity += content.matches(keyword).count() as f32 * 0.5;
+        }
+
+        complexity.min(10.0)
+    }
+}
+
+impl GoParser {
+    /// Go has no `pub` keyword; exported identifiers are capitalized.
+    fn go_visibility(&self, name: &str) -> Visibility {
+        if name.chars().next().map(|c| c.is_uppercase()).unwrap_or(false) {
+            Visibility::Public
+        } else {
+            Visibility::Private
+        }
+    }
+
+    fn parse_go_parameters(&self, params_str: &str) -> Vec<Parameter> {
+        if params_str.trim().is_empty() {
+            return Vec::new();
+        }
+
+        params_str.split(',')
+            .map(|param| {
+                let parts: Vec<&str> = param.trim().split_whitespace().collect();
+                if parts.len() >= 2 {
+                    Parameter {
+                        name: parts[0].trim().to_string(),
+                        param_type: parts[1..].join(" "),
+                        default_value: None,
+                        description: None,
+                    }
+                } else {
+                    Parameter {
+                        name: param.trim().to_string(),
+                        param_type: "unknown".to_string(),
+                        default_value: None,
+                        description: None,
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+impl JavaParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl LanguageParser for JavaParser {
+    async fn parse_file(&self, content: &str, _file_path: &str) -> Result<Vec<ParsedSymbol>> {
+        let mut symbols = Vec::new();
+
+        let type_regex = Regex::new(
+            r"^(?:(public|private|protected)\s+)?(?:static\s+)?(?:final\s+)?(class|interface|enum)\s+(\w+)"
+        )?;
+        let method_regex = Regex::new(
+            r"^(?:(public|private|protected)\s+)?(?:static\s+)?(?:final\s+)?(?:[\w<>\[\],\s]+?)\s+(\w+)\s*\((.*?)\)\s*(?:throws\s+[\w,\s]+)?\s*\{"
+        )?;
+
+        for (line_num, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+
+            if let Some(captures) = type_regex.captures(trimmed) {
+                let name = captures.get(3).unwrap().as_str().to_string();
+                let symbol_type = match captures.get(2).unwrap().as_str() {
+                    "interface" => SymbolType::Interface,
+                    "enum" => SymbolType::Enum,
+                    _ => SymbolType::Class,
+                };
+                let visibility = self.java_visibility(captures.get(1).map(|m| m.as_str()));
+
+                symbols.push(ParsedSymbol {
+                    name,
+                    symbol_type,
+                    line_start: line_num + 1,
+                    line_end: line_num + 1,
+                    content: trimmed.to_string(),
+                    signature: Some(trimmed.to_string()),
+                    documentation: None,
+                    parameters: Vec::new(),
+                    return_type: None,
+                    visibility,
+                    references: Vec::new(),
+                });
+            } else if let Some(captures) = method_regex.captures(trimmed) {
+                let name = captures.get(2).unwrap().as_str().to_string();
+                // Constructors and control-flow keywords masquerade as methods
+                // under this regex; skip the obvious false positives.
+                if matches!(name.as_str(), "if" | "for" | "while" | "switch" | "catch") {
+                    continue;
+                }
+
+                let params_str = captures.get(3).map(|m| m.as_str()).unwrap_or("");
+                let visibility = self.java_visibility(captures.get(1).map(|m| m.as_str()));
+
+                symbols.push(ParsedSymbol {
+                    name,
+                    symbol_type: SymbolType::Method,
+                    line_start: line_num + 1,
+                    line_end: line_num + 1,
+                    content: trimmed.to_string(),
+                    signature: Some(trimmed.to_string()),
+                    documentation: None,
+                    parameters: self.parse_java_parameters(params_str),
+                    return_type: None,
+                    visibility,
+                    references: Vec::new(),
+                });
+            }
+        }
+
+        Ok(symbols)
+    }
+
+    fn get_language(&self) -> &str {
+        "java"
+    }
+
+    fn get_file_extensions(&self) -> Vec<&str> {
+        vec!["java"]
+    }
+
+    fn extract_imports(&self, content: &str) -> Vec<String> {
+        let import_regex = Regex::new(r"(?m)^import\s+(?:static\s+)?([\w.]+(?:\.\*)?)\s*;").unwrap();
+        import_regex.captures_iter(content)
+            .map(|cap| cap[1].to_string())
+            .collect()
+    }
+
+    fn extract_comments(&self, content: &str) -> Vec<String> {
+        let comment_regex = Regex::new(r"//(.*)").unwrap();
+        comment_regex.captures_iter(content)
+            .map(|cap| cap[1].trim().to_string())
+            .collect()
+    }
+
+    fn calculate_complexity(&self, content: &str) -> f32 {
+        let control_keywords = ["if", "else", "switch", "for", "while", "case", "catch"];
+        let mut complexity = 1.0;
+
+        for keyword in &control_keywords {
+            complexity += content.matches(keyword).count() as f32 * 0.5;
+        }
+
+        complexity.min(10.0)
+    }
+}
+
+impl JavaParser {
+    fn java_visibility(&self, modifier: Option<&str>) -> Visibility {
+        match modifier {
+            Some("public") => Visibility::Public,
+            Some("protected") => Visibility::Protected,
+            Some("private") => Visibility::Private,
+            _ => Visibility::Package,
+        }
+    }
+
+    fn parse_java_parameters(&self, params_str: &str) -> Vec<Parameter> {
+        if params_str.trim().is_empty() {
+            return Vec::new();
+        }
+
+        params_str.split(',')
+            .map(|param| {
+                let parts: Vec<&str> = param.trim().split_whitespace().collect();
+                if parts.len() >= 2 {
+                    Parameter {
+                        name: parts[parts.len() - 1].trim().to_string(),
+                        param_type: parts[..parts.len() - 1].join(" "),
+                        default_value: None,
+                        description: None,
+                    }
+                } else {
+                    Parameter {
+                        name: param.trim().to_string(),
+                        param_type: "unknown".to_string(),
+                        default_value: None,
+                        description: None,
+                    }
+                }
+            })
+            .collect()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(relative_path: &str) -> String {
+        std::fs::read_to_string(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(relative_path),
+        )
+        .unwrap()
+    }
+
+    async fn test_indexer() -> CodeIndexer {
+        let config = Arc::new(crate::config::Config::from_env().expect("Config::from_env should always succeed with defaults"));
+        let router = crate::providers::ProviderRouter::new(config)
+            .await
+            .expect("ProviderRouter::new should always succeed");
+        CodeIndexer::new(Arc::new(EmbeddingManager::new(Arc::new(router))))
+    }
+
+    /// Builds a throwaway workspace under the OS temp dir containing a
+    /// `.gitignore`-excluded directory, a binary file (one null byte among
+    /// otherwise plausible source text), and a plain source file, so
+    /// discovery can be tested against real filesystem behavior rather than
+    /// mocked `ignore`/`tokio::fs` internals.
+    async fn fixture_workspace() -> PathBuf {
+        let workspace = std::env::temp_dir().join(format!("code_indexer_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(workspace.join("vendor")).await.unwrap();
+
+        fs::write(workspace.join(".gitignore"), "vendor/\n").await.unwrap();
+        fs::write(workspace.join("vendor").join("lib.rs"), "fn vendored() {}").await.unwrap();
+        fs::write(workspace.join("main.rs"), "fn main() {}").await.unwrap();
+        fs::write(workspace.join("app.bin"), b"fn broken(\0) {}").await.unwrap();
+
+        workspace
+    }
+
+    #[tokio::test]
+    async fn discovery_skips_gitignored_and_binary_files() {
+        let indexer = test_indexer().await;
+        let workspace = fixture_workspace().await;
+
+        let discovered = indexer.discover_workspace_files(&workspace.to_string_lossy()).await.unwrap();
+        let _ = fs::remove_dir_all(&workspace).await;
+
+        assert_eq!(discovered.files.len(), 1);
+        assert!(discovered.files[0].ends_with("main.rs"));
+        assert_eq!(discovered.ignored.get("gitignored").copied(), Some(1));
+        assert_eq!(discovered.ignored.get("binary").copied(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn discovery_skips_files_over_the_configured_size_limit() {
+        let indexer = test_indexer().await.with_max_file_size_bytes(4);
+        let workspace = std::env::temp_dir().join(format!("code_indexer_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&workspace).await.unwrap();
+        fs::write(workspace.join("big.rs"), "fn main() {}").await.unwrap();
+
+        let discovered = indexer.discover_workspace_files(&workspace.to_string_lossy()).await.unwrap();
+        let _ = fs::remove_dir_all(&workspace).await;
+
+        assert!(discovered.files.is_empty());
+        assert_eq!(discovered.ignored.get("too_large").copied(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn extracts_go_symbols() {
+        let content = fixture("tests/fixtures/go_toy/main.go");
+        let parser = GoParser::new();
+
+        let symbols = parser.parse_file(&content, "main.go").await.unwrap();
+
+        let greeter = symbols.iter().find(|s| s.name == "Greeter").unwrap();
+        assert_eq!(greeter.symbol_type, SymbolType::Struct);
+        assert_eq!(greeter.visibility, Visibility::Public);
+
+        let speaker = symbols.iter().find(|s| s.name == "Speaker").unwrap();
+        assert_eq!(speaker.symbol_type, SymbolType::Interface);
+
+        let new_greeter = symbols.iter().find(|s| s.name == "NewGreeter").unwrap();
+        assert_eq!(new_greeter.symbol_type, SymbolType::Function);
+        assert_eq!(new_greeter.return_type.as_deref(), Some("*Greeter"));
+
+        let speak = symbols.iter().find(|s| s.name == "Speak").unwrap();
+        assert_eq!(speak.symbol_type, SymbolType::Function);
+
+        let imports = parser.extract_imports(&content);
+        assert!(imports.contains(&"fmt".to_string()));
+        assert!(imports.contains(&"os".to_string()));
+    }
+
+    #[tokio::test]
+    async fn extracts_java_symbols() {
+        let content = fixture("tests/fixtures/java_toy/Greeter.java");
+        let parser = JavaParser::new();
+
+        let symbols = parser.parse_file(&content, "Greeter.java").await.unwrap();
+
+        let speaker = symbols.iter().find(|s| s.name == "Speaker").unwrap();
+        assert_eq!(speaker.symbol_type, SymbolType::Interface);
+        assert_eq!(speaker.visibility, Visibility::Public);
+
+        let greeter = symbols.iter().find(|s| s.name == "Greeter").unwrap();
+        assert_eq!(greeter.symbol_type, SymbolType::Class);
+
+        let speak = symbols.iter().find(|s| s.name == "speak").unwrap();
+        assert_eq!(speak.symbol_type, SymbolType::Method);
+        assert_eq!(speak.visibility, Visibility::Public);
+
+        let history = symbols.iter().find(|s| s.name == "history").unwrap();
+        assert_eq!(history.visibility, Visibility::Private);
+
+        let mood = symbols.iter().find(|s| s.name == "Mood").unwrap();
+        assert_eq!(mood.symbol_type, SymbolType::Enum);
+
+        let imports = parser.extract_imports(&content);
+        assert!(imports.contains(&"java.util.List".to_string()));
+        assert!(imports.contains(&"java.util.ArrayList".to_string()));
+    }
+}
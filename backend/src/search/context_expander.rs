@@ -0,0 +1,204 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::debug;
+
+/// How long a file read is kept in `ContextExpander::file_cache` before the
+/// next `expand` call re-reads it from disk. Short enough that an edit made
+/// right after a search is picked up quickly, long enough to absorb a user
+/// expanding several results from the same file back to back.
+const FILE_CACHE_TTL: Duration = Duration::from_secs(30);
+
+struct CachedFile {
+    content: String,
+    expires_at: Instant,
+}
+
+/// A window of file content returned by [`ContextExpander::expand`], wider
+/// than the `content`/`surrounding_code` a `SearchResult` was built with
+/// (those are truncated to 500/200 chars to keep search responses small).
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ExpandedContext {
+    pub file_path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub content: String,
+}
+
+/// Re-reads a result's source file and returns a wider, syntax-aware window
+/// of lines around it, for `/search/result/:result_id/expand`. Separate
+/// from `SemanticSearchEngine`'s indexing/search path since this works
+/// against the live file on disk rather than an indexed snapshot.
+pub struct ContextExpander {
+    file_cache: Arc<RwLock<HashMap<String, CachedFile>>>,
+}
+
+impl ContextExpander {
+    pub fn new() -> Self {
+        Self {
+            file_cache: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Returns `Ok(None)` when `file_path` no longer exists (the file was
+    /// deleted since indexing), so the caller can surface a 404 instead of
+    /// an internal error.
+    pub async fn expand(
+        &self,
+        file_path: &str,
+        start_line: usize,
+        end_line: usize,
+        context_lines: usize,
+    ) -> Result<Option<ExpandedContext>> {
+        let content = match self.read_cached(file_path).await? {
+            Some(content) => content,
+            None => return Ok(None),
+        };
+
+        let lines: Vec<&str> = content.lines().collect();
+        if lines.is_empty() {
+            return Ok(Some(ExpandedContext {
+                file_path: file_path.to_string(),
+                start_line: 1,
+                end_line: 1,
+                content: String::new(),
+            }));
+        }
+
+        // `start_line`/`end_line` are 1-indexed and inclusive; clamp into
+        // `lines`' 0-indexed range before widening.
+        let requested_start = start_line.saturating_sub(1).min(lines.len() - 1);
+        let requested_end = end_line.saturating_sub(1).min(lines.len() - 1).max(requested_start);
+
+        let widened_start = requested_start.saturating_sub(context_lines);
+        let widened_end = (requested_end + context_lines).min(lines.len() - 1);
+
+        let (expand_start, expand_end) = widen_to_syntax_boundaries(&lines, widened_start, widened_end);
+
+        let window = lines[expand_start..=expand_end].join("\n");
+
+        Ok(Some(ExpandedContext {
+            file_path: file_path.to_string(),
+            start_line: expand_start + 1,
+            end_line: expand_end + 1,
+            content: window,
+        }))
+    }
+
+    async fn read_cached(&self, file_path: &str) -> Result<Option<String>> {
+        {
+            let cache = self.file_cache.read().await;
+            if let Some(cached) = cache.get(file_path) {
+                if cached.expires_at > Instant::now() {
+                    debug!("Context expander cache hit for: {}", file_path);
+                    return Ok(Some(cached.content.clone()));
+                }
+            }
+        }
+
+        match tokio::fs::read_to_string(file_path).await {
+            Ok(content) => {
+                self.file_cache.write().await.insert(file_path.to_string(), CachedFile {
+                    content: content.clone(),
+                    expires_at: Instant::now() + FILE_CACHE_TTL,
+                });
+                Ok(Some(content))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl Default for ContextExpander {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Widens `[start, end]` outward, one line at a time, until brace depth
+/// returns to zero at both ends — a cheap stand-in for a real parser that
+/// keeps the window from cutting a `{ ... }` block (typically a function or
+/// impl body) in half. Only counts `{`/`}` outside of string/char literals
+/// is not attempted; this is a heuristic, not a parser, so it can still
+/// misjudge content with unbalanced braces in comments or strings.
+fn widen_to_syntax_boundaries(lines: &[&str], start: usize, end: usize) -> (usize, usize) {
+    let mut start = start;
+    let mut end = end;
+
+    let depth_at = |from: usize, to: usize| -> i64 {
+        lines[from..=to].iter().fold(0i64, |depth, line| {
+            depth + line.matches('{').count() as i64 - line.matches('}').count() as i64
+        })
+    };
+
+    while start > 0 && depth_at(start, end) < 0 {
+        start -= 1;
+    }
+
+    while end < lines.len() - 1 && depth_at(start, end) > 0 {
+        end += 1;
+    }
+
+    (start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widens_window_to_close_an_open_brace() {
+        let lines = vec![
+            "fn example() {",
+            "    let x = 1;",
+            "    if x == 1 {",
+            "        println!(\"one\");",
+            "    }",
+            "    println!(\"{}\", x);",
+            "}",
+        ];
+
+        // A window that only covers the `if` block's opening line should
+        // widen to include its closing `}` at minimum.
+        let (start, end) = widen_to_syntax_boundaries(&lines, 2, 2);
+
+        assert!(end >= 4, "expected window to extend through the if block's closing brace, got end={}", end);
+    }
+
+    #[test]
+    fn leaves_already_balanced_window_untouched() {
+        let lines = vec!["fn example() {", "    let x = 1;", "}"];
+
+        let (start, end) = widen_to_syntax_boundaries(&lines, 0, 2);
+
+        assert_eq!((start, end), (0, 2));
+    }
+
+    #[tokio::test]
+    async fn returns_none_for_a_missing_file() {
+        let expander = ContextExpander::new();
+
+        let result = expander.expand("/nonexistent/path/does-not-exist.rs", 1, 1, 5).await.unwrap();
+
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn expands_around_the_requested_line_range() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("context_expander_test_{}.rs", std::process::id()));
+        tokio::fs::write(&path, "line1\nline2\nline3\nline4\nline5\n").await.unwrap();
+
+        let expander = ContextExpander::new();
+        let expanded = expander.expand(path.to_str().unwrap(), 3, 3, 1).await.unwrap().unwrap();
+
+        assert_eq!(expanded.start_line, 2);
+        assert_eq!(expanded.end_line, 4);
+        assert_eq!(expanded.content, "line2\nline3\nline4");
+
+        tokio::fs::remove_file(&path).await.ok();
+    }
+}
@@ -3,9 +3,8 @@ use std::sync::Arc;
 use std::collections::HashMap;
 use tokio::sync::RwLock;
 use tracing::{info, debug, warn, error};
-use serde_json;
 
-use crate::providers::{ProviderRouter, CompletionRequest};
+use crate::providers::ProviderRouter;
 use super::{
     EmbeddingRequest, EmbeddingResponse, EmbeddingType, SimilarityRequest, 
     SimilarityResponse, SimilarityMetric
@@ -25,6 +24,10 @@ struct CachedEmbedding {
     last_accessed: chrono::DateTime<chrono::Utc>,
 }
 
+/// Number of ANN candidates exactly re-ranked per `calculate_similarity`
+/// call when an index is used, regardless of workspace size.
+const ANN_CANDIDATE_POOL: usize = 50;
+
 #[derive(Debug, Clone)]
 pub struct EmbeddingModelConfig {
     pub code_model: String,
@@ -116,18 +119,13 @@ impl EmbeddingManager {
 
     pub async fn calculate_similarity(&self, request: SimilarityRequest) -> Result<SimilarityResponse> {
         let query_embedding = &request.query_embedding;
-        let mut scores = Vec::new();
-        
-        for candidate in &request.candidate_embeddings {
-            let score = match request.similarity_metric {
-                SimilarityMetric::Cosine => self.cosine_similarity(query_embedding, candidate),
-                SimilarityMetric::Euclidean => self.euclidean_distance(query_embedding, candidate),
-                SimilarityMetric::DotProduct => self.dot_product(query_embedding, candidate),
-                SimilarityMetric::Manhattan => self.manhattan_distance(query_embedding, candidate),
-                SimilarityMetric::Jaccard => self.jaccard_similarity(query_embedding, candidate),
-            };
-            scores.push(score);
-        }
+
+        let scores = match (&request.ann_index, &request.similarity_metric) {
+            (Some(ann_index), SimilarityMetric::Cosine) if !ann_index.is_empty() => {
+                self.calculate_scores_via_ann(query_embedding, &request.candidate_embeddings, ann_index)
+            }
+            _ => self.calculate_scores_exact(query_embedding, &request.candidate_embeddings, &request.similarity_metric),
+        };
 
         // Skorlara göre sırala
         let mut indexed_scores: Vec<(usize, f32)> = scores
@@ -178,72 +176,13 @@ impl EmbeddingManager {
     async fn create_embedding(&self, request: &EmbeddingRequest) -> Result<Vec<f32>> {
         let model = self.get_model_for_type(&request.embedding_type);
         let processed_text = self.preprocess_text(&request.text, &request.embedding_type);
-        
-        // OpenAI embedding API kullan
-        let embedding_request = serde_json::json!({
-            "input": processed_text,
-            "model": model,
-            "encoding_format": "float"
-        });
 
-        // Provider router üzerinden embedding API'sini çağır
-        let response = self.call_embedding_api(&embedding_request).await?;
-        
-        // Response'dan embedding'i çıkar
-        self.parse_embedding_response(&response)
-    }
+        let mut embeddings = self.provider_router
+            .embed(vec![processed_text], Some(model))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to generate embedding: {}", e))?;
 
-    async fn call_embedding_api(&self, request: &serde_json::Value) -> Result<serde_json::Value> {
-        // Bu fonksiyon provider router'ı kullanarak embedding API'sini çağırır
-        // Şimdilik basit bir implementasyon yapıyoruz
-        
-        let prompt = format!(
-            "Generate a semantic embedding for this text: {}",
-            request["input"].as_str().unwrap_or("")
-        );
-
-        let completion_request = CompletionRequest {
-            prompt,
-            model: Some("gpt-3.5-turbo".to_string()),
-            provider: Some("openai".to_string()),
-            max_tokens: Some(10),
-            temperature: Some(0.0),
-            system_prompt: Some("Return only a JSON array of 1536 floating point numbers representing the embedding.".to_string()),
-            ..Default::default()
-        };
-
-        let response = self.provider_router.complete(completion_request).await?;
-        
-        // Gerçek implementasyonda burada OpenAI embedding API'si çağrılacak
-        // Şimdilik mock embedding döndürüyoruz
-        Ok(serde_json::json!({
-            "data": [{
-                "embedding": self.generate_mock_embedding(self.model_config.dimension)
-            }]
-        }))
-    }
-
-    fn parse_embedding_response(&self, response: &serde_json::Value) -> Result<Vec<f32>> {
-        if let Some(data) = response["data"].as_array() {
-            if let Some(first_item) = data.first() {
-                if let Some(embedding_array) = first_item["embedding"].as_array() {
-                    let embedding: Result<Vec<f32>, _> = embedding_array
-                        .iter()
-                        .map(|v| v.as_f64().map(|f| f as f32).ok_or_else(|| anyhow::anyhow!("Invalid embedding value")))
-                        .collect();
-                    return embedding;
-                }
-            }
-        }
-        
-        Err(anyhow::anyhow!("Invalid embedding response format"))
-    }
-
-    fn generate_mock_embedding(&self, dimension: usize) -> Vec<f32> {
-        // Mock embedding oluştur (gerçek implementasyonda kaldırılacak)
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        (0..dimension).map(|_| rng.gen_range(-1.0..1.0)).collect()
+        embeddings.pop().ok_or_else(|| anyhow::anyhow!("Provider returned no embeddings"))
     }
 
     fn preprocess_text(&self, text: &str, embedding_type: &EmbeddingType) -> String {
@@ -393,6 +332,51 @@ impl EmbeddingManager {
         });
     }
 
+    /// Exact brute-force scoring over every candidate, same as before the
+    /// ANN index existed. Used when no index is available for this call, or
+    /// when the caller explicitly asked for an exact search.
+    fn calculate_scores_exact(
+        &self,
+        query_embedding: &[f32],
+        candidate_embeddings: &[Vec<f32>],
+        similarity_metric: &SimilarityMetric,
+    ) -> Vec<f32> {
+        candidate_embeddings
+            .iter()
+            .map(|candidate| match similarity_metric {
+                SimilarityMetric::Cosine => self.cosine_similarity(query_embedding, candidate),
+                SimilarityMetric::Euclidean => self.euclidean_distance(query_embedding, candidate),
+                SimilarityMetric::DotProduct => self.dot_product(query_embedding, candidate),
+                SimilarityMetric::Manhattan => self.manhattan_distance(query_embedding, candidate),
+                SimilarityMetric::Jaccard => self.jaccard_similarity(query_embedding, candidate),
+            })
+            .collect()
+    }
+
+    /// ANN-accelerated scoring: ask the index for its approximate nearest
+    /// neighbours, then exactly re-score only those candidates. Candidates
+    /// the index didn't surface are left at `f32::NEG_INFINITY`, which never
+    /// clears a real threshold and always sorts last for cosine similarity.
+    fn calculate_scores_via_ann(
+        &self,
+        query_embedding: &[f32],
+        candidate_embeddings: &[Vec<f32>],
+        ann_index: &super::vector_index::VectorIndex,
+    ) -> Vec<f32> {
+        // Cap the candidate pool pulled from the index: this is the knob
+        // that trades recall for the O(N) scan the index exists to avoid.
+        let top_k = candidate_embeddings.len().min(ann_index.len()).min(ANN_CANDIDATE_POOL);
+        let neighbours = ann_index.search(query_embedding, top_k);
+
+        let mut scores = vec![f32::NEG_INFINITY; candidate_embeddings.len()];
+        for (candidate_id, _approx_similarity) in neighbours {
+            if let Some(candidate) = candidate_embeddings.get(candidate_id) {
+                scores[candidate_id] = self.cosine_similarity(query_embedding, candidate);
+            }
+        }
+        scores
+    }
+
     // Similarity calculation methods
     fn cosine_similarity(&self, a: &[f32], b: &[f32]) -> f32 {
         if a.len() != b.len() {
@@ -516,4 +500,89 @@ impl Default for EmbeddingModelConfig {
             cache_ttl_hours: 24,
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    async fn test_manager() -> EmbeddingManager {
+        let config = Arc::new(Config::from_env().expect("Config::from_env should always succeed with defaults"));
+        let router = ProviderRouter::new(config).await.expect("ProviderRouter::new should always succeed");
+        EmbeddingManager::new(Arc::new(router))
+    }
+
+    fn request(metric: SimilarityMetric, candidates: Vec<Vec<f32>>) -> SimilarityRequest {
+        SimilarityRequest {
+            query_embedding: vec![1.0, 0.0, 0.0],
+            candidate_embeddings: candidates,
+            similarity_metric: metric,
+            threshold: None,
+            ann_index: None,
+        }
+    }
+
+    fn candidates() -> Vec<Vec<f32>> {
+        vec![
+            vec![1.0, 0.0, 0.0],  // identical to the query
+            vec![0.0, 1.0, 0.0],  // orthogonal
+            vec![-1.0, 0.0, 0.0], // opposite
+        ]
+    }
+
+    #[tokio::test]
+    async fn cosine_ranks_identical_vector_first() {
+        let manager = test_manager().await;
+        let response = manager.calculate_similarity(request(SimilarityMetric::Cosine, candidates())).await.unwrap();
+        assert_eq!(response.ranked_indices[0], 0);
+        assert_eq!(response.ranked_indices[2], 2);
+    }
+
+    #[tokio::test]
+    async fn dot_product_ranks_identical_vector_first() {
+        let manager = test_manager().await;
+        let response = manager.calculate_similarity(request(SimilarityMetric::DotProduct, candidates())).await.unwrap();
+        assert_eq!(response.ranked_indices[0], 0);
+        assert_eq!(response.ranked_indices[2], 2);
+    }
+
+    #[tokio::test]
+    async fn euclidean_ranks_identical_vector_first_despite_being_a_distance() {
+        let manager = test_manager().await;
+        let response = manager.calculate_similarity(request(SimilarityMetric::Euclidean, candidates())).await.unwrap();
+        // Euclidean distance is smallest (best) for the identical vector, even though the
+        // raw score scale is inverted relative to cosine/dot-product similarity.
+        assert_eq!(response.ranked_indices[0], 0);
+        assert_eq!(response.ranked_indices[2], 2);
+    }
+
+    #[tokio::test]
+    async fn manhattan_ranks_identical_vector_first() {
+        let manager = test_manager().await;
+        let response = manager.calculate_similarity(request(SimilarityMetric::Manhattan, candidates())).await.unwrap();
+        assert_eq!(response.ranked_indices[0], 0);
+        assert_eq!(response.ranked_indices[2], 2);
+    }
+
+    #[tokio::test]
+    async fn threshold_direction_matches_metric_scale() {
+        let manager = test_manager().await;
+
+        // Cosine: above_threshold keeps scores >= threshold.
+        let cosine_response = manager.calculate_similarity(SimilarityRequest {
+            threshold: Some(0.5),
+            ..request(SimilarityMetric::Cosine, candidates())
+        }).await.unwrap();
+        assert!(cosine_response.above_threshold.contains(&0));
+        assert!(!cosine_response.above_threshold.contains(&2));
+
+        // Euclidean: above_threshold keeps scores <= threshold (it's a distance).
+        let euclidean_response = manager.calculate_similarity(SimilarityRequest {
+            threshold: Some(0.5),
+            ..request(SimilarityMetric::Euclidean, candidates())
+        }).await.unwrap();
+        assert!(euclidean_response.above_threshold.contains(&0));
+        assert!(!euclidean_response.above_threshold.contains(&2));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use super::semantic_engine::ReindexStats;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexingStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexingJob {
+    pub job_id: Uuid,
+    pub workspace_path: String,
+    pub status: IndexingStatus,
+    pub files_indexed: usize,
+    pub files_total: usize,
+    pub started_at: DateTime<Utc>,
+    pub error: Option<String>,
+    /// Set once the job completes, reporting the added/updated/removed/
+    /// skipped breakdown for both full and incremental indexing modes.
+    pub reindex_stats: Option<ReindexStats>,
+}
+
+/// Tracks in-flight `POST /search/index` jobs so `GET /search/index/status`
+/// can report progress and `DELETE /search/index/jobs/:job_id` can cancel
+/// one, without either endpoint needing a handle to the background task
+/// itself. Cancellation tokens live in a separate map since they aren't
+/// `Serialize` and shouldn't leak into the `IndexingJob` API response.
+#[derive(Clone, Default)]
+pub struct IndexingJobRegistry {
+    jobs: Arc<DashMap<Uuid, IndexingJob>>,
+    cancellation_tokens: Arc<DashMap<Uuid, CancellationToken>>,
+}
+
+impl IndexingJobRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a new pending job and returns its id plus the token the
+    /// background task should poll for cancellation.
+    pub fn create_job(&self, workspace_path: String) -> (Uuid, CancellationToken) {
+        let job_id = Uuid::new_v4();
+        let token = CancellationToken::new();
+
+        self.jobs.insert(
+            job_id,
+            IndexingJob {
+                job_id,
+                workspace_path,
+                status: IndexingStatus::Pending,
+                files_indexed: 0,
+                files_total: 0,
+                started_at: Utc::now(),
+                error: None,
+                reindex_stats: None,
+            },
+        );
+        self.cancellation_tokens.insert(job_id, token.clone());
+
+        (job_id, token)
+    }
+
+    pub fn mark_running(&self, job_id: Uuid, files_total: usize) {
+        if let Some(mut job) = self.jobs.get_mut(&job_id) {
+            job.status = IndexingStatus::Running;
+            job.files_total = files_total;
+        }
+    }
+
+    pub fn update_progress(&self, job_id: Uuid, files_indexed: usize) {
+        if let Some(mut job) = self.jobs.get_mut(&job_id) {
+            job.files_indexed = files_indexed;
+        }
+    }
+
+    pub fn mark_completed(&self, job_id: Uuid, reindex_stats: ReindexStats) {
+        if let Some(mut job) = self.jobs.get_mut(&job_id) {
+            job.status = IndexingStatus::Completed;
+            job.reindex_stats = Some(reindex_stats);
+        }
+        self.cancellation_tokens.remove(&job_id);
+    }
+
+    pub fn mark_failed(&self, job_id: Uuid, error: String) {
+        if let Some(mut job) = self.jobs.get_mut(&job_id) {
+            job.status = IndexingStatus::Failed;
+            job.error = Some(error);
+        }
+        self.cancellation_tokens.remove(&job_id);
+    }
+
+    /// Signals the token for `job_id` and flips the job's status to
+    /// `Cancelled`. Returns `false` if the job doesn't exist or already
+    /// finished (its token was removed).
+    pub fn cancel(&self, job_id: Uuid) -> bool {
+        let Some(token) = self.cancellation_tokens.get(&job_id) else {
+            return false;
+        };
+        token.cancel();
+        drop(token);
+        self.cancellation_tokens.remove(&job_id);
+
+        if let Some(mut job) = self.jobs.get_mut(&job_id) {
+            job.status = IndexingStatus::Cancelled;
+        }
+
+        true
+    }
+
+    pub fn get(&self, job_id: Uuid) -> Option<IndexingJob> {
+        self.jobs.get(&job_id).map(|job| job.clone())
+    }
+
+    /// Returns the most recently started job for `workspace_path`, if any.
+    pub fn find_by_workspace(&self, workspace_path: &str) -> Option<IndexingJob> {
+        self.jobs
+            .iter()
+            .filter(|entry| entry.workspace_path == workspace_path)
+            .map(|entry| entry.clone())
+            .max_by_key(|job| job.started_at)
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{IndexedSymbol, ReferenceType, SymbolType};
+
+/// A single indexed symbol as a node in the cross-file relationship graph.
+/// Distinct from `IndexedSymbol` (which is scoped to one `CodeIndex`): `id`
+/// is deterministic, derived from `file_path` + `signature_hash`, so
+/// `SymbolEdge`s keep pointing at the same node across re-indexing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolNode {
+    pub id: Uuid,
+    pub name: String,
+    pub file_path: String,
+    pub symbol_type: SymbolType,
+    pub signature_hash: String,
+}
+
+/// Mirrors `symbol_nodes` columns selected by `find_transitive_callers`.
+/// `symbol_type` stays a `String` here (its `serde_json::to_string` text
+/// representation, same as how `record_symbol` stores it) and gets decoded
+/// into `SymbolType` when building the real `SymbolNode`.
+#[derive(Debug, FromRow)]
+struct SymbolNodeRow {
+    id: Uuid,
+    name: String,
+    file_path: String,
+    symbol_type: String,
+    signature_hash: String,
+}
+
+/// How `from_id` relates to `to_id` in a `SymbolEdge`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EdgeType {
+    Calls,
+    Imports,
+    Inherits,
+    Implements,
+    Overrides,
+}
+
+impl EdgeType {
+    /// Maps the reference kinds `code_indexer` attaches to a symbol onto the
+    /// edge type they imply, so `KnowledgeMesh::record_references` doesn't
+    /// need its own parallel classification.
+    fn from_reference_type(reference_type: &ReferenceType) -> Option<Self> {
+        match reference_type {
+            ReferenceType::Call => Some(EdgeType::Calls),
+            ReferenceType::Import => Some(EdgeType::Imports),
+            ReferenceType::Inheritance => Some(EdgeType::Inherits),
+            ReferenceType::Implementation => Some(EdgeType::Implements),
+            ReferenceType::Definition | ReferenceType::Usage => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolEdge {
+    pub from_id: Uuid,
+    pub to_id: Uuid,
+    pub edge_type: EdgeType,
+}
+
+/// Cross-file symbol relationship graph, persisted to `symbol_nodes` and
+/// `symbol_edges` (see migration 019) so questions like "who transitively
+/// calls this function" can be answered with one query instead of loading
+/// every indexed file into memory and re-deriving the call graph each time.
+///
+/// Node identity is deterministic (`Uuid::new_v5` over `file_path` +
+/// `signature_hash`), so re-indexing a file upserts the same node rather
+/// than creating a duplicate every time `save_indices_to_db` runs.
+pub struct KnowledgeMesh {
+    pool: Arc<PgPool>,
+}
+
+impl KnowledgeMesh {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    /// Deterministic node id for a symbol, so the same symbol maps to the
+    /// same node across re-indexing without a round trip to look it up.
+    fn node_id(file_path: &str, signature_hash: &str) -> Uuid {
+        Uuid::new_v5(
+            &Uuid::NAMESPACE_OID,
+            format!("{file_path}:{signature_hash}").as_bytes(),
+        )
+    }
+
+    /// Upserts `symbol` as a node and returns its id. Called once per
+    /// `IndexedSymbol` from `SemanticSearchEngine::save_indices_to_db`.
+    pub async fn record_symbol(&self, file_path: &str, symbol: &IndexedSymbol) -> Result<Uuid> {
+        let id = Self::node_id(file_path, &symbol.signature_hash);
+
+        sqlx::query(
+            r#"
+            INSERT INTO symbol_nodes (id, name, file_path, symbol_type, signature_hash)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (id) DO UPDATE SET
+                name = EXCLUDED.name,
+                symbol_type = EXCLUDED.symbol_type
+            "#,
+        )
+        .bind(id)
+        .bind(&symbol.name)
+        .bind(file_path)
+        .bind(serde_json::to_string(&symbol.symbol_type)?)
+        .bind(&symbol.signature_hash)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Records an edge from `from_id` to `to_id`, upserting both endpoints
+    /// first so this can't fail on a dangling reference.
+    pub async fn add_edge(&self, edge: &SymbolEdge) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO symbol_edges (from_id, to_id, edge_type)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (from_id, to_id, edge_type) DO NOTHING
+            "#,
+        )
+        .bind(edge.from_id)
+        .bind(edge.to_id)
+        .bind(serde_json::to_string(&edge.edge_type)?)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records edges for `symbol`'s `references` that resolve to a known
+    /// edge type (call/import/inheritance/implementation) and whose target
+    /// can be found by name among already-indexed nodes. `code_indexer`
+    /// currently leaves `references` empty for every symbol it produces, so
+    /// this is a no-op in practice until a parser starts populating them —
+    /// it exists so that work doesn't also need to touch the graph layer.
+    pub async fn record_references(&self, from_id: Uuid, symbol: &IndexedSymbol) -> Result<()> {
+        for reference in &symbol.references {
+            let Some(edge_type) = EdgeType::from_reference_type(&reference.reference_type) else {
+                continue;
+            };
+
+            let target = sqlx::query_as::<_, (Uuid,)>(
+                "SELECT id FROM symbol_nodes WHERE file_path = $1 AND id != $2 LIMIT 1",
+            )
+            .bind(&reference.file_path)
+            .bind(from_id)
+            .fetch_optional(&*self.pool)
+            .await?;
+
+            if let Some((target_id,)) = target {
+                self.add_edge(&SymbolEdge {
+                    from_id,
+                    to_id: target_id,
+                    edge_type,
+                })
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Breadth-first traversal (via a recursive CTE) of every node that
+    /// transitively calls `symbol_id`, i.e. all `from_id`s reachable by
+    /// walking `Calls` edges backwards from `symbol_id`.
+    pub async fn find_transitive_callers(&self, symbol_id: Uuid) -> Result<Vec<SymbolNode>> {
+        let edge_type = serde_json::to_string(&EdgeType::Calls)?;
+
+        let rows = sqlx::query_as::<_, SymbolNodeRow>(
+            r#"
+            WITH RECURSIVE callers AS (
+                SELECT from_id FROM symbol_edges
+                WHERE to_id = $1 AND edge_type = $2
+                UNION
+                SELECT e.from_id FROM symbol_edges e
+                INNER JOIN callers c ON e.to_id = c.from_id
+                WHERE e.edge_type = $2
+            )
+            SELECT n.id, n.name, n.file_path, n.symbol_type, n.signature_hash
+            FROM symbol_nodes n
+            INNER JOIN callers c ON n.id = c.from_id
+            "#,
+        )
+        .bind(symbol_id)
+        .bind(edge_type)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(SymbolNode {
+                    id: row.id,
+                    name: row.name,
+                    file_path: row.file_path,
+                    symbol_type: serde_json::from_str(&row.symbol_type)?,
+                    signature_hash: row.signature_hash,
+                })
+            })
+            .collect()
+    }
+
+    /// Looks a node up by its deterministic id without a DB round trip,
+    /// for callers (like `create_search_context`) that already have the
+    /// `file_path`/`signature_hash` pair from an `IndexedSymbol`.
+    pub fn node_id_for(file_path: &str, signature_hash: &str) -> Uuid {
+        Self::node_id(file_path, signature_hash)
+    }
+}
@@ -4,11 +4,18 @@ pub mod embedding_manager;
 pub mod query_processor;
 pub mod result_ranker;
 pub mod search_service;
+pub mod indexing_jobs;
+pub mod vector_index;
+pub mod context_expander;
+pub mod workspace_watcher;
+pub mod knowledge_mesh;
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use vector_index::VectorIndex;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchRequest {
@@ -20,6 +27,26 @@ pub struct SearchRequest {
     pub max_results: Option<usize>,
     pub similarity_threshold: Option<f32>,
     pub include_context: bool,
+    /// Skip the HNSW approximate-nearest-neighbour index and fall back to a
+    /// brute-force exact scan, even when an index is available for the
+    /// requested workspace. Used for correctness testing and for workspaces
+    /// small enough that an exact scan is cheap anyway.
+    #[serde(default)]
+    pub force_exact_search: bool,
+    /// Similarity metric to score candidates with; defaults to `Cosine` when
+    /// omitted. Choosing anything other than `Cosine` also disables the ANN
+    /// index for this request (see `SimilarityRequest::ann_index`), since the
+    /// index is only built for cosine-ranked neighbours.
+    #[serde(default)]
+    pub similarity_metric: Option<SimilarityMetric>,
+    /// Set by `SearchService::apply_user_preferences` before the request
+    /// reaches `SemanticSearchEngine::search`, so `build_ranking_context` can
+    /// look up the requester's preferences without threading a separate
+    /// parameter through every call in between. `None` for requests that
+    /// never go through `SearchService::search` (e.g. constructed directly
+    /// in tests), in which case ranking skips the personalized context.
+    #[serde(default)]
+    pub user_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,7 +102,7 @@ pub struct SymbolInfo {
     pub complexity_score: f32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SymbolType {
     Function,
     Method,
@@ -99,7 +126,7 @@ pub struct Parameter {
     pub description: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Visibility {
     Public,
     Private,
@@ -135,7 +162,7 @@ pub struct Highlight {
     pub explanation: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum HighlightType {
     ExactMatch,
     SemanticMatch,
@@ -196,6 +223,12 @@ pub struct IndexMetadata {
     pub quality_score: f32,
     pub tags: Vec<String>,
     pub categories: Vec<String>,
+    /// Name of the embedding model that produced `CodeIndex.embedding`, so a
+    /// later model change (e.g. switching `EmbeddingModelConfig::code_model`)
+    /// can be detected via `CodeIndex::embedding_model_mismatch` instead of
+    /// silently comparing vectors from two different embedding spaces.
+    #[serde(default)]
+    pub embedding_model: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -208,6 +241,18 @@ pub struct SearchResponse {
     pub related_queries: Vec<String>,
     pub filters_applied: Vec<String>,
     pub aggregations: SearchAggregations,
+    /// True when this response was served from `SemanticSearchEngine`'s
+    /// result cache instead of re-running the search pipeline. `search_time_ms`
+    /// still reflects the original (uncached) run that populated the entry.
+    #[serde(default)]
+    pub cached: bool,
+    /// Identifies this search for `SearchService::rerank_search_results` and
+    /// `provide_search_feedback`. Assigned by `SearchService::search`, which
+    /// is also the only place results are cached for later reranking — a
+    /// `SearchResponse` built any other way (e.g. in tests) gets the nil
+    /// UUID and can't be reranked.
+    #[serde(default)]
+    pub search_id: Uuid,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -281,14 +326,35 @@ pub struct SimilarityRequest {
     pub candidate_embeddings: Vec<Vec<f32>>,
     pub similarity_metric: SimilarityMetric,
     pub threshold: Option<f32>,
+    /// Pre-built ANN index whose candidate ids line up positionally with
+    /// `candidate_embeddings`. When present (and `similarity_metric` is
+    /// `Cosine`), `calculate_similarity` scores only the top ANN candidates
+    /// exactly instead of scanning every candidate.
+    #[serde(skip)]
+    pub ann_index: Option<Arc<VectorIndex>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SimilarityMetric {
+    /// Angle between vectors; scale-invariant, so it suits embeddings that
+    /// aren't L2-normalized as well as ones that are. The default, and the
+    /// only metric the ANN index accelerates.
     Cosine,
+    /// Straight-line distance. Only meaningful for embeddings that are
+    /// already normalized to a comparable scale — otherwise magnitude
+    /// differences between vectors dominate the score.
     Euclidean,
+    /// Raw dot product, with no normalization. Only comparable across
+    /// candidates when every embedding has (roughly) unit norm; on
+    /// unnormalized embeddings it conflates vector magnitude with
+    /// similarity, so prefer `Cosine` unless the embeddings are known to be
+    /// normalized.
     DotProduct,
+    /// Sum of absolute per-dimension differences. Like `Euclidean`, assumes
+    /// embeddings are on a comparable scale.
     Manhattan,
+    /// Overlap of active dimensions; most meaningful for sparse/binary-ish
+    /// embeddings rather than dense normalized ones.
     Jaccard,
 }
 
@@ -376,6 +442,10 @@ pub struct BoostTerm {
 // Search analytics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchAnalytics {
+    /// Same id as the originating `SearchResponse::search_id`, so feedback
+    /// (`provide_search_feedback`) and reranking (`rerank_search_results`)
+    /// both key off the one id a client already has from the search
+    /// response.
     pub query_id: Uuid,
     pub user_id: Uuid,
     pub query: String,
@@ -416,6 +486,9 @@ impl Default for SearchRequest {
             max_results: Some(50),
             similarity_threshold: Some(0.7),
             include_context: true,
+            force_exact_search: false,
+            similarity_metric: None,
+            user_id: None,
         }
     }
 }
@@ -449,4 +522,11 @@ impl CodeIndex {
     pub fn needs_reindexing(&self, current_hash: &str) -> bool {
         self.content_hash != current_hash
     }
+
+    /// Whether `self.embedding` was produced by a different embedding model
+    /// than `current_model`, meaning it lives in an incomparable vector space
+    /// and must be re-embedded rather than reused as-is.
+    pub fn embedding_model_mismatch(&self, current_model: &str) -> bool {
+        !self.metadata.embedding_model.is_empty() && self.metadata.embedding_model != current_model
+    }
 }
\ No newline at end of file
@@ -1,15 +1,44 @@
 use anyhow::Result;
+use regex::Regex;
 use std::collections::HashMap;
+use std::sync::RwLock;
 use tracing::{debug, info};
 
 use super::{
-    SearchResult, SearchRequest, ProcessedQuery, MatchType, QueryIntent, 
+    SearchResult, SearchRequest, ProcessedQuery, MatchType, QueryIntent,
     SymbolType, Highlight, HighlightType, SearchAggregations,
-    ComplexityBucket, TemporalBucket
+    ComplexityBucket, TemporalBucket, EntityType,
+    search_service::SearchFeedbackType,
 };
 
+/// Half-life for decaying per-(query, file) feedback weight in
+/// `ResultRanker::feedback_boost` — old feedback keeps influencing ranking,
+/// just with rapidly shrinking weight, rather than being dropped outright
+/// once a file's content has likely moved on.
+const FEEDBACK_DECAY_HALF_LIFE_DAYS: f64 = 14.0;
+
+struct FeedbackAggregate {
+    weighted_score: f32,
+    last_updated: chrono::DateTime<chrono::Utc>,
+}
+
+fn normalize_query_pattern(query: &str) -> String {
+    query.trim().to_lowercase()
+}
+
+fn decay(weighted_score: f32, last_updated: chrono::DateTime<chrono::Utc>, now: chrono::DateTime<chrono::Utc>) -> f32 {
+    let age_days = now.signed_duration_since(last_updated).num_seconds().max(0) as f64 / 86400.0;
+    (weighted_score as f64 * 0.5f64.powf(age_days / FEEDBACK_DECAY_HALF_LIFE_DAYS)) as f32
+}
+
 pub struct ResultRanker {
     ranking_weights: RankingWeights,
+    /// Per-(normalized query, file_path) feedback weight, fed by
+    /// `SearchService::provide_search_feedback` and read back in
+    /// `calculate_final_score` so a file users marked `Perfect`/`Helpful`
+    /// for a query ranks higher the next time that query (or an identical
+    /// re-phrasing) comes in, while `Irrelevant` demotes it.
+    feedback_aggregates: RwLock<HashMap<(String, String), FeedbackAggregate>>,
 }
 
 #[derive(Debug, Clone)]
@@ -68,24 +97,61 @@ impl ResultRanker {
     pub fn new() -> Self {
         Self {
             ranking_weights: RankingWeights::default(),
+            feedback_aggregates: RwLock::new(HashMap::new()),
         }
     }
 
     pub fn with_weights(weights: RankingWeights) -> Self {
         Self {
             ranking_weights: weights,
+            feedback_aggregates: RwLock::new(HashMap::new()),
         }
     }
 
+    /// Records feedback on `file_path` for `query`, decaying and folding it
+    /// into the running weight for that (query, file) pair.
+    pub fn record_feedback(&self, query: &str, file_path: &str, feedback_type: SearchFeedbackType) {
+        let weight = match feedback_type {
+            SearchFeedbackType::Perfect => 2.0,
+            SearchFeedbackType::Helpful => 1.0,
+            SearchFeedbackType::NotHelpful => -1.0,
+            SearchFeedbackType::Irrelevant => -2.0,
+        };
+
+        let key = (normalize_query_pattern(query), file_path.to_string());
+        let now = chrono::Utc::now();
+        let mut aggregates = self.feedback_aggregates.write().unwrap();
+        let aggregate = aggregates.entry(key).or_insert_with(|| FeedbackAggregate {
+            weighted_score: 0.0,
+            last_updated: now,
+        });
+        aggregate.weighted_score = decay(aggregate.weighted_score, aggregate.last_updated, now) + weight;
+        aggregate.last_updated = now;
+    }
+
+    fn feedback_boost(&self, query: &str, file_path: &str) -> f32 {
+        let key = (normalize_query_pattern(query), file_path.to_string());
+        let aggregates = self.feedback_aggregates.read().unwrap();
+        aggregates
+            .get(&key)
+            .map(|aggregate| decay(aggregate.weighted_score, aggregate.last_updated, chrono::Utc::now()))
+            .unwrap_or(0.0)
+    }
+
     pub fn rank_results(
         &self,
-        mut results: Vec<SearchResult>,
+        results: Vec<SearchResult>,
         request: &SearchRequest,
         processed_query: &ProcessedQuery,
         context: Option<&RankingContext>,
     ) -> Result<Vec<SearchResult>> {
         info!("Ranking {} search results", results.len());
 
+        // Overlapping/monorepo-nested `workspace_paths` can index and return
+        // the same file twice; collapse those before scoring so diversity
+        // filtering and aggregations see each file at most once.
+        let mut results = deduplicate_by_canonical_path(results);
+
         // Her result için final score hesapla
         for result in &mut results {
             result.relevance_score = self.calculate_final_score(
@@ -170,6 +236,10 @@ impl ResultRanker {
         let recency_boost = self.calculate_recency_boost(result, context);
         score *= recency_boost * self.ranking_weights.file_recency;
 
+        // Per-(query, file) feedback boost/demotion from prior user feedback
+        let feedback_boost = self.feedback_boost(&request.query, &result.file_path);
+        score *= (1.0 + feedback_boost * 0.15).clamp(0.1, 3.0);
+
         // Language filter boost
         if !request.language_filters.is_empty() {
             if request.language_filters.contains(&result.language) {
@@ -280,56 +350,81 @@ impl ResultRanker {
 
     fn generate_highlights(&self, result: &SearchResult, processed_query: &ProcessedQuery) -> Result<Vec<Highlight>> {
         let mut highlights = Vec::new();
-        let content_lower = result.content.to_lowercase();
-        
-        // Exact keyword matches
+
+        // Exact keyword matches (every occurrence)
         for keyword in &processed_query.keywords {
-            let keyword_lower = keyword.to_lowercase();
-            let mut start = 0;
-            
-            while let Some(pos) = content_lower[start..].find(&keyword_lower) {
-                let actual_pos = start + pos;
-                highlights.push(Highlight {
-                    start_offset: actual_pos,
-                    end_offset: actual_pos + keyword.len(),
-                    highlight_type: HighlightType::KeywordMatch,
-                    explanation: Some(format!("Keyword match: {}", keyword)),
-                });
-                start = actual_pos + keyword.len();
-            }
+            highlights.extend(self.find_highlights(
+                &result.content,
+                keyword,
+                HighlightType::ExactMatch,
+                format!("Keyword match: {}", keyword),
+            )?);
         }
-        
-        // Entity matches
+
+        // Entity matches - symbol-shaped entities (functions, classes,
+        // variables) get SymbolMatch so clients can style them distinctly
+        // from a plain keyword hit.
         for entity in &processed_query.entities {
-            let entity_lower = entity.text.to_lowercase();
-            if let Some(pos) = content_lower.find(&entity_lower) {
-                highlights.push(Highlight {
-                    start_offset: pos,
-                    end_offset: pos + entity.text.len(),
-                    highlight_type: HighlightType::SymbolMatch,
-                    explanation: Some(format!("Entity match: {:?}", entity.entity_type)),
-                });
-            }
+            let highlight_type = match entity.entity_type {
+                EntityType::FunctionName | EntityType::ClassName | EntityType::VariableName => {
+                    HighlightType::SymbolMatch
+                }
+                _ => HighlightType::ExactMatch,
+            };
+            highlights.extend(self.find_highlights(
+                &result.content,
+                &entity.text,
+                highlight_type,
+                format!("Entity match: {:?}", entity.entity_type),
+            )?);
         }
-        
+
         // Boost term matches
         for boost_term in &processed_query.boost_terms {
-            let term_lower = boost_term.term.to_lowercase();
-            if let Some(pos) = content_lower.find(&term_lower) {
-                highlights.push(Highlight {
-                    start_offset: pos,
-                    end_offset: pos + boost_term.term.len(),
-                    highlight_type: HighlightType::SemanticMatch,
-                    explanation: Some(format!("Boost term: {}", boost_term.reason)),
-                });
-            }
+            highlights.extend(self.find_highlights(
+                &result.content,
+                &boost_term.term,
+                HighlightType::SemanticMatch,
+                format!("Boost term: {}", boost_term.reason),
+            )?);
         }
-        
+
         // Remove overlapping highlights
         highlights.sort_by_key(|h| h.start_offset);
         self.remove_overlapping_highlights(highlights)
     }
 
+    /// Finds every case-insensitive occurrence of `needle` in `content` and
+    /// returns a `Highlight` per match. Matching is done directly on
+    /// `content` (via `regex`'s Unicode-aware case folding) rather than on a
+    /// separately-lowercased copy, so offsets always land on `content`'s own
+    /// char boundaries even when lowercasing a character changes its byte
+    /// length (e.g. `İ`).
+    fn find_highlights(
+        &self,
+        content: &str,
+        needle: &str,
+        highlight_type: HighlightType,
+        explanation: String,
+    ) -> Result<Vec<Highlight>> {
+        if needle.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let pattern = format!(r"(?i){}", regex::escape(needle));
+        let re = Regex::new(&pattern)?;
+
+        Ok(re
+            .find_iter(content)
+            .map(|m| Highlight {
+                start_offset: m.start(),
+                end_offset: m.end(),
+                highlight_type: highlight_type.clone(),
+                explanation: Some(explanation.clone()),
+            })
+            .collect())
+    }
+
     fn remove_overlapping_highlights(&self, mut highlights: Vec<Highlight>) -> Result<Vec<Highlight>> {
         highlights.sort_by_key(|h| (h.start_offset, h.end_offset));
         let mut result = Vec::new();
@@ -393,13 +488,8 @@ impl ResultRanker {
                 .to_string();
             *file_types.entry(file_extension).or_insert(0) += 1;
             
-            // Project aggregation (from file path)
-            let project_name = result.file_path
-                .split('/')
-                .nth(1)
-                .unwrap_or("unknown")
-                .to_string();
-            *projects.entry(project_name).or_insert(0) += 1;
+            // Project aggregation
+            *projects.entry(result.context.project_context.project_name.clone()).or_insert(0) += 1;
             
             // Symbol type aggregation
             if let Some(symbol_info) = &result.symbol_info {
@@ -522,6 +612,147 @@ impl ResultRanker {
     }
 }
 
+/// Collapses results that resolve to the same file on disk — e.g. when
+/// `workspace_paths` contains overlapping or monorepo-nested directories and
+/// the same file gets indexed and returned once per matching root — keeping
+/// whichever copy has the highest `relevance_score`. Paths that don't exist
+/// on disk (already-deleted files surfaced from a stale index) fall back to
+/// their raw `file_path` string as the dedup key.
+fn deduplicate_by_canonical_path(results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let mut best: HashMap<std::path::PathBuf, SearchResult> = HashMap::new();
+
+    for result in results {
+        let canonical = std::fs::canonicalize(&result.file_path)
+            .unwrap_or_else(|_| std::path::PathBuf::from(&result.file_path));
+
+        match best.get(&canonical) {
+            Some(existing) if existing.relevance_score >= result.relevance_score => {}
+            _ => {
+                best.insert(canonical, result);
+            }
+        }
+    }
+
+    best.into_values().collect()
+}
+
+/// Re-scores a prior search's results with a cross-encoder model.
+///
+/// `SemanticSearchEngine`/`EmbeddingManager` rank candidates with a
+/// bi-encoder: query and candidate are embedded independently, so
+/// similarity is a single vector comparison and cheap enough to run over an
+/// entire index. A cross-encoder trades that speed for precision by
+/// encoding the `(query, candidate)` pair jointly, so it only makes sense as
+/// a second-stage reranker over the small result set a bi-encoder search
+/// already narrowed down — this struct never sees the full index.
+pub struct CrossEncoderReranker {
+    provider_router: std::sync::Arc<crate::providers::ProviderRouter>,
+    config: CrossEncoderConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct CrossEncoderConfig {
+    /// Model id passed to `CompletionRequest::with_model` — an Ollama tag
+    /// (e.g. `"bge-reranker-v2-m3"`) for a local cross-encoder, or a hosted
+    /// provider's model id.
+    pub model: String,
+    /// Result content is truncated to this many characters before scoring,
+    /// since a cross-encoder prompt pays for the full candidate on every
+    /// pairwise call rather than once at index time.
+    pub max_content_chars: usize,
+}
+
+impl Default for CrossEncoderConfig {
+    fn default() -> Self {
+        Self {
+            model: "bge-reranker-v2-m3".to_string(),
+            max_content_chars: 2000,
+        }
+    }
+}
+
+impl CrossEncoderReranker {
+    pub fn new(provider_router: std::sync::Arc<crate::providers::ProviderRouter>) -> Self {
+        Self::with_config(provider_router, CrossEncoderConfig::default())
+    }
+
+    pub fn with_config(
+        provider_router: std::sync::Arc<crate::providers::ProviderRouter>,
+        config: CrossEncoderConfig,
+    ) -> Self {
+        Self { provider_router, config }
+    }
+
+    /// The model id used for scoring, for attribution in stored rerank
+    /// events (`search_rerank_events.model`).
+    pub fn model_name(&self) -> &str {
+        &self.config.model
+    }
+
+    /// Scores every result against `query` and returns them paired with
+    /// their cross-encoder score, sorted most-relevant first. A result whose
+    /// scoring call fails falls back to its existing `relevance_score`
+    /// rather than dropping it, so a single flaky completion can't shrink
+    /// the result set.
+    pub async fn rerank(&self, query: &str, results: &[SearchResult]) -> Result<Vec<(SearchResult, f32)>> {
+        let mut scored = Vec::with_capacity(results.len());
+
+        for result in results {
+            let score = match self.score_pair(query, result).await {
+                Ok(score) => score,
+                Err(e) => {
+                    tracing::warn!(
+                        "Cross-encoder scoring failed for {}: {} — keeping original rank",
+                        result.file_path, e
+                    );
+                    result.relevance_score
+                }
+            };
+            scored.push((result.clone(), score));
+        }
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        Ok(scored)
+    }
+
+    async fn score_pair(&self, query: &str, result: &SearchResult) -> Result<f32> {
+        // Truncate by char count rather than `String::truncate`'s byte count,
+        // since the latter panics if the cut point lands inside a multi-byte
+        // character (e.g. a `//` comment in a non-English language).
+        let content: String = result.content.chars().take(self.config.max_content_chars).collect();
+
+        let prompt = format!(
+            "Query: {}\n\nCode:\n{}\n\nRate how relevant this code is to the query on a scale from 0.0 (irrelevant) to 1.0 (perfectly relevant). Respond with only the number.",
+            query, content
+        );
+
+        let request = crate::providers::CompletionRequest::new(prompt)
+            .with_model(self.config.model.clone())
+            .with_temperature(0.0)
+            .with_max_tokens(8);
+
+        let response = self.provider_router
+            .complete_with_fallback(request)
+            .await
+            .map_err(|e| anyhow::anyhow!("cross-encoder completion failed: {}", e))?;
+
+        let text = response.choices.first().map(|c| c.text.as_str()).unwrap_or("");
+        parse_relevance_score(text)
+    }
+}
+
+/// Parses a cross-encoder response like `"0.82"` or `"Score: 0.82"` into a
+/// score clamped to `[0.0, 1.0]`.
+fn parse_relevance_score(text: &str) -> Result<f32> {
+    let first_token = text.trim().split_whitespace().next().unwrap_or("");
+    let cleaned: String = first_token.chars().filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-').collect();
+
+    cleaned.parse::<f32>()
+        .map(|v| v.clamp(0.0, 1.0))
+        .map_err(|_| anyhow::anyhow!("could not parse relevance score from {:?}", text))
+}
+
 #[derive(Debug, Clone)]
 pub struct SearchFeedback {
     pub result: SearchResult,
@@ -572,4 +803,231 @@ impl Default for WorkspaceContext {
             project_patterns: Vec::new(),
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::{Entity, EntityType, ProjectContext, SearchContext, SymbolInfo, SymbolType, Visibility};
+    use uuid::Uuid;
+
+    fn sample_result(content: &str) -> SearchResult {
+        SearchResult {
+            id: Uuid::new_v4(),
+            file_path: "src/lib.rs".to_string(),
+            content: content.to_string(),
+            start_line: 1,
+            end_line: 1,
+            relevance_score: 1.0,
+            match_type: MatchType::ExactMatch,
+            language: "rust".to_string(),
+            symbol_info: Some(SymbolInfo {
+                name: "calculate_total".to_string(),
+                symbol_type: SymbolType::Function,
+                signature: None,
+                documentation: None,
+                parameters: Vec::new(),
+                return_type: None,
+                visibility: Visibility::Public,
+                complexity_score: 1.0,
+            }),
+            context: SearchContext {
+                surrounding_code: String::new(),
+                imports: Vec::new(),
+                dependencies: Vec::new(),
+                related_symbols: Vec::new(),
+                file_summary: String::new(),
+                project_context: ProjectContext {
+                    project_name: "demo".to_string(),
+                    project_type: "library".to_string(),
+                    main_language: "rust".to_string(),
+                    framework: None,
+                    version: None,
+                },
+            },
+            highlights: Vec::new(),
+        }
+    }
+
+    fn sample_result_at(file_path: &str, project: &str, relevance_score: f32) -> SearchResult {
+        let mut result = sample_result("fn calculate_total() {}");
+        result.file_path = file_path.to_string();
+        result.relevance_score = relevance_score;
+        result.context.project_context.project_name = project.to_string();
+        result
+    }
+
+    fn processed_query_for(function_name: &str) -> ProcessedQuery {
+        ProcessedQuery {
+            original_query: function_name.to_string(),
+            normalized_query: function_name.to_lowercase(),
+            keywords: Vec::new(),
+            entities: vec![Entity {
+                text: function_name.to_string(),
+                entity_type: EntityType::FunctionName,
+                confidence: 1.0,
+                start_pos: 0,
+                end_pos: function_name.len(),
+            }],
+            intent: QueryIntent::FindFunction,
+            filters: Vec::new(),
+            boost_terms: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn highlights_cover_exact_function_name_substring() {
+        let ranker = ResultRanker::new();
+        let content = "fn calculate_total(items: &[Item]) -> u32 {";
+        let result = sample_result(content);
+        let query = processed_query_for("calculate_total");
+
+        let highlights = ranker.generate_highlights(&result, &query).unwrap();
+
+        assert_eq!(highlights.len(), 1);
+        let highlight = &highlights[0];
+        assert_eq!(highlight.highlight_type, HighlightType::SymbolMatch);
+        assert_eq!(&content[highlight.start_offset..highlight.end_offset], "calculate_total");
+    }
+
+    #[test]
+    fn deduplicates_file_indexed_under_overlapping_workspace_roots() {
+        // "Cargo.toml" and "./Cargo.toml" canonicalize to the same absolute
+        // path, standing in for the same file being indexed once per
+        // overlapping/monorepo-nested workspace root.
+        let results = vec![
+            sample_result_at("Cargo.toml", "backend", 0.4),
+            sample_result_at("./Cargo.toml", "backend", 0.9),
+        ];
+
+        let deduped = deduplicate_by_canonical_path(results);
+
+        assert_eq!(deduped.len(), 1);
+        assert_eq!(deduped[0].relevance_score, 0.9);
+
+        let ranker = ResultRanker::new();
+        let aggregations = ranker.generate_aggregations(&deduped);
+        assert_eq!(aggregations.projects.get("backend"), Some(&1));
+    }
+
+    #[test]
+    fn highlights_respect_utf8_char_boundaries() {
+        let ranker = ResultRanker::new();
+        let content = "// naïve implementation\nfn naive_sum() {}";
+        let result = sample_result(content);
+        let query = ProcessedQuery {
+            keywords: vec!["naive".to_string()],
+            ..processed_query_for("naive_sum")
+        };
+
+        let highlights = ranker.generate_highlights(&result, &query).unwrap();
+
+        for highlight in &highlights {
+            assert!(content.is_char_boundary(highlight.start_offset));
+            assert!(content.is_char_boundary(highlight.end_offset));
+        }
+    }
+
+    #[test]
+    fn parses_plain_score() {
+        assert_eq!(parse_relevance_score("0.82").unwrap(), 0.82);
+    }
+
+    #[test]
+    fn parses_score_with_surrounding_text() {
+        assert_eq!(parse_relevance_score("Score: 0.5\n").unwrap(), 0.5);
+    }
+
+    #[test]
+    fn clamps_out_of_range_scores() {
+        assert_eq!(parse_relevance_score("1.7").unwrap(), 1.0);
+        assert_eq!(parse_relevance_score("-0.3").unwrap(), 0.0);
+    }
+
+    #[test]
+    fn rejects_unparseable_text() {
+        assert!(parse_relevance_score("not a number").is_err());
+    }
+
+    #[test]
+    fn perfect_feedback_boosts_the_same_result_on_a_later_identical_query() {
+        let ranker = ResultRanker::new();
+        let request = SearchRequest {
+            query: "calculate total".to_string(),
+            ..Default::default()
+        };
+        let processed_query = processed_query_for("calculate_total");
+
+        let result_a = sample_result_at("src/billing.rs", "demo", 1.0);
+        let result_b = sample_result_at("src/legacy.rs", "demo", 1.0);
+
+        let before = ranker
+            .rank_results(vec![result_a.clone(), result_b.clone()], &request, &processed_query, None)
+            .unwrap();
+        assert!((before[0].relevance_score - before[1].relevance_score).abs() < 1e-6);
+
+        ranker.record_feedback(&request.query, &result_b.file_path, SearchFeedbackType::Perfect);
+
+        let after = ranker
+            .rank_results(vec![result_a, result_b.clone()], &request, &processed_query, None)
+            .unwrap();
+        assert_eq!(after[0].file_path, result_b.file_path);
+        assert!(after[0].relevance_score > after[1].relevance_score);
+    }
+
+    #[test]
+    fn irrelevant_feedback_demotes_a_result() {
+        let ranker = ResultRanker::new();
+        let request = SearchRequest {
+            query: "calculate total".to_string(),
+            ..Default::default()
+        };
+        let processed_query = processed_query_for("calculate_total");
+
+        let result_a = sample_result_at("src/billing.rs", "demo", 1.0);
+        let result_b = sample_result_at("src/legacy.rs", "demo", 1.0);
+
+        ranker.record_feedback(&request.query, &result_b.file_path, SearchFeedbackType::Irrelevant);
+
+        let after = ranker
+            .rank_results(vec![result_a.clone(), result_b], &request, &processed_query, None)
+            .unwrap();
+        assert_eq!(after[0].file_path, result_a.file_path);
+    }
+
+    #[test]
+    fn rust_preferring_user_ranks_rust_above_equally_similar_python() {
+        let ranker = ResultRanker::new();
+        let request = SearchRequest { query: "calculate total".to_string(), ..Default::default() };
+        let processed_query = processed_query_for("calculate_total");
+
+        let mut rust_result = sample_result_at("src/billing.rs", "demo", 1.0);
+        rust_result.language = "rust".to_string();
+        let mut python_result = sample_result_at("src/billing.py", "demo", 1.0);
+        python_result.language = "python".to_string();
+
+        let context = RankingContext {
+            query_intent: QueryIntent::FindFunction,
+            user_preferences: UserPreferences {
+                preferred_languages: vec!["rust".to_string()],
+                preferred_complexity: ComplexityPreference::Any,
+                preferred_file_types: vec!["rs".to_string()],
+                boost_recent_files: true,
+                boost_frequently_accessed: true,
+            },
+            workspace_context: WorkspaceContext {
+                current_project_languages: vec!["rust".to_string()],
+                recently_modified_files: Vec::new(),
+                frequently_accessed_files: Vec::new(),
+                project_patterns: Vec::new(),
+            },
+            search_history: Vec::new(),
+        };
+
+        let ranked = ranker
+            .rank_results(vec![rust_result.clone(), python_result.clone()], &request, &processed_query, Some(&context))
+            .unwrap();
+
+        assert_eq!(ranked[0].language, "rust");
+        assert!(ranked[0].relevance_score > ranked[1].relevance_score);
+    }
+}
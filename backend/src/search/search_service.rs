@@ -1,29 +1,85 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use sqlx::PgPool;
 use uuid::Uuid;
-use tracing::{info, debug, error};
+use tracing::{info, debug, error, warn};
 
+use crate::auth::audit::{AuditEvent, AuditEventType, AuditOutcome, AuditService, log_event_fire_and_forget};
+use crate::config::SearchCacheConfig;
 use crate::providers::ProviderRouter;
 use super::{
-    SearchRequest, SearchResponse, SearchAnalytics, QueryRefinement, RefinementType,
+    SearchRequest, SearchResponse, SearchResult, SearchAnalytics, QueryRefinement, RefinementType,
     embedding_manager::EmbeddingManager,
     query_processor::QueryProcessor,
-    result_ranker::ResultRanker,
+    result_ranker::{ResultRanker, CrossEncoderReranker},
     code_indexer::CodeIndexer,
-    semantic_engine::{SemanticSearchEngine, IndexStats},
+    context_expander::{ContextExpander, ExpandedContext},
+    semantic_engine::{SemanticSearchEngine, IndexStats, ReindexStats},
+    knowledge_mesh::{KnowledgeMesh, SymbolNode},
 };
 
 pub struct SearchService {
     semantic_engine: Arc<SemanticSearchEngine>,
+    cross_encoder_reranker: Arc<CrossEncoderReranker>,
+    context_expander: Arc<ContextExpander>,
+    provider_router: Arc<ProviderRouter>,
+    /// Provider `search()` reranks results through post-processing, if
+    /// configured via `config.providers.rerank_provider`. Distinct from
+    /// `cross_encoder_reranker`, which only runs on-demand through
+    /// `rerank_search_results`.
+    rerank_provider: Option<String>,
+    /// Same instance `semantic_engine` ranks with — kept here too so
+    /// `provide_search_feedback` can feed it `record_feedback` calls
+    /// without round-tripping through `semantic_engine`.
+    result_ranker: Arc<ResultRanker>,
     pool: Arc<PgPool>,
     analytics_enabled: bool,
+    /// Results of recent `search()` calls, keyed by `SearchResponse::search_id`
+    /// so `rerank_search_results` can re-score them without re-running the
+    /// search pipeline. Separate from `SemanticSearchEngine`'s own
+    /// query-keyed cache, which exists to skip redundant searches rather
+    /// than to look a specific past search back up by id.
+    recent_results: Arc<tokio::sync::RwLock<HashMap<Uuid, CachedSearchResults>>>,
+    /// Flattened out of `recent_results` at the same time, keyed by
+    /// individual `SearchResult::id` so `expand_result_context` can look a
+    /// single result up without knowing which search it came from.
+    recent_results_by_id: Arc<tokio::sync::RwLock<HashMap<Uuid, CachedResult>>>,
+    search_cache_config: SearchCacheConfig,
+    audit_service: Option<Arc<dyn AuditService>>,
+}
+
+struct CachedSearchResults {
+    user_id: Uuid,
+    query: String,
+    results: Vec<SearchResult>,
+    expires_at: Instant,
+}
+
+struct CachedResult {
+    user_id: Uuid,
+    result: SearchResult,
+    expires_at: Instant,
+}
+
+/// Drops an arbitrary entry once `cache` is at `max_entries`, rather than
+/// tracking recency — the TTL each cache entry carries already keeps things
+/// from growing stale, so this only guards against unbounded growth.
+fn evict_one_if_full<V>(cache: &mut HashMap<Uuid, V>, max_entries: usize) {
+    if cache.len() >= max_entries {
+        if let Some(key) = cache.keys().next().copied() {
+            cache.remove(&key);
+        }
+    }
 }
 
 impl SearchService {
     pub fn new(
         provider_router: Arc<ProviderRouter>,
         pool: Arc<PgPool>,
+        search_cache_config: SearchCacheConfig,
+        rerank_provider: Option<String>,
     ) -> Self {
         // Initialize all components
         let embedding_manager = Arc::new(EmbeddingManager::new(provider_router.clone()));
@@ -33,45 +89,256 @@ impl SearchService {
         ));
         let result_ranker = Arc::new(ResultRanker::new());
         let code_indexer = Arc::new(CodeIndexer::new(embedding_manager.clone()));
-        
+        let knowledge_mesh = Arc::new(KnowledgeMesh::new(pool.clone()));
+
         let semantic_engine = Arc::new(SemanticSearchEngine::new(
             embedding_manager,
             query_processor,
-            result_ranker,
+            result_ranker.clone(),
             code_indexer,
             pool.clone(),
+            search_cache_config.clone(),
+            knowledge_mesh,
         ));
 
+        let cross_encoder_reranker = Arc::new(CrossEncoderReranker::new(provider_router.clone()));
+        let context_expander = Arc::new(ContextExpander::new());
+
         Self {
             semantic_engine,
+            cross_encoder_reranker,
+            context_expander,
+            provider_router,
+            rerank_provider,
+            result_ranker,
             pool,
             analytics_enabled: true,
+            recent_results: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            recent_results_by_id: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            search_cache_config,
+            audit_service: None,
+        }
+    }
+
+    /// Emits one `AuditEvent` per call into `search` and `index_workspace`,
+    /// so what a user searched for and what they indexed is traceable.
+    /// No-op when not set.
+    pub fn with_audit_service(mut self, audit_service: Arc<dyn AuditService>) -> Self {
+        self.audit_service = Some(audit_service);
+        self
+    }
+
+    fn audit_event(&self, user_id: Uuid, action: &str, resource_id: Option<String>, outcome: AuditOutcome) -> AuditEvent {
+        AuditEvent {
+            id: Uuid::new_v4(),
+            timestamp: chrono::Utc::now(),
+            event_type: AuditEventType::DataAccess,
+            user_id: Some(user_id),
+            session_id: None,
+            organization_id: Uuid::new_v4(), // Would be retrieved from user context
+            ip_address: "unknown".to_string(),
+            user_agent: "unknown".to_string(),
+            resource_type: Some("search".to_string()),
+            resource_id,
+            action: action.to_string(),
+            outcome,
+            details: HashMap::new(),
+            risk_score: None,
         }
     }
 
     pub async fn search(&self, mut request: SearchRequest, user_id: Uuid) -> Result<SearchResponse> {
         info!("Processing search request for user: {}", user_id);
-        
+
         // Validate request
         self.validate_request(&request)?;
-        
+
         // Apply user preferences
         self.apply_user_preferences(&mut request, user_id).await?;
-        
+
         // Perform search
-        let response = self.semantic_engine.search(request.clone()).await?;
-        
+        let mut response = self.semantic_engine.search(request.clone()).await?;
+
+        if let Some(provider_name) = &self.rerank_provider {
+            self.rerank_with_provider(provider_name, &request.query, &mut response.results).await;
+        }
+
+        let search_id = Uuid::new_v4();
+        response.search_id = search_id;
+
+        self.cache_search_results(search_id, user_id, &request.query, &response.results).await;
+
         // Log analytics
         if self.analytics_enabled {
-            self.log_search_analytics(&request, &response, user_id).await?;
+            self.log_search_analytics(search_id, &request, &response, user_id).await?;
         }
-        
+
         // Update search history
         self.update_search_history(&request, user_id).await?;
-        
+
+        if let Some(audit_service) = &self.audit_service {
+            let event = self.audit_event(user_id, "search", Some(search_id.to_string()), AuditOutcome::Success);
+            log_event_fire_and_forget(audit_service.clone(), event);
+        }
+
         Ok(response)
     }
 
+    async fn cache_search_results(&self, search_id: Uuid, user_id: Uuid, query: &str, results: &[SearchResult]) {
+        let expires_at = Instant::now() + Duration::from_secs(self.search_cache_config.ttl_seconds);
+
+        {
+            let mut cache = self.recent_results.write().await;
+            evict_one_if_full(&mut cache, self.search_cache_config.max_entries);
+            cache.insert(search_id, CachedSearchResults {
+                user_id,
+                query: query.to_string(),
+                results: results.to_vec(),
+                expires_at,
+            });
+        }
+
+        {
+            let mut cache = self.recent_results_by_id.write().await;
+            for result in results {
+                evict_one_if_full(&mut cache, self.search_cache_config.max_entries);
+                cache.insert(result.id, CachedResult {
+                    user_id,
+                    result: result.clone(),
+                    expires_at,
+                });
+            }
+        }
+    }
+
+    /// Post-processes `results` in place through `provider_name`'s native
+    /// rerank endpoint (e.g. Cohere's `/rerank`, via `ProviderRouter::rerank`),
+    /// re-sorting them by the returned scores. A failed or unavailable
+    /// rerank call (provider not configured, circuit open, etc.) is logged
+    /// and the original ranking is kept, so this step never breaks
+    /// `search()` itself.
+    async fn rerank_with_provider(&self, provider_name: &str, query: &str, results: &mut Vec<SearchResult>) {
+        if results.is_empty() {
+            return;
+        }
+
+        let documents: Vec<String> = results.iter().map(|r| r.content.clone()).collect();
+
+        match self.provider_router.rerank(provider_name, query, &documents).await {
+            Ok(scored) => {
+                let reordered: Vec<SearchResult> = scored.into_iter()
+                    .filter_map(|(index, score)| {
+                        results.get(index).cloned().map(|mut result| {
+                            result.relevance_score = score;
+                            result
+                        })
+                    })
+                    .collect();
+
+                if !reordered.is_empty() {
+                    *results = reordered;
+                }
+            }
+            Err(e) => {
+                warn!("Rerank via {} failed, keeping original ranking: {}", provider_name, e);
+            }
+        }
+    }
+
+    /// Re-reads `result_id`'s source file and returns a wider window of
+    /// lines around it than the truncated `content`/`surrounding_code` on
+    /// the cached `SearchResult`. `Ok(None)` means either the result has
+    /// expired out of `recent_results_by_id` (the search is stale — re-run
+    /// it) or its file was deleted since indexing; both surface as 404 to
+    /// the caller, since distinguishing them isn't actionable.
+    pub async fn expand_result_context(&self, result_id: Uuid, context_lines: usize, user_id: Uuid) -> Result<Option<ExpandedContext>> {
+        let result = {
+            let cache = self.recent_results_by_id.read().await;
+            cache.get(&result_id)
+                .filter(|c| c.expires_at > Instant::now() && c.user_id == user_id)
+                .map(|c| c.result.clone())
+        };
+
+        let Some(result) = result else {
+            return Ok(None);
+        };
+
+        self.context_expander
+            .expand(&result.file_path, result.start_line, result.end_line, context_lines)
+            .await
+    }
+
+    /// Re-scores a prior search's results with `CrossEncoderReranker` and
+    /// returns the top `top_k`. The prior results must still be in
+    /// `recent_results` (i.e. the search happened within
+    /// `search_cache_config.ttl_seconds` and hasn't been evicted) and must
+    /// belong to `user_id`, so a guessed `search_id` can't be used to read
+    /// back another user's search results.
+    pub async fn rerank_search_results(&self, search_id: Uuid, top_k: Option<usize>, user_id: Uuid) -> Result<SearchResponse> {
+        let cached = {
+            let cache = self.recent_results.read().await;
+            cache.get(&search_id)
+                .filter(|c| c.expires_at > Instant::now() && c.user_id == user_id)
+                .map(|c| (c.query.clone(), c.results.clone()))
+        };
+
+        let (query, original_results) = cached
+            .ok_or_else(|| anyhow!("no recent search results found for search_id {}", search_id))?;
+
+        let start_time = Instant::now();
+        let scored = self.cross_encoder_reranker.rerank(&query, &original_results).await?;
+
+        let reranked_results: Vec<SearchResult> = scored.into_iter()
+            .map(|(mut result, score)| {
+                result.relevance_score = score;
+                result
+            })
+            .collect();
+
+        let top_k = top_k.unwrap_or(reranked_results.len());
+        let original_order: Vec<Uuid> = original_results.iter().map(|r| r.id).collect();
+        let reranked_order: Vec<Uuid> = reranked_results.iter().take(top_k).map(|r| r.id).collect();
+
+        if self.analytics_enabled {
+            self.log_rerank_event(search_id, user_id, &query, &original_order, &reranked_order).await?;
+        }
+
+        let results: Vec<SearchResult> = reranked_results.into_iter().take(top_k).collect();
+        let aggregations = self.semantic_engine.generate_aggregations(&results);
+
+        Ok(SearchResponse {
+            query,
+            total_matches: results.len(),
+            search_time_ms: start_time.elapsed().as_millis() as u64,
+            suggestions: Vec::new(),
+            related_queries: Vec::new(),
+            filters_applied: vec!["cross_encoder_rerank".to_string()],
+            aggregations,
+            cached: false,
+            search_id,
+            results,
+        })
+    }
+
+    async fn log_rerank_event(&self, search_id: Uuid, user_id: Uuid, query: &str, original_order: &[Uuid], reranked_order: &[Uuid]) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO search_rerank_events (search_id, user_id, query, model, original_order, reranked_order)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(search_id)
+        .bind(user_id)
+        .bind(query)
+        .bind(self.cross_encoder_reranker.model_name())
+        .bind(original_order)
+        .bind(reranked_order)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn search_similar_code(&self, code_snippet: &str, workspace_paths: Vec<String>, user_id: Uuid) -> Result<SearchResponse> {
         info!("Searching for similar code for user: {}", user_id);
         
@@ -84,6 +351,9 @@ impl SearchService {
             max_results: Some(20),
             similarity_threshold: Some(0.8),
             include_context: true,
+            force_exact_search: false,
+            similarity_metric: None,
+            user_id: Some(user_id),
         };
         
         self.search(request, user_id).await
@@ -107,6 +377,9 @@ impl SearchService {
             max_results: Some(50),
             similarity_threshold: Some(0.7),
             include_context: true,
+            force_exact_search: false,
+            similarity_metric: None,
+            user_id: Some(user_id),
         };
         
         self.search(request, user_id).await
@@ -137,6 +410,9 @@ impl SearchService {
             max_results: Some(30),
             similarity_threshold: Some(0.6),
             include_context: true,
+            force_exact_search: false,
+            similarity_metric: None,
+            user_id: Some(user_id),
         };
         
         self.search(request, user_id).await
@@ -154,6 +430,9 @@ impl SearchService {
             max_results: Some(25),
             similarity_threshold: Some(0.6),
             include_context: true,
+            force_exact_search: false,
+            similarity_metric: None,
+            user_id: Some(user_id),
         };
         
         self.search(request, user_id).await
@@ -177,22 +456,29 @@ impl SearchService {
         Ok(all_suggestions.into_iter().take(10).collect())
     }
 
-    pub async fn index_workspace(&self, workspace_path: &str, user_id: Uuid) -> Result<IndexStats> {
-        info!("Indexing workspace: {} for user: {}", workspace_path, user_id);
-        
+    pub async fn index_workspace(&self, workspace_path: &str, user_id: Uuid, mode: IndexMode) -> Result<ReindexStats> {
+        info!("Indexing workspace: {} for user: {} (mode: {:?})", workspace_path, user_id, mode);
+
         // Check if user has permission to index this workspace
         self.check_workspace_permission(workspace_path, user_id).await?;
-        
+
         // Perform indexing
-        self.semantic_engine.reindex_workspace(workspace_path).await?;
-        
-        // Get stats
+        let reindex_stats = match mode {
+            IndexMode::Full => self.semantic_engine.reindex_workspace(workspace_path).await?,
+            IndexMode::Incremental => self.semantic_engine.index_workspace_incremental(workspace_path).await?,
+        };
+
+        // Get stats for the `indexing_activity` audit log, which predates
+        // `ReindexStats` and tracks totals rather than a per-run diff.
         let stats = self.semantic_engine.get_index_stats(workspace_path).await?;
-        
-        // Log indexing activity
         self.log_indexing_activity(workspace_path, user_id, &stats).await?;
-        
-        Ok(stats)
+
+        if let Some(audit_service) = &self.audit_service {
+            let event = self.audit_event(user_id, "index_workspace", Some(workspace_path.to_string()), AuditOutcome::Success);
+            audit_service.log_event(event).await?;
+        }
+
+        Ok(reindex_stats)
     }
 
     pub async fn get_workspace_stats(&self, workspace_path: &str, user_id: Uuid) -> Result<IndexStats> {
@@ -202,6 +488,12 @@ impl SearchService {
         self.semantic_engine.get_index_stats(workspace_path).await
     }
 
+    /// Every symbol that transitively calls `symbol_id`, via the cross-file
+    /// symbol graph `semantic_engine` indexes into during `save_indices_to_db`.
+    pub async fn find_transitive_callers(&self, symbol_id: Uuid) -> Result<Vec<SymbolNode>> {
+        self.semantic_engine.knowledge_mesh().find_transitive_callers(symbol_id).await
+    }
+
     pub async fn get_user_search_analytics(&self, user_id: Uuid, days: i32) -> Result<UserSearchAnalytics> {
         let start_date = chrono::Utc::now() - chrono::Duration::days(days as i64);
         
@@ -254,11 +546,11 @@ impl SearchService {
 
     pub async fn provide_search_feedback(&self, search_id: Uuid, feedback: SearchFeedback, user_id: Uuid) -> Result<()> {
         info!("Received search feedback from user: {}", user_id);
-        
+
         // Update analytics
         sqlx::query!(
             r#"
-            UPDATE search_analytics 
+            UPDATE search_analytics
             SET user_satisfaction = $1
             WHERE query_id = $2 AND user_id = $3
             "#,
@@ -285,6 +577,29 @@ impl SearchService {
         .execute(&*self.pool)
         .await?;
 
+        // Feeding this into ranking requires knowing which file and which
+        // query it's about; both only exist in the short-lived in-memory
+        // caches `search()` populates, so feedback on an expired/foreign
+        // search quietly skips the ranking signal rather than failing.
+        if let Some(result_id) = feedback.result_id {
+            let file_path = {
+                let by_id = self.recent_results_by_id.read().await;
+                by_id.get(&result_id)
+                    .filter(|cached| cached.user_id == user_id)
+                    .map(|cached| cached.result.file_path.clone())
+            };
+            let query = {
+                let cache = self.recent_results.read().await;
+                cache.get(&search_id)
+                    .filter(|cached| cached.user_id == user_id)
+                    .map(|cached| cached.query.clone())
+            };
+
+            if let (Some(file_path), Some(query)) = (file_path, query) {
+                self.result_ranker.record_feedback(&query, &file_path, feedback.feedback_type);
+            }
+        }
+
         Ok(())
     }
 
@@ -313,7 +628,10 @@ impl SearchService {
     async fn apply_user_preferences(&self, request: &mut SearchRequest, user_id: Uuid) -> Result<()> {
         // Load user preferences from database
         let preferences = self.load_user_search_preferences(user_id).await?;
-        
+
+        // Let `build_ranking_context` personalize ranking for this requester.
+        request.user_id = Some(user_id);
+
         // Apply language preferences
         if request.language_filters.is_empty() && !preferences.preferred_languages.is_empty() {
             request.language_filters = preferences.preferred_languages;
@@ -353,9 +671,9 @@ impl SearchService {
         Ok(UserSearchPreferences::default())
     }
 
-    async fn log_search_analytics(&self, request: &SearchRequest, response: &SearchResponse, user_id: Uuid) -> Result<()> {
+    async fn log_search_analytics(&self, search_id: Uuid, request: &SearchRequest, response: &SearchResponse, user_id: Uuid) -> Result<()> {
         let analytics = SearchAnalytics {
-            query_id: Uuid::new_v4(),
+            query_id: search_id,
             user_id,
             query: request.query.clone(),
             results_count: response.results.len(),
@@ -516,6 +834,11 @@ pub struct SearchFeedback {
     pub feedback_type: SearchFeedbackType,
     pub satisfaction_score: f32,
     pub comments: Option<String>,
+    /// The specific result this feedback is about, if any — required for
+    /// `provide_search_feedback` to feed a per-file ranking signal into
+    /// `ResultRanker::record_feedback`. `None` records search-level
+    /// satisfaction only, same as before this field existed.
+    pub result_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -524,4 +847,21 @@ pub enum SearchFeedbackType {
     NotHelpful,
     Irrelevant,
     Perfect,
+}
+
+/// Which indexing strategy `SearchService::index_workspace` should use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexMode {
+    /// Drop and rebuild every index under the workspace.
+    Full,
+    /// Only re-embed files that were added or changed since the last index,
+    /// via `SemanticSearchEngine::index_workspace_incremental`.
+    Incremental,
+}
+
+impl Default for IndexMode {
+    fn default() -> Self {
+        IndexMode::Full
+    }
 }
\ No newline at end of file
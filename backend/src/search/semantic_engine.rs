@@ -1,19 +1,39 @@
 use anyhow::Result;
 use std::sync::Arc;
 use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+use serde::{Serialize, Deserialize};
 use sqlx::PgPool;
 use tracing::{info, debug, warn, error};
 use uuid::Uuid;
 
+use crate::auth::preferences::{ExplanationLevel, PreferencesService};
+use crate::config::SearchCacheConfig;
 use super::{
     SearchRequest, SearchResponse, SearchResult, ProcessedQuery, CodeIndex,
-    MatchType, SimilarityRequest, SimilarityMetric, SearchSuggestion, SuggestionType,
+    MatchType, SearchQueryType, SimilarityRequest, SimilarityMetric, SearchSuggestion, SuggestionType,
     embedding_manager::EmbeddingManager,
     query_processor::QueryProcessor,
-    result_ranker::{ResultRanker, RankingContext},
+    result_ranker::{ResultRanker, RankingContext, UserPreferences, WorkspaceContext, ComplexityPreference},
     code_indexer::CodeIndexer,
+    vector_index::VectorIndex,
+    knowledge_mesh::KnowledgeMesh,
 };
 
+/// Upper bound on how long `perform_code_pattern_search` spends scanning
+/// indexed files for a single request, so a pathological pattern (or just a
+/// very large workspace) can't hang a search indefinitely.
+const CODE_PATTERN_SEARCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A cached result plus when it expires, so expiry can be checked without a
+/// second map lookup.
+struct CachedSearch {
+    response: SearchResponse,
+    expires_at: Instant,
+}
+
 pub struct SemanticSearchEngine {
     embedding_manager: Arc<EmbeddingManager>,
     query_processor: Arc<QueryProcessor>,
@@ -21,6 +41,21 @@ pub struct SemanticSearchEngine {
     code_indexer: Arc<CodeIndexer>,
     pool: Arc<PgPool>,
     index_cache: Arc<tokio::sync::RwLock<HashMap<String, Vec<CodeIndex>>>>,
+    /// Per-workspace HNSW index over `index_cache`'s file-level embeddings,
+    /// rebuilt alongside it. `candidate_id`s returned by a search line up
+    /// with the position a `CodeIndex` has in `index_cache[workspace_path]`.
+    ann_index_cache: Arc<tokio::sync::RwLock<HashMap<String, Arc<VectorIndex>>>>,
+    /// Results of recent `search()` calls, keyed on a hash of everything that
+    /// affects the outcome. Cleared in full by `reindex_workspace` and
+    /// `index_workspace_incremental`, since a reindex can change which
+    /// results a query should return and a cached entry doesn't record which
+    /// workspaces it touched.
+    search_cache: Arc<tokio::sync::RwLock<HashMap<u64, CachedSearch>>>,
+    search_cache_config: SearchCacheConfig,
+    /// Cross-file symbol relationship graph, consulted by
+    /// `create_search_context` to populate `related_symbols` with callers
+    /// from outside the current file, not just symbols in `index.symbols`.
+    knowledge_mesh: Arc<KnowledgeMesh>,
 }
 
 impl SemanticSearchEngine {
@@ -30,6 +65,8 @@ impl SemanticSearchEngine {
         result_ranker: Arc<ResultRanker>,
         code_indexer: Arc<CodeIndexer>,
         pool: Arc<PgPool>,
+        search_cache_config: SearchCacheConfig,
+        knowledge_mesh: Arc<KnowledgeMesh>,
     ) -> Self {
         Self {
             embedding_manager,
@@ -38,10 +75,87 @@ impl SemanticSearchEngine {
             code_indexer,
             pool,
             index_cache: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            ann_index_cache: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            search_cache: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            search_cache_config,
+            knowledge_mesh,
+        }
+    }
+
+    /// Shared accessor so `SearchService` (and the `/search/callers/:symbol_id`
+    /// handler behind it) can reach the same mesh this engine indexes into,
+    /// without constructing a second one.
+    pub fn knowledge_mesh(&self) -> &Arc<KnowledgeMesh> {
+        &self.knowledge_mesh
+    }
+
+    /// Hashes the parts of `request` that determine its result set: the
+    /// normalized query, the sorted workspace paths, filters, and similarity
+    /// threshold. Two requests that differ only in, say, `max_results`
+    /// ordering of `workspace_paths` still hash the same.
+    fn cache_key(request: &SearchRequest) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        request.query.trim().to_lowercase().hash(&mut hasher);
+        std::mem::discriminant(&request.query_type).hash(&mut hasher);
+
+        let mut workspace_paths = request.workspace_paths.clone();
+        workspace_paths.sort();
+        workspace_paths.hash(&mut hasher);
+
+        for filter in &request.file_filters {
+            filter.pattern.hash(&mut hasher);
+            filter.include.hash(&mut hasher);
         }
+
+        let mut language_filters = request.language_filters.clone();
+        language_filters.sort();
+        language_filters.hash(&mut hasher);
+
+        request.max_results.hash(&mut hasher);
+        request.similarity_threshold.map(|t| t.to_bits()).hash(&mut hasher);
+        request.include_context.hash(&mut hasher);
+        request.force_exact_search.hash(&mut hasher);
+        request.similarity_metric.as_ref().map(std::mem::discriminant).hash(&mut hasher);
+
+        hasher.finish()
     }
 
     pub async fn search(&self, request: SearchRequest) -> Result<SearchResponse> {
+        let cache_key = Self::cache_key(&request);
+
+        {
+            let cache = self.search_cache.read().await;
+            if let Some(cached) = cache.get(&cache_key) {
+                if cached.expires_at > Instant::now() {
+                    debug!("Search cache hit for: {}", request.query);
+                    let mut response = cached.response.clone();
+                    response.cached = true;
+                    return Ok(response);
+                }
+            }
+        }
+
+        let response = self.search_uncached(request).await?;
+
+        let mut cache = self.search_cache.write().await;
+        if cache.len() >= self.search_cache_config.max_entries {
+            // Simple bound: drop an arbitrary entry rather than tracking
+            // recency, since the TTL already keeps the cache from growing
+            // stale and unbounded growth is the only failure mode this
+            // guards against.
+            if let Some(key) = cache.keys().next().copied() {
+                cache.remove(&key);
+            }
+        }
+        cache.insert(cache_key, CachedSearch {
+            response: response.clone(),
+            expires_at: Instant::now() + Duration::from_secs(self.search_cache_config.ttl_seconds),
+        });
+
+        Ok(response)
+    }
+
+    async fn search_uncached(&self, request: SearchRequest) -> Result<SearchResponse> {
         let start_time = std::time::Instant::now();
         info!("Starting semantic search for: {}", request.query);
 
@@ -49,26 +163,31 @@ impl SemanticSearchEngine {
         let processed_query = self.query_processor.process_query(&request).await?;
         debug!("Processed query: {:?}", processed_query.intent);
 
-        // Query embedding'i oluştur
-        let query_embedding = self.query_processor.generate_query_embedding(&processed_query).await?;
-
         // Workspace'leri index'le (gerekirse)
         let mut all_indices = Vec::new();
         for workspace_path in &request.workspace_paths {
-            let indices = self.get_or_create_indices(workspace_path).await?;
+            let (indices, _ignored) = self.get_or_create_indices(workspace_path).await?;
             all_indices.extend(indices);
         }
 
-        // Semantic search yap
-        let mut search_results = self.perform_semantic_search(
-            &query_embedding,
-            &all_indices,
-            &processed_query,
-            &request,
-        ).await?;
+        let mut search_results = if matches!(request.query_type, SearchQueryType::CodePattern) {
+            // Developers pasting a literal snippet or regex expect an exact,
+            // explainable match — skip embeddings entirely for this mode.
+            self.perform_code_pattern_search(&all_indices, &request).await?
+        } else {
+            // Query embedding'i oluştur
+            let query_embedding = self.query_processor.generate_query_embedding(&processed_query).await?;
+
+            self.perform_semantic_search(
+                &query_embedding,
+                &all_indices,
+                &processed_query,
+                &request,
+            ).await?
+        };
 
         // Sonuçları rank'le
-        let ranking_context = self.build_ranking_context(&request).await?;
+        let ranking_context = self.build_ranking_context(&request, &processed_query).await?;
         search_results = self.result_ranker.rank_results(
             search_results,
             &request,
@@ -104,40 +223,118 @@ impl SemanticSearchEngine {
             related_queries,
             filters_applied,
             aggregations,
+            cached: false,
+            search_id: Uuid::nil(),
         })
     }
 
-    async fn get_or_create_indices(&self, workspace_path: &str) -> Result<Vec<CodeIndex>> {
+    /// Drops every cached search result, since a workspace reindex can
+    /// change which results any given query should return.
+    async fn invalidate_search_cache(&self) {
+        self.search_cache.write().await.clear();
+    }
+
+    /// Exposes `ResultRanker::generate_aggregations` so callers building a
+    /// `SearchResponse` outside the normal `search()` pipeline (e.g.
+    /// `SearchService::rerank_search_results`) don't have to hand-roll
+    /// aggregation counts themselves.
+    pub fn generate_aggregations(&self, results: &[SearchResult]) -> super::SearchAggregations {
+        self.result_ranker.generate_aggregations(results)
+    }
+
+    /// Returns the workspace's indices plus a `files_ignored` reason
+    /// breakdown from `CodeIndexer::index_workspace`. The breakdown is only
+    /// ever non-empty on the "brand new indexing" path below — a cache or DB
+    /// hit means discovery didn't run this call, so there's nothing to report.
+    async fn get_or_create_indices(&self, workspace_path: &str) -> Result<(Vec<CodeIndex>, HashMap<String, usize>)> {
         // Cache'den kontrol et
         {
             let cache = self.index_cache.read().await;
             if let Some(indices) = cache.get(workspace_path) {
                 debug!("Using cached indices for workspace: {}", workspace_path);
-                return Ok(indices.clone());
+                let indices = self.refresh_stale_indices(indices.clone()).await?;
+                self.cache_indices(workspace_path, indices.clone()).await;
+                return Ok((indices, HashMap::new()));
             }
         }
 
         // Veritabanından kontrol et
         let db_indices = self.load_indices_from_db(workspace_path).await?;
         if !db_indices.is_empty() {
+            let db_indices = self.refresh_stale_indices(db_indices).await?;
             // Cache'e kaydet
-            let mut cache = self.index_cache.write().await;
-            cache.insert(workspace_path.to_string(), db_indices.clone());
-            return Ok(db_indices);
+            self.cache_indices(workspace_path, db_indices.clone()).await;
+            return Ok((db_indices, HashMap::new()));
         }
 
         // Yeni indexleme yap
         info!("Creating new indices for workspace: {}", workspace_path);
-        let indices = self.code_indexer.index_workspace(workspace_path).await?;
-        
+        let (indices, files_ignored) = self.code_indexer.index_workspace(workspace_path).await?;
+
         // Veritabanına kaydet
         self.save_indices_to_db(&indices).await?;
-        
+
         // Cache'e kaydet
+        self.cache_indices(workspace_path, indices.clone()).await;
+
+        Ok((indices, files_ignored))
+    }
+
+    /// Stats each indexed file and re-embeds any whose on-disk mtime is newer
+    /// than `CodeIndex.last_updated` (per `CodeIndex::is_stale`), so a cache
+    /// or DB hit never serves snippets from before the user's last edit.
+    /// Files that no longer exist on disk are dropped and their rows purged.
+    async fn refresh_stale_indices(&self, indices: Vec<CodeIndex>) -> Result<Vec<CodeIndex>> {
+        let mut refreshed = Vec::with_capacity(indices.len());
+        let mut changed = false;
+
+        for index in indices {
+            match tokio::fs::metadata(&index.file_path).await {
+                Ok(metadata) => {
+                    let modified: chrono::DateTime<chrono::Utc> = match metadata.modified() {
+                        Ok(m) => m.into(),
+                        Err(_) => {
+                            refreshed.push(index);
+                            continue;
+                        }
+                    };
+
+                    if index.is_stale(modified) {
+                        debug!("Re-embedding stale index for file: {}", index.file_path);
+                        let new_index = self.code_indexer.update_index(&index).await?;
+                        self.save_indices_to_db(std::slice::from_ref(&new_index)).await?;
+                        refreshed.push(new_index);
+                        changed = true;
+                    } else {
+                        refreshed.push(index);
+                    }
+                }
+                Err(_) => {
+                    warn!("Indexed file no longer exists, purging: {}", index.file_path);
+                    self.delete_index_from_db(index.id).await?;
+                    changed = true;
+                }
+            }
+        }
+
+        if changed {
+            self.invalidate_search_cache().await;
+        }
+
+        Ok(refreshed)
+    }
+
+    /// Writes `indices` into `index_cache` and rebuilds that workspace's
+    /// ANN index to match, so the two caches never drift out of sync.
+    async fn cache_indices(&self, workspace_path: &str, indices: Vec<CodeIndex>) {
+        let file_embeddings: Vec<Vec<f32>> = indices.iter().map(|idx| idx.embedding.clone()).collect();
+        let ann_index = Arc::new(VectorIndex::build(&file_embeddings));
+
         let mut cache = self.index_cache.write().await;
-        cache.insert(workspace_path.to_string(), indices.clone());
+        cache.insert(workspace_path.to_string(), indices);
 
-        Ok(indices)
+        let mut ann_cache = self.ann_index_cache.write().await;
+        ann_cache.insert(workspace_path.to_string(), ann_index);
     }
 
     async fn load_indices_from_db(&self, workspace_path: &str) -> Result<Vec<CodeIndex>> {
@@ -236,6 +433,8 @@ impl SemanticSearchEngine {
             .execute(&*self.pool)
             .await?;
 
+            self.sync_embedding_vector(index.id, &index.embedding).await?;
+
             // Symbols'ları kaydet
             for symbol in &index.symbols {
                 sqlx::query!(
@@ -263,12 +462,113 @@ impl SemanticSearchEngine {
                 )
                 .execute(&*self.pool)
                 .await?;
+
+                let node_id = self.knowledge_mesh.record_symbol(&index.file_path, symbol).await?;
+                self.knowledge_mesh.record_references(node_id, symbol).await?;
             }
         }
 
         Ok(())
     }
 
+    /// Decides whether `perform_semantic_search`'s file-level stage should run
+    /// server-side via `file_candidates_via_pgvector` instead of the in-memory
+    /// `embedding_manager.calculate_similarity` path, and runs it if so.
+    /// Returns `Ok(None)` to fall back to the in-memory path: when the
+    /// `pgvector` feature isn't compiled in, when `force_exact_search` is set,
+    /// or when `request.workspace_paths` isn't exactly one workspace (the
+    /// candidate ids returned by the query need a single `file_path LIKE`
+    /// prefix to scope to). Symbol-level search always stays in memory: by
+    /// the time it runs, the file-level stage has already narrowed `indices`
+    /// down to one workspace, so its symbol lists are small enough that
+    /// pushing them into SQL too wouldn't meaningfully reduce memory use.
+    #[cfg(feature = "pgvector")]
+    async fn file_candidates_via_pgvector_if_enabled(
+        &self,
+        query_embedding: &[f32],
+        threshold: f32,
+        request: &SearchRequest,
+    ) -> Result<Option<Vec<(Uuid, f32)>>> {
+        if request.force_exact_search {
+            return Ok(None);
+        }
+        let [workspace_path] = request.workspace_paths.as_slice() else {
+            return Ok(None);
+        };
+        let limit = request.max_results.unwrap_or(50) as i64;
+
+        let scored = self
+            .file_candidates_via_pgvector(query_embedding, workspace_path, threshold, limit)
+            .await?;
+        Ok(Some(scored))
+    }
+
+    #[cfg(not(feature = "pgvector"))]
+    async fn file_candidates_via_pgvector_if_enabled(
+        &self,
+        _query_embedding: &[f32],
+        _threshold: f32,
+        _request: &SearchRequest,
+    ) -> Result<Option<Vec<(Uuid, f32)>>> {
+        Ok(None)
+    }
+
+    /// Runs the file-level cosine similarity search in Postgres via the
+    /// `pgvector` extension (threshold and `LIMIT` applied in the query
+    /// itself), rather than scoring every candidate's `embedding` in Rust.
+    #[cfg(feature = "pgvector")]
+    async fn file_candidates_via_pgvector(
+        &self,
+        query_embedding: &[f32],
+        workspace_path: &str,
+        threshold: f32,
+        limit: i64,
+    ) -> Result<Vec<(Uuid, f32)>> {
+        let query_vector = pgvector::Vector::from(query_embedding.to_vec());
+
+        let rows = sqlx::query_as::<_, (Uuid, Option<f64>)>(
+            r#"
+            SELECT id, 1 - (embedding_vector <=> $1) AS similarity
+            FROM code_index
+            WHERE file_path LIKE $2
+              AND embedding_vector IS NOT NULL
+              AND 1 - (embedding_vector <=> $1) >= $3
+            ORDER BY embedding_vector <=> $1
+            LIMIT $4
+            "#,
+        )
+        .bind(query_vector)
+        .bind(format!("{}%", workspace_path))
+        .bind(threshold as f64)
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(rows.into_iter()
+            .filter_map(|(id, similarity)| similarity.map(|similarity| (id, similarity as f32)))
+            .collect())
+    }
+
+    /// Mirrors `index.embedding` into the `embedding_vector` column so
+    /// `file_candidates_via_pgvector` has something to query. A no-op when
+    /// the `pgvector` feature isn't compiled in, so `save_indices_to_db`
+    /// doesn't need its own `#[cfg]` branch.
+    #[cfg(feature = "pgvector")]
+    async fn sync_embedding_vector(&self, index_id: Uuid, embedding: &[f32]) -> Result<()> {
+        let vector = pgvector::Vector::from(embedding.to_vec());
+        sqlx::query("UPDATE code_index SET embedding_vector = $1 WHERE id = $2")
+            .bind(vector)
+            .bind(index_id)
+            .execute(&*self.pool)
+            .await?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "pgvector"))]
+    async fn sync_embedding_vector(&self, _index_id: Uuid, _embedding: &[f32]) -> Result<()> {
+        Ok(())
+    }
+
     async fn perform_semantic_search(
         &self,
         query_embedding: &[f32],
@@ -278,30 +578,61 @@ impl SemanticSearchEngine {
     ) -> Result<Vec<SearchResult>> {
         let mut results = Vec::new();
         let similarity_threshold = request.similarity_threshold.unwrap_or(0.7);
+        let similarity_metric = request.similarity_metric.clone().unwrap_or(SimilarityMetric::Cosine);
+
+        // File-level search. When the `pgvector` feature is enabled and the
+        // request is eligible (see `file_candidates_via_pgvector_if_enabled`),
+        // the similarity query and threshold/limit run server-side in
+        // Postgres instead of loading every candidate embedding into memory.
+        let pgvector_candidates = self
+            .file_candidates_via_pgvector_if_enabled(query_embedding, similarity_threshold, request)
+            .await?;
 
-        // File-level search
-        let file_embeddings: Vec<Vec<f32>> = indices.iter()
-            .map(|index| index.embedding.clone())
-            .collect();
+        if let Some(scored) = pgvector_candidates {
+            let by_id: HashMap<Uuid, &CodeIndex> = indices.iter().map(|index| (index.id, index)).collect();
+            for (id, score) in scored {
+                if let Some(index) = by_id.get(&id) {
+                    if self.passes_filters(index, request)? {
+                        results.push(self.create_file_search_result(index, score, processed_query).await?);
+                    }
+                }
+            }
+        } else {
+            let file_embeddings: Vec<Vec<f32>> = indices.iter()
+                .map(|index| index.embedding.clone())
+                .collect();
 
-        if !file_embeddings.is_empty() {
-            let similarity_request = SimilarityRequest {
-                query_embedding: query_embedding.to_vec(),
-                candidate_embeddings: file_embeddings,
-                similarity_metric: SimilarityMetric::Cosine,
-                threshold: Some(similarity_threshold),
-            };
+            if !file_embeddings.is_empty() {
+                // The ANN index is built per workspace, so its candidate ids only
+                // line up with `file_embeddings`'s positions when that's exactly
+                // one workspace's indices (see `all_indices` in `search`).
+                let ann_index = if request.force_exact_search {
+                    None
+                } else if let [workspace_path] = request.workspace_paths.as_slice() {
+                    self.ann_index_cache.read().await.get(workspace_path).cloned()
+                } else {
+                    None
+                };
 
-            let similarity_response = self.embedding_manager.calculate_similarity(similarity_request).await?;
+                let similarity_request = SimilarityRequest {
+                    query_embedding: query_embedding.to_vec(),
+                    candidate_embeddings: file_embeddings,
+                    similarity_metric: similarity_metric.clone(),
+                    threshold: Some(similarity_threshold),
+                    ann_index,
+                };
 
-            // File-level results
-            for &index_idx in &similarity_response.above_threshold {
-                if index_idx < indices.len() {
-                    let index = &indices[index_idx];
-                    let score = similarity_response.scores[index_idx];
-                    
-                    if self.passes_filters(index, request)? {
-                        results.push(self.create_file_search_result(index, score, processed_query).await?);
+                let similarity_response = self.embedding_manager.calculate_similarity(similarity_request).await?;
+
+                // File-level results
+                for &index_idx in &similarity_response.above_threshold {
+                    if index_idx < indices.len() {
+                        let index = &indices[index_idx];
+                        let score = similarity_response.scores[index_idx];
+
+                        if self.passes_filters(index, request)? {
+                            results.push(self.create_file_search_result(index, score, processed_query).await?);
+                        }
                     }
                 }
             }
@@ -321,8 +652,11 @@ impl SemanticSearchEngine {
                 let similarity_request = SimilarityRequest {
                     query_embedding: query_embedding.to_vec(),
                     candidate_embeddings: symbol_embeddings,
-                    similarity_metric: SimilarityMetric::Cosine,
+                    similarity_metric: similarity_metric.clone(),
                     threshold: Some(similarity_threshold),
+                    // Symbol embeddings are indexed per-file, not per-workspace,
+                    // so they're too small in number to be worth an ANN index.
+                    ann_index: None,
                 };
 
                 let similarity_response = self.embedding_manager.calculate_similarity(similarity_request).await?;
@@ -452,6 +786,91 @@ impl SemanticSearchEngine {
         Ok(results)
     }
 
+    /// Runs `SearchQueryType::CodePattern` queries directly against indexed
+    /// file contents instead of going through embeddings. `query` is either:
+    ///
+    /// - a plain regex (e.g. `fn \w+\(.*\) -> Result`), matched as-is, or
+    /// - a structural pattern using `$NAME`-style placeholders (e.g.
+    ///   `fn $NAME($ARGS)`), where each placeholder becomes a wildcard and
+    ///   everything else is matched literally.
+    ///
+    /// The whole scan is bounded by `CODE_PATTERN_SEARCH_TIMEOUT` so a
+    /// pathological pattern or a very large workspace can't hang a request.
+    async fn perform_code_pattern_search(
+        &self,
+        indices: &[CodeIndex],
+        request: &SearchRequest,
+    ) -> Result<Vec<SearchResult>> {
+        let pattern = Self::compile_pattern_query(&request.query)?;
+
+        let scan = async {
+            let mut results = Vec::new();
+
+            for index in indices {
+                if !self.passes_filters(index, request)? {
+                    continue;
+                }
+
+                let content = match tokio::fs::read_to_string(&index.file_path).await {
+                    Ok(content) => content,
+                    Err(_) => continue,
+                };
+
+                for found in pattern.find_iter(&content) {
+                    let start_line = content[..found.start()].matches('\n').count() + 1;
+                    let end_line = content[..found.end()].matches('\n').count() + 1;
+
+                    results.push(SearchResult {
+                        id: Uuid::new_v4(),
+                        file_path: index.file_path.clone(),
+                        content: found.as_str().to_string(),
+                        start_line,
+                        end_line,
+                        relevance_score: 1.0, // Exact pattern match, not a heuristic guess
+                        match_type: MatchType::PatternMatch,
+                        language: index.metadata.language.clone(),
+                        symbol_info: None,
+                        context: self.create_search_context(index, &content).await?,
+                        highlights: Vec::new(),
+                    });
+                }
+            }
+
+            Ok::<_, anyhow::Error>(results)
+        };
+
+        tokio::time::timeout(CODE_PATTERN_SEARCH_TIMEOUT, scan)
+            .await
+            .map_err(|_| anyhow::anyhow!("Code pattern search timed out scanning indexed files"))?
+    }
+
+    /// Translates a `$NAME`-style structural pattern into a regex: each
+    /// `$PLACEHOLDER` becomes a wildcard and the surrounding text is matched
+    /// literally. Patterns with no placeholders are compiled as a plain
+    /// regex instead, so a raw regex or a literal snippet works unchanged.
+    fn compile_pattern_query(query: &str) -> Result<regex::Regex> {
+        let placeholder = regex::Regex::new(r"\$[A-Z_][A-Z0-9_]*").unwrap();
+
+        let pattern = if placeholder.is_match(query) {
+            let mut translated = String::new();
+            let mut last_end = 0;
+            for found in placeholder.find_iter(query) {
+                translated.push_str(&regex::escape(&query[last_end..found.start()]));
+                translated.push_str(r"[\s\S]*?");
+                last_end = found.end();
+            }
+            translated.push_str(&regex::escape(&query[last_end..]));
+            translated
+        } else {
+            query.to_string()
+        };
+
+        regex::RegexBuilder::new(&pattern)
+            .size_limit(1 << 20)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Invalid code pattern '{}': {}", query, e))
+    }
+
     fn passes_filters(&self, index: &CodeIndex, request: &SearchRequest) -> Result<bool> {
         // Language filters
         if !request.language_filters.is_empty() {
@@ -488,10 +907,23 @@ impl SemanticSearchEngine {
     ) -> Result<super::SearchContext> {
         let imports = self.extract_imports(content, &index.metadata.language);
         let dependencies = self.extract_dependencies(&index.file_path).await?;
-        let related_symbols: Vec<String> = index.symbols.iter()
+        let mut related_symbols: Vec<String> = index.symbols.iter()
             .map(|s| s.name.clone())
             .collect();
 
+        // Pull in transitive callers from other files, via the cross-file
+        // symbol graph, so `related_symbols` isn't limited to this file's
+        // own symbols.
+        for symbol in &index.symbols {
+            let node_id = KnowledgeMesh::node_id_for(&index.file_path, &symbol.signature_hash);
+            match self.knowledge_mesh.find_transitive_callers(node_id).await {
+                Ok(callers) => related_symbols.extend(callers.into_iter().map(|node| node.name)),
+                Err(e) => warn!(error = %e, symbol = %symbol.name, "Failed to look up transitive callers"),
+            }
+        }
+        related_symbols.sort();
+        related_symbols.dedup();
+
         let file_summary = format!(
             "{} file with {} symbols, {} lines",
             index.metadata.language,
@@ -637,24 +1069,83 @@ impl SemanticSearchEngine {
         }
     }
 
-    async fn build_ranking_context(&self, request: &SearchRequest) -> Result<Option<RankingContext>> {
-        // Build ranking context from user preferences and workspace context
+    /// Builds the ranking signal `ResultRanker::calculate_final_score` uses to
+    /// boost results in the user's preferred language/complexity and from the
+    /// active project. Returns `None` for anonymous requests (no `user_id`),
+    /// in which case ranking falls back to its context-free scoring.
+    async fn build_ranking_context(
+        &self,
+        request: &SearchRequest,
+        processed_query: &ProcessedQuery,
+    ) -> Result<Option<RankingContext>> {
+        let Some(user_id) = request.user_id else {
+            return Ok(None);
+        };
+
+        let preferences_service = PreferencesService::new((*self.pool).clone());
+        let explanation_level = preferences_service
+            .get_user_preferences(user_id)
+            .await
+            .map(|preferences| preferences.explanation_level)
+            .unwrap_or(ExplanationLevel::Intermediate);
+
+        let preferred_complexity = match explanation_level {
+            ExplanationLevel::Beginner => ComplexityPreference::Simple,
+            ExplanationLevel::Intermediate => ComplexityPreference::Moderate,
+            ExplanationLevel::Advanced => ComplexityPreference::Complex,
+            ExplanationLevel::Expert => ComplexityPreference::Any,
+        };
+
+        // `SearchService::apply_user_preferences` already fills
+        // `language_filters` from the user's saved search preferences when the
+        // request left it empty, so it doubles as the preferred-language
+        // signal here rather than re-querying it.
+        let preferred_languages = request.language_filters.clone();
+        let preferred_file_types = preferred_languages
+            .iter()
+            .map(|language| file_extension_for_language(language).to_string())
+            .collect();
+
         let context = RankingContext {
-            user_preferences: request.user_id.map(|_| UserPreferences {
-                preferred_languages: vec!["rust".to_string(), "python".to_string()],
-                recent_files: Vec::new(),
-                coding_style: "functional".to_string(),
-            }),
-            workspace_context: Some(WorkspaceContext {
-                current_file: request.file_path.clone(),
-                project_type: "web".to_string(),
-                dependencies: Vec::new(),
-            }),
+            query_intent: processed_query.intent.clone(),
+            user_preferences: UserPreferences {
+                preferred_languages,
+                preferred_complexity,
+                preferred_file_types,
+                boost_recent_files: true,
+                boost_frequently_accessed: true,
+            },
+            workspace_context: WorkspaceContext {
+                current_project_languages: self.workspace_languages(&request.workspace_paths).await,
+                recently_modified_files: Vec::new(),
+                frequently_accessed_files: Vec::new(),
+                project_patterns: Vec::new(),
+            },
             search_history: Vec::new(),
         };
         Ok(Some(context))
     }
 
+    /// Languages present in the already-indexed workspaces, most common
+    /// first, used as the "active project" signal in `RankingContext`.
+    async fn workspace_languages(&self, workspace_paths: &[String]) -> Vec<String> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        {
+            let cache = self.index_cache.read().await;
+            for workspace_path in workspace_paths {
+                if let Some(indices) = cache.get(workspace_path) {
+                    for index in indices {
+                        *counts.entry(index.metadata.language.clone()).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        let mut languages: Vec<(String, usize)> = counts.into_iter().collect();
+        languages.sort_by(|a, b| b.1.cmp(&a.1));
+        languages.into_iter().map(|(language, _)| language).collect()
+    }
+
     async fn generate_suggestions(
         &self,
         request: &SearchRequest,
@@ -734,7 +1225,11 @@ impl SemanticSearchEngine {
         filters
     }
 
-    pub async fn reindex_workspace(&self, workspace_path: &str) -> Result<()> {
+    /// Full reindex: drops every index row under `workspace_path` and
+    /// rebuilds from scratch. Reports the same [`ReindexStats`] shape as
+    /// [`Self::index_workspace_incremental`] (with every file counted as
+    /// added) so callers can treat both modes identically.
+    pub async fn reindex_workspace(&self, workspace_path: &str) -> Result<ReindexStats> {
         info!("Reindexing workspace: {}", workspace_path);
 
         // Cache'den kaldır
@@ -744,7 +1239,7 @@ impl SemanticSearchEngine {
         }
 
         // Veritabanından eski index'leri sil
-        sqlx::query!(
+        let deleted = sqlx::query!(
             "DELETE FROM code_index WHERE file_path LIKE $1",
             format!("{}%", workspace_path)
         )
@@ -752,9 +1247,93 @@ impl SemanticSearchEngine {
         .await?;
 
         // Yeniden index'le
-        self.get_or_create_indices(workspace_path).await?;
+        let (indices, files_ignored) = self.get_or_create_indices(workspace_path).await?;
+        self.invalidate_search_cache().await;
 
         info!("Workspace reindexing completed: {}", workspace_path);
+        Ok(ReindexStats {
+            files_added: indices.len(),
+            files_updated: 0,
+            files_removed: deleted.rows_affected() as usize,
+            files_skipped: 0,
+            files_ignored,
+        })
+    }
+
+    /// Incremental counterpart to `reindex_workspace`. Instead of dropping
+    /// every index for the workspace and rebuilding from scratch, this
+    /// compares the current file set and content hashes against what's
+    /// already in the DB (via `CodeIndex::needs_reindexing`) and only
+    /// re-embeds files that were added or changed, leaving unchanged files
+    /// untouched. Index rows for files that no longer exist on disk are
+    /// deleted.
+    pub async fn index_workspace_incremental(&self, workspace_path: &str) -> Result<ReindexStats> {
+        info!("Incrementally reindexing workspace: {}", workspace_path);
+
+        let mut existing_by_path: HashMap<String, CodeIndex> = self
+            .load_indices_from_db(workspace_path)
+            .await?
+            .into_iter()
+            .map(|index| (index.file_path.clone(), index))
+            .collect();
+
+        let discovered = self.code_indexer.discover_workspace_files(workspace_path).await?;
+        let mut stats = ReindexStats {
+            files_ignored: discovered.ignored,
+            ..Default::default()
+        };
+        let mut indices = Vec::with_capacity(discovered.files.len());
+
+        for file_path in &discovered.files {
+            match existing_by_path.remove(file_path) {
+                Some(existing) => {
+                    let current_hash = self.code_indexer.content_hash(file_path).await?;
+                    match classify_file(&existing, &current_hash) {
+                        FileIndexAction::Changed => {
+                            let updated = self.code_indexer.update_index(&existing).await?;
+                            self.save_indices_to_db(std::slice::from_ref(&updated)).await?;
+                            indices.push(updated);
+                            stats.files_updated += 1;
+                        }
+                        FileIndexAction::Unchanged => {
+                            indices.push(existing);
+                            stats.files_skipped += 1;
+                        }
+                    }
+                }
+                None => {
+                    let new_index = self.code_indexer.index_file(file_path).await?;
+                    self.save_indices_to_db(std::slice::from_ref(&new_index)).await?;
+                    indices.push(new_index);
+                    stats.files_added += 1;
+                }
+            }
+        }
+
+        // Anything left in `existing_by_path` was indexed before but no
+        // longer has a matching file on disk.
+        for stale in existing_by_path.into_values() {
+            self.delete_index_from_db(stale.id).await?;
+            stats.files_removed += 1;
+        }
+
+        self.cache_indices(workspace_path, indices).await;
+        self.invalidate_search_cache().await;
+
+        info!(
+            "Incremental reindex of {} complete: {} added, {} updated, {} removed, {} skipped",
+            workspace_path, stats.files_added, stats.files_updated, stats.files_removed, stats.files_skipped
+        );
+
+        Ok(stats)
+    }
+
+    async fn delete_index_from_db(&self, index_id: Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM code_index WHERE id = $1")
+            .bind(index_id)
+            .execute(&*self.pool)
+            .await?;
+
         Ok(())
     }
 
@@ -783,6 +1362,23 @@ impl SemanticSearchEngine {
     }
 }
 
+/// Whether a file already in `code_index` needs re-embedding, decided
+/// purely from content hashes so `index_workspace_incremental`'s decision
+/// logic can be unit-tested without a database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileIndexAction {
+    Unchanged,
+    Changed,
+}
+
+fn classify_file(existing: &CodeIndex, current_hash: &str) -> FileIndexAction {
+    if existing.needs_reindexing(current_hash) {
+        FileIndexAction::Changed
+    } else {
+        FileIndexAction::Unchanged
+    }
+}
+
 // Helper functions for semantic analysis
 fn extract_documentation(content: &str, line: usize) -> Option<String> {
     let lines: Vec<&str> = content.lines().collect();
@@ -816,6 +1412,22 @@ fn extract_documentation(content: &str, line: usize) -> Option<String> {
     }
 }
 
+fn file_extension_for_language(language: &str) -> &str {
+    match language {
+        "rust" => "rs",
+        "python" => "py",
+        "javascript" => "js",
+        "typescript" => "ts",
+        "java" => "java",
+        "go" => "go",
+        "cpp" | "c++" => "cpp",
+        "c" => "c",
+        "csharp" | "c#" => "cs",
+        "php" => "php",
+        _ => language,
+    }
+}
+
 fn parse_function_parameters(content: &str, line: usize) -> Vec<String> {
     let lines: Vec<&str> = content.lines().collect();
     if line >= lines.len() {
@@ -872,27 +1484,139 @@ fn determine_visibility(content: &str, line: usize) -> super::Visibility {
     }
 }
 
-// Additional structs for ranking context
-#[derive(Debug, Clone)]
-struct UserPreferences {
-    preferred_languages: Vec<String>,
-    recent_files: Vec<String>,
-    coding_style: String,
-}
-
-#[derive(Debug, Clone)]
-struct WorkspaceContext {
-    current_file: Option<String>,
-    project_type: String,
-    dependencies: Vec<String>,
-}
-
-// RankingContext moved to avoid duplicate definition
-
 #[derive(Debug, Clone)]
 pub struct IndexStats {
     pub total_files: i64,
     pub total_symbols: i64,
     pub languages_count: i64,
     pub avg_complexity: f32,
+}
+
+/// Outcome of `SemanticSearchEngine::index_workspace_incremental`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReindexStats {
+    pub files_added: usize,
+    pub files_updated: usize,
+    pub files_removed: usize,
+    pub files_skipped: usize,
+    /// Reason breakdown (e.g. `"gitignored"`, `"too_large"`, `"binary"`) for
+    /// files `CodeIndexer` discovery excluded, from `DiscoveredFiles::ignored`.
+    #[serde(default)]
+    pub files_ignored: HashMap<String, usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::search::FileFilter;
+
+    async fn test_indexer() -> CodeIndexer {
+        let config = Arc::new(Config::from_env().expect("Config::from_env should always succeed with defaults"));
+        let router = crate::providers::ProviderRouter::new(config)
+            .await
+            .expect("ProviderRouter::new should always succeed");
+        CodeIndexer::new(Arc::new(EmbeddingManager::new(Arc::new(router))))
+    }
+
+    fn sample_code_index(file_path: &str, content_hash: &str) -> CodeIndex {
+        let now = chrono::Utc::now();
+        CodeIndex {
+            id: Uuid::new_v4(),
+            file_path: file_path.to_string(),
+            content_hash: content_hash.to_string(),
+            embedding: Vec::new(),
+            symbols: Vec::new(),
+            metadata: IndexMetadata {
+                language: "rust".to_string(),
+                file_size: 0,
+                line_count: 0,
+                symbol_count: 0,
+                complexity_score: 0.0,
+                quality_score: 0.0,
+                tags: Vec::new(),
+                categories: Vec::new(),
+                embedding_model: String::new(),
+            },
+            indexed_at: now,
+            last_updated: now,
+        }
+    }
+
+    #[tokio::test]
+    async fn classify_file_detects_a_change_between_incremental_runs() {
+        let indexer = test_indexer().await;
+        let dir = std::env::temp_dir().join(format!("incremental_index_test_{}", Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let file_path = dir.join("lib.rs");
+        let file_path = file_path.to_str().unwrap();
+
+        tokio::fs::write(file_path, "fn original() {}").await.unwrap();
+        let original_hash = indexer.content_hash(file_path).await.unwrap();
+        let existing = sample_code_index(file_path, &original_hash);
+
+        // Re-checking before the file changes should report no work to do.
+        let unchanged_hash = indexer.content_hash(file_path).await.unwrap();
+        assert_eq!(classify_file(&existing, &unchanged_hash), FileIndexAction::Unchanged);
+
+        // A real edit between runs should be picked up via the content hash.
+        tokio::fs::write(file_path, "fn changed() { /* now different */ }").await.unwrap();
+        let changed_hash = indexer.content_hash(file_path).await.unwrap();
+        assert_eq!(classify_file(&existing, &changed_hash), FileIndexAction::Changed);
+
+        tokio::fs::remove_dir_all(&dir).await.ok();
+    }
+
+    fn sample_request(query: &str) -> SearchRequest {
+        SearchRequest {
+            query: query.to_string(),
+            query_type: SearchQueryType::NaturalLanguage,
+            workspace_paths: vec!["/workspace/a".to_string(), "/workspace/b".to_string()],
+            file_filters: vec![FileFilter { pattern: "*.rs".to_string(), include: true }],
+            language_filters: vec!["rust".to_string()],
+            max_results: Some(20),
+            similarity_threshold: Some(0.7),
+            include_context: true,
+            force_exact_search: false,
+            similarity_metric: None,
+            user_id: None,
+        }
+    }
+
+    #[test]
+    fn cache_key_is_stable_and_order_insensitive() {
+        let mut request = sample_request("find the auth handler");
+        let key = SemanticSearchEngine::cache_key(&request);
+
+        // Re-hashing the same request produces the same key.
+        assert_eq!(key, SemanticSearchEngine::cache_key(&sample_request("find the auth handler")));
+
+        // Case and surrounding whitespace shouldn't change the key.
+        assert_eq!(key, SemanticSearchEngine::cache_key(&sample_request("  Find The Auth Handler  ")));
+
+        // Workspace path order shouldn't change the key.
+        request.workspace_paths.reverse();
+        assert_eq!(key, SemanticSearchEngine::cache_key(&request));
+    }
+
+    #[test]
+    fn cache_key_changes_with_filters() {
+        let base = sample_request("find the auth handler");
+
+        let mut different_threshold = base.clone();
+        different_threshold.similarity_threshold = Some(0.9);
+        assert_ne!(SemanticSearchEngine::cache_key(&base), SemanticSearchEngine::cache_key(&different_threshold));
+
+        let mut different_workspace = base.clone();
+        different_workspace.workspace_paths = vec!["/workspace/c".to_string()];
+        assert_ne!(SemanticSearchEngine::cache_key(&base), SemanticSearchEngine::cache_key(&different_workspace));
+
+        let mut different_query = base.clone();
+        different_query.query = "find the logging middleware".to_string();
+        assert_ne!(SemanticSearchEngine::cache_key(&base), SemanticSearchEngine::cache_key(&different_query));
+
+        let mut different_metric = base.clone();
+        different_metric.similarity_metric = Some(SimilarityMetric::Euclidean);
+        assert_ne!(SemanticSearchEngine::cache_key(&base), SemanticSearchEngine::cache_key(&different_metric));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,75 @@
+use hnsw_rs::prelude::*;
+
+/// Approximate-nearest-neighbour index over a fixed set of embeddings,
+/// used to avoid a brute-force O(N) cosine scan in
+/// [`super::semantic_engine::SemanticSearchEngine`] and
+/// [`super::embedding_manager::EmbeddingManager::calculate_similarity`].
+///
+/// Candidate ids are just positions into the `Vec<Vec<f32>>` the index was
+/// built from, so callers can use the ids returned by [`VectorIndex::search`]
+/// to index straight back into their original embedding list for exact
+/// re-ranking.
+pub struct VectorIndex {
+    hnsw: Hnsw<'static, f32, DistCosine>,
+    len: usize,
+}
+
+impl std::fmt::Debug for VectorIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VectorIndex").field("len", &self.len).finish()
+    }
+}
+
+/// Neighbours stored per layer; keep modest since workspaces are typically
+/// a few thousand files, not millions.
+const MAX_NB_CONNECTION: usize = 16;
+const MAX_LAYER: usize = 16;
+const EF_CONSTRUCTION: usize = 200;
+const EF_SEARCH_MULTIPLIER: usize = 4;
+
+impl VectorIndex {
+    /// Builds a new index from `embeddings`. The index is immutable once
+    /// built; callers rebuild it wholesale (see
+    /// `SemanticSearchEngine::get_or_create_indices`) rather than mutating
+    /// it in place, since workspaces are already re-indexed as a unit.
+    pub fn build(embeddings: &[Vec<f32>]) -> Self {
+        let hnsw = Hnsw::new(
+            MAX_NB_CONNECTION,
+            embeddings.len().max(1),
+            MAX_LAYER,
+            EF_CONSTRUCTION,
+            DistCosine {},
+        );
+        for (id, embedding) in embeddings.iter().enumerate() {
+            hnsw.insert((embedding.as_slice(), id));
+        }
+        Self {
+            hnsw,
+            len: embeddings.len(),
+        }
+    }
+
+    /// Returns up to `top_k` approximate nearest neighbours of `query` as
+    /// `(candidate_id, cosine_similarity)`, sorted best-first. `candidate_id`
+    /// is the position the candidate had in the slice passed to
+    /// [`VectorIndex::build`].
+    pub fn search(&self, query: &[f32], top_k: usize) -> Vec<(usize, f32)> {
+        if self.len == 0 || top_k == 0 {
+            return Vec::new();
+        }
+        let ef_search = (top_k * EF_SEARCH_MULTIPLIER).max(EF_CONSTRUCTION);
+        self.hnsw
+            .search(query, top_k, ef_search)
+            .into_iter()
+            .map(|neighbour| (neighbour.d_id, 1.0 - neighbour.distance))
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+}
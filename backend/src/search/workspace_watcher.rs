@@ -0,0 +1,201 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use notify::RecursiveMode;
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use super::search_service::{IndexMode, SearchService};
+
+/// Paths are ignored if any component matches one of these, on top of
+/// whatever the workspace's own `.gitignore` excludes — these three are
+/// common enough (and large/noisy enough to churn the debouncer) that they're
+/// skipped unconditionally, the same way editors like VS Code hardcode them.
+const ALWAYS_IGNORED_COMPONENTS: [&str; 3] = [".git", "target", "node_modules"];
+
+/// How long to wait after the last filesystem event before reindexing, so a
+/// burst of saves (e.g. a branch checkout or a formatter rewriting a file)
+/// collapses into a single incremental reindex instead of one per event.
+const DEBOUNCE_WINDOW: Duration = Duration::from_secs(2);
+
+/// Snapshot of a workspace watcher's state, returned alongside
+/// `IndexStats` from `GET /search/stats/:workspace_path`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatcherStatus {
+    pub active: bool,
+    pub started_at: DateTime<Utc>,
+    pub last_event_at: Option<DateTime<Utc>>,
+    pub last_reindex_at: Option<DateTime<Utc>>,
+    pub reindex_count: u64,
+    pub last_error: Option<String>,
+}
+
+impl WatcherStatus {
+    fn new() -> Self {
+        Self {
+            active: true,
+            started_at: Utc::now(),
+            last_event_at: None,
+            last_reindex_at: None,
+            reindex_count: 0,
+            last_error: None,
+        }
+    }
+}
+
+/// A live watch on one workspace. Dropping `debouncer` is the only way to
+/// stop its background thread, so it's kept alive here for as long as the
+/// entry stays in the registry.
+struct WatchEntry {
+    debouncer: Debouncer<notify::RecommendedWatcher>,
+    status: Arc<tokio::sync::RwLock<WatcherStatus>>,
+}
+
+/// Tracks per-workspace file watchers that feed changed paths into
+/// `SearchService::index_workspace` (incremental mode), so
+/// `POST /search/watch` / `DELETE /search/watch` can enable or disable
+/// auto-reindexing without either endpoint holding a handle to the
+/// background debouncer task itself — mirrors `IndexingJobRegistry`.
+#[derive(Clone, Default)]
+pub struct WorkspaceWatcherRegistry {
+    watchers: Arc<DashMap<String, WatchEntry>>,
+}
+
+impl WorkspaceWatcherRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts watching `workspace_path`, debouncing filesystem events and
+    /// triggering an incremental reindex (as `user_id`) whenever they settle.
+    /// Idempotent: watching an already-watched workspace is a no-op.
+    pub fn start_watching(
+        &self,
+        workspace_path: String,
+        user_id: Uuid,
+        search_service: Arc<SearchService>,
+    ) -> Result<()> {
+        if self.watchers.contains_key(&workspace_path) {
+            return Ok(());
+        }
+
+        let gitignore = load_gitignore(&workspace_path);
+        let status = Arc::new(tokio::sync::RwLock::new(WatcherStatus::new()));
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<DebounceEventResult>();
+        let mut debouncer = new_debouncer(DEBOUNCE_WINDOW, move |result: DebounceEventResult| {
+            let _ = tx.send(result);
+        })?;
+        debouncer
+            .watcher()
+            .watch(Path::new(&workspace_path), RecursiveMode::Recursive)?;
+
+        let task_workspace_path = workspace_path.clone();
+        let task_status = status.clone();
+        tokio::spawn(async move {
+            while let Some(result) = rx.recv().await {
+                let events = match result {
+                    Ok(events) => events,
+                    Err(e) => {
+                        warn!("Watcher error for workspace {}: {}", task_workspace_path, e);
+                        task_status.write().await.last_error = Some(e.to_string());
+                        continue;
+                    }
+                };
+
+                let relevant = events
+                    .iter()
+                    .any(|event| !is_ignored(&event.path, &gitignore));
+                if !relevant {
+                    continue;
+                }
+
+                task_status.write().await.last_event_at = Some(Utc::now());
+
+                match search_service
+                    .index_workspace(&task_workspace_path, user_id, IndexMode::Incremental)
+                    .await
+                {
+                    Ok(_) => {
+                        info!("Auto-reindexed workspace after file change: {}", task_workspace_path);
+                        let mut status = task_status.write().await;
+                        status.last_reindex_at = Some(Utc::now());
+                        status.reindex_count += 1;
+                        status.last_error = None;
+                    }
+                    Err(e) => {
+                        error!("Auto-reindex failed for workspace {}: {}", task_workspace_path, e);
+                        task_status.write().await.last_error = Some(e.to_string());
+                    }
+                }
+            }
+        });
+
+        self.watchers.insert(workspace_path, WatchEntry { debouncer, status });
+        Ok(())
+    }
+
+    /// Stops watching `workspace_path`, dropping its debouncer. Returns
+    /// `false` if it wasn't being watched.
+    pub fn stop_watching(&self, workspace_path: &str) -> bool {
+        self.watchers.remove(workspace_path).is_some()
+    }
+
+    pub async fn status(&self, workspace_path: &str) -> Option<WatcherStatus> {
+        let status = self.watchers.get(workspace_path)?.status.clone();
+        let snapshot = status.read().await.clone();
+        Some(snapshot)
+    }
+}
+
+fn load_gitignore(workspace_path: &str) -> ignore::gitignore::Gitignore {
+    let (gitignore, error) = ignore::gitignore::Gitignore::new(Path::new(workspace_path).join(".gitignore"));
+    if let Some(error) = error {
+        warn!("Failed to fully parse .gitignore for {}: {}", workspace_path, error);
+    }
+    gitignore
+}
+
+/// Whether a changed path should be skipped: it's under `.git`, `target`, or
+/// `node_modules`, or it's matched by the workspace's `.gitignore`.
+fn is_ignored(path: &Path, gitignore: &ignore::gitignore::Gitignore) -> bool {
+    if path
+        .components()
+        .any(|component| ALWAYS_IGNORED_COMPONENTS.contains(&component.as_os_str().to_string_lossy().as_ref()))
+    {
+        return true;
+    }
+
+    gitignore.matched(path, path.is_dir()).is_ignore()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ignore::gitignore::GitignoreBuilder;
+
+    #[test]
+    fn hardcoded_directories_are_always_ignored() {
+        let gitignore = ignore::gitignore::Gitignore::empty();
+        assert!(is_ignored(Path::new("/ws/.git/HEAD"), &gitignore));
+        assert!(is_ignored(Path::new("/ws/target/debug/app"), &gitignore));
+        assert!(is_ignored(Path::new("/ws/frontend/node_modules/x.js"), &gitignore));
+        assert!(!is_ignored(Path::new("/ws/src/main.rs"), &gitignore));
+    }
+
+    #[test]
+    fn gitignore_patterns_are_respected() {
+        let mut builder = GitignoreBuilder::new("/ws");
+        builder.add_line(None, "*.log").unwrap();
+        let gitignore = builder.build().unwrap();
+
+        assert!(is_ignored(Path::new("/ws/debug.log"), &gitignore));
+        assert!(!is_ignored(Path::new("/ws/src/main.rs"), &gitignore));
+    }
+}
@@ -1,62 +1,218 @@
 // P0 Task #2: Security guardrails implementation
 use axum::{
-    extract::Request,
+    extract::{FromRequestParts, Request, State},
     http::{HeaderName, HeaderValue, StatusCode},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
+};
+use base64::Engine as _;
+use governor::{
+    clock::{Clock, DefaultClock},
+    DefaultDirectRateLimiter, Quota, RateLimiter,
 };
 use headers::{Header, HeaderMapExt};
+use rand::RngCore;
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::RwLock;
 use tower_governor::{
     governor::GovernorConfigBuilder, key_extractor::SmartIpKeyExtractor, GovernorLayer,
 };
 use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::auth::organization::OrganizationService;
+use crate::auth::AuthContext;
+
+/// Per-user token bucket rate limiter, keyed on `Uuid` rather than IP.
+///
+/// `create_rate_limit_layer`'s `SmartIpKeyExtractor` buckets by IP, so users
+/// behind the same NAT/proxy share a bucket. This limiter is applied as a
+/// separate [`axum::middleware::from_fn_with_state`] layer *after* auth so it
+/// can key on `AuthContext::user.id` instead, falling back to no-op for
+/// unauthenticated (public) requests, which remain covered by the IP limiter.
+#[derive(Clone)]
+pub struct UserRateLimiter {
+    limiters: Arc<RwLock<HashMap<Uuid, Arc<DefaultDirectRateLimiter>>>>,
+}
+
+impl UserRateLimiter {
+    pub fn new() -> Self {
+        Self {
+            limiters: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Checks and consumes one request of the given user's tier-based quota,
+    /// creating their bucket on first use. Returns `Err(retry_after)` once the
+    /// bucket is exhausted.
+    async fn check(&self, user_id: Uuid, requests_per_minute: u32) -> Result<(), Duration> {
+        if let Some(limiter) = self.limiters.read().await.get(&user_id) {
+            return limiter.check().map_err(|not_until| {
+                not_until.wait_time_from(DefaultClock::default().now())
+            });
+        }
+
+        let quota = Quota::per_minute(NonZeroU32::new(requests_per_minute.max(1)).unwrap());
+        let limiter = Arc::new(RateLimiter::direct(quota));
+        self.limiters
+            .write()
+            .await
+            .insert(user_id, limiter.clone());
+
+        limiter
+            .check()
+            .map_err(|not_until| not_until.wait_time_from(DefaultClock::default().now()))
+    }
+}
+
+impl Default for UserRateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// State for [`user_rate_limit_middleware`], kept separate from the
+/// application's own `AppState` so this module has no dependency on the
+/// binary crate root.
+#[derive(Clone)]
+pub struct RateLimitState {
+    pub limiter: UserRateLimiter,
+    pub organizations: Arc<OrganizationService>,
+}
+
+/// Tier-based per-user request budget, in case an organization has none
+/// configured yet (e.g. created before `api_rate_limits` existed).
+const DEFAULT_REQUESTS_PER_MINUTE: u32 = 60;
+
+/// Per-user rate limiting middleware. Runs after `auth_middleware_wrapper`, so
+/// an `AuthContext` extension is present for authenticated requests; public
+/// routes have none and pass through untouched, relying on the IP-based
+/// [`create_rate_limit_layer`] instead.
+pub async fn user_rate_limit_middleware(
+    State(state): State<RateLimitState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let Some(auth_context) = request.extensions().get::<AuthContext>() else {
+        return Ok(next.run(request).await);
+    };
+
+    let user_id = auth_context.user.id;
+    let requests_per_minute = state
+        .organizations
+        .get_organization(auth_context.user.organization_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|org| org.settings.api_rate_limits.requests_per_minute)
+        .unwrap_or(DEFAULT_REQUESTS_PER_MINUTE);
+
+    match state.limiter.check(user_id, requests_per_minute).await {
+        Ok(()) => Ok(next.run(request).await),
+        Err(retry_after) => {
+            warn!(user_id = %user_id, "Per-user rate limit exceeded");
+            let mut response = StatusCode::TOO_MANY_REQUESTS.into_response();
+            if let Ok(value) = HeaderValue::from_str(&retry_after.as_secs().max(1).to_string()) {
+                response
+                    .headers_mut()
+                    .insert(HeaderName::from_static("retry-after"), value);
+            }
+            Ok(response)
+        }
+    }
+}
+
+/// Per-request CSP nonce, generated in [`security_headers_middleware`] and
+/// stashed in request extensions so handlers can embed it into any inline
+/// `<script nonce="...">`/`<style nonce="...">` they render for the Tauri
+/// frontend. Extract it with `CspNonce` like any other axum extractor.
+#[derive(Debug, Clone)]
+pub struct CspNonce(pub String);
+
+impl<S> FromRequestParts<S> for CspNonce
+where
+    S: Send + Sync,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CspNonce>()
+            .cloned()
+            .ok_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+/// `base64(rand_bytes(16))`, unique per request -- this is what lets the CSP
+/// allow specific inline `<script>`/`<style>` tags instead of blanket
+/// `'unsafe-inline'`.
+fn generate_csp_nonce() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
 
 /// Security headers middleware for P0 compliance
 pub async fn security_headers_middleware(
-    request: Request,
+    mut request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
+    let nonce = generate_csp_nonce();
+    request.extensions_mut().insert(CspNonce(nonce.clone()));
+
     let mut response = next.run(request).await;
-    
+
     let headers = response.headers_mut();
-    
+
     // X-Content-Type-Options: nosniff
     headers.insert(
         HeaderName::from_static("x-content-type-options"),
         HeaderValue::from_static("nosniff"),
     );
-    
+
     // X-Frame-Options: DENY
     headers.insert(
         HeaderName::from_static("x-frame-options"),
         HeaderValue::from_static("DENY"),
     );
-    
+
     // Referrer-Policy: no-referrer
     headers.insert(
         HeaderName::from_static("referrer-policy"),
         HeaderValue::from_static("no-referrer"),
     );
-    
-    // Content-Security-Policy: minimal CSP
+
+    // Content-Security-Policy: per-request nonce instead of 'unsafe-inline',
+    // with CSP violations reported to `POST /csp-report` for the audit log.
+    let csp = format!(
+        "default-src 'self'; script-src 'self' 'nonce-{nonce}'; style-src 'self' 'nonce-{nonce}'; object-src 'none'; report-uri /csp-report;"
+    );
     headers.insert(
         HeaderName::from_static("content-security-policy"),
-        HeaderValue::from_static("default-src 'self'; script-src 'self'; style-src 'self' 'unsafe-inline'; object-src 'none';"),
+        HeaderValue::from_str(&csp).unwrap_or_else(|_| {
+            HeaderValue::from_static("default-src 'self'; object-src 'none';")
+        }),
     );
-    
+
     // X-Permitted-Cross-Domain-Policies: none
     headers.insert(
         HeaderName::from_static("x-permitted-cross-domain-policies"),
         HeaderValue::from_static("none"),
     );
-    
+
     // Permissions-Policy: minimal permissions
     headers.insert(
         HeaderName::from_static("permissions-policy"),
         HeaderValue::from_static("geolocation=(), microphone=(), camera=()"),
     );
-    
+
     info!("Security headers applied to response");
     Ok(response)
 }
@@ -0,0 +1,237 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::warn;
+use tree_sitter::{Language, Node, Parser, Tree};
+
+// Tree-sitter language declarations, mirroring `ai_engine::code_smell_detector`.
+extern "C" {
+    fn tree_sitter_python() -> Language;
+    fn tree_sitter_javascript() -> Language;
+    fn tree_sitter_typescript() -> Language;
+    fn tree_sitter_rust() -> Language;
+    fn tree_sitter_go() -> Language;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolLocation {
+    pub name: String,
+    pub kind: String,
+    pub line: usize,
+    pub complexity_score: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentationCoverage {
+    pub total_public_symbols: usize,
+    pub documented_symbols: usize,
+    pub coverage_percent: f32,
+    /// Undocumented public symbols, sorted by `complexity_score` descending
+    /// so the functions most worth documenting surface first.
+    pub undocumented: Vec<SymbolLocation>,
+}
+
+/// Scores how much of a file's public API carries a doc comment: functions,
+/// methods, structs, enums, and traits, checked against a preceding
+/// triple-slash comment (Rust), JSDoc block (JS/TS), or docstring (Python).
+/// A heuristic pass like `ai_engine::code_smell_detector`, not a full
+/// semantic analysis.
+pub struct DocumentationCoverageScorer {
+    parsers: HashMap<String, Parser>,
+}
+
+impl DocumentationCoverageScorer {
+    pub fn new() -> Result<Self> {
+        let mut parsers = HashMap::new();
+        Self::try_init_parser(&mut parsers, "python", unsafe { tree_sitter_python() });
+        Self::try_init_parser(&mut parsers, "javascript", unsafe { tree_sitter_javascript() });
+        Self::try_init_parser(&mut parsers, "typescript", unsafe { tree_sitter_typescript() });
+        Self::try_init_parser(&mut parsers, "rust", unsafe { tree_sitter_rust() });
+        Self::try_init_parser(&mut parsers, "go", unsafe { tree_sitter_go() });
+
+        Ok(Self { parsers })
+    }
+
+    fn try_init_parser(parsers: &mut HashMap<String, Parser>, language: &str, ts_language: Language) {
+        let mut parser = Parser::new();
+        match parser.set_language(ts_language) {
+            Ok(()) => {
+                parsers.insert(language.to_string(), parser);
+            }
+            Err(e) => warn!("Failed to initialize tree-sitter parser for {}: {}", language, e),
+        }
+    }
+
+    /// Returns empty-but-zeroed coverage (rather than an error) for languages
+    /// without a registered parser, matching `CodeSmellDetector::analyze`'s
+    /// "can't analyze this language" handling.
+    pub fn score(&mut self, source: &str, language: &str) -> Result<DocumentationCoverage> {
+        let Some(parser) = self.parsers.get_mut(language) else {
+            warn!("No tree-sitter parser for language '{}', skipping doc coverage scoring", language);
+            return Ok(DocumentationCoverage {
+                total_public_symbols: 0,
+                documented_symbols: 0,
+                coverage_percent: 100.0,
+                undocumented: Vec::new(),
+            });
+        };
+
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| anyhow!("Failed to parse source as {}", language))?;
+
+        let public_symbols = collect_public_symbols(&tree, source, language);
+        let total_public_symbols = public_symbols.len();
+
+        let mut undocumented: Vec<SymbolLocation> = public_symbols
+            .into_iter()
+            .filter(|symbol| !symbol.documented)
+            .map(|symbol| SymbolLocation {
+                name: symbol.name,
+                kind: symbol.kind,
+                line: symbol.line,
+                complexity_score: complexity_score(symbol.node, source),
+            })
+            .collect();
+
+        undocumented.sort_by(|a, b| b.complexity_score.partial_cmp(&a.complexity_score).unwrap());
+
+        let documented_symbols = total_public_symbols - undocumented.len();
+        let coverage_percent = if total_public_symbols == 0 {
+            100.0
+        } else {
+            (documented_symbols as f32 / total_public_symbols as f32) * 100.0
+        };
+
+        Ok(DocumentationCoverage {
+            total_public_symbols,
+            documented_symbols,
+            coverage_percent,
+            undocumented,
+        })
+    }
+}
+
+struct PublicSymbol<'a> {
+    name: String,
+    kind: String,
+    line: usize,
+    documented: bool,
+    node: Node<'a>,
+}
+
+fn is_documentable_kind(kind: &str, language: &str) -> bool {
+    match language {
+        "rust" => matches!(kind, "function_item" | "struct_item" | "enum_item" | "trait_item") || kind == "function_signature_item",
+        "python" => matches!(kind, "function_definition" | "class_definition"),
+        "javascript" | "typescript" => {
+            matches!(kind, "function_declaration" | "class_declaration" | "method_definition")
+        }
+        "go" => matches!(kind, "function_declaration" | "method_declaration" | "type_declaration"),
+        _ => false,
+    }
+}
+
+fn is_public(node: Node, source: &str, language: &str) -> bool {
+    match language {
+        "rust" => node
+            .children(&mut node.walk())
+            .any(|child| child.kind() == "visibility_modifier"),
+        "go" => symbol_name(node, source)
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_uppercase()),
+        "python" => !symbol_name(node, source).starts_with('_'),
+        // JS/TS export-ness depends on the parent statement (`export function
+        // foo() {}`), which a pure node-kind check can't see cheaply; treat
+        // every declaration as part of the public surface to document.
+        "javascript" | "typescript" => true,
+        _ => true,
+    }
+}
+
+fn symbol_name(node: Node, source: &str) -> String {
+    node.child_by_field_name("name")
+        .map(|n| n.utf8_text(source.as_bytes()).unwrap_or("").to_string())
+        .unwrap_or_else(|| format!("<anonymous @ line {}>", node.start_position().row + 1))
+}
+
+/// Walks backwards from `node` over its preceding siblings, skipping blank
+/// space, to see whether the first non-blank thing immediately above it is a
+/// doc comment in the target language's convention.
+fn has_doc_comment(node: Node, source: &str, language: &str) -> bool {
+    let mut sibling = node.prev_sibling();
+    while let Some(current) = sibling {
+        let text = current.utf8_text(source.as_bytes()).unwrap_or("");
+        if current.kind() == "line_comment" || current.kind() == "comment" {
+            return match language {
+                "rust" => text.trim_start().starts_with("///") || text.trim_start().starts_with("/**"),
+                "javascript" | "typescript" => text.trim_start().starts_with("/**"),
+                "go" => true,
+                _ => true,
+            };
+        }
+        // Anything else directly adjacent (e.g. an attribute in Rust) breaks
+        // the "immediately preceding" chain for everything but attributes,
+        // which we skip over to keep looking.
+        if language == "rust" && current.kind() == "attribute_item" {
+            sibling = current.prev_sibling();
+            continue;
+        }
+        break;
+    }
+
+    if language == "python" {
+        return python_has_docstring(node, source);
+    }
+
+    false
+}
+
+/// Python docstrings live *inside* the body as its first statement, not as a
+/// preceding sibling comment, so they need their own check.
+fn python_has_docstring(node: Node, source: &str) -> bool {
+    let Some(body) = node.child_by_field_name("body") else { return false };
+    let Some(first_statement) = body.named_child(0) else { return false };
+    first_statement.kind() == "expression_statement"
+        && first_statement
+            .named_child(0)
+            .is_some_and(|expr| expr.kind() == "string")
+}
+
+fn collect_public_symbols<'a>(tree: &'a Tree, source: &'a str, language: &str) -> Vec<PublicSymbol<'a>> {
+    let mut symbols = Vec::new();
+    let mut cursor = tree.root_node().walk();
+    let mut stack = vec![tree.root_node()];
+
+    while let Some(current) = stack.pop() {
+        if is_documentable_kind(current.kind(), language) && is_public(current, source, language) {
+            symbols.push(PublicSymbol {
+                name: symbol_name(current, source),
+                kind: current.kind().to_string(),
+                line: current.start_position().row + 1,
+                documented: has_doc_comment(current, source, language),
+                node: current,
+            });
+        }
+        for child in current.children(&mut cursor) {
+            stack.push(child);
+        }
+    }
+
+    symbols
+}
+
+/// Cheap text-based complexity heuristic (control-flow keyword density),
+/// matching `CodeIndexer::calculate_complexity`'s convention of a 1.0 base
+/// plus 0.5 per control-flow keyword, uncapped here since it's only used to
+/// rank undocumented symbols relative to each other.
+fn complexity_score(node: Node, source: &str) -> f32 {
+    let text = node.utf8_text(source.as_bytes()).unwrap_or("");
+    let control_keywords = ["if", "else", "match", "for", "while", "loop", "switch", "case"];
+    let mut complexity = 1.0;
+    for keyword in &control_keywords {
+        complexity += text.matches(keyword).count() as f32 * 0.5;
+    }
+    complexity
+}
@@ -0,0 +1 @@
+pub mod documentation_generator;
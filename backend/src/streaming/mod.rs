@@ -1,3 +1,5 @@
+pub mod quality;
+
 use anyhow::Result;
 use axum::{
     extract::State,
@@ -12,9 +14,23 @@ use serde::{Deserialize, Serialize};
 use std::{convert::Infallible, time::Duration};
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
-use crate::{auth::AuthContext, providers::traits::CompletionRequest, AppState};
+use crate::{
+    auth::AuthContext, conversation::ConversationRequest, providers::tokenizer,
+    providers::traits::CompletionRequest, AppState,
+};
+
+/// Builds one SSE `Event` carrying `payload` JSON-encoded as its `data`,
+/// tagged with `id` and `event_name`. Shared by [`StreamingManager::send_event`]
+/// and [`crate::conversation::conversation_service::ConversationService::process_message_streaming`]
+/// so both streaming endpoints encode events the same way instead of each
+/// hand-rolling `Event::default()`.
+pub fn encode_sse_event<T: Serialize>(id: &str, event_name: &str, payload: &T) -> Result<Event> {
+    let data = serde_json::to_string(payload)?;
+    Ok(Event::default().id(id).event(event_name).data(data))
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamingRequest {
@@ -85,6 +101,10 @@ pub struct QualityMetrics {
 
 pub struct StreamingManager {
     active_streams: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, StreamInfo>>>,
+    /// One token per entry of `active_streams`, so `cancel_all_streams` (used
+    /// by graceful shutdown) can stop every in-flight stream without a
+    /// handle to its spawned task.
+    cancellation_tokens: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, CancellationToken>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -101,16 +121,49 @@ impl StreamingManager {
     pub fn new() -> Self {
         Self {
             active_streams: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            cancellation_tokens: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
         }
     }
 
+    /// Cancels every currently active stream (provider completions and
+    /// conversation turns alike) and returns how many were cancelled. Used
+    /// by graceful shutdown; each stream's own task notices its token and
+    /// exits after sending a final `error` event, removing itself from
+    /// `active_streams`/`cancellation_tokens` the same way it would on any
+    /// other early exit.
+    pub fn cancel_all_streams(&self) -> usize {
+        let tokens = self.cancellation_tokens.lock().unwrap();
+        for token in tokens.values() {
+            token.cancel();
+        }
+        tokens.len()
+    }
+
     pub async fn create_stream(
         &self,
         state: &AppState,
         auth_context: &AuthContext,
-        request: StreamingRequest,
+        mut request: StreamingRequest,
     ) -> Result<impl Stream<Item = Result<Event, Infallible>>> {
-        let stream_id = request.stream_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        if request.provider.is_none() || request.model.is_none() {
+            match state.preferences_service.get_user_preferences(auth_context.user.id).await {
+                Ok(prefs) => {
+                    let (provider, model) =
+                        prefs.resolve_provider_and_model(request.provider.take(), request.model.take());
+                    request.provider = Some(provider);
+                    request.model = Some(model);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to load preferences for user {}, using hardcoded defaults: {}",
+                        auth_context.user.id,
+                        e
+                    );
+                }
+            }
+        }
+
+        let stream_id = request.stream_id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
         let provider = request.provider.as_deref().unwrap_or("openrouter");
         let model = request.model.as_deref().unwrap_or("gpt-4o-mini");
 
@@ -143,26 +196,35 @@ impl StreamingManager {
             );
         }
 
+        let cancellation_token = CancellationToken::new();
+        {
+            let mut tokens = self.cancellation_tokens.lock().unwrap();
+            tokens.insert(stream_id.clone(), cancellation_token.clone());
+        }
+
         // Start streaming task
         let stream_id_clone = stream_id.clone();
         let state_clone = state.clone();
         let auth_context_clone = auth_context.clone();
         let request_clone = request.clone();
         let streams_clone = self.active_streams.clone();
+        let cancellation_tokens_clone = self.cancellation_tokens.clone();
 
         tokio::spawn(async move {
             if let Err(e) = Self::handle_streaming(
-                stream_id_clone,
+                stream_id_clone.clone(),
                 state_clone,
                 auth_context_clone,
                 request_clone,
                 tx,
                 streams_clone,
+                cancellation_token,
             )
             .await
             {
                 tracing::error!("Streaming error: {}", e);
             }
+            cancellation_tokens_clone.lock().unwrap().remove(&stream_id_clone);
         });
 
         Ok(ReceiverStream::new(rx))
@@ -175,16 +237,18 @@ impl StreamingManager {
         request: StreamingRequest,
         tx: mpsc::Sender<Result<Event, Infallible>>,
         streams: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, StreamInfo>>>,
+        cancellation_token: CancellationToken,
     ) -> Result<()> {
         let provider = request.provider.as_deref().unwrap_or("openrouter");
         let model = request.model.as_deref().unwrap_or("gpt-4o-mini");
 
         // Send start event
+        let estimated_tokens = tokenizer::count_tokens(&request.prompt, model) as u32;
         let start_event = StreamEvent::Start {
             stream_id: stream_id.clone(),
             provider: provider.to_string(),
             model: model.to_string(),
-            estimated_tokens: Some(request.max_tokens.unwrap_or(1000)),
+            estimated_tokens: Some(estimated_tokens),
         };
 
         Self::send_event(&tx, &stream_id, start_event).await?;
@@ -202,20 +266,29 @@ impl StreamingManager {
         };
 
         // Start streaming from provider
-        match Self::stream_from_provider(&state, &completion_request, &stream_id, &tx, &streams).await {
-            Ok(_) => {
+        match Self::stream_from_provider(
+            &state,
+            &completion_request,
+            &stream_id,
+            &tx,
+            &streams,
+            &cancellation_token,
+        )
+        .await
+        {
+            Ok(full_response) => {
+                let scorer = quality::QualityScorer::new(state.provider_router.clone());
+                let quality_metrics = scorer
+                    .score(&completion_request.prompt, &full_response, completion_request.language.as_deref())
+                    .await;
+
                 // Send completion event
                 let completion_event = StreamEvent::Complete {
                     stream_id: stream_id.clone(),
                     total_tokens: Self::calculate_total_tokens(&streams, &stream_id),
                     total_cost: Self::calculate_total_cost(&streams, &stream_id),
                     completion_time: Self::calculate_completion_time(&streams, &stream_id),
-                    quality_metrics: QualityMetrics {
-                        coherence_score: 0.9,
-                        relevance_score: 0.85,
-                        code_quality_score: Some(0.8),
-                        security_score: Some(0.95),
-                    },
+                    quality_metrics,
                 };
                 Self::send_event(&tx, &stream_id, completion_event).await?;
             }
@@ -245,7 +318,8 @@ impl StreamingManager {
         stream_id: &str,
         tx: &mpsc::Sender<Result<Event, Infallible>>,
         streams: &std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, StreamInfo>>>,
-    ) -> Result<()> {
+        cancellation_token: &CancellationToken,
+    ) -> Result<String> {
         // Simulate streaming for now - in real implementation, this would call the actual provider
         let chunks = vec![
             "Here's",
@@ -268,6 +342,10 @@ impl StreamingManager {
         let total_chunks = chunks.len();
         
         for (i, chunk) in chunks.iter().enumerate() {
+            if cancellation_token.is_cancelled() {
+                return Err(anyhow::anyhow!("Stream cancelled by server shutdown"));
+            }
+
             // Send chunk event
             let chunk_event = StreamEvent::Chunk {
                 stream_id: stream_id.to_string(),
@@ -302,7 +380,7 @@ impl StreamingManager {
             tokio::time::sleep(Duration::from_millis(100 + i as u64 * 50)).await;
         }
 
-        Ok(())
+        Ok(chunks.concat())
     }
 
     async fn send_event(
@@ -310,18 +388,15 @@ impl StreamingManager {
         stream_id: &str,
         event: StreamEvent,
     ) -> Result<()> {
-        let event_data = serde_json::to_string(&event)?;
-        let sse_event = Event::default()
-            .id(stream_id)
-            .event(match &event {
-                StreamEvent::Start { .. } => "start",
-                StreamEvent::Chunk { .. } => "chunk",
-                StreamEvent::Progress { .. } => "progress",
-                StreamEvent::Metadata { .. } => "metadata",
-                StreamEvent::Complete { .. } => "complete",
-                StreamEvent::Error { .. } => "error",
-            })
-            .data(event_data);
+        let event_name = match &event {
+            StreamEvent::Start { .. } => "start",
+            StreamEvent::Chunk { .. } => "chunk",
+            StreamEvent::Progress { .. } => "progress",
+            StreamEvent::Metadata { .. } => "metadata",
+            StreamEvent::Complete { .. } => "complete",
+            StreamEvent::Error { .. } => "error",
+        };
+        let sse_event = encode_sse_event(stream_id, event_name, &event)?;
 
         tx.send(Ok(sse_event)).await.map_err(|_| anyhow::anyhow!("Failed to send event"))?;
         Ok(())
@@ -336,6 +411,39 @@ impl StreamingManager {
         let streams = self.active_streams.lock().unwrap();
         streams.get(stream_id).cloned()
     }
+
+    /// Streams a `/conversation/message` turn as SSE events by delegating to
+    /// [`crate::conversation::conversation_service::ConversationService::process_message_streaming`],
+    /// reusing the same channel/`Sse` plumbing [`Self::create_stream`] uses
+    /// for provider completions instead of a second copy of it.
+    pub fn create_conversation_stream(
+        &self,
+        state: AppState,
+        request: ConversationRequest,
+    ) -> impl Stream<Item = Result<Event, Infallible>> {
+        let (tx, rx) = mpsc::channel::<Result<Event, Infallible>>(100);
+
+        let stream_id = Uuid::new_v4().to_string();
+        let cancellation_token = CancellationToken::new();
+        {
+            let mut tokens = self.cancellation_tokens.lock().unwrap();
+            tokens.insert(stream_id.clone(), cancellation_token.clone());
+        }
+
+        let cancellation_tokens_clone = self.cancellation_tokens.clone();
+        tokio::spawn(async move {
+            if let Err(e) = state
+                .conversation_service
+                .process_message_streaming(request, tx, cancellation_token)
+                .await
+            {
+                tracing::error!("Conversation streaming error: {}", e);
+            }
+            cancellation_tokens_clone.lock().unwrap().remove(&stream_id);
+        });
+
+        ReceiverStream::new(rx)
+    }
 }
 
 // Handler for streaming endpoint
@@ -344,8 +452,8 @@ pub async fn streaming_completion_handler(
     auth_context: AuthContext,
     Json(request): Json<StreamingRequest>,
 ) -> Response {
-    let streaming_manager = StreamingManager::new();
-    
+    let streaming_manager = state.streaming_manager.clone();
+
     match streaming_manager.create_stream(&state, &auth_context, request).await {
         Ok(stream) => {
             let sse = Sse::new(stream).keep_alive(
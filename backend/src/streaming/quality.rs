@@ -0,0 +1,194 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use crate::providers::ProviderRouter;
+use crate::search::embedding_manager::EmbeddingManager;
+use crate::search::{EmbeddingRequest, EmbeddingType, SimilarityMetric, SimilarityRequest};
+
+use super::QualityMetrics;
+
+/// Scores a fully assembled streamed response against the prompt that
+/// produced it. Replaces the placeholder constants `StreamingManager` used
+/// to fill `QualityMetrics` with.
+pub struct QualityScorer {
+    embedding_manager: EmbeddingManager,
+}
+
+/// A small, deliberately conservative subset of the hardcoded-secret checks
+/// in `agents::security_analyzer` - enough to flag obviously risky
+/// completions without pulling the full analyzer into the streaming path.
+const SECURITY_RED_FLAGS: &[&str] = &["password", "secret", "api_key", "token"];
+
+impl QualityScorer {
+    pub fn new(provider_router: Arc<ProviderRouter>) -> Self {
+        Self {
+            embedding_manager: EmbeddingManager::new(provider_router),
+        }
+    }
+
+    /// Computes syntax validity, prompt relevance, and a basic security
+    /// score for the assembled response text.
+    pub async fn score(&self, prompt: &str, response: &str, language: Option<&str>) -> QualityMetrics {
+        let coherence_score = self.syntax_validity_score(response, language);
+        let relevance_score = self.relevance_score(prompt, response).await;
+        let code_quality_score = language.map(|_| coherence_score);
+        let security_score = Some(self.security_score(response));
+
+        QualityMetrics {
+            coherence_score,
+            relevance_score,
+            code_quality_score,
+            security_score,
+        }
+    }
+
+    /// Approximates syntax validity by checking that brackets, parens and
+    /// quotes balance out. This is a lightweight stand-in for a real parser
+    /// and is deliberately forgiving for non-code languages.
+    fn syntax_validity_score(&self, response: &str, language: Option<&str>) -> f32 {
+        if language.is_none() {
+            return 1.0;
+        }
+
+        let mut stack = Vec::new();
+        let mut mismatches = 0u32;
+        let mut total = 0u32;
+
+        for ch in response.chars() {
+            match ch {
+                '(' | '[' | '{' => {
+                    stack.push(ch);
+                    total += 1;
+                }
+                ')' | ']' | '}' => {
+                    total += 1;
+                    let expected = match ch {
+                        ')' => '(',
+                        ']' => '[',
+                        _ => '{',
+                    };
+                    match stack.pop() {
+                        Some(open) if open == expected => {}
+                        _ => mismatches += 1,
+                    }
+                }
+                _ => {}
+            }
+        }
+        mismatches += stack.len() as u32;
+
+        if total == 0 {
+            return 0.7; // no structural tokens to judge; neither confirm nor penalize heavily
+        }
+
+        (1.0 - (mismatches as f32 / total as f32)).clamp(0.0, 1.0)
+    }
+
+    /// Cosine similarity between prompt and response embeddings, via the
+    /// same `EmbeddingManager` used by semantic search.
+    async fn relevance_score(&self, prompt: &str, response: &str) -> f32 {
+        let prompt_embedding = self
+            .embedding_manager
+            .generate_embedding(EmbeddingRequest {
+                text: prompt.to_string(),
+                context: None,
+                embedding_type: EmbeddingType::Query,
+            })
+            .await;
+
+        let response_embedding = self
+            .embedding_manager
+            .generate_embedding(EmbeddingRequest {
+                text: response.to_string(),
+                context: None,
+                embedding_type: EmbeddingType::Code,
+            })
+            .await;
+
+        match (prompt_embedding, response_embedding) {
+            (Ok(prompt_emb), Ok(response_emb)) => {
+                match self
+                    .embedding_manager
+                    .calculate_similarity(SimilarityRequest {
+                        query_embedding: prompt_emb.embedding,
+                        candidate_embeddings: vec![response_emb.embedding],
+                        similarity_metric: SimilarityMetric::Cosine,
+                        threshold: None,
+                        ann_index: None,
+                    })
+                    .await
+                {
+                    Ok(similarity) => similarity.scores.first().copied().unwrap_or(0.0),
+                    Err(e) => {
+                        warn!("Failed to compute relevance similarity: {}", e);
+                        0.0
+                    }
+                }
+            }
+            (Err(e), _) | (_, Err(e)) => {
+                debug!("Embedding generation failed during quality scoring: {}", e);
+                0.0
+            }
+        }
+    }
+
+    /// Flags the same class of hardcoded-secret patterns as
+    /// `security_analyzer::check_generic_security`, scaled down to a 0-1
+    /// score where 1.0 means no red flags were found.
+    fn security_score(&self, response: &str) -> f32 {
+        let lowered = response.to_lowercase();
+        let hits = SECURITY_RED_FLAGS
+            .iter()
+            .filter(|pattern| lowered.contains(*pattern) && response.contains('='))
+            .count();
+
+        (1.0 - hits as f32 * 0.2).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn router() -> Arc<ProviderRouter> {
+        // ProviderRouter::new only fails if provider initialization panics;
+        // with no API keys configured it falls back to Ollama only.
+        let config = Arc::new(Config::from_env().expect("config"));
+        Arc::new(
+            tokio::runtime::Runtime::new()
+                .unwrap()
+                .block_on(ProviderRouter::new(config))
+                .expect("router"),
+        )
+    }
+
+    #[test]
+    fn balanced_code_scores_highly() {
+        let scorer = QualityScorer::new(router());
+        let score = scorer.syntax_validity_score("fn main() { println!(\"hi\"); }", Some("rust"));
+        assert_eq!(score, 1.0);
+    }
+
+    #[test]
+    fn unbalanced_code_is_penalized() {
+        let scorer = QualityScorer::new(router());
+        let score = scorer.syntax_validity_score("fn main() { println!(\"hi\";", Some("rust"));
+        assert!(score < 1.0);
+    }
+
+    #[test]
+    fn hardcoded_secret_lowers_security_score() {
+        let scorer = QualityScorer::new(router());
+        let score = scorer.security_score("let api_key = \"sk-12345\";");
+        assert!(score < 1.0);
+    }
+
+    #[test]
+    fn clean_response_has_full_security_score() {
+        let scorer = QualityScorer::new(router());
+        let score = scorer.security_score("fn add(a: i32, b: i32) -> i32 { a + b }");
+        assert_eq!(score, 1.0);
+    }
+}
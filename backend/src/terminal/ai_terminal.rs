@@ -1,21 +1,28 @@
 use anyhow::Result;
 use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
 use chrono::Utc;
 use tracing::{info, warn, error};
 
+use crate::cache::{redis_cache::session_cache_key, Cache, RedisCache};
 use crate::providers::ProviderRouter;
 use super::{
     TerminalSession, TerminalContext, CommandEntry, TerminalRequest, TerminalResponse,
     QueryType, SafetyLevel, command_suggester::AICommandSuggester,
-    history_manager::HistoryManager, shell_integration::ShellExecutor,
+    history_manager::{HistoryManager, ImportOutcome}, impact_predictor::ImpactPredictor,
+    shell_integration::{ShellExecutor, ShellIntegration, ShellType},
 };
 
 pub struct AITerminalService {
     command_suggester: AICommandSuggester,
     history_manager: HistoryManager,
     shell_executor: ShellExecutor,
+    impact_predictor: ImpactPredictor,
     provider_router: Arc<ProviderRouter>,
+    deduplicate_on_write: bool,
+    redis_cache: Option<Arc<RedisCache>>,
+    session_ttl: Duration,
 }
 
 impl AITerminalService {
@@ -27,7 +34,45 @@ impl AITerminalService {
             command_suggester: AICommandSuggester::new(provider_router.clone()),
             history_manager,
             shell_executor: ShellExecutor::new(),
+            impact_predictor: ImpactPredictor::new(),
             provider_router,
+            deduplicate_on_write: false,
+            redis_cache: None,
+            session_ttl: Duration::from_secs(
+                crate::auth::SecurityPolicy::default().session_timeout_minutes as u64 * 60,
+            ),
+        }
+    }
+
+    /// Enables automatic deduplication of a session's command history on
+    /// every `add_command` write (see [`Self::deduplicate_session_history`]).
+    pub fn with_deduplicate_on_write(mut self, deduplicate_on_write: bool) -> Self {
+        self.deduplicate_on_write = deduplicate_on_write;
+        self
+    }
+
+    /// Enables write-through Redis caching of sessions, keyed
+    /// `session:{user_id}:{session_id}` with a TTL matching
+    /// `SecurityPolicy::session_timeout_minutes`.
+    pub fn with_redis_cache(mut self, redis_cache: Arc<RedisCache>, session_timeout_minutes: u32) -> Self {
+        self.redis_cache = Some(redis_cache);
+        self.session_ttl = Duration::from_secs(session_timeout_minutes as u64 * 60);
+        self
+    }
+
+    async fn cache_session(&self, session: &TerminalSession) {
+        let Some(cache) = &self.redis_cache else { return };
+        let key = session_cache_key(session.user_id, session.id);
+        if let Err(e) = cache.set(&key, session, Some(self.session_ttl)).await {
+            warn!("Failed to write terminal session {} through to Redis: {}", session.id, e);
+        }
+    }
+
+    async fn invalidate_cached_session(&self, user_id: Uuid, session_id: Uuid) {
+        let Some(cache) = &self.redis_cache else { return };
+        let key = session_cache_key(user_id, session_id);
+        if let Err(e) = cache.delete(&key).await {
+            warn!("Failed to invalidate cached terminal session {}: {}", session_id, e);
         }
     }
 
@@ -37,20 +82,37 @@ impl AITerminalService {
         workspace_path: Option<String>,
     ) -> Result<TerminalSession> {
         let mut session = TerminalSession::new(user_id, workspace_path);
-        
+
         // Context'i güncelle
         session.context.detect_project_type();
         session.context.update_git_status().await?;
-        
+
         // Veritabanına kaydet
         self.history_manager.create_session(&session).await?;
-        
+        self.cache_session(&session).await;
+
         info!("New terminal session created: {} for user: {}", session.id, user_id);
         Ok(session)
     }
 
+    /// Looks up a session in Redis first, falling back to Postgres on a
+    /// cache miss (and re-populating Redis so the next lookup is a hit).
     pub async fn get_session(&self, session_id: Uuid) -> Result<Option<TerminalSession>> {
-        self.history_manager.get_session(session_id).await
+        if let Some(cache) = &self.redis_cache {
+            if let Ok(Some(key)) = cache.find_session_key(session_id).await {
+                match cache.get::<TerminalSession>(&key).await {
+                    Ok(Some(session)) => return Ok(Some(session)),
+                    Ok(None) => {}
+                    Err(e) => warn!("Redis terminal session lookup failed for {}: {}", session_id, e),
+                }
+            }
+        }
+
+        let session = self.history_manager.get_session(session_id).await?;
+        if let Some(session) = &session {
+            self.cache_session(session).await;
+        }
+        Ok(session)
     }
 
     pub async fn process_request(
@@ -99,7 +161,7 @@ impl AITerminalService {
         ).await?;
 
         let mut warnings = Vec::new();
-        
+
         // Tehlikeli komutlar için uyarı ekle
         for suggestion in &suggestions {
             if matches!(suggestion.safety_level, SafetyLevel::Dangerous) {
@@ -109,12 +171,19 @@ impl AITerminalService {
             }
         }
 
+        let predicted_impact = if request.explain_impact {
+            suggestions.first().and_then(|s| self.impact_predictor.predict(&s.command, context))
+        } else {
+            None
+        };
+
         Ok(TerminalResponse {
             session_id: session.id,
             suggestions,
             execution_result: None,
             explanation: Some(format!("'{}' için {} komut önerisi bulundu", request.query, suggestions.len())),
             warnings,
+            predicted_impact,
         })
     }
 
@@ -136,6 +205,7 @@ impl AITerminalService {
                 execution_result: None,
                 explanation: Some("Komut güvenlik nedeniyle engellendi".to_string()),
                 warnings: vec!["🚫 Bu komut çalıştırılamaz".to_string()],
+                predicted_impact: None,
             });
         }
 
@@ -155,12 +225,19 @@ impl AITerminalService {
             execution_time_ms: execution_result.execution_time_ms,
             ai_suggested: false,
             safety_level: safety_level.clone(),
+            category: self.command_suggester.categorize_command(command),
             timestamp: Utc::now(),
         };
 
         session.add_command(command_entry.clone());
         self.history_manager.add_command(session.id, &command_entry).await?;
 
+        if self.deduplicate_on_write {
+            self.history_manager.deduplicate_session_history(session.id).await?;
+        }
+
+        self.cache_session(session).await;
+
         let mut warnings = Vec::new();
         if execution_result.exit_code != 0 {
             warnings.push("Komut hata ile sonlandı".to_string());
@@ -176,6 +253,7 @@ impl AITerminalService {
             execution_result: Some(execution_result),
             explanation: None,
             warnings,
+            predicted_impact: None,
         })
     }
 
@@ -199,6 +277,7 @@ impl AITerminalService {
             execution_result: None,
             explanation: Some("Komut açıklaması".to_string()),
             warnings: vec![],
+            predicted_impact: None,
         })
     }
 
@@ -221,6 +300,7 @@ impl AITerminalService {
             execution_result: None,
             explanation: Some(format!("Geçmişte '{}' ile ilgili {} komut bulundu", request.query, suggestions.len())),
             warnings: vec![],
+            predicted_impact: None,
         })
     }
 
@@ -232,6 +312,17 @@ impl AITerminalService {
         self.history_manager.get_user_sessions(user_id, limit).await
     }
 
+    pub async fn get_user_sessions_page(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<TerminalSession>, i64)> {
+        let sessions = self.history_manager.get_user_sessions_page(user_id, limit, offset).await?;
+        let total = self.history_manager.count_user_sessions(user_id).await?;
+        Ok((sessions, total))
+    }
+
     pub async fn search_user_commands(
         &self,
         user_id: Uuid,
@@ -244,14 +335,41 @@ impl AITerminalService {
     pub async fn get_command_statistics(
         &self,
         user_id: Uuid,
+        days: u32,
     ) -> Result<super::history_manager::CommandStatistics> {
-        self.history_manager.get_command_statistics(user_id).await
+        self.history_manager.get_command_statistics(user_id, days).await
     }
 
     pub async fn delete_session(&self, session_id: Uuid) -> Result<()> {
+        if let Some(session) = self.history_manager.get_session(session_id).await? {
+            self.invalidate_cached_session(session.user_id, session_id).await;
+        }
         self.history_manager.delete_session(session_id).await
     }
 
+    pub async fn archive_session(&self, session_id: Uuid) -> Result<()> {
+        self.history_manager.archive_session(session_id).await
+    }
+
+    pub async fn restore_session(&self, session_id: Uuid) -> Result<()> {
+        self.history_manager.restore_session(session_id).await
+    }
+
+    pub async fn deduplicate_session_history(&self, session_id: Uuid) -> Result<usize> {
+        self.history_manager.deduplicate_session_history(session_id).await
+    }
+
+    pub async fn get_archived_sessions_page(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<TerminalSession>, i64)> {
+        let sessions = self.history_manager.get_archived_sessions_page(user_id, limit, offset).await?;
+        let total = self.history_manager.count_archived_sessions(user_id).await?;
+        Ok((sessions, total))
+    }
+
     pub async fn cleanup_old_sessions(&self, days_old: i32) -> Result<u64> {
         self.history_manager.cleanup_old_sessions(days_old).await
     }
@@ -275,6 +393,32 @@ impl AITerminalService {
     ) -> Result<super::shell_integration::InteractiveSession> {
         self.shell_executor.execute_interactive_command(command, context).await
     }
+
+    /// Imports `bash`/`zsh` history into `session_id`, either from
+    /// `file_content` (base64-encoded, e.g. uploaded by a client that can't
+    /// reach the server's filesystem) or from the shell's default history
+    /// file on disk when no content is given.
+    pub async fn import_shell_history(
+        &self,
+        session_id: Uuid,
+        shell: ShellType,
+        file_content: Option<&str>,
+    ) -> Result<ImportOutcome> {
+        let commands = match file_content {
+            Some(encoded) => {
+                use base64::Engine as _;
+                let decoded = base64::engine::general_purpose::STANDARD
+                    .decode(encoded)
+                    .map_err(|e| anyhow::anyhow!("Invalid base64 file_content: {e}"))?;
+                let content = String::from_utf8(decoded)
+                    .map_err(|e| anyhow::anyhow!("file_content is not valid UTF-8: {e}"))?;
+                ShellIntegration::parse_and_cap(shell, &content)
+            }
+            None => ShellIntegration::import_shell_history(shell, None).await?,
+        };
+
+        self.history_manager.bulk_insert_imported_history(session_id, &commands).await
+    }
 }
 
 // Terminal servisi için yardımcı fonksiyonlar
@@ -300,6 +444,7 @@ impl AITerminalService {
             query: context_prompt,
             query_type: QueryType::NaturalLanguage,
             context: Some(session.context.clone()),
+            explain_impact: false,
         };
 
         self.command_suggester.suggest_commands(
@@ -313,7 +458,7 @@ impl AITerminalService {
         &self,
         user_id: Uuid,
     ) -> Result<CommandPatternAnalysis> {
-        let stats = self.get_command_statistics(user_id).await?;
+        let stats = self.get_command_statistics(user_id, 30).await?;
         let recent_commands = self.search_user_commands(user_id, "", 50).await?;
 
         // Komut kategorilerini analiz et
@@ -1,11 +1,34 @@
 use anyhow::Result;
-use sqlx::{PgPool, Row};
+use sqlx::{FromRow, PgPool, Row};
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use tracing::{info, error};
 
-use super::{TerminalSession, CommandEntry, TerminalContext, SafetyLevel};
+use super::{TerminalSession, CommandEntry, TerminalContext, SafetyLevel, CommandCategory};
+use super::shell_integration::ImportedCommand;
+
+/// Mirrors the `terminal_sessions` columns selected by
+/// [`HistoryManager::get_archived_sessions_page`].
+#[derive(Debug, FromRow)]
+struct TerminalSessionRow {
+    id: Uuid,
+    user_id: Uuid,
+    workspace_path: Option<String>,
+    session_data: serde_json::Value,
+    created_at: DateTime<Utc>,
+    last_activity: DateTime<Utc>,
+}
+
+/// Mirrors the `command_history` columns selected by
+/// [`HistoryManager::get_daily_command_breakdown`].
+#[derive(Debug, FromRow)]
+struct DailyCommandRow {
+    day: DateTime<Utc>,
+    category: String,
+    count: Option<i64>,
+}
 
 pub struct HistoryManager {
     pool: Arc<PgPool>,
@@ -84,17 +107,68 @@ impl HistoryManager {
     }
 
     pub async fn get_user_sessions(&self, user_id: Uuid, limit: i64) -> Result<Vec<TerminalSession>> {
+        self.get_user_sessions_page(user_id, limit, 0).await
+    }
+
+    pub async fn get_user_sessions_page(&self, user_id: Uuid, limit: i64, offset: i64) -> Result<Vec<TerminalSession>> {
         let rows = sqlx::query!(
             r#"
             SELECT id, user_id, workspace_path, session_data, created_at, last_activity
             FROM terminal_sessions
-            WHERE user_id = $1
+            WHERE user_id = $1 AND archived_at IS NULL
             ORDER BY last_activity DESC
-            LIMIT $2
+            LIMIT $2 OFFSET $3
             "#,
             user_id,
-            limit
+            limit,
+            offset
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            let context: TerminalContext = serde_json::from_value(row.session_data)?;
+            let command_history = self.get_session_commands(row.id).await?;
+
+            sessions.push(TerminalSession {
+                id: row.id,
+                user_id: row.user_id,
+                workspace_path: row.workspace_path,
+                command_history,
+                context,
+                created_at: row.created_at,
+                last_activity: row.last_activity,
+            });
+        }
+
+        Ok(sessions)
+    }
+
+    pub async fn count_user_sessions(&self, user_id: Uuid) -> Result<i64> {
+        let (count,) = sqlx::query_as::<_, (i64,)>(
+            "SELECT COUNT(*) as count FROM terminal_sessions WHERE user_id = $1 AND archived_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&*self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    pub async fn get_archived_sessions_page(&self, user_id: Uuid, limit: i64, offset: i64) -> Result<Vec<TerminalSession>> {
+        let rows = sqlx::query_as::<_, TerminalSessionRow>(
+            r#"
+            SELECT id, user_id, workspace_path, session_data, created_at, last_activity
+            FROM terminal_sessions
+            WHERE user_id = $1 AND archived_at IS NOT NULL
+            ORDER BY last_activity DESC
+            LIMIT $2 OFFSET $3
+            "#,
         )
+        .bind(user_id)
+        .bind(limit)
+        .bind(offset)
         .fetch_all(&*self.pool)
         .await?;
 
@@ -117,11 +191,43 @@ impl HistoryManager {
         Ok(sessions)
     }
 
+    pub async fn count_archived_sessions(&self, user_id: Uuid) -> Result<i64> {
+        let (count,) = sqlx::query_as::<_, (i64,)>(
+            "SELECT COUNT(*) as count FROM terminal_sessions WHERE user_id = $1 AND archived_at IS NOT NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&*self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Soft-deletes a session by marking it archived instead of removing its rows.
+    pub async fn archive_session(&self, session_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE terminal_sessions SET archived_at = NOW() WHERE id = $1")
+            .bind(session_id)
+            .execute(&*self.pool)
+            .await?;
+
+        info!("Terminal session archived: {}", session_id);
+        Ok(())
+    }
+
+    pub async fn restore_session(&self, session_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE terminal_sessions SET archived_at = NULL WHERE id = $1")
+            .bind(session_id)
+            .execute(&*self.pool)
+            .await?;
+
+        info!("Terminal session restored: {}", session_id);
+        Ok(())
+    }
+
     pub async fn add_command(&self, session_id: Uuid, command: &CommandEntry) -> Result<()> {
         sqlx::query!(
             r#"
-            INSERT INTO command_history (id, session_id, command, output, exit_code, ai_suggested, safety_level, executed_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            INSERT INTO command_history (id, session_id, command, output, exit_code, ai_suggested, safety_level, category, executed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             "#,
             command.id,
             session_id,
@@ -130,6 +236,7 @@ impl HistoryManager {
             command.exit_code,
             command.ai_suggested,
             serde_json::to_string(&command.safety_level)?,
+            serde_json::to_string(&command.category)?,
             command.timestamp
         )
         .execute(&*self.pool)
@@ -151,10 +258,78 @@ impl HistoryManager {
         Ok(())
     }
 
+    /// Inserts `commands` (already parsed by `ShellIntegration::import_shell_history`)
+    /// into `command_history` for `session_id`, skipping any whose command text
+    /// already exists there -- either from a prior import or from commands the
+    /// session actually ran. Imported rows are tagged `ai_suggested = false`,
+    /// `source = "imported"`, and land under `CommandCategory::System` since
+    /// history files carry no category info to classify from.
+    pub async fn bulk_insert_imported_history(
+        &self,
+        session_id: Uuid,
+        commands: &[ImportedCommand],
+    ) -> Result<ImportOutcome> {
+        let existing = sqlx::query_as::<_, (String,)>(
+            "SELECT command FROM command_history WHERE session_id = $1",
+        )
+        .bind(session_id)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        let mut seen: std::collections::HashSet<String> =
+            existing.into_iter().map(|(command,)| command).collect();
+
+        let safety_level = serde_json::to_string(&SafetyLevel::Safe)?;
+        let category = serde_json::to_string(&CommandCategory::System)?;
+
+        let mut imported = 0usize;
+        let mut skipped_duplicates = 0usize;
+
+        for entry in commands {
+            if !seen.insert(entry.command.clone()) {
+                skipped_duplicates += 1;
+                continue;
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO command_history
+                    (id, session_id, command, output, exit_code, ai_suggested, safety_level, category, executed_at, source)
+                VALUES ($1, $2, $3, '', 0, false, $4, $5, $6, 'imported')
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(session_id)
+            .bind(&entry.command)
+            .bind(&safety_level)
+            .bind(&category)
+            .bind(entry.timestamp)
+            .execute(&*self.pool)
+            .await?;
+
+            imported += 1;
+        }
+
+        if imported > 0 {
+            sqlx::query("UPDATE terminal_sessions SET last_activity = $1 WHERE id = $2")
+                .bind(Utc::now())
+                .bind(session_id)
+                .execute(&*self.pool)
+                .await?;
+        }
+
+        info!(
+            "Imported {} commands ({} duplicates skipped) into terminal session {}",
+            imported, skipped_duplicates, session_id
+        );
+
+        Ok(ImportOutcome { imported, skipped_duplicates })
+    }
+
     pub async fn get_session_commands(&self, session_id: Uuid) -> Result<Vec<CommandEntry>> {
         let rows = sqlx::query!(
             r#"
-            SELECT id, command, output, exit_code, ai_suggested, safety_level, executed_at
+            SELECT id, command, output, exit_code, ai_suggested, safety_level, category, executed_at
             FROM command_history
             WHERE session_id = $1
             ORDER BY executed_at ASC
@@ -167,7 +342,8 @@ impl HistoryManager {
         let mut commands = Vec::new();
         for row in rows {
             let safety_level: SafetyLevel = serde_json::from_str(&row.safety_level)?;
-            
+            let category: CommandCategory = serde_json::from_str(&row.category)?;
+
             commands.push(CommandEntry {
                 id: row.id,
                 command: row.command,
@@ -176,6 +352,7 @@ impl HistoryManager {
                 execution_time_ms: 0, // Bu bilgiyi ayrı tutacağız
                 ai_suggested: row.ai_suggested,
                 safety_level,
+                category,
                 timestamp: row.executed_at,
             });
         }
@@ -191,10 +368,10 @@ impl HistoryManager {
     ) -> Result<Vec<CommandEntry>> {
         let rows = sqlx::query!(
             r#"
-            SELECT ch.id, ch.command, ch.output, ch.exit_code, ch.ai_suggested, ch.safety_level, ch.executed_at
+            SELECT ch.id, ch.command, ch.output, ch.exit_code, ch.ai_suggested, ch.safety_level, ch.category, ch.executed_at
             FROM command_history ch
             JOIN terminal_sessions ts ON ch.session_id = ts.id
-            WHERE ts.user_id = $1 
+            WHERE ts.user_id = $1
             AND (ch.command ILIKE $2 OR ch.output ILIKE $2)
             ORDER BY ch.executed_at DESC
             LIMIT $3
@@ -209,7 +386,8 @@ impl HistoryManager {
         let mut commands = Vec::new();
         for row in rows {
             let safety_level: SafetyLevel = serde_json::from_str(&row.safety_level)?;
-            
+            let category: CommandCategory = serde_json::from_str(&row.category)?;
+
             commands.push(CommandEntry {
                 id: row.id,
                 command: row.command,
@@ -218,6 +396,7 @@ impl HistoryManager {
                 execution_time_ms: 0,
                 ai_suggested: row.ai_suggested,
                 safety_level,
+                category,
                 timestamp: row.executed_at,
             });
         }
@@ -225,10 +404,12 @@ impl HistoryManager {
         Ok(commands)
     }
 
-    pub async fn get_command_statistics(&self, user_id: Uuid) -> Result<CommandStatistics> {
+    pub async fn get_command_statistics(&self, user_id: Uuid, days: u32) -> Result<CommandStatistics> {
+        let since = Utc::now() - chrono::Duration::days(days as i64);
+
         let stats = sqlx::query!(
             r#"
-            SELECT 
+            SELECT
                 COUNT(*) as total_commands,
                 COUNT(*) FILTER (WHERE ai_suggested = true) as ai_suggested_count,
                 COUNT(*) FILTER (WHERE exit_code = 0) as successful_commands,
@@ -262,15 +443,67 @@ impl HistoryManager {
             .map(|row| (row.command, row.usage_count.unwrap_or(0)))
             .collect();
 
+        let daily_breakdown = self.get_daily_command_breakdown(user_id, since).await?;
+
         Ok(CommandStatistics {
             total_commands: stats.total_commands.unwrap_or(0),
             ai_suggested_count: stats.ai_suggested_count.unwrap_or(0),
             successful_commands: stats.successful_commands.unwrap_or(0),
             total_sessions: stats.total_sessions.unwrap_or(0),
             most_used_commands,
+            daily_breakdown,
         })
     }
 
+    /// Per-day command counts broken down by `CommandCategory`, for
+    /// `get_command_statistics`'s `daily_breakdown`. Grouped in SQL by
+    /// `date_trunc('day', executed_at)` and the stored `category` column
+    /// rather than pulled into memory, since a wide `days` window can cover
+    /// thousands of rows.
+    async fn get_daily_command_breakdown(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<DailyCommandSummary>> {
+        let rows = sqlx::query_as::<_, DailyCommandRow>(
+            r#"
+            SELECT
+                date_trunc('day', ch.executed_at) as day,
+                ch.category,
+                COUNT(*) as count
+            FROM command_history ch
+            JOIN terminal_sessions ts ON ch.session_id = ts.id
+            WHERE ts.user_id = $1 AND ch.executed_at >= $2
+            GROUP BY day, ch.category
+            ORDER BY day ASC
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        let mut by_day: HashMap<NaiveDate, DailyCommandSummary> = HashMap::new();
+        for row in rows {
+            let date = row.day.date_naive();
+            let category: CommandCategory = serde_json::from_str(&row.category)?;
+            let count = row.count.unwrap_or(0) as usize;
+
+            let summary = by_day.entry(date).or_insert_with(|| DailyCommandSummary {
+                date,
+                total_commands: 0,
+                by_category: HashMap::new(),
+            });
+            summary.total_commands += count;
+            *summary.by_category.entry(category).or_insert(0) += count;
+        }
+
+        let mut daily_breakdown: Vec<DailyCommandSummary> = by_day.into_values().collect();
+        daily_breakdown.sort_by_key(|summary| summary.date);
+
+        Ok(daily_breakdown)
+    }
+
     pub async fn cleanup_old_sessions(&self, days_old: i32) -> Result<u64> {
         let result = sqlx::query!(
             r#"
@@ -286,6 +519,40 @@ impl HistoryManager {
         Ok(result.rows_affected())
     }
 
+    /// Removes consecutive duplicate commands (same `command` string) from a
+    /// session's history, keeping the most recent occurrence of each run.
+    /// Typing the same command repeatedly (e.g. `ls` a dozen times) otherwise
+    /// fills the 1000-entry history with noise. Returns the number of
+    /// entries removed.
+    pub async fn deduplicate_session_history(&self, session_id: Uuid) -> Result<usize> {
+        let rows = sqlx::query_as::<_, (Uuid, String)>(
+            r#"
+            SELECT id, command
+            FROM command_history
+            WHERE session_id = $1
+            ORDER BY executed_at ASC
+            "#,
+        )
+        .bind(session_id)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        let mut removed = 0;
+        for window in rows.windows(2) {
+            let (previous, next) = (&window[0], &window[1]);
+            if previous.1 == next.1 {
+                sqlx::query("DELETE FROM command_history WHERE id = $1")
+                    .bind(previous.0)
+                    .execute(&*self.pool)
+                    .await?;
+                removed += 1;
+            }
+        }
+
+        info!("Deduplicated {} commands from session {}", removed, session_id);
+        Ok(removed)
+    }
+
     pub async fn delete_session(&self, session_id: Uuid) -> Result<()> {
         // Önce komut geçmişini sil
         sqlx::query!(
@@ -308,6 +575,13 @@ impl HistoryManager {
     }
 }
 
+/// Returned by [`HistoryManager::bulk_insert_imported_history`].
+#[derive(Debug, Clone)]
+pub struct ImportOutcome {
+    pub imported: usize,
+    pub skipped_duplicates: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct CommandStatistics {
     pub total_commands: i64,
@@ -315,6 +589,14 @@ pub struct CommandStatistics {
     pub successful_commands: i64,
     pub total_sessions: i64,
     pub most_used_commands: Vec<(String, i64)>,
+    pub daily_breakdown: Vec<DailyCommandSummary>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DailyCommandSummary {
+    pub date: NaiveDate,
+    pub total_commands: usize,
+    pub by_category: HashMap<CommandCategory, usize>,
 }
 
 impl CommandStatistics {
@@ -0,0 +1,145 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::TerminalContext;
+
+/// Predicted filesystem effects of a command, computed without executing it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PredictedImpact {
+    pub created: Vec<String>,
+    pub modified: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
+/// Predicts which files a file-mutating command would touch by glob-expanding
+/// its arguments against the current directory, without running the command.
+pub struct ImpactPredictor;
+
+impl ImpactPredictor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Returns `None` for commands this predictor doesn't understand.
+    pub fn predict(&self, command: &str, context: &TerminalContext) -> Option<PredictedImpact> {
+        let tokens: Vec<&str> = command.split_whitespace().collect();
+        let (verb, args) = tokens.split_first()?;
+        let args: Vec<&str> = args.iter().copied().filter(|a| !a.starts_with('-')).collect();
+
+        match *verb {
+            "rm" => {
+                let deleted = self.expand_args(&args, &context.current_directory);
+                Some(PredictedImpact { created: vec![], modified: vec![], deleted })
+            }
+            "mv" if args.len() >= 2 => {
+                let (sources, dest) = args.split_at(args.len() - 1);
+                let sources = self.expand_args(sources, &context.current_directory);
+                let created = self.destination_paths(&sources, dest[0]);
+                Some(PredictedImpact { created, modified: vec![], deleted: sources })
+            }
+            "cp" if args.len() >= 2 => {
+                let (sources, dest) = args.split_at(args.len() - 1);
+                let sources = self.expand_args(sources, &context.current_directory);
+                let created = self.destination_paths(&sources, dest[0]);
+                Some(PredictedImpact { created, modified: vec![], deleted: vec![] })
+            }
+            "git" if args.first() == Some(&"checkout") => {
+                let modified = self.expand_args(&args[1..], &context.current_directory);
+                Some(PredictedImpact { created: vec![], modified, deleted: vec![] })
+            }
+            _ => None,
+        }
+    }
+
+    fn destination_paths(&self, sources: &[String], dest: &str) -> Vec<String> {
+        sources.iter().filter_map(|source| {
+            let name = Path::new(source).file_name()?.to_str()?;
+            Some(format!("{}/{}", dest.trim_end_matches('/'), name))
+        }).collect()
+    }
+
+    fn expand_args(&self, args: &[&str], current_directory: &str) -> Vec<String> {
+        let mut matches = Vec::new();
+        for arg in args {
+            if arg.contains('*') || arg.contains('?') {
+                matches.extend(self.glob_expand(arg, current_directory));
+            } else {
+                matches.push(arg.to_string());
+            }
+        }
+        matches
+    }
+
+    fn glob_expand(&self, pattern: &str, current_directory: &str) -> Vec<String> {
+        let regex_pattern = format!(
+            "^{}$",
+            regex::escape(pattern).replace(r"\*", ".*").replace(r"\?", ".")
+        );
+        let re = match Regex::new(&regex_pattern) {
+            Ok(re) => re,
+            Err(_) => return vec![],
+        };
+
+        let entries = match std::fs::read_dir(current_directory) {
+            Ok(entries) => entries,
+            Err(_) => return vec![],
+        };
+
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| re.is_match(name))
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn context_for(dir: &str) -> TerminalContext {
+        TerminalContext {
+            current_directory: dir.to_string(),
+            environment_vars: Default::default(),
+            git_status: None,
+            project_type: None,
+            recent_files: vec![],
+            active_processes: vec![],
+        }
+    }
+
+    #[test]
+    fn predicts_rm_glob_deletions_without_touching_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir_path = temp_dir.path();
+
+        std::fs::write(dir_path.join("access.log"), "").unwrap();
+        std::fs::write(dir_path.join("error.log"), "").unwrap();
+        std::fs::write(dir_path.join("readme.md"), "").unwrap();
+
+        let context = context_for(dir_path.to_str().unwrap());
+        let predictor = ImpactPredictor::new();
+
+        let impact = predictor.predict("rm *.log", &context).unwrap();
+
+        assert_eq!(impact.deleted, vec!["access.log", "error.log"]);
+        assert!(impact.created.is_empty());
+        assert!(impact.modified.is_empty());
+
+        // Nothing should have actually been deleted.
+        assert!(dir_path.join("access.log").exists());
+        assert!(dir_path.join("error.log").exists());
+    }
+
+    #[test]
+    fn returns_none_for_non_mutating_commands() {
+        let context = context_for(".");
+        let predictor = ImpactPredictor::new();
+
+        assert!(predictor.predict("ls -la", &context).is_none());
+    }
+}
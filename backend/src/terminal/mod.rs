@@ -1,6 +1,7 @@
 pub mod ai_terminal;
 pub mod command_suggester;
 pub mod history_manager;
+pub mod impact_predictor;
 pub mod shell_integration;
 // pub mod safety_checker; // TODO: Implement safety checker
 
@@ -9,6 +10,10 @@ use std::collections::HashMap;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
+/// Sentinel branch name used while parsing porcelain output for a detached
+/// HEAD, replaced with the short commit SHA once resolved.
+const DETACHED_HEAD_BRANCH: &str = "HEAD (detached)";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TerminalSession {
     pub id: Uuid,
@@ -29,6 +34,7 @@ pub struct CommandEntry {
     pub execution_time_ms: u64,
     pub ai_suggested: bool,
     pub safety_level: SafetyLevel,
+    pub category: CommandCategory,
     pub timestamp: DateTime<Utc>,
 }
 
@@ -77,7 +83,7 @@ pub struct CommandSuggestion {
     pub estimated_time: Option<u32>, // seconds
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CommandCategory {
     FileSystem,
     Git,
@@ -95,6 +101,10 @@ pub struct TerminalRequest {
     pub query: String,
     pub query_type: QueryType,
     pub context: Option<TerminalContext>,
+    /// When true, suggested file-mutating commands are additionally analyzed
+    /// for `TerminalResponse::predicted_impact` instead of (or before) running them.
+    #[serde(default)]
+    pub explain_impact: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -112,6 +122,9 @@ pub struct TerminalResponse {
     pub execution_result: Option<CommandExecutionResult>,
     pub explanation: Option<String>,
     pub warnings: Vec<String>,
+    /// Files the top suggestion would create, modify, or delete, when
+    /// `TerminalRequest::explain_impact` was requested and the command is understood.
+    pub predicted_impact: Option<impact_predictor::PredictedImpact>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -194,7 +207,23 @@ impl TerminalContext {
         {
             if output.status.success() {
                 let status_output = String::from_utf8_lossy(&output.stdout);
-                self.git_status = Some(self.parse_git_status(&status_output));
+                let branch_line = status_output.lines().next().unwrap_or("");
+                let mut status = self.parse_git_status(&status_output);
+
+                if status.branch == DETACHED_HEAD_BRANCH {
+                    // Detached HEAD: no upstream to compare against, show the short SHA instead.
+                    if let Some(sha) = self.short_head_sha().await {
+                        status.branch = sha;
+                    }
+                } else if branch_line.contains("...") && !branch_line.contains('[') {
+                    // Tracking branch configured but porcelain omitted the ahead/behind
+                    // counts (older git versions truncate this on a clean sync) - ask git directly.
+                    let (ahead, behind) = self.fetch_ahead_behind().await;
+                    status.ahead_commits = ahead;
+                    status.behind_commits = behind;
+                }
+
+                self.git_status = Some(status);
             }
         }
         Ok(())
@@ -203,6 +232,8 @@ impl TerminalContext {
     fn parse_git_status(&self, output: &str) -> GitStatus {
         let lines: Vec<&str> = output.lines().collect();
         let mut branch = "main".to_string();
+        let mut ahead_commits = 0;
+        let mut behind_commits = 0;
         let mut modified_files = Vec::new();
         let mut has_changes = false;
 
@@ -210,9 +241,26 @@ impl TerminalContext {
             if line.starts_with("##") {
                 // Branch bilgisi
                 if let Some(branch_info) = line.strip_prefix("## ") {
-                    branch = branch_info.split("...").next()
-                        .unwrap_or("main")
-                        .to_string();
+                    if branch_info.starts_with("HEAD (no branch)") {
+                        branch = DETACHED_HEAD_BRANCH.to_string();
+                    } else {
+                        branch = branch_info.split("...").next()
+                            .unwrap_or("main")
+                            .to_string();
+
+                        if let Some(bracket_start) = branch_info.find('[') {
+                            if let Some(bracket_end) = branch_info[bracket_start..].find(']') {
+                                let tracking = &branch_info[bracket_start + 1..bracket_start + bracket_end];
+                                for part in tracking.split(", ") {
+                                    if let Some(n) = part.strip_prefix("ahead ") {
+                                        ahead_commits = n.trim().parse().unwrap_or(0);
+                                    } else if let Some(n) = part.strip_prefix("behind ") {
+                                        behind_commits = n.trim().parse().unwrap_or(0);
+                                    }
+                                }
+                            }
+                        }
+                    }
                 }
             } else if !line.trim().is_empty() {
                 // Değiştirilmiş dosyalar
@@ -226,12 +274,50 @@ impl TerminalContext {
         GitStatus {
             branch,
             has_changes,
-            ahead_commits: 0, // Bu bilgiyi ayrı bir komutla alacağız
-            behind_commits: 0,
+            ahead_commits,
+            behind_commits,
             modified_files,
         }
     }
 
+    /// Fallback for git versions/states where the porcelain branch line
+    /// doesn't carry ahead/behind counts even though an upstream is set.
+    async fn fetch_ahead_behind(&self) -> (u32, u32) {
+        let ahead = self.count_revs("@{u}..HEAD").await.unwrap_or(0);
+        let behind = self.count_revs("HEAD..@{u}").await.unwrap_or(0);
+        (ahead, behind)
+    }
+
+    async fn count_revs(&self, range: &str) -> Option<u32> {
+        let output = tokio::process::Command::new("git")
+            .args(&["rev-list", "--count", range])
+            .current_dir(&self.current_directory)
+            .output()
+            .await
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+    }
+
+    async fn short_head_sha(&self) -> Option<String> {
+        let output = tokio::process::Command::new("git")
+            .args(&["rev-parse", "--short", "HEAD"])
+            .current_dir(&self.current_directory)
+            .output()
+            .await
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
     pub fn detect_project_type(&mut self) {
         let current_path = std::path::Path::new(&self.current_directory);
         
@@ -253,4 +339,60 @@ impl Default for SafetyLevel {
     fn default() -> Self {
         SafetyLevel::Safe
     }
+}
+
+#[cfg(test)]
+mod git_status_tests {
+    use super::*;
+
+    fn context() -> TerminalContext {
+        TerminalContext::new(Some(".".to_string()))
+    }
+
+    #[test]
+    fn parses_ahead_and_behind_counts() {
+        let output = "## main...origin/main [ahead 2, behind 1]\n M src/main.rs\n";
+        let status = context().parse_git_status(output);
+        assert_eq!(status.branch, "main");
+        assert_eq!(status.ahead_commits, 2);
+        assert_eq!(status.behind_commits, 1);
+        assert!(status.has_changes);
+    }
+
+    #[test]
+    fn parses_ahead_only() {
+        let output = "## feature/foo...origin/feature/foo [ahead 3]\n";
+        let status = context().parse_git_status(output);
+        assert_eq!(status.ahead_commits, 3);
+        assert_eq!(status.behind_commits, 0);
+    }
+
+    #[test]
+    fn no_upstream_leaves_counts_at_zero() {
+        let output = "## feature/no-upstream\n";
+        let status = context().parse_git_status(output);
+        assert_eq!(status.branch, "feature/no-upstream");
+        assert_eq!(status.ahead_commits, 0);
+        assert_eq!(status.behind_commits, 0);
+        assert!(!status.has_changes);
+    }
+
+    #[test]
+    fn detached_head_is_flagged_for_sha_resolution() {
+        let output = "## HEAD (no branch)\n";
+        let status = context().parse_git_status(output);
+        assert_eq!(status.branch, DETACHED_HEAD_BRANCH);
+        assert_eq!(status.ahead_commits, 0);
+        assert_eq!(status.behind_commits, 0);
+    }
+
+    #[test]
+    fn clean_tracked_branch_has_zero_counts() {
+        let output = "## main...origin/main\n";
+        let status = context().parse_git_status(output);
+        assert_eq!(status.branch, "main");
+        assert_eq!(status.ahead_commits, 0);
+        assert_eq!(status.behind_commits, 0);
+        assert!(!status.has_changes);
+    }
 }
\ No newline at end of file
@@ -1,13 +1,145 @@
 use anyhow::Result;
+use std::path::PathBuf;
 use std::process::Stdio;
 use std::time::{Duration, Instant};
 use tokio::process::Command;
 use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::fs;
 use tracing::{info, warn, error};
 use uuid::Uuid;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
 
 use super::{CommandExecutionResult, SafetyLevel, TerminalContext};
 
+/// Shells `ShellIntegration::import_shell_history` knows how to parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShellType {
+    Bash,
+    Zsh,
+}
+
+/// A single command parsed out of a shell history file, before it's
+/// deduplicated and inserted into `command_history`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedCommand {
+    pub command: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Caps a single `import_shell_history` call so a multi-hundred-thousand-line
+/// history file can't balloon `command_history` in one request.
+const MAX_IMPORT_ENTRIES: usize = 10_000;
+
+/// Parses and reads `bash`/`zsh` history files for
+/// `ShellIntegration::import_shell_history`. Stateless -- all methods are
+/// plain functions grouped under this type for discoverability, mirroring
+/// `ShellExecutor`.
+pub struct ShellIntegration;
+
+impl ShellIntegration {
+    /// `~/.bash_history` or `~/.zsh_history`, resolved against `$HOME`.
+    pub fn default_history_path(shell: ShellType) -> Result<PathBuf> {
+        let home = std::env::var("HOME")
+            .map_err(|_| anyhow::anyhow!("HOME is not set; pass an explicit history_file"))?;
+
+        Ok(PathBuf::from(home).join(match shell {
+            ShellType::Bash => ".bash_history",
+            ShellType::Zsh => ".zsh_history",
+        }))
+    }
+
+    /// Reads and parses `history_file` (or the shell's default path),
+    /// deduplicating exact repeated commands and capping the result at
+    /// `MAX_IMPORT_ENTRIES` -- keeping the most recent entries when the file
+    /// has more than that.
+    pub async fn import_shell_history(
+        shell: ShellType,
+        history_file: Option<PathBuf>,
+    ) -> Result<Vec<ImportedCommand>> {
+        let path = match history_file {
+            Some(path) => path,
+            None => Self::default_history_path(shell)?,
+        };
+
+        let content = fs::read_to_string(&path).await
+            .map_err(|e| anyhow::anyhow!("Cannot read shell history file '{}': {e}", path.display()))?;
+
+        Ok(Self::parse_and_cap(shell, &content))
+    }
+
+    /// Parses already-read history content, without touching the
+    /// filesystem. Split out from [`Self::import_shell_history`] so the
+    /// `file_content` (base64) path on `POST /terminal/import-history` can
+    /// reuse the same parsing and capping logic.
+    pub fn parse_and_cap(shell: ShellType, content: &str) -> Vec<ImportedCommand> {
+        let mut entries = match shell {
+            ShellType::Bash => Self::parse_bash_history(content),
+            ShellType::Zsh => Self::parse_zsh_history(content),
+        };
+
+        if entries.len() > MAX_IMPORT_ENTRIES {
+            entries.drain(0..entries.len() - MAX_IMPORT_ENTRIES);
+        }
+
+        entries
+    }
+
+    /// Plain `.bash_history`: one command per line, optionally preceded by a
+    /// `#<unix-epoch-seconds>` comment line when `HISTTIMEFORMAT` is set.
+    fn parse_bash_history(content: &str) -> Vec<ImportedCommand> {
+        let mut entries = Vec::new();
+        let mut pending_timestamp: Option<DateTime<Utc>> = None;
+
+        for line in content.lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(epoch) = line.strip_prefix('#').and_then(|s| s.parse::<i64>().ok()) {
+                pending_timestamp = Utc.timestamp_opt(epoch, 0).single();
+                continue;
+            }
+
+            entries.push(ImportedCommand {
+                command: line.to_string(),
+                timestamp: pending_timestamp.take().unwrap_or_else(Utc::now),
+            });
+        }
+
+        entries
+    }
+
+    /// Zsh's `EXTENDED_HISTORY` format: `: <epoch>:<duration>;<command>`.
+    /// Falls back to treating the whole line as the command when it doesn't
+    /// match that format (plain, non-extended `.zsh_history`).
+    fn parse_zsh_history(content: &str) -> Vec<ImportedCommand> {
+        let mut entries = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(rest) = line.strip_prefix(": ") {
+                if let Some((meta, command)) = rest.split_once(';') {
+                    let epoch = meta.split(':').next().and_then(|s| s.parse::<i64>().ok());
+                    let timestamp = epoch.and_then(|e| Utc.timestamp_opt(e, 0).single()).unwrap_or_else(Utc::now);
+                    entries.push(ImportedCommand { command: command.to_string(), timestamp });
+                    continue;
+                }
+            }
+
+            entries.push(ImportedCommand { command: line.to_string(), timestamp: Utc::now() });
+        }
+
+        entries
+    }
+}
+
 pub struct ShellExecutor {
     shell_path: String,
     timeout: Duration,
@@ -58,7 +58,7 @@ pub struct AnalysisResponse {
     pub suggestions: Vec<Suggestion>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct Finding {
     pub severity: String,
     pub category: String,
@@ -248,12 +248,38 @@ impl Client {
     pub async fn metrics(&self) -> Result<serde_json::Value> {
         let url = format!("{}/api/v1/metrics", self.base_url);
         let response = self.http_client.get(&url).send().await?;
-        
+
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("Failed to get metrics: {}", response.status()));
         }
-        
+
         let metrics: serde_json::Value = response.json().await?;
         Ok(metrics)
     }
+
+    /// Downloads a conversation session transcript. Returns the raw export
+    /// body plus the filename the server suggests via `Content-Disposition`,
+    /// since the export endpoint serves a document, not a JSON envelope.
+    pub async fn export_conversation_session(&self, session_id: &str, format: &str) -> Result<(String, String)> {
+        let url = format!("{}/conversation/sessions/{}/export?format={}", self.base_url, session_id, format);
+        let response = self.http_client.get(&url).send().await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(anyhow::anyhow!("Session export failed: {}", error_text));
+        }
+
+        let extension = if format == "json" { "json" } else { "md" };
+        let default_filename = response
+            .headers()
+            .get(reqwest::header::CONTENT_DISPOSITION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split("filename=\"").nth(1))
+            .and_then(|v| v.strip_suffix('"'))
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| format!("conversation-{session_id}.{extension}"));
+
+        let content = response.text().await?;
+        Ok((content, default_filename))
+    }
 }
\ No newline at end of file
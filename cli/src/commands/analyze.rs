@@ -1,7 +1,8 @@
 use anyhow::Result;
 use colored::*;
 use std::path::PathBuf;
-use crate::client::Client;
+use std::time::{Duration, Instant};
+use crate::client::{AnalysisRequest, Client, Finding};
 
 pub async fn run(
     file: PathBuf,
@@ -152,4 +153,112 @@ fn detect_language_from_extension(file: &PathBuf) -> String {
         Some("xml") => "xml".to_string(),
         _ => "text".to_string(),
     }
+}
+
+/// Watches `file` for writes and re-runs the analysis on every change,
+/// printing only what changed since the previous run (new findings in red,
+/// resolved ones in green). Runs until interrupted with Ctrl-C.
+pub async fn run_watch(
+    file: PathBuf,
+    analysis_type: String,
+    language: Option<String>,
+    client: &Client,
+) -> Result<()> {
+    use notify::{RecursiveMode, Watcher};
+    use std::sync::mpsc::{channel, RecvTimeoutError};
+
+    println!("{}", "👀 Watching for changes (Ctrl-C to stop)...".bright_blue().bold());
+    println!("  📁 File: {}", file.display().to_string().bright_cyan());
+    println!();
+
+    let mut previous_findings: Option<Vec<Finding>> = None;
+    analyze_once(&file, &analysis_type, language.clone(), client, &mut previous_findings).await;
+
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = tx.send(event);
+    })?;
+    watcher.watch(&file, RecursiveMode::NonRecursive)?;
+
+    const DEBOUNCE: Duration = Duration::from_millis(500);
+    let mut last_run = Instant::now();
+
+    loop {
+        match rx.recv_timeout(DEBOUNCE) {
+            Ok(Ok(event)) => {
+                if !matches!(event.kind, notify::EventKind::Modify(_)) {
+                    continue;
+                }
+                if last_run.elapsed() < DEBOUNCE {
+                    continue;
+                }
+                last_run = Instant::now();
+                println!();
+                println!("{}", "🔄 File changed, re-analyzing...".bright_yellow());
+                analyze_once(&file, &analysis_type, language.clone(), client, &mut previous_findings).await;
+            }
+            Ok(Err(e)) => {
+                println!("{}", format!("⚠️  Watch error: {}", e).bright_red());
+            }
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    Ok(())
+}
+
+async fn analyze_once(
+    file: &PathBuf,
+    analysis_type: &str,
+    language: Option<String>,
+    client: &Client,
+    previous_findings: &mut Option<Vec<Finding>>,
+) {
+    let code = match std::fs::read_to_string(file) {
+        Ok(content) => content,
+        Err(e) => {
+            println!("{}", format!("❌ Failed to read file: {}", e).bright_red());
+            return;
+        }
+    };
+
+    let detected_language = language.unwrap_or_else(|| detect_language_from_extension(file));
+
+    let request = AnalysisRequest {
+        code,
+        language: detected_language,
+        analysis_type: analysis_type.to_string(),
+        context: None,
+    };
+
+    match client.analyze(request).await {
+        Ok(response) => {
+            let new_findings: Vec<&Finding> = response
+                .findings
+                .iter()
+                .filter(|f| !previous_findings.as_ref().is_some_and(|prev| prev.contains(f)))
+                .collect();
+            let resolved_findings: Vec<&Finding> = previous_findings
+                .as_ref()
+                .map(|prev| prev.iter().filter(|f| !response.findings.contains(f)).collect())
+                .unwrap_or_default();
+
+            if new_findings.is_empty() && resolved_findings.is_empty() {
+                println!("{}", "✅ No new or resolved findings".bright_black());
+            } else {
+                for finding in &resolved_findings {
+                    println!("{}", format!("  - resolved: [{}] {}", finding.severity, finding.title).bright_green());
+                }
+                for finding in &new_findings {
+                    println!("{}", format!("  + new: [{}] {}", finding.severity, finding.title).bright_red());
+                }
+            }
+
+            *previous_findings = Some(response.findings);
+        }
+        Err(e) => {
+            println!("{}", format!("❌ Analysis failed: {}", e).bright_red());
+        }
+    }
 }
\ No newline at end of file
@@ -1,8 +1,30 @@
 use anyhow::Result;
 use colored::*;
 use dialoguer::{theme::ColorfulTheme, Input};
+use std::path::PathBuf;
 use crate::client::Client;
 
+/// Downloads a conversation session transcript and writes it to `output`
+/// (or its server-suggested filename, in the current directory, if unset).
+pub async fn export(
+    session_id: String,
+    output: Option<PathBuf>,
+    format: String,
+    client: &Client,
+) -> Result<()> {
+    let (content, default_filename) = client.export_conversation_session(&session_id, &format).await?;
+    let output_path = output.unwrap_or_else(|| PathBuf::from(default_filename));
+
+    tokio::fs::write(&output_path, content).await?;
+    println!(
+        "{} {}",
+        "✅ Exported session to".bright_green(),
+        output_path.display()
+    );
+
+    Ok(())
+}
+
 pub async fn run(
     initial_message: Option<String>,
     mode: String,
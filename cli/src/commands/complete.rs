@@ -9,6 +9,8 @@ pub async fn run(
     provider: Option<String>,
     max_tokens: u32,
     temperature: f32,
+    top_p: Option<f32>,
+    stop: Vec<String>,
     client: &Client,
 ) -> Result<()> {
     println!("{}", "🤖 AI Code Completion".bright_blue().bold());
@@ -28,18 +30,26 @@ pub async fn run(
     }
     println!("  🎛️  Max Tokens: {}", max_tokens.to_string().bright_white());
     println!("  🌡️  Temperature: {}", temperature.to_string().bright_white());
+    if let Some(top_p) = top_p {
+        println!("  🎯 Top P: {}", top_p.to_string().bright_white());
+    }
+    if !stop.is_empty() {
+        println!("  🛑 Stop sequences: {}", stop.join(", ").bright_white());
+    }
     println!();
 
     // Make completion request
     println!("{}", "⏳ Generating completion...".bright_yellow());
-    
+
     let completion_request = serde_json::json!({
         "prompt": prompt,
         "language": language,
         "model": model,
         "provider": provider,
         "max_tokens": max_tokens,
-        "temperature": temperature
+        "temperature": temperature,
+        "top_p": top_p,
+        "stop": stop
     });
 
     match client.post("/api/v1/complete", &completion_request).await {
@@ -72,6 +72,7 @@ pub async fn run_fix(
     file_path: Option<PathBuf>,
     auto_apply: bool,
     search_solutions: bool,
+    create_pr: bool,
     client: &Client,
 ) -> Result<()> {
     println!("{}", "🔧 AI Hata Düzeltici".bright_red().bold());
@@ -117,6 +118,12 @@ pub async fn run_fix(
     if let Some(code_changes) = &fix_response.response.code_changes {
         if !code_changes.is_empty() {
             handle_code_changes(code_changes, auto_apply, &target_file).await?;
+
+            if create_pr {
+                if let Err(e) = create_pr_for_fixes(code_changes, &error_msg, client).await {
+                    println!("{} {}", "⚠️ PR oluşturulamadı (düzeltmeler yine de uygulandı):".bright_yellow(), e);
+                }
+            }
         }
     }
 
@@ -528,6 +535,209 @@ async fn handle_code_changes(
     Ok(())
 }
 
+/// Git host detected from the workspace's `origin` remote, for
+/// `create_pr_for_fixes`. Only github.com and gitlab.com are supported,
+/// since those are the only REST APIs it knows how to call.
+enum GitHost {
+    GitHub { owner: String, repo: String },
+    GitLab { owner: String, repo: String },
+}
+
+fn run_git(args: &[&str]) -> Result<String> {
+    let output = std::process::Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn detect_git_host() -> Result<GitHost> {
+    let remotes = run_git(&["remote", "-v"])?;
+    let origin_url = remotes
+        .lines()
+        .find(|line| line.starts_with("origin\t") || line.starts_with("origin "))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .ok_or_else(|| anyhow::anyhow!("No `origin` remote found; is this a git repository?"))?;
+
+    parse_git_host(origin_url)
+}
+
+fn parse_git_host(url: &str) -> Result<GitHost> {
+    let normalized = url
+        .trim_end_matches(".git")
+        .replace("git@github.com:", "github.com/")
+        .replace("git@gitlab.com:", "gitlab.com/")
+        .replace("https://github.com/", "github.com/")
+        .replace("https://gitlab.com/", "gitlab.com/");
+
+    let (host, path) = normalized
+        .split_once('/')
+        .ok_or_else(|| anyhow::anyhow!("Could not parse remote URL: {}", url))?;
+    let mut parts = path.splitn(2, '/');
+    let owner = parts
+        .next()
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Could not parse owner from remote URL: {}", url))?
+        .to_string();
+    let repo = parts
+        .next()
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Could not parse repo from remote URL: {}", url))?
+        .to_string();
+
+    match host {
+        "github.com" => Ok(GitHost::GitHub { owner, repo }),
+        "gitlab.com" => Ok(GitHost::GitLab { owner, repo }),
+        _ => Err(anyhow::anyhow!(
+            "Unsupported git host (only github.com and gitlab.com are supported): {}",
+            url
+        )),
+    }
+}
+
+/// Asks the server to write a PR description for `diff_summary`, by calling
+/// `POST /conversation/message` with `intent_hint: "CodeReview"` the same
+/// way `get_fix_suggestions` asks it to analyze the original error.
+async fn generate_pr_body(diff_summary: &str, error_message: &str, client: &Client) -> Result<String> {
+    let request = FixRequest {
+        session_id: None,
+        message: format!(
+            "Write a pull request description for the fix below.\n\nOriginal error:\n{}\n\nDiff:\n{}",
+            error_message, diff_summary
+        ),
+        current_file: None,
+        selected_text: None,
+        context_files: Vec::new(),
+        intent_hint: Some("CodeReview".to_string()),
+    };
+
+    let response: FixResponse = client.post("/conversation/message", &request).await?;
+    Ok(response.response.ai_response)
+}
+
+async fn open_pull_request(
+    host: &GitHost,
+    token: &str,
+    branch: &str,
+    base_branch: &str,
+    title: &str,
+    body: &str,
+) -> Result<String> {
+    let http = reqwest::Client::new();
+
+    match host {
+        GitHost::GitHub { owner, repo } => {
+            let response = http
+                .post(format!("https://api.github.com/repos/{}/{}/pulls", owner, repo))
+                .header("Authorization", format!("Bearer {}", token))
+                .header("User-Agent", "uaida-cli")
+                .header("Accept", "application/vnd.github+json")
+                .json(&serde_json::json!({
+                    "title": title,
+                    "head": branch,
+                    "base": base_branch,
+                    "body": body,
+                }))
+                .send()
+                .await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(anyhow::anyhow!("GitHub PR creation failed ({}): {}", status, text));
+            }
+
+            let payload: serde_json::Value = response.json().await?;
+            payload["html_url"]
+                .as_str()
+                .map(|s| s.to_string())
+                .ok_or_else(|| anyhow::anyhow!("GitHub response did not include html_url"))
+        }
+        GitHost::GitLab { owner, repo } => {
+            let project = format!("{}/{}", owner, repo).replace('/', "%2F");
+            let response = http
+                .post(format!("https://gitlab.com/api/v4/projects/{}/merge_requests", project))
+                .header("PRIVATE-TOKEN", token)
+                .json(&serde_json::json!({
+                    "source_branch": branch,
+                    "target_branch": base_branch,
+                    "title": title,
+                    "description": body,
+                }))
+                .send()
+                .await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(anyhow::anyhow!("GitLab MR creation failed ({}): {}", status, text));
+            }
+
+            let payload: serde_json::Value = response.json().await?;
+            payload["web_url"]
+                .as_str()
+                .map(|s| s.to_string())
+                .ok_or_else(|| anyhow::anyhow!("GitLab response did not include web_url"))
+        }
+    }
+}
+
+/// Pushes `code_changes` to a new `uaida/fix-<timestamp>` branch and opens a
+/// PR/MR against whatever branch was checked out. Any failure here (missing
+/// token, git error, API error) is returned to the caller, which treats it
+/// as non-fatal — the fixes already written to disk by `handle_code_changes`
+/// are left in place either way.
+async fn create_pr_for_fixes(code_changes: &[CodeChange], error_message: &str, client: &Client) -> Result<()> {
+    let host = detect_git_host()?;
+    let token = match &host {
+        GitHost::GitHub { .. } => std::env::var("GITHUB_TOKEN")
+            .map_err(|_| anyhow::anyhow!("GITHUB_TOKEN is not set"))?,
+        GitHost::GitLab { .. } => std::env::var("GITLAB_TOKEN")
+            .map_err(|_| anyhow::anyhow!("GITLAB_TOKEN is not set"))?,
+    };
+
+    let base_branch = run_git(&["rev-parse", "--abbrev-ref", "HEAD"])?;
+    let branch_name = format!("uaida/fix-{}", chrono::Utc::now().format("%Y%m%d%H%M%S"));
+
+    run_git(&["checkout", "-b", &branch_name])?;
+    run_git(&["add", "-A"])?;
+    run_git(&["commit", "-m", "uaida: apply automated fix"])?;
+    run_git(&["push", "-u", "origin", &branch_name])?;
+
+    let diff_summary = code_changes
+        .iter()
+        .map(|c| {
+            format!(
+                "--- {}\n{}\n+++ {}\n{}",
+                c.file_path,
+                c.old_content.as_deref().unwrap_or("(none)"),
+                c.file_path,
+                c.new_content
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let body = generate_pr_body(&diff_summary, error_message, client)
+        .await
+        .unwrap_or_else(|_| format!("Automated fix applied by `uaida fix` for:\n\n{}", error_message));
+    let title = format!(
+        "uaida: fix {}",
+        error_message.lines().next().unwrap_or("reported error")
+    );
+
+    let pr_url = open_pull_request(&host, &token, &branch_name, &base_branch, &title, &body).await?;
+
+    println!("{} {}", "🔗 PR/MR oluşturuldu:".bright_green().bold(), pr_url.bright_cyan());
+    println!("{}", pr_url);
+
+    Ok(())
+}
+
 fn format_code_preview(code: &str) -> String {
     code.lines()
         .enumerate()
@@ -2,6 +2,7 @@ use anyhow::Result;
 use colored::*;
 use dialoguer::{theme::ColorfulTheme, Input, Select, Confirm};
 use std::io::{self, Write};
+use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 
 use crate::client::Client;
@@ -42,6 +43,57 @@ pub struct ExecutionResult {
     pub execution_time_ms: u64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TerminalImportHistoryRequest {
+    pub session_id: Option<String>,
+    pub shell: String,
+    pub file_content: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TerminalImportHistoryResponse {
+    pub success: bool,
+    pub imported: usize,
+    pub skipped_duplicates: usize,
+}
+
+/// `uaida terminal import-history`: reads a local `bash`/`zsh` history file
+/// and uploads it to `POST /terminal/import-history` as base64, since the
+/// CLI and the server are not guaranteed to share a filesystem.
+pub async fn import_history(
+    session_id: Option<String>,
+    shell: String,
+    file: Option<PathBuf>,
+    client: &Client,
+) -> Result<()> {
+    let file_content = match file {
+        Some(path) => {
+            use base64::Engine as _;
+            let bytes = std::fs::read(&path)
+                .map_err(|e| anyhow::anyhow!("Cannot read history file '{}': {e}", path.display()))?;
+            Some(base64::engine::general_purpose::STANDARD.encode(bytes))
+        }
+        None => None,
+    };
+
+    let request = TerminalImportHistoryRequest {
+        session_id,
+        shell,
+        file_content,
+    };
+
+    let response: TerminalImportHistoryResponse = client.post("/terminal/import-history", &request).await?;
+
+    println!(
+        "{} {} komut içe aktarıldı, {} yinelenen atlandı",
+        "✅".bright_green(),
+        response.imported,
+        response.skipped_duplicates
+    );
+
+    Ok(())
+}
+
 pub async fn run_interactive_terminal(client: &Client) -> Result<()> {
     println!("{}", "🖥️  AI Destekli Terminal".bright_blue().bold());
     println!("{}", "Komutları yazın veya doğal dilde ne yapmak istediğinizi açıklayın".bright_white().dimmed());
@@ -73,26 +73,38 @@ enum Commands {
         /// Max tokens
         #[arg(long, default_value = "1000")]
         max_tokens: u32,
-        
+
         /// Temperature (0.0-1.0)
         #[arg(long, default_value = "0.7")]
         temperature: f32,
+
+        /// Nucleus sampling threshold (0.0-1.0)
+        #[arg(long)]
+        top_p: Option<f32>,
+
+        /// Sequence that stops generation when produced (may be repeated)
+        #[arg(long)]
+        stop: Vec<String>,
     },
-    
+
     /// Analyze code
     Analyze {
         /// File to analyze
         file: PathBuf,
         
-        /// Analysis type (security, performance, quality, bugs)
+        /// Analysis type (security, performance, quality, bugs, smells)
         #[arg(short, long, default_value = "quality")]
         analysis_type: String,
         
         /// Programming language (auto-detect if not specified)
         #[arg(short, long)]
         language: Option<String>,
+
+        /// Watch the file for changes and re-analyze on every save
+        #[arg(short, long)]
+        watch: bool,
     },
-    
+
     /// Generate documentation
     Doc {
         /// File to document
@@ -162,8 +174,11 @@ enum Commands {
         output: Option<PathBuf>,
     },
     
-    /// Interactive terminal mode
-    Terminal,
+    /// Interactive terminal mode, or a terminal subcommand (e.g. `import-history`)
+    Terminal {
+        #[command(subcommand)]
+        action: Option<TerminalAction>,
+    },
     
     /// Search code patterns
     Search {
@@ -183,21 +198,30 @@ enum Commands {
     Fix {
         /// File to fix
         file: PathBuf,
-        
+
         /// Issue type (auto, security, performance, style)
         #[arg(short, long, default_value = "auto")]
         issue_type: String,
-        
+
         /// Apply fixes automatically
         #[arg(short, long)]
         auto_apply: bool,
+
+        /// After applying fixes, push them to a new branch and open a
+        /// GitHub/GitLab PR (requires GITHUB_TOKEN or GITLAB_TOKEN).
+        /// Failure to create the PR does not undo the applied fixes.
+        #[arg(long)]
+        create_pr: bool,
     },
     
-    /// Interactive chat mode
+    /// Interactive chat mode, or a chat subcommand (e.g. `export`)
     Chat {
+        #[command(subcommand)]
+        action: Option<ChatAction>,
+
         /// Initial message
         message: Option<String>,
-        
+
         /// Chat mode (code, general, debug)
         #[arg(short, long, default_value = "code")]
         mode: String,
@@ -230,6 +254,42 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum ChatAction {
+    /// Export a conversation session transcript as Markdown or JSON
+    Export {
+        /// Session ID to export
+        session_id: String,
+
+        /// Output file (defaults to the session's default export filename)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Export format (markdown, json)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TerminalAction {
+    /// Import `bash`/`zsh` history into a terminal session
+    ImportHistory {
+        /// Session to import into
+        #[arg(short, long)]
+        session_id: Option<String>,
+
+        /// Shell whose history file to import (bash, zsh)
+        #[arg(short, long, default_value = "bash")]
+        shell: String,
+
+        /// History file to read (defaults to the shell's own history file
+        /// on this machine, e.g. ~/.bash_history)
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+    },
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
@@ -241,7 +301,7 @@ async fn main() -> anyhow::Result<()> {
     let client = client::Client::new(&cli.server, &config)?;
     
     // Print banner for non-interactive commands
-    if !matches!(cli.command, Commands::Chat { .. } | Commands::Terminal) {
+    if !matches!(cli.command, Commands::Chat { .. } | Commands::Terminal { .. }) {
         print_banner();
     }
     
@@ -255,27 +315,35 @@ async fn main() -> anyhow::Result<()> {
             commands::dev::run(project, language, &client).await?;
         }
         
-        Commands::Complete { 
-            prompt, 
-            language, 
-            model, 
-            provider, 
-            max_tokens, 
-            temperature 
+        Commands::Complete {
+            prompt,
+            language,
+            model,
+            provider,
+            max_tokens,
+            temperature,
+            top_p,
+            stop,
         } => {
             commands::complete::run(
-                prompt, 
-                language, 
-                model, 
-                provider, 
-                max_tokens, 
-                temperature, 
+                prompt,
+                language,
+                model,
+                provider,
+                max_tokens,
+                temperature,
+                top_p,
+                stop,
                 &client
             ).await?;
         }
         
-        Commands::Analyze { file, analysis_type, language } => {
-            commands::analyze::run(file, analysis_type, language, &client).await?;
+        Commands::Analyze { file, analysis_type, language, watch } => {
+            if watch {
+                commands::analyze::run_watch(file, analysis_type, language, &client).await?;
+            } else {
+                commands::analyze::run(file, analysis_type, language, &client).await?;
+            }
         }
         
         Commands::Doc { file, output, format } => {
@@ -298,19 +366,27 @@ async fn main() -> anyhow::Result<()> {
             commands::translate::run(file, target, output, &client).await?;
         }
         
-        Commands::Terminal => {
-            commands::terminal::run(&client).await?;
+        Commands::Terminal { action: Some(TerminalAction::ImportHistory { session_id, shell, file }) } => {
+            commands::terminal::import_history(session_id, shell, file, &client).await?;
+        }
+
+        Commands::Terminal { action: None } => {
+            commands::terminal::run_interactive_terminal(&client).await?;
         }
         
         Commands::Search { query, directory, extensions } => {
             commands::search::run(query, directory, extensions, &client).await?;
         }
         
-        Commands::Fix { file, issue_type, auto_apply } => {
-            commands::fix::run(file, issue_type, auto_apply, &client).await?;
+        Commands::Fix { file, issue_type, auto_apply, create_pr } => {
+            commands::fix::run(file, issue_type, auto_apply, create_pr, &client).await?;
         }
         
-        Commands::Chat { message, mode } => {
+        Commands::Chat { action: Some(ChatAction::Export { session_id, output, format }), .. } => {
+            commands::chat::export(session_id, output, format, &client).await?;
+        }
+
+        Commands::Chat { action: None, message, mode } => {
             commands::chat::run(message, mode, &client).await?;
         }
         
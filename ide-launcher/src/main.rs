@@ -33,6 +33,10 @@ async fn complete_code(
     language: Option<String>,
     model: Option<String>,
     provider: Option<String>,
+    // `{ kind: "image", data: <base64 | url>, mime_type }` per attachment,
+    // e.g. a pasted screenshot of a stack trace. Forwarded to the backend's
+    // `CompletionRequest::attachments` unchanged.
+    attachments: Option<Vec<ai_client::Attachment>>,
     state: State<'_, AppState>
 ) -> Result<serde_json::Value, String> {
     let request = ai_client::CompletionRequest {
@@ -43,8 +47,9 @@ async fn complete_code(
         max_tokens: Some(1000),
         temperature: Some(0.7),
         system_prompt: None,
+        attachments,
     };
-    
+
     state.ai_client.complete(request).await
         .map_err(|e| e.to_string())
 }